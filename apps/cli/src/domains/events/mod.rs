@@ -214,6 +214,16 @@ fn summarize_event(event: &Event) -> String {
 		Event::VolumeUpdated { fingerprint, .. } => {
 			format!("Volume updated: {}", fingerprint.0)
 		}
+		Event::VolumeUsageChanged {
+			fingerprint,
+			available_space,
+			total_capacity,
+		} => {
+			format!(
+				"Volume {} usage: {} of {} bytes free",
+				fingerprint.0, available_space, total_capacity
+			)
+		}
 		Event::VolumeSpeedTested {
 			fingerprint,
 			read_speed_mbps,
@@ -234,6 +244,12 @@ fn summarize_event(event: &Event) -> String {
 				if *is_mounted { "mounted" } else { "unmounted" }
 			)
 		}
+		Event::VolumeMounted { mount_point } => {
+			format!("Filesystem mounted at {}", mount_point.display())
+		}
+		Event::VolumeUnmounted { mount_point } => {
+			format!("Filesystem unmounted from {}", mount_point.display())
+		}
 		Event::VolumeError { fingerprint, error } => {
 			format!("Volume {} error: {}", fingerprint.0, error)
 		}
@@ -329,6 +345,11 @@ fn summarize_event(event: &Event) -> String {
 		Event::IndexingFailed { location_id, error } => {
 			format!("Indexing failed for location {}: {}", location_id, error)
 		}
+		Event::LocationScanStateChanged {
+			location_id, state, ..
+		} => {
+			format!("Location {} scan state: {:?}", location_id, state)
+		}
 
 		// Device events
 		Event::DeviceConnected {
@@ -340,6 +361,19 @@ fn summarize_event(event: &Event) -> String {
 		Event::DeviceDisconnected { device_id } => {
 			format!("Device disconnected: {}", device_id)
 		}
+		Event::DeviceInfoChanged { device_id, changed } => {
+			format!("Device info changed: {} ({:?})", device_id, changed)
+		}
+		Event::DeviceUnpaired {
+			device_id,
+			purged_synced_data,
+		} => {
+			if *purged_synced_data {
+				format!("Device unpaired: {} (synced data purged)", device_id)
+			} else {
+				format!("Device unpaired: {}", device_id)
+			}
+		}
 
 		// Resource events
 		Event::ResourceChanged {
@@ -458,6 +492,19 @@ fn summarize_event(event: &Event) -> String {
 				vouchee_device_id
 			)
 		}
+		Event::ProxiedDeviceConfirmationRequired { device_name, .. } => {
+			format!(
+				"Proxy pairing confirmation required: {} accepted our vouch",
+				device_name
+			)
+		}
+		Event::ProxyPairingCompleted { paired, failed, .. } => {
+			format!(
+				"Proxy pairing completed: {} paired, {} failed",
+				paired.len(),
+				failed.len()
+			)
+		}
 
 		// Config events
 		Event::ConfigChanged { .. } => "Configuration changed".to_string(),
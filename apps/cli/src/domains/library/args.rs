@@ -122,6 +122,10 @@ pub struct SetupArgs {
 	#[arg(long)]
 	pub local_device: Option<Uuid>,
 
+	/// Only share these locations (repeatable; shares the whole library if omitted)
+	#[arg(long = "location")]
+	pub locations: Vec<Uuid>,
+
 	/// Use interactive mode (default if no arguments provided)
 	#[arg(short, long)]
 	pub interactive: bool,
@@ -212,6 +216,7 @@ impl SetupArgs {
 			remote_library_id: self.remote_library,
 			action,
 			leader_device_id,
+			location_filter: (!self.locations.is_empty()).then(|| self.locations.clone()),
 		})
 	}
 }
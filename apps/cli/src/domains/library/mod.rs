@@ -416,5 +416,6 @@ async fn run_interactive_sync_setup(ctx: &Context) -> Result<LibrarySyncSetupInp
 		remote_library_id: action.1,
 		action: action.0,
 		leader_device_id,
+		location_filter: None,
 	})
 }
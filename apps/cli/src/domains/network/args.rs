@@ -5,8 +5,10 @@ use sd_core::{
 	domain::addressing::SdPath,
 	ops::network::{
 		pair::{
-			cancel::input::PairCancelInput, generate::input::PairGenerateInput,
-			join::input::PairJoinInput,
+			cancel::input::PairCancelInput,
+			cancel_vouching_session::input::VouchingSessionCancelInput,
+			generate::input::PairGenerateInput, join::input::PairJoinInput,
+			prune_vouching_queue::input::VouchingQueuePruneInput,
 		},
 		revoke::input::DeviceRevokeInput,
 		spacedrop::send::input::SpacedropSendInput,
@@ -29,12 +31,25 @@ pub enum PairCmd {
 	Status,
 	/// Cancel a pairing session
 	Cancel { session_id: Uuid },
+	/// Drop expired vouches from the vouching queue
+	PruneVouches {
+		/// Also drop every vouch of this vouching session
+		#[arg(long)]
+		session: Option<Uuid>,
+		/// Also drop every vouch addressed to this device
+		#[arg(long)]
+		target: Option<Uuid>,
+	},
+	/// Show vouching sessions
+	Vouching,
+	/// Cancel a vouching session and drop its queued vouches
+	CancelVouching { session_id: Uuid },
 }
 
 impl PairCmd {
 	pub fn to_generate_input(&self) -> Option<PairGenerateInput> {
 		match self {
-			Self::Generate {} => Some(PairGenerateInput {}),
+			Self::Generate {} => Some(PairGenerateInput::default()),
 			_ => None,
 		}
 	}
@@ -80,6 +95,25 @@ impl PairCmd {
 			_ => None,
 		}
 	}
+
+	pub fn to_cancel_vouching_input(&self) -> Option<VouchingSessionCancelInput> {
+		match self {
+			Self::CancelVouching { session_id } => Some(VouchingSessionCancelInput {
+				session_id: *session_id,
+			}),
+			_ => None,
+		}
+	}
+
+	pub fn to_prune_vouches_input(&self) -> Option<VouchingQueuePruneInput> {
+		match self {
+			Self::PruneVouches { session, target } => Some(VouchingQueuePruneInput {
+				session_id: *session,
+				target_device_id: *target,
+			}),
+			_ => None,
+		}
+	}
 }
 
 #[derive(Args, Debug, Clone)]
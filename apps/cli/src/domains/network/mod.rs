@@ -10,9 +10,12 @@ use sd_core::ops::network::{
 	devices::{output::ListPairedDevicesOutput, query::ListPairedDevicesInput},
 	pair::{
 		cancel::output::PairCancelOutput,
+		cancel_vouching_session::output::VouchingSessionCancelOutput,
 		generate::output::PairGenerateOutput,
 		join::output::PairJoinOutput,
+		prune_vouching_queue::output::VouchingQueuePruneOutput,
 		status::{output::PairStatusOutput, query::PairStatusQuery},
+		vouching_sessions::{output::VouchingSessionsOutput, query::VouchingSessionsQueryInput},
 	},
 	revoke::output::DeviceRevokeOutput,
 	spacedrop::send::output::SpacedropSendOutput,
@@ -140,6 +143,42 @@ pub async fn run(ctx: &Context, cmd: NetworkCmd) -> Result<()> {
 					println!("Cancelled: {}", o.cancelled);
 				});
 			}
+			PairCmd::PruneVouches { .. } => {
+				let input = pc.to_prune_vouches_input().unwrap();
+				let out: VouchingQueuePruneOutput = execute_action!(ctx, input);
+				print_output!(ctx, &out, |o: &VouchingQueuePruneOutput| {
+					println!(
+						"Pruned {} vouches ({} expired, {} for session, {} for target)",
+						o.total, o.expired, o.for_session, o.for_target
+					);
+				});
+			}
+			PairCmd::Vouching => {
+				let out: VouchingSessionsOutput =
+					execute_core_query!(ctx, VouchingSessionsQueryInput);
+				print_output!(ctx, &out, |o: &VouchingSessionsOutput| {
+					if o.sessions.is_empty() {
+						println!("No vouching sessions");
+						return;
+					}
+					for s in o.sessions.clone() {
+						println!(
+							"- {} {:?} vouchee={} ({} vouches)",
+							s.id,
+							s.state,
+							s.vouchee_device_name,
+							s.vouches.len()
+						);
+					}
+				});
+			}
+			PairCmd::CancelVouching { .. } => {
+				let input = pc.to_cancel_vouching_input().unwrap();
+				let out: VouchingSessionCancelOutput = execute_action!(ctx, input);
+				print_output!(ctx, &out, |o: &VouchingSessionCancelOutput| {
+					println!("Cancelled: {}", o.cancelled);
+				});
+			}
 		},
 		NetworkCmd::Devices { connected } => {
 			let input = ListPairedDevicesInput {
@@ -77,6 +77,7 @@ async fn run_single_query(
 		state_only: if args.state { Some(true) } else { None },
 		operations_only: if args.operations { Some(true) } else { None },
 		errors_only: if args.errors { Some(true) } else { None },
+		throughput_only: None,
 	};
 
 	let json_response = ctx.core.query(&input, Some(library_id)).await?;
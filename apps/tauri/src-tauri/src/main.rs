@@ -47,6 +47,8 @@ fn get_default_event_subscription() -> Vec<&'static str> {
 		"VolumeUpdated",
 		"VolumeSpeedTested",
 		"VolumeMountChanged",
+		"VolumeMounted",
+		"VolumeUnmounted",
 		"VolumeError",
 		// Job lifecycle
 		"JobQueued",
@@ -61,9 +63,12 @@ fn get_default_event_subscription() -> Vec<&'static str> {
 		"IndexingStarted",
 		"IndexingCompleted",
 		"IndexingFailed",
+		"LocationScanStateChanged",
 		// Device events
 		"DeviceConnected",
 		"DeviceDisconnected",
+		"DeviceInfoChanged",
+		"DeviceUnpaired",
 		// Resource events
 		"ResourceChanged",
 		"ResourceChangedBatch",
@@ -24,6 +24,8 @@ async fn main() {
 		include_virtual: false,
 		run_speed_test: false,
 		refresh_interval_secs: 0,
+		pinned_fingerprints: Vec::new(),
+		write_external_dotfiles: true,
 	};
 
 	match detect_volumes(device_id, &config).await {
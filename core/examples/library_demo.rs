@@ -86,7 +86,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 			boot_disk_type: Set(None),
 			boot_disk_capacity_bytes: Set(None),
 			swap_total_bytes: Set(None),
-			network_addresses: Set(serde_json::json!([])),
+			network_addresses: Set(Default::default()),
 			is_online: Set(true),
 			last_seen_at: Set(chrono::Utc::now()),
 			capabilities: Set(serde_json::json!({
@@ -2,11 +2,13 @@
 
 use super::{default_data_dir, Preferences};
 use crate::config::migration::Migrate;
+use crate::volume::VolumeDetectionConfig;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use tracing::{info, warn};
+use uuid::Uuid;
 
 /// Main application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +47,21 @@ pub struct AppConfig {
 	/// Spacebot companion runtime configuration
 	#[serde(default)]
 	pub spacebot: SpacebotConfig,
+
+	/// Relay and peer discovery overrides
+	#[serde(default)]
+	pub network: NetworkDiscoveryConfig,
+
+	/// Directory pairing sessions and the vouching queue are stored under
+	///
+	/// Unset means the data directory. Read when networking starts, which moves
+	/// the files over from the directory they were kept in before.
+	#[serde(default)]
+	pub pairing_data_dir: Option<PathBuf>,
+
+	/// Volume filters, pinned volumes and polling intervals, read when the core starts
+	#[serde(default)]
+	pub volume_detection: VolumeDetectionConfig,
 }
 
 /// Spacebot integration configuration.
@@ -99,6 +116,42 @@ fn default_true() -> bool {
 	true
 }
 
+fn default_vouch_max_future_skew() -> u64 {
+	300
+}
+
+fn default_vouch_queue_poll_secs() -> u64 {
+	10
+}
+
+fn default_session_cleanup_poll_secs() -> u64 {
+	60
+}
+
+fn default_max_vouching_sessions() -> usize {
+	128
+}
+
+fn default_vouching_session_ttl_secs() -> u64 {
+	7 * 24 * 60 * 60
+}
+
+fn default_vouch_rejection_threshold() -> u32 {
+	3
+}
+
+fn default_vouch_rejection_cooldown_secs() -> u64 {
+	24 * 60 * 60
+}
+
+fn default_max_device_info_size() -> usize {
+	crate::service::network::device::DeviceInfo::DEFAULT_MAX_SIZE
+}
+
+fn default_min_code_entropy_bits() -> u32 {
+	crate::service::network::protocol::pairing::types::DEFAULT_MIN_ENTROPY_BITS
+}
+
 impl Default for ServiceConfig {
 	fn default() -> Self {
 		Self {
@@ -173,6 +226,106 @@ pub struct LoggingConfig {
 	pub streams: Vec<LogStreamConfig>,
 }
 
+/// Relay servers the networking endpoint uses
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "mode", content = "url")]
+pub enum RelayConfig {
+	/// The public n0 relays
+	#[default]
+	Default,
+	/// No relay; peers are only reached directly
+	Disabled,
+	/// A self-hosted relay at this URL
+	Custom(String),
+}
+
+/// A peer address known ahead of time, reachable without any discovery service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticPeerAddr {
+	/// Iroh node ID of the peer
+	pub node_id: String,
+	/// Direct socket addresses of the peer
+	#[serde(default)]
+	pub addrs: Vec<std::net::SocketAddr>,
+	/// Relay the peer is reachable through
+	#[serde(default)]
+	pub relay_url: Option<String>,
+}
+
+/// How the networking endpoint finds peers
+///
+/// The defaults use the public relays and n0 DNS discovery, which need internet
+/// access. Self-hosted setups and offline tests can point at a local relay, turn
+/// public discovery off and list peer addresses up front. Read when networking
+/// starts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkDiscoveryConfig {
+	#[serde(default)]
+	pub relay: RelayConfig,
+	/// Publish and resolve addresses through the n0 pkarr/DNS servers
+	#[serde(default = "default_true")]
+	pub public_discovery: bool,
+	/// Find peers on the local network over mDNS
+	#[serde(default = "default_true")]
+	pub mdns: bool,
+	/// Peers whose addresses are known without discovery
+	#[serde(default)]
+	pub static_addrs: Vec<StaticPeerAddr>,
+	/// Close cached connections that carried no data for this long, in seconds
+	///
+	/// Zero keeps idle connections open until the peer or the network drops them.
+	#[serde(default)]
+	pub connection_idle_timeout_secs: u64,
+	/// Self-hosted relays used alongside `relay`, probed in the background
+	///
+	/// Unreachable relays are left out when networking starts and probed less
+	/// often until they answer again.
+	#[serde(default)]
+	pub trusted_relays: Vec<String>,
+	/// Commands the networking event loop queues before senders have to wait
+	///
+	/// Unset uses the built-in capacity.
+	#[serde(default)]
+	pub command_channel_capacity: Option<usize>,
+	/// How long a sender waits for room in a full command queue, in milliseconds
+	///
+	/// Zero drops the command with a warning instead of waiting. Unset uses the
+	/// built-in timeout.
+	#[serde(default)]
+	pub command_send_timeout_ms: Option<u64>,
+}
+
+impl NetworkDiscoveryConfig {
+	/// No relay, no public discovery and no mDNS: only `static_addrs` are reachable
+	pub fn offline() -> Self {
+		Self {
+			relay: RelayConfig::Disabled,
+			public_discovery: false,
+			mdns: false,
+			static_addrs: Vec::new(),
+			connection_idle_timeout_secs: 0,
+			trusted_relays: Vec::new(),
+			command_channel_capacity: None,
+			command_send_timeout_ms: None,
+		}
+	}
+}
+
+impl Default for NetworkDiscoveryConfig {
+	fn default() -> Self {
+		Self {
+			relay: RelayConfig::Default,
+			public_discovery: true,
+			mdns: true,
+			static_addrs: Vec::new(),
+			connection_idle_timeout_secs: 0,
+			trusted_relays: Vec::new(),
+			command_channel_capacity: None,
+			command_send_timeout_ms: None,
+		}
+	}
+}
+
 /// Proxy pairing configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyPairingConfig {
@@ -182,10 +335,77 @@ pub struct ProxyPairingConfig {
 	pub auto_vouch_to_all: bool,
 	/// Maximum age of vouch signatures in seconds
 	pub vouch_signature_max_age: u64,
+	/// How far in the future (seconds) a vouch timestamp may be to tolerate clock skew
+	#[serde(default = "default_vouch_max_future_skew")]
+	pub vouch_max_future_skew: u64,
 	/// Timeout for proxy confirmation in seconds
 	pub vouch_response_timeout: u64,
 	/// Maximum retries for queued vouches
 	pub vouch_queue_retry_limit: u32,
+	/// How often the vouching queue is processed, in seconds
+	#[serde(default = "default_vouch_queue_poll_secs")]
+	pub vouch_queue_poll_secs: u64,
+	/// How often expired pairing sessions are cleaned up, in seconds
+	#[serde(default = "default_session_cleanup_poll_secs")]
+	pub session_cleanup_poll_secs: u64,
+	/// Only reveal name, slug and fingerprint to devices that are not yet paired
+	#[serde(default)]
+	pub privacy_mode: bool,
+	/// Maximum number of vouching sessions held at once
+	///
+	/// Completed sessions count until they are cleaned up an hour after finishing.
+	#[serde(default = "default_max_vouching_sessions")]
+	pub max_vouching_sessions: usize,
+	/// How long a vouching session may stay open before it is finalized, in seconds
+	///
+	/// Vouches still pending at the deadline are marked unreachable.
+	#[serde(default = "default_vouching_session_ttl_secs")]
+	pub vouching_session_ttl_secs: u64,
+	/// Devices allowed to complete direct pairing without a pairing code
+	///
+	/// Entries are hex Ed25519 public keys or network fingerprint hashes. Empty means
+	/// no device is ever auto-accepted.
+	#[serde(default)]
+	pub auto_accept_pairing_from: Vec<String>,
+	/// Vouchers whose proxy pairings are auto-accepted
+	///
+	/// Only consulted when `auto_accept_vouched` is on, and the voucher must still be
+	/// trusted. Empty means every trusted voucher qualifies.
+	#[serde(default)]
+	pub auto_accept_vouches_from: Vec<Uuid>,
+	/// Consecutive rejections from a target before auto-vouching to it pauses
+	///
+	/// Zero disables the pause.
+	#[serde(default = "default_vouch_rejection_threshold")]
+	pub vouch_rejection_threshold: u32,
+	/// How long auto-vouching to a target stays paused after it hits the threshold, in seconds
+	#[serde(default = "default_vouch_rejection_cooldown_secs")]
+	pub vouch_rejection_cooldown_secs: u64,
+	/// Ask the user before pairing with each device that accepted a vouch for us
+	///
+	/// Without this a new device is paired with every device that accepted it,
+	/// without a say in which ones.
+	#[serde(default)]
+	pub vouchee_confirms: bool,
+	/// Largest device info accepted in a pairing or vouch message, in bytes
+	///
+	/// Measured as serialized JSON. Each string field is also capped at 256 bytes.
+	#[serde(default = "default_max_device_info_size")]
+	pub max_device_info_size: usize,
+	/// Least estimated entropy a pairing code's secret must carry for us to join, in bits
+	///
+	/// Codes below it are rejected even if they have not expired.
+	#[serde(default = "default_min_code_entropy_bits")]
+	pub min_code_entropy_bits: u32,
+}
+
+impl ProxyPairingConfig {
+	/// Whether a proxy pairing vouched for by this trusted device skips confirmation
+	pub fn auto_accepts_vouches_from(&self, voucher_device_id: Uuid) -> bool {
+		self.auto_accept_vouched
+			&& (self.auto_accept_vouches_from.is_empty()
+				|| self.auto_accept_vouches_from.contains(&voucher_device_id))
+	}
 }
 
 impl Default for ProxyPairingConfig {
@@ -194,8 +414,21 @@ impl Default for ProxyPairingConfig {
 			auto_accept_vouched: true,
 			auto_vouch_to_all: false,
 			vouch_signature_max_age: 300,
+			vouch_max_future_skew: default_vouch_max_future_skew(),
 			vouch_response_timeout: 60,
 			vouch_queue_retry_limit: 5,
+			vouch_queue_poll_secs: default_vouch_queue_poll_secs(),
+			session_cleanup_poll_secs: default_session_cleanup_poll_secs(),
+			privacy_mode: false,
+			max_vouching_sessions: default_max_vouching_sessions(),
+			vouching_session_ttl_secs: default_vouching_session_ttl_secs(),
+			auto_accept_pairing_from: Vec::new(),
+			auto_accept_vouches_from: Vec::new(),
+			vouch_rejection_threshold: default_vouch_rejection_threshold(),
+			vouch_rejection_cooldown_secs: default_vouch_rejection_cooldown_secs(),
+			vouchee_confirms: false,
+			max_device_info_size: default_max_device_info_size(),
+			min_code_entropy_bits: default_min_code_entropy_bits(),
 		}
 	}
 }
@@ -278,6 +511,9 @@ impl AppConfig {
 			logging: LoggingConfig::default(),
 			proxy_pairing: ProxyPairingConfig::default(),
 			spacebot: SpacebotConfig::default(),
+			network: NetworkDiscoveryConfig::default(),
+			pairing_data_dir: None,
+			volume_detection: VolumeDetectionConfig::default(),
 		}
 	}
 
@@ -303,6 +539,13 @@ impl AppConfig {
 		self.data_dir.join("libraries")
 	}
 
+	/// Get the directory pairing sessions and the vouching queue are stored under
+	pub fn pairing_dir(&self) -> PathBuf {
+		self.pairing_data_dir
+			.clone()
+			.unwrap_or_else(|| self.data_dir.clone())
+	}
+
 	/// Ensure all required directories exist
 	///
 	/// Note: On iOS, create_dir_all() can fail with "Operation not permitted" due to sandboxing
@@ -10,8 +10,8 @@ pub mod app_config;
 pub mod migration;
 
 pub use app_config::{
-	AppConfig, JobLoggingConfig, LogStreamConfig, LoggingConfig, ProxyPairingConfig, ServiceConfig,
-	SpacebotConfig,
+	AppConfig, JobLoggingConfig, LogStreamConfig, LoggingConfig, NetworkDiscoveryConfig,
+	ProxyPairingConfig, RelayConfig, ServiceConfig, SpacebotConfig, StaticPeerAddr,
 };
 pub use migration::Migrate;
 
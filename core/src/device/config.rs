@@ -95,7 +95,7 @@ impl DeviceConfig {
 	/// Create a new device configuration
 	pub fn new(name: String, os: String) -> Self {
 		// Generate slug from name
-		let slug = crate::domain::device::Device::generate_slug(&name);
+		let slug = crate::domain::device::slugify_device_name(&name);
 
 		Self {
 			id: Uuid::new_v4(),
@@ -363,6 +363,17 @@ impl DeviceManager {
 			.map_err(|_| DeviceError::LockPoisoned)
 	}
 
+	/// Poison the config lock, as a panic while holding it would
+	#[cfg(test)]
+	pub(crate) fn poison_config(&self) {
+		let config = self.config.clone();
+		let _ = std::thread::spawn(move || {
+			let _guard = config.write().unwrap();
+			panic!("poisoning device config");
+		})
+		.join();
+	}
+
 	/// Create a Device domain object from current configuration
 	pub fn to_device(&self) -> Result<Device, DeviceError> {
 		let config = self.config()?;
@@ -385,7 +396,7 @@ impl DeviceManager {
 
 		config.name = name.clone();
 		// Regenerate slug based on new name
-		config.slug = crate::domain::device::Device::generate_slug(&name);
+		config.slug = crate::domain::device::slugify_device_name(&name);
 
 		// Save to the appropriate location based on whether we have a custom data dir
 		if let Some(data_dir) = &self.data_dir {
@@ -71,7 +71,7 @@ pub struct Device {
 	pub swap_total_bytes: Option<i64>,
 
 	/// Network addresses for P2P connections
-	pub network_addresses: Vec<String>,
+	pub network_addresses: Vec<NetworkAddress>,
 
 	/// Device capabilities (indexing, P2P, volume detection, etc.)
 	pub capabilities: serde_json::Value,
@@ -197,22 +197,109 @@ pub enum DeviceFormFactor {
 	Other,
 }
 
-impl Device {
-	/// Generate URL-safe slug from device name
-	/// Converts to lowercase and replaces non-alphanumeric chars with hyphens
-	pub fn generate_slug(name: &str) -> String {
-		name.to_lowercase()
-			.chars()
-			.map(|c| if c.is_alphanumeric() { c } else { '-' })
-			.collect::<String>()
-			.trim_matches('-')
-			.to_string()
+/// How a network address reaches the device
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub enum AddrKind {
+	/// Directly dialable socket address
+	Direct,
+	/// Address of a relay server the device is reachable through
+	Relay,
+	/// Provenance not recorded (addresses stored before kinds existed)
+	Unknown,
+}
+
+/// A network address for a device, with where it came from and when it last worked
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Type)]
+pub struct NetworkAddress {
+	pub kind: AddrKind,
+	pub value: String,
+	/// Last time a connection over this address succeeded
+	pub last_verified: Option<DateTime<Utc>>,
+}
+
+impl NetworkAddress {
+	pub fn new(kind: AddrKind, value: impl Into<String>) -> Self {
+		Self {
+			kind,
+			value: value.into(),
+			last_verified: None,
+		}
+	}
+
+	/// Check that the address is usable for dialing
+	pub fn validate(&self) -> Result<(), String> {
+		if self.value.trim().is_empty() {
+			return Err("network address value is empty".to_string());
+		}
+		Ok(())
+	}
+}
+
+/// Accepts both the structured form and the legacy bare-string form
+/// (`["a", "b"]`), which parses with `kind: Unknown`.
+impl<'de> Deserialize<'de> for NetworkAddress {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Repr {
+			Legacy(String),
+			Structured {
+				kind: AddrKind,
+				value: String,
+				#[serde(default)]
+				last_verified: Option<DateTime<Utc>>,
+			},
+		}
+
+		Ok(match Repr::deserialize(deserializer)? {
+			Repr::Legacy(value) => NetworkAddress::new(AddrKind::Unknown, value),
+			Repr::Structured {
+				kind,
+				value,
+				last_verified,
+			} => NetworkAddress {
+				kind,
+				value,
+				last_verified,
+			},
+		})
 	}
+}
+
+/// Longest slug a device may use, in characters
+pub const MAX_DEVICE_SLUG_LEN: usize = 50;
+
+/// Base slug for a device name
+///
+/// Every path that derives a slug from a name (creation, rename, sync apply)
+/// goes through here, so the same name gives the same slug on every device and
+/// collision suffixes from `Library::ensure_unique_slug` line up. Lowercases,
+/// turns each run of non-alphanumeric characters into one hyphen and cuts the
+/// result to [`MAX_DEVICE_SLUG_LEN`] characters.
+pub fn slugify_device_name(name: &str) -> String {
+	let mut slug = String::with_capacity(name.len());
+	for c in name.chars().flat_map(char::to_lowercase) {
+		if c.is_alphanumeric() {
+			slug.push(c);
+		} else if !slug.is_empty() && !slug.ends_with('-') {
+			slug.push('-');
+		}
+	}
+
+	let slug: String = slug.chars().take(MAX_DEVICE_SLUG_LEN).collect();
+	let slug = slug.trim_end_matches('-');
+	if slug.is_empty() {
+		"device".to_string()
+	} else {
+		slug.to_string()
+	}
+}
 
+impl Device {
 	/// Create a new device
 	pub fn new(name: String) -> Self {
 		let now = Utc::now();
-		let slug = Self::generate_slug(&name);
+		let slug = slugify_device_name(&name);
 		let system_info = detect_system_info();
 		Self {
 			id: Uuid::new_v4(),
@@ -260,7 +347,7 @@ impl Device {
 	}
 
 	/// Update network addresses
-	pub fn update_network_addresses(&mut self, addresses: Vec<String>) {
+	pub fn update_network_addresses(&mut self, addresses: Vec<NetworkAddress>) {
 		self.network_addresses = addresses;
 		self.updated_at = Utc::now();
 	}
@@ -1198,6 +1285,22 @@ impl std::fmt::Display for DeviceFormFactor {
 	}
 }
 
+impl std::fmt::Display for AddrKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AddrKind::Direct => write!(f, "direct"),
+			AddrKind::Relay => write!(f, "relay"),
+			AddrKind::Unknown => write!(f, "unknown"),
+		}
+	}
+}
+
+impl std::fmt::Display for NetworkAddress {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} ({})", self.value, self.kind)
+	}
+}
+
 /// Parse form factor string to enum
 pub fn parse_device_form_factor_from_string(form_factor_str: &str) -> DeviceFormFactor {
 	match form_factor_str {
@@ -1243,7 +1346,7 @@ impl From<Device> for entities::device::ActiveModel {
 			boot_disk_type: Set(device.boot_disk_type),
 			boot_disk_capacity_bytes: Set(device.boot_disk_capacity_bytes),
 			swap_total_bytes: Set(device.swap_total_bytes),
-			network_addresses: Set(serde_json::json!(device.network_addresses)),
+			network_addresses: Set(entities::device::NetworkAddresses(device.network_addresses)),
 			is_online: Set(device.is_online),
 			last_seen_at: Set(device.last_seen_at),
 			capabilities: Set(device.capabilities),
@@ -1258,8 +1361,6 @@ impl TryFrom<entities::device::Model> for Device {
 	type Error = serde_json::Error;
 
 	fn try_from(model: entities::device::Model) -> Result<Self, Self::Error> {
-		let network_addresses: Vec<String> = serde_json::from_value(model.network_addresses)?;
-
 		let gpu_models: Option<Vec<String>> = model
 			.gpu_models
 			.and_then(|v| serde_json::from_value(v).ok());
@@ -1283,7 +1384,7 @@ impl TryFrom<entities::device::Model> for Device {
 			boot_disk_type: model.boot_disk_type,
 			boot_disk_capacity_bytes: model.boot_disk_capacity_bytes,
 			swap_total_bytes: model.swap_total_bytes,
-			network_addresses,
+			network_addresses: model.network_addresses.0,
 			capabilities: model.capabilities,
 			is_online: model.is_online,
 			last_seen_at: model.last_seen_at,
@@ -1352,3 +1453,89 @@ impl super::resource::Identifiable for Device {
 
 // Register Device as a simple resource
 crate::register_resource!(Device);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_slugify_device_name() {
+		assert_eq!(
+			slugify_device_name("Jamie's MacBook Pro"),
+			"jamie-s-macbook-pro"
+		);
+		assert_eq!(slugify_device_name("  My \t Laptop  "), "my-laptop");
+		assert_eq!(
+			slugify_device_name("Ordinateur de Zoë"),
+			"ordinateur-de-zoë"
+		);
+		assert_eq!(slugify_device_name("東京のPC"), "東京のpc");
+		assert_eq!(slugify_device_name("ÉCOLE"), "école");
+		assert_eq!(slugify_device_name("!!!"), "device");
+	}
+
+	#[test]
+	fn test_slugify_truncates_long_names_deterministically() {
+		let name = "a".repeat(30) + " " + &"é".repeat(40);
+		let slug = slugify_device_name(&name);
+		assert_eq!(slug.chars().count(), MAX_DEVICE_SLUG_LEN);
+		assert_eq!(slug, slugify_device_name(&name));
+
+		// A cut landing right after a separator leaves no trailing hyphen
+		let name = "b".repeat(MAX_DEVICE_SLUG_LEN - 1) + " tail";
+		assert_eq!(
+			slugify_device_name(&name),
+			"b".repeat(MAX_DEVICE_SLUG_LEN - 1)
+		);
+	}
+
+	#[test]
+	fn test_legacy_network_addresses_parse_as_unknown() {
+		let legacy = serde_json::json!(["192.168.1.10:7373", "relay.example.com"]);
+		let addresses: Vec<NetworkAddress> = serde_json::from_value(legacy).unwrap();
+
+		assert_eq!(
+			addresses,
+			vec![
+				NetworkAddress::new(AddrKind::Unknown, "192.168.1.10:7373"),
+				NetworkAddress::new(AddrKind::Unknown, "relay.example.com"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_structured_network_addresses_round_trip() {
+		let addresses = vec![
+			NetworkAddress {
+				kind: AddrKind::Direct,
+				value: "10.0.0.2:7373".to_string(),
+				last_verified: Some(Utc::now()),
+			},
+			NetworkAddress::new(AddrKind::Relay, "https://relay.example.com"),
+		];
+
+		let json = serde_json::to_value(&addresses).unwrap();
+		let parsed: Vec<NetworkAddress> = serde_json::from_value(json).unwrap();
+		assert_eq!(parsed, addresses);
+	}
+
+	#[test]
+	fn test_mixed_legacy_and_structured_addresses() {
+		// A row migrated by a newer peer may be merged with legacy entries
+		let mixed = serde_json::json!([
+			"192.168.1.10:7373",
+			{ "kind": "Direct", "value": "10.0.0.2:7373" },
+		]);
+		let addresses: Vec<NetworkAddress> = serde_json::from_value(mixed).unwrap();
+
+		assert_eq!(addresses[0].kind, AddrKind::Unknown);
+		assert_eq!(addresses[1].kind, AddrKind::Direct);
+		assert_eq!(addresses[1].last_verified, None);
+	}
+
+	#[test]
+	fn test_unknown_addr_kind_is_rejected() {
+		let bad = serde_json::json!([{ "kind": "Carrier", "value": "x" }]);
+		assert!(serde_json::from_value::<Vec<NetworkAddress>>(bad).is_err());
+	}
+}
@@ -94,6 +94,19 @@ pub enum ScanState {
 	Paused,
 }
 
+impl ScanState {
+	/// Parse the `scan_state` column of a location row
+	pub fn from_db_value(value: &str) -> Self {
+		match value {
+			"pending" | "idle" => ScanState::Idle,
+			"scanning" | "running" => ScanState::Scanning { progress: 0 },
+			"completed" => ScanState::Completed,
+			"error" | "failed" => ScanState::Failed,
+			_ => ScanState::Idle,
+		}
+	}
+}
+
 impl Location {
 	/// Create a new location
 	pub fn new(library_id: Uuid, name: String, sd_path: SdPath, index_mode: IndexMode) -> Self {
@@ -281,13 +294,7 @@ impl Location {
 			_ => IndexMode::Deep,
 		};
 
-		let scan_state = match model.scan_state.as_str() {
-			"pending" | "idle" => ScanState::Idle,
-			"scanning" => ScanState::Scanning { progress: 0 },
-			"completed" => ScanState::Completed,
-			"error" | "failed" => ScanState::Failed,
-			_ => ScanState::Idle,
-		};
+		let scan_state = ScanState::from_db_value(&model.scan_state);
 
 		let job_policies = model
 			.job_policies
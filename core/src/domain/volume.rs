@@ -7,6 +7,7 @@ use crate::domain::resource::Identifiable;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::HashMap;
 use std::fmt;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -58,6 +59,31 @@ impl VolumeFingerprint {
 		Self(hasher.finalize().to_hex().to_string())
 	}
 
+	/// Create fingerprint from an identifier the hardware keeps across reconnects
+	///
+	/// Meant for volume GUIDs on Windows, partition UUIDs on Linux and APFS volume
+	/// UUIDs on macOS, which survive drive letter and mount point changes. Callers
+	/// fall back to [`Self::from_primary_volume`] when no such id is available.
+	/// Case, surrounding whitespace and the `\\?\Volume{...}\` wrapper Windows
+	/// puts around GUIDs do not affect the result.
+	pub fn from_stable_hardware_id(id: &str, device_id: Uuid) -> Self {
+		let trimmed = id.trim();
+		let unwrapped = trimmed
+			.strip_prefix(r"\\?\Volume")
+			.map(|rest| rest.trim_end_matches('\\'))
+			.unwrap_or(trimmed);
+		let normalized = unwrapped
+			.trim_start_matches('{')
+			.trim_end_matches('}')
+			.to_lowercase();
+
+		let mut hasher = blake3::Hasher::new();
+		hasher.update(b"stable_hardware_v1:");
+		hasher.update(normalized.as_bytes());
+		hasher.update(device_id.as_bytes());
+		Self(hasher.finalize().to_hex().to_string())
+	}
+
 	/// Create fingerprint for network/cloud volume using backend identifier
 	/// This is used for network shares, cloud storage, etc.
 	pub fn from_network_volume(backend_id: &str, mount_uri: &str) -> Self {
@@ -68,6 +94,18 @@ impl VolumeFingerprint {
 		Self(hasher.finalize().to_hex().to_string())
 	}
 
+	/// Derive a distinct fingerprint for a volume whose base inputs collided
+	///
+	/// Cloned drives can share every identifier we fingerprint. Mixing in the
+	/// mount point keeps each of them addressable while they're mounted apart.
+	pub fn disambiguated(&self, mount_point: &std::path::Path) -> Self {
+		let mut hasher = blake3::Hasher::new();
+		hasher.update(b"disambiguated_v1:");
+		hasher.update(self.0.as_bytes());
+		hasher.update(mount_point.to_string_lossy().as_bytes());
+		Self(hasher.finalize().to_hex().to_string())
+	}
+
 	/// Create a fingerprint from a Spacedrive identifier UUID
 	/// Deprecated: Use from_external_volume instead for proper device binding
 	pub fn from_spacedrive_id(spacedrive_id: Uuid) -> Self {
@@ -204,6 +242,35 @@ pub struct VolumeInfo {
 	pub error_status: Option<String>,
 }
 
+/// How a re-detected volume differs from the copy already cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeChange {
+	Unchanged,
+	/// Only free space or capacity moved
+	Usage,
+	/// Mount state, mount type, filesystem, read-only flag or error changed
+	Structural,
+}
+
+impl VolumeChange {
+	pub fn between(previous: &Volume, current: &Volume) -> Self {
+		if previous.is_mounted != current.is_mounted
+			|| previous.mount_type != current.mount_type
+			|| previous.file_system != current.file_system
+			|| previous.is_read_only != current.is_read_only
+			|| previous.error_message != current.error_message
+		{
+			Self::Structural
+		} else if previous.available_space != current.available_space
+			|| previous.total_capacity != current.total_capacity
+		{
+			Self::Usage
+		} else {
+			Self::Unchanged
+		}
+	}
+}
+
 /// Information about a tracked volume in the database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackedVolume {
@@ -259,15 +326,39 @@ pub enum VolumeEvent {
 		fingerprint: VolumeFingerprint,
 		error: String,
 	},
+	/// A filesystem was mounted, as reported by a platform watcher
+	Mounted {
+		mount_point: PathBuf,
+	},
+	/// A filesystem was unmounted, as reported by a platform watcher
+	Unmounted {
+		mount_point: PathBuf,
+	},
 }
 
 /// Configuration for volume detection and monitoring
-#[derive(Debug, Clone)]
+///
+/// Kept in the app config; settings missing there take their defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct VolumeDetectionConfig {
 	pub include_system: bool,
 	pub include_virtual: bool,
 	pub run_speed_test: bool,
+	/// How often volumes are re-enumerated, unless overridden for their type
 	pub refresh_interval_secs: u64,
+	/// Per-type overrides for `refresh_interval_secs`
+	///
+	/// Network mounts are slow and chatty to enumerate, so by default they are
+	/// polled far less often than local volumes.
+	pub type_refresh_intervals: HashMap<VolumeType, u64>,
+	/// Volumes that are always included, regardless of the system/virtual filters
+	pub pinned_fingerprints: Vec<VolumeFingerprint>,
+	/// Write a `.spacedrive-volume-id` file to external volumes for stable identification
+	///
+	/// When off, external volumes are never touched and fall back to a mount point
+	/// based fingerprint, which changes if the drive is mounted elsewhere.
+	pub write_external_dotfiles: bool,
 }
 
 impl Default for VolumeDetectionConfig {
@@ -277,10 +368,28 @@ impl Default for VolumeDetectionConfig {
 			include_virtual: false,
 			run_speed_test: false,
 			refresh_interval_secs: 30,
+			type_refresh_intervals: HashMap::from([(VolumeType::Network, 300)]),
+			pinned_fingerprints: Vec::new(),
+			write_external_dotfiles: true,
 		}
 	}
 }
 
+impl VolumeDetectionConfig {
+	/// Whether the user explicitly pinned this volume for inclusion
+	pub fn is_pinned(&self, fingerprint: &VolumeFingerprint) -> bool {
+		self.pinned_fingerprints.contains(fingerprint)
+	}
+
+	/// Refresh interval in seconds for volumes of one type
+	pub fn refresh_interval_for(&self, volume_type: VolumeType) -> u64 {
+		self.type_refresh_intervals
+			.get(&volume_type)
+			.copied()
+			.unwrap_or(self.refresh_interval_secs)
+	}
+}
+
 /// Helper function to skip serializing Unknown disk types
 fn is_unknown_disk_type(disk_type: &DiskType) -> bool {
 	matches!(disk_type, DiskType::Unknown)
@@ -413,7 +522,7 @@ pub struct Volume {
 }
 
 /// Volume type classification
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
 pub enum VolumeType {
 	/// Primary system drive containing OS and user data
 	Primary,
@@ -442,6 +551,18 @@ impl Default for VolumeType {
 }
 
 impl VolumeType {
+	pub const ALL: [VolumeType; 9] = [
+		VolumeType::Primary,
+		VolumeType::UserData,
+		VolumeType::External,
+		VolumeType::Secondary,
+		VolumeType::System,
+		VolumeType::Network,
+		VolumeType::Cloud,
+		VolumeType::Virtual,
+		VolumeType::Unknown,
+	];
+
 	/// Should this volume type be auto-tracked by default?
 	pub fn auto_track_by_default(&self) -> bool {
 		matches!(self, VolumeType::Primary)
@@ -1141,4 +1262,50 @@ mod tests {
 		volume.supports_block_cloning = true;
 		assert!(volume.supports_cow());
 	}
+
+	#[test]
+	fn test_detection_config_fills_missing_settings_with_defaults() {
+		let pinned = VolumeFingerprint("pinned".to_string());
+		let config: VolumeDetectionConfig = serde_json::from_value(serde_json::json!({
+			"include_system": false,
+			"pinned_fingerprints": [pinned.0.clone()],
+			"type_refresh_intervals": { "External": 5 },
+		}))
+		.unwrap();
+
+		assert!(!config.include_system);
+		assert!(config.is_pinned(&pinned));
+		assert_eq!(config.refresh_interval_for(VolumeType::External), 5);
+		assert_eq!(config.refresh_interval_for(VolumeType::Primary), 30);
+		assert!(config.write_external_dotfiles);
+	}
+
+	#[test]
+	fn test_stable_hardware_id_survives_reconnects() {
+		let device_id = Uuid::new_v4();
+		let guid = r"\\?\Volume{3F2504E0-4F89-11D3-9A0C-0305E82C3301}\";
+
+		// The drive comes back under a different letter after a reconnect
+		let first = VolumeFingerprint::from_stable_hardware_id(guid, device_id);
+		let reconnected = VolumeFingerprint::from_stable_hardware_id(guid, device_id);
+		assert_eq!(first, reconnected);
+		assert_ne!(
+			VolumeFingerprint::from_primary_volume(&PathBuf::from(r"D:\"), device_id),
+			VolumeFingerprint::from_primary_volume(&PathBuf::from(r"E:\"), device_id)
+		);
+
+		// The same GUID written differently still matches
+		assert_eq!(
+			first,
+			VolumeFingerprint::from_stable_hardware_id(
+				"{3f2504e0-4f89-11d3-9a0c-0305e82c3301}",
+				device_id
+			)
+		);
+
+		assert_ne!(
+			first,
+			VolumeFingerprint::from_stable_hardware_id(guid, Uuid::new_v4())
+		);
+	}
 }
@@ -114,6 +114,87 @@ impl Model {
 	pub fn combined_size(&self) -> i64 {
 		self.entry_count as i64 * self.total_size
 	}
+
+	/// Serialize rows for sync, converting FKs to UUIDs
+	async fn to_sync_records(
+		results: Vec<Model>,
+		db: &DatabaseConnection,
+	) -> Result<Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>, sea_orm::DbErr> {
+		let mut sync_results: Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)> =
+			Vec::with_capacity(results.len());
+		for content in results {
+			let uuid = match content.uuid {
+				Some(u) => u,
+				None => continue,
+			};
+
+			let json = match content.to_sync_json() {
+				Ok(j) => j,
+				Err(e) => {
+					tracing::warn!(error = %e, content_hash = %content.content_hash, "Failed to serialize content_identity for sync");
+					continue;
+				}
+			};
+
+			sync_results.push((uuid, json, content.last_verified_at));
+		}
+
+		// Batch FK → UUID conversion across the whole batch: one DB round trip
+		// per FK type instead of one per (record × FK). Records that fail
+		// resolution are dropped so peers never see a sender-local int in the
+		// payload.
+		let fk_mappings = Self::foreign_key_mappings();
+		if !fk_mappings.is_empty() && !sync_results.is_empty() {
+			let mut payloads: Vec<serde_json::Value> = sync_results
+				.iter()
+				.map(|(_, json, _)| json.clone())
+				.collect();
+			let mut failed_indices: std::collections::HashSet<usize> =
+				std::collections::HashSet::new();
+
+			for fk in &fk_mappings {
+				match crate::infra::sync::fk_mapper::convert_fks_to_uuids_batch(
+					&mut payloads,
+					fk,
+					db,
+				)
+				.await
+				{
+					Ok(failed) => failed_indices.extend(failed),
+					Err(e) => {
+						tracing::warn!(
+							error = %e,
+							fk_field = fk.local_field,
+							"Batch FK conversion failed for content_identity"
+						);
+						return Err(sea_orm::DbErr::Custom(format!(
+							"ContentIdentity FK batch conversion failed: {}",
+							e
+						)));
+					}
+				}
+			}
+
+			sync_results = sync_results
+				.into_iter()
+				.zip(payloads.into_iter())
+				.enumerate()
+				.filter_map(|(idx, ((uuid, _, ts), resolved))| {
+					if failed_indices.contains(&idx) {
+						tracing::warn!(
+							uuid = %uuid,
+							"Dropping content_identity with unresolved FK from sync batch"
+						);
+						None
+					} else {
+						Some((uuid, resolved, ts))
+					}
+				})
+				.collect();
+		}
+
+		Ok(sync_results)
+	}
 }
 
 // Syncable Implementation
@@ -245,81 +326,53 @@ impl Syncable for Model {
 		query = query.limit(batch_size as u64);
 
 		let results = query.all(db).await?;
+		Self::to_sync_records(results, db).await
+	}
 
-		let mut sync_results: Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)> =
-			Vec::with_capacity(results.len());
-		for content in results {
-			let uuid = match content.uuid {
-				Some(u) => u,
-				None => continue,
-			};
-
-			let json = match content.to_sync_json() {
-				Ok(j) => j,
-				Err(e) => {
-					tracing::warn!(error = %e, content_hash = %content.content_hash, "Failed to serialize content_identity for sync");
-					continue;
-				}
-			};
+	fn content_hash_field() -> Option<&'static str> {
+		Some("content_hash")
+	}
 
-			sync_results.push((uuid, json, content.last_verified_at));
+	async fn local_content(
+		hashes: Vec<String>,
+		db: &DatabaseConnection,
+	) -> Result<std::collections::HashMap<String, chrono::DateTime<chrono::Utc>>, sea_orm::DbErr> {
+		use crate::infra::sync::CONTENT_HASH_BATCH_SIZE;
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+
+		let mut local = std::collections::HashMap::with_capacity(hashes.len());
+		for chunk in hashes.chunks(CONTENT_HASH_BATCH_SIZE) {
+			let rows: Vec<(String, DateTimeUtc)> = Entity::find()
+				.select_only()
+				.column(Column::ContentHash)
+				.column(Column::LastVerifiedAt)
+				.filter(Column::ContentHash.is_in(chunk.to_vec()))
+				.into_tuple()
+				.all(db)
+				.await?;
+			local.extend(rows);
 		}
+		Ok(local)
+	}
 
-		// Batch FK → UUID conversion across the whole batch: one DB round trip
-		// per FK type instead of one per (record × FK). Records that fail
-		// resolution are dropped so peers never see a sender-local int in the
-		// payload.
-		let fk_mappings = Self::foreign_key_mappings();
-		if !fk_mappings.is_empty() && !sync_results.is_empty() {
-			let mut payloads: Vec<serde_json::Value> = sync_results
-				.iter()
-				.map(|(_, json, _)| json.clone())
-				.collect();
-			let mut failed_indices: std::collections::HashSet<usize> =
-				std::collections::HashSet::new();
-
-			for fk in &fk_mappings {
-				match crate::infra::sync::fk_mapper::convert_fks_to_uuids_batch(
-					&mut payloads,
-					fk,
-					db,
-				)
-				.await
-				{
-					Ok(failed) => failed_indices.extend(failed),
-					Err(e) => {
-						tracing::warn!(
-							error = %e,
-							fk_field = fk.local_field,
-							"Batch FK conversion failed for content_identity"
-						);
-						return Err(sea_orm::DbErr::Custom(format!(
-							"ContentIdentity FK batch conversion failed: {}",
-							e
-						)));
-					}
-				}
-			}
+	async fn query_content_for_sync(
+		hashes: Vec<String>,
+		db: &DatabaseConnection,
+	) -> Result<Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>, sea_orm::DbErr> {
+		use crate::infra::sync::CONTENT_HASH_BATCH_SIZE;
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
-			sync_results = sync_results
-				.into_iter()
-				.zip(payloads.into_iter())
-				.enumerate()
-				.filter_map(|(idx, ((uuid, _, ts), resolved))| {
-					if failed_indices.contains(&idx) {
-						tracing::warn!(
-							uuid = %uuid,
-							"Dropping content_identity with unresolved FK from sync batch"
-						);
-						None
-					} else {
-						Some((uuid, resolved, ts))
-					}
-				})
-				.collect();
+		let mut results = Vec::with_capacity(hashes.len());
+		for chunk in hashes.chunks(CONTENT_HASH_BATCH_SIZE) {
+			results.extend(
+				Entity::find()
+					.filter(Column::Uuid.is_not_null())
+					.filter(Column::ContentHash.is_in(chunk.to_vec()))
+					.all(db)
+					.await?,
+			);
 		}
-
-		Ok(sync_results)
+		Self::to_sync_records(results, db).await
 	}
 
 	async fn apply_shared_change(
@@ -1,8 +1,10 @@
 //! Device entity
 
-use sea_orm::entity::prelude::*;
+use sea_orm::{entity::prelude::*, FromJsonQueryResult};
 use serde::{Deserialize, Serialize};
 
+use crate::domain::NetworkAddress;
+
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "devices")]
 pub struct Model {
@@ -30,7 +32,8 @@ pub struct Model {
 	pub boot_disk_capacity_bytes: Option<i64>,
 	pub swap_total_bytes: Option<i64>,
 
-	pub network_addresses: Json, // Vec<String> as JSON
+	#[sea_orm(column_type = "Json")]
+	pub network_addresses: NetworkAddresses,
 	pub is_online: bool,
 	pub last_seen_at: DateTimeUtc,
 	pub capabilities: Json, // DeviceCapabilities as JSON
@@ -42,6 +45,12 @@ pub struct Model {
 	pub sync_enabled: bool,
 }
 
+/// JSON column holding a device's structured network addresses
+///
+/// Legacy rows (`["a", "b"]`) still load; see `NetworkAddress`'s deserializer.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct NetworkAddresses(pub Vec<NetworkAddress>);
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
 	#[sea_orm(has_many = "super::location::Entity")]
@@ -166,9 +175,118 @@ impl crate::infra::sync::Syncable for Model {
 		entry: crate::infra::sync::SharedChangeEntry,
 		db: &DatabaseConnection,
 	) -> Result<(), sea_orm::DbErr> {
-		use crate::infra::sync::ChangeType;
-		use sea_orm::{ActiveValue::NotSet, ColumnTrait, EntityTrait, QueryFilter, Set};
+		apply_shared_changes_batch(vec![entry], db).await
+	}
+
+	fn batches_shared_changes() -> bool {
+		true
+	}
+
+	async fn apply_shared_changes_batch(
+		entries: Vec<crate::infra::sync::SharedChangeEntry>,
+		db: &DatabaseConnection,
+	) -> Result<(), sea_orm::DbErr> {
+		apply_shared_changes_batch(entries, db).await
+	}
+}
+
+/// Rows per multi-row upsert, kept well below SQLite's bound variable limit
+const UPSERT_CHUNK_SIZE: usize = 200;
+
+/// Columns overwritten when an incoming device already exists
+const UPSERT_UPDATE_COLUMNS: [Column; 23] = [
+	Column::Name,
+	Column::Slug, // Now updated on conflict to allow slug changes
+	Column::Os,
+	Column::OsVersion,
+	Column::HardwareModel,
+	Column::CpuModel,
+	Column::CpuArchitecture,
+	Column::CpuCoresPhysical,
+	Column::CpuCoresLogical,
+	Column::CpuFrequencyMhz,
+	Column::MemoryTotalBytes,
+	Column::FormFactor,
+	Column::Manufacturer,
+	Column::GpuModels,
+	Column::BootDiskType,
+	Column::BootDiskCapacityBytes,
+	Column::SwapTotalBytes,
+	Column::NetworkAddresses,
+	Column::IsOnline,
+	Column::LastSeenAt,
+	Column::Capabilities,
+	Column::UpdatedAt,
+	Column::SyncEnabled,
+];
+
+/// Apply many shared device changes at once
+///
+/// Inserts and updates become multi-row upserts and deletes a single
+/// `DELETE ... IN`, instead of one statement per change. Only the batch's own
+/// devices are read back; the table's slugs are loaded the first time a new
+/// device needs a collision check. When a device appears more than once, the
+/// last change wins, and a device deleted earlier in the batch is treated as new.
+pub async fn apply_shared_changes_batch(
+	entries: Vec<crate::infra::sync::SharedChangeEntry>,
+	db: &DatabaseConnection,
+) -> Result<(), sea_orm::DbErr> {
+	use sea_orm::TransactionTrait;
+
+	match entries.len() {
+		0 => Ok(()),
+		// A single change runs at most one statement, so it needs no transaction
+		1 => apply_changes(entries, db).await,
+		_ => {
+			let txn = db.begin().await?;
+			apply_changes(entries, &txn).await?;
+			txn.commit().await
+		}
+	}
+}
+
+async fn apply_changes<C: sea_orm::ConnectionTrait>(
+	entries: Vec<crate::infra::sync::SharedChangeEntry>,
+	conn: &C,
+) -> Result<(), sea_orm::DbErr> {
+	use crate::infra::sync::ChangeType;
+	use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+	use std::collections::{HashMap, HashSet};
+
+	// Stored rows for the devices this batch touches
+	let batch_uuids: Vec<Uuid> = entries
+		.iter()
+		.flat_map(|entry| {
+			let data_uuid = entry
+				.data
+				.get("uuid")
+				.and_then(|uuid| serde_json::from_value(uuid.clone()).ok());
+			std::iter::once(entry.record_uuid).chain(data_uuid)
+		})
+		.collect::<HashSet<_>>()
+		.into_iter()
+		.collect();
+	let mut existing: HashMap<Uuid, Model> = HashMap::new();
+	for chunk in batch_uuids.chunks(UPSERT_CHUNK_SIZE) {
+		let rows = Entity::find()
+			.filter(Column::Uuid.is_in(chunk.to_vec()))
+			.all(conn)
+			.await?;
+		existing.extend(rows.into_iter().map(|d| (d.uuid, d)));
+	}
+	let mut known_uuids: HashSet<Uuid> = existing.keys().copied().collect();
+
+	// Slug per device; only loaded once a new device needs a collision check,
+	// until then the batch's own slug changes are collected on their own
+	let mut slugs: Option<HashMap<Uuid, String>> = None;
+	let mut slug_changes: HashMap<Uuid, Option<String>> = HashMap::new();
 
+	// Last upsert per device, in first-seen order
+	let mut upserts: Vec<Option<ActiveModel>> = Vec::new();
+	let mut upsert_index: HashMap<Uuid, usize> = HashMap::new();
+	let mut deletes: HashSet<Uuid> = HashSet::new();
+
+	for entry in entries {
 		match entry.change_type {
 			ChangeType::Insert | ChangeType::Update => {
 				tracing::debug!(
@@ -189,39 +307,53 @@ impl crate::infra::sync::Syncable for Model {
 				)
 				.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid uuid: {}", e)))?;
 
-				// Check if device already exists
-				let existing_device = Entity::find().filter(Column::Uuid.eq(uuid)).one(db).await?;
-
 				// Determine slug to use: collision avoidance only on INSERT
-				let slug_from_data: String = serde_json::from_value(
-					data.get("slug")
-						.cloned()
-						.unwrap_or(serde_json::Value::String("unknown".to_string())),
-				)
-				.unwrap_or_else(|_| "unknown".to_string());
-
-				let slug_to_use = if let Some(existing) = &existing_device {
+				// Peers that didn't send a slug get the one their name would produce locally
+				let slug_from_data: String = data
+					.get("slug")
+					.and_then(|slug| slug.as_str())
+					.map(str::to_string)
+					.unwrap_or_else(|| {
+						crate::domain::device::slugify_device_name(
+							data.get("name")
+								.and_then(|name| name.as_str())
+								.unwrap_or("unknown"),
+						)
+					});
+
+				let slug_to_use = if known_uuids.contains(&uuid) {
 					// Device exists - use incoming slug (allow slug changes to propagate)
 					tracing::debug!(
-						"[DEVICE_SYNC] Updating existing device, accepting slug change: {} -> {}",
-						existing.slug,
+						"[DEVICE_SYNC] Updating existing device, accepting slug: {}",
 						slug_from_data
 					);
 					slug_from_data
 				} else {
 					// New device - check for slug collisions
-					tracing::debug!("[DEVICE_SYNC] New device, checking for slug collisions");
-					let existing_slugs: Vec<String> = Entity::find()
-						.all(db)
-						.await?
+					if slugs.is_none() {
+						let mut loaded: HashMap<Uuid, String> = Entity::find()
+							.select_only()
+							.column(Column::Uuid)
+							.column(Column::Slug)
+							.into_tuple::<(Uuid, String)>()
+							.all(conn)
+							.await?
+							.into_iter()
+							.collect();
+						for (uuid, slug) in slug_changes.drain() {
+							match slug {
+								Some(slug) => loaded.insert(uuid, slug),
+								None => loaded.remove(&uuid),
+							};
+						}
+						slugs = Some(loaded);
+					}
+					let taken: Vec<String> = slugs
 						.iter()
-						.map(|d| d.slug.clone())
+						.flat_map(|slugs| slugs.values().cloned())
 						.collect();
-
-					let unique_slug = crate::library::Library::ensure_unique_slug(
-						&slug_from_data,
-						&existing_slugs,
-					);
+					let unique_slug =
+						crate::library::Library::ensure_unique_slug(&slug_from_data, &taken);
 
 					if unique_slug != slug_from_data {
 						tracing::debug!(
@@ -231,237 +363,418 @@ impl crate::infra::sync::Syncable for Model {
 						);
 					}
 
+					known_uuids.insert(uuid);
 					unique_slug
 				};
 
-				// Build ActiveModel for upsert
-				let active = ActiveModel {
-					id: NotSet,
-					uuid: Set(uuid),
-					name: Set(serde_json::from_value(
-						data.get("name")
-							.cloned()
-							.unwrap_or(serde_json::Value::String("Unknown".to_string())),
-					)
-					.unwrap_or_else(|_| "Unknown".to_string())),
-					slug: Set(slug_to_use),
-					os: Set(serde_json::from_value(
-						data.get("os")
-							.cloned()
-							.unwrap_or(serde_json::Value::String("Unknown".to_string())),
-					)
-					.unwrap_or_else(|_| "Unknown".to_string())),
-					os_version: Set(data
-						.get("os_version")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<String>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid os_version: {}", e))
-							})
-						})
-						.transpose()?),
-					hardware_model: Set(data
-						.get("hardware_model")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<String>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid hardware_model: {}", e))
-							})
-						})
-						.transpose()?),
-					cpu_model: Set(data
-						.get("cpu_model")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<String>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid cpu_model: {}", e))
-							})
-						})
-						.transpose()?),
-					cpu_architecture: Set(data
-						.get("cpu_architecture")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<String>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid cpu_architecture: {}", e))
-							})
-						})
-						.transpose()?),
-					cpu_cores_physical: Set(data
-						.get("cpu_cores_physical")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<u32>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid cpu_cores_physical: {}", e))
-							})
-						})
-						.transpose()?),
-					cpu_cores_logical: Set(data
-						.get("cpu_cores_logical")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<u32>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid cpu_cores_logical: {}", e))
-							})
-						})
-						.transpose()?),
-					cpu_frequency_mhz: Set(data
-						.get("cpu_frequency_mhz")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<i64>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid cpu_frequency_mhz: {}", e))
-							})
-						})
-						.transpose()?),
-					memory_total_bytes: Set(data
-						.get("memory_total_bytes")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<i64>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid memory_total_bytes: {}", e))
-							})
-						})
-						.transpose()?),
-					form_factor: Set(data
-						.get("form_factor")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<String>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid form_factor: {}", e))
-							})
-						})
-						.transpose()?),
-					manufacturer: Set(data
-						.get("manufacturer")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<String>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid manufacturer: {}", e))
-							})
-						})
-						.transpose()?),
-					gpu_models: Set(data
-						.get("gpu_models")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<Json>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid gpu_models: {}", e))
-							})
-						})
-						.transpose()?),
-					boot_disk_type: Set(data
-						.get("boot_disk_type")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<String>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid boot_disk_type: {}", e))
-							})
-						})
-						.transpose()?),
-					boot_disk_capacity_bytes: Set(data
-						.get("boot_disk_capacity_bytes")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<i64>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!(
-									"Invalid boot_disk_capacity_bytes: {}",
-									e
-								))
-							})
-						})
-						.transpose()?),
-					swap_total_bytes: Set(data
-						.get("swap_total_bytes")
-						.filter(|v| !v.is_null())
-						.map(|v| {
-							serde_json::from_value::<i64>(v.clone()).map_err(|e| {
-								sea_orm::DbErr::Custom(format!("Invalid swap_total_bytes: {}", e))
-							})
-						})
-						.transpose()?),
-					network_addresses: Set(serde_json::from_value(
-						data.get("network_addresses")
-							.cloned()
-							.unwrap_or(serde_json::json!([])),
-					)
-					.map_err(|e| {
-						sea_orm::DbErr::Custom(format!("Invalid network_addresses: {}", e))
-					})?),
-					is_online: Set(serde_json::from_value(
-						data.get("is_online")
-							.cloned()
-							.unwrap_or(serde_json::Value::Bool(false)),
-					)
-					.unwrap_or(false)),
-					last_seen_at: Set(serde_json::from_value(
-						data.get("last_seen_at")
-							.cloned()
-							.unwrap_or_else(|| serde_json::json!(chrono::Utc::now())),
-					)
-					.unwrap_or_else(|_| chrono::Utc::now().into())),
-					capabilities: Set(serde_json::from_value(
-						data.get("capabilities")
-							.cloned()
-							.unwrap_or(serde_json::json!({})),
-					)
-					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid capabilities: {}", e)))?),
-					created_at: Set(chrono::Utc::now().into()),
-					updated_at: Set(chrono::Utc::now().into()),
-					sync_enabled: Set(serde_json::from_value(
-						data.get("sync_enabled")
-							.cloned()
-							.unwrap_or(serde_json::Value::Bool(true)),
-					)
-					.unwrap_or(true)),
-				};
+				let mut active = active_model_from_sync(data, uuid, slug_to_use.clone())?;
+
+				// Records may leave out columns; keep what we already have for those
+				let mut recorded_slug = slug_to_use;
+				if let Some(current) = existing.get(&uuid) {
+					for column in UPSERT_UPDATE_COLUMNS {
+						if !matches!(column, Column::UpdatedAt)
+							&& !data.contains_key(column.as_str())
+						{
+							active.set(column, current.get(column));
+						}
+					}
+					if !data.contains_key("slug") {
+						recorded_slug = current.slug.clone();
+					}
+				}
+				match slugs.as_mut() {
+					Some(slugs) => {
+						slugs.insert(uuid, recorded_slug);
+					}
+					None => {
+						slug_changes.insert(uuid, Some(recorded_slug));
+					}
+				}
 
-				// Idempotent upsert: insert or update based on UUID
-				Entity::insert(active)
-					.on_conflict(
-						sea_orm::sea_query::OnConflict::column(Column::Uuid)
-							.update_columns([
-								Column::Name,
-								Column::Slug, // Now updated on conflict to allow slug changes
-								Column::Os,
-								Column::OsVersion,
-								Column::HardwareModel,
-								Column::CpuModel,
-								Column::CpuArchitecture,
-								Column::CpuCoresPhysical,
-								Column::CpuCoresLogical,
-								Column::CpuFrequencyMhz,
-								Column::MemoryTotalBytes,
-								Column::FormFactor,
-								Column::Manufacturer,
-								Column::GpuModels,
-								Column::BootDiskType,
-								Column::BootDiskCapacityBytes,
-								Column::SwapTotalBytes,
-								Column::NetworkAddresses,
-								Column::IsOnline,
-								Column::LastSeenAt,
-								Column::Capabilities,
-								Column::UpdatedAt,
-								Column::SyncEnabled,
-							])
-							.to_owned(),
-					)
-					.exec(db)
-					.await?;
+				deletes.remove(&uuid);
+				match upsert_index.get(&uuid) {
+					Some(&i) => upserts[i] = Some(active),
+					None => {
+						upsert_index.insert(uuid, upserts.len());
+						upserts.push(Some(active));
+					}
+				}
 			}
 
 			ChangeType::Delete => {
-				// Delete by UUID
 				tracing::debug!("[DEVICE_SYNC] Deleting device: uuid={}", entry.record_uuid);
-				Entity::delete_many()
-					.filter(Column::Uuid.eq(entry.record_uuid))
-					.exec(db)
-					.await?;
+				if let Some(&i) = upsert_index.get(&entry.record_uuid) {
+					upserts[i] = None;
+				}
+				// A later insert of the same device starts from scratch
+				known_uuids.remove(&entry.record_uuid);
+				existing.remove(&entry.record_uuid);
+				match slugs.as_mut() {
+					Some(slugs) => {
+						slugs.remove(&entry.record_uuid);
+					}
+					None => {
+						slug_changes.insert(entry.record_uuid, None);
+					}
+				}
+				deletes.insert(entry.record_uuid);
+			}
+		}
+	}
+
+	if !deletes.is_empty() {
+		Entity::delete_many()
+			.filter(Column::Uuid.is_in(deletes))
+			.exec(conn)
+			.await?;
+	}
+
+	let upserts: Vec<ActiveModel> = upserts.into_iter().flatten().collect();
+	for chunk in upserts.chunks(UPSERT_CHUNK_SIZE) {
+		// Idempotent upsert: insert or update based on UUID
+		Entity::insert_many(chunk.to_vec())
+			.on_conflict(
+				sea_orm::sea_query::OnConflict::column(Column::Uuid)
+					.update_columns(UPSERT_UPDATE_COLUMNS)
+					.to_owned(),
+			)
+			.exec(conn)
+			.await?;
+	}
+
+	Ok(())
+}
+
+/// Build the row for an incoming device from its sync JSON
+fn active_model_from_sync(
+	data: &serde_json::Map<String, serde_json::Value>,
+	uuid: Uuid,
+	slug: String,
+) -> Result<ActiveModel, sea_orm::DbErr> {
+	use sea_orm::{ActiveValue::NotSet, Set};
+
+	Ok(ActiveModel {
+		id: NotSet,
+		uuid: Set(uuid),
+		name: Set(serde_json::from_value(
+			data.get("name")
+				.cloned()
+				.unwrap_or(serde_json::Value::String("Unknown".to_string())),
+		)
+		.unwrap_or_else(|_| "Unknown".to_string())),
+		slug: Set(slug),
+		os: Set(serde_json::from_value(
+			data.get("os")
+				.cloned()
+				.unwrap_or(serde_json::Value::String("Unknown".to_string())),
+		)
+		.unwrap_or_else(|_| "Unknown".to_string())),
+		os_version: Set(data
+			.get("os_version")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<String>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid os_version: {}", e)))
+			})
+			.transpose()?),
+		hardware_model: Set(data
+			.get("hardware_model")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<String>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid hardware_model: {}", e)))
+			})
+			.transpose()?),
+		cpu_model: Set(data
+			.get("cpu_model")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<String>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid cpu_model: {}", e)))
+			})
+			.transpose()?),
+		cpu_architecture: Set(data
+			.get("cpu_architecture")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<String>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid cpu_architecture: {}", e)))
+			})
+			.transpose()?),
+		cpu_cores_physical: Set(data
+			.get("cpu_cores_physical")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<u32>(v.clone()).map_err(|e| {
+					sea_orm::DbErr::Custom(format!("Invalid cpu_cores_physical: {}", e))
+				})
+			})
+			.transpose()?),
+		cpu_cores_logical: Set(data
+			.get("cpu_cores_logical")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<u32>(v.clone()).map_err(|e| {
+					sea_orm::DbErr::Custom(format!("Invalid cpu_cores_logical: {}", e))
+				})
+			})
+			.transpose()?),
+		cpu_frequency_mhz: Set(data
+			.get("cpu_frequency_mhz")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<i64>(v.clone()).map_err(|e| {
+					sea_orm::DbErr::Custom(format!("Invalid cpu_frequency_mhz: {}", e))
+				})
+			})
+			.transpose()?),
+		memory_total_bytes: Set(data
+			.get("memory_total_bytes")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<i64>(v.clone()).map_err(|e| {
+					sea_orm::DbErr::Custom(format!("Invalid memory_total_bytes: {}", e))
+				})
+			})
+			.transpose()?),
+		form_factor: Set(data
+			.get("form_factor")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<String>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid form_factor: {}", e)))
+			})
+			.transpose()?),
+		manufacturer: Set(data
+			.get("manufacturer")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<String>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid manufacturer: {}", e)))
+			})
+			.transpose()?),
+		gpu_models: Set(data
+			.get("gpu_models")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<Json>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid gpu_models: {}", e)))
+			})
+			.transpose()?),
+		boot_disk_type: Set(data
+			.get("boot_disk_type")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<String>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid boot_disk_type: {}", e)))
+			})
+			.transpose()?),
+		boot_disk_capacity_bytes: Set(data
+			.get("boot_disk_capacity_bytes")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<i64>(v.clone()).map_err(|e| {
+					sea_orm::DbErr::Custom(format!("Invalid boot_disk_capacity_bytes: {}", e))
+				})
+			})
+			.transpose()?),
+		swap_total_bytes: Set(data
+			.get("swap_total_bytes")
+			.filter(|v| !v.is_null())
+			.map(|v| {
+				serde_json::from_value::<i64>(v.clone())
+					.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid swap_total_bytes: {}", e)))
+			})
+			.transpose()?),
+		network_addresses: Set({
+			let addresses: NetworkAddresses = serde_json::from_value(
+				data.get("network_addresses")
+					.cloned()
+					.unwrap_or(serde_json::json!([])),
+			)
+			.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid network_addresses: {}", e)))?;
+			for address in &addresses.0 {
+				address.validate().map_err(|e| {
+					sea_orm::DbErr::Custom(format!("Invalid network_addresses: {}", e))
+				})?;
 			}
+			addresses
+		}),
+		is_online: Set(serde_json::from_value(
+			data.get("is_online")
+				.cloned()
+				.unwrap_or(serde_json::Value::Bool(false)),
+		)
+		.unwrap_or(false)),
+		last_seen_at: Set(serde_json::from_value(
+			data.get("last_seen_at")
+				.cloned()
+				.unwrap_or_else(|| serde_json::json!(chrono::Utc::now())),
+		)
+		.unwrap_or_else(|_| chrono::Utc::now().into())),
+		capabilities: Set(serde_json::from_value(
+			data.get("capabilities")
+				.cloned()
+				.unwrap_or(serde_json::json!({})),
+		)
+		.map_err(|e| sea_orm::DbErr::Custom(format!("Invalid capabilities: {}", e)))?),
+		created_at: Set(chrono::Utc::now().into()),
+		updated_at: Set(chrono::Utc::now().into()),
+		sync_enabled: Set(serde_json::from_value(
+			data.get("sync_enabled")
+				.cloned()
+				.unwrap_or(serde_json::Value::Bool(true)),
+		)
+		.unwrap_or(true)),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::infra::db::Database;
+	use crate::infra::sync::{ChangeType, SharedChangeEntry, SystemTimeSource, HLC};
+	use tempfile::TempDir;
+
+	fn device_change(uuid: Uuid, slug: &str) -> SharedChangeEntry {
+		SharedChangeEntry {
+			hlc: HLC::now(Uuid::new_v4(), &SystemTimeSource),
+			model_type: "device".to_string(),
+			record_uuid: uuid,
+			change_type: ChangeType::Insert,
+			data: serde_json::json!({
+				"uuid": uuid,
+				"name": "Peer",
+				"slug": slug,
+				"os": "Linux",
+				"network_addresses": [],
+				"is_online": false,
+				"capabilities": {},
+				"sync_enabled": true,
+			}),
 		}
+	}
+
+	#[tokio::test]
+	async fn test_batch_applies_many_device_changes() {
+		let temp_dir = TempDir::new().unwrap();
+		let db = Database::create(&temp_dir.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+
+		let uuids: Vec<Uuid> = (0..100).map(|_| Uuid::new_v4()).collect();
+		let changes = uuids
+			.iter()
+			.map(|uuid| device_change(*uuid, "peer"))
+			.collect();
+		apply_shared_changes_batch(changes, db.conn())
+			.await
+			.unwrap();
+
+		let rows = Entity::find().all(db.conn()).await.unwrap();
+		assert_eq!(rows.len(), 100);
+
+		// Every insert got a distinct slug even though they all asked for the same one
+		let slugs: std::collections::HashSet<_> = rows.iter().map(|d| d.slug.as_str()).collect();
+		assert_eq!(slugs.len(), 100);
+		assert!(slugs.contains("peer"));
+
+		// Re-applying with each device's stored slug updates the rows in place
+		let changes = rows
+			.iter()
+			.map(|d| device_change(d.uuid, &d.slug))
+			.collect();
+		apply_shared_changes_batch(changes, db.conn())
+			.await
+			.unwrap();
+		assert_eq!(Entity::find().all(db.conn()).await.unwrap().len(), 100);
+	}
+
+	#[tokio::test]
+	async fn test_delete_then_insert_in_one_batch_starts_fresh() {
+		let temp_dir = TempDir::new().unwrap();
+		let db = Database::create(&temp_dir.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+
+		let uuid = Uuid::new_v4();
+		let mut original = device_change(uuid, "peer");
+		original.data["cpu_model"] = serde_json::json!("M2");
+		apply_shared_changes_batch(vec![original], db.conn())
+			.await
+			.unwrap();
+
+		let mut delete = device_change(uuid, "peer");
+		delete.change_type = ChangeType::Delete;
+		let mut reinsert = device_change(uuid, "peer");
+		reinsert.data.as_object_mut().unwrap().remove("cpu_model");
+		apply_shared_changes_batch(vec![delete, reinsert], db.conn())
+			.await
+			.unwrap();
+
+		// The deleted row's slug doesn't collide with its own re-insert, and none
+		// of its columns are carried over
+		let rows = Entity::find().all(db.conn()).await.unwrap();
+		assert_eq!(rows.len(), 1);
+		assert_eq!(rows[0].slug, "peer");
+		assert_eq!(rows[0].cpu_model, None);
+	}
 
-		Ok(())
+	#[tokio::test]
+	async fn test_partial_record_keeps_missing_columns() {
+		let temp_dir = TempDir::new().unwrap();
+		let receiver = Database::create(&temp_dir.path().join("receiver.db"))
+			.await
+			.unwrap();
+		receiver.migrate().await.unwrap();
+
+		let uuid = Uuid::new_v4();
+		let mut change = device_change(uuid, "peer");
+		change.data["cpu_model"] = serde_json::json!("M2");
+		change.data["memory_total_bytes"] = serde_json::json!(17_179_869_184i64);
+		apply_shared_changes_batch(vec![change.clone()], receiver.conn())
+			.await
+			.unwrap();
+
+		// A record from a peer that doesn't send hardware specs
+		let mut data = change.data.clone();
+		let fields = data.as_object_mut().unwrap();
+		fields.remove("cpu_model");
+		fields.remove("memory_total_bytes");
+		data["name"] = serde_json::json!("Renamed");
+		let partial = SharedChangeEntry {
+			hlc: HLC::now(Uuid::new_v4(), &SystemTimeSource),
+			model_type: "device".to_string(),
+			record_uuid: uuid,
+			change_type: ChangeType::Update,
+			data,
+		};
+
+		// An existing row takes the fields sent and keeps its hardware specs
+		apply_shared_changes_batch(vec![partial.clone()], receiver.conn())
+			.await
+			.unwrap();
+		let row = Entity::find().one(receiver.conn()).await.unwrap().unwrap();
+		assert_eq!(row.name, "Renamed");
+		assert_eq!(row.slug, "peer");
+		assert_eq!(row.cpu_model.as_deref(), Some("M2"));
+		assert_eq!(row.memory_total_bytes, Some(17_179_869_184));
+
+		// A new row is inserted with hardware left unset
+		let fresh = Database::create(&temp_dir.path().join("fresh.db"))
+			.await
+			.unwrap();
+		fresh.migrate().await.unwrap();
+		apply_shared_changes_batch(vec![partial], fresh.conn())
+			.await
+			.unwrap();
+		let row = Entity::find().one(fresh.conn()).await.unwrap().unwrap();
+		assert_eq!(row.name, "Renamed");
+		assert_eq!(row.cpu_model, None);
+		assert_eq!(row.memory_total_bytes, None);
 	}
 }
 
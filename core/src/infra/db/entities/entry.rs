@@ -204,45 +204,9 @@ impl crate::infra::sync::Syncable for Model {
 		use crate::infra::sync::Syncable;
 		use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
 
-		let mut query = Entity::find();
-
-		// Filter by device ownership if specified (critical for device-owned data sync)
-		// Entries reference volumes, which reference devices - join through the chain
-		if let Some(owner_device_uuid) = device_id {
-			tracing::debug!(
-				device_uuid = %owner_device_uuid,
-				"Filtering entries by volume ownership"
-			);
-
-			// Join through volume to filter by device ownership
-			// This allows volume ownership changes to automatically transfer all entries
-			use sea_orm::JoinType;
-			query = query
-				.join(JoinType::InnerJoin, super::entry::Relation::Volume.def())
-				.filter(super::volume::Column::DeviceId.eq(owner_device_uuid));
-
-			// Check how many entries match for debugging
-			let count_with_device = query.clone().count(db).await?;
-
-			tracing::debug!(
-				entries_with_device = count_with_device,
-				"Entries matching device ownership through volume"
-			);
-
-			if count_with_device == 0 {
-				tracing::warn!(
-					device_uuid = %owner_device_uuid,
-					"No entries found for device, returning empty"
-				);
-				return Ok(Vec::new());
-			}
-		}
-
-		// Filter by watermark timestamp if specified
-		// Use indexed_at (when we indexed/synced) not modified_at (file modification time)
-		if let Some(since_time) = since {
-			query = query.filter(Column::IndexedAt.gte(since_time));
-		}
+		let Some(mut query) = sync_scope(device_id, since, db).await? else {
+			return Ok(Vec::new());
+		};
 
 		// Cursor-based pagination with tie-breaker
 		// WHERE (indexed_at > cursor_ts) OR (indexed_at = cursor_ts AND uuid > cursor_uuid)
@@ -396,6 +360,17 @@ impl crate::infra::sync::Syncable for Model {
 		Ok(staged)
 	}
 
+	async fn count_for_sync(
+		device_id: Option<Uuid>,
+		since: Option<chrono::DateTime<chrono::Utc>>,
+		db: &DatabaseConnection,
+	) -> Result<Option<u64>, sea_orm::DbErr> {
+		match sync_scope(device_id, since, db).await? {
+			Some(query) => Ok(Some(query.count(db).await?)),
+			None => Ok(Some(0)),
+		}
+	}
+
 	/// Apply state change - already implemented in Model impl block below
 	async fn apply_state_change(
 		data: serde_json::Value,
@@ -420,6 +395,70 @@ impl crate::infra::sync::Syncable for Model {
 	}
 }
 
+/// Entries a backfill covers, before pagination
+///
+/// Shared by `query_for_sync` and `count_for_sync` so progress counts the same
+/// records the pages carry. `None` means nothing is in scope.
+async fn sync_scope(
+	device_id: Option<Uuid>,
+	since: Option<chrono::DateTime<chrono::Utc>>,
+	db: &DatabaseConnection,
+) -> Result<Option<Select<Entity>>, sea_orm::DbErr> {
+	use sea_orm::{Condition, QuerySelect};
+
+	let mut query = Entity::find();
+
+	// Filter by device ownership if specified (critical for device-owned data sync)
+	// Entries reference volumes, which reference devices - join through the chain
+	if let Some(owner_device_uuid) = device_id {
+		tracing::debug!(
+			device_uuid = %owner_device_uuid,
+			"Filtering entries by volume ownership"
+		);
+
+		// Join through volume to filter by device ownership
+		// This allows volume ownership changes to automatically transfer all entries
+		use sea_orm::JoinType;
+		query = query
+			.join(JoinType::InnerJoin, super::entry::Relation::Volume.def())
+			.filter(super::volume::Column::DeviceId.eq(owner_device_uuid));
+	}
+
+	// Partial sync: only entries inside locations shared with the requesting peer
+	if let Some(filter) = crate::infra::sync::current_location_filter() {
+		let roots: Vec<i32> = super::location::Entity::find()
+			.filter(super::location::Column::Uuid.is_in(filter.iter().copied()))
+			.all(db)
+			.await?
+			.into_iter()
+			.filter_map(|location| location.entry_id)
+			.collect();
+		if roots.is_empty() {
+			return Ok(None);
+		}
+
+		query = query.filter(
+			Condition::any().add(Column::Id.is_in(roots.clone())).add(
+				Column::Id.in_subquery(
+					sea_orm::sea_query::Query::select()
+						.column(super::entry_closure::Column::DescendantId)
+						.from(super::entry_closure::Entity)
+						.and_where(super::entry_closure::Column::AncestorId.is_in(roots))
+						.to_owned(),
+				),
+			),
+		);
+	}
+
+	// Filter by watermark timestamp if specified
+	// Use indexed_at (when we indexed/synced) not modified_at (file modification time)
+	if let Some(since_time) = since {
+		query = query.filter(Column::IndexedAt.gte(since_time));
+	}
+
+	Ok(Some(query))
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EntryKind {
 	File = 0,
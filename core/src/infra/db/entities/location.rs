@@ -175,23 +175,9 @@ impl Syncable for Model {
 		db: &DatabaseConnection,
 	) -> Result<Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>, sea_orm::DbErr> {
 		use crate::infra::sync::Syncable;
-		use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+		use sea_orm::{ColumnTrait, Condition, QueryFilter, QueryOrder, QuerySelect};
 
-		let mut query = Entity::find();
-
-		// Filter by device ownership - need to join through devices table
-		// since location.device_id is an integer FK to devices.id
-		if let Some(device_uuid) = device_id {
-			use super::device;
-			query = query
-				.inner_join(device::Entity)
-				.filter(device::Column::Uuid.eq(device_uuid));
-		}
-
-		// Filter by watermark timestamp if specified
-		if let Some(since_time) = since {
-			query = query.filter(Column::UpdatedAt.gte(since_time));
-		}
+		let mut query = sync_scope(device_id, since);
 
 		// Cursor-based pagination with tie-breaker
 		if let Some((cursor_ts, cursor_uuid)) = cursor {
@@ -248,6 +234,14 @@ impl Syncable for Model {
 		Ok(sync_results)
 	}
 
+	async fn count_for_sync(
+		device_id: Option<Uuid>,
+		since: Option<chrono::DateTime<chrono::Utc>>,
+		db: &DatabaseConnection,
+	) -> Result<Option<u64>, sea_orm::DbErr> {
+		Ok(Some(sync_scope(device_id, since).count(db).await?))
+	}
+
 	/// Apply state change with idempotent upsert by UUID.
 	/// No conflict resolution needed (device-owned).
 	async fn apply_state_change(
@@ -373,6 +367,40 @@ impl Syncable for Model {
 	}
 }
 
+/// Locations a backfill covers, before pagination
+///
+/// Shared by `query_for_sync` and `count_for_sync` so progress counts the same
+/// records the pages carry.
+fn sync_scope(
+	device_id: Option<Uuid>,
+	since: Option<chrono::DateTime<chrono::Utc>>,
+) -> Select<Entity> {
+	use sea_orm::QuerySelect;
+
+	let mut query = Entity::find();
+
+	// Filter by device ownership - need to join through devices table
+	// since location.device_id is an integer FK to devices.id
+	if let Some(device_uuid) = device_id {
+		use super::device;
+		query = query
+			.inner_join(device::Entity)
+			.filter(device::Column::Uuid.eq(device_uuid));
+	}
+
+	// Partial sync: only locations shared with the requesting peer
+	if let Some(filter) = crate::infra::sync::current_location_filter() {
+		query = query.filter(Column::Uuid.is_in(filter.iter().copied()));
+	}
+
+	// Filter by watermark timestamp if specified
+	if let Some(since_time) = since {
+		query = query.filter(Column::UpdatedAt.gte(since_time));
+	}
+
+	query
+}
+
 // Register location model for automatic sync handling
 // TODO: Re-enable when register_syncable_model macro is implemented for leaderless
 // crate::register_syncable_model!(Model);
@@ -432,6 +460,105 @@ mod tests {
 
 	// Note: apply_state_change requires database setup, tested in integration tests
 	// See core/tests/sync/location_sync_test.rs
+
+	#[tokio::test]
+	async fn test_count_for_sync_matches_query_scope() {
+		use crate::infra::db::Database;
+		use crate::infra::sync::{
+			with_location_filter, ChangeType, SharedChangeEntry, SystemTimeSource, HLC,
+		};
+		use sea_orm::{ActiveModelTrait, NotSet, Set};
+
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let db = Database::create(&temp_dir.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+
+		let (ours, theirs) = (Uuid::new_v4(), Uuid::new_v4());
+		let devices = [ours, theirs]
+			.into_iter()
+			.enumerate()
+			.map(|(i, uuid)| SharedChangeEntry {
+				hlc: HLC::now(Uuid::new_v4(), &SystemTimeSource),
+				model_type: "device".to_string(),
+				record_uuid: uuid,
+				change_type: ChangeType::Insert,
+				data: serde_json::json!({
+					"uuid": uuid,
+					"name": "Device",
+					"slug": format!("device-{}", i),
+					"os": "Linux",
+					"network_addresses": [],
+					"is_online": false,
+					"capabilities": {},
+					"sync_enabled": true,
+				}),
+			})
+			.collect();
+		super::super::device::apply_shared_changes_batch(devices, db.conn())
+			.await
+			.unwrap();
+
+		let mut shared = Vec::new();
+		for (device_uuid, locations) in [(ours, 3), (theirs, 2)] {
+			let device = super::super::device::Entity::find()
+				.filter(super::super::device::Column::Uuid.eq(device_uuid))
+				.one(db.conn())
+				.await
+				.unwrap()
+				.unwrap();
+			for _ in 0..locations {
+				let location = ActiveModel {
+					id: NotSet,
+					uuid: Set(Uuid::new_v4()),
+					device_id: Set(device.id),
+					volume_id: Set(None),
+					entry_id: Set(None),
+					name: Set(Some("Location".to_string())),
+					index_mode: Set("deep".to_string()),
+					scan_state: Set("completed".to_string()),
+					last_scan_at: Set(None),
+					error_message: Set(None),
+					total_file_count: Set(0),
+					total_byte_size: Set(0),
+					job_policies: Set(None),
+					created_at: Set(chrono::Utc::now()),
+					updated_at: Set(chrono::Utc::now()),
+				}
+				.insert(db.conn())
+				.await
+				.unwrap();
+				if device_uuid == ours && shared.len() < 2 {
+					shared.push(location.uuid);
+				}
+			}
+		}
+
+		// Counts only what the backfill pages would carry, not the whole table
+		let scoped = with_location_filter(Some(shared), async {
+			let count = Model::count_for_sync(Some(ours), None, db.conn())
+				.await
+				.unwrap();
+			let records = Model::query_for_sync(Some(ours), None, None, 100, db.conn())
+				.await
+				.unwrap();
+			(count, records.len())
+		})
+		.await;
+		assert_eq!(scoped, (Some(2), 2));
+
+		assert_eq!(
+			Model::count_for_sync(Some(ours), None, db.conn())
+				.await
+				.unwrap(),
+			Some(3)
+		);
+		assert_eq!(
+			Model::count_for_sync(None, None, db.conn()).await.unwrap(),
+			Some(5)
+		);
+	}
 }
 
 // Register with sync system via inventory
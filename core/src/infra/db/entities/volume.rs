@@ -187,18 +187,9 @@ impl Syncable for Model {
 		db: &DatabaseConnection,
 	) -> Result<Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>, sea_orm::DbErr> {
 		use crate::infra::sync::Syncable;
-		use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+		use sea_orm::{ColumnTrait, Condition, QueryFilter, QueryOrder, QuerySelect};
 
-		let mut query = Entity::find();
-
-		// Filter by device ownership
-		if let Some(device_uuid) = device_id {
-			query = query.filter(Column::DeviceId.eq(device_uuid));
-		}
-
-		if let Some(since_time) = since {
-			query = query.filter(Column::LastSeenAt.gte(since_time));
-		}
+		let mut query = sync_scope(device_id, since);
 
 		// Cursor-based pagination with tie-breaker
 		if let Some((cursor_ts, cursor_uuid)) = cursor {
@@ -237,6 +228,14 @@ impl Syncable for Model {
 		Ok(sync_results)
 	}
 
+	async fn count_for_sync(
+		device_id: Option<Uuid>,
+		since: Option<chrono::DateTime<chrono::Utc>>,
+		db: &DatabaseConnection,
+	) -> Result<Option<u64>, sea_orm::DbErr> {
+		Ok(Some(sync_scope(device_id, since).count(db).await?))
+	}
+
 	async fn apply_state_change(
 		data: serde_json::Value,
 		db: &DatabaseConnection,
@@ -375,5 +374,27 @@ impl Syncable for Model {
 	}
 }
 
+/// Volumes a backfill covers, before pagination
+///
+/// Shared by `query_for_sync` and `count_for_sync` so progress counts the same
+/// records the pages carry.
+fn sync_scope(
+	device_id: Option<Uuid>,
+	since: Option<chrono::DateTime<chrono::Utc>>,
+) -> Select<Entity> {
+	let mut query = Entity::find();
+
+	// Filter by device ownership
+	if let Some(device_uuid) = device_id {
+		query = query.filter(Column::DeviceId.eq(device_uuid));
+	}
+
+	if let Some(since_time) = since {
+		query = query.filter(Column::LastSeenAt.gte(since_time));
+	}
+
+	query
+}
+
 // Register with sync system via inventory
 crate::register_syncable_device_owned!(Model, "volume", "volumes", with_deletion);
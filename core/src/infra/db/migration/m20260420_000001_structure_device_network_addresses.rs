@@ -0,0 +1,53 @@
+//! Rewrite `devices.network_addresses` from bare strings to structured entries.
+//!
+//! Older rows store `["a", "b"]`. Each string becomes
+//! `{"kind": "Unknown", "value": "a", "last_verified": null}` so the column
+//! matches `NetworkAddress`. Entries that are already objects are left as-is.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.get_connection()
+			.execute_unprepared(
+				"UPDATE devices SET network_addresses = ( \
+				   SELECT json_group_array( \
+				     CASE WHEN addr.type = 'text' \
+				       THEN json_object('kind', 'Unknown', 'value', addr.value, 'last_verified', NULL) \
+				       ELSE json(addr.value) END) \
+				   FROM json_each(devices.network_addresses) AS addr) \
+				 WHERE json_valid(network_addresses) \
+				   AND json_type(network_addresses) = 'array' \
+				   AND EXISTS ( \
+				     SELECT 1 FROM json_each(devices.network_addresses) WHERE type = 'text')",
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.get_connection()
+			.execute_unprepared(
+				"UPDATE devices SET network_addresses = ( \
+				   SELECT json_group_array( \
+				     CASE WHEN addr.type = 'object' \
+				       THEN json_extract(addr.value, '$.value') \
+				       ELSE addr.value END) \
+				   FROM json_each(devices.network_addresses) AS addr) \
+				 WHERE json_valid(network_addresses) \
+				   AND json_type(network_addresses) = 'array' \
+				   AND EXISTS ( \
+				     SELECT 1 FROM json_each(devices.network_addresses) WHERE type = 'object')",
+			)
+			.await?;
+
+		Ok(())
+	}
+}
@@ -2,11 +2,14 @@
 
 pub mod log_emitter;
 
-use crate::domain::SdPath;
+use crate::domain::{ScanState, SdPath};
 use crate::infra::job::{generic_progress::GenericProgress, output::JobOutput};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 use tokio::sync::broadcast;
 use tracing::{debug, warn};
@@ -34,6 +37,8 @@ pub enum SubscriptionFilter {
 		resource_type: String,
 		path_scope: SdPath,
 	},
+	/// Kind subscription - every event of this variant (e.g. "VolumeAdded")
+	EventKind { event_type: String },
 }
 
 impl SubscriptionFilter {
@@ -52,6 +57,7 @@ impl SubscriptionFilter {
 					.map_or(false, |rt| rt == resource_type)
 					&& event.affects_path(path_scope, true) // SubscriptionFilter is legacy, default to recursive
 			}
+			Self::EventKind { event_type } => event.variant_name() == event_type,
 		}
 	}
 }
@@ -145,6 +151,22 @@ pub enum Event {
 		session_id: Uuid,
 		vouchee_device_id: Uuid,
 	},
+	/// A device accepted a vouch for this device and waits on our confirmation
+	ProxiedDeviceConfirmationRequired {
+		session_id: Uuid,
+		device_id: Uuid,
+		device_name: String,
+		voucher_device_id: Uuid,
+	},
+	/// A voucher's completion message was applied, with the devices it paired us with
+	///
+	/// Devices held for our confirmation appear in neither list.
+	ProxyPairingCompleted {
+		session_id: Uuid,
+		voucher_device_id: Uuid,
+		paired: Vec<Uuid>,
+		failed: Vec<ProxiedPairingFailure>,
+	},
 
 	// Entry events (file/directory operations)
 	// DEPRECATED: Use ResourceChanged instead
@@ -188,10 +210,24 @@ pub enum Event {
 		read_speed_mbps: u64,
 		write_speed_mbps: u64,
 	},
+	/// Free space or capacity changed and nothing else did
+	VolumeUsageChanged {
+		fingerprint: crate::volume::VolumeFingerprint,
+		available_space: u64,
+		total_capacity: u64,
+	},
 	VolumeMountChanged {
 		fingerprint: crate::volume::VolumeFingerprint,
 		is_mounted: bool,
 	},
+	/// A platform watcher saw a filesystem mounted, ahead of the refresh that tracks it
+	VolumeMounted {
+		mount_point: PathBuf,
+	},
+	/// A platform watcher saw a filesystem unmounted, ahead of the refresh that drops it
+	VolumeUnmounted {
+		mount_point: PathBuf,
+	},
 	VolumeError {
 		fingerprint: crate::volume::VolumeFingerprint,
 		error: String,
@@ -261,6 +297,12 @@ pub enum Event {
 		location_id: Uuid,
 		error: String,
 	},
+	/// A location's scan moved to a new state
+	LocationScanStateChanged {
+		library_id: Uuid,
+		location_id: Uuid,
+		state: ScanState,
+	},
 
 	// Device events
 	DeviceConnected {
@@ -270,6 +312,15 @@ pub enum Event {
 	DeviceDisconnected {
 		device_id: Uuid,
 	},
+	/// A paired device announced info that differs from what we had
+	DeviceInfoChanged {
+		device_id: Uuid,
+		changed: Vec<crate::service::network::device::DeviceInfoField>,
+	},
+	DeviceUnpaired {
+		device_id: Uuid,
+		purged_synced_data: bool,
+	},
 
 	// Sync events
 	SyncStateChanged {
@@ -388,6 +439,20 @@ impl Event {
 		}
 	}
 
+	/// Whether this event may carry data that must not outlive its delivery
+	///
+	/// `Custom` payloads are untyped, so nothing guarantees they are free of
+	/// keys or codes; they are never kept for replay.
+	pub fn may_carry_secrets(&self) -> bool {
+		matches!(self, Event::Custom { .. })
+	}
+
+	/// Key an event is buffered under for replay: its resource type, or its
+	/// variant name for events that aren't about a resource
+	fn replay_key(&self) -> &str {
+		self.resource_type().unwrap_or_else(|| self.variant_name())
+	}
+
 	/// Check if this event affects the given path scope
 	///
 	/// # Arguments
@@ -694,6 +759,13 @@ pub enum FileOperation {
 	Rename,
 }
 
+/// A device that accepted a vouch for us but could not be paired with
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ProxiedPairingFailure {
+	pub device_id: Uuid,
+	pub error: String,
+}
+
 /// A filtered subscriber with its own broadcast channel
 #[derive(Debug)]
 struct FilteredSubscriber {
@@ -702,6 +774,129 @@ struct FilteredSubscriber {
 	sender: broadcast::Sender<Event>,
 }
 
+/// Events kept for replay per resource type (or event kind)
+pub const REPLAY_BUFFER_CAPACITY: usize = 256;
+
+/// Resource types and event kinds that get their own replay ring; any beyond
+/// this share one overflow ring so the buffer stays bounded
+const MAX_REPLAY_KEYS: usize = 64;
+
+/// An event kept for clients that reconnect after it was emitted
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReplayedEvent {
+	pub emitted_at: DateTime<Utc>,
+	pub event: Event,
+}
+
+#[derive(Debug)]
+struct ReplayEntry {
+	seq: u64,
+	emitted_at: DateTime<Utc>,
+	event: Event,
+}
+
+/// Fixed-size ring of recent events for one key
+///
+/// Writers claim a slot with an atomic counter and only lock that slot, so
+/// concurrent emits rarely contend and readers never block the whole ring.
+#[derive(Debug)]
+struct ReplayRing {
+	next_slot: AtomicU64,
+	slots: Box<[parking_lot::Mutex<Option<ReplayEntry>>]>,
+}
+
+impl ReplayRing {
+	fn new() -> Self {
+		Self {
+			next_slot: AtomicU64::new(0),
+			slots: (0..REPLAY_BUFFER_CAPACITY)
+				.map(|_| parking_lot::Mutex::new(None))
+				.collect(),
+		}
+	}
+
+	fn push(&self, entry: ReplayEntry) {
+		let slot = self.next_slot.fetch_add(1, Ordering::Relaxed) as usize % self.slots.len();
+		*self.slots[slot].lock() = Some(entry);
+	}
+
+	fn collect_since(&self, since: DateTime<Utc>, out: &mut Vec<(u64, ReplayedEvent)>) {
+		for slot in self.slots.iter() {
+			if let Some(entry) = slot.lock().as_ref().filter(|e| e.emitted_at > since) {
+				out.push((
+					entry.seq,
+					ReplayedEvent {
+						emitted_at: entry.emitted_at,
+						event: entry.event.clone(),
+					},
+				));
+			}
+		}
+	}
+}
+
+/// Recent events, bounded per key so a burst of one kind can't push out the rest
+#[derive(Debug)]
+struct ReplayBuffer {
+	next_seq: AtomicU64,
+	rings: RwLock<HashMap<String, Arc<ReplayRing>>>,
+	overflow: Arc<ReplayRing>,
+}
+
+impl Default for ReplayBuffer {
+	fn default() -> Self {
+		Self {
+			next_seq: AtomicU64::new(0),
+			rings: RwLock::new(HashMap::new()),
+			overflow: Arc::new(ReplayRing::new()),
+		}
+	}
+}
+
+impl ReplayBuffer {
+	fn ring_for(&self, key: &str) -> Arc<ReplayRing> {
+		if let Some(ring) = self.rings.read().unwrap().get(key) {
+			return ring.clone();
+		}
+
+		let mut rings = self.rings.write().unwrap();
+		if let Some(ring) = rings.get(key) {
+			return ring.clone();
+		}
+		if rings.len() >= MAX_REPLAY_KEYS {
+			return self.overflow.clone();
+		}
+		rings
+			.entry(key.to_string())
+			.or_insert_with(|| Arc::new(ReplayRing::new()))
+			.clone()
+	}
+
+	fn push(&self, event: &Event) {
+		if event.may_carry_secrets() {
+			return;
+		}
+
+		self.ring_for(event.replay_key()).push(ReplayEntry {
+			seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+			emitted_at: Utc::now(),
+			event: event.clone(),
+		});
+	}
+
+	fn since(&self, since: DateTime<Utc>) -> Vec<ReplayedEvent> {
+		let rings: Vec<Arc<ReplayRing>> = self.rings.read().unwrap().values().cloned().collect();
+
+		let mut entries = Vec::new();
+		for ring in rings.iter().chain(std::iter::once(&self.overflow)) {
+			ring.collect_since(since, &mut entries);
+		}
+		entries.sort_by_key(|(seq, _)| *seq);
+
+		entries.into_iter().map(|(_, event)| event).collect()
+	}
+}
+
 /// Event bus for broadcasting events with optional filtering
 #[derive(Debug, Clone)]
 pub struct EventBus {
@@ -709,6 +904,8 @@ pub struct EventBus {
 	sender: broadcast::Sender<Event>,
 	// Filtered subscribers
 	subscribers: Arc<RwLock<Vec<FilteredSubscriber>>>,
+	// Recent events for clients catching up after a reconnect
+	replay: Arc<ReplayBuffer>,
 }
 
 impl EventBus {
@@ -718,11 +915,14 @@ impl EventBus {
 		Self {
 			sender,
 			subscribers: Arc::new(RwLock::new(Vec::new())),
+			replay: Arc::new(ReplayBuffer::default()),
 		}
 	}
 
 	/// Emit an event to all subscribers (filtered and unfiltered)
 	pub fn emit(&self, event: Event) {
+		self.replay.push(&event);
+
 		// Emit to unfiltered subscribers
 		match self.sender.send(event.clone()) {
 			Ok(count) => {
@@ -771,6 +971,9 @@ impl EventBus {
 	}
 
 	/// Subscribe with filters
+	///
+	/// Only events matching at least one filter are delivered, so consumers
+	/// interested in a few resource types or event kinds skip the rest.
 	pub fn subscribe_filtered(&self, filters: Vec<SubscriptionFilter>) -> EventSubscriber {
 		let id = Uuid::new_v4();
 		let (sender, receiver) = broadcast::channel(1024);
@@ -802,6 +1005,16 @@ impl EventBus {
 		}
 	}
 
+	/// Events emitted after `since`, oldest first
+	///
+	/// Lets a client that reconnects catch up on what it missed, such as a
+	/// pending `ProxyPairingConfirmationRequired`. Only the most recent
+	/// [`REPLAY_BUFFER_CAPACITY`] events of each resource type or event kind are
+	/// kept, and events that may carry secrets are never kept.
+	pub fn replay_since(&self, since: DateTime<Utc>) -> Vec<ReplayedEvent> {
+		self.replay.since(since)
+	}
+
 	/// Unsubscribe a filtered subscription
 	pub fn unsubscribe(&self, subscription_id: Uuid) {
 		let mut subscribers = self.subscribers.write().unwrap();
@@ -914,8 +1127,11 @@ impl EventFilter for Event {
 			Event::VolumeAdded(_)
 				| Event::VolumeRemoved { .. }
 				| Event::VolumeUpdated { .. }
+				| Event::VolumeUsageChanged { .. }
 				| Event::VolumeSpeedTested { .. }
 				| Event::VolumeMountChanged { .. }
+				| Event::VolumeMounted { .. }
+				| Event::VolumeUnmounted { .. }
 				| Event::VolumeError { .. }
 		)
 	}
@@ -967,6 +1183,9 @@ impl EventFilter for Event {
 			| Event::LocationRemoved {
 				library_id: lid, ..
 			}
+			| Event::LocationScanStateChanged {
+				library_id: lid, ..
+			}
 			| Event::FilesIndexed {
 				library_id: lid, ..
 			}
@@ -27,6 +27,7 @@ impl BackfillCheckpointStore {
 				resume_token TEXT,
 				last_watermark TEXT,
 				records_synced INTEGER NOT NULL DEFAULT 0,
+				estimated_total INTEGER,
 				started_at TEXT NOT NULL,
 				updated_at TEXT NOT NULL,
 				PRIMARY KEY (device_uuid, peer_device_uuid, resource_type)
@@ -37,6 +38,26 @@ impl BackfillCheckpointStore {
 		.await
 		.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
 
+		// Tables created before backfill progress was tracked lack the count
+		let columns = conn
+			.query_all(Statement::from_string(
+				DbBackend::Sqlite,
+				"PRAGMA table_info(backfill_checkpoints)".to_string(),
+			))
+			.await
+			.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
+		if !columns
+			.iter()
+			.any(|row| row.try_get::<String>("", "name").ok().as_deref() == Some("estimated_total"))
+		{
+			conn.execute(Statement::from_string(
+				DbBackend::Sqlite,
+				"ALTER TABLE backfill_checkpoints ADD COLUMN estimated_total INTEGER".to_string(),
+			))
+			.await
+			.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
+		}
+
 		// Create index for active checkpoints
 		conn.execute(Statement::from_string(
 			DbBackend::Sqlite,
@@ -63,13 +84,14 @@ impl BackfillCheckpointStore {
 			DbBackend::Sqlite,
 			r#"
 			INSERT INTO backfill_checkpoints
-			(device_uuid, peer_device_uuid, resource_type, resume_token, last_watermark, records_synced, started_at, updated_at)
-			VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+			(device_uuid, peer_device_uuid, resource_type, resume_token, last_watermark, records_synced, estimated_total, started_at, updated_at)
+			VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
 			ON CONFLICT (device_uuid, peer_device_uuid, resource_type)
 			DO UPDATE SET
 				resume_token = excluded.resume_token,
 				last_watermark = excluded.last_watermark,
 				records_synced = excluded.records_synced,
+				estimated_total = excluded.estimated_total,
 				updated_at = excluded.updated_at
 			"#,
 			vec![
@@ -79,6 +101,7 @@ impl BackfillCheckpointStore {
 				checkpoint.resume_token.into(),
 				checkpoint.last_watermark.map(|ts| ts.to_rfc3339()).into(),
 				(checkpoint.records_synced as i64).into(),
+				checkpoint.estimated_total.map(|total| total as i64).into(),
 				checkpoint.started_at.to_rfc3339().into(),
 				now.into(),
 			],
@@ -100,7 +123,7 @@ impl BackfillCheckpointStore {
 			.query_one(Statement::from_sql_and_values(
 				DbBackend::Sqlite,
 				r#"
-				SELECT device_uuid, peer_device_uuid, resource_type, resume_token, last_watermark, records_synced, started_at, updated_at
+				SELECT device_uuid, peer_device_uuid, resource_type, resume_token, last_watermark, records_synced, estimated_total, started_at, updated_at
 				FROM backfill_checkpoints
 				WHERE device_uuid = ? AND peer_device_uuid = ? AND resource_type = ?
 				"#,
@@ -151,6 +174,10 @@ impl BackfillCheckpointStore {
 					.try_get("", "records_synced")
 					.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
 
+				let estimated_total: Option<i64> = row
+					.try_get("", "estimated_total")
+					.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
+
 				let started_at_str: String = row
 					.try_get("", "started_at")
 					.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
@@ -165,6 +192,7 @@ impl BackfillCheckpointStore {
 					resume_token,
 					last_watermark,
 					records_synced: records_synced as usize,
+					estimated_total: estimated_total.map(|total| total as u64),
 					started_at,
 				}))
 			}
@@ -230,7 +258,7 @@ impl BackfillCheckpointStore {
 			.query_all(Statement::from_sql_and_values(
 				DbBackend::Sqlite,
 				r#"
-				SELECT device_uuid, peer_device_uuid, resource_type, resume_token, last_watermark, records_synced, started_at, updated_at
+				SELECT device_uuid, peer_device_uuid, resource_type, resume_token, last_watermark, records_synced, estimated_total, started_at, updated_at
 				FROM backfill_checkpoints
 				WHERE device_uuid = ?
 				ORDER BY peer_device_uuid, resource_type
@@ -278,6 +306,10 @@ impl BackfillCheckpointStore {
 				.try_get("", "records_synced")
 				.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
 
+			let estimated_total: Option<i64> = row
+				.try_get("", "estimated_total")
+				.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
+
 			let started_at_str: String = row
 				.try_get("", "started_at")
 				.map_err(|e| CheckpointError::QueryError(e.to_string()))?;
@@ -292,6 +324,7 @@ impl BackfillCheckpointStore {
 				resume_token,
 				last_watermark,
 				records_synced: records_synced as usize,
+				estimated_total: estimated_total.map(|total| total as u64),
 				started_at,
 			});
 		}
@@ -309,6 +342,8 @@ pub struct BackfillCheckpoint {
 	pub resume_token: Option<String>,
 	pub last_watermark: Option<DateTime<Utc>>,
 	pub records_synced: usize,
+	/// Records the peer counted when the backfill started, if it reported a count
+	pub estimated_total: Option<u64>,
 	pub started_at: DateTime<Utc>,
 }
 
@@ -354,6 +389,7 @@ mod tests {
 			resume_token: Some("token123".to_string()),
 			last_watermark: Some(Utc::now()),
 			records_synced: 500,
+			estimated_total: Some(2000),
 			started_at: Utc::now(),
 		};
 
@@ -374,6 +410,7 @@ mod tests {
 		assert_eq!(loaded.resource_type, "location");
 		assert_eq!(loaded.resume_token, Some("token123".to_string()));
 		assert_eq!(loaded.records_synced, 500);
+		assert_eq!(loaded.estimated_total, Some(2000));
 	}
 
 	#[tokio::test]
@@ -391,6 +428,7 @@ mod tests {
 			resume_token: Some("token1".to_string()),
 			last_watermark: Some(Utc::now()),
 			records_synced: 100,
+			estimated_total: None,
 			started_at: Utc::now(),
 		};
 
@@ -406,6 +444,7 @@ mod tests {
 			resume_token: Some("token2".to_string()),
 			last_watermark: Some(Utc::now() + chrono::Duration::seconds(10)),
 			records_synced: 250,
+			estimated_total: None,
 			started_at: Utc::now(),
 		};
 
@@ -437,6 +476,7 @@ mod tests {
 			resume_token: None,
 			last_watermark: None,
 			records_synced: 0,
+			estimated_total: None,
 			started_at: Utc::now(),
 		};
 
@@ -478,6 +518,7 @@ mod tests {
 				resume_token: None,
 				last_watermark: None,
 				records_synced: 0,
+				estimated_total: None,
 				started_at: Utc::now(),
 			};
 			BackfillCheckpointStore::save(&conn, checkpoint)
@@ -50,6 +50,7 @@ impl SyncConfig {
 				max_snapshot_size: 50_000,
 				realtime_batch_max_entries: 50,
 				realtime_batch_flush_interval_ms: 25,
+				apply_sub_batch_size: 250,
 			},
 			retention: RetentionConfig {
 				strategy: PruningStrategy::AcknowledgmentBased,
@@ -87,6 +88,7 @@ impl SyncConfig {
 				max_snapshot_size: 200_000,
 				realtime_batch_max_entries: 200,
 				realtime_batch_flush_interval_ms: 100,
+				apply_sub_batch_size: 1_000,
 			},
 			retention: RetentionConfig {
 				strategy: PruningStrategy::Conservative {
@@ -126,6 +128,7 @@ impl SyncConfig {
 				max_snapshot_size: 50_000,
 				realtime_batch_max_entries: 50,
 				realtime_batch_flush_interval_ms: 100,
+				apply_sub_batch_size: 250,
 			},
 			retention: RetentionConfig {
 				strategy: PruningStrategy::TimeBased { retention_days: 14 },
@@ -186,6 +189,13 @@ pub struct BatchingConfig {
 	/// Used for: Event listener batching in peer.rs
 	/// Default: 50ms
 	pub realtime_batch_flush_interval_ms: u64,
+
+	/// Records applied between watermark commits during backfill
+	///
+	/// Used for: advancing per-resource watermarks while a StateResponse is applied,
+	/// so an interrupted backfill resumes from the last committed sub-batch
+	/// Default: 500
+	pub apply_sub_batch_size: usize,
 }
 
 impl Default for BatchingConfig {
@@ -197,6 +207,7 @@ impl Default for BatchingConfig {
 			max_snapshot_size: 100_000,
 			realtime_batch_max_entries: 100,
 			realtime_batch_flush_interval_ms: 50,
+			apply_sub_batch_size: 500,
 		}
 	}
 }
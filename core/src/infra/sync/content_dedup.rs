@@ -0,0 +1,122 @@
+//! Content-addressed deduplication for backfill snapshots
+//!
+//! Content-bearing models (see [`Syncable::content_hash_field`]) appear in
+//! snapshots as `{ "uuid", "content_hash", "timestamp" }` hints instead of full
+//! records, when the requester said it understands them. The receiver drops
+//! hints for content it already holds at least as recently and fetches the rest
+//! by hash, so content indexed on both devices is only transferred once.
+//!
+//! [`Syncable::content_hash_field`]: super::Syncable::content_hash_field
+
+use super::registry::{self, ApplyError};
+use sea_orm::DatabaseConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Content hashes per local lookup or ContentRequest
+///
+/// Each hash is one bound variable in an `IN (...)` filter, so batches stay
+/// well under SQLite's variable limit.
+pub const CONTENT_HASH_BATCH_SIZE: usize = 500;
+
+/// Build snapshot entries for one model's records
+///
+/// With `content_hints`, records of content-bearing models become hash hints;
+/// everything else, and every record for requesters that did not ask for hints,
+/// is sent in full as `{ "uuid", "data" }`.
+pub async fn snapshot_records(
+	model_type: &str,
+	records: Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>,
+	content_hints: bool,
+) -> Vec<serde_json::Value> {
+	let hash_field = if content_hints {
+		registry::content_hash_field(model_type).await
+	} else {
+		None
+	};
+
+	records
+		.into_iter()
+		.map(|(uuid, data, timestamp)| {
+			let hash = hash_field
+				.and_then(|field| data.get(field))
+				.and_then(|hash| hash.as_str());
+			match hash {
+				Some(hash) => serde_json::json!({
+					"uuid": uuid,
+					"content_hash": hash,
+					"timestamp": timestamp,
+				}),
+				None => serde_json::json!({
+					"uuid": uuid,
+					"data": data,
+				}),
+			}
+		})
+		.collect()
+}
+
+/// Snapshot entries sorted by what this device still needs
+#[derive(Debug, Default)]
+pub struct ContentHints {
+	/// Full records, ready to apply
+	pub records: Vec<serde_json::Value>,
+	/// Hashes of content this device doesn't have yet, or has an older copy of
+	pub missing: Vec<String>,
+	/// Hints dropped because the content already exists locally
+	pub skipped: usize,
+}
+
+/// Check snapshot hints against local content
+///
+/// The content's UUID is derived from its hash, so a local record with the
+/// same hash is the same record. It is kept unless the hint is newer, in which
+/// case the record is fetched so the newer write wins as it would for a full
+/// record.
+pub async fn resolve_content_hints(
+	model_type: &str,
+	entries: &[serde_json::Value],
+	db: Arc<DatabaseConnection>,
+) -> Result<ContentHints, ApplyError> {
+	let mut hints = ContentHints::default();
+	let mut hinted = Vec::new();
+
+	for entry in entries {
+		match entry.get("content_hash").and_then(|h| h.as_str()) {
+			Some(hash) if entry.get("data").is_none() => {
+				let timestamp = entry
+					.get("timestamp")
+					.and_then(|ts| serde_json::from_value(ts.clone()).ok());
+				hinted.push((hash.to_string(), timestamp));
+			}
+			_ => hints.records.push(entry.clone()),
+		}
+	}
+	if hinted.is_empty() {
+		return Ok(hints);
+	}
+
+	let local = registry::local_content(
+		model_type,
+		hinted.iter().map(|(hash, _)| hash.clone()).collect(),
+		db,
+	)
+	.await?;
+
+	let mut seen = HashSet::new();
+	for (hash, timestamp) in hinted {
+		let up_to_date = match (local.get(&hash), timestamp) {
+			(Some(local_ts), Some(remote_ts)) => *local_ts >= remote_ts,
+			(Some(_), None) => true,
+			(None, _) => false,
+		};
+		if up_to_date {
+			hints.skipped += 1;
+		} else if seen.insert(hash.clone()) {
+			hints.missing.push(hash);
+		}
+	}
+
+	Ok(hints)
+}
@@ -121,11 +121,12 @@ pub fn compute_sync_order<'a>(
 	if result.len() != all_models.len() {
 		// Find the models that couldn't be sorted (part of a cycle)
 		let sorted: HashSet<_> = result.iter().cloned().collect();
-		let unsorted: Vec<_> = all_models
+		let mut unsorted: Vec<_> = all_models
 			.iter()
 			.filter(|m| !sorted.contains(*m))
 			.cloned()
 			.collect();
+		unsorted.sort();
 
 		return Err(DependencyError::CircularDependency(format!(
 			"Models involved in cycle: {}",
@@ -136,6 +137,111 @@ pub fn compute_sync_order<'a>(
 	Ok(result)
 }
 
+/// Find a dependency cycle among the declared models, if there is one
+///
+/// Returns the cycle as a path that starts and ends on the same model, e.g.
+/// `["a", "b", "a"]` when `a` depends on `b` and `b` on `a`. Models are visited
+/// in name order so the reported path is stable across runs. Dependencies on
+/// models that are not declared are treated as leaves.
+pub fn find_dependency_cycle<'a>(
+	models: impl Iterator<Item = (&'a str, &'a [&'a str])>,
+) -> Option<Vec<String>> {
+	#[derive(Clone, Copy, PartialEq)]
+	enum Mark {
+		InProgress,
+		Done,
+	}
+
+	fn visit<'a>(
+		model: &'a str,
+		graph: &HashMap<&'a str, Vec<&'a str>>,
+		marks: &mut HashMap<&'a str, Mark>,
+		path: &mut Vec<&'a str>,
+	) -> Option<Vec<String>> {
+		match marks.get(model) {
+			Some(Mark::Done) => return None,
+			Some(Mark::InProgress) => {
+				let start = path.iter().position(|m| *m == model).unwrap_or(0);
+				let mut cycle: Vec<String> = path[start..].iter().map(|m| m.to_string()).collect();
+				cycle.push(model.to_string());
+				return Some(cycle);
+			}
+			None => {}
+		}
+
+		marks.insert(model, Mark::InProgress);
+		path.push(model);
+		for dep in graph.get(model).into_iter().flatten() {
+			if let Some(cycle) = visit(dep, graph, marks, path) {
+				return Some(cycle);
+			}
+		}
+		path.pop();
+		marks.insert(model, Mark::Done);
+		None
+	}
+
+	let mut graph: HashMap<&str, Vec<&str>> = HashMap::new();
+	for (model, deps) in models {
+		let mut deps = deps.to_vec();
+		deps.sort_unstable();
+		graph.entry(model).or_default().extend(deps);
+	}
+
+	let mut roots: Vec<&str> = graph.keys().copied().collect();
+	roots.sort_unstable();
+
+	let mut marks = HashMap::new();
+	let mut path = Vec::new();
+	roots
+		.into_iter()
+		.find_map(|model| visit(model, &graph, &mut marks, &mut path))
+}
+
+/// Check that the declared dependencies can be ordered
+///
+/// Unlike [`compute_sync_order`], the error names the exact cycle path
+/// (`a -> b -> a`) so a misdeclared `sync_depends_on` is easy to find.
+pub fn validate_dependencies<'a>(
+	models: impl Iterator<Item = (&'a str, &'a [&'a str])>,
+) -> Result<(), DependencyError> {
+	match find_dependency_cycle(models) {
+		Some(cycle) => Err(DependencyError::CircularDependency(cycle.join(" -> "))),
+		None => Ok(()),
+	}
+}
+
+/// Order a subset of models so that each is applied after its dependencies
+///
+/// `graph` is the full set of registered models and their dependencies, while
+/// `models` is what actually needs applying (e.g. the model types present in a
+/// snapshot). Models missing from the graph keep their relative order and are
+/// placed last, where applying them will surface an unknown model error.
+///
+/// Unlike silently falling back to arbitrary order, a cycle in the graph is
+/// returned as an error so callers never apply children before parents.
+pub fn order_for_apply<'a>(
+	graph: impl Iterator<Item = (&'a str, &'a [&'a str])>,
+	models: impl IntoIterator<Item = String>,
+) -> Result<Vec<String>, DependencyError> {
+	let mut pending: Vec<String> = models.into_iter().collect();
+	let order = match compute_sync_order(graph) {
+		Ok(order) => order,
+		Err(DependencyError::NoModels) => Vec::new(),
+		Err(e) => return Err(e),
+	};
+
+	let mut result = Vec::with_capacity(pending.len());
+	for model in order {
+		if let Some(pos) = pending.iter().position(|m| *m == model) {
+			result.push(pending.remove(pos));
+		}
+	}
+	result.extend(pending);
+
+	Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -214,6 +320,137 @@ mod tests {
 		assert!(tag_idx < tag_rel_idx);
 	}
 
+	#[test]
+	fn test_order_for_apply_applies_dependency_first() {
+		let graph = vec![("a", &["b"][..]), ("b", &[][..])];
+		let mut applied = Vec::new();
+
+		for model in order_for_apply(graph.into_iter(), ["a".to_string(), "b".to_string()]).unwrap()
+		{
+			applied.push(model);
+		}
+
+		assert_eq!(applied, vec!["b".to_string(), "a".to_string()]);
+	}
+
+	#[test]
+	fn test_order_for_apply_only_returns_requested_models() {
+		let graph = vec![
+			("entry", &["location"][..]),
+			("location", &["device"][..]),
+			("device", &[][..]),
+		];
+
+		let order = order_for_apply(
+			graph.into_iter(),
+			[
+				"unknown".to_string(),
+				"entry".to_string(),
+				"device".to_string(),
+			],
+		)
+		.unwrap();
+
+		assert_eq!(order, vec!["device", "entry", "unknown"]);
+	}
+
+	#[test]
+	fn test_order_for_apply_reports_cycle() {
+		let graph = vec![("a", &["b"][..]), ("b", &["a"][..]), ("c", &[][..])];
+
+		let err = order_for_apply(graph.into_iter(), ["c".to_string()]).unwrap_err();
+
+		assert!(matches!(err, DependencyError::CircularDependency(_)));
+		assert_eq!(
+			err.to_string(),
+			"Circular dependency detected: Models involved in cycle: a, b"
+		);
+	}
+
+	mod mock {
+		use crate::infra::sync::Syncable;
+		use serde::Serialize;
+		use uuid::Uuid;
+
+		#[derive(Clone, Serialize)]
+		pub struct Album;
+
+		#[derive(Clone, Serialize)]
+		pub struct Cover;
+
+		impl Syncable for Album {
+			const SYNC_MODEL: &'static str = "mock_album";
+
+			fn sync_id(&self) -> Uuid {
+				Uuid::nil()
+			}
+
+			fn version(&self) -> i64 {
+				1
+			}
+
+			fn sync_depends_on() -> &'static [&'static str] {
+				&["mock_cover"]
+			}
+		}
+
+		impl Syncable for Cover {
+			const SYNC_MODEL: &'static str = "mock_cover";
+
+			fn sync_id(&self) -> Uuid {
+				Uuid::nil()
+			}
+
+			fn version(&self) -> i64 {
+				1
+			}
+
+			fn sync_depends_on() -> &'static [&'static str] {
+				&["mock_album"]
+			}
+		}
+	}
+
+	#[test]
+	fn test_validate_reports_cycle_path_between_models() {
+		use crate::infra::sync::Syncable;
+		use mock::{Album, Cover};
+
+		let models = vec![
+			(Album::SYNC_MODEL, Album::sync_depends_on()),
+			(Cover::SYNC_MODEL, Cover::sync_depends_on()),
+			("device", &[][..]),
+		];
+
+		assert_eq!(
+			find_dependency_cycle(models.clone().into_iter()),
+			Some(vec![
+				"mock_album".to_string(),
+				"mock_cover".to_string(),
+				"mock_album".to_string(),
+			])
+		);
+
+		let err = validate_dependencies(models.into_iter()).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"Circular dependency detected: mock_album -> mock_cover -> mock_album"
+		);
+	}
+
+	#[test]
+	fn test_validate_accepts_acyclic_graph() {
+		let models = vec![
+			("entry", &["location"][..]),
+			("location", &["device"][..]),
+			("device", &[][..]),
+			("tag_relationship", &["tag", "unregistered"][..]),
+		];
+
+		assert!(find_dependency_cycle(models.clone().into_iter()).is_none());
+		assert!(validate_dependencies(models.into_iter()).is_ok());
+	}
+
 	#[test]
 	fn test_no_models() {
 		let models: Vec<(&str, &[&str])> = vec![];
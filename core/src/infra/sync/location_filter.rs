@@ -0,0 +1,195 @@
+//! Task-local location filter for partial library sync.
+//!
+//! A library can be shared with a peer for only some of its locations. State
+//! requests from that peer are served inside a filter scope, and the location
+//! and entry `query_for_sync` implementations check it so rows from other
+//! locations never leave the device. Real-time changes are checked one record
+//! at a time with [`record_in_locations`].
+
+use crate::infra::db::entities::{entry, entry_closure, location};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+tokio::task_local! {
+	static LOCATION_FILTER: Arc<HashSet<Uuid>>;
+}
+
+/// Run `fut` with sync queries limited to `locations`. `None` runs it unfiltered.
+pub async fn with_location_filter<F, T>(locations: Option<Vec<Uuid>>, fut: F) -> T
+where
+	F: std::future::Future<Output = T>,
+{
+	match locations {
+		Some(locations) => {
+			LOCATION_FILTER
+				.scope(Arc::new(locations.into_iter().collect()), fut)
+				.await
+		}
+		None => fut.await,
+	}
+}
+
+/// Locations the current task's sync queries are limited to, if any.
+pub fn current_location_filter() -> Option<Arc<HashSet<Uuid>>> {
+	LOCATION_FILTER.try_with(|filter| filter.clone()).ok()
+}
+
+/// Whether the filter applies to a model at all
+pub fn is_location_scoped(model_type: &str) -> bool {
+	matches!(model_type, "location" | "entry")
+}
+
+/// Whether a single record lies inside `locations`
+///
+/// Models without a location always pass. An entry is placed by its own row
+/// when we have it and otherwise by the `parent_uuid` in `data`, so incoming
+/// entries can be checked before they are applied. `None` means the entry could
+/// not be placed, e.g. a root entry or one whose parent has not arrived yet.
+pub async fn record_in_locations(
+	model_type: &str,
+	record_uuid: Uuid,
+	data: &serde_json::Value,
+	locations: &HashSet<Uuid>,
+	db: &DatabaseConnection,
+) -> Result<Option<bool>, DbErr> {
+	match model_type {
+		"location" => Ok(Some(locations.contains(&record_uuid))),
+		"entry" => {
+			let roots: Vec<i32> = location::Entity::find()
+				.filter(location::Column::Uuid.is_in(locations.iter().copied()))
+				.all(db)
+				.await?
+				.into_iter()
+				.filter_map(|location| location.entry_id)
+				.collect();
+			if roots.is_empty() {
+				return Ok(Some(false));
+			}
+
+			let parent_uuid = data
+				.get("parent_uuid")
+				.and_then(|v| v.as_str())
+				.and_then(|s| Uuid::parse_str(s).ok());
+			for uuid in std::iter::once(record_uuid).chain(parent_uuid) {
+				let local = entry::Entity::find()
+					.filter(entry::Column::Uuid.eq(Some(uuid)))
+					.one(db)
+					.await?;
+				if let Some(local) = local {
+					return entry_under_roots(local.id, &roots, db).await.map(Some);
+				}
+			}
+			Ok(None)
+		}
+		_ => Ok(Some(true)),
+	}
+}
+
+async fn entry_under_roots(
+	entry_id: i32,
+	roots: &[i32],
+	db: &DatabaseConnection,
+) -> Result<bool, DbErr> {
+	if roots.contains(&entry_id) {
+		return Ok(true);
+	}
+	let ancestors = entry_closure::Entity::find()
+		.filter(entry_closure::Column::DescendantId.eq(entry_id))
+		.filter(entry_closure::Column::AncestorId.is_in(roots.to_vec()))
+		.count(db)
+		.await?;
+	Ok(ancestors > 0)
+}
+
+/// Location filters of the peers a library is shared with, keyed by peer
+///
+/// Used on the real-time broadcast path, where each record is checked against
+/// each filtered peer before it is sent or queued for retry.
+#[derive(Debug, Clone, Default)]
+pub struct PeerLocationFilters {
+	filters: HashMap<Uuid, HashSet<Uuid>>,
+}
+
+impl PeerLocationFilters {
+	pub fn new(filters: &HashMap<Uuid, Vec<Uuid>>) -> Self {
+		Self {
+			filters: filters
+				.iter()
+				.map(|(peer, locations)| (*peer, locations.iter().copied().collect()))
+				.collect(),
+		}
+	}
+
+	/// Whether `peer` may receive the record
+	///
+	/// Fails closed: a record that can't be placed, or a lookup error, keeps it
+	/// from a filtered peer. The peer still gets it through its filtered backfill
+	/// if it turns out to be shared.
+	pub async fn allows(
+		&self,
+		peer: Uuid,
+		model_type: &str,
+		record_uuid: Uuid,
+		data: &serde_json::Value,
+		db: &DatabaseConnection,
+	) -> bool {
+		let Some(locations) = self.filters.get(&peer) else {
+			return true;
+		};
+		if !is_location_scoped(model_type) {
+			return true;
+		}
+		match record_in_locations(model_type, record_uuid, data, locations, db).await {
+			Ok(Some(allowed)) => allowed,
+			Ok(None) => false,
+			Err(e) => {
+				tracing::warn!(
+					peer = %peer,
+					model_type = %model_type,
+					record_uuid = %record_uuid,
+					error = %e,
+					"Failed to check location filter, holding record back"
+				);
+				false
+			}
+		}
+	}
+
+	/// Keep only the peers that may receive the record
+	pub async fn retain_allowed(
+		&self,
+		peers: Vec<Uuid>,
+		model_type: &str,
+		record_uuid: Uuid,
+		data: &serde_json::Value,
+		db: &DatabaseConnection,
+	) -> Vec<Uuid> {
+		let mut allowed = Vec::with_capacity(peers.len());
+		for peer in peers {
+			if self.allows(peer, model_type, record_uuid, data, db).await {
+				allowed.push(peer);
+			}
+		}
+		allowed
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_filter_scope() {
+		assert!(current_location_filter().is_none());
+
+		let location = Uuid::new_v4();
+		let seen =
+			with_location_filter(Some(vec![location]), async { current_location_filter() }).await;
+		assert!(seen.unwrap().contains(&location));
+
+		let unfiltered = with_location_filter(None, async { current_location_filter() }).await;
+		assert!(unfiltered.is_none());
+	}
+}
@@ -13,12 +13,14 @@
 pub mod backfill_context;
 pub mod checkpoints;
 pub mod config;
+pub mod content_dedup;
 pub mod dependency_graph;
 pub mod deterministic;
 pub mod event_bus;
 pub mod event_log;
 pub mod fk_mapper;
 pub mod hlc;
+pub mod location_filter;
 pub mod peer_log;
 pub mod peer_watermarks;
 pub mod registry;
@@ -33,7 +35,13 @@ pub use checkpoints::{BackfillCheckpoint, BackfillCheckpointStore, CheckpointErr
 pub use config::{
 	BatchingConfig, MonitoringConfig, NetworkConfig, PruningStrategy, RetentionConfig, SyncConfig,
 };
-pub use dependency_graph::{compute_sync_order, DependencyError};
+pub use content_dedup::{
+	resolve_content_hints, snapshot_records, ContentHints, CONTENT_HASH_BATCH_SIZE,
+};
+pub use dependency_graph::{
+	compute_sync_order, find_dependency_cycle, order_for_apply, validate_dependencies,
+	DependencyError,
+};
 pub use deterministic::{
 	deterministic_library_default_uuid, deterministic_system_album_uuid,
 	deterministic_system_tag_uuid, system_tags,
@@ -48,14 +56,19 @@ pub use fk_mapper::{
 	map_sync_json_to_local, BatchFkMapResult, FKMapping,
 };
 pub use hlc::{HLCGenerator, HLC};
+pub use location_filter::{
+	current_location_filter, record_in_locations, with_location_filter, PeerLocationFilters,
+};
 pub use peer_log::{ChangeType, PeerLog, PeerLogError, SharedChangeEntry};
 pub use peer_watermarks::PeerWatermarkStore;
 pub use registry::{
 	apply_shared_change, apply_state_change, compute_registry_sync_order, get_fk_mappings,
-	get_table_name, is_device_owned, ApplyError, SyncableInventoryEntry, SyncableModelRegistration,
+	get_table_name, is_device_owned, list_registered_models, order_models_for_apply,
+	validate_dependency_graph, ApplyError, RegisteredSyncModel, SyncableInventoryEntry,
+	SyncableModelRegistration,
 };
 pub use syncable::Syncable;
 pub use time_source::{FakeTimeSource, SystemTimeSource, TimeSource};
 pub use transaction::{BulkOperation, BulkOperationMetadata, TransactionManager, TxError};
-pub use transport::NetworkTransport;
-pub use watermarks::{ResourceWatermarkStore, WatermarkError};
+pub use transport::{library_sync_partners, NetworkTransport};
+pub use watermarks::{ResourceWatermarkStore, SubBatchProgress, WatermarkError};
@@ -67,6 +67,9 @@ macro_rules! register_syncable_device_owned {
 					)
 					.with_fk_mappings(<$model as $crate::infra::sync::Syncable>::foreign_key_mappings)
 					.with_depends_on(<$model as $crate::infra::sync::Syncable>::sync_depends_on)
+					.with_state_count(|device_id, since, db| Box::pin(async move {
+						<$model as $crate::infra::sync::Syncable>::count_for_sync(device_id, since, db.as_ref()).await
+					}))
 				}
 			}
 		}
@@ -98,6 +101,9 @@ macro_rules! register_syncable_device_owned {
 					)
 					.with_fk_mappings(<$model as $crate::infra::sync::Syncable>::foreign_key_mappings)
 					.with_depends_on(<$model as $crate::infra::sync::Syncable>::sync_depends_on)
+					.with_state_count(|device_id, since, db| Box::pin(async move {
+						<$model as $crate::infra::sync::Syncable>::count_for_sync(device_id, since, db.as_ref()).await
+					}))
 				}
 			}
 		}
@@ -129,6 +135,9 @@ macro_rules! register_syncable_device_owned {
 					)
 					.with_fk_mappings(<$model as $crate::infra::sync::Syncable>::foreign_key_mappings)
 					.with_depends_on(<$model as $crate::infra::sync::Syncable>::sync_depends_on)
+					.with_state_count(|device_id, since, db| Box::pin(async move {
+						<$model as $crate::infra::sync::Syncable>::count_for_sync(device_id, since, db.as_ref()).await
+					}))
 					.with_post_backfill_rebuild(|db| Box::pin(async move {
 						<$model as $crate::infra::sync::Syncable>::post_backfill_rebuild(db.as_ref()).await
 					}))
@@ -168,6 +177,17 @@ macro_rules! register_syncable_shared {
 					)
 					.with_fk_mappings(<$model as $crate::infra::sync::Syncable>::foreign_key_mappings)
 					.with_depends_on(<$model as $crate::infra::sync::Syncable>::sync_depends_on)
+					.with_shared_batch_apply(
+						<$model as $crate::infra::sync::Syncable>::batches_shared_changes(),
+						|entries, db| Box::pin(async move {
+							<$model as $crate::infra::sync::Syncable>::apply_shared_changes_batch(entries, db.as_ref()).await
+						}),
+					)
+					.with_content(
+						<$model as $crate::infra::sync::Syncable>::content_hash_field(),
+						|hashes, db| Box::pin(async move { <$model as $crate::infra::sync::Syncable>::local_content(hashes, db.as_ref()).await }),
+						|hashes, db| Box::pin(async move { <$model as $crate::infra::sync::Syncable>::query_content_for_sync(hashes, db.as_ref()).await }),
+					)
 				}
 			}
 		}
@@ -195,6 +215,17 @@ macro_rules! register_syncable_shared {
 					)
 					.with_fk_mappings(<$model as $crate::infra::sync::Syncable>::foreign_key_mappings)
 					.with_depends_on(<$model as $crate::infra::sync::Syncable>::sync_depends_on)
+					.with_shared_batch_apply(
+						<$model as $crate::infra::sync::Syncable>::batches_shared_changes(),
+						|entries, db| Box::pin(async move {
+							<$model as $crate::infra::sync::Syncable>::apply_shared_changes_batch(entries, db.as_ref()).await
+						}),
+					)
+					.with_content(
+						<$model as $crate::infra::sync::Syncable>::content_hash_field(),
+						|hashes, db| Box::pin(async move { <$model as $crate::infra::sync::Syncable>::local_content(hashes, db.as_ref()).await }),
+						|hashes, db| Box::pin(async move { <$model as $crate::infra::sync::Syncable>::query_content_for_sync(hashes, db.as_ref()).await }),
+					)
 					.with_post_backfill_rebuild(|db| Box::pin(async move {
 						<$model as $crate::infra::sync::Syncable>::post_backfill_rebuild(db.as_ref()).await
 					}))
@@ -216,6 +247,13 @@ pub type SharedApplyFn = fn(
 	Arc<DatabaseConnection>,
 ) -> Pin<Box<dyn Future<Output = Result<(), sea_orm::DbErr>> + Send>>;
 
+/// Type alias for applying many log-based changes at once (shared models)
+pub type SharedBatchApplyFn =
+	fn(
+		Vec<SharedChangeEntry>,
+		Arc<DatabaseConnection>,
+	) -> Pin<Box<dyn Future<Output = Result<(), sea_orm::DbErr>> + Send>>;
+
 /// Type alias for state query function (device-owned models)
 ///
 /// Parameters: device_id, since, batch_size, db
@@ -246,6 +284,17 @@ pub type StateDeleteFn = fn(
 	Arc<DatabaseConnection>,
 ) -> Pin<Box<dyn Future<Output = Result<(), sea_orm::DbErr>> + Send>>;
 
+/// Type alias for backfill count function (device-owned models)
+///
+/// Parameters: device_id filter, since watermark, db
+/// Returns: Records the state query would return over all pages, if known
+pub type StateCountFn =
+	fn(
+		Option<uuid::Uuid>,
+		Option<chrono::DateTime<chrono::Utc>>,
+		Arc<DatabaseConnection>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<u64>, sea_orm::DbErr>> + Send>>;
+
 /// Type alias for FK ID lookup by UUID
 pub type FkLookupIdFn =
 	fn(
@@ -292,6 +341,36 @@ pub type FkMappingsFn = fn() -> Vec<super::FKMapping>;
 /// Type alias for sync depends on function
 pub type SyncDependsOnFn = fn() -> &'static [&'static str];
 
+/// Type alias for looking up local content by hash (content-bearing models)
+///
+/// Returns: content hash -> timestamp of the local record, for hashes held locally
+pub type LocalContentFn = fn(
+	Vec<String>,
+	Arc<DatabaseConnection>,
+) -> Pin<
+	Box<
+		dyn Future<Output = Result<HashMap<String, chrono::DateTime<chrono::Utc>>, sea_orm::DbErr>>
+			+ Send,
+	>,
+>;
+
+/// Type alias for querying full sync records by content hash
+///
+/// Returns: Vec of (uuid, data, timestamp)
+pub type ContentQueryFn = fn(
+	Vec<String>,
+	Arc<DatabaseConnection>,
+) -> Pin<
+	Box<
+		dyn Future<
+				Output = Result<
+					Vec<(uuid::Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>,
+					sea_orm::DbErr,
+				>,
+			> + Send,
+	>,
+>;
+
 /// Registry of syncable models
 ///
 /// Maps model_type strings (e.g., "album", "tag") to their registration info.
@@ -318,12 +397,18 @@ pub struct SyncableModelRegistration {
 	/// Apply function for log-based sync (shared models)
 	pub shared_apply_fn: Option<SharedApplyFn>,
 
+	/// Apply function for many log-based changes at once (shared models that batch)
+	pub shared_batch_apply_fn: Option<SharedBatchApplyFn>,
+
 	/// Query function for backfill (both device-owned and shared models)
 	pub state_query_fn: Option<StateQueryFn>,
 
 	/// Deletion apply function for device-owned models
 	pub state_delete_fn: Option<StateDeleteFn>,
 
+	/// Count function for backfill progress (device-owned models)
+	pub state_count_fn: Option<StateCountFn>,
+
 	// FK lookup functions (for models used as FK targets)
 	/// Lookup local ID by UUID
 	pub fk_lookup_id_fn: Option<FkLookupIdFn>,
@@ -341,6 +426,14 @@ pub struct SyncableModelRegistration {
 	pub sync_depends_on_fn: Option<SyncDependsOnFn>,
 	/// Post-backfill rebuild function (e.g., for closure tables)
 	pub post_backfill_rebuild_fn: Option<PostBackfillRebuildFn>,
+
+	// Content deduplication (content-bearing models)
+	/// Field in the sync JSON holding the content hash
+	pub content_hash_field: Option<&'static str>,
+	/// Look up which content hashes exist locally, and as of when
+	pub local_content_fn: Option<LocalContentFn>,
+	/// Query full records by content hash
+	pub content_query_fn: Option<ContentQueryFn>,
 }
 
 impl SyncableModelRegistration {
@@ -358,8 +451,10 @@ impl SyncableModelRegistration {
 			is_device_owned: true,
 			state_apply_fn: Some(apply_fn),
 			shared_apply_fn: None,
+			shared_batch_apply_fn: None,
 			state_query_fn: Some(query_fn),
 			state_delete_fn: delete_fn,
+			state_count_fn: None,
 			fk_lookup_id_fn: None,
 			fk_lookup_uuid_fn: None,
 			fk_batch_lookup_ids_fn: None,
@@ -367,6 +462,9 @@ impl SyncableModelRegistration {
 			fk_mappings_fn: None,
 			sync_depends_on_fn: None,
 			post_backfill_rebuild_fn: None,
+			content_hash_field: None,
+			local_content_fn: None,
+			content_query_fn: None,
 		}
 	}
 
@@ -382,8 +480,10 @@ impl SyncableModelRegistration {
 			is_device_owned: false,
 			state_apply_fn: None,
 			shared_apply_fn: Some(apply_fn),
+			shared_batch_apply_fn: None,
 			state_query_fn: None,
 			state_delete_fn: None,
+			state_count_fn: None,
 			fk_lookup_id_fn: None,
 			fk_lookup_uuid_fn: None,
 			fk_batch_lookup_ids_fn: None,
@@ -391,6 +491,9 @@ impl SyncableModelRegistration {
 			fk_mappings_fn: None,
 			sync_depends_on_fn: None,
 			post_backfill_rebuild_fn: None,
+			content_hash_field: None,
+			local_content_fn: None,
+			content_query_fn: None,
 		}
 	}
 
@@ -407,8 +510,10 @@ impl SyncableModelRegistration {
 			is_device_owned: false,
 			state_apply_fn: None,
 			shared_apply_fn: Some(apply_fn),
+			shared_batch_apply_fn: None,
 			state_query_fn: Some(query_fn),
 			state_delete_fn: None,
+			state_count_fn: None,
 			fk_lookup_id_fn: None,
 			fk_lookup_uuid_fn: None,
 			fk_batch_lookup_ids_fn: None,
@@ -416,6 +521,9 @@ impl SyncableModelRegistration {
 			fk_mappings_fn: None,
 			sync_depends_on_fn: None,
 			post_backfill_rebuild_fn: None,
+			content_hash_field: None,
+			local_content_fn: None,
+			content_query_fn: None,
 		}
 	}
 
@@ -451,6 +559,41 @@ impl SyncableModelRegistration {
 		self.post_backfill_rebuild_fn = Some(rebuild_fn);
 		self
 	}
+
+	/// Builder method to add the backfill count function
+	pub fn with_state_count(mut self, count_fn: StateCountFn) -> Self {
+		self.state_count_fn = Some(count_fn);
+		self
+	}
+
+	/// Builder method to add the batch apply function
+	///
+	/// Does nothing when `batches` is false, so the macros can call it for
+	/// every model.
+	pub fn with_shared_batch_apply(mut self, batches: bool, apply_fn: SharedBatchApplyFn) -> Self {
+		if batches {
+			self.shared_batch_apply_fn = Some(apply_fn);
+		}
+		self
+	}
+
+	/// Builder method to add content deduplication functions
+	///
+	/// Does nothing when `hash_field` is None, so the macros can call it for
+	/// every model.
+	pub fn with_content(
+		mut self,
+		hash_field: Option<&'static str>,
+		local_content: LocalContentFn,
+		query_content: ContentQueryFn,
+	) -> Self {
+		if hash_field.is_some() {
+			self.content_hash_field = hash_field;
+			self.local_content_fn = Some(local_content);
+			self.content_query_fn = Some(query_content);
+		}
+		self
+	}
 }
 
 /// Register a device-owned model with state-based apply and query functions
@@ -498,6 +641,35 @@ fn initialize_registry() -> HashMap<String, SyncableModelRegistration> {
 	registry
 }
 
+/// Summary of a registered syncable model, for introspection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredSyncModel {
+	/// Model type identifier (the model's `SYNC_MODEL`)
+	pub model_type: &'static str,
+	/// Table name in database
+	pub table_name: &'static str,
+	/// Whether this is device-owned (state-based) or shared (log-based)
+	pub is_device_owned: bool,
+	/// Models that must sync before this one
+	pub depends_on: &'static [&'static str],
+}
+
+/// List all registered syncable models, sorted by model type
+pub async fn list_registered_models() -> Vec<RegisteredSyncModel> {
+	let registry = SYNCABLE_REGISTRY.read().await;
+	let mut models: Vec<_> = registry
+		.values()
+		.map(|reg| RegisteredSyncModel {
+			model_type: reg.model_type,
+			table_name: reg.table_name,
+			is_device_owned: reg.is_device_owned,
+			depends_on: reg.sync_depends_on_fn.map(|f| f()).unwrap_or(&[]),
+		})
+		.collect();
+	models.sort_by_key(|model| model.model_type);
+	models
+}
+
 /// Get table name for a model type
 pub async fn get_table_name(model_type: &str) -> Option<&'static str> {
 	SYNCABLE_REGISTRY
@@ -645,6 +817,44 @@ pub async fn apply_shared_change(
 		.map_err(|e| ApplyError::DatabaseError(e.to_string()))
 }
 
+/// Whether a shared model applies many changes at once more cheaply
+pub async fn has_shared_batch_apply(model_type: &str) -> bool {
+	let registry = SYNCABLE_REGISTRY.read().await;
+	registry
+		.get(model_type)
+		.is_some_and(|reg| reg.shared_batch_apply_fn.is_some())
+}
+
+/// Apply many log-based sync entries of one shared model
+///
+/// Uses the model's batch apply function, or applies the entries one at a time
+/// when it has none.
+pub async fn apply_shared_changes_batch(
+	model_type: &str,
+	entries: Vec<SharedChangeEntry>,
+	db: Arc<DatabaseConnection>,
+) -> Result<(), ApplyError> {
+	let batch_fn = {
+		let registry = SYNCABLE_REGISTRY.read().await;
+		let registration = registry
+			.get(model_type)
+			.ok_or_else(|| ApplyError::UnknownModel(model_type.to_string()))?;
+		registration.shared_batch_apply_fn
+	};
+
+	match batch_fn {
+		Some(batch_fn) => batch_fn(entries, db)
+			.await
+			.map_err(|e| ApplyError::DatabaseError(e.to_string())),
+		None => {
+			for entry in entries {
+				apply_shared_change(entry, db.clone()).await?;
+			}
+			Ok(())
+		}
+	}
+}
+
 /// Query device state for a model type (for backfill)
 ///
 /// Routes to the appropriate model's query function via registry.
@@ -681,6 +891,33 @@ pub async fn query_device_state(
 		.map_err(|e| ApplyError::DatabaseError(e.to_string()))
 }
 
+/// Number of records a backfill of a device-owned model will send
+///
+/// Applies the same device and `since` scope as [`query_device_state`], and the
+/// location filter when called inside one, so progress lines up with the pages
+/// the peer receives. `None` when the model has no count function.
+pub async fn estimate_record_count(
+	model_type: &str,
+	device_id: Option<uuid::Uuid>,
+	since: Option<chrono::DateTime<chrono::Utc>>,
+	db: Arc<DatabaseConnection>,
+) -> Result<Option<u64>, ApplyError> {
+	let count_fn = {
+		let registry = SYNCABLE_REGISTRY.read().await;
+		let registration = registry
+			.get(model_type)
+			.ok_or_else(|| ApplyError::UnknownModel(model_type.to_string()))?;
+		registration.state_count_fn
+	};
+
+	let Some(count_fn) = count_fn else {
+		return Ok(None);
+	};
+	count_fn(device_id, since, db)
+		.await
+		.map_err(|e| ApplyError::DatabaseError(e.to_string()))
+}
+
 /// Query all shared models for backfill (generic registry-based approach)
 ///
 /// This discovers and queries ALL shared models registered in the system,
@@ -740,6 +977,64 @@ pub async fn query_all_shared_models(
 	Ok(results)
 }
 
+// =============================================================================
+// Content Deduplication Functions
+// =============================================================================
+
+/// Content hash field for a model, if it is content-bearing
+pub async fn content_hash_field(model_type: &str) -> Option<&'static str> {
+	let registry = SYNCABLE_REGISTRY.read().await;
+	registry
+		.get(model_type)
+		.and_then(|reg| reg.content_hash_field)
+}
+
+/// Look up which of these content hashes exist locally
+///
+/// Returns the local record's timestamp for each hash found. Models without
+/// content deduplication never have content, so their records are always
+/// fetched in full.
+pub async fn local_content(
+	model_type: &str,
+	hashes: Vec<String>,
+	db: Arc<DatabaseConnection>,
+) -> Result<HashMap<String, chrono::DateTime<chrono::Utc>>, ApplyError> {
+	let local_content_fn = {
+		let registry = SYNCABLE_REGISTRY.read().await;
+		let reg = registry
+			.get(model_type)
+			.ok_or_else(|| ApplyError::UnknownModel(model_type.to_string()))?;
+		match reg.local_content_fn {
+			Some(f) => f,
+			None => return Ok(HashMap::new()),
+		}
+	};
+
+	local_content_fn(hashes, db)
+		.await
+		.map_err(|e| ApplyError::DatabaseError(e.to_string()))
+}
+
+/// Query full sync records for a content-bearing model by content hash
+pub async fn query_content(
+	model_type: &str,
+	hashes: Vec<String>,
+	db: Arc<DatabaseConnection>,
+) -> Result<Vec<(uuid::Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>, ApplyError> {
+	let query_fn = {
+		let registry = SYNCABLE_REGISTRY.read().await;
+		let reg = registry
+			.get(model_type)
+			.ok_or_else(|| ApplyError::UnknownModel(model_type.to_string()))?;
+		reg.content_query_fn
+			.ok_or_else(|| ApplyError::MissingQueryFunction(model_type.to_string()))?
+	};
+
+	query_fn(hashes, db)
+		.await
+		.map_err(|e| ApplyError::DatabaseError(e.to_string()))
+}
+
 // =============================================================================
 // FK Lookup Functions (for generic FK mapping)
 // =============================================================================
@@ -908,6 +1203,40 @@ pub async fn compute_registry_sync_order() -> Result<Vec<String>, super::Depende
 	super::dependency_graph::compute_sync_order(registry_deps.into_iter())
 }
 
+/// Check the registered models for `sync_depends_on` cycles
+///
+/// Called during core startup so a cycle fails immediately with its path,
+/// rather than when the first backfill tries to order models.
+pub async fn validate_dependency_graph() -> Result<(), super::DependencyError> {
+	let registry_deps: Vec<(&str, &'static [&'static str])> = {
+		let registry = SYNCABLE_REGISTRY.read().await;
+		registry
+			.iter()
+			.filter_map(|(_, reg)| reg.sync_depends_on_fn.map(|f| (reg.model_type, f())))
+			.collect()
+	};
+
+	super::dependency_graph::validate_dependencies(registry_deps.into_iter())
+}
+
+/// Order the given model types by their registered dependencies
+///
+/// Used when applying a batch that spans several models so parents are always
+/// written before the children that reference them.
+pub async fn order_models_for_apply(
+	models: impl IntoIterator<Item = String>,
+) -> Result<Vec<String>, super::DependencyError> {
+	let registry_deps: Vec<(&str, &'static [&'static str])> = {
+		let registry = SYNCABLE_REGISTRY.read().await;
+		registry
+			.iter()
+			.filter_map(|(_, reg)| reg.sync_depends_on_fn.map(|f| (reg.model_type, f())))
+			.collect()
+	};
+
+	super::dependency_graph::order_for_apply(registry_deps.into_iter(), models)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -976,6 +1305,35 @@ mod tests {
 		assert!(!is_device_owned("nonexistent").await); // Returns false for unknown
 	}
 
+	#[tokio::test]
+	async fn test_list_registered_models() {
+		let models = list_registered_models().await;
+
+		let device = models
+			.iter()
+			.find(|model| model.model_type == "device")
+			.expect("device should be registered");
+		assert_eq!(device.table_name, "devices");
+		assert!(!device.is_device_owned);
+		assert!(device.depends_on.is_empty());
+
+		let location = models
+			.iter()
+			.find(|model| model.model_type == "location")
+			.expect("location should be registered");
+		assert!(location.is_device_owned);
+		assert!(location.depends_on.contains(&"device"));
+
+		assert!(models
+			.windows(2)
+			.all(|pair| pair[0].model_type < pair[1].model_type));
+	}
+
+	#[tokio::test]
+	async fn test_registered_dependency_graph_is_valid() {
+		validate_dependency_graph().await.unwrap();
+	}
+
 	#[tokio::test]
 	async fn test_sync_order_computation() {
 		let order = compute_registry_sync_order().await.unwrap();
@@ -183,6 +183,25 @@ pub trait Syncable: Serialize + Clone {
 		}
 	}
 
+	/// Count the records `query_for_sync` would return over all its pages
+	///
+	/// Must apply the same device, location and `since` scope as
+	/// `query_for_sync`. Used for backfill progress; the default reports no
+	/// count, so the backfill shows no percentage.
+	fn count_for_sync(
+		device_id: Option<Uuid>,
+		since: Option<chrono::DateTime<chrono::Utc>>,
+		db: &DatabaseConnection,
+	) -> impl std::future::Future<Output = Result<Option<u64>, sea_orm::DbErr>> + Send
+	where
+		Self: Sized,
+	{
+		async move {
+			let _ = (device_id, since, db);
+			Ok(None)
+		}
+	}
+
 	/// Apply a state change from sync (device-owned models only)
 	///
 	/// This is an associated function that applies a state change received
@@ -227,6 +246,37 @@ pub trait Syncable: Serialize + Clone {
 		}
 	}
 
+	/// Whether `apply_shared_changes_batch` is cheaper than applying one change
+	/// at a time
+	///
+	/// Backfill hands snapshot pages of such models over in one call. Default is
+	/// false.
+	fn batches_shared_changes() -> bool
+	where
+		Self: Sized,
+	{
+		false
+	}
+
+	/// Apply many shared changes at once (shared models only)
+	///
+	/// Models that override this should also return true from
+	/// `batches_shared_changes`. The default applies the changes in order.
+	fn apply_shared_changes_batch(
+		entries: Vec<super::SharedChangeEntry>,
+		db: &DatabaseConnection,
+	) -> impl std::future::Future<Output = Result<(), sea_orm::DbErr>> + Send
+	where
+		Self: Sized,
+	{
+		async move {
+			for entry in entries {
+				Self::apply_shared_change(entry, db).await?;
+			}
+			Ok(())
+		}
+	}
+
 	/// Apply a deletion by UUID (device-owned models only)
 	///
 	/// This is called when a deletion tombstone is received during sync.
@@ -369,6 +419,64 @@ pub trait Syncable: Serialize + Clone {
 		}
 	}
 
+	// ============================================
+	// Content Deduplication (content-bearing models)
+	// ============================================
+
+	/// Field in the sync JSON holding a hash of the record's content
+	///
+	/// Content-bearing models override this so backfill snapshots can send the
+	/// hash instead of the full record. A receiver that already has content with
+	/// that hash skips the record, and fetches the rest by hash via
+	/// `query_content_for_sync`. Default is None (always send full records).
+	fn content_hash_field() -> Option<&'static str>
+	where
+		Self: Sized,
+	{
+		None
+	}
+
+	/// Look up which of these content hashes exist locally
+	///
+	/// Returns the local record's sync timestamp for each hash found, so a newer
+	/// remote record is still fetched. Implementations should query in batches of
+	/// [`CONTENT_HASH_BATCH_SIZE`](super::CONTENT_HASH_BATCH_SIZE).
+	fn local_content(
+		hashes: Vec<String>,
+		db: &DatabaseConnection,
+	) -> impl std::future::Future<
+		Output = Result<HashMap<String, chrono::DateTime<chrono::Utc>>, sea_orm::DbErr>,
+	> + Send
+	where
+		Self: Sized,
+	{
+		async move {
+			let _ = (hashes, db);
+			Ok(HashMap::new())
+		}
+	}
+
+	/// Query full sync records for the given content hashes
+	///
+	/// Returns the same (uuid, data, timestamp) tuples as `query_for_sync`.
+	fn query_content_for_sync(
+		hashes: Vec<String>,
+		db: &DatabaseConnection,
+	) -> impl std::future::Future<
+		Output = Result<
+			Vec<(Uuid, serde_json::Value, chrono::DateTime<chrono::Utc>)>,
+			sea_orm::DbErr,
+		>,
+	> + Send
+	where
+		Self: Sized,
+	{
+		async move {
+			let _ = (hashes, db);
+			Ok(Vec::new())
+		}
+	}
+
 	// ============================================
 	// Post-Backfill Hook
 	// ============================================
@@ -157,6 +157,32 @@ pub trait NetworkTransport: Send + Sync {
 	}
 }
 
+/// Sync partners in a library, as every transport computes them
+///
+/// A partner is another device in the library's devices table with `sync_enabled`
+/// set that the transport can address (`is_known`, e.g. a NodeId mapping). Shared
+/// so the filter applied by the real transport is the one tests run against.
+pub async fn library_sync_partners(
+	db: &sea_orm::DatabaseConnection,
+	our_device_id: Uuid,
+	is_known: impl Fn(Uuid) -> bool,
+) -> Result<Vec<Uuid>> {
+	use crate::infra::db::entities;
+	use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+	let library_devices = entities::device::Entity::find()
+		.filter(entities::device::Column::SyncEnabled.eq(true))
+		.all(db)
+		.await
+		.map_err(|e| anyhow::anyhow!("Failed to query library devices: {}", e))?;
+
+	Ok(library_devices
+		.into_iter()
+		.map(|device| device.uuid)
+		.filter(|uuid| *uuid != our_device_id && is_known(*uuid))
+		.collect())
+}
+
 /// Mock implementation for testing - collects messages without sending
 #[cfg(test)]
 pub struct MockNetworkTransport {
@@ -210,6 +236,7 @@ impl NetworkTransport for MockNetworkTransport {
 				deleted_uuids: vec![],
 				checkpoint: None,
 				has_more: false,
+				estimated_total: None,
 			}),
 			SyncMessage::SharedChangeRequest { library_id, .. } => {
 				Ok(SyncMessage::SharedChangeResponse {
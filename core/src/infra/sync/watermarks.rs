@@ -6,6 +6,7 @@
 
 use chrono::{DateTime, Utc};
 use sea_orm::{ConnectionTrait, DbBackend, Statement};
+use std::collections::BTreeSet;
 use uuid::Uuid;
 
 /// Resource watermark tracking for incremental sync
@@ -277,6 +278,46 @@ pub enum WatermarkError {
 	ParseError(String),
 }
 
+/// Progress through one page of backfilled records applied in sub-batches
+///
+/// The watermark committed after a sub-batch may only reach the oldest record of
+/// the page that is still unapplied. Peers send every record at or after the
+/// watermark, so a backfill interrupted between sub-batches resumes with all
+/// unapplied records and skips the ones already committed.
+#[derive(Debug, Default)]
+pub struct SubBatchProgress {
+	pending: BTreeSet<(DateTime<Utc>, Uuid)>,
+	applied_max: Option<DateTime<Utc>>,
+}
+
+impl SubBatchProgress {
+	/// Track a page of records that are about to be applied
+	pub fn new(records: impl IntoIterator<Item = (Uuid, DateTime<Utc>)>) -> Self {
+		Self {
+			pending: records
+				.into_iter()
+				.map(|(uuid, timestamp)| (timestamp, uuid))
+				.collect(),
+			applied_max: None,
+		}
+	}
+
+	/// Record that a record was applied to the database
+	pub fn mark_applied(&mut self, uuid: Uuid, timestamp: DateTime<Utc>) {
+		self.pending.remove(&(timestamp, uuid));
+		self.applied_max = self.applied_max.max(Some(timestamp));
+	}
+
+	/// Watermark that is safe to commit now, if any record was applied
+	pub fn committable(&self) -> Option<DateTime<Utc>> {
+		let applied_max = self.applied_max?;
+		match self.pending.first() {
+			Some((oldest_pending, _)) if *oldest_pending < applied_max => Some(*oldest_pending),
+			_ => Some(applied_max),
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -392,6 +433,21 @@ mod tests {
 		assert_eq!(all.len(), 3);
 	}
 
+	#[test]
+	fn test_sub_batch_watermark_held_by_oldest_unapplied_record() {
+		let base_time = Utc::now();
+		let old = (Uuid::new_v4(), base_time);
+		let new = (Uuid::new_v4(), base_time + chrono::Duration::seconds(10));
+
+		// Applying out of order (e.g. parents first) must not skip the older record
+		let mut progress = SubBatchProgress::new([old, new]);
+		progress.mark_applied(new.0, new.1);
+		assert_eq!(progress.committable(), Some(old.1));
+
+		progress.mark_applied(old.0, old.1);
+		assert_eq!(progress.committable(), Some(new.1));
+	}
+
 	#[tokio::test]
 	async fn test_delete_peer() {
 		let (conn, _temp) = create_test_db().await;
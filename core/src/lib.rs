@@ -33,10 +33,12 @@ use crate::{
 	library::LibraryManager,
 	service::session::SessionStateService,
 	service::{
-		network::{protocol::pairing::PairingProtocolHandler, utils::logging::NetworkLogger},
+		network::{
+			protocol::pairing::PairingProtocolHandler, utils::logging::NetworkLogger, Protocol,
+		},
 		Services,
 	},
-	volume::{VolumeDetectionConfig, VolumeManager},
+	volume::VolumeManager,
 };
 
 use std::{path::PathBuf, sync::Arc};
@@ -94,6 +96,9 @@ impl Core {
 	) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
 		info!("Initializing Spacedrive at {:?}", data_dir);
 
+		// Fail fast if Syncable models declare a dependency cycle
+		crate::infra::sync::validate_dependency_graph().await?;
+
 		// Load or create app config
 		let config = match config {
 			Some(c) => c,
@@ -129,7 +134,7 @@ impl Core {
 		let logs = Arc::new(LogBus::default());
 
 		// Initialize volume manager
-		let volume_config = VolumeDetectionConfig::default();
+		let volume_config = config.read().await.volume_detection.clone();
 		let device_id = device.device_id()?;
 		let volumes = Arc::new(VolumeManager::new(device_id, volume_config, events.clone()));
 
@@ -308,6 +313,7 @@ impl Core {
 					device.clone(),
 					services.key_manager.clone(),
 					config.read().await.data_dir.clone(),
+					config.read().await.network.clone(),
 				)
 				.await
 			{
@@ -501,12 +507,16 @@ impl Core {
 
 		if !already_initialized {
 			// Initialize networking service through the services container
-			let data_dir = self.config.read().await.data_dir.clone();
+			let (data_dir, discovery_config) = {
+				let config = self.config.read().await;
+				(config.data_dir.clone(), config.network.clone())
+			};
 			self.services
 				.init_networking(
 					self.device.clone(),
 					self.services.key_manager.clone(),
 					data_dir,
+					discovery_config,
 				)
 				.await?;
 
@@ -530,7 +540,7 @@ impl Core {
 				.protocol_registry()
 				.read()
 				.await
-				.get_handler("pairing")
+				.get_handler(Protocol::Pairing)
 				.is_some();
 
 			if !already_initialized || !pairing_registered {
@@ -656,13 +666,37 @@ async fn register_default_protocol_handlers(
 		.ok_or("NetworkingEventLoop command sender not available")?
 		.clone();
 
+	// Load proxy pairing config and the pairing directory from app config
+	let app_config = crate::config::AppConfig::load_from(&context.data_dir).ok();
+	let pairing_dir = app_config
+		.as_ref()
+		.map(|config| config.pairing_dir())
+		.unwrap_or_else(|| data_dir.clone());
+	let proxy_config = app_config.map(|config| config.proxy_pairing);
+
+	// Bring pairing data over if the directory changed since the last start
+	if let Err(e) = service::network::protocol::pairing::persistence::relocate_pairing_data(
+		&data_dir,
+		&pairing_dir,
+	)
+	.await
+	{
+		logger
+			.warn(&format!(
+				"Failed to prepare pairing directory {}: {}",
+				pairing_dir.display(),
+				e
+			))
+			.await;
+	}
+
 	let pairing_handler = Arc::new(
 		service::network::protocol::PairingProtocolHandler::new_with_persistence(
 			networking.identity().clone(),
 			networking.device_registry(),
 			logger.clone(),
 			command_sender,
-			data_dir.clone(),
+			pairing_dir.clone(),
 			networking.endpoint().cloned(),
 			networking.active_connections(),
 		),
@@ -671,15 +705,12 @@ async fn register_default_protocol_handlers(
 	// Inject event bus for proxy pairing events
 	pairing_handler.set_event_bus(context.events.clone()).await;
 
-	// Load proxy pairing config from app config
-	if let Ok(app_config) = crate::config::AppConfig::load_from(&context.data_dir) {
-		pairing_handler
-			.set_proxy_config(app_config.proxy_pairing)
-			.await;
+	if let Some(proxy_config) = proxy_config {
+		pairing_handler.set_proxy_config(proxy_config).await;
 	}
 
 	// Initialize vouching queue for proxy pairing
-	if let Err(e) = pairing_handler.init_vouching_queue(data_dir.clone()).await {
+	if let Err(e) = pairing_handler.init_vouching_queue(pairing_dir).await {
 		logger
 			.warn(&format!("Failed to initialize vouching queue: {}", e))
 			.await;
@@ -716,6 +747,7 @@ async fn register_default_protocol_handlers(
 
 	// Inject context for library operations
 	messaging_handler.set_context(context.clone());
+	messaging_handler.set_connection_activity(networking.connection_activity());
 
 	let mut file_transfer_handler =
 		service::network::protocol::FileTransferProtocolHandler::new_default(logger.clone());
@@ -779,7 +811,7 @@ async fn reload_protocol_configs(
 	let guard = registry.read().await;
 
 	// Reload proxy pairing config
-	if let Some(handler) = guard.get_handler("pairing") {
+	if let Some(handler) = guard.get_handler(Protocol::Pairing) {
 		if let Some(pairing_handler) = handler
 			.as_any()
 			.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>(
@@ -792,7 +824,7 @@ async fn reload_protocol_configs(
 
 	// Future: Add config reloading for other protocol handlers here
 	// Example:
-	// if let Some(handler) = guard.get_handler("file_transfer") {
+	// if let Some(handler) = guard.get_handler(Protocol::FileTransfer) {
 	//     if let Some(ft_handler) = handler.as_any().downcast_ref::<FileTransferProtocolHandler>() {
 	//         ft_handler.set_config(app_config.file_transfer).await;
 	//     }
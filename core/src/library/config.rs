@@ -3,6 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Library configuration stored in library.json
@@ -48,6 +49,10 @@ pub struct LibrarySettings {
 	/// Whether sync is enabled for this library
 	pub sync_enabled: bool,
 
+	/// Whether the user paused sync for this library, kept so a pause survives a restart
+	#[serde(default)]
+	pub sync_paused: bool,
+
 	/// Whether the library is encrypted at rest
 	pub encryption_enabled: bool,
 
@@ -69,6 +74,16 @@ pub struct LibrarySettings {
 	/// Indexer settings (rule toggles and related)
 	#[serde(default)]
 	pub indexer: IndexerSettings,
+
+	/// Locations shared with each peer device, for peers that only sync part of the library
+	///
+	/// Peers without an entry receive every location.
+	#[serde(default)]
+	pub shared_location_filters: HashMap<Uuid, Vec<Uuid>>,
+
+	/// Locations each peer device shares with us, as announced during sync setup
+	#[serde(default)]
+	pub received_location_filters: HashMap<Uuid, Vec<Uuid>>,
 }
 
 impl LibraryConfig {
@@ -90,6 +105,7 @@ impl Default for LibrarySettings {
 			thumbnail_quality: 85,
 			enable_ai_tagging: false,
 			sync_enabled: false,
+			sync_paused: false,
 			encryption_enabled: false,
 			thumbnail_sizes: vec![128, 256, 512],
 			ignored_extensions: vec![
@@ -102,6 +118,8 @@ impl Default for LibrarySettings {
 			auto_track_system_volumes: true,               // Default to true for user convenience
 			auto_track_external_volumes: false,            // Default to false for privacy
 			indexer: IndexerSettings::default(),
+			shared_location_filters: HashMap::new(),
+			received_location_filters: HashMap::new(),
 		}
 	}
 }
@@ -0,0 +1,158 @@
+//! Cached library totals for network discovery
+//!
+//! Discovery responses report entry, location and device counts for every open
+//! library. Counting the entry table on each request gets slow for large
+//! libraries, so the totals are computed once, then kept current by the indexer
+//! as entries are created and deleted. A full recount only happens when the
+//! cache is empty, invalidated, or older than [`DISCOVERY_STATS_MAX_AGE`].
+
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// How long cached totals are trusted before discovery forces a recount
+///
+/// Incremental updates only cover the indexer. Changes arriving through sync
+/// are picked up by this periodic recount.
+pub const DISCOVERY_STATS_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+/// Library totals reported in discovery responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiscoveryStats {
+	pub total_entries: u64,
+	pub total_locations: u64,
+	/// Sum of file sizes (directories are not counted)
+	pub total_size_bytes: u64,
+	pub device_count: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachedStats {
+	stats: DiscoveryStats,
+	computed_at: DateTime<Utc>,
+}
+
+/// Incrementally maintained [`DiscoveryStats`] for one library
+#[derive(Debug, Default)]
+pub struct DiscoveryStatsCache {
+	cached: RwLock<Option<CachedStats>>,
+	recounts: AtomicU64,
+}
+
+impl DiscoveryStatsCache {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Cached totals, or None if a recount is needed
+	pub fn get(&self) -> Option<DiscoveryStats> {
+		let cached = (*self.cached.read().unwrap())?;
+		let age = Utc::now().signed_duration_since(cached.computed_at);
+		if age
+			.to_std()
+			.map_or(false, |age| age > DISCOVERY_STATS_MAX_AGE)
+		{
+			return None;
+		}
+		Some(cached.stats)
+	}
+
+	/// Store totals from a full recount
+	pub fn store_recount(&self, stats: DiscoveryStats) {
+		*self.cached.write().unwrap() = Some(CachedStats {
+			stats,
+			computed_at: Utc::now(),
+		});
+		self.recounts.fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Number of full recounts stored so far
+	pub fn recount_count(&self) -> u64 {
+		self.recounts.load(Ordering::Relaxed)
+	}
+
+	/// Drop the cached totals so the next read recounts
+	pub fn invalidate(&self) {
+		*self.cached.write().unwrap() = None;
+	}
+
+	/// Account for newly created entries
+	///
+	/// Does nothing while the cache is empty, since the next recount will
+	/// include them anyway.
+	pub fn record_added(&self, entries: u64, size_bytes: u64) {
+		if let Some(cached) = self.cached.write().unwrap().as_mut() {
+			cached.stats.total_entries = cached.stats.total_entries.saturating_add(entries);
+			cached.stats.total_size_bytes =
+				cached.stats.total_size_bytes.saturating_add(size_bytes);
+		}
+	}
+
+	/// Account for deleted entries
+	pub fn record_removed(&self, entries: u64, size_bytes: u64) {
+		if let Some(cached) = self.cached.write().unwrap().as_mut() {
+			cached.stats.total_entries = cached.stats.total_entries.saturating_sub(entries);
+			cached.stats.total_size_bytes =
+				cached.stats.total_size_bytes.saturating_sub(size_bytes);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn seeded() -> DiscoveryStatsCache {
+		let cache = DiscoveryStatsCache::new();
+		cache.store_recount(DiscoveryStats {
+			total_entries: 10,
+			total_locations: 1,
+			total_size_bytes: 1_000,
+			device_count: 2,
+		});
+		cache
+	}
+
+	#[test]
+	fn test_indexed_entries_update_cache_without_recount() {
+		let cache = seeded();
+		assert_eq!(cache.recount_count(), 1);
+
+		// An indexer batch of three files totalling 300 bytes
+		cache.record_added(3, 300);
+
+		let stats = cache.get().unwrap();
+		assert_eq!(stats.total_entries, 13);
+		assert_eq!(stats.total_size_bytes, 1_300);
+		assert_eq!(stats.total_locations, 1);
+		assert_eq!(cache.recount_count(), 1);
+
+		cache.record_removed(5, 2_000);
+		let stats = cache.get().unwrap();
+		assert_eq!(stats.total_entries, 8);
+		assert_eq!(stats.total_size_bytes, 0);
+		assert_eq!(cache.recount_count(), 1);
+	}
+
+	#[test]
+	fn test_empty_cache_ignores_increments() {
+		let cache = DiscoveryStatsCache::new();
+		cache.record_added(3, 300);
+		assert!(cache.get().is_none());
+
+		let cache = seeded();
+		cache.invalidate();
+		cache.record_added(3, 300);
+		assert!(cache.get().is_none());
+	}
+
+	#[test]
+	fn test_stale_cache_needs_recount() {
+		let cache = seeded();
+		cache.cached.write().unwrap().as_mut().unwrap().computed_at = Utc::now()
+			- chrono::Duration::from_std(DISCOVERY_STATS_MAX_AGE).unwrap()
+			- chrono::Duration::seconds(1);
+		assert!(cache.get().is_none());
+	}
+}
@@ -4,7 +4,7 @@ use super::{
 	config::{LibraryConfig, LibrarySettings, LibraryStatistics, ThumbnailMetadata},
 	error::{LibraryError, Result},
 	lock::LibraryLock,
-	Library, LIBRARY_CONFIG_VERSION, LIBRARY_EXTENSION,
+	DiscoveryStatsCache, Library, LIBRARY_CONFIG_VERSION, LIBRARY_EXTENSION,
 };
 
 /// Legacy database filename (for migration)
@@ -323,7 +323,7 @@ impl LibraryManager {
 			boot_disk_type: Set(initial_device_boot_disk_type),
 			boot_disk_capacity_bytes: Set(initial_device_boot_disk_capacity_bytes),
 			swap_total_bytes: Set(initial_device_swap_total_bytes),
-			network_addresses: Set(serde_json::json!([])),
+			network_addresses: Set(Default::default()),
 			is_online: Set(false),
 			last_seen_at: Set(Utc::now()),
 			capabilities: Set(serde_json::json!({
@@ -540,6 +540,7 @@ impl LibraryManager {
 			file_sync_service: OnceCell::new(), // Initialized later
 			source_manager: OnceCell::new(),    // Initialized lazily
 			device_cache: Arc::new(std::sync::RwLock::new(device_cache)),
+			discovery_stats: DiscoveryStatsCache::new(),
 			_lock: std::sync::Mutex::new(Some(lock)),
 		});
 
@@ -1137,7 +1138,9 @@ impl LibraryManager {
 				boot_disk_type: Set(device.boot_disk_type),
 				boot_disk_capacity_bytes: Set(device.boot_disk_capacity_bytes),
 				swap_total_bytes: Set(device.swap_total_bytes),
-				network_addresses: Set(serde_json::json!(device.network_addresses)),
+				network_addresses: Set(entities::device::NetworkAddresses(
+					device.network_addresses,
+				)),
 				is_online: Set(true),
 				last_seen_at: Set(Utc::now()),
 				capabilities: Set(serde_json::json!({
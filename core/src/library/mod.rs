@@ -5,12 +5,14 @@
 //! thumbnails, and other data.
 
 pub(crate) mod config;
+mod discovery_stats;
 mod error;
 mod lock;
 mod manager;
 mod sync_helpers;
 
 pub use config::{LibraryConfig, LibrarySettings, LibraryStatistics};
+pub use discovery_stats::{DiscoveryStats, DiscoveryStatsCache, DISCOVERY_STATS_MAX_AGE};
 pub use error::{LibraryError, Result};
 pub use lock::LibraryLock;
 pub use manager::{DiscoveredLibrary, LibraryManager};
@@ -72,6 +74,9 @@ pub struct Library {
 	/// Loaded from this library's devices table for per-library device resolution
 	device_cache: Arc<StdRwLock<HashMap<String, Uuid>>>,
 
+	/// Totals reported to peers in discovery responses, kept current by the indexer
+	discovery_stats: DiscoveryStatsCache,
+
 	/// Lock preventing concurrent access (wrapped in Mutex to allow explicit release during shutdown)
 	_lock: std::sync::Mutex<Option<LibraryLock>>,
 }
@@ -111,6 +116,11 @@ impl Library {
 		&self.sync_events
 	}
 
+	/// Get the shared configuration handle, for services that must see later updates
+	pub(crate) fn config_handle(&self) -> &Arc<RwLock<LibraryConfig>> {
+		&self.config
+	}
+
 	/// Get the job manager
 	pub fn jobs(&self) -> &Arc<JobManager> {
 		&self.jobs
@@ -193,6 +203,22 @@ impl Library {
 		&self,
 		device_id: Uuid,
 		network: Arc<dyn crate::infra::sync::NetworkTransport>,
+	) -> Result<()> {
+		self.init_sync_service_with_config(
+			device_id,
+			network,
+			crate::infra::sync::SyncConfig::default(),
+		)
+		.await
+	}
+
+	/// Initialize the sync service with a custom sync configuration
+	#[cfg_attr(test, allow(dead_code))] // Exposed for integration tests
+	pub async fn init_sync_service_with_config(
+		&self,
+		device_id: Uuid,
+		network: Arc<dyn crate::infra::sync::NetworkTransport>,
+		config: crate::infra::sync::SyncConfig,
 	) -> Result<()> {
 		if self.sync_service.get().is_some() {
 			warn!(
@@ -203,12 +229,11 @@ impl Library {
 			return Ok(());
 		}
 
-		let sync_service =
-			crate::service::sync::SyncService::new_from_library(self, device_id, network)
-				.await
-				.map_err(|e| {
-					LibraryError::Other(format!("Failed to create sync service: {}", e))
-				})?;
+		let sync_service = crate::service::sync::SyncService::new_from_library_with_config(
+			self, device_id, network, config,
+		)
+		.await
+		.map_err(|e| LibraryError::Other(format!("Failed to create sync service: {}", e)))?;
 
 		self.sync_service
 			.set(Arc::new(sync_service))
@@ -692,6 +717,83 @@ impl Library {
 		stats
 	}
 
+	/// Totals for discovery responses, recounting only when the cache is empty or stale
+	pub async fn discovery_stats(&self) -> Result<DiscoveryStats> {
+		if let Some(stats) = self.discovery_stats.get() {
+			return Ok(stats);
+		}
+		self.recompute_discovery_stats().await
+	}
+
+	/// Recount discovery totals from the database and replace the cached values
+	pub async fn recompute_discovery_stats(&self) -> Result<DiscoveryStats> {
+		use crate::infra::db::entities::{device, entry, location};
+		use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect};
+
+		let db = self.db.conn();
+		let total_entries = entry::Entity::find().count(db).await?;
+		let total_locations = location::Entity::find().count(db).await?;
+		let device_count = device::Entity::find().count(db).await?;
+		let total_size_bytes = entry::Entity::find()
+			.filter(entry::Column::Kind.eq(0)) // Files only
+			.select_only()
+			.column_as(entry::Column::Size.sum(), "total_size")
+			.into_tuple::<Option<i64>>()
+			.one(db)
+			.await?
+			.flatten()
+			.unwrap_or(0)
+			.max(0) as u64;
+
+		let stats = DiscoveryStats {
+			total_entries,
+			total_locations,
+			total_size_bytes,
+			device_count,
+		};
+		self.discovery_stats.store_recount(stats);
+
+		debug!(
+			library_id = %self.id(),
+			total_entries,
+			total_size_bytes,
+			"Recomputed discovery statistics"
+		);
+
+		Ok(stats)
+	}
+
+	/// The cache behind [`Self::discovery_stats`]
+	pub fn discovery_stats_cache(&self) -> &DiscoveryStatsCache {
+		&self.discovery_stats
+	}
+
+	/// Drop cached discovery totals, e.g. after locations are added or removed
+	pub fn invalidate_discovery_stats(&self) {
+		self.discovery_stats.invalidate();
+	}
+
+	/// Count newly created entries towards the cached discovery totals
+	pub fn record_entries_added(&self, entries: &[crate::infra::db::entities::entry::Model]) {
+		let (count, size) = Self::entry_totals(entries);
+		self.discovery_stats.record_added(count, size);
+	}
+
+	/// Remove deleted entries from the cached discovery totals
+	pub fn record_entries_removed(&self, entries: &[crate::infra::db::entities::entry::Model]) {
+		let (count, size) = Self::entry_totals(entries);
+		self.discovery_stats.record_removed(count, size);
+	}
+
+	fn entry_totals(entries: &[crate::infra::db::entities::entry::Model]) -> (u64, u64) {
+		let size = entries
+			.iter()
+			.filter(|e| e.kind == 0)
+			.map(|e| e.size.max(0) as u64)
+			.sum();
+		(entries.len() as u64, size)
+	}
+
 	/// Calculate statistics directly from database (for queries)
 	/// This is synchronous and queries the database directly for accurate real-time stats
 	pub async fn calculate_statistics_for_query(&self) -> Result<LibraryStatistics> {
@@ -2,7 +2,7 @@
 
 use super::{IndexMode, LocationError, LocationResult, ManagedLocation};
 use crate::{
-	domain::addressing::SdPath,
+	domain::{addressing::SdPath, ScanState},
 	infra::{
 		db::entities::{self, entry::EntryKind},
 		event::{Event, EventBus},
@@ -258,6 +258,7 @@ impl LocationManager {
 		// Commit transaction
 		txn.commit().await?;
 		info!("Created location record with ID: {}", location_record.id);
+		library.invalidate_discovery_stats();
 
 		// Sync location root entry FIRST (before location) to ensure FK dependency exists
 		// Location references entry_id, so entry must exist in sync system before location is synced
@@ -457,6 +458,8 @@ impl LocationManager {
 	}
 
 	/// Update scan state for a location
+	///
+	/// Emits `LocationScanStateChanged` when the state actually changes.
 	async fn update_scan_state(
 		&self,
 		library: &Library,
@@ -471,6 +474,7 @@ impl LocationManager {
 			.one(library.db().conn())
 			.await?
 			.ok_or_else(|| LocationError::LocationNotFound { id: location_id })?;
+		let previous_state = ScanState::from_db_value(&location.scan_state);
 
 		let mut active_location: entities::location::ActiveModel = location.into();
 		active_location.scan_state = Set(scan_state.to_string());
@@ -481,6 +485,15 @@ impl LocationManager {
 		active_location.updated_at = Set(chrono::Utc::now());
 
 		active_location.update(library.db().conn()).await?;
+
+		let new_state = ScanState::from_db_value(scan_state);
+		if new_state != previous_state {
+			self.events.emit(Event::LocationScanStateChanged {
+				library_id: library.id(),
+				location_id,
+				state: new_state,
+			});
+		}
 		Ok(())
 	}
 
@@ -641,6 +654,7 @@ impl LocationManager {
 		use crate::domain::{resource::EventEmitter, Location};
 		Location::emit_deleted(location_id, &self.events);
 
+		library.invalidate_discovery_stats();
 		info!("Successfully removed location {}", location_id);
 		Ok(())
 	}
@@ -4,7 +4,7 @@ pub mod backfill;
 pub mod manager;
 
 use crate::{
-	domain::addressing::SdPath,
+	domain::{addressing::SdPath, ScanState},
 	infra::{
 		db::entities::{self, entry::EntryKind},
 		event::{Event, EventBus},
@@ -369,7 +369,7 @@ async fn start_location_indexing(
 	info!("Starting indexing for location: {}", path.display());
 
 	// Update scan state to "running"
-	update_location_scan_state(library.clone(), location_db_id, "running", None).await?;
+	update_location_scan_state(library.clone(), events, location_db_id, "running", None).await?;
 
 	// Emit indexing started event
 	events.emit(Event::IndexingStarted {
@@ -429,6 +429,7 @@ async fn start_location_indexing(
 			// Update scan state to failed
 			if let Err(update_err) = update_location_scan_state(
 				library.clone(),
+				events,
 				location_db_id,
 				"failed",
 				Some(e.to_string()),
@@ -493,9 +494,14 @@ async fn monitor_indexing_job(
 				}
 
 				// Update scan state to completed
-				if let Err(e) =
-					update_location_scan_state(library.clone(), location_db_id, "completed", None)
-						.await
+				if let Err(e) = update_location_scan_state(
+					library.clone(),
+					&events,
+					location_db_id,
+					"completed",
+					None,
+				)
+				.await
 				{
 					error!("Failed to update scan state: {}", e);
 				}
@@ -524,9 +530,14 @@ async fn monitor_indexing_job(
 				warn!("Job completed but output format was unexpected");
 
 				// Update scan state to completed anyway
-				if let Err(e) =
-					update_location_scan_state(library.clone(), location_db_id, "completed", None)
-						.await
+				if let Err(e) = update_location_scan_state(
+					library.clone(),
+					&events,
+					location_db_id,
+					"completed",
+					None,
+				)
+				.await
 				{
 					error!("Failed to update scan state: {}", e);
 				}
@@ -538,6 +549,7 @@ async fn monitor_indexing_job(
 			// Update scan state to failed
 			if let Err(update_err) = update_location_scan_state(
 				library.clone(),
+				&events,
 				location_db_id,
 				"failed",
 				Some(e.to_string()),
@@ -581,8 +593,11 @@ async fn scan_directory_stats(path: &PathBuf) -> Result<(u64, u64), std::io::Err
 }
 
 /// Update location scan state
+///
+/// Emits `LocationScanStateChanged` when the state actually changes.
 async fn update_location_scan_state(
 	library: Arc<Library>,
+	events: &EventBus,
 	location_id: i32,
 	state: &str,
 	error_message: Option<String>,
@@ -591,6 +606,8 @@ async fn update_location_scan_state(
 		.one(library.db().conn())
 		.await?
 		.ok_or_else(|| LocationError::LocationNotFound { id: Uuid::nil() })?;
+	let location_uuid = location.uuid;
+	let previous_state = ScanState::from_db_value(&location.scan_state);
 
 	let mut active_location: entities::location::ActiveModel = location.into();
 	active_location.scan_state = Set(state.to_string());
@@ -602,6 +619,15 @@ async fn update_location_scan_state(
 	}
 
 	active_location.update(library.db().conn()).await?;
+
+	let new_state = ScanState::from_db_value(state);
+	if new_state != previous_state {
+		events.emit(Event::LocationScanStateChanged {
+			library_id: library.id(),
+			location_id: location_uuid,
+			state: new_state,
+		});
+	}
 	Ok(())
 }
 
@@ -4,6 +4,7 @@ use std::{path::PathBuf, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use uuid::Uuid;
 
 use crate::{
 	config::{
@@ -49,6 +50,9 @@ pub struct AppConfigOutput {
 
 	/// Spacebot companion configuration
 	pub spacebot: SpacebotConfigOutput,
+
+	/// Directory pairing sessions and the vouching queue are stored under, if not the data directory
+	pub pairing_data_dir: Option<PathBuf>,
 }
 
 /// User preferences output
@@ -89,8 +93,21 @@ pub struct ProxyPairingConfigOutput {
 	pub auto_accept_vouched: bool,
 	pub auto_vouch_to_all: bool,
 	pub vouch_signature_max_age: u64,
+	pub vouch_max_future_skew: u64,
 	pub vouch_response_timeout: u64,
 	pub vouch_queue_retry_limit: u32,
+	pub vouch_queue_poll_secs: u64,
+	pub session_cleanup_poll_secs: u64,
+	pub privacy_mode: bool,
+	pub max_vouching_sessions: usize,
+	pub vouching_session_ttl_secs: u64,
+	pub auto_accept_pairing_from: Vec<String>,
+	pub auto_accept_vouches_from: Vec<Uuid>,
+	pub vouch_rejection_threshold: u32,
+	pub vouch_rejection_cooldown_secs: u64,
+	pub vouchee_confirms: bool,
+	pub max_device_info_size: usize,
+	pub min_code_entropy_bits: u32,
 }
 
 /// Spacebot companion configuration output
@@ -146,10 +163,24 @@ impl From<&AppConfig> for AppConfigOutput {
 				auto_accept_vouched: config.proxy_pairing.auto_accept_vouched,
 				auto_vouch_to_all: config.proxy_pairing.auto_vouch_to_all,
 				vouch_signature_max_age: config.proxy_pairing.vouch_signature_max_age,
+				vouch_max_future_skew: config.proxy_pairing.vouch_max_future_skew,
 				vouch_response_timeout: config.proxy_pairing.vouch_response_timeout,
 				vouch_queue_retry_limit: config.proxy_pairing.vouch_queue_retry_limit,
+				vouch_queue_poll_secs: config.proxy_pairing.vouch_queue_poll_secs,
+				session_cleanup_poll_secs: config.proxy_pairing.session_cleanup_poll_secs,
+				privacy_mode: config.proxy_pairing.privacy_mode,
+				max_vouching_sessions: config.proxy_pairing.max_vouching_sessions,
+				vouching_session_ttl_secs: config.proxy_pairing.vouching_session_ttl_secs,
+				auto_accept_pairing_from: config.proxy_pairing.auto_accept_pairing_from.clone(),
+				auto_accept_vouches_from: config.proxy_pairing.auto_accept_vouches_from.clone(),
+				vouch_rejection_threshold: config.proxy_pairing.vouch_rejection_threshold,
+				vouch_rejection_cooldown_secs: config.proxy_pairing.vouch_rejection_cooldown_secs,
+				vouchee_confirms: config.proxy_pairing.vouchee_confirms,
+				max_device_info_size: config.proxy_pairing.max_device_info_size,
+				min_code_entropy_bits: config.proxy_pairing.min_code_entropy_bits,
 			},
 			spacebot: SpacebotConfigOutput::from(&config.spacebot),
+			pairing_data_dir: config.pairing_data_dir.clone(),
 		}
 	}
 }
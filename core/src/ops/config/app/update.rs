@@ -1,15 +1,17 @@
 //! Update app configuration action
 
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use tracing::info;
+use uuid::Uuid;
 
 use crate::{
 	config::AppConfig,
 	context::CoreContext,
 	infra::action::{error::ActionError, CoreAction, ValidationResult},
+	service::network::Protocol,
 };
 
 /// Input for updating app configuration
@@ -68,6 +70,10 @@ pub struct UpdateAppConfigInput {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub proxy_pairing_vouch_signature_max_age: Option<u64>,
 
+	/// Tolerated clock skew for vouch timestamps ahead of ours, in seconds
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_vouch_max_future_skew: Option<u64>,
+
 	/// Timeout for proxy confirmation in seconds
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub proxy_pairing_vouch_response_timeout: Option<u64>,
@@ -76,6 +82,58 @@ pub struct UpdateAppConfigInput {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub proxy_pairing_vouch_queue_retry_limit: Option<u32>,
 
+	/// How often the vouching queue is processed, in seconds
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_vouch_queue_poll_secs: Option<u64>,
+
+	/// How often expired pairing sessions are cleaned up, in seconds
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_session_cleanup_poll_secs: Option<u64>,
+
+	/// Only reveal name, slug and fingerprint to devices that are not yet paired
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_privacy_mode: Option<bool>,
+
+	/// Maximum number of vouching sessions held at once
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_max_vouching_sessions: Option<usize>,
+
+	/// How long a vouching session may stay open before it is finalized, in seconds
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_vouching_session_ttl_secs: Option<u64>,
+
+	/// Devices allowed to complete direct pairing without a pairing code
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_auto_accept_pairing_from: Option<Vec<String>>,
+
+	/// Vouchers whose proxy pairings are auto-accepted; empty allows every trusted voucher
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_auto_accept_vouches_from: Option<Vec<Uuid>>,
+
+	/// Consecutive rejections before auto-vouching to a target pauses; 0 disables the pause
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_vouch_rejection_threshold: Option<u32>,
+
+	/// How long auto-vouching to a rejecting target stays paused, in seconds
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_vouch_rejection_cooldown_secs: Option<u64>,
+
+	/// Whether a new device confirms each device that accepted a vouch for it
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_vouchee_confirms: Option<bool>,
+
+	/// Largest device info accepted in a pairing or vouch message, in bytes
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_max_device_info_size: Option<usize>,
+
+	/// Least estimated entropy, in bits, a pairing code must carry for us to join it
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub proxy_pairing_min_code_entropy_bits: Option<u32>,
+
+	/// Directory for pairing sessions and the vouching queue; an empty path resets it to the data directory
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pairing_data_dir: Option<PathBuf>,
+
 	/// Whether Spacebot features are enabled in the UI
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub spacebot_enabled: Option<bool>,
@@ -194,6 +252,72 @@ impl CoreAction for UpdateAppConfigAction {
 			}
 		}
 
+		if let Some(poll_secs) = self.input.proxy_pairing_vouch_queue_poll_secs {
+			if poll_secs == 0 {
+				return Err(ActionError::Validation {
+					field: "proxy_pairing_vouch_queue_poll_secs".to_string(),
+					message: "Vouch queue poll interval must be greater than 0".to_string(),
+				});
+			}
+		}
+
+		if let Some(poll_secs) = self.input.proxy_pairing_session_cleanup_poll_secs {
+			if poll_secs == 0 {
+				return Err(ActionError::Validation {
+					field: "proxy_pairing_session_cleanup_poll_secs".to_string(),
+					message: "Session cleanup poll interval must be greater than 0".to_string(),
+				});
+			}
+		}
+
+		if let Some(max_sessions) = self.input.proxy_pairing_max_vouching_sessions {
+			if max_sessions == 0 {
+				return Err(ActionError::Validation {
+					field: "proxy_pairing_max_vouching_sessions".to_string(),
+					message: "Maximum vouching sessions must be greater than 0".to_string(),
+				});
+			}
+		}
+
+		if let Some(ttl) = self.input.proxy_pairing_vouching_session_ttl_secs {
+			if ttl == 0 {
+				return Err(ActionError::Validation {
+					field: "proxy_pairing_vouching_session_ttl_secs".to_string(),
+					message: "Vouching session TTL must be greater than 0".to_string(),
+				});
+			}
+		}
+
+		if let Some(max_size) = self.input.proxy_pairing_max_device_info_size {
+			if max_size < 1024 {
+				return Err(ActionError::Validation {
+					field: "proxy_pairing_max_device_info_size".to_string(),
+					message: "Device info size cap must be at least 1024 bytes".to_string(),
+				});
+			}
+		}
+
+		if let Some(bits) = self.input.proxy_pairing_min_code_entropy_bits {
+			if bits > 128 {
+				return Err(ActionError::Validation {
+					field: "proxy_pairing_min_code_entropy_bits".to_string(),
+					message: "Pairing codes carry at most 128 bits of entropy".to_string(),
+				});
+			}
+		}
+
+		if let Some(ref allowlist) = self.input.proxy_pairing_auto_accept_pairing_from {
+			if let Some(entry) = allowlist
+				.iter()
+				.find(|entry| entry.is_empty() || hex::decode(entry).is_err())
+			{
+				return Err(ActionError::Validation {
+					field: "proxy_pairing_auto_accept_pairing_from".to_string(),
+					message: format!("Invalid public key or fingerprint: '{}'", entry),
+				});
+			}
+		}
+
 		if let Some(ref base_url) = self.input.spacebot_base_url {
 			if base_url.trim().is_empty() {
 				return Err(ActionError::Validation {
@@ -328,6 +452,13 @@ impl CoreAction for UpdateAppConfigAction {
 			}
 		}
 
+		if let Some(skew) = self.input.proxy_pairing_vouch_max_future_skew {
+			if config.proxy_pairing.vouch_max_future_skew != skew {
+				config.proxy_pairing.vouch_max_future_skew = skew;
+				changes.push("proxy_pairing_vouch_max_future_skew");
+			}
+		}
+
 		if let Some(timeout) = self.input.proxy_pairing_vouch_response_timeout {
 			if config.proxy_pairing.vouch_response_timeout != timeout {
 				config.proxy_pairing.vouch_response_timeout = timeout;
@@ -342,6 +473,100 @@ impl CoreAction for UpdateAppConfigAction {
 			}
 		}
 
+		if let Some(poll_secs) = self.input.proxy_pairing_vouch_queue_poll_secs {
+			if config.proxy_pairing.vouch_queue_poll_secs != poll_secs {
+				config.proxy_pairing.vouch_queue_poll_secs = poll_secs;
+				changes.push("proxy_pairing_vouch_queue_poll_secs");
+			}
+		}
+
+		if let Some(poll_secs) = self.input.proxy_pairing_session_cleanup_poll_secs {
+			if config.proxy_pairing.session_cleanup_poll_secs != poll_secs {
+				config.proxy_pairing.session_cleanup_poll_secs = poll_secs;
+				changes.push("proxy_pairing_session_cleanup_poll_secs");
+			}
+		}
+
+		if let Some(privacy_mode) = self.input.proxy_pairing_privacy_mode {
+			if config.proxy_pairing.privacy_mode != privacy_mode {
+				config.proxy_pairing.privacy_mode = privacy_mode;
+				changes.push("proxy_pairing_privacy_mode");
+			}
+		}
+
+		if let Some(max_sessions) = self.input.proxy_pairing_max_vouching_sessions {
+			if config.proxy_pairing.max_vouching_sessions != max_sessions {
+				config.proxy_pairing.max_vouching_sessions = max_sessions;
+				changes.push("proxy_pairing_max_vouching_sessions");
+			}
+		}
+
+		if let Some(ttl) = self.input.proxy_pairing_vouching_session_ttl_secs {
+			if config.proxy_pairing.vouching_session_ttl_secs != ttl {
+				config.proxy_pairing.vouching_session_ttl_secs = ttl;
+				changes.push("proxy_pairing_vouching_session_ttl_secs");
+			}
+		}
+
+		if let Some(ref allowlist) = self.input.proxy_pairing_auto_accept_pairing_from {
+			if config.proxy_pairing.auto_accept_pairing_from != *allowlist {
+				config.proxy_pairing.auto_accept_pairing_from = allowlist.clone();
+				changes.push("proxy_pairing_auto_accept_pairing_from");
+			}
+		}
+
+		if let Some(ref vouchers) = self.input.proxy_pairing_auto_accept_vouches_from {
+			if config.proxy_pairing.auto_accept_vouches_from != *vouchers {
+				config.proxy_pairing.auto_accept_vouches_from = vouchers.clone();
+				changes.push("proxy_pairing_auto_accept_vouches_from");
+			}
+		}
+
+		if let Some(threshold) = self.input.proxy_pairing_vouch_rejection_threshold {
+			if config.proxy_pairing.vouch_rejection_threshold != threshold {
+				config.proxy_pairing.vouch_rejection_threshold = threshold;
+				changes.push("proxy_pairing_vouch_rejection_threshold");
+			}
+		}
+
+		if let Some(cooldown) = self.input.proxy_pairing_vouch_rejection_cooldown_secs {
+			if config.proxy_pairing.vouch_rejection_cooldown_secs != cooldown {
+				config.proxy_pairing.vouch_rejection_cooldown_secs = cooldown;
+				changes.push("proxy_pairing_vouch_rejection_cooldown_secs");
+			}
+		}
+
+		if let Some(vouchee_confirms) = self.input.proxy_pairing_vouchee_confirms {
+			if config.proxy_pairing.vouchee_confirms != vouchee_confirms {
+				config.proxy_pairing.vouchee_confirms = vouchee_confirms;
+				changes.push("proxy_pairing_vouchee_confirms");
+			}
+		}
+
+		if let Some(max_size) = self.input.proxy_pairing_max_device_info_size {
+			if config.proxy_pairing.max_device_info_size != max_size {
+				config.proxy_pairing.max_device_info_size = max_size;
+				changes.push("proxy_pairing_max_device_info_size");
+			}
+		}
+
+		if let Some(bits) = self.input.proxy_pairing_min_code_entropy_bits {
+			if config.proxy_pairing.min_code_entropy_bits != bits {
+				config.proxy_pairing.min_code_entropy_bits = bits;
+				changes.push("proxy_pairing_min_code_entropy_bits");
+			}
+		}
+
+		if let Some(ref pairing_data_dir) = self.input.pairing_data_dir {
+			let pairing_data_dir =
+				(!pairing_data_dir.as_os_str().is_empty()).then(|| pairing_data_dir.clone());
+			if config.pairing_data_dir != pairing_data_dir {
+				config.pairing_data_dir = pairing_data_dir;
+				changes.push("pairing_data_dir");
+				requires_restart = true;
+			}
+		}
+
 		if let Some(spacebot_enabled) = self.input.spacebot_enabled {
 			if config.spacebot.enabled != spacebot_enabled {
 				config.spacebot.enabled = spacebot_enabled;
@@ -400,7 +625,7 @@ impl CoreAction for UpdateAppConfigAction {
 		if let Some(networking) = context.get_networking().await {
 			let registry = networking.protocol_registry();
 			let guard = registry.read().await;
-			if let Some(handler) = guard.get_handler("pairing") {
+			if let Some(handler) = guard.get_handler(Protocol::Pairing) {
 				if let Some(pairing) = handler
 					.as_any()
 					.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>(
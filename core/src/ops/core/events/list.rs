@@ -64,8 +64,11 @@ impl CoreQuery for ListEventsQuery {
 			"VolumeAdded",
 			"VolumeRemoved",
 			"VolumeUpdated",
+			"VolumeUsageChanged",
 			"VolumeSpeedTested",
 			"VolumeMountChanged",
+			"VolumeMounted",
+			"VolumeUnmounted",
 			"VolumeError",
 			// Job events
 			"JobQueued",
@@ -81,9 +84,12 @@ impl CoreQuery for ListEventsQuery {
 			"IndexingProgress",
 			"IndexingCompleted",
 			"IndexingFailed",
+			"LocationScanStateChanged",
 			// Device events
 			"DeviceConnected",
 			"DeviceDisconnected",
+			"DeviceInfoChanged",
+			"DeviceUnpaired",
 			// Resource events
 			"ResourceChanged",
 			"ResourceDeleted",
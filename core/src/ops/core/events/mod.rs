@@ -1,3 +1,5 @@
 mod list;
+mod replay;
 
 pub use list::*;
+pub use replay::*;
@@ -0,0 +1,46 @@
+use crate::infra::{
+	event::ReplayedEvent,
+	query::{CoreQuery, QueryResult},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReplayEventsInput {
+	/// Return events emitted after this time, e.g. when the client last saw an event
+	pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReplayEventsOutput {
+	/// Buffered events emitted after `since`, oldest first
+	pub events: Vec<ReplayedEvent>,
+}
+
+/// Events a reconnecting client missed, from the event bus replay buffer
+pub struct ReplayEventsQuery {
+	since: DateTime<Utc>,
+}
+
+impl CoreQuery for ReplayEventsQuery {
+	type Input = ReplayEventsInput;
+	type Output = ReplayEventsOutput;
+
+	fn from_input(input: Self::Input) -> QueryResult<Self> {
+		Ok(Self { since: input.since })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<crate::context::CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		Ok(ReplayEventsOutput {
+			events: context.events.replay_since(self.since),
+		})
+	}
+}
+
+crate::register_core_query!(ReplayEventsQuery, "core.events.replay");
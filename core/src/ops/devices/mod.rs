@@ -1,7 +1,9 @@
 //! Device operations for library database
 
 pub mod list;
+pub mod set_sync_enabled;
 pub mod update;
 
 pub use list::*;
+pub use set_sync_enabled::*;
 pub use update::*;
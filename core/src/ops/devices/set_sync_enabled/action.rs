@@ -0,0 +1,100 @@
+//! Enable or disable sync with a library device at runtime
+
+use super::{input::SetDeviceSyncEnabledInput, output::SetDeviceSyncEnabledOutput};
+use crate::{
+	context::CoreContext,
+	infra::{
+		action::{error::ActionError, LibraryAction},
+		db::entities::device,
+		sync::ChangeType,
+	},
+	library::Library,
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetDeviceSyncEnabledAction {
+	input: SetDeviceSyncEnabledInput,
+}
+
+impl LibraryAction for SetDeviceSyncEnabledAction {
+	type Input = SetDeviceSyncEnabledInput;
+	type Output = SetDeviceSyncEnabledOutput;
+
+	fn from_input(input: SetDeviceSyncEnabledInput) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let db = library.db().conn();
+		let device_id = self.input.device_id;
+		let enabled = self.input.enabled;
+
+		let device_model = device::Entity::find()
+			.filter(device::Column::Uuid.eq(device_id))
+			.one(db)
+			.await
+			.map_err(ActionError::SeaOrm)?
+			.ok_or(ActionError::DeviceNotFound(device_id))?;
+
+		if device_model.sync_enabled == enabled {
+			return Ok(SetDeviceSyncEnabledOutput {
+				device_id,
+				sync_enabled: enabled,
+				changed: false,
+			});
+		}
+
+		let mut active_model: device::ActiveModel = device_model.into();
+		active_model.sync_enabled = Set(enabled);
+		active_model.updated_at = Set(Utc::now());
+
+		let result = active_model.update(db).await.map_err(ActionError::SeaOrm)?;
+
+		// Sync to peers as a shared change so every library member agrees on the partner set
+		library
+			.sync_model(&result, ChangeType::Update)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to sync device: {}", e)))?;
+
+		// Re-evaluate partners now rather than on the next sync loop tick
+		if let Some(sync_service) = library.sync_service() {
+			sync_service.sync_partner_changed(device_id, enabled).await;
+		}
+
+		info!(
+			"Set sync_enabled={} for device {} in library {}",
+			enabled,
+			device_id,
+			library.id()
+		);
+
+		Ok(SetDeviceSyncEnabledOutput {
+			device_id,
+			sync_enabled: enabled,
+			changed: true,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"devices.set_sync_enabled"
+	}
+
+	async fn validate(
+		&self,
+		_library: &Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+}
+
+crate::register_library_action!(SetDeviceSyncEnabledAction, "devices.set_sync_enabled");
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SetDeviceSyncEnabledInput {
+	/// Device to update
+	pub device_id: Uuid,
+	/// Whether the device should be treated as a sync partner
+	pub enabled: bool,
+}
@@ -0,0 +1,9 @@
+//! Toggle whether a library device participates in sync
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::*;
+pub use input::*;
+pub use output::*;
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SetDeviceSyncEnabledOutput {
+	pub device_id: Uuid,
+	pub sync_enabled: bool,
+	/// False when the flag already had the requested value
+	pub changed: bool,
+}
@@ -3,6 +3,7 @@
 use crate::{
 	context::CoreContext,
 	device::DeviceConfig,
+	domain::device::MAX_DEVICE_SLUG_LEN,
 	infra::action::{error::ActionError, CoreAction, ValidationResult},
 };
 use serde::{Deserialize, Serialize};
@@ -61,8 +62,11 @@ impl CoreAction for UpdateDeviceAction {
 			if slug.trim().is_empty() {
 				return Err("Device slug cannot be empty".to_string());
 			}
-			if slug.len() > 50 {
-				return Err("Device slug cannot exceed 50 characters".to_string());
+			if slug.chars().count() > MAX_DEVICE_SLUG_LEN {
+				return Err(format!(
+					"Device slug cannot exceed {} characters",
+					MAX_DEVICE_SLUG_LEN
+				));
 			}
 			// Validate slug format (alphanumeric + hyphens only)
 			if !slug.chars().all(|c| c.is_alphanumeric() || c == '-') {
@@ -38,7 +38,13 @@
 //! ```
 
 use crate::{
-	domain::addressing::SdPath, infra::job::prelude::*, ops::files::copy::job::CopyPhase,
+	domain::addressing::SdPath,
+	infra::job::prelude::*,
+	ops::files::copy::job::CopyPhase,
+	service::network::{
+		protocol::{read_frame, TransferConfig, DEFAULT_MAX_MESSAGE_SIZE},
+		Protocol,
+	},
 	volume::VolumeManager,
 };
 use anyhow::Result;
@@ -362,7 +368,7 @@ impl RemoteTransferStrategy {
 		let registry_guard = protocol_registry.read().await;
 
 		let file_transfer_handler = registry_guard
-			.get_handler("file_transfer")
+			.get_handler(Protocol::FileTransfer)
 			.ok_or_else(|| anyhow::anyhow!("File transfer protocol not registered"))?;
 
 		let file_transfer_protocol = file_transfer_handler
@@ -526,6 +532,9 @@ impl RemoteTransferStrategy {
 
 		use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+		// The sender streams chunks sized by its transfer config
+		let max_message_size = TransferConfig::default().max_message_size();
+
 		send_stream.write_u8(0).await?;
 		send_stream
 			.write_all(&(request_data.len() as u32).to_be_bytes())
@@ -537,12 +546,7 @@ impl RemoteTransferStrategy {
 		let mut msg_type = [0u8; 1];
 		recv_stream.read_exact(&mut msg_type).await?;
 
-		let mut len_buf = [0u8; 4];
-		recv_stream.read_exact(&mut len_buf).await?;
-		let msg_len = u32::from_be_bytes(len_buf) as usize;
-
-		let mut msg_buf = vec![0u8; msg_len];
-		recv_stream.read_exact(&mut msg_buf).await?;
+		let msg_buf = read_frame(&mut recv_stream, max_message_size).await?;
 
 		let response: crate::service::network::protocol::file_transfer::FileTransferMessage =
 			rmp_serde::from_slice(&msg_buf)?;
@@ -633,12 +637,7 @@ impl RemoteTransferStrategy {
 				}
 			}
 
-			let mut len_buf = [0u8; 4];
-			recv_stream.read_exact(&mut len_buf).await?;
-			let msg_len = u32::from_be_bytes(len_buf) as usize;
-
-			let mut msg_buf = vec![0u8; msg_len];
-			recv_stream.read_exact(&mut msg_buf).await?;
+			let msg_buf = read_frame(&mut recv_stream, max_message_size).await?;
 
 			let msg: crate::service::network::protocol::file_transfer::FileTransferMessage =
 				rmp_serde::from_slice(&msg_buf)?;
@@ -1286,12 +1285,7 @@ async fn stream_file_data<'a>(
 		return Err(anyhow::anyhow!("Unexpected response type: {}", msg_type[0]));
 	}
 
-	let mut len_buf = [0u8; 4];
-	recv_stream.read_exact(&mut len_buf).await?;
-	let msg_len = u32::from_be_bytes(len_buf) as usize;
-
-	let mut msg_buf = vec![0u8; msg_len];
-	recv_stream.read_exact(&mut msg_buf).await?;
+	let msg_buf = read_frame(&mut recv_stream, DEFAULT_MAX_MESSAGE_SIZE).await?;
 
 	let ack_message: crate::service::network::protocol::file_transfer::FileTransferMessage =
 		rmp_serde::from_slice(&msg_buf)?;
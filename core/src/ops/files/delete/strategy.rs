@@ -4,7 +4,7 @@
 //! 1. **LocalDeleteStrategy** - Local file deletion (trash, permanent, secure)
 //! 2. **RemoteDeleteStrategy** - Cross-device deletion via network
 
-use crate::{domain::addressing::SdPath, infra::job::prelude::*};
+use crate::{domain::addressing::SdPath, infra::job::prelude::*, service::network::Protocol};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -401,7 +401,7 @@ impl RemoteDeleteStrategy {
 		// Send request via networking service
 		let networking_guard = &*networking;
 		networking_guard
-			.send_message(device_id, "file_delete", request_data)
+			.send_message(device_id, Protocol::FileDelete, request_data)
 			.await?;
 
 		// TODO: Implement proper request/response pattern
@@ -398,6 +398,10 @@ impl ChangeHandler for DatabaseAdapter {
 		txn.commit().await?;
 		self.entry_id_cache.remove(&entry.path);
 
+		if let Some(library) = self.context.get_library(self.library_id).await {
+			library.record_entries_removed(&entries_to_delete);
+		}
+
 		Ok(())
 	}
 
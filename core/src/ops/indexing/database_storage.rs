@@ -575,6 +575,7 @@ impl DatabaseStorage {
 
 		// Sync entry to other devices
 		if let Some(library) = library {
+			library.record_entries_added(std::slice::from_ref(&entry_model));
 			tracing::info!(
 				"ENTRY_SYNC: About to sync entry name={} uuid={:?}",
 				entry_model.name,
@@ -439,6 +439,8 @@ pub async fn run_processing_phase(
 			JobError::execution(format!("Failed to commit processing transaction: {}", e))
 		})?;
 
+		ctx.library().record_entries_added(&created_entries);
+
 		// All entries now have UUIDs assigned during creation
 		// Sync directories and empty files immediately (sync-ready)
 		// Regular files will be synced again after content identification
@@ -749,6 +751,7 @@ pub async fn run_processing_phase(
 					txn.commit().await.map_err(|e| {
 						JobError::execution(format!("Failed to commit deletion transaction: {}", e))
 					})?;
+					ctx.library().record_entries_removed(&entries_to_delete);
 
 					// Update in-memory caches
 					state.entry_id_cache.remove(&path);
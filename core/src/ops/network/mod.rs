@@ -8,6 +8,8 @@ pub mod start;
 pub mod status;
 pub mod stop;
 pub mod sync_setup;
+pub mod trust;
+pub mod unpair;
 
 // Re-exports for convenience
 pub use devices::*;
@@ -18,3 +20,5 @@ pub use start::*;
 pub use status::*;
 pub use stop::*;
 pub use sync_setup::*;
+pub use trust::*;
+pub use unpair::*;
@@ -1,5 +1,6 @@
 use super::{input::PairCancelInput, output::PairCancelOutput};
 use crate::infra::action::{error::ActionError, CoreAction};
+use crate::service::network::Protocol;
 use std::sync::Arc;
 
 pub struct PairCancelAction {
@@ -27,7 +28,7 @@ impl CoreAction for PairCancelAction {
 		// Cancel via pairing protocol handler if available
 		let reg = net.protocol_registry();
 		let guard = reg.read().await;
-		if let Some(handler) = guard.get_handler("pairing") {
+		if let Some(handler) = guard.get_handler(Protocol::Pairing) {
 			if let Some(pairing) = handler
 				.as_any()
 				.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>(
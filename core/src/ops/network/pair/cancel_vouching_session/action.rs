@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use super::{input::VouchingSessionCancelInput, output::VouchingSessionCancelOutput};
+use crate::infra::action::{error::ActionError, CoreAction};
+use crate::service::network::{protocol::PairingProtocolHandler, Protocol};
+
+/// Abort an in-progress vouching session and drop its queued vouches
+pub struct VouchingSessionCancelAction {
+	pub session_id: uuid::Uuid,
+}
+
+impl CoreAction for VouchingSessionCancelAction {
+	type Output = VouchingSessionCancelOutput;
+	type Input = VouchingSessionCancelInput;
+
+	fn from_input(input: Self::Input) -> std::result::Result<Self, String> {
+		Ok(Self {
+			session_id: input.session_id,
+		})
+	}
+
+	async fn execute(
+		self,
+		context: Arc<crate::context::CoreContext>,
+	) -> std::result::Result<Self::Output, ActionError> {
+		let net = context
+			.get_networking()
+			.await
+			.ok_or_else(|| ActionError::Internal("Networking not initialized".to_string()))?;
+
+		let handler = net
+			.protocol_registry()
+			.read()
+			.await
+			.get_handler(Protocol::Pairing);
+		let pairing = handler
+			.as_ref()
+			.and_then(|handler| handler.as_any().downcast_ref::<PairingProtocolHandler>())
+			.ok_or_else(|| ActionError::Internal("Pairing protocol not registered".to_string()))?;
+		let cancelled = pairing
+			.cancel_vouching_session(self.session_id)
+			.await
+			.map_err(|e| ActionError::Internal(e.to_string()))?;
+
+		Ok(VouchingSessionCancelOutput { cancelled })
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"network.pair.vouching_session.cancel"
+	}
+}
+
+crate::register_core_action!(
+	VouchingSessionCancelAction,
+	"network.pair.vouching_session.cancel"
+);
@@ -0,0 +1,7 @@
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::VouchingSessionCancelAction;
+pub use input::VouchingSessionCancelInput;
+pub use output::VouchingSessionCancelOutput;
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VouchingSessionCancelOutput {
+	/// Whether the session existed and was cancelled
+	pub cancelled: bool,
+}
@@ -2,10 +2,12 @@ use std::sync::Arc;
 
 use super::{input::PairConfirmProxyInput, output::PairConfirmProxyOutput};
 use crate::infra::action::{error::ActionError, CoreAction};
+use crate::service::network::{protocol::pairing::ProxyConfirmationOutcome, Protocol};
 
 pub struct PairConfirmProxyAction {
 	pub session_id: uuid::Uuid,
 	pub accepted: bool,
+	pub device_id: Option<uuid::Uuid>,
 }
 
 impl CoreAction for PairConfirmProxyAction {
@@ -16,6 +18,7 @@ impl CoreAction for PairConfirmProxyAction {
 		Ok(Self {
 			session_id: input.session_id,
 			accepted: input.accepted,
+			device_id: input.device_id,
 		})
 	}
 
@@ -30,26 +33,55 @@ impl CoreAction for PairConfirmProxyAction {
 
 		let registry = net.protocol_registry();
 		let guard = registry.read().await;
-		if let Some(handler) = guard.get_handler("pairing") {
+		if let Some(handler) = guard.get_handler(Protocol::Pairing) {
 			if let Some(pairing) = handler
 				.as_any()
 				.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>(
 			) {
+				if let Some(device_id) = self.device_id {
+					let result = pairing
+						.confirm_proxied_device(self.session_id, device_id, self.accepted)
+						.await;
+
+					return Ok(match result {
+						Ok(true) => PairConfirmProxyOutput {
+							success: true,
+							error: None,
+							already_confirmed: false,
+						},
+						Ok(false) => PairConfirmProxyOutput {
+							success: false,
+							error: Some("No pending confirmation for device".to_string()),
+							already_confirmed: false,
+						},
+						Err(e) => PairConfirmProxyOutput {
+							success: false,
+							error: Some(e.to_string()),
+							already_confirmed: false,
+						},
+					});
+				}
+
 				let result = pairing
 					.confirm_proxy_pairing(self.session_id, self.accepted)
 					.await;
 
 				match result {
-					Ok(_) => {
+					Ok(outcome) => {
 						return Ok(PairConfirmProxyOutput {
 							success: true,
 							error: None,
+							already_confirmed: matches!(
+								outcome,
+								ProxyConfirmationOutcome::AlreadyConfirmed { .. }
+							),
 						});
 					}
 					Err(e) => {
 						return Ok(PairConfirmProxyOutput {
 							success: false,
 							error: Some(e.to_string()),
+							already_confirmed: false,
 						});
 					}
 				}
@@ -59,6 +91,7 @@ impl CoreAction for PairConfirmProxyAction {
 		Ok(PairConfirmProxyOutput {
 			success: false,
 			error: Some("Pairing handler not available".to_string()),
+			already_confirmed: false,
 		})
 	}
 
@@ -6,4 +6,7 @@ use uuid::Uuid;
 pub struct PairConfirmProxyInput {
 	pub session_id: Uuid,
 	pub accepted: bool,
+	/// Set when, as the vouchee, confirming a device that accepted our vouch
+	#[serde(default)]
+	pub device_id: Option<Uuid>,
 }
@@ -5,4 +5,6 @@ use specta::Type;
 pub struct PairConfirmProxyOutput {
 	pub success: bool,
 	pub error: Option<String>,
+	/// The session had already been answered; this request changed nothing
+	pub already_confirmed: bool,
 }
@@ -0,0 +1,9 @@
+use crate::service::network::protocol::pairing::PairingStateDump;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PairDiagnosticsOutput {
+	/// Redacted pairing state, None when networking is not running
+	pub state: Option<PairingStateDump>,
+}
@@ -0,0 +1,38 @@
+use super::output::PairDiagnosticsOutput;
+use crate::infra::query::{QueryError, QueryResult};
+use crate::{context::CoreContext, infra::query::CoreQuery};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PairDiagnosticsQueryInput;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PairDiagnosticsQuery;
+
+impl CoreQuery for PairDiagnosticsQuery {
+	type Input = PairDiagnosticsQueryInput;
+	type Output = PairDiagnosticsOutput;
+
+	fn from_input(_input: Self::Input) -> QueryResult<Self> {
+		Ok(Self)
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let Some(net) = context.get_networking().await else {
+			return Ok(PairDiagnosticsOutput { state: None });
+		};
+		let state = net
+			.get_pairing_diagnostics()
+			.await
+			.map_err(|e| QueryError::Internal(e.to_string()))?;
+		Ok(PairDiagnosticsOutput { state: Some(state) })
+	}
+}
+
+crate::register_core_query!(PairDiagnosticsQuery, "network.pair.diagnostics");
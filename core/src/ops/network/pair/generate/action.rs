@@ -1,16 +1,62 @@
 use super::{input::PairGenerateInput, output::PairGenerateOutput};
-use crate::infra::action::{error::ActionError, CoreAction};
+use crate::{
+	infra::action::{error::ActionError, CoreAction},
+	service::network::protocol::pairing::{EndpointAddrInfo, PairingCode},
+};
 use chrono::Utc;
 use std::sync::Arc;
 
-pub struct PairGenerateAction {}
+pub struct PairGenerateAction {
+	/// Pre-seeded pairing code, if the caller supplied a secret
+	pub pairing_code: Option<PairingCode>,
+}
 
 impl CoreAction for PairGenerateAction {
 	type Output = PairGenerateOutput;
 	type Input = PairGenerateInput;
 
-	fn from_input(_input: Self::Input) -> std::result::Result<Self, String> {
-		Ok(Self {})
+	fn from_input(input: Self::Input) -> std::result::Result<Self, String> {
+		let pairing_code = match input.secret {
+			Some(secret) => {
+				let bytes = hex::decode(secret.trim())
+					.map_err(|e| format!("Invalid pairing secret: {}", e))?;
+				let entropy: [u8; 16] = bytes.try_into().map_err(|b: Vec<u8>| {
+					format!("Pairing secret must be 16 bytes, got {}", b.len())
+				})?;
+				Some(PairingCode::from_entropy(entropy).map_err(|e| e.to_string())?)
+			}
+			None => None,
+		};
+
+		if let Some(session_id) = input.session_id {
+			match &pairing_code {
+				Some(code) if code.session_id() != session_id => {
+					return Err(format!(
+						"Session ID {} does not match the ID derived from the secret ({})",
+						session_id,
+						code.session_id()
+					));
+				}
+				Some(_) => {}
+				None => {
+					return Err(
+						"A session ID can only be pre-seeded together with a secret".to_string()
+					);
+				}
+			}
+		}
+
+		let pairing_code = if input.multi_use {
+			let code = match pairing_code {
+				Some(code) => code,
+				None => PairingCode::generate().map_err(|e| e.to_string())?,
+			};
+			Some(code.multi_use())
+		} else {
+			pairing_code
+		};
+
+		Ok(Self { pairing_code })
 	}
 
 	async fn execute(
@@ -22,7 +68,7 @@ impl CoreAction for PairGenerateAction {
 			.await
 			.ok_or_else(|| ActionError::Internal("Networking not initialized".to_string()))?;
 		let (code, expires_in) = net
-			.start_pairing_as_initiator(false)
+			.start_pairing_as_initiator_with_code(self.pairing_code, false)
 			.await
 			.map_err(|e| ActionError::Internal(e.to_string()))?;
 
@@ -37,12 +83,21 @@ impl CoreAction for PairGenerateAction {
 		let qr_json = pairing_code.to_qr_json();
 		let node_id = pairing_code.node_id().map(|id| id.to_string());
 
+		// Read the address after the session is set up so it reflects the
+		// endpoint's current direct addresses and relay
+		let node_addr = net
+			.get_node_addr()
+			.ok()
+			.flatten()
+			.map(|addr| EndpointAddrInfo::from_endpoint_addr(&addr));
+
 		Ok(PairGenerateOutput {
 			code,
 			session_id,
 			expires_at: Utc::now() + chrono::Duration::seconds(expires_in as i64),
 			qr_json,
 			node_id,
+			node_addr,
 		})
 	}
 
@@ -52,3 +107,56 @@ impl CoreAction for PairGenerateAction {
 }
 
 crate::register_core_action!(PairGenerateAction, "network.pair.generate");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const FIXED_SECRET: &str = "000102030405060708090a0b0c0d0e0f";
+
+	#[test]
+	fn test_fixed_session_id_is_deterministic() {
+		let expected =
+			PairingCode::from_entropy([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15])
+				.unwrap()
+				.session_id();
+
+		let action = PairGenerateAction::from_input(PairGenerateInput {
+			session_id: Some(expected),
+			secret: Some(FIXED_SECRET.to_string()),
+			multi_use: false,
+		})
+		.unwrap();
+		assert_eq!(action.pairing_code.unwrap().session_id(), expected);
+	}
+
+	#[test]
+	fn test_mismatched_session_id_rejected() {
+		let result = PairGenerateAction::from_input(PairGenerateInput {
+			session_id: Some(uuid::Uuid::new_v4()),
+			secret: Some(FIXED_SECRET.to_string()),
+			multi_use: false,
+		});
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn test_session_id_without_secret_rejected() {
+		let result = PairGenerateAction::from_input(PairGenerateInput {
+			session_id: Some(uuid::Uuid::new_v4()),
+			secret: None,
+			multi_use: false,
+		});
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn test_invalid_secret_length_rejected() {
+		let result = PairGenerateAction::from_input(PairGenerateInput {
+			session_id: None,
+			secret: Some("0011".to_string()),
+			multi_use: false,
+		});
+		assert!(result.is_err());
+	}
+}
@@ -1,5 +1,17 @@
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
-pub struct PairGenerateInput {}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct PairGenerateInput {
+	/// Expected session ID. Session IDs are derived from the code secret, so this
+	/// must be paired with `secret` and is checked against the derived value.
+	#[serde(default)]
+	pub session_id: Option<Uuid>,
+	/// Hex-encoded 16-byte pairing secret. When omitted a random one is generated.
+	#[serde(default)]
+	pub secret: Option<String>,
+	/// Let the code pair any number of devices until it expires instead of only the first
+	#[serde(default)]
+	pub multi_use: bool,
+}
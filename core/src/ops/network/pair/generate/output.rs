@@ -1,3 +1,4 @@
+use crate::service::network::protocol::pairing::EndpointAddrInfo;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -12,4 +13,7 @@ pub struct PairGenerateOutput {
 	pub qr_json: String,
 	/// Node ID for relay-based pairing (share this for cross-network pairing)
 	pub node_id: Option<String>,
+	/// This node's direct addresses and relay at generation time, for embedding in
+	/// a link or QR code so the joiner can connect without a discovery lookup
+	pub node_addr: Option<EndpointAddrInfo>,
 }
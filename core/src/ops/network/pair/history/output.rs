@@ -0,0 +1,9 @@
+use crate::service::network::protocol::pairing::PairingHistoryEntry;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PairHistoryOutput {
+	/// Most recent first, capped at the last 50 sessions
+	pub entries: Vec<PairingHistoryEntry>,
+}
@@ -0,0 +1,35 @@
+use super::output::PairHistoryOutput;
+use crate::infra::query::QueryResult;
+use crate::{context::CoreContext, infra::query::CoreQuery};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PairHistoryQueryInput;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PairHistoryQuery;
+
+impl CoreQuery for PairHistoryQuery {
+	type Input = PairHistoryQueryInput;
+	type Output = PairHistoryOutput;
+
+	fn from_input(_input: Self::Input) -> QueryResult<Self> {
+		Ok(Self)
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let mut entries = Vec::new();
+		if let Some(net) = context.get_networking().await {
+			entries = net.get_pairing_history().await.unwrap_or_default();
+		}
+		Ok(PairHistoryOutput { entries })
+	}
+}
+
+crate::register_core_query!(PairHistoryQuery, "network.pair.history");
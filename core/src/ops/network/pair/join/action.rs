@@ -1,6 +1,12 @@
 use super::{input::PairJoinInput, output::PairJoinOutput};
-use crate::infra::action::{error::ActionError, CoreAction};
-use std::sync::Arc;
+use crate::{
+	infra::action::{error::ActionError, CoreAction},
+	service::network::protocol::pairing::JoinOutcome,
+};
+use std::{sync::Arc, time::Duration};
+
+/// How long a join waits for the initiator before reporting a timeout
+const JOIN_TIMEOUT: Duration = Duration::from_secs(60);
 
 pub struct PairJoinAction {
 	pub code: String,
@@ -46,9 +52,13 @@ impl CoreAction for PairJoinAction {
 			pairing_code = pairing_code.with_node_id(node_id);
 		}
 
-		net.start_pairing_as_joiner_with_code(pairing_code, false)
+		let outcome = net
+			.start_pairing_as_joiner_with_timeout(pairing_code, false, JOIN_TIMEOUT)
 			.await
 			.map_err(|e| ActionError::Internal(e.to_string()))?;
+		if outcome != JoinOutcome::Accepted {
+			return Err(ActionError::Internal(outcome.to_string()));
+		}
 		// Best-effort: fetch pairing sessions and find completed one
 		let sessions = net
 			.get_pairing_status()
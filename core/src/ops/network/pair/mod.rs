@@ -1,15 +1,29 @@
 pub mod cancel;
+pub mod cancel_vouching_session;
 pub mod confirm_proxy;
+pub mod diagnostics;
 pub mod generate;
+pub mod history;
 pub mod join;
+pub mod proxy_audit;
+pub mod prune_vouching_queue;
+pub mod refresh_vouching_session;
 pub mod status;
 pub mod vouch;
 pub mod vouching_session;
+pub mod vouching_sessions;
 
 pub use cancel::*;
+pub use cancel_vouching_session::*;
 pub use confirm_proxy::*;
+pub use diagnostics::*;
 pub use generate::*;
+pub use history::*;
 pub use join::*;
+pub use proxy_audit::*;
+pub use prune_vouching_queue::*;
+pub use refresh_vouching_session::*;
 pub use status::*;
 pub use vouch::*;
 pub use vouching_session::*;
+pub use vouching_sessions::*;
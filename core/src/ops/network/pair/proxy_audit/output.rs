@@ -0,0 +1,9 @@
+use crate::service::network::protocol::pairing::ProxyPairingAuditEntry;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ProxyAuditOutput {
+	/// Proxy pairing decisions, oldest first
+	pub entries: Vec<ProxyPairingAuditEntry>,
+}
@@ -0,0 +1,40 @@
+use super::output::ProxyAuditOutput;
+use crate::infra::query::{QueryError, QueryResult};
+use crate::{context::CoreContext, infra::query::CoreQuery};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ProxyAuditQueryInput;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ProxyAuditQuery;
+
+impl CoreQuery for ProxyAuditQuery {
+	type Input = ProxyAuditQueryInput;
+	type Output = ProxyAuditOutput;
+
+	fn from_input(_input: Self::Input) -> QueryResult<Self> {
+		Ok(Self)
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let Some(net) = context.get_networking().await else {
+			return Ok(ProxyAuditOutput {
+				entries: Vec::new(),
+			});
+		};
+		let entries = net
+			.get_proxy_pairing_audit()
+			.await
+			.map_err(|e| QueryError::Internal(e.to_string()))?;
+		Ok(ProxyAuditOutput { entries })
+	}
+}
+
+crate::register_core_query!(ProxyAuditQuery, "network.pair.proxy_audit");
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use super::{input::VouchingQueuePruneInput, output::VouchingQueuePruneOutput};
+use crate::infra::action::{error::ActionError, CoreAction};
+use crate::service::network::{protocol::PairingProtocolHandler, Protocol};
+
+/// Clear expired or stuck vouches from the vouching queue
+pub struct VouchingQueuePruneAction {
+	pub session_id: Option<uuid::Uuid>,
+	pub target_device_id: Option<uuid::Uuid>,
+}
+
+impl CoreAction for VouchingQueuePruneAction {
+	type Output = VouchingQueuePruneOutput;
+	type Input = VouchingQueuePruneInput;
+
+	fn from_input(input: Self::Input) -> std::result::Result<Self, String> {
+		Ok(Self {
+			session_id: input.session_id,
+			target_device_id: input.target_device_id,
+		})
+	}
+
+	async fn execute(
+		self,
+		context: Arc<crate::context::CoreContext>,
+	) -> std::result::Result<Self::Output, ActionError> {
+		let net = context
+			.get_networking()
+			.await
+			.ok_or_else(|| ActionError::Internal("Networking not initialized".to_string()))?;
+
+		let handler = net
+			.protocol_registry()
+			.read()
+			.await
+			.get_handler(Protocol::Pairing);
+		let pairing = handler
+			.as_ref()
+			.and_then(|handler| handler.as_any().downcast_ref::<PairingProtocolHandler>())
+			.ok_or_else(|| ActionError::Internal("Pairing protocol not registered".to_string()))?;
+		let pruned = pairing
+			.prune_vouching_queue(self.session_id, self.target_device_id)
+			.await
+			.map_err(|e| ActionError::Internal(e.to_string()))?;
+
+		Ok(VouchingQueuePruneOutput {
+			expired: pruned.expired,
+			for_session: pruned.for_session,
+			for_target: pruned.for_target,
+			total: pruned.total(),
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"network.pair.pruneVouchingQueue"
+	}
+}
+
+crate::register_core_action!(VouchingQueuePruneAction, "network.pair.pruneVouchingQueue");
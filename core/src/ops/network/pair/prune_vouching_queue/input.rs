@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct VouchingQueuePruneInput {
+	/// Also drop every queued vouch of this vouching session
+	#[serde(default)]
+	pub session_id: Option<Uuid>,
+	/// Also drop every queued vouch addressed to this device
+	#[serde(default)]
+	pub target_device_id: Option<Uuid>,
+}
@@ -0,0 +1,7 @@
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::VouchingQueuePruneAction;
+pub use input::VouchingQueuePruneInput;
+pub use output::VouchingQueuePruneOutput;
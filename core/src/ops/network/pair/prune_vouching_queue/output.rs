@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VouchingQueuePruneOutput {
+	/// Vouches dropped because they had expired
+	pub expired: u64,
+	/// Vouches dropped for the requested session
+	pub for_session: u64,
+	/// Vouches dropped for the requested target device
+	pub for_target: u64,
+	pub total: u64,
+}
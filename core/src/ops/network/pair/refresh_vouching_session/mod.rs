@@ -0,0 +1,7 @@
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::VouchingSessionRefreshAction;
+pub use input::VouchingSessionRefreshInput;
+pub use output::VouchingSessionRefreshOutput;
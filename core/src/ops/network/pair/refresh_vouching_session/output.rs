@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VouchingSessionRefreshOutput {
+	/// Whether the session exists and its state was emitted again
+	pub republished: bool,
+}
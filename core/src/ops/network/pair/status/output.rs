@@ -1,4 +1,4 @@
-use crate::service::network::PairingState;
+use crate::service::network::{protocol::pairing::PairingFailureReason, PairingState};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -20,7 +20,10 @@ pub enum SerializablePairingState {
 	ResponsePending,
 	ResponseSent,
 	Completed,
-	Failed { reason: String },
+	Failed {
+		reason: PairingFailureReason,
+		detail: Option<String>,
+	},
 }
 
 impl From<PairingState> for SerializablePairingState {
@@ -40,7 +43,7 @@ impl From<PairingState> for SerializablePairingState {
 			PairingState::ResponsePending { .. } => Self::ResponsePending,
 			PairingState::ResponseSent => Self::ResponseSent,
 			PairingState::Completed => Self::Completed,
-			PairingState::Failed { reason } => Self::Failed { reason },
+			PairingState::Failed { reason, detail } => Self::Failed { reason, detail },
 		}
 	}
 }
@@ -51,9 +54,15 @@ pub struct PairingSessionSummary {
 	pub state: SerializablePairingState,
 	pub remote_device_id: Option<Uuid>,
 	pub expires_at: Option<DateTime<Utc>>, // optional if available
+	/// Connection fell back to the relay; expect degraded performance
+	pub via_relay: bool,
+	/// Requester is on the auto-accept allowlist; no confirmation prompt is needed
+	pub auto_accepted: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct PairStatusOutput {
 	pub sessions: Vec<PairingSessionSummary>,
+	/// Pairing sessions are saved to disk; when false they are lost on restart
+	pub persistence_enabled: bool,
 }
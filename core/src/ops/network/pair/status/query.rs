@@ -25,7 +25,9 @@ impl CoreQuery for PairStatusQuery {
 		session: crate::infra::api::SessionContext,
 	) -> QueryResult<Self::Output> {
 		let mut sessions_out = Vec::new();
+		let mut persistence_enabled = false;
 		if let Some(net) = context.get_networking().await {
+			persistence_enabled = net.pairing_persistence_enabled().await.unwrap_or(false);
 			let sessions = net.get_pairing_status().await.unwrap_or_default();
 			for s in sessions.into_iter() {
 				sessions_out.push(PairingSessionSummary {
@@ -33,11 +35,14 @@ impl CoreQuery for PairStatusQuery {
 					state: s.state.into(),
 					remote_device_id: s.remote_device_id,
 					expires_at: None,
+					via_relay: s.via_relay,
+					auto_accepted: s.auto_accepted,
 				});
 			}
 		}
 		Ok(PairStatusOutput {
 			sessions: sessions_out,
+			persistence_enabled,
 		})
 	}
 }
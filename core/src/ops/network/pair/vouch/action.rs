@@ -2,6 +2,10 @@ use std::sync::Arc;
 
 use super::{input::PairVouchInput, output::PairVouchOutput};
 use crate::infra::action::{error::ActionError, CoreAction};
+use crate::service::network::Protocol;
+
+/// Most devices a single vouching session may target
+pub const MAX_VOUCH_TARGETS: usize = 256;
 
 pub struct PairVouchAction {
 	pub session_id: uuid::Uuid,
@@ -13,9 +17,29 @@ impl CoreAction for PairVouchAction {
 	type Input = PairVouchInput;
 
 	fn from_input(input: Self::Input) -> std::result::Result<Self, String> {
+		// Duplicates would queue the same vouch twice and inflate the session's vouches
+		let mut seen = std::collections::HashSet::new();
+		let mut target_device_ids = Vec::new();
+		for device_id in input.target_device_ids {
+			if device_id.is_nil() {
+				return Err("Vouch target device ID must not be nil".to_string());
+			}
+			if seen.insert(device_id) {
+				target_device_ids.push(device_id);
+			}
+		}
+
+		if target_device_ids.len() > MAX_VOUCH_TARGETS {
+			return Err(format!(
+				"Too many vouch targets: {} (at most {})",
+				target_device_ids.len(),
+				MAX_VOUCH_TARGETS
+			));
+		}
+
 		Ok(Self {
 			session_id: input.session_id,
-			target_device_ids: input.target_device_ids,
+			target_device_ids,
 		})
 	}
 
@@ -30,7 +54,7 @@ impl CoreAction for PairVouchAction {
 
 		let registry = net.protocol_registry();
 		let guard = registry.read().await;
-		if let Some(handler) = guard.get_handler("pairing") {
+		if let Some(handler) = guard.get_handler(Protocol::Pairing) {
 			if let Some(pairing) = handler
 				.as_any()
 				.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>(
@@ -71,3 +95,47 @@ impl CoreAction for PairVouchAction {
 }
 
 crate::register_core_action!(PairVouchAction, "network.pair.vouch");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use uuid::Uuid;
+
+	#[test]
+	fn test_duplicate_targets_collapse() {
+		let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+		let action = PairVouchAction::from_input(PairVouchInput {
+			session_id: Uuid::new_v4(),
+			target_device_ids: vec![a, b, a, a, b],
+		})
+		.unwrap();
+		assert_eq!(action.target_device_ids, vec![a, b]);
+	}
+
+	#[test]
+	fn test_nil_target_rejected() {
+		let result = PairVouchAction::from_input(PairVouchInput {
+			session_id: Uuid::new_v4(),
+			target_device_ids: vec![Uuid::new_v4(), Uuid::nil()],
+		});
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn test_target_count_capped() {
+		let result = PairVouchAction::from_input(PairVouchInput {
+			session_id: Uuid::new_v4(),
+			target_device_ids: (0..=MAX_VOUCH_TARGETS).map(|_| Uuid::new_v4()).collect(),
+		});
+		assert!(result.is_err());
+
+		// Duplicates do not count towards the cap
+		let target = Uuid::new_v4();
+		let action = PairVouchAction::from_input(PairVouchInput {
+			session_id: Uuid::new_v4(),
+			target_device_ids: vec![target; MAX_VOUCH_TARGETS + 1],
+		})
+		.unwrap();
+		assert_eq!(action.target_device_ids, vec![target]);
+	}
+}
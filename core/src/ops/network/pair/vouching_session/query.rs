@@ -5,7 +5,10 @@ use specta::Type;
 
 use super::{input::VouchingSessionInput, output::VouchingSessionOutput};
 use crate::infra::query::{CoreQuery, QueryError, QueryResult};
-use crate::{context::CoreContext, service::network::protocol::PairingProtocolHandler};
+use crate::{
+	context::CoreContext,
+	service::network::{protocol::PairingProtocolHandler, Protocol},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct VouchingSessionQuery {
@@ -34,7 +37,7 @@ impl CoreQuery for VouchingSessionQuery {
 
 		let registry = net.protocol_registry();
 		let guard = registry.read().await;
-		if let Some(handler) = guard.get_handler("pairing") {
+		if let Some(handler) = guard.get_handler(Protocol::Pairing) {
 			if let Some(pairing) = handler.as_any().downcast_ref::<PairingProtocolHandler>() {
 				let session = pairing.get_vouching_session(self.session_id).await;
 				return Ok(VouchingSessionOutput { session });
@@ -0,0 +1,5 @@
+pub mod output;
+pub mod query;
+
+pub use output::VouchingSessionsOutput;
+pub use query::{VouchingSessionsQuery, VouchingSessionsQueryInput};
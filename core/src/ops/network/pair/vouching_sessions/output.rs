@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::service::network::protocol::pairing::VouchingSession;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VouchingSessionsOutput {
+	/// Newest first
+	pub sessions: Vec<VouchingSession>,
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::output::VouchingSessionsOutput;
+use crate::infra::query::{CoreQuery, QueryError, QueryResult};
+use crate::{
+	context::CoreContext,
+	service::network::{protocol::PairingProtocolHandler, Protocol},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VouchingSessionsQueryInput;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct VouchingSessionsQuery;
+
+impl CoreQuery for VouchingSessionsQuery {
+	type Input = VouchingSessionsQueryInput;
+	type Output = VouchingSessionsOutput;
+
+	fn from_input(_input: Self::Input) -> QueryResult<Self> {
+		Ok(Self)
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let net = context
+			.get_networking()
+			.await
+			.ok_or_else(|| QueryError::Internal("Networking not initialized".to_string()))?;
+
+		let registry = net.protocol_registry();
+		let guard = registry.read().await;
+		if let Some(handler) = guard.get_handler(Protocol::Pairing) {
+			if let Some(pairing) = handler.as_any().downcast_ref::<PairingProtocolHandler>() {
+				let sessions = pairing.list_vouching_sessions().await;
+				return Ok(VouchingSessionsOutput { sessions });
+			}
+		}
+
+		Ok(VouchingSessionsOutput { sessions: vec![] })
+	}
+}
+
+crate::register_core_query!(VouchingSessionsQuery, "network.pair.vouching_sessions");
@@ -1,5 +1,6 @@
 //! Output types for network status
 
+use crate::service::network::core::relay_health::RelayHealth;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
@@ -12,4 +13,6 @@ pub struct NetworkStatus {
 	pub connected_devices: usize,
 	pub version: String,
 	pub relay_url: Option<String>,
+	/// Health of the trusted relays in the discovery config, in config order
+	pub trusted_relays: Vec<RelayHealth>,
 }
@@ -41,6 +41,7 @@ impl CoreQuery for NetworkStatusQuery {
 			};
 			let connected = net.get_connected_devices().await.len();
 			let relay_url = net.get_relay_url().await;
+			let trusted_relays = net.relay_health().await;
 			Ok(NetworkStatus {
 				running: true,
 				node_id: Some(node_id),
@@ -49,6 +50,7 @@ impl CoreQuery for NetworkStatusQuery {
 				connected_devices: connected,
 				version: env!("CARGO_PKG_VERSION").to_string(),
 				relay_url,
+				trusted_relays,
 			})
 		} else {
 			Ok(NetworkStatus {
@@ -59,6 +61,7 @@ impl CoreQuery for NetworkStatusQuery {
 				connected_devices: 0,
 				version: env!("CARGO_PKG_VERSION").to_string(),
 				relay_url: None,
+				trusted_relays: Vec::new(),
 			})
 		}
 	}
@@ -1,7 +1,13 @@
 //! Library sync setup action
 
-use super::{input::LibrarySyncSetupInput, output::LibrarySyncSetupOutput, LibrarySyncAction};
+use super::{
+	input::LibrarySyncSetupInput, output::LibrarySyncSetupOutput, LibrarySyncAction, SyncSetupRole,
+};
 use crate::infra::action::{error::ActionError, CoreAction};
+use crate::service::network::{
+	protocol::sync::{SyncPayloadFormat, SUPPORTED_SYNC_FORMATS},
+	NetworkingService,
+};
 use std::sync::Arc;
 use tracing::{info, warn};
 use uuid::Uuid;
@@ -94,7 +100,6 @@ impl CoreAction for LibrarySyncSetupAction {
 		"network.sync_setup"
 	}
 
-	// DEPRICATED: Sync no longer requires a leader device
 	async fn validate(
 		&self,
 		context: Arc<crate::context::CoreContext>,
@@ -109,11 +114,71 @@ impl CoreAction for LibrarySyncSetupAction {
 			});
 		}
 
+		if let Some(locations) = &self.input.location_filter {
+			self.validate_location_filter(&context, locations).await?;
+		}
+
 		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
 	}
 }
 
 impl LibrarySyncSetupAction {
+	/// Check that a location filter names existing locations of the library being shared
+	async fn validate_location_filter(
+		&self,
+		context: &Arc<crate::context::CoreContext>,
+		locations: &[Uuid],
+	) -> Result<(), ActionError> {
+		use crate::infra::db::entities;
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+		if !matches!(
+			self.input.action,
+			LibrarySyncAction::ShareLocalLibrary { .. }
+		) {
+			return Err(ActionError::Validation {
+				field: "location_filter".to_string(),
+				message: "Location filters are only supported when sharing a local library"
+					.to_string(),
+			});
+		}
+
+		if locations.is_empty() {
+			return Err(ActionError::Validation {
+				field: "location_filter".to_string(),
+				message: "Location filter must name at least one location".to_string(),
+			});
+		}
+
+		let library = context
+			.libraries()
+			.await
+			.get_library(self.input.local_library_id)
+			.await
+			.ok_or_else(|| ActionError::Validation {
+				field: "local_library_id".to_string(),
+				message: "Local library not found".to_string(),
+			})?;
+
+		let found: std::collections::HashSet<Uuid> = entities::location::Entity::find()
+			.filter(entities::location::Column::Uuid.is_in(locations.iter().copied()))
+			.all(library.db().conn())
+			.await
+			.map_err(|e| ActionError::Internal(format!("Database error: {}", e)))?
+			.into_iter()
+			.map(|location| location.uuid)
+			.collect();
+
+		if let Some(missing) = locations.iter().find(|id| !found.contains(id)) {
+			return Err(ActionError::Validation {
+				field: "location_filter".to_string(),
+				message: format!("Location {} not found in library", missing),
+			});
+		}
+
+		Ok(())
+	}
+
 	/// Register remote device in local library using its library-specific slug
 	/// The slug should come from the remote device (either from CreateSharedLibraryResponse
 	/// or from the remote device's DeviceInfo which includes library overrides)
@@ -194,7 +259,7 @@ impl LibrarySyncSetupAction {
 				boot_disk_type: Set(None),
 				boot_disk_capacity_bytes: Set(None),
 				swap_total_bytes: Set(None),
-				network_addresses: Set(serde_json::json!([])),
+				network_addresses: Set(Default::default()),
 				is_online: Set(false),
 				last_seen_at: Set(Utc::now()),
 				capabilities: Set(serde_json::json!({
@@ -306,8 +371,66 @@ impl LibrarySyncSetupAction {
 			requesting_device_boot_disk_type: local_device.boot_disk_type,
 			requesting_device_boot_disk_capacity_bytes: local_device.boot_disk_capacity_bytes,
 			requesting_device_swap_total_bytes: local_device.swap_total_bytes,
+			location_filter: self.input.location_filter.clone(),
 		};
 
+		// Only serve the selected locations to this peer, before it can start requesting state
+		let remote_device_id = self.input.remote_device_id;
+		let location_filter = self.input.location_filter.clone();
+		local_library
+			.update_config(|config| {
+				let filters = &mut config.settings.shared_location_filters;
+				match location_filter {
+					Some(locations) => {
+						filters.insert(remote_device_id, locations);
+					}
+					None => {
+						filters.remove(&remote_device_id);
+					}
+				}
+			})
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to save location filter: {}", e)))?;
+
+		// The role is settled before anything is sent: only the leader asks for the
+		// library to be created, a follower never does and only registers
+		let role = SyncSetupRole::for_setup(
+			self.input.local_device_id,
+			remote_device_id,
+			self.input.leader_device_id,
+		);
+		if role == SyncSetupRole::Follower {
+			if !self
+				.remote_has_library(&networking, remote_device_id, library_id)
+				.await
+			{
+				return Err(ActionError::Validation {
+					field: "leader_device_id".to_string(),
+					message: format!(
+						"Leader device {} does not hold library {} yet; share it from that device",
+						remote_device_id, library_id
+					),
+				});
+			}
+
+			info!(
+				"Leader device {} holds library {}, registering only",
+				remote_device_id, library_id
+			);
+			self.register_with_remote(&context, library_id).await?;
+
+			return Ok(LibrarySyncSetupOutput {
+				success: true,
+				local_library_id: library_id,
+				remote_library_id: Some(library_id),
+				devices_registered: true,
+				message: format!(
+					"Registered with library '{}' on the leader device",
+					library_name
+				),
+			});
+		}
+
 		info!(
 			"Sending CreateSharedLibraryRequest to remote device: library={}, name={}",
 			library_id, library_name
@@ -345,74 +468,7 @@ impl LibrarySyncSetupAction {
 					remote_slug
 				);
 
-				// Send RegisterDeviceRequest to remote device
-				// Remote will register us, then send RegisterDeviceRequest back to register themselves
-				// This bidirectional exchange ensures both devices have full hardware specs
-				let networking = context
-					.get_networking()
-					.await
-					.ok_or_else(|| ActionError::Internal("Networking not available".to_string()))?;
-
-				// Get full device information including hardware specs
-				let local_device = context.device_manager.to_device().map_err(|e| {
-					ActionError::Internal(format!("Failed to get device info: {}", e))
-				})?;
-
-				// Get library-specific slug (uses override if set, otherwise global slug)
-				let local_device_slug = context
-					.device_manager
-					.slug_for_library(library_id)
-					.map_err(|e| {
-						ActionError::Internal(format!("Failed to get device slug: {}", e))
-					})?;
-
-				let register_request = LibraryMessage::RegisterDeviceRequest {
-					request_id: Uuid::new_v4(),
-					library_id: Some(library_id),
-					device_id: self.input.local_device_id,
-					device_name: local_device.name,
-					device_slug: local_device_slug,
-					os_name: local_device.os.to_string(),
-					os_version: local_device.os_version,
-					hardware_model: local_device.hardware_model,
-					cpu_model: local_device.cpu_model,
-					cpu_architecture: local_device.cpu_architecture,
-					cpu_cores_physical: local_device.cpu_cores_physical,
-					cpu_cores_logical: local_device.cpu_cores_logical,
-					cpu_frequency_mhz: local_device.cpu_frequency_mhz,
-					memory_total_bytes: local_device.memory_total_bytes,
-					form_factor: local_device.form_factor.map(|f| f.to_string()),
-					manufacturer: local_device.manufacturer,
-					gpu_models: local_device.gpu_models,
-					boot_disk_type: local_device.boot_disk_type,
-					boot_disk_capacity_bytes: local_device.boot_disk_capacity_bytes,
-					swap_total_bytes: local_device.swap_total_bytes,
-				};
-
-				match networking
-					.send_library_request(self.input.remote_device_id, register_request)
-					.await
-				{
-					Ok(LibraryMessage::RegisterDeviceResponse { success: true, .. }) => {
-						info!("Successfully registered local device on remote device");
-					}
-					Ok(LibraryMessage::RegisterDeviceResponse {
-						success: false,
-						message,
-						..
-					}) => {
-						warn!(
-							"Remote device failed to register local device: {}",
-							message.unwrap_or_else(|| "Unknown error".to_string())
-						);
-					}
-					Err(e) => {
-						warn!("Failed to send register request to remote device: {}", e);
-					}
-					_ => {
-						warn!("Unexpected response from remote device for register request");
-					}
-				}
+				self.register_with_remote(&context, library_id).await?;
 
 				Ok(LibrarySyncSetupOutput {
 					success: true,
@@ -440,6 +496,134 @@ impl LibrarySyncSetupAction {
 		}
 	}
 
+	/// Whether the remote device already holds a library with this id
+	async fn remote_has_library(
+		&self,
+		networking: &Arc<crate::service::network::NetworkingService>,
+		remote_device_id: Uuid,
+		library_id: Uuid,
+	) -> bool {
+		use crate::service::network::protocol::library_messages::LibraryMessage;
+
+		let request = LibraryMessage::LibraryStateRequest {
+			request_id: Uuid::new_v4(),
+			library_id,
+		};
+		matches!(
+			networking.send_library_request(remote_device_id, request).await,
+			Ok(LibraryMessage::LibraryStateResponse { library_id: id, .. }) if id == library_id
+		)
+	}
+
+	/// Send RegisterDeviceRequest to the remote device
+	///
+	/// Remote will register us, then send RegisterDeviceRequest back to register themselves.
+	/// This bidirectional exchange ensures both devices have full hardware specs.
+	async fn register_with_remote(
+		&self,
+		context: &Arc<crate::context::CoreContext>,
+		library_id: Uuid,
+	) -> Result<(), ActionError> {
+		use crate::service::network::protocol::library_messages::LibraryMessage;
+
+		let networking = context
+			.get_networking()
+			.await
+			.ok_or_else(|| ActionError::Internal("Networking not available".to_string()))?;
+
+		// Get full device information including hardware specs
+		let local_device = context
+			.device_manager
+			.to_device()
+			.map_err(|e| ActionError::Internal(format!("Failed to get device info: {}", e)))?;
+
+		// Get library-specific slug (uses override if set, otherwise global slug)
+		let local_device_slug = context
+			.device_manager
+			.slug_for_library(library_id)
+			.map_err(|e| ActionError::Internal(format!("Failed to get device slug: {}", e)))?;
+
+		let register_request = LibraryMessage::RegisterDeviceRequest {
+			request_id: Uuid::new_v4(),
+			library_id: Some(library_id),
+			device_id: self.input.local_device_id,
+			device_name: local_device.name,
+			device_slug: local_device_slug,
+			os_name: local_device.os.to_string(),
+			os_version: local_device.os_version,
+			hardware_model: local_device.hardware_model,
+			cpu_model: local_device.cpu_model,
+			cpu_architecture: local_device.cpu_architecture,
+			cpu_cores_physical: local_device.cpu_cores_physical,
+			cpu_cores_logical: local_device.cpu_cores_logical,
+			cpu_frequency_mhz: local_device.cpu_frequency_mhz,
+			memory_total_bytes: local_device.memory_total_bytes,
+			form_factor: local_device.form_factor.map(|f| f.to_string()),
+			manufacturer: local_device.manufacturer,
+			gpu_models: local_device.gpu_models,
+			boot_disk_type: local_device.boot_disk_type,
+			boot_disk_capacity_bytes: local_device.boot_disk_capacity_bytes,
+			swap_total_bytes: local_device.swap_total_bytes,
+			sync_formats: SUPPORTED_SYNC_FORMATS.to_vec(),
+		};
+
+		match networking
+			.send_library_request(self.input.remote_device_id, register_request)
+			.await
+		{
+			Ok(LibraryMessage::RegisterDeviceResponse {
+				success: true,
+				sync_formats,
+				..
+			}) => {
+				info!("Successfully registered local device on remote device");
+				self.record_sync_format(&networking, &sync_formats).await;
+			}
+			Ok(LibraryMessage::RegisterDeviceResponse {
+				success: false,
+				message,
+				..
+			}) => {
+				warn!(
+					"Remote device failed to register local device: {}",
+					message.unwrap_or_else(|| "Unknown error".to_string())
+				);
+			}
+			Err(e) => {
+				warn!("Failed to send register request to remote device: {}", e);
+			}
+			_ => {
+				warn!("Unexpected response from remote device for register request");
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Record the sync payload format negotiated with the remote device
+	async fn record_sync_format(
+		&self,
+		networking: &NetworkingService,
+		remote_formats: &[SyncPayloadFormat],
+	) {
+		match networking
+			.device_registry()
+			.write()
+			.await
+			.negotiate_sync_format(self.input.remote_device_id, remote_formats)
+			.await
+		{
+			Ok(format) => info!(
+				"Negotiated {:?} sync payloads with device {}",
+				format, self.input.remote_device_id
+			),
+			Err(e) => warn!(
+				"Failed to record sync payload format for device {}: {}",
+				self.input.remote_device_id, e
+			),
+		}
+	}
+
 	/// Execute JoinRemoteLibrary action - join an existing remote library
 	async fn execute_join_remote(
 		&self,
@@ -534,14 +718,20 @@ impl LibrarySyncSetupAction {
 			boot_disk_type: local_device.boot_disk_type,
 			boot_disk_capacity_bytes: local_device.boot_disk_capacity_bytes,
 			swap_total_bytes: local_device.swap_total_bytes,
+			sync_formats: SUPPORTED_SYNC_FORMATS.to_vec(),
 		};
 
 		match networking
 			.send_library_request(self.input.remote_device_id, register_request)
 			.await
 		{
-			Ok(LibraryMessage::RegisterDeviceResponse { success: true, .. }) => {
+			Ok(LibraryMessage::RegisterDeviceResponse {
+				success: true,
+				sync_formats,
+				..
+			}) => {
 				info!("Successfully registered local device on remote device");
+				self.record_sync_format(&networking, &sync_formats).await;
 			}
 			Ok(LibraryMessage::RegisterDeviceResponse {
 				success: false,
@@ -50,6 +50,14 @@ pub struct LibrarySyncSetupInput {
 	/// Sync action to perform
 	pub action: LibrarySyncAction,
 
-	/// DEPRICATED: Which device should be the sync leader (for future sync implementation)
+	/// Only sync these locations (and their entries) with the remote device
+	///
+	/// `None` shares the whole library. Only supported when sharing a local library.
+	#[serde(default)]
+	pub location_filter: Option<Vec<Uuid>>,
+
+	/// Which device creates the shared library; the other one only registers
+	///
+	/// Must be the local or the remote device.
 	pub leader_device_id: Uuid,
 }
@@ -0,0 +1,94 @@
+//! Deciding which device of a pair creates a shared library
+//!
+//! If both devices set up sync for the same library at once, each would ask the
+//! other to create it and both sides would race to insert the same rows. The
+//! setup names its leader in `leader_device_id`; the role follows from that
+//! before anything is sent, so there is no check for the library followed by a
+//! create. The leader creates, the follower only registers. Setups that name
+//! neither device fall back to the lower device id, which both sides agree on
+//! without another round trip.
+
+use uuid::Uuid;
+
+/// This device's part in setting up a shared library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncSetupRole {
+	/// Creates the shared library on the other device
+	Leader,
+	/// Registers with a library the leader already holds
+	Follower,
+}
+
+impl SyncSetupRole {
+	/// This device's role in a setup that names `leader_device_id` as its leader
+	pub fn for_setup(
+		local_device_id: Uuid,
+		remote_device_id: Uuid,
+		leader_device_id: Uuid,
+	) -> Self {
+		if leader_device_id == local_device_id {
+			Self::Leader
+		} else if leader_device_id == remote_device_id {
+			Self::Follower
+		} else {
+			Self::for_devices(local_device_id, remote_device_id)
+		}
+	}
+
+	pub fn for_devices(local_device_id: Uuid, remote_device_id: Uuid) -> Self {
+		if elect_sync_leader(local_device_id, remote_device_id) == local_device_id {
+			Self::Leader
+		} else {
+			Self::Follower
+		}
+	}
+}
+
+/// The device that creates a library shared between `a` and `b`
+///
+/// Symmetric in its arguments, so both devices elect the same leader.
+pub fn elect_sync_leader(a: Uuid, b: Uuid) -> Uuid {
+	a.min(b)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_both_devices_elect_the_same_leader() {
+		let a = Uuid::new_v4();
+		let b = Uuid::new_v4();
+
+		assert_eq!(elect_sync_leader(a, b), elect_sync_leader(b, a));
+
+		let roles = (
+			SyncSetupRole::for_devices(a, b),
+			SyncSetupRole::for_devices(b, a),
+		);
+		assert!(matches!(
+			roles,
+			(SyncSetupRole::Leader, SyncSetupRole::Follower)
+				| (SyncSetupRole::Follower, SyncSetupRole::Leader)
+		));
+	}
+
+	#[test]
+	fn test_named_leader_decides_the_roles() {
+		let local = Uuid::new_v4();
+		let remote = Uuid::new_v4();
+
+		assert_eq!(
+			SyncSetupRole::for_setup(local, remote, local),
+			SyncSetupRole::Leader
+		);
+		assert_eq!(
+			SyncSetupRole::for_setup(local, remote, remote),
+			SyncSetupRole::Follower
+		);
+		assert_eq!(
+			SyncSetupRole::for_setup(local, remote, Uuid::nil()),
+			SyncSetupRole::for_devices(local, remote)
+		);
+	}
+}
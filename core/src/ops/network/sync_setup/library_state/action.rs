@@ -0,0 +1,115 @@
+//! Remote library state action
+
+use super::{input::GetRemoteLibraryStateInput, output::GetRemoteLibraryStateOutput};
+use crate::{
+	infra::action::{error::ActionError, CoreAction},
+	service::network::protocol::library_messages::LibraryMessage,
+};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// How long to wait for the remote device to answer
+const LIBRARY_STATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Ask a paired device for the state of one of its libraries
+///
+/// Lets the UI confirm the library identity and warn about device slug collisions
+/// before starting sync setup.
+pub struct GetRemoteLibraryStateAction {
+	input: GetRemoteLibraryStateInput,
+}
+
+impl CoreAction for GetRemoteLibraryStateAction {
+	type Input = GetRemoteLibraryStateInput;
+	type Output = GetRemoteLibraryStateOutput;
+
+	fn from_input(input: Self::Input) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		context: Arc<crate::context::CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let networking = context
+			.get_networking()
+			.await
+			.ok_or_else(|| ActionError::Internal("Networking not initialized".to_string()))?;
+
+		{
+			let device_registry = networking.device_registry();
+			let registry = device_registry.read().await;
+			match registry.get_device_state(self.input.device_id) {
+				Some(crate::service::network::device::DeviceState::Paired { .. })
+				| Some(crate::service::network::device::DeviceState::Connected { .. }) => {}
+				Some(_) => {
+					return Err(ActionError::Validation {
+						field: "device_id".to_string(),
+						message: "Device must be paired before inspecting its libraries"
+							.to_string(),
+					});
+				}
+				None => {
+					return Err(ActionError::Validation {
+						field: "device_id".to_string(),
+						message: "Device not found".to_string(),
+					});
+				}
+			}
+		}
+
+		let request_id = Uuid::new_v4();
+		let request = LibraryMessage::LibraryStateRequest {
+			request_id,
+			library_id: self.input.library_id,
+		};
+
+		let response = tokio::time::timeout(
+			LIBRARY_STATE_TIMEOUT,
+			networking.send_library_request(self.input.device_id, request),
+		)
+		.await
+		.map_err(|_| {
+			ActionError::Internal(format!(
+				"Device {} did not answer the library state request within {}s",
+				self.input.device_id,
+				LIBRARY_STATE_TIMEOUT.as_secs()
+			))
+		})?
+		.map_err(|e| {
+			ActionError::Internal(format!("Failed to send library state request: {}", e))
+		})?;
+
+		match response {
+			LibraryMessage::LibraryStateResponse {
+				request_id: response_id,
+				library_id,
+				library_name,
+				device_slugs,
+				device_count,
+			} if response_id == request_id && library_id == self.input.library_id => {
+				Ok(GetRemoteLibraryStateOutput {
+					library_id,
+					library_name,
+					device_slugs,
+					device_count,
+				})
+			}
+			LibraryMessage::LibraryStateResponse { .. } => Err(ActionError::Internal(
+				"Library state response does not match the request".to_string(),
+			)),
+			_ => Err(ActionError::Internal(
+				"Unexpected response from device".to_string(),
+			)),
+		}
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"network.sync_setup.library_state"
+	}
+}
+
+crate::register_core_action!(
+	GetRemoteLibraryStateAction,
+	"network.sync_setup.library_state"
+);
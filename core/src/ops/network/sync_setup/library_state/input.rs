@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GetRemoteLibraryStateInput {
+	/// Paired device to ask
+	pub device_id: Uuid,
+
+	/// Library to inspect on that device
+	pub library_id: Uuid,
+}
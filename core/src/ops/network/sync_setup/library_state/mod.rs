@@ -0,0 +1,9 @@
+//! Inspect a remote device's copy of a library before joining it
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::*;
+pub use input::*;
+pub use output::*;
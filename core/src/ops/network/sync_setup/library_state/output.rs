@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GetRemoteLibraryStateOutput {
+	/// Library ID the remote device answered for
+	pub library_id: Uuid,
+
+	/// Library name on the remote device
+	pub library_name: String,
+
+	/// Slugs of every device registered in the remote library
+	pub device_slugs: Vec<String>,
+
+	/// Number of devices registered in the remote library
+	pub device_count: usize,
+}
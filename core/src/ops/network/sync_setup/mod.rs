@@ -7,9 +7,13 @@
 pub mod action;
 pub mod discovery;
 pub mod input;
+pub mod leader;
+pub mod library_state;
 pub mod output;
 
 pub use action::LibrarySyncSetupAction;
 pub use discovery::*;
 pub use input::*;
+pub use leader::*;
+pub use library_state::*;
 pub use output::*;
@@ -0,0 +1,76 @@
+use super::{input::SetDeviceTrustInput, output::SetDeviceTrustOutput};
+use crate::{
+	infra::action::{error::ActionError, CoreAction},
+	service::network::{
+		device::TrustLevel, protocol::PairingProtocolHandler, NetworkingError, Protocol,
+	},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct SetDeviceTrustAction {
+	pub device_id: Uuid,
+	pub trust_level: TrustLevel,
+}
+
+impl CoreAction for SetDeviceTrustAction {
+	type Output = SetDeviceTrustOutput;
+	type Input = SetDeviceTrustInput;
+
+	fn from_input(input: Self::Input) -> std::result::Result<Self, String> {
+		Ok(Self {
+			device_id: input.device_id,
+			trust_level: input.trust_level,
+		})
+	}
+
+	async fn execute(
+		self,
+		context: Arc<crate::context::CoreContext>,
+	) -> std::result::Result<Self::Output, ActionError> {
+		tracing::info!(
+			"Setting trust of device {} to {:?}",
+			self.device_id,
+			self.trust_level
+		);
+
+		let net = context
+			.get_networking()
+			.await
+			.ok_or_else(|| ActionError::Internal("Networking not initialized".to_string()))?;
+
+		// Voucher eligibility and queued vouches live behind the pairing handler
+		let handler = net
+			.protocol_registry()
+			.read()
+			.await
+			.get_handler(Protocol::Pairing);
+		let pairing = handler
+			.as_ref()
+			.and_then(|handler| handler.as_any().downcast_ref::<PairingProtocolHandler>())
+			.ok_or_else(|| ActionError::Internal("Pairing protocol not registered".to_string()))?;
+		let change = pairing
+			.set_device_trust(self.device_id, self.trust_level)
+			.await
+			.map_err(|e| match e {
+				NetworkingError::DeviceNotFound(_) => ActionError::Validation {
+					field: "device_id".to_string(),
+					message: format!("Device {} is not paired", self.device_id),
+				},
+				e => ActionError::Internal(e.to_string()),
+			})?;
+
+		Ok(SetDeviceTrustOutput {
+			previous_trust_level: change.previous,
+			trust_level: change.current,
+			can_vouch: change.can_vouch,
+			dropped_vouches: change.dropped_vouches,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"network.device.trust"
+	}
+}
+
+crate::register_core_action!(SetDeviceTrustAction, "network.device.trust");
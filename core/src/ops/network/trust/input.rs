@@ -0,0 +1,10 @@
+use crate::service::network::device::TrustLevel;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SetDeviceTrustInput {
+	pub device_id: Uuid,
+	pub trust_level: TrustLevel,
+}
@@ -0,0 +1,13 @@
+use crate::service::network::device::TrustLevel;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SetDeviceTrustOutput {
+	pub previous_trust_level: TrustLevel,
+	pub trust_level: TrustLevel,
+	/// Whether the device is now accepted as a voucher for proxy pairing
+	pub can_vouch: bool,
+	/// Queued vouches involving the device that were dropped
+	pub dropped_vouches: u64,
+}
@@ -0,0 +1,357 @@
+use super::{input::UnpairDeviceInput, output::UnpairDeviceOutput};
+use crate::{
+	infra::{
+		action::{error::ActionError, CoreAction},
+		db::entities::{device, entry, location, volume},
+		event::Event,
+	},
+	ops::indexing::DatabaseStorage,
+	service::network::{protocol::PairingProtocolHandler, Protocol},
+};
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+	TransactionTrait,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct UnpairDeviceAction {
+	pub device_id: Uuid,
+	pub purge_synced_data: bool,
+}
+
+impl CoreAction for UnpairDeviceAction {
+	type Output = UnpairDeviceOutput;
+	type Input = UnpairDeviceInput;
+
+	fn from_input(input: Self::Input) -> std::result::Result<Self, String> {
+		Ok(Self {
+			device_id: input.device_id,
+			purge_synced_data: input.purge_synced_data,
+		})
+	}
+
+	async fn execute(
+		self,
+		context: Arc<crate::context::CoreContext>,
+	) -> std::result::Result<Self::Output, ActionError> {
+		tracing::info!(
+			"Unpairing device {} (purge_synced_data={})",
+			self.device_id,
+			self.purge_synced_data
+		);
+
+		let net = context
+			.get_networking()
+			.await
+			.ok_or_else(|| ActionError::Internal("Networking not initialized".to_string()))?;
+
+		// Registry, session keys and queued vouches all live behind the pairing handler
+		let handler = net
+			.protocol_registry()
+			.read()
+			.await
+			.get_handler(Protocol::Pairing);
+		let pairing = handler
+			.as_ref()
+			.and_then(|handler| handler.as_any().downcast_ref::<PairingProtocolHandler>())
+			.ok_or_else(|| ActionError::Internal("Pairing protocol not registered".to_string()))?;
+		let unpaired = pairing
+			.unpair_device(self.device_id)
+			.await
+			.map_err(|e| ActionError::Internal(e.to_string()))?;
+
+		if let Err(e) = context
+			.device_manager
+			.remove_paired_device_from_cache(self.device_id)
+		{
+			tracing::warn!("Failed to remove device from cache: {}", e);
+		}
+
+		let mut purged_libraries = 0;
+		if self.purge_synced_data {
+			for library in context.libraries().await.get_open_libraries().await {
+				match purge_device_rows(library.db().conn(), self.device_id).await {
+					Ok(true) => purged_libraries += 1,
+					Ok(false) => {}
+					Err(e) => {
+						tracing::warn!(
+							"Failed to purge device {} from library {}: {}",
+							self.device_id,
+							library.id(),
+							e
+						);
+					}
+				}
+			}
+		}
+
+		context.events.emit(Event::DeviceUnpaired {
+			device_id: self.device_id,
+			purged_synced_data: purged_libraries > 0,
+		});
+
+		tracing::info!(
+			"Device {} unpaired ({} vouches dropped, purged from {} libraries)",
+			self.device_id,
+			unpaired.dropped_vouches,
+			purged_libraries
+		);
+		Ok(UnpairDeviceOutput {
+			unpaired: unpaired.was_paired,
+			dropped_vouches: unpaired.dropped_vouches,
+			purged_libraries,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"network.device.unpair"
+	}
+}
+
+/// Delete the rows a device owns from one library, returning whether the device was present
+///
+/// Locations, their entries and volumes are deleted. The device record itself is
+/// shared with every peer in the library, and a local delete leaves no tombstone,
+/// so backfill would bring it straight back; it is kept and taken out of sync
+/// instead. Other shared rows (content identities, tags, user metadata) are never
+/// touched since entries from other devices may still reference them. The deletes
+/// are local only, so nothing is propagated to other peers.
+async fn purge_device_rows(db: &DatabaseConnection, device_id: Uuid) -> Result<bool, DbErr> {
+	let txn = db.begin().await?;
+
+	let Some(device) = device::Entity::find()
+		.filter(device::Column::Uuid.eq(device_id))
+		.one(&txn)
+		.await?
+	else {
+		return Ok(false);
+	};
+
+	let locations = location::Entity::find()
+		.filter(location::Column::DeviceId.eq(device.id))
+		.all(&txn)
+		.await?;
+	for location in locations {
+		if let Some(entry_id) = location.entry_id {
+			DatabaseStorage::delete_subtree_in_txn(entry_id, &txn).await?;
+		}
+		location::Entity::delete_by_id(location.id)
+			.exec(&txn)
+			.await?;
+	}
+
+	let volume_ids: Vec<i32> = volume::Entity::find()
+		.filter(volume::Column::DeviceId.eq(device_id))
+		.all(&txn)
+		.await?
+		.into_iter()
+		.map(|volume| volume.id)
+		.collect();
+	if !volume_ids.is_empty() {
+		// Entries indexed on the device's volumes outside of any location
+		let roots = entry::Entity::find()
+			.filter(entry::Column::VolumeId.is_in(volume_ids.clone()))
+			.filter(entry::Column::ParentId.is_null())
+			.all(&txn)
+			.await?;
+		for root in roots {
+			DatabaseStorage::delete_subtree_in_txn(root.id, &txn).await?;
+		}
+		volume::Entity::delete_many()
+			.filter(volume::Column::Id.is_in(volume_ids))
+			.exec(&txn)
+			.await?;
+	}
+
+	let mut device: device::ActiveModel = device.into();
+	device.sync_enabled = Set(false);
+	device.is_online = Set(false);
+	device.update(&txn).await?;
+	txn.commit().await?;
+
+	Ok(true)
+}
+
+crate::register_core_action!(UnpairDeviceAction, "network.device.unpair");
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::infra::db::{entities::entry_closure, Database};
+	use chrono::Utc;
+	use tempfile::TempDir;
+
+	struct DeviceRows {
+		device_id: Uuid,
+		location_id: i32,
+		entry_ids: Vec<i32>,
+		volume_id: i32,
+	}
+
+	/// A device with one volume, one location on it, and a file in the location
+	async fn insert_device_rows(db: &DatabaseConnection, slug: &str) -> DeviceRows {
+		let now = Utc::now();
+		let device_id = Uuid::new_v4();
+		let device = device::ActiveModel {
+			uuid: Set(device_id),
+			name: Set(slug.to_string()),
+			slug: Set(slug.to_string()),
+			os: Set("Linux".to_string()),
+			network_addresses: Set(Default::default()),
+			is_online: Set(true),
+			last_seen_at: Set(now),
+			capabilities: Set(serde_json::json!({})),
+			created_at: Set(now),
+			updated_at: Set(now),
+			sync_enabled: Set(true),
+			..Default::default()
+		}
+		.insert(db)
+		.await
+		.unwrap();
+
+		let volume = volume::ActiveModel {
+			uuid: Set(Uuid::new_v4()),
+			device_id: Set(device_id),
+			fingerprint: Set(format!("{}-volume", slug)),
+			tracked_at: Set(now),
+			last_seen_at: Set(now),
+			is_online: Set(true),
+			..Default::default()
+		}
+		.insert(db)
+		.await
+		.unwrap();
+
+		let new_entry = |name: &str, kind: i32, parent_id: Option<i32>| entry::ActiveModel {
+			uuid: Set(Some(Uuid::new_v4())),
+			name: Set(name.to_string()),
+			kind: Set(kind),
+			size: Set(0),
+			aggregate_size: Set(0),
+			child_count: Set(0),
+			file_count: Set(0),
+			created_at: Set(now),
+			modified_at: Set(now),
+			parent_id: Set(parent_id),
+			volume_id: Set(Some(volume.id)),
+			..Default::default()
+		};
+		let root = new_entry(slug, 1, None).insert(db).await.unwrap();
+		let file = new_entry("file.txt", 0, Some(root.id))
+			.insert(db)
+			.await
+			.unwrap();
+		for (ancestor_id, descendant_id, depth) in [
+			(root.id, root.id, 0),
+			(file.id, file.id, 0),
+			(root.id, file.id, 1),
+		] {
+			entry_closure::ActiveModel {
+				ancestor_id: Set(ancestor_id),
+				descendant_id: Set(descendant_id),
+				depth: Set(depth),
+			}
+			.insert(db)
+			.await
+			.unwrap();
+		}
+
+		let location = location::ActiveModel {
+			uuid: Set(Uuid::new_v4()),
+			device_id: Set(device.id),
+			volume_id: Set(Some(volume.id)),
+			entry_id: Set(Some(root.id)),
+			name: Set(Some(slug.to_string())),
+			index_mode: Set("deep".to_string()),
+			scan_state: Set("completed".to_string()),
+			total_file_count: Set(1),
+			total_byte_size: Set(0),
+			created_at: Set(now),
+			updated_at: Set(now),
+			..Default::default()
+		}
+		.insert(db)
+		.await
+		.unwrap();
+
+		DeviceRows {
+			device_id,
+			location_id: location.id,
+			entry_ids: vec![root.id, file.id],
+			volume_id: volume.id,
+		}
+	}
+
+	#[tokio::test]
+	async fn test_purge_removes_owned_rows_and_keeps_device_record() {
+		let temp_dir = TempDir::new().unwrap();
+		let db = Database::create(&temp_dir.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+		let db = db.conn();
+
+		let purged = insert_device_rows(db, "purged").await;
+		let kept = insert_device_rows(db, "kept").await;
+
+		assert!(purge_device_rows(db, purged.device_id).await.unwrap());
+		assert!(!purge_device_rows(db, Uuid::new_v4()).await.unwrap());
+
+		assert!(location::Entity::find_by_id(purged.location_id)
+			.one(db)
+			.await
+			.unwrap()
+			.is_none());
+		assert!(volume::Entity::find_by_id(purged.volume_id)
+			.one(db)
+			.await
+			.unwrap()
+			.is_none());
+		assert!(entry::Entity::find()
+			.filter(entry::Column::Id.is_in(purged.entry_ids))
+			.all(db)
+			.await
+			.unwrap()
+			.is_empty());
+
+		// The shared device record stays, out of sync, so backfill cannot revive it
+		let record = device::Entity::find()
+			.filter(device::Column::Uuid.eq(purged.device_id))
+			.one(db)
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(!record.sync_enabled);
+		assert!(!record.is_online);
+
+		// Another device's rows are untouched
+		assert!(location::Entity::find_by_id(kept.location_id)
+			.one(db)
+			.await
+			.unwrap()
+			.is_some());
+		assert!(volume::Entity::find_by_id(kept.volume_id)
+			.one(db)
+			.await
+			.unwrap()
+			.is_some());
+		assert_eq!(
+			entry::Entity::find()
+				.filter(entry::Column::Id.is_in(kept.entry_ids))
+				.all(db)
+				.await
+				.unwrap()
+				.len(),
+			2
+		);
+		let kept_record = device::Entity::find()
+			.filter(device::Column::Uuid.eq(kept.device_id))
+			.one(db)
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(kept_record.sync_enabled);
+	}
+}
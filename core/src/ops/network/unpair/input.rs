@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct UnpairDeviceInput {
+	pub device_id: Uuid,
+
+	/// Whether to also delete the device's synced data from every open library
+	///
+	/// Only rows the device owns (its locations, their entries and its volumes) are
+	/// deleted. The device record is shared with the library, so it is kept with
+	/// sync turned off. Shared rows such as tags and content identities may still be
+	/// referenced by other devices and are left untouched.
+	#[serde(default)]
+	pub purge_synced_data: bool,
+}
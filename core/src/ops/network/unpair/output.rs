@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct UnpairDeviceOutput {
+	/// Whether the device had a persisted pairing that was removed
+	pub unpaired: bool,
+	/// Queued vouches involving the device that were dropped
+	pub dropped_vouches: u64,
+	/// Libraries the device's synced data was purged from
+	pub purged_libraries: u32,
+}
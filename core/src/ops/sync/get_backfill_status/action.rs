@@ -0,0 +1,66 @@
+//! Get sync backfill status query
+
+use crate::context::CoreContext;
+use crate::infra::query::{LibraryQuery, QueryError, QueryResult};
+use std::sync::Arc;
+
+use super::output::BackfillModelStatus;
+use super::{SyncBackfillStatusInput, SyncBackfillStatusOutput};
+
+/// Report per-peer, per-model progress of the backfills running in the current library
+pub struct SyncBackfillStatusQuery;
+
+impl LibraryQuery for SyncBackfillStatusQuery {
+	type Input = SyncBackfillStatusInput;
+	type Output = SyncBackfillStatusOutput;
+
+	fn from_input(_input: Self::Input) -> QueryResult<Self> {
+		Ok(Self)
+	}
+
+	async fn execute(
+		self,
+		context: Arc<CoreContext>,
+		session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let library_id = session
+			.current_library_id
+			.ok_or_else(|| QueryError::Internal("No library in session".to_string()))?;
+		let library = context
+			.libraries()
+			.await
+			.get_library(library_id)
+			.await
+			.ok_or_else(|| QueryError::LibraryNotFound(library_id))?;
+
+		let sync_service = library
+			.sync_service()
+			.ok_or_else(|| QueryError::Internal("Sync service not available".to_string()))?;
+
+		let mut backfills = Vec::new();
+		for progress in sync_service.backfill_manager().backfill_progress() {
+			// A missing watermark only means nothing has been recorded for the model yet
+			let watermark = sync_service
+				.peer_sync()
+				.get_resource_watermark(progress.peer, &progress.model_type)
+				.await
+				.ok()
+				.flatten();
+
+			backfills.push(BackfillModelStatus {
+				peer_device_id: progress.peer,
+				percentage: progress.percentage(),
+				model_type: progress.model_type,
+				last_applied_cursor: progress.last_applied_cursor,
+				watermark,
+				records_applied: progress.records_applied,
+				estimated_total: progress.estimated_total,
+				updated_at: progress.updated_at,
+			});
+		}
+
+		Ok(SyncBackfillStatusOutput { backfills })
+	}
+}
+
+crate::register_library_query!(SyncBackfillStatusQuery, "sync.backfill_status");
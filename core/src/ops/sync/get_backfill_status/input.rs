@@ -0,0 +1,8 @@
+//! Get sync backfill status input
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Input for getting the progress of active backfills
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SyncBackfillStatusInput {}
@@ -0,0 +1,9 @@
+//! Get sync backfill status operation
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::SyncBackfillStatusQuery;
+pub use input::SyncBackfillStatusInput;
+pub use output::{BackfillModelStatus, SyncBackfillStatusOutput};
@@ -0,0 +1,29 @@
+//! Get sync backfill status output
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SyncBackfillStatusOutput {
+	/// Models currently being backfilled, sorted by peer then model; empty when idle
+	pub backfills: Vec<BackfillModelStatus>,
+}
+
+/// Progress of backfilling one model from one peer
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackfillModelStatus {
+	pub peer_device_id: Uuid,
+	pub model_type: String,
+	/// Cursor of the last applied page ("timestamp|uuid")
+	pub last_applied_cursor: Option<String>,
+	/// Watermark recorded for this peer and model, if any
+	pub watermark: Option<DateTime<Utc>>,
+	pub records_applied: u64,
+	/// Record count the peer reported, if it sent one
+	pub estimated_total: Option<u64>,
+	/// Share of the estimated total applied, from 0 to 100
+	pub percentage: Option<f32>,
+	pub updated_at: DateTime<Utc>,
+}
@@ -67,6 +67,7 @@ impl LibraryQuery for GetSyncMetrics {
 			snapshot.data_volume = Default::default();
 			snapshot.performance = Default::default();
 			snapshot.errors = Default::default();
+			snapshot.throughput = Default::default();
 		}
 
 		if input.operations_only.unwrap_or(false) {
@@ -75,6 +76,7 @@ impl LibraryQuery for GetSyncMetrics {
 			snapshot.data_volume = Default::default();
 			snapshot.performance = Default::default();
 			snapshot.errors = Default::default();
+			snapshot.throughput = Default::default();
 		}
 
 		if input.errors_only.unwrap_or(false) {
@@ -83,6 +85,16 @@ impl LibraryQuery for GetSyncMetrics {
 			snapshot.operations = Default::default();
 			snapshot.data_volume = Default::default();
 			snapshot.performance = Default::default();
+			snapshot.throughput = Default::default();
+		}
+
+		if input.throughput_only.unwrap_or(false) {
+			// Keep only throughput metrics, clear others
+			snapshot.state = Default::default();
+			snapshot.operations = Default::default();
+			snapshot.data_volume = Default::default();
+			snapshot.performance = Default::default();
+			snapshot.errors = Default::default();
 		}
 
 		Ok(GetSyncMetricsOutput { metrics: snapshot })
@@ -24,4 +24,7 @@ pub struct GetSyncMetricsInput {
 
 	/// Show only error metrics
 	pub errors_only: Option<bool>,
+
+	/// Show only throughput metrics
+	pub throughput_only: Option<bool>,
 }
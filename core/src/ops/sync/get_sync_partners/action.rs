@@ -4,8 +4,11 @@ use crate::context::CoreContext;
 use crate::infra::query::{LibraryQuery, QueryError, QueryResult};
 use crate::infra::sync::NetworkTransport;
 use std::sync::Arc;
+use uuid::Uuid;
 
-use super::output::{DeviceDebugInfo, SyncPartnerInfo, SyncPartnersDebugInfo};
+use super::output::{
+	DeviceDebugInfo, SyncPartnerExclusion, SyncPartnerInfo, SyncPartnersDebugInfo,
+};
 use super::{GetSyncPartnersInput, GetSyncPartnersOutput};
 
 /// Get computed sync partners for the current library
@@ -86,6 +89,19 @@ impl LibraryQuery for GetSyncPartners {
 		let mut paired_count = 0;
 		let mut device_details = Vec::new();
 
+		let local_device_id = context.device_manager.device_id().ok();
+		let excluded_reason = |uuid: Uuid, in_library: bool, sync_enabled: bool, has_node_id| {
+			if partner_uuids.contains(&uuid) {
+				return None;
+			}
+			Some(SyncPartnerExclusion::diagnose(
+				Some(uuid) == local_device_id,
+				in_library,
+				sync_enabled,
+				has_node_id,
+			))
+		};
+
 		if let Some(registry_arc) = device_registry {
 			let registry = registry_arc.read().await;
 
@@ -103,6 +119,33 @@ impl LibraryQuery for GetSyncPartners {
 					sync_enabled: device.sync_enabled,
 					has_node_id,
 					node_id: node_id.map(|id| id.to_string()),
+					excluded_reason: excluded_reason(
+						device.uuid,
+						true,
+						device.sync_enabled,
+						has_node_id,
+					),
+				});
+			}
+
+			// Paired devices that never joined this library
+			for info in registry.get_paired_devices() {
+				if all_devices.iter().any(|d| d.uuid == info.device_id) {
+					continue;
+				}
+				let node_id = registry.get_node_id_for_device(info.device_id);
+				device_details.push(DeviceDebugInfo {
+					uuid: info.device_id,
+					name: info.device_name.clone(),
+					sync_enabled: false,
+					has_node_id: node_id.is_some(),
+					node_id: node_id.map(|id| id.to_string()),
+					excluded_reason: excluded_reason(
+						info.device_id,
+						false,
+						false,
+						node_id.is_some(),
+					),
 				});
 			}
 		}
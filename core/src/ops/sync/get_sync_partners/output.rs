@@ -33,4 +33,83 @@ pub struct DeviceDebugInfo {
 	pub sync_enabled: bool,
 	pub has_node_id: bool,
 	pub node_id: Option<String>,
+	/// Why this device is not a sync partner, None when it is one
+	pub excluded_reason: Option<SyncPartnerExclusion>,
+}
+
+/// The first condition that keeps a device out of the sync partners
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum SyncPartnerExclusion {
+	/// The device is this one
+	LocalDevice,
+	/// Paired, but not a member of this library
+	NotInLibrary,
+	/// A library member with sync turned off
+	SyncDisabled,
+	/// Not paired, so the network layer has no node to reach it at
+	NoNodeId,
+	/// Paired and syncing, but not connected right now
+	NotConnected,
+}
+
+impl SyncPartnerExclusion {
+	/// Diagnose a device that is not in the partner list
+	///
+	/// Checks run in the order the partner computation applies them, so the reason
+	/// is the one to fix first.
+	pub fn diagnose(
+		is_local: bool,
+		in_library: bool,
+		sync_enabled: bool,
+		has_node_id: bool,
+	) -> Self {
+		if is_local {
+			Self::LocalDevice
+		} else if !in_library {
+			Self::NotInLibrary
+		} else if !sync_enabled {
+			Self::SyncDisabled
+		} else if !has_node_id {
+			Self::NoNodeId
+		} else {
+			Self::NotConnected
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_each_exclusion_is_diagnosed() {
+		use SyncPartnerExclusion::*;
+
+		assert_eq!(
+			SyncPartnerExclusion::diagnose(true, true, true, true),
+			LocalDevice
+		);
+		assert_eq!(
+			SyncPartnerExclusion::diagnose(false, false, false, true),
+			NotInLibrary
+		);
+		assert_eq!(
+			SyncPartnerExclusion::diagnose(false, true, false, true),
+			SyncDisabled
+		);
+		assert_eq!(
+			SyncPartnerExclusion::diagnose(false, true, true, false),
+			NoNodeId
+		);
+		assert_eq!(
+			SyncPartnerExclusion::diagnose(false, true, true, true),
+			NotConnected
+		);
+
+		// Sync being off is reported before the missing pairing
+		assert_eq!(
+			SyncPartnerExclusion::diagnose(false, true, false, false),
+			SyncDisabled
+		);
+	}
 }
@@ -0,0 +1,39 @@
+//! List syncable models query
+
+use crate::context::CoreContext;
+use crate::infra::query::{CoreQuery, QueryResult};
+use crate::infra::sync::list_registered_models;
+use std::sync::Arc;
+
+use super::{SyncModelsInput, SyncModelsOutput};
+
+/// List every model registered with the sync system
+///
+/// Reads the inventory-backed registry, so the result reflects what this build
+/// will actually sync. Useful for debugging sync ordering.
+pub struct SyncModelsQuery;
+
+impl CoreQuery for SyncModelsQuery {
+	type Input = SyncModelsInput;
+	type Output = SyncModelsOutput;
+
+	fn from_input(_input: Self::Input) -> QueryResult<Self> {
+		Ok(Self)
+	}
+
+	async fn execute(
+		self,
+		_context: Arc<CoreContext>,
+		_session: crate::infra::api::SessionContext,
+	) -> QueryResult<Self::Output> {
+		let models = list_registered_models()
+			.await
+			.into_iter()
+			.map(Into::into)
+			.collect();
+
+		Ok(SyncModelsOutput { models })
+	}
+}
+
+crate::register_core_query!(SyncModelsQuery, "sync.models");
@@ -0,0 +1,7 @@
+//! Input for listing syncable models
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SyncModelsInput {}
@@ -0,0 +1,9 @@
+//! List syncable models operation
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::SyncModelsQuery;
+pub use input::SyncModelsInput;
+pub use output::{SyncModelInfo, SyncModelKind, SyncModelsOutput};
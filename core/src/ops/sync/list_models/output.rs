@@ -0,0 +1,46 @@
+//! Output for listing syncable models
+
+use crate::infra::sync::RegisteredSyncModel;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SyncModelsOutput {
+	/// Registered models, sorted by name
+	pub models: Vec<SyncModelInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SyncModelInfo {
+	/// The model's `SYNC_MODEL` name
+	pub sync_model: String,
+	pub table_name: String,
+	pub kind: SyncModelKind,
+	/// Models that must be synced before this one
+	pub depends_on: Vec<String>,
+}
+
+/// How a model is replicated between devices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncModelKind {
+	/// State-based sync, only the owning device writes
+	DeviceOwned,
+	/// Log-based sync with HLC ordering
+	Shared,
+}
+
+impl From<RegisteredSyncModel> for SyncModelInfo {
+	fn from(model: RegisteredSyncModel) -> Self {
+		Self {
+			sync_model: model.model_type.to_string(),
+			table_name: model.table_name.to_string(),
+			kind: if model.is_device_owned {
+				SyncModelKind::DeviceOwned
+			} else {
+				SyncModelKind::Shared
+			},
+			depends_on: model.depends_on.iter().map(|dep| dep.to_string()).collect(),
+		}
+	}
+}
@@ -1,6 +1,11 @@
 //! Sync operations
 
 pub mod get_activity;
+pub mod get_backfill_status;
 pub mod get_event_log;
 pub mod get_metrics;
 pub mod get_sync_partners;
+pub mod list_models;
+pub mod pause;
+pub mod resume;
+pub mod trigger;
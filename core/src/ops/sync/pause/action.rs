@@ -0,0 +1,78 @@
+//! Pause sync for a library without unpairing its devices
+
+use super::{input::PauseLibrarySyncInput, output::PauseLibrarySyncOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, LibraryAction},
+	library::Library,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PauseLibrarySyncAction {
+	input: PauseLibrarySyncInput,
+}
+
+impl LibraryAction for PauseLibrarySyncAction {
+	type Input = PauseLibrarySyncInput;
+	type Output = PauseLibrarySyncOutput;
+
+	fn from_input(input: PauseLibrarySyncInput) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let sync_service = library
+			.sync_service()
+			.ok_or_else(|| ActionError::Internal("Sync service not available".to_string()))?;
+
+		let changed = sync_service
+			.pause()
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to pause sync: {}", e)))?;
+
+		// Keep the pause across restarts
+		library
+			.save_config(&library.config().await)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to save library config: {}", e)))?;
+
+		info!(
+			"Paused sync for library {} (changed={})",
+			library.id(),
+			changed
+		);
+
+		Ok(PauseLibrarySyncOutput {
+			library_id: library.id(),
+			changed,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"sync.pause"
+	}
+
+	async fn validate(
+		&self,
+		library: &Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		if self.input.library_id != library.id() {
+			return Err(ActionError::Validation {
+				field: "library_id".to_string(),
+				message: "Library does not match the current session".to_string(),
+			});
+		}
+
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+}
+
+crate::register_library_action!(PauseLibrarySyncAction, "sync.pause");
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PauseLibrarySyncInput {
+	/// Library whose sync should be paused
+	pub library_id: Uuid,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PauseLibrarySyncOutput {
+	pub library_id: Uuid,
+	/// False when sync was already paused
+	pub changed: bool,
+}
@@ -0,0 +1,81 @@
+//! Resume sync for a paused library
+//!
+//! Changes buffered while paused are applied and broadcast, and watermarks are
+//! exchanged with connected partners so nothing made while paused is missed.
+
+use super::{input::ResumeLibrarySyncInput, output::ResumeLibrarySyncOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, LibraryAction},
+	library::Library,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumeLibrarySyncAction {
+	input: ResumeLibrarySyncInput,
+}
+
+impl LibraryAction for ResumeLibrarySyncAction {
+	type Input = ResumeLibrarySyncInput;
+	type Output = ResumeLibrarySyncOutput;
+
+	fn from_input(input: ResumeLibrarySyncInput) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let sync_service = library
+			.sync_service()
+			.ok_or_else(|| ActionError::Internal("Sync service not available".to_string()))?;
+
+		let changed = sync_service
+			.resume()
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to resume sync: {}", e)))?;
+
+		// Keep the pause across restarts
+		library
+			.save_config(&library.config().await)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to save library config: {}", e)))?;
+
+		info!(
+			"Resumed sync for library {} (changed={})",
+			library.id(),
+			changed
+		);
+
+		Ok(ResumeLibrarySyncOutput {
+			library_id: library.id(),
+			changed,
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"sync.resume"
+	}
+
+	async fn validate(
+		&self,
+		library: &Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		if self.input.library_id != library.id() {
+			return Err(ActionError::Validation {
+				field: "library_id".to_string(),
+				message: "Library does not match the current session".to_string(),
+			});
+		}
+
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+}
+
+crate::register_library_action!(ResumeLibrarySyncAction, "sync.resume");
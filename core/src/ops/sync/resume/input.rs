@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ResumeLibrarySyncInput {
+	/// Library whose sync should be resumed
+	pub library_id: Uuid,
+}
@@ -0,0 +1,9 @@
+//! Resume real-time sync for a library
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::*;
+pub use input::*;
+pub use output::*;
@@ -0,0 +1,80 @@
+//! Trigger a sync pass for a library without waiting for the sync loop
+//!
+//! Mostly a debugging aid: queued messages are flushed to the partners and
+//! their changes pulled right away. Safe to call while the sync loop runs, as a
+//! partner that already has a pass in flight is skipped.
+
+use super::{input::TriggerSyncInput, output::TriggerSyncOutput};
+use crate::{
+	context::CoreContext,
+	infra::action::{error::ActionError, LibraryAction},
+	library::Library,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerSyncAction {
+	input: TriggerSyncInput,
+}
+
+impl LibraryAction for TriggerSyncAction {
+	type Input = TriggerSyncInput;
+	type Output = TriggerSyncOutput;
+
+	fn from_input(input: TriggerSyncInput) -> Result<Self, String> {
+		Ok(Self { input })
+	}
+
+	async fn execute(
+		self,
+		library: Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<Self::Output, ActionError> {
+		let sync_service = library
+			.sync_service()
+			.ok_or_else(|| ActionError::Internal("Sync service not available".to_string()))?;
+
+		let summary = sync_service
+			.trigger_sync(self.input.peer)
+			.await
+			.map_err(|e| ActionError::Internal(format!("Failed to trigger sync: {}", e)))?;
+
+		info!(
+			"Triggered sync for library {} ({} peers, {} pushed, {} pulled)",
+			library.id(),
+			summary.peers.len(),
+			summary.records_pushed(),
+			summary.records_pulled()
+		);
+
+		Ok(TriggerSyncOutput {
+			library_id: library.id(),
+			records_pushed: summary.records_pushed(),
+			records_pulled: summary.records_pulled(),
+			peers: summary.peers.into_iter().map(Into::into).collect(),
+		})
+	}
+
+	fn action_kind(&self) -> &'static str {
+		"sync.trigger"
+	}
+
+	async fn validate(
+		&self,
+		library: &Arc<Library>,
+		_context: Arc<CoreContext>,
+	) -> Result<crate::infra::action::ValidationResult, ActionError> {
+		if self.input.library_id != library.id() {
+			return Err(ActionError::Validation {
+				field: "library_id".to_string(),
+				message: "Library does not match the current session".to_string(),
+			});
+		}
+
+		Ok(crate::infra::action::ValidationResult::Success { metadata: None })
+	}
+}
+
+crate::register_library_action!(TriggerSyncAction, "sync.trigger");
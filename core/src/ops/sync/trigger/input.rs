@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TriggerSyncInput {
+	/// Library to sync
+	pub library_id: Uuid,
+	/// Only sync with this partner; all connected partners when omitted
+	#[serde(default)]
+	pub peer: Option<Uuid>,
+}
@@ -0,0 +1,9 @@
+//! Run a sync pass for a library immediately
+
+pub mod action;
+pub mod input;
+pub mod output;
+
+pub use action::*;
+pub use input::*;
+pub use output::*;
@@ -0,0 +1,42 @@
+use crate::service::sync::{PeerSyncPass, SyncPassStatus};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TriggerSyncOutput {
+	pub library_id: Uuid,
+	/// Change records delivered across all partners
+	pub records_pushed: u64,
+	/// Records received across all partners
+	pub records_pulled: u64,
+	pub peers: Vec<TriggeredPeerSync>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TriggeredPeerSync {
+	pub device_id: Uuid,
+	pub records_pushed: u64,
+	pub records_pulled: u64,
+	/// False when another pass was already running against this partner
+	pub ran: bool,
+	/// Why the pass failed, if it did
+	pub error: Option<String>,
+}
+
+impl From<PeerSyncPass> for TriggeredPeerSync {
+	fn from(pass: PeerSyncPass) -> Self {
+		let (ran, error) = match pass.status {
+			SyncPassStatus::Completed => (true, None),
+			SyncPassStatus::AlreadyRunning => (false, None),
+			SyncPassStatus::Failed(error) => (true, Some(error)),
+		};
+		Self {
+			device_id: pass.device_id,
+			records_pushed: pass.pushed,
+			records_pulled: pass.pulled,
+			ran,
+			error,
+		}
+	}
+}
@@ -178,15 +178,22 @@ impl Services {
 		device_manager: std::sync::Arc<crate::device::DeviceManager>,
 		key_manager: std::sync::Arc<crate::crypto::key_manager::KeyManager>,
 		data_dir: impl AsRef<std::path::Path>,
+		discovery_config: crate::config::NetworkDiscoveryConfig,
 	) -> Result<()> {
-		use crate::service::network::{utils::logging::ConsoleLogger, NetworkingService};
+		use crate::service::network::{
+			core::CommandChannelConfig, utils::logging::ConsoleLogger, NetworkingService,
+		};
 
 		info!("Initializing networking service");
 		let logger = std::sync::Arc::new(ConsoleLogger);
-		let networking_service =
+		let mut networking_service =
 			NetworkingService::new(device_manager, key_manager, data_dir, logger)
 				.await
 				.map_err(|e| anyhow::anyhow!("Failed to create networking service: {}", e))?;
+		networking_service.set_command_channel_config(CommandChannelConfig::from_network_config(
+			&discovery_config,
+		));
+		networking_service.set_discovery_config(discovery_config);
 
 		self.networking = Some(Arc::new(networking_service));
 		Ok(())
@@ -0,0 +1,134 @@
+//! Relay and discovery overrides for the Iroh endpoint
+//!
+//! Turns the serialized [`NetworkDiscoveryConfig`] into the Iroh types the
+//! endpoint builder takes, so a bad URL or node ID fails networking startup
+//! instead of leaving the endpoint silently unreachable.
+
+use crate::config::{NetworkDiscoveryConfig, RelayConfig, StaticPeerAddr};
+use crate::service::network::{NetworkingError, Result};
+use iroh::discovery::static_provider::StaticProvider;
+use iroh::{EndpointAddr, EndpointId, RelayMap, RelayMode, RelayUrl};
+
+/// Relay mode for the configured relay
+pub(crate) fn relay_mode(relay: &RelayConfig) -> Result<RelayMode> {
+	match relay {
+		RelayConfig::Default => Ok(RelayMode::Default),
+		RelayConfig::Disabled => Ok(RelayMode::Disabled),
+		RelayConfig::Custom(url) => Ok(RelayMode::Custom(RelayMap::from(parse_relay_url(url)?))),
+	}
+}
+
+/// Relay mode with the trusted relays added to the configured relay
+///
+/// The configured relay map, Iroh's default relays included, is kept and the
+/// trusted relays are added to it. With relays disabled, or no trusted relays to
+/// add, this is [`relay_mode`].
+pub(crate) fn relay_mode_with_trusted(
+	relay: &RelayConfig,
+	trusted: Vec<RelayUrl>,
+) -> Result<RelayMode> {
+	if matches!(relay, RelayConfig::Disabled) || trusted.is_empty() {
+		return relay_mode(relay);
+	}
+	let mut urls: Vec<RelayUrl> = relay_mode(relay)?.relay_map().urls();
+	for url in trusted {
+		if !urls.contains(&url) {
+			urls.push(url);
+		}
+	}
+	Ok(RelayMode::Custom(RelayMap::from_iter(urls)))
+}
+
+/// Trusted relays listed in the config
+pub(crate) fn trusted_relay_urls(config: &NetworkDiscoveryConfig) -> Result<Vec<RelayUrl>> {
+	config
+		.trusted_relays
+		.iter()
+		.map(|url| parse_relay_url(url))
+		.collect()
+}
+
+/// Endpoint address for a peer listed in the config
+pub(crate) fn static_endpoint_addr(peer: &StaticPeerAddr) -> Result<EndpointAddr> {
+	let id = peer.node_id.parse::<EndpointId>().map_err(|e| {
+		NetworkingError::Transport(format!("Invalid static node ID {}: {}", peer.node_id, e))
+	})?;
+
+	let mut addr = EndpointAddr::new(id);
+	for socket_addr in &peer.addrs {
+		addr = addr.with_ip_addr(*socket_addr);
+	}
+	if let Some(relay_url) = &peer.relay_url {
+		addr = addr.with_relay_url(parse_relay_url(relay_url)?);
+	}
+	Ok(addr)
+}
+
+/// Seed a static provider with every peer listed in the config
+pub(crate) fn add_static_addrs(
+	provider: &StaticProvider,
+	config: &NetworkDiscoveryConfig,
+) -> Result<()> {
+	for peer in &config.static_addrs {
+		provider.add_endpoint_info(static_endpoint_addr(peer)?);
+	}
+	Ok(())
+}
+
+fn parse_relay_url(url: &str) -> Result<RelayUrl> {
+	url.parse::<RelayUrl>()
+		.map_err(|e| NetworkingError::Transport(format!("Invalid relay URL {}: {}", url, e)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_static_peer_addr_parses() {
+		let node_id = iroh::SecretKey::from_bytes(&[9u8; 32]).public();
+		let peer = StaticPeerAddr {
+			node_id: node_id.to_string(),
+			addrs: vec!["127.0.0.1:4433".parse().unwrap()],
+			relay_url: Some("http://localhost:3340".to_string()),
+		};
+
+		let addr = static_endpoint_addr(&peer).unwrap();
+		assert_eq!(addr.id, node_id);
+		assert_eq!(addr.ip_addrs().count(), 1);
+		assert_eq!(addr.relay_urls().count(), 1);
+
+		let bad = StaticPeerAddr {
+			node_id: "not-a-node".to_string(),
+			..peer
+		};
+		assert!(static_endpoint_addr(&bad).is_err());
+		assert!(relay_mode(&RelayConfig::Custom("not a url".to_string())).is_err());
+	}
+
+	#[test]
+	fn test_trusted_relays_join_the_relay_map() {
+		let trusted: RelayUrl = "http://localhost:3340".parse().unwrap();
+		// The default relays stay in the map next to the trusted one
+		let RelayMode::Custom(map) =
+			relay_mode_with_trusted(&RelayConfig::Default, vec![trusted.clone()]).unwrap()
+		else {
+			panic!("expected a custom relay map");
+		};
+		let urls: Vec<RelayUrl> = map.urls();
+		let defaults: Vec<RelayUrl> = RelayMode::Default.relay_map().urls();
+		assert!(!defaults.is_empty());
+		assert_eq!(urls.len(), defaults.len() + 1);
+		assert!(defaults.iter().all(|url| urls.contains(url)));
+		assert!(urls.contains(&trusted));
+		assert!(matches!(
+			relay_mode_with_trusted(&RelayConfig::Default, Vec::new()).unwrap(),
+			RelayMode::Default
+		));
+
+		assert!(matches!(
+			relay_mode_with_trusted(&RelayConfig::Disabled, vec![trusted]).unwrap(),
+			RelayMode::Disabled
+		));
+	}
+}
@@ -1,12 +1,10 @@
 //! Networking event loop for handling Iroh connections and messages
 
+use crate::config::NetworkDiscoveryConfig;
 use crate::service::network::{
-	core::{
-		NetworkEvent, FILE_TRANSFER_ALPN, JOB_ACTIVITY_ALPN, MESSAGING_ALPN, PAIRING_ALPN,
-		SYNC_ALPN,
-	},
+	core::{NetworkEvent, PAIRING_ALPN},
 	device::DeviceRegistry,
-	protocol::ProtocolRegistry,
+	protocol::{Protocol, ProtocolRegistry},
 	utils::{logging::NetworkLogger, NetworkIdentity},
 	NetworkingError, Result,
 };
@@ -14,6 +12,7 @@ use iroh::endpoint::Connection;
 use iroh::EndpointId;
 use iroh::{Endpoint, EndpointAddr};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::{broadcast, mpsc, RwLock};
 use uuid::Uuid;
@@ -39,12 +38,12 @@ pub enum EventLoopCommand {
 	// Message sending
 	SendMessage {
 		device_id: Uuid,
-		protocol: String,
+		protocol: Protocol,
 		data: Vec<u8>,
 	},
 	SendMessageToNode {
 		node_id: EndpointId,
-		protocol: String,
+		protocol: Protocol,
 		data: Vec<u8>,
 	},
 
@@ -52,6 +51,137 @@ pub enum EventLoopCommand {
 	Shutdown,
 }
 
+/// Default capacity of the event loop command channel
+pub const DEFAULT_COMMAND_CHANNEL_CAPACITY: usize = 1024;
+
+/// Default time to wait for capacity when the command channel is full
+pub const DEFAULT_COMMAND_SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What `CommandSender::send` does when the command channel is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandOverflowPolicy {
+	/// Drop the command and log a warning
+	DropWithWarning,
+	/// Wait for capacity, giving up after the timeout
+	AwaitWithTimeout(Duration),
+}
+
+/// Configuration for the event loop command channel
+#[derive(Debug, Clone, Copy)]
+pub struct CommandChannelConfig {
+	/// Maximum number of queued commands
+	pub capacity: usize,
+	/// Behaviour of `CommandSender::send` when the channel is full
+	pub overflow: CommandOverflowPolicy,
+}
+
+impl Default for CommandChannelConfig {
+	fn default() -> Self {
+		Self {
+			capacity: DEFAULT_COMMAND_CHANNEL_CAPACITY,
+			overflow: CommandOverflowPolicy::AwaitWithTimeout(DEFAULT_COMMAND_SEND_TIMEOUT),
+		}
+	}
+}
+
+impl CommandChannelConfig {
+	/// Channel settings from the networking config, keeping the defaults for unset fields
+	pub fn from_network_config(config: &NetworkDiscoveryConfig) -> Self {
+		let defaults = Self::default();
+		let overflow = match config.command_send_timeout_ms {
+			Some(0) => CommandOverflowPolicy::DropWithWarning,
+			Some(ms) => CommandOverflowPolicy::AwaitWithTimeout(Duration::from_millis(ms)),
+			None => defaults.overflow,
+		};
+		Self {
+			capacity: config.command_channel_capacity.unwrap_or(defaults.capacity),
+			overflow,
+		}
+	}
+}
+
+/// Errors returned when a command cannot be delivered to the event loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CommandSendError {
+	#[error("Event loop command channel is full")]
+	Full,
+	#[error("Event loop is not running")]
+	Closed,
+}
+
+impl From<CommandSendError> for NetworkingError {
+	fn from(err: CommandSendError) -> Self {
+		match err {
+			CommandSendError::Full => NetworkingError::Timeout(err.to_string()),
+			CommandSendError::Closed => NetworkingError::ConnectionFailed(err.to_string()),
+		}
+	}
+}
+
+/// Bounded sender for event loop commands
+///
+/// Callers that must never block (state machines, connection watchers) use
+/// `try_send`; everyone else uses `send`, which applies the configured
+/// overflow policy once the fast path finds the channel full.
+#[derive(Debug, Clone)]
+pub struct CommandSender {
+	inner: mpsc::Sender<EventLoopCommand>,
+	overflow: CommandOverflowPolicy,
+}
+
+impl CommandSender {
+	/// Create a bounded command channel
+	pub fn channel(config: CommandChannelConfig) -> (Self, mpsc::Receiver<EventLoopCommand>) {
+		let (tx, rx) = mpsc::channel(config.capacity.max(1));
+		(
+			Self {
+				inner: tx,
+				overflow: config.overflow,
+			},
+			rx,
+		)
+	}
+
+	/// Queue a command without waiting
+	pub fn try_send(&self, command: EventLoopCommand) -> std::result::Result<(), CommandSendError> {
+		self.inner.try_send(command).map_err(|e| match e {
+			mpsc::error::TrySendError::Full(_) => CommandSendError::Full,
+			mpsc::error::TrySendError::Closed(_) => CommandSendError::Closed,
+		})
+	}
+
+	/// Queue a command, falling back to the overflow policy if the channel is full
+	pub async fn send(
+		&self,
+		command: EventLoopCommand,
+	) -> std::result::Result<(), CommandSendError> {
+		let command = match self.inner.try_send(command) {
+			Ok(()) => return Ok(()),
+			Err(mpsc::error::TrySendError::Closed(_)) => return Err(CommandSendError::Closed),
+			Err(mpsc::error::TrySendError::Full(command)) => command,
+		};
+
+		match self.overflow {
+			CommandOverflowPolicy::DropWithWarning => {
+				tracing::warn!("Event loop command channel full, dropping {:?}", command);
+				Err(CommandSendError::Full)
+			}
+			CommandOverflowPolicy::AwaitWithTimeout(timeout) => {
+				match tokio::time::timeout(timeout, self.inner.send(command)).await {
+					Ok(Ok(())) => Ok(()),
+					Ok(Err(_)) => Err(CommandSendError::Closed),
+					Err(_) => Err(CommandSendError::Full),
+				}
+			}
+		}
+	}
+
+	/// Whether the event loop has stopped receiving commands
+	pub fn is_closed(&self) -> bool {
+		self.inner.is_closed()
+	}
+}
+
 /// Networking event loop that processes Iroh connections
 pub struct NetworkingEventLoop {
 	/// Iroh endpoint
@@ -67,10 +197,10 @@ pub struct NetworkingEventLoop {
 	event_sender: broadcast::Sender<NetworkEvent>,
 
 	/// Command receiver
-	command_rx: mpsc::UnboundedReceiver<EventLoopCommand>,
+	command_rx: mpsc::Receiver<EventLoopCommand>,
 
 	/// Command sender (for cloning)
-	command_tx: mpsc::UnboundedSender<EventLoopCommand>,
+	command_tx: CommandSender,
 
 	/// Shutdown receiver
 	shutdown_rx: mpsc::UnboundedReceiver<()>,
@@ -103,8 +233,9 @@ impl NetworkingEventLoop {
 			RwLock<std::collections::HashMap<(EndpointId, Vec<u8>), Connection>>,
 		>,
 		logger: Arc<dyn NetworkLogger>,
+		command_config: CommandChannelConfig,
 	) -> Self {
-		let (command_tx, command_rx) = mpsc::unbounded_channel();
+		let (command_tx, command_rx) = CommandSender::channel(command_config);
 		let (shutdown_tx, shutdown_rx) = mpsc::unbounded_channel();
 
 		Self {
@@ -124,7 +255,7 @@ impl NetworkingEventLoop {
 	}
 
 	/// Get the command sender for sending commands to the event loop
-	pub fn command_sender(&self) -> mpsc::UnboundedSender<EventLoopCommand> {
+	pub fn command_sender(&self) -> CommandSender {
 		self.command_tx.clone()
 	}
 
@@ -247,13 +378,21 @@ impl NetworkingEventLoop {
 				.await;
 
 			// Mark device as connected immediately when connection arrives
+			// We are running on the event loop itself, so never wait on our own channel
 			if let Some(device_id) = paired_device_id {
-				let _ = self
+				if let Err(e) = self
 					.command_tx
-					.send(EventLoopCommand::ConnectionEstablished {
+					.try_send(EventLoopCommand::ConnectionEstablished {
 						device_id,
 						node_id: remote_node_id,
-					});
+					}) {
+					self.logger
+						.warn(&format!(
+							"Failed to queue ConnectionEstablished for {}: {}",
+							device_id, e
+						))
+						.await;
+				}
 			}
 		}
 
@@ -311,7 +450,7 @@ impl NetworkingEventLoop {
 		protocol_registry: Arc<RwLock<ProtocolRegistry>>,
 		device_registry: Arc<RwLock<DeviceRegistry>>,
 		event_sender: broadcast::Sender<NetworkEvent>,
-		command_sender: mpsc::UnboundedSender<EventLoopCommand>,
+		command_sender: CommandSender,
 		remote_node_id: EndpointId,
 		logger: Arc<dyn NetworkLogger>,
 	) {
@@ -345,58 +484,13 @@ impl NetworkingEventLoop {
 							let _ = command_sender.send(EventLoopCommand::ConnectionEstablished {
 								device_id,
 								node_id: remote_node_id,
-							});
+							}).await;
 						}
 					}
 
 					// Route to handler based on ALPN
 					let alpn_bytes = conn.alpn().to_vec();
-
-					if alpn_bytes == MESSAGING_ALPN {
-						let registry = protocol_registry.read().await;
-						if let Some(handler) = registry.get_handler("messaging") {
-							logger.info("Routing to messaging handler (ALPN match)").await;
-							handler
-								.handle_stream(Box::new(send), Box::new(recv), remote_node_id)
-								.await;
-						}
-						continue;
-					} else if alpn_bytes == PAIRING_ALPN {
-						let registry = protocol_registry.read().await;
-						if let Some(handler) = registry.get_handler("pairing") {
-							logger.info("Routing to pairing handler (ALPN match)").await;
-							handler
-								.handle_stream(Box::new(send), Box::new(recv), remote_node_id)
-								.await;
-						}
-						continue;
-					} else if alpn_bytes == FILE_TRANSFER_ALPN {
-						let registry = protocol_registry.read().await;
-						if let Some(handler) = registry.get_handler("file_transfer") {
-							logger.info("Routing to file_transfer handler (ALPN match)").await;
-							handler
-								.handle_stream(Box::new(send), Box::new(recv), remote_node_id)
-								.await;
-						}
-						continue;
-					} else if alpn_bytes == SYNC_ALPN {
-						let registry = protocol_registry.read().await;
-						if let Some(handler) = registry.get_handler("sync") {
-							handler
-								.handle_stream(Box::new(send), Box::new(recv), remote_node_id)
-								.await;
-						}
-						continue;
-					} else if alpn_bytes == JOB_ACTIVITY_ALPN {
-						let registry = protocol_registry.read().await;
-						if let Some(handler) = registry.get_handler("job_activity") {
-							logger.info("Routing to job_activity handler (ALPN match)").await;
-							handler
-								.handle_stream(Box::new(send), Box::new(recv), remote_node_id)
-								.await;
-						}
-						continue;
-					} else {
+					let Some(protocol) = Protocol::from_alpn(&alpn_bytes) else {
 						logger
 							.warn(&format!(
 								"Unknown ALPN: {:?}",
@@ -404,7 +498,18 @@ impl NetworkingEventLoop {
 							))
 							.await;
 						continue;
+					};
+
+					let registry = protocol_registry.read().await;
+					if let Some(handler) = registry.get_handler(protocol) {
+						logger
+							.debug(&format!("Routing to {} handler (ALPN match)", protocol))
+							.await;
+						handler
+							.handle_stream(Box::new(send), Box::new(recv), remote_node_id)
+							.await;
 					}
+					continue;
 				}
 					Err(e) => {
 						// Check if the QUIC connection itself is closed
@@ -419,7 +524,7 @@ impl NetworkingEventLoop {
 									device_id,
 									node_id: remote_node_id,
 									reason: "Connection closed".to_string(),
-								});
+								}).await;
 							}
 							break;
 						} else {
@@ -441,18 +546,10 @@ impl NetworkingEventLoop {
 							let registry = protocol_registry.read().await;
 
 							// Route based on ALPN
-							if alpn_bytes == SYNC_ALPN {
-								if let Some(handler) = registry.get_handler("sync") {
-									logger.info("Directing unidirectional stream to sync handler").await;
-									handler.handle_stream(
-										Box::new(tokio::io::empty()), // No send stream for unidirectional
-										Box::new(recv),
-										remote_node_id,
-									).await;
-								}
-							} else if alpn_bytes == FILE_TRANSFER_ALPN {
-								if let Some(handler) = registry.get_handler("file_transfer") {
-									logger.debug("Directing unidirectional stream to file transfer handler").await;
+							let protocol = Protocol::from_alpn(&alpn_bytes);
+							if let Some(protocol @ (Protocol::Sync | Protocol::FileTransfer)) = protocol {
+								if let Some(handler) = registry.get_handler(protocol) {
+									logger.debug(&format!("Directing unidirectional stream to {} handler", protocol)).await;
 									handler.handle_stream(
 										Box::new(tokio::io::empty()), // No send stream for unidirectional
 										Box::new(recv),
@@ -476,7 +573,7 @@ impl NetworkingEventLoop {
 									device_id,
 									node_id: remote_node_id,
 									reason: "Connection closed".to_string(),
-								});
+								}).await;
 							}
 							break;
 						} else {
@@ -522,7 +619,7 @@ impl NetworkingEventLoop {
 
 				if let Some(node_id) = node_id {
 					// Send to node
-					self.send_to_node(node_id, &protocol, data).await;
+					self.send_to_node(node_id, protocol, data).await;
 				} else {
 					self.logger
 						.warn(&format!("No node ID found for device {}", device_id))
@@ -535,7 +632,7 @@ impl NetworkingEventLoop {
 				protocol,
 				data,
 			} => {
-				self.send_to_node(node_id, &protocol, data).await;
+				self.send_to_node(node_id, protocol, data).await;
 			}
 
 			EventLoopCommand::ConnectionLost {
@@ -700,7 +797,7 @@ impl NetworkingEventLoop {
 	}
 
 	/// Send a message to a specific node
-	async fn send_to_node(&self, node_id: EndpointId, protocol: &str, data: Vec<u8>) {
+	async fn send_to_node(&self, node_id: EndpointId, protocol: Protocol, data: Vec<u8>) {
 		self.logger
 			.debug(&format!(
 				"Sending {} message to {} ({} bytes)",
@@ -711,24 +808,14 @@ impl NetworkingEventLoop {
 			.await;
 
 		// Determine ALPN based on protocol
-		let alpn = match protocol {
-			"pairing" => PAIRING_ALPN,
-			"file_transfer" => {
-				self.logger
-					.debug(&format!(
-						"Using ALPN: {:?}",
-						String::from_utf8_lossy(FILE_TRANSFER_ALPN)
-					))
-					.await;
-				FILE_TRANSFER_ALPN
-			}
-			"messaging" => MESSAGING_ALPN,
-			_ => {
-				self.logger
-					.error(&format!("Unknown protocol: {}", protocol))
-					.await;
-				return;
-			}
+		let Some(alpn) = protocol.alpn() else {
+			self.logger
+				.error(&format!(
+					"Protocol {} has no connections of its own",
+					protocol
+				))
+				.await;
+			return;
 		};
 
 		// Create node address (Iroh will use existing connection if available)
@@ -763,12 +850,12 @@ impl NetworkingEventLoop {
 
 				// Open appropriate stream based on protocol
 				match protocol {
-					"pairing" | "messaging" => {
+					Protocol::Pairing | Protocol::Messaging => {
 						// Bidirectional stream
 						match conn.open_bi().await {
 							Ok((mut send, _recv)) => {
 								// For pairing, send with length prefix like send_pairing_message_to_node does
-								if protocol == "pairing" {
+								if protocol == Protocol::Pairing {
 									self.logger
 										.info(&format!(
 											"Sending pairing message to {} ({} bytes)",
@@ -826,7 +913,7 @@ impl NetworkingEventLoop {
 							}
 						}
 					}
-					"file_transfer" => {
+					Protocol::FileTransfer => {
 						// Unidirectional stream
 						self.logger
 							.debug(&format!("Opening unidirectional stream to {}", node_id))
@@ -983,3 +1070,101 @@ impl NetworkingEventLoop {
 		.await;
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn config(capacity: usize, overflow: CommandOverflowPolicy) -> CommandChannelConfig {
+		CommandChannelConfig { capacity, overflow }
+	}
+
+	#[test]
+	fn test_config_from_network_config() {
+		let defaults = CommandChannelConfig::default();
+		let unset = CommandChannelConfig::from_network_config(&NetworkDiscoveryConfig::default());
+		assert_eq!(unset.capacity, defaults.capacity);
+		assert_eq!(unset.overflow, defaults.overflow);
+
+		let set = CommandChannelConfig::from_network_config(&NetworkDiscoveryConfig {
+			command_channel_capacity: Some(16),
+			command_send_timeout_ms: Some(250),
+			..NetworkDiscoveryConfig::default()
+		});
+		assert_eq!(set.capacity, 16);
+		assert_eq!(
+			set.overflow,
+			CommandOverflowPolicy::AwaitWithTimeout(Duration::from_millis(250))
+		);
+
+		let dropping = CommandChannelConfig::from_network_config(&NetworkDiscoveryConfig {
+			command_send_timeout_ms: Some(0),
+			..NetworkDiscoveryConfig::default()
+		});
+		assert_eq!(dropping.overflow, CommandOverflowPolicy::DropWithWarning);
+	}
+
+	#[tokio::test]
+	async fn test_full_channel_drop_with_warning() {
+		let (sender, _rx) =
+			CommandSender::channel(config(1, CommandOverflowPolicy::DropWithWarning));
+
+		sender.send(EventLoopCommand::Shutdown).await.unwrap();
+		assert_eq!(
+			sender.send(EventLoopCommand::Shutdown).await,
+			Err(CommandSendError::Full)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_full_channel_await_times_out() {
+		let (sender, _rx) = CommandSender::channel(config(
+			1,
+			CommandOverflowPolicy::AwaitWithTimeout(Duration::from_millis(20)),
+		));
+
+		sender.try_send(EventLoopCommand::Shutdown).unwrap();
+		assert_eq!(
+			sender.try_send(EventLoopCommand::Shutdown),
+			Err(CommandSendError::Full)
+		);
+		assert_eq!(
+			sender.send(EventLoopCommand::Shutdown).await,
+			Err(CommandSendError::Full)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_await_succeeds_once_capacity_frees() {
+		let (sender, mut rx) = CommandSender::channel(config(
+			1,
+			CommandOverflowPolicy::AwaitWithTimeout(Duration::from_secs(5)),
+		));
+
+		sender.try_send(EventLoopCommand::Shutdown).unwrap();
+		let drain = tokio::spawn(async move {
+			tokio::time::sleep(Duration::from_millis(20)).await;
+			rx.recv().await;
+			rx
+		});
+
+		sender.send(EventLoopCommand::Shutdown).await.unwrap();
+		drain.await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_closed_channel() {
+		let (sender, rx) = CommandSender::channel(CommandChannelConfig::default());
+		drop(rx);
+
+		assert!(sender.is_closed());
+		assert_eq!(
+			sender.try_send(EventLoopCommand::Shutdown),
+			Err(CommandSendError::Closed)
+		);
+		assert_eq!(
+			sender.send(EventLoopCommand::Shutdown).await,
+			Err(CommandSendError::Closed)
+		);
+	}
+}
@@ -1,22 +1,34 @@
 //! Core networking engine with Iroh P2P
 
+pub mod discovery;
 pub mod event_loop;
+pub mod relay_health;
 
+use crate::config::{NetworkDiscoveryConfig, RelayConfig};
 use crate::device::DeviceManager;
 use crate::service::network::{
 	device::{DeviceInfo, DeviceRegistry},
-	protocol::{pairing::PairingProtocolHandler, sync::SyncMultiplexer, ProtocolRegistry},
-	utils::{logging::NetworkLogger, NetworkIdentity},
+	protocol::{
+		pairing::PairingProtocolHandler, sync::SyncMultiplexer, Protocol, ProtocolRegistry,
+	},
+	utils::{
+		logging::NetworkLogger, sweep_idle_connections, NetworkIdentity, SharedConnectionActivity,
+	},
 	NetworkingError, Result,
 };
+use iroh::discovery::static_provider::StaticProvider;
 use iroh::discovery::{dns::DnsDiscovery, mdns::MdnsDiscovery, pkarr::PkarrPublisher, Discovery};
 use iroh::endpoint::Connection;
 use iroh::{Endpoint, EndpointAddr, EndpointId, RelayMode, RelayUrl, Watcher};
+use relay_health::{check_due_relays, relay_map_changes, RelayHealth, RelayHealthTracker};
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, RwLock};
 use uuid::Uuid;
 
-pub use event_loop::{EventLoopCommand, NetworkingEventLoop};
+pub use event_loop::{
+	CommandChannelConfig, CommandOverflowPolicy, CommandSendError, CommandSender, EventLoopCommand,
+	NetworkingEventLoop,
+};
 
 /// Protocol ALPN identifiers
 pub const PAIRING_ALPN: &[u8] = b"spacedrive/pairing/1";
@@ -25,6 +37,9 @@ pub const MESSAGING_ALPN: &[u8] = b"spacedrive/messaging/1";
 pub const SYNC_ALPN: &[u8] = b"spacedrive/sync/1";
 pub const JOB_ACTIVITY_ALPN: &[u8] = b"spacedrive/jobactivity/1";
 
+/// How often a joiner checks whether the initiator has answered
+const JOIN_STATE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
 /// Central networking event types
 #[derive(Debug, Clone)]
 pub enum NetworkEvent {
@@ -92,7 +107,16 @@ pub struct NetworkingService {
 	shutdown_sender: Arc<RwLock<Option<mpsc::UnboundedSender<()>>>>,
 
 	/// Command sender for sending commands to the event loop
-	command_sender: Option<mpsc::UnboundedSender<event_loop::EventLoopCommand>>,
+	command_sender: Option<CommandSender>,
+
+	/// Capacity and overflow behaviour for the event loop command channel
+	command_channel_config: CommandChannelConfig,
+
+	/// Relay and discovery overrides applied when the endpoint is built
+	discovery_config: NetworkDiscoveryConfig,
+
+	/// Peer addresses known without discovery
+	static_addrs: StaticProvider,
 
 	/// Registry for protocol handlers
 	protocol_registry: Arc<RwLock<ProtocolRegistry>>,
@@ -110,9 +134,15 @@ pub struct NetworkingService {
 	/// Nodes that already have connection watchers spawned (to prevent duplicates)
 	watched_nodes: Arc<RwLock<std::collections::HashSet<EndpointId>>>,
 
+	/// Traffic seen on cached connections, shared by the idle sweep and health pings
+	connection_activity: SharedConnectionActivity,
+
 	/// Sync multiplexer for routing sync messages to correct library
 	sync_multiplexer: Arc<SyncMultiplexer>,
 
+	/// Probe results for the trusted relays in the discovery config
+	relay_health: Arc<RwLock<RelayHealthTracker>>,
+
 	/// Logger for networking operations
 	logger: Arc<dyn NetworkLogger>,
 }
@@ -158,12 +188,17 @@ impl NetworkingService {
 			discovery: None,
 			shutdown_sender: Arc::new(RwLock::new(None)),
 			command_sender: None,
+			command_channel_config: CommandChannelConfig::default(),
+			discovery_config: NetworkDiscoveryConfig::default(),
+			static_addrs: StaticProvider::new(),
 			protocol_registry,
 			device_registry,
 			event_sender,
 			active_connections: Arc::new(RwLock::new(std::collections::HashMap::new())),
 			watched_nodes: Arc::new(RwLock::new(std::collections::HashSet::new())),
+			connection_activity: Default::default(),
 			sync_multiplexer,
+			relay_health: Arc::new(RwLock::new(RelayHealthTracker::default())),
 			logger,
 		})
 	}
@@ -189,6 +224,25 @@ impl NetworkingService {
 		registry.set_library_manager(library_manager);
 	}
 
+	/// Configure the event loop command channel
+	///
+	/// Only takes effect if called before `start()`.
+	pub fn set_command_channel_config(&mut self, config: CommandChannelConfig) {
+		self.command_channel_config = config;
+	}
+
+	/// Configure the relay and peer discovery
+	///
+	/// Only takes effect if called before `start()`.
+	pub fn set_discovery_config(&mut self, config: NetworkDiscoveryConfig) {
+		self.discovery_config = config;
+	}
+
+	/// Make a peer reachable by node ID without any discovery service
+	pub fn add_static_addr(&self, addr: EndpointAddr) {
+		self.static_addrs.add_endpoint_info(addr);
+	}
+
 	/// Start the networking service
 	pub async fn start(&mut self) -> Result<()> {
 		// Check if already started
@@ -201,15 +255,32 @@ impl NetworkingService {
 
 		// Create Iroh endpoint with discovery and relay configuration
 		let secret_key = self.identity.to_iroh_secret_key()?;
+		// Probe trusted relays once up front so the endpoint starts with healthy ones
+		let trusted_relays = discovery::trusted_relay_urls(&self.discovery_config)?;
+		if !trusted_relays.is_empty() {
+			*self.relay_health.write().await =
+				RelayHealthTracker::new(trusted_relays, std::time::Instant::now());
+			check_due_relays(&self.relay_health, &reqwest::Client::new(), &self.logger).await;
+		}
+		let relay_mode = discovery::relay_mode_with_trusted(
+			&self.discovery_config.relay,
+			self.relay_health.read().await.usable(),
+		)?;
+		discovery::add_static_addrs(&self.static_addrs, &self.discovery_config)?;
+		let public_discovery = self.discovery_config.public_discovery;
 
 		self.logger
 			.info(&format!(
-				"Creating endpoint with mDNS + pkarr discovery for node {}",
-				self.node_id
+				"Creating endpoint for node {} (relay: {:?}, public discovery: {}, mDNS: {})",
+				self.node_id,
+				self.discovery_config.relay,
+				public_discovery,
+				self.discovery_config.mdns
 			))
 			.await;
 
 		// Create endpoint with combined discovery:
+		// - Static addresses from the config (and added at runtime)
 		// - mDNS for local network discovery
 		// - PkarrPublisher to publish our address to dns.iroh.link (enables remote discovery)
 		// - DnsDiscovery to resolve other nodes from dns.iroh.link
@@ -229,9 +300,8 @@ impl NetworkingService {
 					SYNC_ALPN.to_vec(),
 					JOB_ACTIVITY_ALPN.to_vec(),
 				])
-				.relay_mode(iroh::RelayMode::Default)
-				.discovery(PkarrPublisher::n0_dns())
-				.discovery(DnsDiscovery::n0_dns())
+				.relay_mode(relay_mode.clone())
+				.discovery(self.static_addrs.clone())
 				.bind_addr_v4(std::net::SocketAddrV4::new(
 					std::net::Ipv4Addr::UNSPECIFIED,
 					0,
@@ -242,22 +312,25 @@ impl NetworkingService {
 					0,
 					0,
 				));
+			if public_discovery {
+				builder = builder
+					.discovery(PkarrPublisher::n0_dns())
+					.discovery(DnsDiscovery::n0_dns());
+			}
 			if with_mdns {
 				builder = builder.discovery(MdnsDiscovery::builder());
 			}
 			builder.bind()
 		};
 
-		let endpoint = match build_endpoint(true).await {
+		let endpoint = match build_endpoint(self.discovery_config.mdns).await {
 			Ok(ep) => {
-				self.logger
-					.info("Endpoint bound successfully with mDNS + pkarr discovery enabled")
-					.await;
+				self.logger.info("Endpoint bound successfully").await;
 				ep
 			}
 			Err(e) => {
 				let err_str = e.to_string().to_lowercase();
-				if err_str.contains("mdns") {
+				if self.discovery_config.mdns && err_str.contains("mdns") {
 					self.logger
 						.warn(&format!(
 							"mDNS discovery unavailable ({}); retrying with pkarr + DNS only. \
@@ -294,6 +367,7 @@ impl NetworkingService {
 			self.identity.clone(),
 			self.active_connections.clone(),
 			self.logger.clone(),
+			self.command_channel_config,
 		);
 
 		// Store shutdown and command senders before starting
@@ -313,6 +387,12 @@ impl NetworkingService {
 		// Start periodic reconnection attempts
 		self.start_periodic_reconnection().await;
 
+		// Close cached connections nobody has used for a while
+		self.start_idle_connection_sweep();
+
+		// Keep probing trusted relays so a dead one shows up in the health report
+		self.start_relay_health_checks().await;
+
 		Ok(())
 	}
 
@@ -378,7 +458,7 @@ impl NetworkingService {
 	async fn attempt_device_reconnection(
 		device_id: Uuid,
 		persisted_device: crate::service::network::device::PersistedPairedDevice,
-		command_sender: Option<tokio::sync::mpsc::UnboundedSender<EventLoopCommand>>,
+		command_sender: Option<CommandSender>,
 		endpoint: Option<Endpoint>,
 		logger: Arc<dyn NetworkLogger>,
 	) {
@@ -461,20 +541,24 @@ impl NetworkingService {
 								.await;
 
 							// Track this outbound connection so it persists
-							let _ = sender.send(EventLoopCommand::TrackOutboundConnection {
-								node_id,
-								conn: conn.clone(),
-							});
+							let _ = sender
+								.send(EventLoopCommand::TrackOutboundConnection {
+									node_id,
+									conn: conn.clone(),
+								})
+								.await;
 
 							logger
 								.info(&format!("Connection established to device {}", device_id))
 								.await;
 
 							// Send connection established command
-							let _ = sender.send(EventLoopCommand::ConnectionEstablished {
-								device_id,
-								node_id,
-							});
+							let _ = sender
+								.send(EventLoopCommand::ConnectionEstablished {
+									device_id,
+									node_id,
+								})
+								.await;
 
 							break;
 						}
@@ -572,6 +656,106 @@ impl NetworkingService {
 		});
 	}
 
+	/// Start the sweep that closes cached connections left idle
+	///
+	/// Does nothing when `connection_idle_timeout_secs` is zero.
+	fn start_idle_connection_sweep(&self) {
+		let idle_timeout = self.discovery_config.connection_idle_timeout_secs;
+		if idle_timeout == 0 {
+			return;
+		}
+		let idle_timeout = std::time::Duration::from_secs(idle_timeout);
+		let active_connections = self.active_connections.clone();
+		let activity = self.connection_activity.clone();
+		let logger = self.logger.clone();
+
+		tokio::spawn(async move {
+			// Sweep at half the timeout so idle connections do not linger long past it
+			let mut interval =
+				tokio::time::interval((idle_timeout / 2).max(std::time::Duration::from_secs(1)));
+
+			loop {
+				interval.tick().await;
+				let evicted = sweep_idle_connections(
+					&active_connections,
+					&activity,
+					idle_timeout,
+					std::time::Instant::now(),
+					&logger,
+				)
+				.await;
+				if !evicted.is_empty() {
+					logger
+						.info(&format!("Closed {} idle connections", evicted.len()))
+						.await;
+				}
+			}
+		});
+	}
+
+	/// Start the background probes of the trusted relays
+	///
+	/// Does nothing when the config lists no trusted relays.
+	///
+	/// Whenever a relay turns healthy or unhealthy the endpoint's relay map is
+	/// updated to match, the same way startup picks the relays it begins with.
+	async fn start_relay_health_checks(&self) {
+		if self.relay_health.read().await.is_empty() {
+			return;
+		}
+		let relay_health = self.relay_health.clone();
+		let logger = self.logger.clone();
+		let endpoint = match self.discovery_config.relay {
+			RelayConfig::Disabled => None,
+			_ => self.endpoint.clone(),
+		};
+		let pinned: Vec<RelayUrl> = discovery::relay_mode(&self.discovery_config.relay)
+			.map(|mode| mode.relay_map().urls())
+			.unwrap_or_default();
+		let mut applied = relay_health.read().await.usable();
+
+		tokio::spawn(async move {
+			let client = reqwest::Client::new();
+			let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+
+			loop {
+				interval.tick().await;
+				if !check_due_relays(&relay_health, &client, &logger).await {
+					continue;
+				}
+				let Some(endpoint) = &endpoint else {
+					continue;
+				};
+
+				let usable = relay_health.read().await.usable();
+				let (added, removed) = relay_map_changes(&applied, &usable, &pinned);
+				for url in &removed {
+					endpoint.remove_relay(url).await;
+				}
+				for url in &added {
+					endpoint
+						.insert_relay(url.clone(), Arc::new(iroh::RelayConfig::from(url.clone())))
+						.await;
+				}
+				if !added.is_empty() || !removed.is_empty() {
+					logger
+						.info(&format!(
+							"Updated trusted relays: {} added, {} removed",
+							added.len(),
+							removed.len()
+						))
+						.await;
+				}
+				applied = usable;
+			}
+		});
+	}
+
+	/// Health of the trusted relays, in config order
+	pub async fn relay_health(&self) -> Vec<RelayHealth> {
+		self.relay_health.read().await.snapshot()
+	}
+
 	/// Start periodic health checks for connected devices
 	async fn start_health_check_task(&self) {
 		let device_registry = self.device_registry.clone();
@@ -579,6 +763,7 @@ impl NetworkingService {
 		let endpoint = self.endpoint.clone();
 		let logger = self.logger.clone();
 		let active_connections = self.active_connections.clone();
+		let connection_activity = self.connection_activity.clone();
 
 		tokio::spawn(async move {
 			let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
@@ -630,6 +815,18 @@ impl NetworkingService {
 						connections.keys().any(|(nid, _alpn)| *nid == node_id)
 					};
 
+					if !has_connection && connection_activity.lock().was_idle_closed(&node_id) {
+						// The idle sweep closed it on purpose; the next message reconnects
+						logger
+							.debug(&format!(
+								"Health check: skipping device {}, its connection was closed as idle",
+								device_id
+							))
+							.await;
+						failed_pings.remove(&device_id);
+						continue;
+					}
+
 					if !has_connection {
 						// Connection was lost but device is still marked as connected
 						logger
@@ -644,7 +841,7 @@ impl NetworkingService {
 								device_id,
 								node_id,
 								reason: "Connection not found in active connections".to_string(),
-							});
+							}).await;
 						}
 						failed_pings.remove(&device_id);
 						continue;
@@ -657,17 +854,25 @@ impl NetworkingService {
 					};
 
 					if let Ok(ping_data) = serde_json::to_vec(&ping_msg) {
-						// Use existing connection from active_connections
-						let connections = active_connections.read().await;
-						let conn_opt = connections
-							.iter()
-							.find(|((nid, _alpn), _conn)| *nid == node_id)
-							.map(|(_key, conn)| conn.clone());
-						let ping_result = if let Some(conn) = conn_opt {
+						// Use the existing messaging connection, the only protocol that answers pings
+						let ping_key = (node_id, MESSAGING_ALPN.to_vec());
+						let conn = active_connections.read().await.get(&ping_key).cloned();
+						let Some(conn) = conn else {
+							logger
+								.debug(&format!(
+									"Health check: device {} has no messaging connection to ping",
+									device_id
+								))
+								.await;
+							failed_pings.remove(&device_id);
+							continue;
+						};
+						connection_activity.lock().record_ping(&ping_key);
+						let ping_result =
 							tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
 								match conn.open_bi().await {
 									Ok((mut send, mut recv)) => {
-										use tokio::io::{AsyncReadExt, AsyncWriteExt};
+										use tokio::io::AsyncWriteExt;
 
 										// Send ping with length prefix
 										let len = ping_data.len() as u32;
@@ -682,16 +887,14 @@ impl NetworkingService {
 										}
 
 										// Wait for pong response
-										let mut len_buf = [0u8; 4];
-										if recv.read_exact(&mut len_buf).await.is_err() {
-											return false;
-										}
-										let resp_len = u32::from_be_bytes(len_buf) as usize;
-
-										let mut resp_buf = vec![0u8; resp_len];
-										if recv.read_exact(&mut resp_buf).await.is_err() {
-											return false;
-										}
+										let Ok(resp_buf) = crate::service::network::protocol::read_frame(
+										&mut recv,
+										crate::service::network::protocol::DEFAULT_MAX_MESSAGE_SIZE,
+									)
+									.await
+									else {
+										return false;
+									};
 
 										// Verify it's a pong
 										if let Ok(msg) = serde_json::from_slice::<
@@ -706,18 +909,7 @@ impl NetworkingService {
 									Err(_) => false,
 								}
 							})
-							.await
-						} else {
-							// No active connection found
-							logger
-								.warn(&format!(
-									"No active connection for health check to device {}",
-									device_id
-								))
-								.await;
-							Ok(false)
-						};
-						drop(connections);
+							.await;
 
 						match ping_result {
 							Ok(true) => {
@@ -756,7 +948,7 @@ impl NetworkingService {
 												device_id,
 												node_id,
 												reason: "Failed health check (3 consecutive ping timeouts)".to_string(),
-											});
+											}).await;
 									}
 									failed_pings.remove(&device_id);
 								}
@@ -810,9 +1002,9 @@ impl NetworkingService {
 			if let Ok(goodbye_data) = serde_json::to_vec(&goodbye_msg) {
 				if let Some(command_sender) = &self.command_sender {
 					// Best effort - don't block if it fails
-					let _ = command_sender.send(EventLoopCommand::SendMessageToNode {
+					let _ = command_sender.try_send(EventLoopCommand::SendMessageToNode {
 						node_id,
-						protocol: "messaging".to_string(),
+						protocol: Protocol::Messaging,
 						data: goodbye_data,
 					});
 				}
@@ -831,6 +1023,25 @@ impl NetworkingService {
 			tokio::time::sleep(std::time::Duration::from_millis(500)).await;
 		}
 
+		// Stop pairing background tasks and flush in-flight sessions
+		let pairing_handler = self
+			.protocol_registry()
+			.read()
+			.await
+			.get_handler(Protocol::Pairing);
+		if let Some(pairing_handler) = pairing_handler {
+			if let Some(pairing_handler) = pairing_handler
+				.as_any()
+				.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>(
+			) {
+				if let Err(e) = pairing_handler.shutdown().await {
+					self.logger
+						.warn(&format!("Failed to shut down pairing handler: {}", e))
+						.await;
+				}
+			}
+		}
+
 		if let Some(shutdown_sender) = self.shutdown_sender.write().await.take() {
 			let _ = shutdown_sender.send(());
 			// Wait a bit for graceful shutdown
@@ -876,17 +1087,20 @@ impl NetworkingService {
 	}
 
 	/// Send a message to a device
-	pub async fn send_message(&self, device_id: Uuid, protocol: &str, data: Vec<u8>) -> Result<()> {
+	pub async fn send_message(
+		&self,
+		device_id: Uuid,
+		protocol: Protocol,
+		data: Vec<u8>,
+	) -> Result<()> {
 		if let Some(command_sender) = &self.command_sender {
 			let command = event_loop::EventLoopCommand::SendMessage {
 				device_id,
-				protocol: protocol.to_string(),
+				protocol,
 				data,
 			};
 
-			command_sender.send(command).map_err(|_| {
-				NetworkingError::ConnectionFailed("Event loop not running".to_string())
-			})?;
+			command_sender.send(command).await?;
 
 			Ok(())
 		} else {
@@ -913,9 +1127,11 @@ impl NetworkingService {
 
 		// Get messaging handler from protocol registry
 		let protocol_registry = self.protocol_registry.read().await;
-		let handler = protocol_registry.get_handler("messaging").ok_or_else(|| {
-			NetworkingError::Protocol("Messaging handler not registered".to_string())
-		})?;
+		let handler = protocol_registry
+			.get_handler(Protocol::Messaging)
+			.ok_or_else(|| {
+				NetworkingError::Protocol("Messaging handler not registered".to_string())
+			})?;
 
 		// Downcast to MessagingProtocolHandler to access send_library_message method
 		let messaging_handler = handler
@@ -955,6 +1171,11 @@ impl NetworkingService {
 		self.active_connections.clone()
 	}
 
+	/// Get the connection activity the idle sweep reads, for recording health pings
+	pub fn connection_activity(&self) -> SharedConnectionActivity {
+		self.connection_activity.clone()
+	}
+
 	/// Get the sync multiplexer for registering library sync handlers
 	pub fn sync_multiplexer(&self) -> &Arc<SyncMultiplexer> {
 		&self.sync_multiplexer
@@ -979,7 +1200,7 @@ impl NetworkingService {
 	}
 
 	/// Get the command sender for the event loop
-	pub fn command_sender(&self) -> Option<&mpsc::UnboundedSender<event_loop::EventLoopCommand>> {
+	pub fn command_sender(&self) -> Option<&CommandSender> {
 		self.command_sender.as_ref()
 	}
 
@@ -987,19 +1208,17 @@ impl NetworkingService {
 	pub async fn send_message_to_node(
 		&self,
 		node_id: EndpointId,
-		protocol: &str,
+		protocol: Protocol,
 		data: Vec<u8>,
 	) -> Result<()> {
 		if let Some(command_sender) = &self.command_sender {
 			let command = event_loop::EventLoopCommand::SendMessageToNode {
 				node_id,
-				protocol: protocol.to_string(),
+				protocol,
 				data,
 			};
 
-			command_sender.send(command).map_err(|_| {
-				NetworkingError::ConnectionFailed("Event loop not running".to_string())
-			})?;
+			command_sender.send(command).await?;
 
 			Ok(())
 		} else {
@@ -1284,16 +1503,29 @@ impl NetworkingService {
 	/// # Parameters
 	/// * `force_relay` - If true, only use relay connections (no direct addresses). Useful for testing.
 	pub async fn start_pairing_as_initiator(&self, force_relay: bool) -> Result<(String, u32)> {
+		self.start_pairing_as_initiator_with_code(None, force_relay)
+			.await
+	}
+
+	/// Start pairing as an initiator using a caller-supplied pairing code
+	///
+	/// When `pairing_code` is `None` a fresh code is generated. Supplying a code lets
+	/// scripted callers know the session ID ahead of time; the session must not
+	/// already exist.
+	///
+	/// # Parameters
+	/// * `pairing_code` - Optional pre-built pairing code (see `PairingCode::from_entropy`)
+	/// * `force_relay` - If true, only use relay connections (no direct addresses). Useful for testing.
+	pub async fn start_pairing_as_initiator_with_code(
+		&self,
+		pairing_code: Option<crate::service::network::protocol::pairing::PairingCode>,
+		force_relay: bool,
+	) -> Result<(String, u32)> {
 		// Get pairing handler from protocol registry
 		let registry = self.protocol_registry();
-		let pairing_handler =
-			registry
-				.read()
-				.await
-				.get_handler("pairing")
-				.ok_or(NetworkingError::Protocol(
-					"Pairing protocol not registered".to_string(),
-				))?;
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
 
 		// Cast to pairing handler to access pairing-specific methods
 		let pairing_handler = pairing_handler
@@ -1308,8 +1540,11 @@ impl NetworkingService {
 
 		// Generate pairing code with node_id for remote discovery via pkarr
 		// Note: relay_url is no longer included - joiner discovers it via pkarr/DNS
-		let pairing_code = crate::service::network::protocol::pairing::PairingCode::generate()?
-			.with_node_id(initiator_node_id);
+		let pairing_code = match pairing_code {
+			Some(code) => code,
+			None => crate::service::network::protocol::pairing::PairingCode::generate()?,
+		}
+		.with_node_id(initiator_node_id);
 
 		let session_id = pairing_code.session_id();
 
@@ -1356,31 +1591,37 @@ impl NetworkingService {
 		tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
 		// Ensure relay connection is established before pkarr publishing
-		// In v0.95+, we wait for the endpoint to be online (has relay + direct addresses)
-		self.logger
-			.info("Waiting for endpoint to come online...")
-			.await;
-		endpoint.online().await;
-		let relay_url = endpoint
-			.addr()
-			.relay_urls()
-			.next()
-			.map(|u| u.to_string())
-			.unwrap_or_else(|| "unknown".to_string());
-		self.logger
-			.info(&format!("Endpoint online, relay: {}", relay_url))
-			.await;
+		// In v0.95+, we wait for the endpoint to be online (has relay + direct addresses).
+		// Without a relay the endpoint never comes online, and joiners reach us
+		// through static addresses instead.
+		if self.discovery_config.relay != RelayConfig::Disabled {
+			self.logger
+				.info("Waiting for endpoint to come online...")
+				.await;
+			endpoint.online().await;
+			let relay_url = endpoint
+				.addr()
+				.relay_urls()
+				.next()
+				.map(|u| u.to_string())
+				.unwrap_or_else(|| "unknown".to_string());
+			self.logger
+				.info(&format!("Endpoint online, relay: {}", relay_url))
+				.await;
+		}
 
 		// Give pkarr sufficient time to publish our node address to dns.iroh.link
 		// Pkarr publishing to DHT can take 3-10 seconds to propagate
-		self.logger
-			.info("Waiting for pkarr to publish node address to dns.iroh.link...")
-			.await;
-		tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+		if self.discovery_config.public_discovery {
+			self.logger
+				.info("Waiting for pkarr to publish node address to dns.iroh.link...")
+				.await;
+			tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+		}
 
 		self.logger
 			.info(&format!(
-				"Pairing session {} ready for cross-network discovery via pkarr",
+				"Pairing session {} ready for cross-network discovery",
 				session_id
 			))
 			.await;
@@ -1417,14 +1658,9 @@ impl NetworkingService {
 
 		// Get pairing handler
 		let registry = self.protocol_registry();
-		let pairing_handler =
-			registry
-				.read()
-				.await
-				.get_handler("pairing")
-				.ok_or(NetworkingError::Protocol(
-					"Pairing protocol not registered".to_string(),
-				))?;
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
 		let pairing_handler = pairing_handler
 			.as_any()
 			.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>()
@@ -1443,7 +1679,9 @@ impl NetworkingService {
 		// Implement dual-path discovery: try mDNS first (fast for local), then relay (for remote)
 		// If force_relay is true, skip mDNS and only use relay
 		// If pairing code has no node_id (text-based), only use mDNS (local network only)
+		// With mDNS turned off in the config, the node ID path is the only one left
 		let has_node_id = pairing_code_clone.node_id().is_some();
+		let force_relay = force_relay || !self.discovery_config.mdns;
 
 		if force_relay {
 			self.logger
@@ -1637,25 +1875,22 @@ impl NetworkingService {
 							app_version: env!("CARGO_PKG_VERSION").to_string(),
 							network_fingerprint: self.identity().network_fingerprint(),
 							last_seen: chrono::Utc::now(),
+							version: DeviceInfo::CURRENT_VERSION,
 						}
 					})
 				};
 
-				let pairing_request =
-					crate::service::network::protocol::pairing::messages::PairingMessage::PairingRequest {
-						session_id,
-						device_info: local_device_info,
-						public_key: self.identity().public_key_bytes(),
-					};
-
 				// Send via Iroh stream using the pairing handler and wait for response
 				if let Some(endpoint) = &self.endpoint {
 					let registry = self.protocol_registry();
 					let guard = registry.read().await;
-					if let Some(handler) = guard.get_handler("pairing") {
+					if let Some(handler) = guard.get_handler(Protocol::Pairing) {
 						if let Some(pairing_handler) =
 							handler.as_any().downcast_ref::<PairingProtocolHandler>()
 						{
+							let pairing_request = pairing_handler
+								.build_pairing_request(session_id, local_device_info)
+								.await;
 							self.logger
 								.info(&format!("Sending pairing request to node {}", node_id))
 								.await;
@@ -1698,6 +1933,86 @@ impl NetworkingService {
 		Ok(())
 	}
 
+	/// Join a pairing session, giving up after `timeout`
+	///
+	/// Instead of a bare error, reports whether the initiator could not be
+	/// reached, turned the code down, or never answered before the timeout.
+	/// The attempt returns once the request is out, so this also waits for the
+	/// initiator to move the session past scanning. A session still scanning
+	/// when the timeout fires is marked failed rather than left behind.
+	pub async fn start_pairing_as_joiner_with_timeout(
+		&self,
+		pairing_code: crate::service::network::protocol::pairing::PairingCode,
+		force_relay: bool,
+		timeout: std::time::Duration,
+	) -> Result<crate::service::network::protocol::pairing::JoinOutcome> {
+		use crate::service::network::protocol::pairing::JoinOutcome;
+
+		let session_id = pairing_code.session_id();
+		let attempt = tokio::time::timeout(timeout, async {
+			self.start_pairing_as_joiner_with_code(pairing_code, force_relay)
+				.await?;
+			while matches!(
+				self.pairing_session_state(session_id).await?,
+				Some(crate::service::network::PairingState::Scanning)
+			) {
+				tokio::time::sleep(JOIN_STATE_POLL_INTERVAL).await;
+			}
+			Ok(())
+		})
+		.await
+		.ok();
+
+		let state = if attempt.is_none() {
+			self.abandon_scanning_session(session_id).await?
+		} else {
+			self.pairing_session_state(session_id).await?
+		};
+
+		let outcome = JoinOutcome::classify(attempt.as_ref(), state.as_ref());
+		self.logger
+			.info(&format!(
+				"Join attempt for session {} ended: {}",
+				session_id, outcome
+			))
+			.await;
+		Ok(outcome)
+	}
+
+	/// Current state of a pairing session, if it is still active
+	async fn pairing_session_state(
+		&self,
+		session_id: uuid::Uuid,
+	) -> Result<Option<crate::service::network::PairingState>> {
+		Ok(self
+			.get_pairing_status()
+			.await?
+			.into_iter()
+			.find(|session| session.id == session_id)
+			.map(|session| session.state))
+	}
+
+	/// Fail a join the initiator never answered, returning the session's state
+	async fn abandon_scanning_session(
+		&self,
+		session_id: uuid::Uuid,
+	) -> Result<Option<crate::service::network::PairingState>> {
+		let registry = self.protocol_registry();
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
+
+		match pairing_handler
+			.as_any()
+			.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>()
+		{
+			Some(handler) => Ok(handler.abandon_scanning_session(session_id).await),
+			None => Err(NetworkingError::Protocol(
+				"Failed to downcast pairing handler".to_string(),
+			)),
+		}
+	}
+
 	/// Get the PairingCode object for the current session (for generating QR codes)
 	/// This is useful for getting the full pairing code with relay info
 	pub async fn get_pairing_code_for_current_session(
@@ -1705,14 +2020,9 @@ impl NetworkingService {
 	) -> Result<Option<crate::service::network::protocol::pairing::PairingCode>> {
 		// Get pairing handler from protocol registry
 		let registry = self.protocol_registry();
-		let pairing_handler =
-			registry
-				.read()
-				.await
-				.get_handler("pairing")
-				.ok_or(NetworkingError::Protocol(
-					"Pairing protocol not registered".to_string(),
-				))?;
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
 
 		// Cast to pairing handler
 		let pairing_handler = pairing_handler
@@ -1730,14 +2040,9 @@ impl NetworkingService {
 	pub async fn get_pairing_status(&self) -> Result<Vec<crate::service::network::PairingSession>> {
 		// Get pairing handler from protocol registry
 		let registry = self.protocol_registry();
-		let pairing_handler =
-			registry
-				.read()
-				.await
-				.get_handler("pairing")
-				.ok_or(NetworkingError::Protocol(
-					"Pairing protocol not registered".to_string(),
-				))?;
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
 
 		// Downcast to concrete pairing handler type to access sessions
 		if let Some(pairing_handler) = pairing_handler
@@ -1753,6 +2058,82 @@ impl NetworkingService {
 		}
 	}
 
+	/// Recently finished pairing sessions, most recent first
+	pub async fn get_pairing_history(
+		&self,
+	) -> Result<Vec<crate::service::network::protocol::pairing::PairingHistoryEntry>> {
+		let registry = self.protocol_registry();
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
+
+		match pairing_handler
+			.as_any()
+			.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>()
+		{
+			Some(handler) => Ok(handler.get_pairing_history().await),
+			None => Err(NetworkingError::Protocol(
+				"Failed to downcast pairing handler".to_string(),
+			)),
+		}
+	}
+
+	/// Redacted snapshot of the pairing handler's state for support bundles
+	pub async fn get_pairing_diagnostics(
+		&self,
+	) -> Result<crate::service::network::protocol::pairing::PairingStateDump> {
+		let registry = self.protocol_registry();
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
+
+		match pairing_handler
+			.as_any()
+			.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>()
+		{
+			Some(handler) => Ok(handler.dump_state().await),
+			None => Err(NetworkingError::Protocol(
+				"Failed to downcast pairing handler".to_string(),
+			)),
+		}
+	}
+
+	/// Proxy pairing decisions made on this device, oldest first
+	pub async fn get_proxy_pairing_audit(
+		&self,
+	) -> Result<Vec<crate::service::network::protocol::pairing::ProxyPairingAuditEntry>> {
+		let registry = self.protocol_registry();
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
+
+		match pairing_handler
+			.as_any()
+			.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>()
+		{
+			Some(handler) => handler.get_proxy_pairing_audit().await,
+			None => Err(NetworkingError::Protocol(
+				"Failed to downcast pairing handler".to_string(),
+			)),
+		}
+	}
+
+	/// Whether the pairing handler persists sessions across restarts
+	pub async fn pairing_persistence_enabled(&self) -> Result<bool> {
+		let registry = self.protocol_registry();
+		let pairing_handler = registry.read().await.get_handler(Protocol::Pairing).ok_or(
+			NetworkingError::Protocol("Pairing protocol not registered".to_string()),
+		)?;
+
+		pairing_handler
+			.as_any()
+			.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>()
+			.map(|handler| handler.persistence_enabled())
+			.ok_or_else(|| {
+				NetworkingError::Protocol("Failed to downcast pairing handler".to_string())
+			})
+	}
+
 	/// Enhanced pairing request sending with robust active polling
 	async fn ensure_pairing_requests_sent(&self, session_id: uuid::Uuid) -> Result<()> {
 		const MAX_WAIT_TIME: u64 = 15000; // 15 seconds
@@ -1763,7 +2144,7 @@ impl NetworkingService {
 			// First, check if the session has already advanced
 			let registry = self.protocol_registry();
 			let registry_guard = registry.read().await;
-			if let Some(pairing_handler) = registry_guard.get_handler("pairing") {
+			if let Some(pairing_handler) = registry_guard.get_handler(Protocol::Pairing) {
 				if let Some(handler) = pairing_handler
 					.as_any()
 					.downcast_ref::<crate::service::network::protocol::PairingProtocolHandler>(
@@ -1798,25 +2179,22 @@ impl NetworkingService {
 								app_version: env!("CARGO_PKG_VERSION").to_string(),
 								network_fingerprint: self.identity().network_fingerprint(),
 								last_seen: chrono::Utc::now(),
+								version: DeviceInfo::CURRENT_VERSION,
 							}
 						})
 					};
 
-					let pairing_request =
-						crate::service::network::protocol::pairing::messages::PairingMessage::PairingRequest {
-							session_id,
-							device_info: local_device_info,
-							public_key: self.identity().public_key_bytes(),
-						};
-
 					// Send via Iroh stream using the pairing handler and wait for response
 					if let Some(endpoint) = &self.endpoint {
 						let registry = self.protocol_registry();
 						let guard = registry.read().await;
-						if let Some(handler) = guard.get_handler("pairing") {
+						if let Some(handler) = guard.get_handler(Protocol::Pairing) {
 							if let Some(pairing_handler) =
 								handler.as_any().downcast_ref::<PairingProtocolHandler>()
 							{
+								let pairing_request = pairing_handler
+									.build_pairing_request(session_id, local_device_info)
+									.await;
 								match pairing_handler
 									.send_pairing_message_to_node(
 										endpoint,
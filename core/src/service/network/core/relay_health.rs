@@ -0,0 +1,337 @@
+//! Health of the trusted relays listed in the config
+//!
+//! A dead self-hosted relay otherwise degrades every connection without a
+//! trace. Each relay is probed in the background; failures push the next probe
+//! out with exponential backoff and move the relay behind healthy ones.
+
+use crate::service::network::utils::logging::NetworkLogger;
+use chrono::{DateTime, Utc};
+use iroh::RelayUrl;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// How often a healthy relay is probed again
+pub const RELAY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Longest wait between probes of a relay that keeps failing
+pub const RELAY_MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// Time allowed for a single probe
+pub const RELAY_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Result of the most recent probe of a relay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum RelayHealthStatus {
+	/// Not probed yet
+	Unknown,
+	Healthy,
+	Unhealthy,
+}
+
+/// Health of one trusted relay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct RelayHealth {
+	pub url: String,
+	pub status: RelayHealthStatus,
+	/// Probes failed in a row; zero once one succeeds
+	pub consecutive_failures: u32,
+	pub last_error: Option<String>,
+	pub last_checked_at: Option<DateTime<Utc>>,
+}
+
+struct RelayEntry {
+	url: RelayUrl,
+	health: RelayHealth,
+	next_probe_at: Instant,
+}
+
+/// Tracks probe results for the trusted relays
+#[derive(Default)]
+pub struct RelayHealthTracker {
+	relays: Vec<RelayEntry>,
+}
+
+impl RelayHealthTracker {
+	/// Track these relays, all due for a probe right away
+	pub fn new(urls: Vec<RelayUrl>, now: Instant) -> Self {
+		Self {
+			relays: urls
+				.into_iter()
+				.map(|url| RelayEntry {
+					health: RelayHealth {
+						url: url.to_string(),
+						status: RelayHealthStatus::Unknown,
+						consecutive_failures: 0,
+						last_error: None,
+						last_checked_at: None,
+					},
+					url,
+					next_probe_at: now,
+				})
+				.collect(),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.relays.is_empty()
+	}
+
+	/// Relays whose next probe is due
+	pub fn due(&self, now: Instant) -> Vec<RelayUrl> {
+		self.relays
+			.iter()
+			.filter(|entry| entry.next_probe_at <= now)
+			.map(|entry| entry.url.clone())
+			.collect()
+	}
+
+	/// Record a successful probe; returns true if the relay was unhealthy before
+	pub fn record_success(&mut self, url: &RelayUrl, now: Instant) -> bool {
+		let Some(entry) = self.entry_mut(url) else {
+			return false;
+		};
+		let recovered = entry.health.status == RelayHealthStatus::Unhealthy;
+		entry.health.status = RelayHealthStatus::Healthy;
+		entry.health.consecutive_failures = 0;
+		entry.health.last_error = None;
+		entry.health.last_checked_at = Some(Utc::now());
+		entry.next_probe_at = now + RELAY_CHECK_INTERVAL;
+		recovered
+	}
+
+	/// Record a failed probe and back off; returns true if the relay was healthy before
+	pub fn record_failure(&mut self, url: &RelayUrl, error: String, now: Instant) -> bool {
+		let Some(entry) = self.entry_mut(url) else {
+			return false;
+		};
+		let lost = entry.health.status != RelayHealthStatus::Unhealthy;
+		entry.health.status = RelayHealthStatus::Unhealthy;
+		entry.health.consecutive_failures = entry.health.consecutive_failures.saturating_add(1);
+		entry.health.last_error = Some(error);
+		entry.health.last_checked_at = Some(Utc::now());
+		entry.next_probe_at = now + backoff(entry.health.consecutive_failures);
+		lost
+	}
+
+	/// Relays in the order connections should use them
+	///
+	/// Healthy relays come first, then ones not probed yet, then unhealthy ones
+	/// with the fewest failures first. Ties keep the config order.
+	pub fn preferred(&self) -> Vec<RelayUrl> {
+		let mut entries: Vec<&RelayEntry> = self.relays.iter().collect();
+		entries.sort_by_key(|entry| {
+			let rank = match entry.health.status {
+				RelayHealthStatus::Healthy => 0,
+				RelayHealthStatus::Unknown => 1,
+				RelayHealthStatus::Unhealthy => 2,
+			};
+			(rank, entry.health.consecutive_failures)
+		});
+		entries.into_iter().map(|entry| entry.url.clone()).collect()
+	}
+
+	/// Relays worth handing to the endpoint
+	///
+	/// Leaves out unhealthy relays unless none of the others are left.
+	pub fn usable(&self) -> Vec<RelayUrl> {
+		let usable: Vec<RelayUrl> = self
+			.relays
+			.iter()
+			.filter(|entry| entry.health.status != RelayHealthStatus::Unhealthy)
+			.map(|entry| entry.url.clone())
+			.collect();
+		if usable.is_empty() {
+			self.preferred()
+		} else {
+			self.preferred()
+				.into_iter()
+				.filter(|url| usable.contains(url))
+				.collect()
+		}
+	}
+
+	/// Health of every tracked relay, in config order
+	pub fn snapshot(&self) -> Vec<RelayHealth> {
+		self.relays
+			.iter()
+			.map(|entry| entry.health.clone())
+			.collect()
+	}
+
+	fn entry_mut(&mut self, url: &RelayUrl) -> Option<&mut RelayEntry> {
+		self.relays.iter_mut().find(|entry| &entry.url == url)
+	}
+}
+
+/// Wait before probing a relay again after `failures` failures in a row
+fn backoff(failures: u32) -> Duration {
+	let factor = 2u32.saturating_pow(failures.saturating_sub(1).min(16));
+	RELAY_CHECK_INTERVAL
+		.saturating_mul(factor)
+		.min(RELAY_MAX_BACKOFF)
+}
+
+/// Probe a relay over HTTP(S)
+///
+/// Iroh relays answer `GET /ping` with a 200, which is what their own latency
+/// probes use.
+pub async fn probe_relay(client: &reqwest::Client, url: &RelayUrl) -> Result<(), String> {
+	let ping = url.join("ping").map_err(|e| e.to_string())?;
+	let response = client
+		.get(ping)
+		.timeout(RELAY_PROBE_TIMEOUT)
+		.send()
+		.await
+		.map_err(|e| e.to_string())?;
+	if response.status().is_success() {
+		Ok(())
+	} else {
+		Err(format!("Relay answered with status {}", response.status()))
+	}
+}
+
+/// Relays to add to and remove from the endpoint to go from `applied` to `usable`
+///
+/// Relays in `pinned`, the ones the relay config itself names, are never removed.
+pub fn relay_map_changes(
+	applied: &[RelayUrl],
+	usable: &[RelayUrl],
+	pinned: &[RelayUrl],
+) -> (Vec<RelayUrl>, Vec<RelayUrl>) {
+	let added = usable
+		.iter()
+		.filter(|url| !applied.contains(url))
+		.cloned()
+		.collect();
+	let removed = applied
+		.iter()
+		.filter(|url| !usable.contains(url) && !pinned.contains(url))
+		.cloned()
+		.collect();
+	(added, removed)
+}
+
+/// Probe every relay that is due and record the results
+///
+/// Probes run without holding the tracker lock. Relays changing state are
+/// logged, and true is returned if any did.
+pub async fn check_due_relays(
+	tracker: &RwLock<RelayHealthTracker>,
+	client: &reqwest::Client,
+	logger: &Arc<dyn NetworkLogger>,
+) -> bool {
+	let due = tracker.read().await.due(Instant::now());
+	if due.is_empty() {
+		return false;
+	}
+
+	let results = futures::future::join_all(due.iter().map(|url| probe_relay(client, url))).await;
+
+	let mut tracker = tracker.write().await;
+	let now = Instant::now();
+	let mut changed = false;
+	for (url, result) in due.iter().zip(results) {
+		match result {
+			Ok(()) => {
+				if tracker.record_success(url, now) {
+					logger
+						.info(&format!("Relay {} is reachable again", url))
+						.await;
+					changed = true;
+				}
+			}
+			Err(e) => {
+				let message = format!("Relay {} is unreachable: {}", url, e);
+				if tracker.record_failure(url, e, now) {
+					logger.warn(&message).await;
+					changed = true;
+				}
+			}
+		}
+	}
+	changed
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+	/// Serve `GET /ping` with a 200 until the test ends
+	async fn spawn_healthy_relay() -> RelayUrl {
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let addr = listener.local_addr().unwrap();
+		tokio::spawn(async move {
+			while let Ok((mut stream, _)) = listener.accept().await {
+				let mut buf = [0u8; 1024];
+				let _ = stream.read(&mut buf).await;
+				let _ = stream
+					.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+					.await;
+			}
+		});
+		format!("http://{}", addr).parse().unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_failing_relay_is_unhealthy_and_healthy_one_preferred() {
+		// Nothing listens on a port we bound and released
+		let dead_addr = {
+			let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+			listener.local_addr().unwrap()
+		};
+		let dead: RelayUrl = format!("http://{}", dead_addr).parse().unwrap();
+		let healthy = spawn_healthy_relay().await;
+
+		let now = Instant::now();
+		let mut tracker = RelayHealthTracker::new(vec![dead.clone(), healthy.clone()], now);
+		assert_eq!(tracker.due(now).len(), 2);
+
+		let client = reqwest::Client::new();
+		for url in tracker.due(now) {
+			match probe_relay(&client, &url).await {
+				Ok(()) => {
+					tracker.record_success(&url, now);
+				}
+				Err(e) => {
+					tracker.record_failure(&url, e, now);
+				}
+			}
+		}
+
+		let snapshot = tracker.snapshot();
+		assert_eq!(snapshot[0].status, RelayHealthStatus::Unhealthy);
+		assert_eq!(snapshot[0].consecutive_failures, 1);
+		assert!(snapshot[0].last_error.is_some());
+		assert_eq!(snapshot[1].status, RelayHealthStatus::Healthy);
+
+		assert_eq!(tracker.preferred(), vec![healthy.clone(), dead.clone()]);
+		assert_eq!(tracker.usable(), vec![healthy]);
+
+		// The dead relay backs off further with each failure
+		assert!(tracker.due(now).is_empty());
+		tracker.record_failure(&dead, "still down".to_string(), now);
+		assert!(!tracker.due(now + RELAY_CHECK_INTERVAL).contains(&dead));
+		assert!(tracker.due(now + RELAY_CHECK_INTERVAL * 2).contains(&dead));
+	}
+
+	#[test]
+	fn test_relay_map_follows_health_but_keeps_pinned_relays() {
+		let [a, b, c]: [RelayUrl; 3] =
+			["http://a.test", "http://b.test", "http://c.test"].map(|url| url.parse().unwrap());
+
+		// b went down and c recovered
+		let (added, removed) =
+			relay_map_changes(&[a.clone(), b.clone()], &[a.clone(), c.clone()], &[]);
+		assert_eq!(added, vec![c.clone()]);
+		assert_eq!(removed, vec![b.clone()]);
+
+		// A relay the config names itself stays even when unhealthy
+		let (added, removed) = relay_map_changes(&[a.clone(), b.clone()], &[a], &[b]);
+		assert!(added.is_empty());
+		assert!(removed.is_empty());
+	}
+}
@@ -10,6 +10,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::service::network::{NetworkingError, Result};
+
 // Note: The connection module has a more complex DeviceConnection for active connections
 // This simpler one is used in DeviceState for tracking connection metadata
 #[derive(Debug, Clone)]
@@ -18,10 +20,22 @@ pub struct ConnectionInfo {
 	pub rx_bytes: u64,
 	pub tx_bytes: u64,
 }
-pub use persistence::{DevicePersistence, PairingType, PersistedPairedDevice, TrustLevel};
+pub use persistence::{
+	DevicePersistence, PairingType, PersistedPairedDevice, TrustBundle, TrustLevel,
+};
 pub use registry::DeviceRegistry;
 
 /// Information about a device on the network
+///
+/// `DeviceInfo` travels in pairing messages and vouch payloads and is stored in the
+/// vouching queue and paired device records, so peers and databases may hold copies
+/// written by older or newer builds. To keep those readable:
+///
+/// - Fields added after version 1 must be `Option` or carry `#[serde(default)]`, so
+///   older blobs still deserialize.
+/// - Unknown fields are ignored, so newer blobs deserialize on older builds.
+/// - Adding a field bumps [`DeviceInfo::CURRENT_VERSION`]. Blobs without a version
+///   were written before versioning and read as version 1.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceInfo {
 	pub device_id: Uuid,
@@ -32,10 +46,175 @@ pub struct DeviceInfo {
 	pub app_version: String,
 	pub network_fingerprint: crate::service::network::utils::identity::NetworkFingerprint,
 	pub last_seen: DateTime<Utc>,
+	/// Schema version this info was written with
+	#[serde(default = "DeviceInfo::legacy_version")]
+	pub version: u16,
+}
+
+impl DeviceInfo {
+	/// Schema version written by this build
+	pub const CURRENT_VERSION: u16 = 2;
+
+	/// Longest any string field may be, in bytes
+	pub const MAX_FIELD_LEN: usize = 256;
+
+	/// Default cap on the serialized size of info received from a peer, in bytes
+	pub const DEFAULT_MAX_SIZE: usize = 4 * 1024;
+
+	/// Version assumed for blobs written before `version` existed
+	fn legacy_version() -> u16 {
+		1
+	}
+
+	/// Copy of this info reduced to name, slug and fingerprint
+	///
+	/// Sent in privacy mode before pairing completes, so an unpaired peer learns
+	/// nothing about the hardware or software. The full info follows once the
+	/// pairing is confirmed.
+	pub fn minimized(&self) -> Self {
+		Self {
+			device_id: self.device_id,
+			device_name: self.device_name.clone(),
+			device_slug: self.device_slug.clone(),
+			device_type: DeviceType::Other(String::new()),
+			os_version: String::new(),
+			app_version: String::new(),
+			network_fingerprint: self.network_fingerprint.clone(),
+			last_seen: self.last_seen,
+			version: self.version,
+		}
+	}
+
+	/// Check info received from a peer against the field caps and [`Self::DEFAULT_MAX_SIZE`]
+	pub fn validate(&self) -> Result<()> {
+		self.validate_with_max_size(Self::DEFAULT_MAX_SIZE)
+	}
+
+	/// Reject info with a field over [`Self::MAX_FIELD_LEN`] or a JSON size over `max_size`
+	///
+	/// Peer info is stored, shown and re-signed into vouches, so it is bounded on
+	/// its own rather than only by the size of the message carrying it.
+	pub fn validate_with_max_size(&self, max_size: usize) -> Result<()> {
+		let device_type = match &self.device_type {
+			DeviceType::Other(name) => name.as_str(),
+			_ => "",
+		};
+		let fields = [
+			("device_name", self.device_name.as_str()),
+			("device_slug", self.device_slug.as_str()),
+			("device_type", device_type),
+			("os_version", self.os_version.as_str()),
+			("app_version", self.app_version.as_str()),
+			("node_id", self.network_fingerprint.node_id.as_str()),
+			(
+				"public_key_hash",
+				self.network_fingerprint.public_key_hash.as_str(),
+			),
+		];
+		for (name, value) in fields {
+			if value.len() > Self::MAX_FIELD_LEN {
+				return Err(NetworkingError::Protocol(format!(
+					"Device info {} too long: {} bytes (max: {} bytes)",
+					name,
+					value.len(),
+					Self::MAX_FIELD_LEN
+				)));
+			}
+		}
+
+		// Escaping can make the JSON much larger than the field lengths suggest
+		let size = serde_json::to_vec(self)?.len();
+		if size > max_size {
+			return Err(NetworkingError::Protocol(format!(
+				"Device info too large: {} bytes (max: {} bytes)",
+				size, max_size
+			)));
+		}
+
+		Ok(())
+	}
+
+	/// Whether this info was reduced with [`DeviceInfo::minimized`]
+	pub fn is_minimized(&self) -> bool {
+		self.os_version.is_empty() && self.app_version.is_empty()
+	}
+
+	/// Fields that differ between this info and `other`
+	pub fn diff(&self, other: &DeviceInfo) -> DeviceInfoDiff {
+		let mut changed = Vec::new();
+		if self.device_id != other.device_id {
+			changed.push(DeviceInfoField::DeviceId);
+		}
+		if self.device_name != other.device_name {
+			changed.push(DeviceInfoField::DeviceName);
+		}
+		if self.device_slug != other.device_slug {
+			changed.push(DeviceInfoField::DeviceSlug);
+		}
+		if self.device_type != other.device_type {
+			changed.push(DeviceInfoField::DeviceType);
+		}
+		if self.os_version != other.os_version {
+			changed.push(DeviceInfoField::OsVersion);
+		}
+		if self.app_version != other.app_version {
+			changed.push(DeviceInfoField::AppVersion);
+		}
+		if self.network_fingerprint != other.network_fingerprint {
+			changed.push(DeviceInfoField::NetworkFingerprint);
+		}
+		if self.last_seen != other.last_seen {
+			changed.push(DeviceInfoField::LastSeen);
+		}
+		if self.version != other.version {
+			changed.push(DeviceInfoField::Version);
+		}
+		DeviceInfoDiff { changed }
+	}
+}
+
+/// A field of [`DeviceInfo`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, specta::Type)]
+pub enum DeviceInfoField {
+	DeviceId,
+	DeviceName,
+	DeviceSlug,
+	DeviceType,
+	OsVersion,
+	AppVersion,
+	NetworkFingerprint,
+	LastSeen,
+	Version,
+}
+
+/// Fields that changed between two [`DeviceInfo`]s, from [`DeviceInfo::diff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceInfoDiff {
+	pub changed: Vec<DeviceInfoField>,
+}
+
+impl DeviceInfoDiff {
+	pub fn is_empty(&self) -> bool {
+		self.changed.is_empty()
+	}
+
+	pub fn contains(&self, field: DeviceInfoField) -> bool {
+		self.changed.contains(&field)
+	}
+
+	/// Whether anything other than `last_seen` changed
+	///
+	/// A peer re-announcing only bumps `last_seen`, which isn't worth persisting
+	/// or telling the UI about.
+	pub fn is_significant(&self) -> bool {
+		self.changed
+			.iter()
+			.any(|field| *field != DeviceInfoField::LastSeen)
+	}
 }
 
 /// Type of device
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DeviceType {
 	Desktop,
 	Laptop,
@@ -88,6 +267,18 @@ pub enum DeviceState {
 	},
 }
 
+impl DeviceState {
+	/// Device info of a paired device, whether or not it is connected
+	pub fn info(&self) -> Option<&DeviceInfo> {
+		match self {
+			DeviceState::Paired { info, .. }
+			| DeviceState::Connected { info, .. }
+			| DeviceState::Disconnected { info, .. } => Some(info),
+			DeviceState::Discovered { .. } | DeviceState::Pairing { .. } => None,
+		}
+	}
+}
+
 /// Reason for disconnection
 #[derive(Debug, Clone)]
 pub enum DisconnectionReason {
@@ -147,6 +338,43 @@ impl SessionKeys {
 		self
 	}
 
+	/// Derive the next generation of keys from the current shared secret
+	///
+	/// Both sides of a pairing ratchet with the same `salt` and end up with
+	/// matching keys. The send/receive orientation is kept, so a joiner's
+	/// swapped keys stay swapped.
+	pub fn ratchet(&self, salt: &[u8]) -> Self {
+		use hkdf::Hkdf;
+		use sha2::Sha256;
+
+		let hk = Hkdf::<Sha256>::new(Some(salt), &self.shared_secret);
+		let mut next_secret = [0u8; 32];
+		hk.expand(b"spacedrive-rekey", &mut next_secret).unwrap();
+
+		let next = Self::from_shared_secret(next_secret.to_vec());
+		if self.is_swapped() {
+			next.swap_keys()
+		} else {
+			next
+		}
+	}
+
+	/// Proof that the holder knows the current shared secret, bound to `nonce`
+	pub fn rekey_proof(&self, nonce: &[u8]) -> Vec<u8> {
+		use hkdf::Hkdf;
+		use sha2::Sha256;
+
+		let hk = Hkdf::<Sha256>::new(Some(nonce), &self.shared_secret);
+		let mut proof = [0u8; 32];
+		hk.expand(b"spacedrive-rekey-proof", &mut proof).unwrap();
+		proof.to_vec()
+	}
+
+	/// Whether these are the joiner-side (swapped) keys for their shared secret
+	fn is_swapped(&self) -> bool {
+		Self::from_shared_secret(self.shared_secret.clone()).send_key != self.send_key
+	}
+
 	/// Check if keys are expired
 	pub fn is_expired(&self) -> bool {
 		if let Some(expires_at) = self.expires_at {
@@ -194,4 +422,152 @@ mod tests {
 		// After swap, receive should equal original send
 		assert_eq!(swapped.receive_key, original_send);
 	}
+
+	#[test]
+	fn test_ratchet_matches_on_both_sides() {
+		let initiator = SessionKeys::from_shared_secret(vec![1u8; 32]);
+		let joiner = SessionKeys::from_shared_secret(vec![1u8; 32]).swap_keys();
+
+		let initiator_next = initiator.ratchet(b"nonce");
+		let joiner_next = joiner.ratchet(b"nonce");
+
+		assert_eq!(initiator_next.shared_secret, joiner_next.shared_secret);
+		assert_eq!(initiator_next.send_key, joiner_next.receive_key);
+		assert_eq!(initiator_next.receive_key, joiner_next.send_key);
+		assert_ne!(initiator_next.send_key, initiator.send_key);
+		assert_ne!(
+			initiator.ratchet(b"other").send_key,
+			initiator_next.send_key
+		);
+	}
+
+	#[test]
+	fn test_minimized_device_info_keeps_identity_only() {
+		let info = DeviceInfo {
+			device_id: Uuid::new_v4(),
+			device_name: "Studio".to_string(),
+			device_slug: "studio".to_string(),
+			device_type: DeviceType::Laptop,
+			os_version: "macOS 15.1".to_string(),
+			app_version: "0.9.0".to_string(),
+			network_fingerprint: crate::service::network::utils::identity::NetworkFingerprint {
+				node_id: "node".to_string(),
+				public_key_hash: "hash".to_string(),
+			},
+			last_seen: Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
+		};
+
+		let minimized = info.minimized();
+
+		assert_eq!(minimized.device_id, info.device_id);
+		assert_eq!(minimized.device_name, info.device_name);
+		assert_eq!(minimized.device_slug, info.device_slug);
+		assert_eq!(minimized.network_fingerprint.node_id, "node");
+		assert!(minimized.os_version.is_empty());
+		assert!(minimized.app_version.is_empty());
+		assert!(matches!(minimized.device_type, DeviceType::Other(ref s) if s.is_empty()));
+		assert!(minimized.is_minimized());
+		assert!(!info.is_minimized());
+	}
+
+	#[test]
+	fn test_device_info_diff_lists_changed_fields() {
+		let info = DeviceInfo {
+			device_id: Uuid::new_v4(),
+			device_name: "Studio".to_string(),
+			device_slug: "studio".to_string(),
+			device_type: DeviceType::Laptop,
+			os_version: "macOS 15.1".to_string(),
+			app_version: "0.9.0".to_string(),
+			network_fingerprint: crate::service::network::utils::identity::NetworkFingerprint {
+				node_id: "node".to_string(),
+				public_key_hash: "hash".to_string(),
+			},
+			last_seen: Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
+		};
+
+		let diff = info.diff(&info.clone());
+		assert!(diff.is_empty());
+		assert!(!diff.is_significant());
+
+		let renamed = DeviceInfo {
+			device_name: "Studio 2".to_string(),
+			..info.clone()
+		};
+		let diff = info.diff(&renamed);
+		assert_eq!(diff.changed, vec![DeviceInfoField::DeviceName]);
+		assert!(diff.is_significant());
+
+		// A re-announce that only bumps last_seen is not worth an update
+		let seen_again = DeviceInfo {
+			last_seen: info.last_seen + chrono::Duration::seconds(5),
+			..info.clone()
+		};
+		assert!(!info.diff(&seen_again).is_significant());
+	}
+
+	#[test]
+	fn test_oversized_device_info_is_rejected() {
+		let info = DeviceInfo {
+			device_id: Uuid::new_v4(),
+			device_name: "Studio".to_string(),
+			device_slug: "studio".to_string(),
+			device_type: DeviceType::Laptop,
+			os_version: "macOS 15.1".to_string(),
+			app_version: "0.9.0".to_string(),
+			network_fingerprint: crate::service::network::utils::identity::NetworkFingerprint {
+				node_id: "node".to_string(),
+				public_key_hash: "hash".to_string(),
+			},
+			last_seen: Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
+		};
+		assert!(info.validate().is_ok());
+
+		let long_name = DeviceInfo {
+			device_name: "a".repeat(DeviceInfo::MAX_FIELD_LEN + 1),
+			..info.clone()
+		};
+		assert!(long_name.validate().is_err());
+
+		// Each field fits, but control characters escape to six bytes apiece
+		let padded = "\u{1}".repeat(DeviceInfo::MAX_FIELD_LEN);
+		let bloated = DeviceInfo {
+			device_name: padded.clone(),
+			device_slug: padded.clone(),
+			device_type: DeviceType::Other(padded.clone()),
+			os_version: padded.clone(),
+			app_version: padded,
+			..info.clone()
+		};
+		assert!(bloated.validate().is_err());
+		assert!(bloated.validate_with_max_size(64 * 1024).is_ok());
+	}
+
+	#[test]
+	fn test_v1_device_info_deserializes() {
+		// Written before `version` existed
+		let v1 = r#"{
+			"device_id": "5f0c8a3e-2b1d-4c7e-9a6f-3d2e1b0c9a8f",
+			"device_name": "Studio",
+			"device_slug": "studio",
+			"device_type": "Laptop",
+			"os_version": "macOS 15.1",
+			"app_version": "0.9.0",
+			"network_fingerprint": { "node_id": "node", "public_key_hash": "hash" },
+			"last_seen": "2025-01-01T00:00:00Z"
+		}"#;
+
+		let info: DeviceInfo = serde_json::from_str(v1).unwrap();
+		assert_eq!(info.version, 1);
+		assert_eq!(info.device_name, "Studio");
+		assert_eq!(info.network_fingerprint.node_id, "node");
+
+		// Round-tripping keeps the stored version rather than claiming the current one
+		let json = serde_json::to_string(&info).unwrap();
+		let reread: DeviceInfo = serde_json::from_str(&json).unwrap();
+		assert_eq!(reread.version, 1);
+	}
 }
@@ -2,16 +2,17 @@
 
 use super::{DeviceInfo, SessionKeys};
 use crate::crypto::key_manager::KeyManager;
-use crate::service::network::{NetworkingError, Result};
+use crate::service::network::{protocol::sync::SyncPayloadFormat, NetworkingError, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::info;
 use uuid::Uuid;
 
 /// Pairing type for a device relationship
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PairingType {
 	Direct,
 	Proxied,
@@ -41,10 +42,27 @@ pub struct PersistedPairedDevice {
 	pub vouched_by: Option<Uuid>,
 	#[serde(default)]
 	pub vouched_at: Option<DateTime<Utc>>,
+	/// Trust level was set by the user rather than left at its pairing default
+	#[serde(default)]
+	pub trust_set_by_user: bool,
+	/// Sync payload format negotiated during sync setup; JSON until then
+	#[serde(default)]
+	pub sync_format: SyncPayloadFormat,
+}
+
+impl PersistedPairedDevice {
+	/// Whether this device may vouch for others during proxy pairing
+	///
+	/// Directly paired devices may vouch while trusted. A proxied device only may
+	/// once the user has explicitly marked it as trusted.
+	pub fn can_vouch(&self) -> bool {
+		self.trust_level == TrustLevel::Trusted
+			&& (self.pairing_type == PairingType::Direct || self.trust_set_by_user)
+	}
 }
 
 /// Trust level for persistent connections
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
 pub enum TrustLevel {
 	/// Device was manually paired and should auto-reconnect
 	Trusted,
@@ -60,6 +78,99 @@ impl Default for TrustLevel {
 	}
 }
 
+/// Current trust bundle format version
+const TRUST_BUNDLE_VERSION: u32 = 1;
+
+/// Encrypted export of paired-device trust for backup and migration
+///
+/// Contains every persisted paired device (including session keys) encrypted
+/// with XChaCha20-Poly1305 under a key derived from a user passphrase with
+/// Argon2id. The bundle itself is safe to write to disk; the passphrase is not
+/// stored anywhere.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustBundle {
+	pub version: u32,
+	pub created_at: DateTime<Utc>,
+	/// Argon2id salt
+	pub salt: Vec<u8>,
+	/// XChaCha20-Poly1305 nonce
+	pub nonce: Vec<u8>,
+	/// Encrypted JSON map of device ID to `PersistedPairedDevice`
+	pub ciphertext: Vec<u8>,
+}
+
+impl TrustBundle {
+	/// Derive the bundle encryption key from a passphrase
+	fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+		let mut key = [0u8; 32];
+		argon2::Argon2::default()
+			.hash_password_into(passphrase.as_bytes(), salt, &mut key)
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to derive bundle key: {}", e))
+			})?;
+		Ok(key)
+	}
+
+	/// Encrypt a set of paired devices into a bundle
+	fn seal(devices: &HashMap<Uuid, PersistedPairedDevice>, passphrase: &str) -> Result<Self> {
+		use chacha20poly1305::{
+			aead::{rand_core::RngCore, Aead, KeyInit, OsRng},
+			XChaCha20Poly1305, XNonce,
+		};
+
+		let mut salt = [0u8; 16];
+		OsRng.fill_bytes(&mut salt);
+		let mut nonce = [0u8; 24];
+		OsRng.fill_bytes(&mut nonce);
+
+		let key = Self::derive_key(passphrase, &salt)?;
+		let plaintext = serde_json::to_vec(devices).map_err(NetworkingError::Serialization)?;
+		let ciphertext = XChaCha20Poly1305::new((&key).into())
+			.encrypt(XNonce::from_slice(&nonce), plaintext.as_slice())
+			.map_err(|e| NetworkingError::Protocol(format!("Failed to encrypt bundle: {}", e)))?;
+
+		Ok(Self {
+			version: TRUST_BUNDLE_VERSION,
+			created_at: Utc::now(),
+			salt: salt.to_vec(),
+			nonce: nonce.to_vec(),
+			ciphertext,
+		})
+	}
+
+	/// Decrypt the bundle back into a set of paired devices
+	fn open(&self, passphrase: &str) -> Result<HashMap<Uuid, PersistedPairedDevice>> {
+		use chacha20poly1305::{
+			aead::{Aead, KeyInit},
+			XChaCha20Poly1305, XNonce,
+		};
+
+		if self.version != TRUST_BUNDLE_VERSION {
+			return Err(NetworkingError::Protocol(format!(
+				"Unsupported trust bundle version {}",
+				self.version
+			)));
+		}
+		if self.nonce.len() != 24 {
+			return Err(NetworkingError::Protocol(
+				"Invalid trust bundle nonce".to_string(),
+			));
+		}
+
+		let key = Self::derive_key(passphrase, &self.salt)?;
+		let plaintext = XChaCha20Poly1305::new((&key).into())
+			.decrypt(XNonce::from_slice(&self.nonce), self.ciphertext.as_slice())
+			.map_err(|_| {
+				NetworkingError::AuthenticationFailed(
+					"Failed to decrypt trust bundle (wrong passphrase or corrupted bundle)"
+						.to_string(),
+				)
+			})?;
+
+		serde_json::from_slice(&plaintext).map_err(NetworkingError::Serialization)
+	}
+}
+
 /// Device persistence manager
 #[derive(Clone)]
 pub struct DevicePersistence {
@@ -201,6 +312,8 @@ impl DevicePersistence {
 			pairing_type,
 			vouched_by,
 			vouched_at,
+			trust_set_by_user: false,
+			sync_format: SyncPayloadFormat::default(),
 		};
 
 		devices.insert(device_id, paired_device);
@@ -230,8 +343,8 @@ impl DevicePersistence {
 			} else {
 				device.connection_attempts += 1;
 
-				// Mark as unreliable after 5 failed attempts
-				if device.connection_attempts >= 5 {
+				// Mark as unreliable after 5 failed attempts, leaving blocked devices blocked
+				if device.connection_attempts >= 5 && device.trust_level == TrustLevel::Trusted {
 					device.trust_level = TrustLevel::Unreliable;
 				}
 			}
@@ -242,6 +355,46 @@ impl DevicePersistence {
 		Ok(())
 	}
 
+	/// Replace the stored device info for a paired device
+	pub async fn update_device_info(&self, device_id: Uuid, device_info: DeviceInfo) -> Result<()> {
+		let mut devices = self.load_paired_devices().await?;
+
+		if let Some(device) = devices.get_mut(&device_id) {
+			device.device_info = device_info;
+			self.save_paired_devices(&devices).await?;
+		}
+
+		Ok(())
+	}
+
+	/// Record the sync payload format negotiated with a paired device
+	pub async fn set_sync_format(&self, device_id: Uuid, format: SyncPayloadFormat) -> Result<()> {
+		let mut devices = self.load_paired_devices().await?;
+
+		if let Some(device) = devices.get_mut(&device_id) {
+			device.sync_format = format;
+			self.save_paired_devices(&devices).await?;
+		}
+
+		Ok(())
+	}
+
+	/// Replace the stored session keys of a paired device
+	pub async fn update_session_keys(
+		&self,
+		device_id: Uuid,
+		session_keys: SessionKeys,
+	) -> Result<()> {
+		let mut devices = self.load_paired_devices().await?;
+
+		if let Some(device) = devices.get_mut(&device_id) {
+			device.session_keys = session_keys;
+			self.save_paired_devices(&devices).await?;
+		}
+
+		Ok(())
+	}
+
 	/// Get a single paired device by ID
 	pub async fn get_paired_device(
 		&self,
@@ -293,20 +446,27 @@ impl DevicePersistence {
 		Ok(removed)
 	}
 
-	/// Set device trust level
+	/// Set device trust level, returning the previous level
+	///
+	/// `set_by_user` marks the level as the user's choice rather than a pairing
+	/// default. Returns `None` if the device is not paired.
 	pub async fn set_device_trust_level(
 		&self,
 		device_id: Uuid,
 		trust_level: TrustLevel,
-	) -> Result<()> {
+		set_by_user: bool,
+	) -> Result<Option<TrustLevel>> {
 		let mut devices = self.load_paired_devices().await?;
 
-		if let Some(device) = devices.get_mut(&device_id) {
-			device.trust_level = trust_level;
-			self.save_paired_devices(&devices).await?;
-		}
+		let Some(device) = devices.get_mut(&device_id) else {
+			return Ok(None);
+		};
+		let previous = device.trust_level;
+		device.trust_level = trust_level;
+		device.trust_set_by_user |= set_by_user;
+		self.save_paired_devices(&devices).await?;
 
-		Ok(())
+		Ok(Some(previous))
 	}
 
 	/// Get devices that should auto-reconnect
@@ -352,6 +512,40 @@ impl DevicePersistence {
 		Ok(initial_count - initial_devices.len())
 	}
 
+	/// Export all paired devices as a passphrase-encrypted trust bundle
+	pub async fn export_trust(&self, passphrase: &str) -> Result<TrustBundle> {
+		let devices = self.load_paired_devices().await?;
+		TrustBundle::seal(&devices, passphrase)
+	}
+
+	/// Import paired devices from a trust bundle
+	///
+	/// Devices are merged by ID: unknown devices are added, and a device already
+	/// present is only replaced if the bundle holds a more recent pairing.
+	/// Returns the IDs of devices that were added or replaced.
+	pub async fn import_trust(&self, bundle: &TrustBundle, passphrase: &str) -> Result<Vec<Uuid>> {
+		let imported = bundle.open(passphrase)?;
+		let mut devices = self.load_paired_devices().await?;
+		let mut merged = Vec::new();
+
+		for (device_id, device) in imported {
+			let replace = devices
+				.get(&device_id)
+				.map_or(true, |existing| device.paired_at > existing.paired_at);
+			if replace {
+				devices.insert(device_id, device);
+				merged.push(device_id);
+			}
+		}
+
+		if !merged.is_empty() {
+			self.save_paired_devices(&devices).await?;
+		}
+
+		info!("Imported {} paired devices from trust bundle", merged.len());
+		Ok(merged)
+	}
+
 	/// Clear all paired devices
 	pub async fn clear_all_devices(&self) -> Result<()> {
 		let device_ids = self.get_device_list().await?;
@@ -405,6 +599,7 @@ mod tests {
 				public_key_hash: "test_hash".to_string(),
 			},
 			last_seen: Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
 		}
 	}
 
@@ -490,7 +685,7 @@ mod tests {
 
 		// Block the device
 		persistence
-			.set_device_trust_level(device_id, TrustLevel::Blocked)
+			.set_device_trust_level(device_id, TrustLevel::Blocked, false)
 			.await
 			.unwrap();
 
@@ -534,4 +729,58 @@ mod tests {
 			session_keys.shared_secret
 		);
 	}
+
+	#[tokio::test]
+	async fn test_trust_bundle_round_trip() {
+		let (persistence, _temp_dir) = create_test_persistence().await;
+
+		let device_id = Uuid::new_v4();
+		let session_keys = SessionKeys::from_shared_secret(vec![1, 2, 3, 4]);
+		persistence
+			.add_paired_device(
+				device_id,
+				create_test_device_info(),
+				session_keys.clone(),
+				None,
+				PairingType::Proxied,
+				Some(Uuid::new_v4()),
+				Some(Utc::now()),
+			)
+			.await
+			.unwrap();
+		persistence
+			.set_device_trust_level(device_id, TrustLevel::Unreliable, false)
+			.await
+			.unwrap();
+
+		let bundle = persistence.export_trust("correct horse").await.unwrap();
+
+		persistence.clear_all_devices().await.unwrap();
+		assert!(persistence.load_paired_devices().await.unwrap().is_empty());
+
+		// Wrong passphrase must not decrypt
+		assert!(persistence.import_trust(&bundle, "wrong").await.is_err());
+
+		let merged = persistence
+			.import_trust(&bundle, "correct horse")
+			.await
+			.unwrap();
+		assert_eq!(merged, vec![device_id]);
+
+		let devices = persistence.load_paired_devices().await.unwrap();
+		assert_eq!(devices.len(), 1);
+		let restored = &devices[&device_id];
+		assert_eq!(restored.session_keys.send_key, session_keys.send_key);
+		assert_eq!(restored.session_keys.receive_key, session_keys.receive_key);
+		assert!(matches!(restored.trust_level, TrustLevel::Unreliable));
+		assert!(matches!(restored.pairing_type, PairingType::Proxied));
+
+		// Re-importing the same bundle does not duplicate devices
+		let merged = persistence
+			.import_trust(&bundle, "correct horse")
+			.await
+			.unwrap();
+		assert!(merged.is_empty());
+		assert_eq!(persistence.load_paired_devices().await.unwrap().len(), 1);
+	}
 }
@@ -1,13 +1,17 @@
 //! Device registry for centralized state management
 
 use super::{
-	ConnectionInfo, DeviceInfo, DevicePersistence, DeviceState, PersistedPairedDevice, SessionKeys,
-	TrustLevel,
+	ConnectionInfo, DeviceInfo, DeviceInfoDiff, DevicePersistence, DeviceState,
+	PersistedPairedDevice, SessionKeys, TrustBundle, TrustLevel,
 };
 use crate::crypto::key_manager::KeyManager;
 use crate::device::DeviceManager;
-use crate::infra::event::EventBus;
-use crate::service::network::{utils::logging::NetworkLogger, NetworkingError, Result};
+use crate::infra::event::{Event, EventBus};
+use crate::service::network::{
+	protocol::sync::{SyncPayloadFormat, SUPPORTED_SYNC_FORMATS},
+	utils::logging::NetworkLogger,
+	NetworkingError, Result,
+};
 use chrono::{DateTime, Utc};
 use iroh::{EndpointAddr, EndpointId};
 use std::collections::HashMap;
@@ -28,6 +32,9 @@ pub struct DeviceRegistry {
 	/// Map of session ID to device ID for pairing lookup
 	session_to_device: HashMap<Uuid, Uuid>,
 
+	/// Sync payload format negotiated with each peer during sync setup
+	sync_formats: HashMap<Uuid, SyncPayloadFormat>,
+
 	/// Persistence manager for paired devices
 	persistence: DevicePersistence,
 
@@ -55,6 +62,7 @@ impl DeviceRegistry {
 			devices: HashMap::new(),
 			node_to_device: HashMap::new(),
 			session_to_device: HashMap::new(),
+			sync_formats: HashMap::new(),
 			persistence,
 			logger,
 			event_bus: None,
@@ -204,6 +212,21 @@ impl DeviceRegistry {
 		});
 	}
 
+	/// Tell listeners which fields of a paired device's info changed
+	fn emit_device_info_changed(&self, device_id: Uuid, diff: &DeviceInfoDiff) {
+		tracing::debug!(
+			device_id = %device_id,
+			changed = ?diff.changed,
+			"Device info changed"
+		);
+		if let Some(event_bus) = &self.event_bus {
+			event_bus.emit(Event::DeviceInfoChanged {
+				device_id,
+				changed: diff.changed.clone(),
+			});
+		}
+	}
+
 	/// Load paired devices from persistence on startup
 	pub async fn load_paired_devices(&mut self) -> Result<Vec<Uuid>> {
 		let paired_devices = self.persistence.load_paired_devices().await?;
@@ -218,6 +241,8 @@ impl DeviceRegistry {
 			};
 
 			self.devices.insert(device_id, state);
+			self.sync_formats
+				.insert(device_id, persisted_device.sync_format);
 			loaded_device_ids.push(device_id);
 
 			// Restore node-to-device mapping so incoming connections can find this device
@@ -346,7 +371,14 @@ impl DeviceRegistry {
 			paired_at: Utc::now(),
 		};
 
-		self.devices.insert(device_id, state);
+		// A device that pairs again re-announces its info, which may have changed
+		let changed = self
+			.devices
+			.insert(device_id, state)
+			.as_ref()
+			.and_then(DeviceState::info)
+			.map(|previous| previous.diff(&info))
+			.filter(DeviceInfoDiff::is_significant);
 
 		// Cache the paired device slug for pre-library address resolution
 		if let Err(e) = self
@@ -404,6 +436,9 @@ impl DeviceRegistry {
 
 		// Emit ResourceChanged event for UI reactivity
 		self.emit_device_changed(device_id, &info, false);
+		if let Some(diff) = changed {
+			self.emit_device_info_changed(device_id, &diff);
+		}
 
 		Ok(())
 	}
@@ -538,6 +573,17 @@ impl DeviceRegistry {
 		self.devices.get(&device_id)
 	}
 
+	/// Get the states of several devices at once
+	///
+	/// Lets callers look up a batch of devices under one registry lock. Unknown IDs
+	/// are left out of the returned map.
+	pub fn get_device_states(&self, device_ids: &[Uuid]) -> HashMap<Uuid, DeviceState> {
+		device_ids
+			.iter()
+			.filter_map(|id| self.devices.get(id).map(|state| (*id, state.clone())))
+			.collect()
+	}
+
 	/// Get device ID by peer ID
 	pub fn get_device_by_node(&self, node_id: EndpointId) -> Option<Uuid> {
 		self.node_to_device.get(&node_id).copied()
@@ -582,6 +628,7 @@ impl DeviceRegistry {
 
 	/// Remove a device from the registry
 	pub fn remove_device(&mut self, device_id: Uuid) -> Result<()> {
+		self.sync_formats.remove(&device_id);
 		if let Some(state) = self.devices.remove(&device_id) {
 			// Clean up node-to-device mappings for all states
 			match &state {
@@ -614,6 +661,75 @@ impl DeviceRegistry {
 		self.persistence.remove_paired_device(device_id).await
 	}
 
+	/// Set a paired device's trust level, returning the previous level
+	pub async fn set_device_trust(
+		&self,
+		device_id: Uuid,
+		trust_level: TrustLevel,
+	) -> Result<TrustLevel> {
+		self.persistence
+			.set_device_trust_level(device_id, trust_level, true)
+			.await?
+			.ok_or(NetworkingError::DeviceNotFound(device_id))
+	}
+
+	/// Export paired-device trust as a passphrase-encrypted bundle
+	pub async fn export_trust(&self, passphrase: &str) -> Result<TrustBundle> {
+		self.persistence.export_trust(passphrase).await
+	}
+
+	/// Import paired-device trust from a bundle and register the merged devices
+	///
+	/// Devices that are currently connected keep their live state; everything
+	/// else merged from the bundle is registered as paired.
+	pub async fn import_trust(
+		&mut self,
+		bundle: &TrustBundle,
+		passphrase: &str,
+	) -> Result<Vec<Uuid>> {
+		let merged = self.persistence.import_trust(bundle, passphrase).await?;
+
+		for device_id in &merged {
+			if matches!(
+				self.devices.get(device_id),
+				Some(DeviceState::Connected { .. })
+			) {
+				continue;
+			}
+
+			let Some(persisted) = self.persistence.get_paired_device(*device_id).await? else {
+				continue;
+			};
+
+			if let Ok(node_id) = persisted
+				.device_info
+				.network_fingerprint
+				.node_id
+				.parse::<EndpointId>()
+			{
+				self.node_to_device.insert(node_id, *device_id);
+			}
+
+			self.devices.insert(
+				*device_id,
+				DeviceState::Paired {
+					info: persisted.device_info,
+					session_keys: persisted.session_keys,
+					paired_at: persisted.paired_at,
+				},
+			);
+		}
+
+		self.logger
+			.info(&format!(
+				"Imported {} paired devices from trust bundle",
+				merged.len()
+			))
+			.await;
+
+		Ok(merged)
+	}
+
 	/// Get persisted paired device info
 	pub async fn get_persisted_device(
 		&self,
@@ -640,6 +756,31 @@ impl DeviceRegistry {
 		self.get_node_by_device(device_id)
 	}
 
+	/// Negotiate the sync payload format with a peer from the formats it advertised
+	///
+	/// Both sides of a `RegisterDevice` exchange call this with the other's list.
+	/// The result is persisted with the paired device, so the peer keeps getting
+	/// this format after a restart without setting up sync again. Peers that
+	/// predate the exchange advertise nothing and stay on JSON.
+	pub async fn negotiate_sync_format(
+		&mut self,
+		device_id: Uuid,
+		remote_formats: &[SyncPayloadFormat],
+	) -> Result<SyncPayloadFormat> {
+		let format = SyncPayloadFormat::negotiate(&SUPPORTED_SYNC_FORMATS, remote_formats);
+		self.sync_formats.insert(device_id, format);
+		self.persistence.set_sync_format(device_id, format).await?;
+		Ok(format)
+	}
+
+	/// Sync payload format to send to a peer, JSON until one is negotiated
+	pub fn sync_format(&self, device_id: Uuid) -> SyncPayloadFormat {
+		self.sync_formats
+			.get(&device_id)
+			.copied()
+			.unwrap_or_default()
+	}
+
 	/// Check if a device is currently connected according to Iroh
 	///
 	/// This is the canonical way to check device connectivity. It queries Iroh's endpoint
@@ -766,6 +907,71 @@ impl DeviceRegistry {
 		Ok(())
 	}
 
+	/// Replace the info of a paired device, returning whether the device was known
+	///
+	/// Used when a peer that paired in privacy mode reveals its full info. An update
+	/// that changes nothing but `last_seen` is kept in memory only, without being
+	/// persisted or announced.
+	pub async fn update_device_info(&mut self, device_id: Uuid, info: DeviceInfo) -> Result<bool> {
+		let (is_connected, diff) = match self.devices.get_mut(&device_id) {
+			Some(DeviceState::Paired { info: current, .. })
+			| Some(DeviceState::Disconnected { info: current, .. }) => {
+				let diff = current.diff(&info);
+				*current = info.clone();
+				(false, diff)
+			}
+			Some(DeviceState::Connected { info: current, .. }) => {
+				let diff = current.diff(&info);
+				*current = info.clone();
+				(true, diff)
+			}
+			_ => return Ok(false),
+		};
+
+		if !diff.is_significant() {
+			return Ok(true);
+		}
+
+		self.persistence
+			.update_device_info(device_id, info.clone())
+			.await?;
+		self.emit_device_changed(device_id, &info, is_connected);
+		self.emit_device_info_changed(device_id, &diff);
+
+		Ok(true)
+	}
+
+	/// Swap in new session keys for a paired device, returning whether it was known
+	///
+	/// The in-memory state changes in one step under the registry lock, so readers
+	/// see either the old keys or the new ones, never a mix.
+	pub async fn replace_session_keys(
+		&mut self,
+		device_id: Uuid,
+		session_keys: SessionKeys,
+	) -> Result<bool> {
+		match self.devices.get_mut(&device_id) {
+			Some(DeviceState::Paired {
+				session_keys: current,
+				..
+			})
+			| Some(DeviceState::Connected {
+				session_keys: current,
+				..
+			})
+			| Some(DeviceState::Disconnected {
+				session_keys: current,
+				..
+			}) => *current = session_keys.clone(),
+			_ => return Ok(false),
+		}
+
+		self.persistence
+			.update_session_keys(device_id, session_keys)
+			.await?;
+		Ok(true)
+	}
+
 	/// Get session keys for a device
 	pub fn get_session_keys(&self, device_id: Uuid) -> Option<super::SessionKeys> {
 		match self.devices.get(&device_id) {
@@ -819,6 +1025,7 @@ impl DeviceRegistry {
 				public_key_hash: "placeholder".to_string(),
 			},
 			last_seen: Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
 		})
 	}
 
@@ -975,3 +1182,239 @@ impl DeviceRegistry {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::service::network::utils::SilentLogger;
+	use tempfile::TempDir;
+
+	fn create_test_registry() -> (DeviceRegistry, TempDir) {
+		let temp_dir = TempDir::new().unwrap();
+		let key_manager = Arc::new(
+			KeyManager::new_with_fallback(
+				temp_dir.path().to_path_buf(),
+				Some(temp_dir.path().join("device_key")),
+			)
+			.unwrap(),
+		);
+		let device_manager = Arc::new(
+			DeviceManager::init(&temp_dir.path().to_path_buf(), key_manager.clone(), None).unwrap(),
+		);
+		let registry = DeviceRegistry::new(device_manager, key_manager, Arc::new(SilentLogger));
+		(registry, temp_dir)
+	}
+
+	#[test]
+	fn test_get_device_states_skips_unknown_ids() {
+		let (mut registry, _temp_dir) = create_test_registry();
+
+		let discovered_id = Uuid::new_v4();
+		let discovered_node = iroh::SecretKey::from_bytes(&[1u8; 32]).public();
+		registry.add_discovered_node(
+			discovered_id,
+			discovered_node,
+			EndpointAddr::new(discovered_node),
+		);
+
+		let pairing_id = Uuid::new_v4();
+		let pairing_node = iroh::SecretKey::from_bytes(&[2u8; 32]).public();
+		registry
+			.start_pairing(
+				pairing_id,
+				pairing_node,
+				Uuid::new_v4(),
+				EndpointAddr::new(pairing_node),
+			)
+			.unwrap();
+
+		let unknown_id = Uuid::new_v4();
+		let states = registry.get_device_states(&[discovered_id, unknown_id, pairing_id]);
+
+		assert_eq!(states.len(), 2);
+		assert!(matches!(
+			states.get(&discovered_id),
+			Some(DeviceState::Discovered { node_id, .. }) if *node_id == discovered_node
+		));
+		assert!(matches!(
+			states.get(&pairing_id),
+			Some(DeviceState::Pairing { node_id, .. }) if *node_id == pairing_node
+		));
+		assert!(!states.contains_key(&unknown_id));
+		assert!(registry.get_device_states(&[]).is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_repairing_emits_changed_device_info_fields() {
+		use crate::service::network::device::{DeviceInfoField, DeviceType, PairingType};
+
+		let (mut registry, _temp_dir) = create_test_registry();
+		let event_bus = Arc::new(EventBus::default());
+		let mut events = event_bus.subscribe();
+		registry.set_event_bus(event_bus);
+
+		let device_id = Uuid::new_v4();
+		let info = DeviceInfo {
+			device_id,
+			device_name: "Studio".to_string(),
+			device_slug: "studio".to_string(),
+			device_type: DeviceType::Laptop,
+			os_version: "macOS 15.1".to_string(),
+			app_version: "0.9.0".to_string(),
+			network_fingerprint: crate::service::network::utils::identity::NetworkFingerprint {
+				node_id: iroh::SecretKey::from_bytes(&[3u8; 32]).public().to_string(),
+				public_key_hash: "hash".to_string(),
+			},
+			last_seen: Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
+		};
+		async fn pair(registry: &mut DeviceRegistry, info: DeviceInfo) {
+			registry
+				.complete_pairing(
+					info.device_id,
+					info,
+					SessionKeys::from_shared_secret(vec![1; 32]),
+					None,
+					PairingType::Direct,
+					None,
+					None,
+				)
+				.await
+				.unwrap();
+		}
+		let info_changes = |events: &mut crate::infra::event::EventSubscriber| {
+			let mut changes = Vec::new();
+			while let Ok(event) = events.try_recv() {
+				if let Event::DeviceInfoChanged { changed, .. } = event {
+					changes.push(changed);
+				}
+			}
+			changes
+		};
+
+		pair(&mut registry, info.clone()).await;
+		assert!(info_changes(&mut events).is_empty());
+
+		// Re-announcing with only a newer last_seen is not a change
+		let seen_again = DeviceInfo {
+			last_seen: info.last_seen + chrono::Duration::seconds(5),
+			..info.clone()
+		};
+		pair(&mut registry, seen_again.clone()).await;
+		assert!(info_changes(&mut events).is_empty());
+
+		let renamed = DeviceInfo {
+			device_name: "Studio 2".to_string(),
+			..seen_again.clone()
+		};
+		pair(&mut registry, renamed.clone()).await;
+		assert_eq!(
+			info_changes(&mut events),
+			vec![vec![DeviceInfoField::DeviceName]]
+		);
+
+		// The privacy reveal path reports the same way
+		let upgraded = DeviceInfo {
+			app_version: "1.0.0".to_string(),
+			..renamed
+		};
+		assert!(registry
+			.update_device_info(device_id, upgraded)
+			.await
+			.unwrap());
+		assert_eq!(
+			info_changes(&mut events),
+			vec![vec![DeviceInfoField::AppVersion]]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_import_trust_round_trip() {
+		use crate::service::network::device::{DeviceType, PairingType};
+
+		let (mut registry, _temp_dir) = create_test_registry();
+		let device_info = |seed: u8| DeviceInfo {
+			device_id: Uuid::new_v4(),
+			device_name: format!("Device {}", seed),
+			device_slug: format!("device-{}", seed),
+			device_type: DeviceType::Desktop,
+			os_version: "Linux".to_string(),
+			app_version: "1.0.0".to_string(),
+			network_fingerprint: crate::service::network::utils::identity::NetworkFingerprint {
+				node_id: iroh::SecretKey::from_bytes(&[seed; 32])
+					.public()
+					.to_string(),
+				public_key_hash: "hash".to_string(),
+			},
+			last_seen: Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
+		};
+		let (paired, connected) = (device_info(4), device_info(5));
+		for info in [&paired, &connected] {
+			registry
+				.complete_pairing(
+					info.device_id,
+					info.clone(),
+					SessionKeys::from_shared_secret(vec![1; 32]),
+					None,
+					PairingType::Direct,
+					None,
+					None,
+				)
+				.await
+				.unwrap();
+		}
+		let connection = ConnectionInfo {
+			latency_ms: None,
+			rx_bytes: 0,
+			tx_bytes: 0,
+		};
+		registry
+			.mark_connected(connected.device_id, connection)
+			.await
+			.unwrap();
+
+		let bundle = registry.export_trust("passphrase").await.unwrap();
+
+		// Forget both devices on disk, but keep the connected one live in memory
+		let paired_node = iroh::SecretKey::from_bytes(&[4; 32]).public();
+		registry.remove_device(paired.device_id).unwrap();
+		for info in [&paired, &connected] {
+			assert!(registry.remove_paired_device(info.device_id).await.unwrap());
+		}
+		assert_eq!(registry.get_device_by_node(paired_node), None);
+
+		let mut merged = registry.import_trust(&bundle, "passphrase").await.unwrap();
+		merged.sort();
+		let mut expected = vec![paired.device_id, connected.device_id];
+		expected.sort();
+		assert_eq!(merged, expected);
+
+		assert_eq!(
+			registry.get_device_by_node(paired_node),
+			Some(paired.device_id)
+		);
+		assert!(matches!(
+			registry.get_device_state(paired.device_id),
+			Some(DeviceState::Paired { .. })
+		));
+		assert!(matches!(
+			registry.get_device_state(connected.device_id),
+			Some(DeviceState::Connected { .. })
+		));
+
+		// Importing the same bundle again adds nothing and duplicates no device
+		assert!(registry
+			.import_trust(&bundle, "passphrase")
+			.await
+			.unwrap()
+			.is_empty());
+		let mut paired_ids: Vec<Uuid> = registry
+			.get_paired_devices()
+			.into_iter()
+			.map(|info| info.device_id)
+			.collect();
+		paired_ids.sort();
+		assert_eq!(paired_ids, expected);
+	}
+}
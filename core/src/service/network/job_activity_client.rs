@@ -3,7 +3,10 @@
 use crate::service::network::core::JOB_ACTIVITY_ALPN;
 use crate::service::network::{
 	device::DeviceRegistry,
-	protocol::job_activity::{JobActivityMessage, RemoteJobEvent},
+	protocol::{
+		job_activity::{JobActivityMessage, RemoteJobEvent},
+		read_frame_if_any, DEFAULT_MAX_MESSAGE_SIZE,
+	},
 	remote_job_cache::RemoteJobCache,
 	utils::{get_or_create_connection, SilentLogger},
 	NetworkingError, Result,
@@ -11,7 +14,7 @@ use crate::service::network::{
 use iroh::{endpoint::Connection, Endpoint, EndpointId};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
 use tokio::sync::RwLock;
 use tracing::{error, info};
 use uuid::Uuid;
@@ -123,20 +126,18 @@ impl JobActivityClient {
 		};
 
 		loop {
-			// Read length
-			let mut len_buf = [0u8; 4];
-			if recv.read_exact(&mut len_buf).await.is_err() {
-				info!("Job activity stream closed for device {}", device_id);
-				break;
-			}
-			let msg_len = u32::from_be_bytes(len_buf) as usize;
-
 			// Read message
-			let mut msg_buf = vec![0u8; msg_len];
-			if recv.read_exact(&mut msg_buf).await.is_err() {
-				error!("Failed to read from device {}", device_id);
-				break;
-			}
+			let msg_buf = match read_frame_if_any(&mut recv, DEFAULT_MAX_MESSAGE_SIZE).await {
+				Ok(Some(buf)) => buf,
+				Ok(None) => {
+					info!("Job activity stream closed for device {}", device_id);
+					break;
+				}
+				Err(e) => {
+					error!("Failed to read from device {}: {}", device_id, e);
+					break;
+				}
+			};
 
 			// Deserialize
 			let message: JobActivityMessage = match rmp_serde::from_slice(&msg_buf) {
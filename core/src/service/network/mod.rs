@@ -27,7 +27,7 @@ pub use core::{NetworkEvent, NetworkingService};
 // Compatibility alias for legacy code
 pub use device::{DeviceInfo, DeviceRegistry, DeviceState};
 pub use job_activity_client::JobActivityClient;
-pub use protocol::{ProtocolHandler, ProtocolRegistry};
+pub use protocol::{Protocol, ProtocolHandler, ProtocolRegistry};
 pub use remote_job_cache::{RemoteJobCache, RemoteJobState};
 pub use utils::{NetworkIdentity, NetworkLogger, SilentLogger};
 pub use NetworkingService as NetworkingCore;
@@ -47,6 +47,12 @@ pub enum NetworkingError {
 	#[error("Device not found: {0}")]
 	DeviceNotFound(uuid::Uuid),
 
+	#[error("Device {device_id} is not authorized for library {library_id}")]
+	NotAuthorizedForLibrary {
+		device_id: uuid::Uuid,
+		library_id: uuid::Uuid,
+	},
+
 	#[error("Connection failed: {0}")]
 	ConnectionFailed(String),
 
@@ -1,5 +1,6 @@
 //! File delete protocol for cross-device deletion operations
 
+use super::ProtocolHandler;
 use crate::{
 	ops::files::delete::{
 		strategy::{DeleteStrategy, FileDeleteMessage, LocalDeleteStrategy},
@@ -232,9 +233,9 @@ impl Default for FileDeleteProtocolHandler {
 }
 
 #[async_trait]
-impl super::ProtocolHandler for FileDeleteProtocolHandler {
-	fn protocol_name(&self) -> &str {
-		"file_delete"
+impl ProtocolHandler for FileDeleteProtocolHandler {
+	fn protocol_name(&self) -> super::Protocol {
+		super::Protocol::FileDelete
 	}
 
 	fn as_any(&self) -> &dyn std::any::Any {
@@ -247,24 +248,19 @@ impl super::ProtocolHandler for FileDeleteProtocolHandler {
 		mut recv: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
 		_remote_node_id: EndpointId,
 	) {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 
 		// Simple request-response over streams
 		loop {
-			// Read message length (4 bytes)
-			let mut len_buf = [0u8; 4];
-			match recv.read_exact(&mut len_buf).await {
-				Ok(_) => {}
-				Err(_) => break, // Connection closed
-			}
-			let msg_len = u32::from_be_bytes(len_buf) as usize;
-
-			// Read message
-			let mut msg_buf = vec![0u8; msg_len];
-			if let Err(e) = recv.read_exact(&mut msg_buf).await {
-				eprintln!("Failed to read delete message: {}", e);
-				break;
-			}
+			// Read the next message
+			let msg_buf = match super::read_frame_if_any(&mut recv, self.max_message_size()).await {
+				Ok(Some(buf)) => buf,
+				Ok(None) => break, // Connection closed
+				Err(e) => {
+					eprintln!("Failed to read delete message: {}", e);
+					break;
+				}
+			};
 
 			// Deserialize and handle
 			match rmp_serde::from_slice::<FileDeleteMessage>(&msg_buf) {
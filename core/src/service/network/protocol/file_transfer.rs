@@ -1,5 +1,6 @@
 //! File transfer protocol for cross-device file operations
 
+use super::ProtocolHandler;
 use crate::service::network::utils::logging::NetworkLogger;
 use crate::service::network::{NetworkingError, Result};
 use async_trait::async_trait;
@@ -71,6 +72,13 @@ impl Default for TransferConfig {
 	}
 }
 
+impl TransferConfig {
+	/// Largest frame a peer using this config may send: one chunk plus the usual budget
+	pub fn max_message_size(&self) -> usize {
+		super::DEFAULT_MAX_MESSAGE_SIZE + self.chunk_size as usize
+	}
+}
+
 /// Active transfer session
 #[derive(Debug, Clone)]
 pub struct TransferSession {
@@ -1424,15 +1432,19 @@ impl FileTransferProtocolHandler {
 }
 
 #[async_trait]
-impl super::ProtocolHandler for FileTransferProtocolHandler {
-	fn protocol_name(&self) -> &str {
-		"file_transfer"
+impl ProtocolHandler for FileTransferProtocolHandler {
+	fn protocol_name(&self) -> super::Protocol {
+		super::Protocol::FileTransfer
 	}
 
 	fn as_any(&self) -> &dyn std::any::Any {
 		self
 	}
 
+	fn max_message_size(&self) -> usize {
+		self.config.max_message_size()
+	}
+
 	async fn handle_stream(
 		&self,
 		mut send: Box<dyn tokio::io::AsyncWrite + Send + Unpin>,
@@ -1497,27 +1509,17 @@ impl super::ProtocolHandler for FileTransferProtocolHandler {
 					}
 					first_message = false;
 
-					// Read message length
-					let mut len_buf = [0u8; 4];
-					match recv.read_exact(&mut len_buf).await {
-						Ok(_) => {}
+					// Read message
+					let msg_buf = match super::read_frame(&mut recv, self.max_message_size()).await
+					{
+						Ok(buf) => buf,
 						Err(e) => {
 							self.logger
-								.error(&format!("Failed to read message length: {}", e))
+								.error(&format!("Failed to read message: {}", e))
 								.await;
 							break;
 						}
-					}
-					let msg_len = u32::from_be_bytes(len_buf) as usize;
-
-					// Read message
-					let mut msg_buf = vec![0u8; msg_len];
-					if let Err(e) = recv.read_exact(&mut msg_buf).await {
-						self.logger
-							.error(&format!("Failed to read message: {}", e))
-							.await;
-						break;
-					}
+					};
 
 					// Deserialize and handle
 					if let Ok(message) = rmp_serde::from_slice::<FileTransferMessage>(&msg_buf) {
@@ -1877,7 +1879,7 @@ mod tests {
 	async fn test_file_transfer_handler_creation() {
 		let logger = Arc::new(SilentLogger);
 		let handler = FileTransferProtocolHandler::new_default(logger);
-		assert_eq!(handler.protocol_name(), "file_transfer");
+		assert_eq!(handler.protocol_name().name(), "file_transfer");
 		assert!(handler.get_active_transfers().is_empty());
 	}
 
@@ -1,6 +1,6 @@
 //! Job activity protocol for sharing job status across devices
 
-use super::{ProtocolEvent, ProtocolHandler};
+use super::{Protocol, ProtocolEvent, ProtocolHandler};
 use crate::{
 	infra::{
 		event::{Event, EventBus},
@@ -22,7 +22,7 @@ use std::{
 	time::{Duration, Instant},
 };
 use tokio::{
-	io::{AsyncReadExt, AsyncWriteExt},
+	io::AsyncWriteExt,
 	sync::{broadcast, Mutex, RwLock},
 };
 use tracing::{debug, error, info, warn};
@@ -390,8 +390,8 @@ impl JobActivityProtocolHandler {
 
 #[async_trait]
 impl ProtocolHandler for JobActivityProtocolHandler {
-	fn protocol_name(&self) -> &str {
-		"job_activity"
+	fn protocol_name(&self) -> Protocol {
+		Protocol::JobActivity
 	}
 
 	fn as_any(&self) -> &dyn std::any::Any {
@@ -408,16 +408,13 @@ impl ProtocolHandler for JobActivityProtocolHandler {
 		let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
 
 		// Read length-prefixed message to get subscription request
-		let mut len_buf = [0u8; 4];
-		if recv.read_exact(&mut len_buf).await.is_err() {
-			return;
-		}
-		let msg_len = u32::from_be_bytes(len_buf) as usize;
-
-		let mut msg_buf = vec![0u8; msg_len];
-		if recv.read_exact(&mut msg_buf).await.is_err() {
-			return;
-		}
+		let msg_buf = match super::read_frame(&mut recv, self.max_message_size()).await {
+			Ok(buf) => buf,
+			Err(e) => {
+				debug!("Failed to read subscription request: {}", e);
+				return;
+			}
+		};
 
 		// Deserialize subscribe message
 		let message: JobActivityMessage = match rmp_serde::from_slice(&msg_buf) {
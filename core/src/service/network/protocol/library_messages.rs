@@ -1,5 +1,6 @@
 //! Library-related messages for sync setup and discovery
 
+use super::sync::SyncPayloadFormat;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -40,6 +41,9 @@ pub enum LibraryMessage {
 		boot_disk_type: Option<String>,
 		boot_disk_capacity_bytes: Option<i64>,
 		swap_total_bytes: Option<i64>,
+		/// Sync payload formats the requester can decode, most preferred first
+		#[serde(default)]
+		sync_formats: Vec<SyncPayloadFormat>,
 	},
 
 	/// Response to device registration
@@ -47,6 +51,12 @@ pub enum LibraryMessage {
 		request_id: Uuid,
 		success: bool,
 		message: Option<String>,
+		/// Slug the device is registered under, when registering in a single library
+		#[serde(default)]
+		device_slug: Option<String>,
+		/// Sync payload formats the responder can decode, most preferred first
+		#[serde(default)]
+		sync_formats: Vec<SyncPayloadFormat>,
 	},
 
 	/// Request to create a shared library on remote device
@@ -75,6 +85,9 @@ pub enum LibraryMessage {
 		requesting_device_boot_disk_type: Option<String>,
 		requesting_device_boot_disk_capacity_bytes: Option<i64>,
 		requesting_device_swap_total_bytes: Option<i64>,
+		/// Locations the requesting device will share, or `None` for the whole library
+		#[serde(default)]
+		location_filter: Option<Vec<Uuid>>,
 	},
 
 	/// Response to library creation request
@@ -97,6 +110,30 @@ pub enum LibraryMessage {
 		device_slugs: Vec<String>,
 		device_count: usize,
 	},
+
+	/// Sent instead of a response when the requester isn't registered in the library
+	NotAuthorizedForLibrary {
+		request_id: Uuid,
+		library_id: Uuid,
+		device_id: Uuid,
+	},
+}
+
+impl LibraryMessage {
+	/// Id pairing a request with its response
+	pub fn request_id(&self) -> Uuid {
+		match self {
+			LibraryMessage::DiscoveryRequest { request_id }
+			| LibraryMessage::DiscoveryResponse { request_id, .. }
+			| LibraryMessage::RegisterDeviceRequest { request_id, .. }
+			| LibraryMessage::RegisterDeviceResponse { request_id, .. }
+			| LibraryMessage::CreateSharedLibraryRequest { request_id, .. }
+			| LibraryMessage::CreateSharedLibraryResponse { request_id, .. }
+			| LibraryMessage::LibraryStateRequest { request_id, .. }
+			| LibraryMessage::LibraryStateResponse { request_id, .. }
+			| LibraryMessage::NotAuthorizedForLibrary { request_id, .. } => *request_id,
+		}
+	}
 }
 
 /// Information about a library for discovery
@@ -1,7 +1,15 @@
 //! Basic messaging protocol handler
 
-use super::{library_messages::LibraryMessage, ProtocolEvent, ProtocolHandler};
-use crate::service::network::{utils, NetworkingError, Result};
+use super::{
+	library_messages::LibraryMessage,
+	sync::{handler::MembershipCache, SUPPORTED_SYNC_FORMATS},
+	Protocol, ProtocolEvent, ProtocolHandler,
+};
+use crate::service::network::{
+	core::MESSAGING_ALPN,
+	utils::{self, SharedConnectionActivity},
+	NetworkingError, Result,
+};
 use async_trait::async_trait;
 use iroh::{endpoint::Connection, Endpoint, EndpointAddr, EndpointId};
 use serde::{Deserialize, Serialize};
@@ -23,6 +31,12 @@ pub struct MessagingProtocolHandler {
 
 	/// Cached connections to remote nodes (keyed by EndpointId and ALPN)
 	connections: Arc<RwLock<HashMap<(EndpointId, Vec<u8>), Connection>>>,
+
+	/// Devices recently confirmed as members of a library
+	membership: MembershipCache,
+
+	/// Connection activity the idle sweep reads, so answered pings don't count
+	connection_activity: Option<SharedConnectionActivity>,
 }
 
 /// Basic message types
@@ -74,6 +88,8 @@ impl MessagingProtocolHandler {
 			device_registry,
 			endpoint,
 			connections: active_connections,
+			membership: MembershipCache::default(),
+			connection_activity: None,
 		}
 	}
 
@@ -82,6 +98,11 @@ impl MessagingProtocolHandler {
 		self.context = Some(context);
 	}
 
+	/// Set the connection activity answered pings are recorded in
+	pub fn set_connection_activity(&mut self, activity: SharedConnectionActivity) {
+		self.connection_activity = Some(activity);
+	}
+
 	async fn handle_ping(
 		&self,
 		_from_device: Uuid,
@@ -155,7 +176,19 @@ impl MessagingProtocolHandler {
 
 	async fn handle_library_message(
 		&self,
-		_from_device: Uuid,
+		from_device: Uuid,
+		library_msg: LibraryMessage,
+	) -> Result<Vec<u8>> {
+		let request_id = library_msg.request_id();
+		refusal_response(
+			request_id,
+			self.serve_library_message(from_device, library_msg).await,
+		)
+	}
+
+	async fn serve_library_message(
+		&self,
+		from_device: Uuid,
 		library_msg: LibraryMessage,
 	) -> Result<Vec<u8>> {
 		use super::library_messages::{LibraryDiscoveryInfo, LibraryMessage};
@@ -179,26 +212,17 @@ impl MessagingProtocolHandler {
 					let name = library.name().await;
 					let config_guard = library.config().await;
 
-					// Get library statistics from database
-					let db = library.db();
-					use crate::infra::db::entities;
-					use sea_orm::{EntityTrait, PaginatorTrait};
-
-					let entry_count = match entities::entry::Entity::find().count(db.conn()).await {
-						Ok(count) => count,
-						Err(_) => 0,
-					};
-
-					let location_count =
-						match entities::location::Entity::find().count(db.conn()).await {
-							Ok(count) => count,
-							Err(_) => 0,
-						};
-
-					let device_count = match entities::device::Entity::find().count(db.conn()).await
-					{
-						Ok(count) => count,
-						Err(_) => 0,
+					// Cached totals, recounted only when missing or stale
+					let stats = match library.discovery_stats().await {
+						Ok(stats) => stats,
+						Err(e) => {
+							tracing::warn!(
+								"Failed to compute discovery stats for library {}: {}",
+								library_id,
+								e
+							);
+							Default::default()
+						}
 					};
 
 					library_infos.push(LibraryDiscoveryInfo {
@@ -206,10 +230,10 @@ impl MessagingProtocolHandler {
 						name,
 						description: config_guard.description.clone(),
 						created_at: config_guard.created_at,
-						total_entries: entry_count,
-						total_locations: location_count,
-						total_size_bytes: 0, // TODO: Calculate from entries
-						device_count,
+						total_entries: stats.total_entries,
+						total_locations: stats.total_locations,
+						total_size_bytes: stats.total_size_bytes,
+						device_count: stats.device_count,
 					});
 				}
 
@@ -242,6 +266,7 @@ impl MessagingProtocolHandler {
 				boot_disk_type,
 				boot_disk_capacity_bytes,
 				swap_total_bytes,
+				sync_formats,
 			} => {
 				// Get context
 				let context = self.context.as_ref().ok_or_else(|| {
@@ -250,144 +275,104 @@ impl MessagingProtocolHandler {
 					)
 				})?;
 
+				// A device may only register itself
+				if device_id != from_device {
+					return Err(NetworkingError::AuthenticationFailed(format!(
+						"Device {} tried to register device {}",
+						from_device, device_id
+					)));
+				}
+
 				let library_manager = context.libraries().await;
 
 				// Determine which library to register device in
 				let libraries = if let Some(lib_id) = library_id {
-					// Specific library
+					// Specific library; registering is how a paired device joins it
 					vec![library_manager.get_library(lib_id).await.ok_or_else(|| {
 						NetworkingError::Protocol(format!("Library not found: {}", lib_id))
 					})?]
 				} else {
-					// All libraries
-					library_manager.list().await
+					// Refresh every library the device already belongs to
+					let mut member_of = Vec::new();
+					for library in library_manager.list().await {
+						match self
+							.membership
+							.ensure_member(library.db().conn(), library.id(), from_device)
+							.await
+						{
+							Ok(()) => member_of.push(library),
+							Err(NetworkingError::NotAuthorizedForLibrary { .. }) => {}
+							Err(e) => return Err(e),
+						}
+					}
+					member_of
 				};
 
 				// Register device in each library
 				use crate::infra::db::entities;
 				use chrono::Utc;
-				use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+				use sea_orm::Set;
 
 				let mut success = true;
 				let mut error_msg = None;
+				let mut registered_slug = None;
 
 				for library in libraries {
-					let db = library.db();
-
-					// Check if device already exists
-					let existing = entities::device::Entity::find()
-						.filter(entities::device::Column::Uuid.eq(device_id))
-						.one(db.conn())
-						.await;
+					let device_model = entities::device::ActiveModel {
+						id: sea_orm::ActiveValue::NotSet,
+						uuid: Set(device_id),
+						name: Set(device_name.clone()),
+						slug: Set(device_slug.clone()),
+						os: Set(os_name.clone()),
+						os_version: Set(os_version.clone()),
+						hardware_model: Set(hardware_model.clone()),
+						cpu_model: Set(cpu_model.clone()),
+						cpu_architecture: Set(cpu_architecture.clone()),
+						cpu_cores_physical: Set(cpu_cores_physical),
+						cpu_cores_logical: Set(cpu_cores_logical),
+						cpu_frequency_mhz: Set(cpu_frequency_mhz),
+						memory_total_bytes: Set(memory_total_bytes),
+						form_factor: Set(form_factor.clone()),
+						manufacturer: Set(manufacturer.clone()),
+						gpu_models: Set(gpu_models.clone().map(|g| serde_json::json!(g))),
+						boot_disk_type: Set(boot_disk_type.clone()),
+						boot_disk_capacity_bytes: Set(boot_disk_capacity_bytes),
+						swap_total_bytes: Set(swap_total_bytes),
+						network_addresses: Set(Default::default()),
+						is_online: Set(false),
+						last_seen_at: Set(Utc::now()),
+						capabilities: Set(serde_json::json!({
+							"indexing": true,
+							"p2p": true,
+							"volume_detection": true
+						})),
+						created_at: Set(Utc::now()),
+						sync_enabled: Set(true),
+						updated_at: Set(Utc::now()),
+					};
 
-					match existing {
-						Ok(Some(existing_device)) => {
-							// Device exists (from pre-registration) - update with full hardware
-							let mut device_model: entities::device::ActiveModel =
-								existing_device.into();
-
-							// Update all fields with data from message
-							device_model.name = Set(device_name.clone());
-							device_model.slug = Set(device_slug.clone());
-							device_model.os = Set(os_name.clone());
-							device_model.os_version = Set(os_version.clone());
-							device_model.hardware_model = Set(hardware_model.clone());
-							device_model.cpu_model = Set(cpu_model.clone());
-							device_model.cpu_architecture = Set(cpu_architecture.clone());
-							device_model.cpu_cores_physical = Set(cpu_cores_physical);
-							device_model.cpu_cores_logical = Set(cpu_cores_logical);
-							device_model.cpu_frequency_mhz = Set(cpu_frequency_mhz);
-							device_model.memory_total_bytes = Set(memory_total_bytes);
-							device_model.form_factor = Set(form_factor.clone());
-							device_model.manufacturer = Set(manufacturer.clone());
-							device_model.gpu_models =
-								Set(gpu_models.clone().map(|g| serde_json::json!(g)));
-							device_model.boot_disk_type = Set(boot_disk_type.clone());
-							device_model.boot_disk_capacity_bytes = Set(boot_disk_capacity_bytes);
-							device_model.swap_total_bytes = Set(swap_total_bytes);
-							device_model.is_online = Set(false);
-							device_model.last_seen_at = Set(Utc::now());
-							device_model.updated_at = Set(Utc::now());
-
-							if let Err(e) = device_model.update(db.conn()).await {
-								success = false;
-								error_msg = Some(format!("Failed to update device: {}", e));
-								break;
+					match register_device_in_library(library.db().conn(), device_model).await {
+						Ok((slug, inserted)) => {
+							if library_id.is_some() {
+								registered_slug = Some(slug.clone());
 							}
-
-							tracing::info!(
-								"Updated existing device {} in library {} with full hardware specs",
-								device_id,
-								library.id()
-							);
-						}
-						Ok(None) => {
-							// Get existing slugs for collision detection
-							let existing_slugs: Vec<String> =
-								match entities::device::Entity::find().all(db.conn()).await {
-									Ok(devices) => devices.iter().map(|d| d.slug.clone()).collect(),
-									Err(e) => {
-										success = false;
-										error_msg = Some(format!("Database error: {}", e));
-										break;
-									}
-								};
-
-							// Check if the device's slug conflicts and rename if needed
-							let unique_slug = crate::library::Library::ensure_unique_slug(
-								&device_slug,
-								&existing_slugs,
-							);
-
-							if unique_slug != device_slug {
+							if !inserted {
+								// Pre-registered, or a retry after a dropped response
+								tracing::info!(
+									"Updated existing device {} in library {} with full hardware specs",
+									device_id,
+									library.id()
+								);
+								continue;
+							}
+							if slug != device_slug {
 								tracing::info!(
 									"Device slug collision in library {}. Registering device as '{}' instead of '{}'",
 									library.id(),
-									unique_slug,
+									slug,
 									device_slug
 								);
 							}
-
-							// Register remote device with full hardware specs
-							let device_model = entities::device::ActiveModel {
-								id: sea_orm::ActiveValue::NotSet,
-								uuid: Set(device_id),
-								name: Set(device_name.clone()),
-								slug: Set(unique_slug),
-								os: Set(os_name.clone()),
-								os_version: Set(os_version.clone()),
-								hardware_model: Set(hardware_model.clone()),
-								cpu_model: Set(cpu_model.clone()),
-								cpu_architecture: Set(cpu_architecture.clone()),
-								cpu_cores_physical: Set(cpu_cores_physical),
-								cpu_cores_logical: Set(cpu_cores_logical),
-								cpu_frequency_mhz: Set(cpu_frequency_mhz),
-								memory_total_bytes: Set(memory_total_bytes),
-								form_factor: Set(form_factor.clone()),
-								manufacturer: Set(manufacturer.clone()),
-								gpu_models: Set(gpu_models.clone().map(|g| serde_json::json!(g))),
-								boot_disk_type: Set(boot_disk_type.clone()),
-								boot_disk_capacity_bytes: Set(boot_disk_capacity_bytes),
-								swap_total_bytes: Set(swap_total_bytes),
-								network_addresses: Set(serde_json::json!([])),
-								is_online: Set(false),
-								last_seen_at: Set(Utc::now()),
-								capabilities: Set(serde_json::json!({
-									"indexing": true,
-									"p2p": true,
-									"volume_detection": true
-								})),
-								created_at: Set(Utc::now()),
-								sync_enabled: Set(true),
-								updated_at: Set(Utc::now()),
-							};
-
-							if let Err(e) = device_model.insert(db.conn()).await {
-								success = false;
-								error_msg = Some(format!("Failed to register device: {}", e));
-								break;
-							}
-
 							tracing::info!(
 								"Registered device {} in library {} with full hardware specs",
 								device_id,
@@ -440,6 +425,8 @@ impl MessagingProtocolHandler {
 															.boot_disk_capacity_bytes,
 														swap_total_bytes: our_device
 															.swap_total_bytes,
+														sync_formats: SUPPORTED_SYNC_FORMATS
+															.to_vec(),
 													};
 
 												// Send to the device that just registered with us
@@ -468,17 +455,40 @@ impl MessagingProtocolHandler {
 						}
 						Err(e) => {
 							success = false;
-							error_msg = Some(format!("Database error: {}", e));
+							error_msg = Some(format!("Failed to register device: {}", e));
 							break;
 						}
 					}
 				}
 
+				if success {
+					match self
+						.device_registry
+						.write()
+						.await
+						.negotiate_sync_format(device_id, &sync_formats)
+						.await
+					{
+						Ok(format) => tracing::debug!(
+							"Negotiated {:?} sync payloads with device {}",
+							format,
+							device_id
+						),
+						Err(e) => tracing::warn!(
+							"Failed to record sync payload format for device {}: {}",
+							device_id,
+							e
+						),
+					}
+				}
+
 				// Send response confirming registration
 				let response = Message::Library(LibraryMessage::RegisterDeviceResponse {
 					request_id,
 					success,
 					message: error_msg.clone(),
+					device_slug: registered_slug,
+					sync_formats: SUPPORTED_SYNC_FORMATS.to_vec(),
 				});
 
 				serde_json::to_vec(&response).map_err(|e| NetworkingError::Serialization(e))
@@ -513,6 +523,7 @@ impl MessagingProtocolHandler {
 				requesting_device_boot_disk_type,
 				requesting_device_boot_disk_capacity_bytes,
 				requesting_device_swap_total_bytes,
+				location_filter,
 			} => {
 				tracing::info!(
 					"Received CreateSharedLibraryRequest: {} ({}) from device {} (slug: {})",
@@ -521,6 +532,14 @@ impl MessagingProtocolHandler {
 					requesting_device_id,
 					requesting_device_slug
 				);
+				if let Some(locations) = &location_filter {
+					tracing::info!(
+						"Device {} is sharing {} selected location(s) of library {}",
+						requesting_device_id,
+						locations.len(),
+						library_id
+					);
+				}
 
 				let context = self.context.as_ref().ok_or_else(|| {
 					NetworkingError::Protocol("Context not available".to_string())
@@ -531,6 +550,12 @@ impl MessagingProtocolHandler {
 				// Check if library already exists
 				if let Some(existing_library) = library_manager.get_library(library_id).await {
 					tracing::info!("Library {} already exists, returning success", library_id);
+					record_received_location_filter(
+						&existing_library,
+						requesting_device_id,
+						location_filter,
+					)
+					.await;
 
 					// Get this device's slug in the library
 					let device_slug = context.device_manager.slug_for_library(library_id).ok();
@@ -574,8 +599,14 @@ impl MessagingProtocolHandler {
 					)
 					.await
 				{
-					Ok(_) => {
+					Ok(library) => {
 						tracing::info!("Successfully created shared library: {}", library_name);
+						record_received_location_filter(
+							&library,
+							requesting_device_id,
+							location_filter,
+						)
+						.await;
 
 						// Get this device's resolved slug in the new library
 						// After ensure_device_registered, this will return the collision-resolved slug
@@ -657,8 +688,9 @@ impl MessagingProtocolHandler {
 				serde_json::to_vec(&response).map_err(|e| NetworkingError::Serialization(e))
 			}
 
-			LibraryMessage::LibraryStateResponse { .. } => {
-				// This is a response, not a request
+			LibraryMessage::LibraryStateResponse { .. }
+			| LibraryMessage::NotAuthorizedForLibrary { .. } => {
+				// These are responses, not requests
 				Ok(Vec::new())
 			}
 		}
@@ -671,7 +703,7 @@ impl MessagingProtocolHandler {
 		node_id: EndpointId,
 		message: LibraryMessage,
 	) -> Result<LibraryMessage> {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 		use tokio::time::{timeout, Duration};
 
 		tracing::info!("Sending library message to node {}: {:?}", node_id, message);
@@ -686,7 +718,7 @@ impl MessagingProtocolHandler {
 			self.connections.clone(),
 			endpoint,
 			node_id,
-			crate::service::network::core::MESSAGING_ALPN,
+			MESSAGING_ALPN,
 			&logger,
 		)
 		.await?;
@@ -717,21 +749,10 @@ impl MessagingProtocolHandler {
 		tracing::debug!("Message sent, waiting for response...");
 
 		// Read response with timeout
-		let result = timeout(Duration::from_secs(30), async {
-			let mut len_buf = [0u8; 4];
-			recv.read_exact(&mut len_buf).await.map_err(|e| {
-				NetworkingError::Transport(format!("Failed to read response length: {}", e))
-			})?;
-			let resp_len = u32::from_be_bytes(len_buf) as usize;
-
-			tracing::debug!("Receiving response of {} bytes", resp_len);
-
-			let mut resp_buf = vec![0u8; resp_len];
-			recv.read_exact(&mut resp_buf).await.map_err(|e| {
-				NetworkingError::Transport(format!("Failed to read response: {}", e))
-			})?;
-			Ok::<_, NetworkingError>(resp_buf)
-		})
+		let result = timeout(
+			Duration::from_secs(30),
+			super::read_frame(&mut recv, self.max_message_size()),
+		)
 		.await;
 
 		let resp_buf = match result {
@@ -751,7 +772,7 @@ impl MessagingProtocolHandler {
 		match envelope {
 			Message::Library(lib_msg) => {
 				tracing::debug!("Received library message response: {:?}", lib_msg);
-				Ok(lib_msg)
+				refusal_to_error(lib_msg)
 			}
 			_ => Err(NetworkingError::Protocol(
 				"Expected Library message in response".to_string(),
@@ -760,10 +781,156 @@ impl MessagingProtocolHandler {
 	}
 }
 
+/// Answer a request refused for lack of membership with
+/// [`LibraryMessage::NotAuthorizedForLibrary`], so the requester can ask for access
+fn refusal_response(request_id: Uuid, result: Result<Vec<u8>>) -> Result<Vec<u8>> {
+	match result {
+		Err(NetworkingError::NotAuthorizedForLibrary {
+			device_id,
+			library_id,
+		}) => {
+			let response = Message::Library(LibraryMessage::NotAuthorizedForLibrary {
+				request_id,
+				library_id,
+				device_id,
+			});
+			serde_json::to_vec(&response).map_err(|e| NetworkingError::Serialization(e))
+		}
+		other => other,
+	}
+}
+
+/// Turn a peer's refusal back into [`NetworkingError::NotAuthorizedForLibrary`]
+fn refusal_to_error(response: LibraryMessage) -> Result<LibraryMessage> {
+	match response {
+		LibraryMessage::NotAuthorizedForLibrary {
+			library_id,
+			device_id,
+			..
+		} => Err(NetworkingError::NotAuthorizedForLibrary {
+			device_id,
+			library_id,
+		}),
+		response => Ok(response),
+	}
+}
+
+/// Remember which locations a peer shares with us, so sync doesn't wait on the rest
+async fn record_received_location_filter(
+	library: &crate::library::Library,
+	peer: Uuid,
+	location_filter: Option<Vec<Uuid>>,
+) {
+	let result = library
+		.update_config(|config| {
+			let filters = &mut config.settings.received_location_filters;
+			match location_filter {
+				Some(locations) => {
+					filters.insert(peer, locations);
+				}
+				None => {
+					filters.remove(&peer);
+				}
+			}
+		})
+		.await;
+
+	if let Err(e) = result {
+		tracing::warn!(
+			"Failed to record location filter from device {} for library {}: {}",
+			peer,
+			library.id(),
+			e
+		);
+	}
+}
+
+/// Register a remote device in a library, or refresh it if it is already there
+///
+/// Registration is keyed by the device UUID and written as a single upsert, so a
+/// request retried after a dropped response, or racing the original, updates the
+/// existing row. A device asking for the slug it was first registered under keeps
+/// the collision-resolved slug it got then; a different requested slug is
+/// resolved and applied. `device` must have its requested slug set. Returns the
+/// registered slug and whether the device was new to the library.
+async fn register_device_in_library(
+	conn: &sea_orm::DatabaseConnection,
+	device: crate::infra::db::entities::device::ActiveModel,
+) -> std::result::Result<(String, bool), sea_orm::DbErr> {
+	use crate::infra::db::entities::device;
+	use sea_orm::{sea_query::OnConflict, EntityTrait, Set};
+
+	let device_uuid = *device.uuid.as_ref();
+	let requested = device.slug.as_ref().clone();
+
+	let mut existing = None;
+	let mut other_slugs = Vec::new();
+	for row in device::Entity::find().all(conn).await? {
+		if row.uuid == device_uuid {
+			existing = Some(row.slug);
+		} else {
+			other_slugs.push(row.slug);
+		}
+	}
+
+	// A suffix ensure_unique_slug added to this same requested slug
+	let resolved_from_requested = |slug: &str| {
+		slug.strip_prefix(&requested)
+			.and_then(|rest| rest.strip_prefix('-'))
+			.is_some_and(|suffix| {
+				suffix.parse::<u32>().is_ok()
+					|| (suffix.len() == 8 && suffix.chars().all(|c| c.is_ascii_hexdigit()))
+			})
+	};
+	let slug = match &existing {
+		Some(slug)
+			if (*slug == requested || resolved_from_requested(slug))
+				&& !other_slugs.contains(slug) =>
+		{
+			slug.clone()
+		}
+		_ => crate::library::Library::ensure_unique_slug(&requested, &other_slugs),
+	};
+
+	let mut device_model = device;
+	device_model.slug = Set(slug.clone());
+	device::Entity::insert(device_model)
+		.on_conflict(
+			OnConflict::column(device::Column::Uuid)
+				.update_columns([
+					device::Column::Name,
+					device::Column::Slug,
+					device::Column::Os,
+					device::Column::OsVersion,
+					device::Column::HardwareModel,
+					device::Column::CpuModel,
+					device::Column::CpuArchitecture,
+					device::Column::CpuCoresPhysical,
+					device::Column::CpuCoresLogical,
+					device::Column::CpuFrequencyMhz,
+					device::Column::MemoryTotalBytes,
+					device::Column::FormFactor,
+					device::Column::Manufacturer,
+					device::Column::GpuModels,
+					device::Column::BootDiskType,
+					device::Column::BootDiskCapacityBytes,
+					device::Column::SwapTotalBytes,
+					device::Column::IsOnline,
+					device::Column::LastSeenAt,
+					device::Column::UpdatedAt,
+				])
+				.to_owned(),
+		)
+		.exec(conn)
+		.await?;
+
+	Ok((slug, existing.is_none()))
+}
+
 #[async_trait]
 impl ProtocolHandler for MessagingProtocolHandler {
-	fn protocol_name(&self) -> &str {
-		"messaging"
+	fn protocol_name(&self) -> Protocol {
+		Protocol::Messaging
 	}
 
 	fn as_any(&self) -> &dyn std::any::Any {
@@ -776,24 +943,19 @@ impl ProtocolHandler for MessagingProtocolHandler {
 		mut recv: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
 		remote_node_id: EndpointId,
 	) {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 
 		// Simple request-response messaging over streams
 		loop {
-			// Read message length (4 bytes)
-			let mut len_buf = [0u8; 4];
-			match recv.read_exact(&mut len_buf).await {
-				Ok(_) => {}
-				Err(_) => break, // Connection closed
-			}
-			let msg_len = u32::from_be_bytes(len_buf) as usize;
-
-			// Read message
-			let mut msg_buf = vec![0u8; msg_len];
-			if let Err(e) = recv.read_exact(&mut msg_buf).await {
-				eprintln!("Failed to read message: {}", e);
-				break;
-			}
+			// Read the next message
+			let msg_buf = match super::read_frame_if_any(&mut recv, self.max_message_size()).await {
+				Ok(Some(buf)) => buf,
+				Ok(None) => break, // Connection closed
+				Err(e) => {
+					eprintln!("Failed to read message: {}", e);
+					break;
+				}
+			};
 
 			// Deserialize and handle
 			match serde_json::from_slice::<Message>(&msg_buf) {
@@ -801,6 +963,11 @@ impl ProtocolHandler for MessagingProtocolHandler {
 					// Process message based on type
 					let response = match &message {
 						Message::Ping { timestamp, payload } => {
+							if let Some(activity) = &self.connection_activity {
+								activity
+									.lock()
+									.record_ping(&(remote_node_id, MESSAGING_ALPN.to_vec()));
+							}
 							let pong = Message::Pong {
 								timestamp: chrono::Utc::now(),
 								original_timestamp: *timestamp,
@@ -937,3 +1104,378 @@ impl ProtocolHandler for MessagingProtocolHandler {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::infra::db::{entities, Database};
+	use crate::service::network::protocol::sync::SyncPayloadFormat;
+	use chrono::Utc;
+	use sea_orm::{EntityTrait, Set};
+	use tempfile::TempDir;
+
+	fn device_model(uuid: Uuid, slug: &str) -> entities::device::ActiveModel {
+		entities::device::ActiveModel {
+			id: sea_orm::ActiveValue::NotSet,
+			uuid: Set(uuid),
+			name: Set("Peer".to_string()),
+			slug: Set(slug.to_string()),
+			os: Set("Linux".to_string()),
+			os_version: Set(None),
+			hardware_model: Set(None),
+			cpu_model: Set(None),
+			cpu_architecture: Set(None),
+			cpu_cores_physical: Set(None),
+			cpu_cores_logical: Set(None),
+			cpu_frequency_mhz: Set(None),
+			memory_total_bytes: Set(None),
+			form_factor: Set(None),
+			manufacturer: Set(None),
+			gpu_models: Set(None),
+			boot_disk_type: Set(None),
+			boot_disk_capacity_bytes: Set(None),
+			swap_total_bytes: Set(None),
+			network_addresses: Set(Default::default()),
+			is_online: Set(false),
+			last_seen_at: Set(Utc::now()),
+			capabilities: Set(serde_json::json!({})),
+			created_at: Set(Utc::now()),
+			sync_enabled: Set(true),
+			updated_at: Set(Utc::now()),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_repeated_registration_keeps_resolved_slug() {
+		let temp_dir = TempDir::new().unwrap();
+		let db = Database::create(&temp_dir.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+
+		// Another device already holds the slug the peer asks for
+		register_device_in_library(db.conn(), device_model(Uuid::new_v4(), "peer"))
+			.await
+			.unwrap();
+
+		let peer = Uuid::new_v4();
+		let first = register_device_in_library(db.conn(), device_model(peer, "peer"))
+			.await
+			.unwrap();
+		assert_eq!(first, ("peer-2".to_string(), true));
+
+		// A retry after a dropped response must not re-resolve the collision
+		let mut retry = device_model(peer, "peer");
+		retry.cpu_model = Set(Some("M2".to_string()));
+		let second = register_device_in_library(db.conn(), retry).await.unwrap();
+		assert_eq!(second, ("peer-2".to_string(), false));
+
+		let rows = entities::device::Entity::find()
+			.all(db.conn())
+			.await
+			.unwrap();
+		assert_eq!(rows.len(), 2);
+		let row = rows.iter().find(|d| d.uuid == peer).unwrap();
+		assert_eq!(row.slug, "peer-2");
+		assert_eq!(row.cpu_model.as_deref(), Some("M2"));
+	}
+
+	#[tokio::test]
+	async fn test_registration_applies_changed_slug() {
+		let temp_dir = TempDir::new().unwrap();
+		let db = Database::create(&temp_dir.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+
+		let peer = Uuid::new_v4();
+		register_device_in_library(db.conn(), device_model(peer, "peer"))
+			.await
+			.unwrap();
+		register_device_in_library(db.conn(), device_model(Uuid::new_v4(), "laptop"))
+			.await
+			.unwrap();
+
+		let renamed = register_device_in_library(db.conn(), device_model(peer, "laptop"))
+			.await
+			.unwrap();
+		assert_eq!(renamed, ("laptop-2".to_string(), false));
+	}
+
+	#[tokio::test]
+	async fn test_racing_registrations_share_one_row() {
+		let temp_dir = TempDir::new().unwrap();
+		let db = Database::create(&temp_dir.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+
+		let peer = Uuid::new_v4();
+		let (first, retry) = tokio::join!(
+			register_device_in_library(db.conn(), device_model(peer, "peer")),
+			register_device_in_library(db.conn(), device_model(peer, "peer")),
+		);
+		assert_eq!(first.unwrap().0, "peer");
+		assert_eq!(retry.unwrap().0, "peer");
+
+		let rows = entities::device::Entity::find()
+			.all(db.conn())
+			.await
+			.unwrap();
+		assert_eq!(rows.len(), 1);
+	}
+
+	fn test_handler(temp_dir: &TempDir) -> MessagingProtocolHandler {
+		use crate::crypto::key_manager::KeyManager;
+		use crate::device::DeviceManager;
+		use crate::service::network::device::DeviceRegistry;
+
+		let key_manager = Arc::new(
+			KeyManager::new_with_fallback(
+				temp_dir.path().to_path_buf(),
+				Some(temp_dir.path().join("device_key")),
+			)
+			.unwrap(),
+		);
+		let device_manager = Arc::new(
+			DeviceManager::init(&temp_dir.path().to_path_buf(), key_manager.clone(), None).unwrap(),
+		);
+		let registry =
+			DeviceRegistry::new(device_manager, key_manager, Arc::new(utils::SilentLogger));
+		MessagingProtocolHandler::new(
+			Arc::new(RwLock::new(registry)),
+			None,
+			Arc::new(RwLock::new(HashMap::new())),
+		)
+	}
+
+	/// Record `peer` as a paired device of the handler's registry
+	async fn pair_with(handler: &MessagingProtocolHandler, peer: Uuid) {
+		use crate::service::network::{
+			device::{DeviceInfo, DeviceType, PairingType, SessionKeys},
+			utils::identity::NetworkFingerprint,
+		};
+
+		let mut key = [0u8; 32];
+		key[..16].copy_from_slice(peer.as_bytes());
+		let info = DeviceInfo {
+			device_id: peer,
+			device_name: "Peer".to_string(),
+			device_slug: "peer".to_string(),
+			device_type: DeviceType::Desktop,
+			os_version: "Linux".to_string(),
+			app_version: "1.0.0".to_string(),
+			network_fingerprint: NetworkFingerprint {
+				node_id: iroh::SecretKey::from_bytes(&key).public().to_string(),
+				public_key_hash: "hash".to_string(),
+			},
+			last_seen: Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
+		};
+		let persistence = handler.device_registry.read().await.persistence();
+		persistence
+			.add_paired_device(
+				peer,
+				info,
+				SessionKeys::from_shared_secret(vec![7u8; 32]),
+				None,
+				PairingType::Direct,
+				None,
+				None,
+			)
+			.await
+			.unwrap();
+		handler
+			.device_registry
+			.write()
+			.await
+			.load_paired_devices()
+			.await
+			.unwrap();
+	}
+
+	/// A `RegisterDeviceRequest` as it arrives on the wire, optionally from a
+	/// peer that predates the format exchange
+	fn register_request(device_id: Uuid, sync_formats: Option<&[SyncPayloadFormat]>) -> Vec<u8> {
+		let mut request = serde_json::json!({
+			"type": "register_device_request",
+			"request_id": Uuid::new_v4(),
+			"library_id": null,
+			"device_id": device_id,
+			"device_name": "Peer",
+			"device_slug": "peer",
+			"os_name": "Linux",
+		});
+		if let Some(formats) = sync_formats {
+			request["sync_formats"] = serde_json::to_value(formats).unwrap();
+		}
+		serde_json::to_vec(&request).unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_register_device_exchange_negotiates_and_persists_format() {
+		let (alice_dir, bob_dir) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+		let (alice, bob) = (test_handler(&alice_dir), test_handler(&bob_dir));
+		let (alice_id, bob_id, legacy_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+		pair_with(&alice, bob_id).await;
+		pair_with(&bob, alice_id).await;
+		pair_with(&bob, legacy_id).await;
+
+		// Bob answers Alice's request and a request from a peer without the exchange
+		for (peer, request) in [
+			(
+				alice_id,
+				register_request(alice_id, Some(&SUPPORTED_SYNC_FORMATS[..])),
+			),
+			(legacy_id, register_request(legacy_id, None)),
+		] {
+			let LibraryMessage::RegisterDeviceRequest { sync_formats, .. } =
+				serde_json::from_slice(&request).unwrap()
+			else {
+				panic!("expected a RegisterDeviceRequest");
+			};
+			bob.device_registry
+				.write()
+				.await
+				.negotiate_sync_format(peer, &sync_formats)
+				.await
+				.unwrap();
+		}
+
+		// Alice reads Bob's advertised formats from his response
+		let response = serde_json::to_vec(&LibraryMessage::RegisterDeviceResponse {
+			request_id: Uuid::new_v4(),
+			success: true,
+			message: None,
+			device_slug: None,
+			sync_formats: SUPPORTED_SYNC_FORMATS.to_vec(),
+		})
+		.unwrap();
+		let LibraryMessage::RegisterDeviceResponse { sync_formats, .. } =
+			serde_json::from_slice(&response).unwrap()
+		else {
+			panic!("expected a RegisterDeviceResponse");
+		};
+		alice
+			.device_registry
+			.write()
+			.await
+			.negotiate_sync_format(bob_id, &sync_formats)
+			.await
+			.unwrap();
+
+		assert_eq!(
+			alice.device_registry.read().await.sync_format(bob_id),
+			SyncPayloadFormat::MessagePack
+		);
+
+		// The formats survive a restart
+		let restarted = test_handler(&bob_dir);
+		restarted
+			.device_registry
+			.write()
+			.await
+			.load_paired_devices()
+			.await
+			.unwrap();
+		let registry = restarted.device_registry.read().await;
+		assert_eq!(
+			registry.sync_format(alice_id),
+			SyncPayloadFormat::MessagePack
+		);
+		assert_eq!(registry.sync_format(legacy_id), SyncPayloadFormat::Json);
+	}
+
+	#[tokio::test]
+	async fn test_stream_stops_at_oversized_frame() {
+		use crate::service::network::protocol::{read_frame, read_frame_if_any};
+		use tokio::io::AsyncWriteExt;
+
+		let temp_dir = TempDir::new().unwrap();
+		let handler = Arc::new(test_handler(&temp_dir));
+		let (client, server) = tokio::io::duplex(64 * 1024);
+		let (server_recv, server_send) = tokio::io::split(server);
+		let (mut client_recv, mut client_send) = tokio::io::split(client);
+		let remote = iroh::SecretKey::from_bytes(&[1u8; 32]).public();
+
+		let stream = tokio::spawn({
+			let handler = handler.clone();
+			async move {
+				handler
+					.handle_stream(Box::new(server_send), Box::new(server_recv), remote)
+					.await
+			}
+		});
+
+		// A message within the limit is answered
+		let ping = serde_json::to_vec(&Message::Ping {
+			timestamp: Utc::now(),
+			payload: None,
+		})
+		.unwrap();
+		client_send
+			.write_all(&(ping.len() as u32).to_be_bytes())
+			.await
+			.unwrap();
+		client_send.write_all(&ping).await.unwrap();
+		let reply = read_frame(&mut client_recv, handler.max_message_size())
+			.await
+			.unwrap();
+		assert!(matches!(
+			serde_json::from_slice::<Message>(&reply).unwrap(),
+			Message::Pong { .. }
+		));
+
+		// An oversized length ends the stream before any body is read
+		let oversized = (handler.max_message_size() + 1) as u32;
+		client_send
+			.write_all(&oversized.to_be_bytes())
+			.await
+			.unwrap();
+		tokio::time::timeout(std::time::Duration::from_secs(5), stream)
+			.await
+			.expect("handler should stop reading")
+			.unwrap();
+		assert!(
+			read_frame_if_any(&mut client_recv, handler.max_message_size())
+				.await
+				.unwrap()
+				.is_none()
+		);
+	}
+
+	#[test]
+	fn test_refusal_reaches_the_requester_as_typed_error() {
+		let (request_id, library_id, device_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+		let bytes = refusal_response(
+			request_id,
+			Err(NetworkingError::NotAuthorizedForLibrary {
+				device_id,
+				library_id,
+			}),
+		)
+		.unwrap();
+		let Message::Library(response) = serde_json::from_slice(&bytes).unwrap() else {
+			panic!("expected a library message");
+		};
+		assert_eq!(response.request_id(), request_id);
+
+		match refusal_to_error(response) {
+			Err(NetworkingError::NotAuthorizedForLibrary {
+				device_id: refused_device,
+				library_id: refused_library,
+			}) => {
+				assert_eq!(refused_device, device_id);
+				assert_eq!(refused_library, library_id);
+			}
+			other => panic!("expected NotAuthorizedForLibrary, got {:?}", other),
+		}
+
+		// Other failures stay errors on the responder's side
+		assert!(matches!(
+			refusal_response(request_id, Err(NetworkingError::Protocol("boom".into()))),
+			Err(NetworkingError::Protocol(_))
+		));
+	}
+}
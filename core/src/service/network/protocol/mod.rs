@@ -9,16 +9,19 @@ pub mod pairing;
 pub mod registry;
 pub mod sync;
 
-use crate::service::network::{NetworkingError, Result};
+use crate::service::network::{
+	core::{FILE_TRANSFER_ALPN, JOB_ACTIVITY_ALPN, MESSAGING_ALPN, PAIRING_ALPN, SYNC_ALPN},
+	NetworkingError, Result,
+};
 use async_trait::async_trait;
 use iroh::EndpointId;
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 use uuid::Uuid;
 
 pub use file_delete::FileDeleteProtocolHandler;
 pub use file_transfer::{
-	FileMetadata, FileTransferMessage, FileTransferProtocolHandler, TransferDirection,
-	TransferMode, TransferSession,
+	FileMetadata, FileTransferMessage, FileTransferProtocolHandler, TransferConfig,
+	TransferDirection, TransferMode, TransferSession,
 };
 pub use job_activity::{JobActivityMessage, JobActivityProtocolHandler, RemoteJobEvent};
 pub use library_messages::{LibraryDiscoveryInfo, LibraryMessage};
@@ -27,11 +30,154 @@ pub use pairing::{PairingMessage, PairingProtocolHandler, PairingSession, Pairin
 pub use registry::ProtocolRegistry;
 pub use sync::{SyncMessage, SyncProtocolHandler};
 
+/// Protocols spoken over the network
+///
+/// Ties each protocol's handler routing name to the ALPN its connections use, so
+/// the two can't drift apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Protocol {
+	Pairing,
+	FileTransfer,
+	FileDelete,
+	Messaging,
+	Sync,
+	JobActivity,
+	/// Handlers that only exist in tests, never registered by the service
+	#[cfg(test)]
+	Test,
+}
+
+impl Protocol {
+	pub const ALL: [Protocol; 6] = [
+		Protocol::Pairing,
+		Protocol::FileTransfer,
+		Protocol::FileDelete,
+		Protocol::Messaging,
+		Protocol::Sync,
+		Protocol::JobActivity,
+	];
+
+	/// Name the protocol's handler is registered and routed under
+	pub fn name(self) -> &'static str {
+		match self {
+			Protocol::Pairing => "pairing",
+			Protocol::FileTransfer => "file_transfer",
+			Protocol::FileDelete => "file_delete",
+			Protocol::Messaging => "messaging",
+			Protocol::Sync => "sync",
+			Protocol::JobActivity => "job_activity",
+			#[cfg(test)]
+			Protocol::Test => "test",
+		}
+	}
+
+	/// ALPN the protocol's connections use
+	///
+	/// `None` for protocols without connections of their own.
+	pub fn alpn(self) -> Option<&'static [u8]> {
+		match self {
+			Protocol::Pairing => Some(PAIRING_ALPN),
+			Protocol::FileTransfer => Some(FILE_TRANSFER_ALPN),
+			Protocol::FileDelete => None,
+			Protocol::Messaging => Some(MESSAGING_ALPN),
+			Protocol::Sync => Some(SYNC_ALPN),
+			Protocol::JobActivity => Some(JOB_ACTIVITY_ALPN),
+			#[cfg(test)]
+			Protocol::Test => None,
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|protocol| protocol.name() == name)
+	}
+
+	pub fn from_alpn(alpn: &[u8]) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|protocol| protocol.alpn() == Some(alpn))
+	}
+}
+
+impl fmt::Display for Protocol {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// Default limit on a single framed message (1MB)
+///
+/// Keeps a peer from making us allocate for an arbitrary length claim.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Read one length-prefixed message from a stream
+///
+/// A frame is a big-endian `u32` length followed by that many bytes. Lengths above
+/// `max_size` are rejected before the body is read.
+pub async fn read_frame(
+	recv: &mut (impl tokio::io::AsyncRead + Unpin),
+	max_size: usize,
+) -> Result<Vec<u8>> {
+	use tokio::io::AsyncReadExt;
+
+	let mut len_buf = [0u8; 4];
+	recv.read_exact(&mut len_buf)
+		.await
+		.map_err(|e| NetworkingError::Transport(format!("Failed to read message length: {}", e)))?;
+	read_frame_body(recv, len_buf, max_size).await
+}
+
+/// Read one length-prefixed message, or None if the stream ends before one starts
+///
+/// For streams the peer may close instead of sending another message, such as a
+/// request loop or a request that gets no reply. Once a length has arrived this
+/// fails like [`read_frame`].
+pub async fn read_frame_if_any(
+	recv: &mut (impl tokio::io::AsyncRead + Unpin),
+	max_size: usize,
+) -> Result<Option<Vec<u8>>> {
+	use tokio::io::AsyncReadExt;
+
+	let mut len_buf = [0u8; 4];
+	if recv.read_exact(&mut len_buf).await.is_err() {
+		return Ok(None);
+	}
+	read_frame_body(recv, len_buf, max_size).await.map(Some)
+}
+
+async fn read_frame_body(
+	recv: &mut (impl tokio::io::AsyncRead + Unpin),
+	len_buf: [u8; 4],
+	max_size: usize,
+) -> Result<Vec<u8>> {
+	use tokio::io::AsyncReadExt;
+
+	let len = u32::from_be_bytes(len_buf) as usize;
+	if len > max_size {
+		return Err(NetworkingError::Protocol(format!(
+			"Message too large: {} bytes (max: {} bytes)",
+			len, max_size
+		)));
+	}
+
+	let mut buf = vec![0u8; len];
+	recv.read_exact(&mut buf)
+		.await
+		.map_err(|e| NetworkingError::Transport(format!("Failed to read message: {}", e)))?;
+	Ok(buf)
+}
+
 /// Trait for handling specific protocols over Iroh streams
 #[async_trait]
 pub trait ProtocolHandler: Send + Sync {
-	/// Get the protocol name
-	fn protocol_name(&self) -> &str;
+	/// Get the protocol this handler serves
+	fn protocol_name(&self) -> Protocol;
+
+	/// Largest message this protocol accepts in a single frame
+	fn max_message_size(&self) -> usize {
+		DEFAULT_MAX_MESSAGE_SIZE
+	}
 
 	/// Handle an incoming stream (bidirectional or unidirectional)
 	async fn handle_stream(
@@ -77,3 +223,89 @@ pub enum ProtocolEvent {
 		data: HashMap<String, serde_json::Value>,
 	},
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct SizedHandler(usize);
+
+	#[async_trait]
+	impl ProtocolHandler for SizedHandler {
+		fn protocol_name(&self) -> Protocol {
+			Protocol::Test
+		}
+
+		fn max_message_size(&self) -> usize {
+			self.0
+		}
+
+		async fn handle_stream(
+			&self,
+			_send: Box<dyn tokio::io::AsyncWrite + Send + Unpin>,
+			_recv: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
+			_remote_node_id: EndpointId,
+		) {
+		}
+
+		fn as_any(&self) -> &dyn std::any::Any {
+			self
+		}
+
+		async fn handle_request(&self, _from_device: Uuid, _data: Vec<u8>) -> Result<Vec<u8>> {
+			Ok(Vec::new())
+		}
+
+		async fn handle_response(
+			&self,
+			_from_device: Uuid,
+			_from_node: EndpointId,
+			_response_data: Vec<u8>,
+		) -> Result<()> {
+			Ok(())
+		}
+
+		async fn handle_event(&self, _event: ProtocolEvent) -> Result<()> {
+			Ok(())
+		}
+	}
+
+	fn frame(payload: &[u8]) -> Vec<u8> {
+		let mut data = (payload.len() as u32).to_be_bytes().to_vec();
+		data.extend_from_slice(payload);
+		data
+	}
+
+	#[tokio::test]
+	async fn test_frame_limit_follows_handler() {
+		let payload = vec![7u8; 4096];
+		let data = frame(&payload);
+
+		let small = SizedHandler(1024);
+		let err = read_frame(&mut data.as_slice(), small.max_message_size())
+			.await
+			.unwrap_err();
+		assert!(matches!(err, NetworkingError::Protocol(_)));
+
+		let large = SizedHandler(8 * 1024);
+		let read = read_frame(&mut data.as_slice(), large.max_message_size())
+			.await
+			.unwrap();
+		assert_eq!(read, payload);
+	}
+
+	#[test]
+	fn test_protocol_alpn_and_name_stay_in_sync() {
+		for protocol in Protocol::ALL {
+			assert_eq!(Protocol::from_name(protocol.name()), Some(protocol));
+			if let Some(alpn) = protocol.alpn() {
+				assert_eq!(Protocol::from_alpn(alpn), Some(protocol));
+			}
+		}
+
+		assert_eq!(Protocol::from_alpn(PAIRING_ALPN), Some(Protocol::Pairing));
+		assert_eq!(Protocol::Pairing.name(), "pairing");
+		assert_eq!(Protocol::from_alpn(b"spacedrive/unknown/1"), None);
+		assert_eq!(Protocol::from_name("unknown"), None);
+	}
+}
@@ -0,0 +1,161 @@
+//! Redacted pairing state for support bundles
+//!
+//! Copies out ids, state names and counts only. Shared secrets, public keys,
+//! challenge and response bytes and session keys never reach the snapshot, so it
+//! can be attached to a bug report as is.
+
+use super::{
+	metrics::PairingTimings,
+	proxy::VouchingSessionState,
+	types::{PairingFailureReason, PairingRole, PairingSession, PairingState},
+	PairingProtocolHandler,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// One active pairing session, without key material
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PairingSessionSnapshot {
+	pub session_id: Uuid,
+	/// State name; challenge and response bytes are left out
+	pub state: String,
+	pub failure_reason: Option<PairingFailureReason>,
+	/// Inferred from the state, None for states both sides pass through
+	pub role: Option<PairingRole>,
+	pub remote_device_id: Option<Uuid>,
+	pub remote_device_name: Option<String>,
+	pub created_at: DateTime<Utc>,
+	pub via_relay: bool,
+	pub auto_accepted: bool,
+	/// Whether a shared secret has been derived, never the secret itself
+	pub has_shared_secret: bool,
+}
+
+impl From<&PairingSession> for PairingSessionSnapshot {
+	fn from(session: &PairingSession) -> Self {
+		Self {
+			session_id: session.id,
+			state: state_name(&session.state).to_string(),
+			failure_reason: match &session.state {
+				PairingState::Failed { reason, .. } => Some(*reason),
+				_ => None,
+			},
+			role: role_for_state(&session.state),
+			remote_device_id: session.remote_device_id,
+			remote_device_name: session
+				.remote_device_info
+				.as_ref()
+				.map(|info| info.device_name.clone()),
+			created_at: session.created_at,
+			via_relay: session.via_relay,
+			auto_accepted: session.auto_accepted,
+			has_shared_secret: session.shared_secret.is_some(),
+		}
+	}
+}
+
+/// One proxy vouching session, without the vouchee's keys
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct VouchingSessionSnapshot {
+	pub session_id: Uuid,
+	pub state: VouchingSessionState,
+	pub vouch_count: usize,
+	pub expires_at: DateTime<Utc>,
+}
+
+/// Snapshot of the pairing handler for debugging
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct PairingStateDump {
+	pub captured_at: DateTime<Utc>,
+	pub sessions: Vec<PairingSessionSnapshot>,
+	pub vouching_sessions: Vec<VouchingSessionSnapshot>,
+	pub pending_proxy_confirmations: usize,
+	pub pending_rekeys: usize,
+	/// Entries in the offline vouching queue, None when the queue is not set up
+	pub vouching_queue_depth: Option<usize>,
+	pub history_entries: usize,
+	pub persistence_enabled: bool,
+	/// Handshake phase durations over the pairings completed since startup
+	pub phase_timings: PairingTimings,
+}
+
+/// Name of a state without the data some variants carry
+fn state_name(state: &PairingState) -> &'static str {
+	match state {
+		PairingState::Idle => "Idle",
+		PairingState::GeneratingCode => "GeneratingCode",
+		PairingState::Broadcasting => "Broadcasting",
+		PairingState::Scanning => "Scanning",
+		PairingState::WaitingForConnection => "WaitingForConnection",
+		PairingState::Connecting => "Connecting",
+		PairingState::Authenticating => "Authenticating",
+		PairingState::ExchangingKeys => "ExchangingKeys",
+		PairingState::AwaitingConfirmation => "AwaitingConfirmation",
+		PairingState::EstablishingSession => "EstablishingSession",
+		PairingState::ChallengeReceived { .. } => "ChallengeReceived",
+		PairingState::ResponsePending { .. } => "ResponsePending",
+		PairingState::ResponseSent => "ResponseSent",
+		PairingState::Completed => "Completed",
+		PairingState::Failed { .. } => "Failed",
+	}
+}
+
+/// Side of the exchange a session in this state is on, where only one side uses it
+fn role_for_state(state: &PairingState) -> Option<PairingRole> {
+	match state {
+		PairingState::GeneratingCode
+		| PairingState::Broadcasting
+		| PairingState::WaitingForConnection
+		| PairingState::ChallengeReceived { .. } => Some(PairingRole::Initiator),
+		PairingState::Scanning
+		| PairingState::ResponsePending { .. }
+		| PairingState::ResponseSent => Some(PairingRole::Joiner),
+		_ => None,
+	}
+}
+
+impl PairingProtocolHandler {
+	/// Redacted snapshot of the handler's state for a support bundle
+	pub async fn dump_state(&self) -> PairingStateDump {
+		let mut sessions: Vec<PairingSessionSnapshot> = self
+			.active_sessions
+			.read()
+			.await
+			.values()
+			.map(PairingSessionSnapshot::from)
+			.collect();
+		sessions.sort_by_key(|session| session.created_at);
+
+		let vouching_sessions = self
+			.vouching_sessions
+			.read()
+			.await
+			.values()
+			.map(|session| VouchingSessionSnapshot {
+				session_id: session.id,
+				state: session.state.clone(),
+				vouch_count: session.vouches.len(),
+				expires_at: session.expires_at,
+			})
+			.collect();
+
+		let queue = { self.vouching_queue.read().await.clone() };
+		let vouching_queue_depth = match queue {
+			Some(queue) => queue.list_entries().await.ok().map(|entries| entries.len()),
+			None => None,
+		};
+
+		PairingStateDump {
+			captured_at: Utc::now(),
+			sessions,
+			vouching_sessions,
+			pending_proxy_confirmations: self.pending_proxy_confirmations.read().await.len(),
+			pending_rekeys: self.pending_rekeys.read().await.len(),
+			vouching_queue_depth,
+			history_entries: self.pairing_history.read().await.entries().len(),
+			persistence_enabled: self.persistence_enabled(),
+			phase_timings: self.get_pairing_timings().await,
+		}
+	}
+}
@@ -0,0 +1,152 @@
+//! Recently finished pairing sessions
+//!
+//! Active sessions are dropped some time after they finish, so the outcome is
+//! copied here when a session reaches a terminal state (or is removed before
+//! reaching one). The buffer is bounded; the oldest outcomes fall off first.
+
+use super::types::{PairingFailureReason, PairingSession, PairingState};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+/// Number of finished sessions kept
+pub const PAIRING_HISTORY_CAPACITY: usize = 50;
+
+/// How a pairing session ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub enum PairingOutcome {
+	Completed,
+	Failed {
+		reason: PairingFailureReason,
+		detail: Option<String>,
+	},
+	/// Removed by the user (or because its device went away) before finishing
+	Cancelled,
+}
+
+impl PairingOutcome {
+	/// Outcome for a session in a terminal state, None while it is still running
+	pub fn from_state(state: &PairingState) -> Option<Self> {
+		match state {
+			PairingState::Completed => Some(Self::Completed),
+			PairingState::Failed { reason, detail } => Some(Self::Failed {
+				reason: *reason,
+				detail: detail.clone(),
+			}),
+			_ => None,
+		}
+	}
+}
+
+/// One finished pairing session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct PairingHistoryEntry {
+	pub session_id: Uuid,
+	pub remote_device_id: Option<Uuid>,
+	pub remote_device_name: Option<String>,
+	pub outcome: PairingOutcome,
+	/// When the session was seen to finish
+	pub finished_at: DateTime<Utc>,
+}
+
+/// Bounded buffer of finished sessions, each recorded once
+#[derive(Debug, Clone)]
+pub struct PairingHistory {
+	entries: VecDeque<PairingHistoryEntry>,
+	capacity: usize,
+}
+
+impl Default for PairingHistory {
+	fn default() -> Self {
+		Self::with_capacity(PAIRING_HISTORY_CAPACITY)
+	}
+}
+
+impl PairingHistory {
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self {
+			entries: VecDeque::with_capacity(capacity),
+			capacity,
+		}
+	}
+
+	/// Record a session's outcome unless it is already in the history
+	///
+	/// Returns whether an entry was added.
+	pub fn record(&mut self, session: &PairingSession, outcome: PairingOutcome) -> bool {
+		if self.contains(session.id) {
+			return false;
+		}
+		if self.entries.len() == self.capacity {
+			self.entries.pop_front();
+		}
+		self.entries.push_back(PairingHistoryEntry {
+			session_id: session.id,
+			remote_device_id: session.remote_device_id,
+			remote_device_name: session
+				.remote_device_info
+				.as_ref()
+				.map(|info| info.device_name.clone()),
+			outcome,
+			finished_at: Utc::now(),
+		});
+		true
+	}
+
+	/// Record a session if it is in a terminal state
+	pub fn record_if_finished(&mut self, session: &PairingSession) -> bool {
+		match PairingOutcome::from_state(&session.state) {
+			Some(outcome) => self.record(session, outcome),
+			None => false,
+		}
+	}
+
+	pub fn contains(&self, session_id: Uuid) -> bool {
+		self.entries
+			.iter()
+			.any(|entry| entry.session_id == session_id)
+	}
+
+	/// Finished sessions, most recent first
+	pub fn entries(&self) -> Vec<PairingHistoryEntry> {
+		self.entries.iter().rev().cloned().collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn session(state: PairingState) -> PairingSession {
+		PairingSession {
+			id: Uuid::new_v4(),
+			state,
+			remote_device_id: None,
+			remote_device_info: None,
+			remote_public_key: None,
+			shared_secret: None,
+			created_at: Utc::now(),
+			via_relay: false,
+			auto_accepted: false,
+		}
+	}
+
+	#[test]
+	fn test_history_is_bounded_and_newest_first() {
+		let mut history = PairingHistory::with_capacity(2);
+		let sessions: Vec<_> = (0..3).map(|_| session(PairingState::Completed)).collect();
+		for s in &sessions {
+			assert!(history.record_if_finished(s));
+		}
+
+		let entries = history.entries();
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].session_id, sessions[2].id);
+		assert_eq!(entries[1].session_id, sessions[1].id);
+
+		// Recording the same session again is a no-op
+		assert!(!history.record(&sessions[2], PairingOutcome::Cancelled));
+		assert!(!history.record_if_finished(&session(PairingState::Scanning)));
+	}
+}
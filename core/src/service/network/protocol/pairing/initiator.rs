@@ -2,8 +2,9 @@
 
 use super::{
 	messages::PairingMessage,
+	metrics::PairingStep,
 	security::PairingSecurity,
-	types::{PairingSession, PairingState},
+	types::{PairingFailureReason, PairingSession, PairingState, MAX_OPEN_SESSIONS_PER_PEER},
 	PairingProtocolHandler,
 };
 use crate::service::network::{
@@ -11,17 +12,123 @@ use crate::service::network::{
 	NetworkingError, Result,
 };
 use iroh::{EndpointId, Watcher};
+use std::time::Instant;
 use uuid::Uuid;
 
+/// Check that a pairing request really comes from the node it claims to be
+///
+/// The device info and public key are supplied by the requester, so both must
+/// name the node the connection was actually opened from. Otherwise a peer could
+/// present another device's fingerprint and have it stored against its own
+/// connection once pairing completes.
+pub(crate) fn verify_requester_identity(
+	device_info: &DeviceInfo,
+	public_key: &[u8],
+	remote_node_id: EndpointId,
+) -> Result<()> {
+	let claimed_node = device_info
+		.network_fingerprint
+		.node_id
+		.parse::<EndpointId>()
+		.map_err(|e| {
+			NetworkingError::AuthenticationFailed(format!(
+				"Pairing request has an invalid fingerprint node id: {}",
+				e
+			))
+		})?;
+	if claimed_node != remote_node_id {
+		return Err(NetworkingError::AuthenticationFailed(format!(
+			"Pairing request claims node {} but arrived from node {}",
+			claimed_node, remote_node_id
+		)));
+	}
+
+	if public_key != remote_node_id.as_bytes() {
+		return Err(NetworkingError::AuthenticationFailed(format!(
+			"Pairing request public key does not belong to node {}",
+			remote_node_id
+		)));
+	}
+
+	Ok(())
+}
+
 impl PairingProtocolHandler {
+	/// Whether a code lets any number of joiners pair with it
+	async fn is_multi_use_code(&self, session_id: Uuid) -> bool {
+		self.pairing_codes
+			.read()
+			.await
+			.get(&session_id)
+			.is_some_and(|code| !code.is_single_use())
+	}
+
+	/// Key of the session that holds one joiner's handshake
+	///
+	/// Every joiner of a multi-use code presents the code's session id, so each
+	/// handshake is kept under an id derived from the joiner's public key instead,
+	/// and joiners pairing at the same time don't overwrite each other's challenge.
+	/// Single-use codes and allowlisted requests keep the code's session id.
+	async fn handshake_session_id(&self, session_id: Uuid, joiner_public_key: &[u8]) -> Uuid {
+		if self.is_multi_use_code(session_id).await {
+			Uuid::new_v5(&session_id, joiner_public_key)
+		} else {
+			session_id
+		}
+	}
+
+	/// Find the handshake a joiner already started under a code's session id
+	///
+	/// Later messages only carry the code's session id, so the joiner's session is
+	/// the one whose id derives from the key it presented with its request.
+	async fn find_handshake_session_id(
+		&self,
+		session_id: Uuid,
+		is_joiner: impl Fn(&PairingSession) -> bool,
+	) -> Uuid {
+		if !self.is_multi_use_code(session_id).await {
+			return session_id;
+		}
+		self.active_sessions
+			.read()
+			.await
+			.values()
+			.find(|session| {
+				is_joiner(session)
+					&& session
+						.remote_public_key
+						.as_deref()
+						.is_some_and(|key| Uuid::new_v5(&session_id, key) == session.id)
+			})
+			.map(|session| session.id)
+			.unwrap_or(session_id)
+	}
+
 	/// Handle an incoming pairing request (Initiator receives this from Joiner)
+	///
+	/// `remote_node_id` is the node the request arrived from; the request is
+	/// rejected unless its fingerprint and key belong to that node.
 	pub(crate) async fn handle_pairing_request(
 		&self,
 		from_device: Uuid,
+		remote_node_id: EndpointId,
 		session_id: Uuid,
 		device_info: DeviceInfo,
 		public_key: Vec<u8>,
 	) -> Result<Vec<u8>> {
+		if let Err(e) = verify_requester_identity(&device_info, &public_key, remote_node_id) {
+			self.log_warn(&format!(
+				"Rejecting pairing request for session {}: {}",
+				session_id, e
+			))
+			.await;
+			return Err(e);
+		}
+
+		// Stamped only once the session checks out, so requests for sessions that
+		// were never opened cannot crowd real ones out of the phase timings
+		let received_at = Instant::now();
+
 		// Validate the public key format first
 		super::security::PairingSecurity::validate_public_key(&public_key)?;
 		self.log_info(&format!(
@@ -30,6 +137,30 @@ impl PairingProtocolHandler {
 		))
 		.await;
 
+		// Allowlisted devices still prove their key with the challenge below, but
+		// need no code from the user to get there
+		let auto_accepted = self.is_auto_accepted_requester(&public_key).await;
+		if auto_accepted {
+			self.log_info(&format!(
+				"Device {} is on the pairing allowlist, auto-accepting session {}",
+				from_device, session_id
+			))
+			.await;
+		} else if !self.pairing_codes.read().await.contains_key(&session_id) {
+			// Anyone else can only join a session the user opened by generating a code
+			self.log_warn(&format!(
+				"Refusing pairing request from device {}: no open session {}",
+				from_device, session_id
+			))
+			.await;
+			return Err(NetworkingError::Protocol(format!(
+				"No open pairing session {}",
+				session_id
+			)));
+		}
+
+		let handshake_id = self.handshake_session_id(session_id, &public_key).await;
+
 		// Generate challenge
 		let challenge = self.generate_challenge()?;
 		self.log_debug(&format!(
@@ -48,7 +179,53 @@ impl PairingProtocolHandler {
 		))
 		.await;
 
-		if let Some(existing_session) = sessions.get_mut(&session_id) {
+		// Bound how many unfinished sessions one peer can hold, so repeated
+		// requests cannot grow the session table until cleanup catches up
+		let open_from_peer = sessions
+			.values()
+			.filter(|s| s.id != handshake_id && s.remote_device_id == Some(from_device))
+			.filter(|s| {
+				!matches!(
+					s.state,
+					PairingState::Completed | PairingState::Failed { .. }
+				)
+			})
+			.count();
+		if open_from_peer >= MAX_OPEN_SESSIONS_PER_PEER {
+			drop(sessions);
+			self.log_warn(&format!(
+				"Refusing pairing session {} from device {}: {} sessions already open",
+				session_id, from_device, open_from_peer
+			))
+			.await;
+			return Err(NetworkingError::Protocol(format!(
+				"Too many open pairing sessions from device {}",
+				from_device
+			)));
+		}
+
+		// A single-use code admits only the first joiner; that joiner may still retry
+		let is_new_joiner =
+			sessions.get(&handshake_id).and_then(|s| s.remote_device_id) != Some(from_device);
+		if is_new_joiner {
+			let consumed = self
+				.pairing_codes
+				.write()
+				.await
+				.get_mut(&session_id)
+				.and_then(|code| code.record_use().err());
+			if let Some(e) = consumed {
+				drop(sessions);
+				self.log_warn(&format!(
+					"Refusing pairing request from device {}: {}",
+					from_device, e
+				))
+				.await;
+				return Err(e);
+			}
+		}
+
+		if let Some(existing_session) = sessions.get_mut(&handshake_id) {
 			self.log_debug(&format!(
 				"INITIATOR_HANDLER_DEBUG: Found existing session {} in state {:?}",
 				session_id, existing_session.state
@@ -67,6 +244,7 @@ impl PairingProtocolHandler {
 			existing_session.remote_device_id = Some(from_device);
 			existing_session.remote_device_info = Some(device_info.clone());
 			existing_session.remote_public_key = Some(public_key.clone());
+			existing_session.auto_accepted = auto_accepted;
 		} else {
 			self.log_debug(&format!(
 				"INITIATOR_HANDLER_DEBUG: No existing session found for {}, creating new session",
@@ -81,7 +259,7 @@ impl PairingProtocolHandler {
 
 			// Create new session only if none exists
 			let session = PairingSession {
-				id: session_id,
+				id: handshake_id,
 				state: PairingState::ChallengeReceived {
 					challenge: challenge.clone(),
 				},
@@ -90,11 +268,15 @@ impl PairingProtocolHandler {
 				remote_public_key: Some(public_key.clone()),
 				shared_secret: None,
 				created_at: chrono::Utc::now(),
+				via_relay: false,
+				auto_accepted,
 			};
 
-			sessions.insert(session_id, session);
+			sessions.insert(handshake_id, session);
 		}
-		// Write lock is automatically released here
+		drop(sessions);
+		self.record_pairing_step_at(handshake_id, PairingStep::Request, received_at)
+			.await;
 
 		// Send challenge response with proper network fingerprint
 		let local_device_info = self.get_device_info().await.map_err(|e| {
@@ -104,7 +286,7 @@ impl PairingProtocolHandler {
 		let response = PairingMessage::Challenge {
 			session_id,
 			challenge: challenge.clone(),
-			device_info: local_device_info,
+			device_info: self.pre_pairing_device_info(local_device_info).await,
 		};
 
 		self.log_info(&format!(
@@ -113,6 +295,8 @@ impl PairingProtocolHandler {
 			challenge.len()
 		))
 		.await;
+		self.record_pairing_step(handshake_id, PairingStep::Challenge)
+			.await;
 		serde_json::to_vec(&response).map_err(|e| NetworkingError::Serialization(e))
 	}
 
@@ -125,12 +309,19 @@ impl PairingProtocolHandler {
 		response: Vec<u8>,
 		device_info: DeviceInfo,
 	) -> Result<Vec<u8>> {
+		let received_at = Instant::now();
+		let handshake_id = self
+			.find_handshake_session_id(session_id, |session| {
+				session.remote_device_id == Some(from_device)
+			})
+			.await;
+
 		// Get session and validate state
 		let session = self
 			.active_sessions
 			.read()
 			.await
-			.get(&session_id)
+			.get(&handshake_id)
 			.cloned()
 			.ok_or_else(|| NetworkingError::Protocol("Session not found".to_string()))?;
 
@@ -143,6 +334,8 @@ impl PairingProtocolHandler {
 				)))
 			}
 		};
+		self.record_pairing_step_at(handshake_id, PairingStep::Response, received_at)
+			.await;
 
 		// Get joiner's public key (stored during pairing request)
 		let device_public_key = session
@@ -174,10 +367,11 @@ impl PairingProtocolHandler {
 			// Mark session as failed
 			{
 				let mut sessions = self.active_sessions.write().await;
-				if let Some(session) = sessions.get_mut(&session_id) {
-					session.state = PairingState::Failed {
-						reason: "Invalid challenge signature".to_string(),
-					};
+				if let Some(session) = sessions.get_mut(&handshake_id) {
+					session.state = PairingState::failed_with(
+						PairingFailureReason::SignatureInvalid,
+						"Invalid challenge signature",
+					);
 				}
 			}
 
@@ -202,7 +396,7 @@ impl PairingProtocolHandler {
 		// This ensures vouching uses the joiner's authoritative device info
 		{
 			let mut sessions = self.active_sessions.write().await;
-			if let Some(session) = sessions.get_mut(&session_id) {
+			if let Some(session) = sessions.get_mut(&handshake_id) {
 				session.remote_device_info = Some(device_info.clone());
 				self.log_debug(&format!(
 					"Updated session {} with joiner's device info (node_id: {})",
@@ -213,7 +407,9 @@ impl PairingProtocolHandler {
 		}
 
 		// Signature is valid - complete pairing on Initiator's side
-		let shared_secret = self.generate_shared_secret(session_id).await?;
+		let shared_secret = self
+			.generate_shared_secret(session_id, &device_public_key, &device_public_key)
+			.await?;
 		let session_keys = SessionKeys::from_shared_secret(shared_secret.clone());
 
 		let actual_device_id = device_info.device_id;
@@ -236,7 +432,7 @@ impl PairingProtocolHandler {
 			let node_addr = iroh::EndpointAddr::new(node_id);
 
 			registry
-				.start_pairing(actual_device_id, node_id, session_id, node_addr)
+				.start_pairing(actual_device_id, node_id, handshake_id, node_addr)
 				.map_err(|e| {
 					self.log_warn(&format!(
 						"Warning: Could not register device in Pairing state: {}",
@@ -299,7 +495,7 @@ impl PairingProtocolHandler {
 		// Update session to Completed
 		{
 			let mut sessions = self.active_sessions.write().await;
-			if let Some(session) = sessions.get_mut(&session_id) {
+			if let Some(session) = sessions.get_mut(&handshake_id) {
 				session.state = PairingState::Completed;
 				session.shared_secret = Some(shared_secret);
 				session.remote_device_id = Some(actual_device_id);
@@ -310,10 +506,15 @@ impl PairingProtocolHandler {
 				.await;
 			}
 		}
+		self.record_pairing_step(handshake_id, PairingStep::Complete)
+			.await;
 
 		// Initialize proxy pairing session for vouching UI (best-effort, don't break pairing if it fails)
-		match self.create_vouching_session(session_id, &device_info).await {
-			Ok(()) => {
+		match self
+			.create_vouching_session(handshake_id, &device_info)
+			.await
+		{
+			Ok(_) => {
 				self.log_info(&format!(
 					"Created vouching session for pairing {}",
 					session_id
@@ -348,4 +549,26 @@ impl PairingProtocolHandler {
 			NetworkingError::Serialization(e)
 		})
 	}
+
+	/// Handle the joiner's full device info after pairing, replying with our own
+	pub(crate) async fn handle_device_info_update(
+		&self,
+		session_id: Uuid,
+		device_info: DeviceInfo,
+		remote_node_id: EndpointId,
+	) -> Result<Vec<u8>> {
+		let handshake_id = self
+			.find_handshake_session_id(session_id, |session| {
+				session.remote_device_id == Some(device_info.device_id)
+			})
+			.await;
+		self.apply_device_info_update(handshake_id, device_info, remote_node_id)
+			.await?;
+
+		let response = PairingMessage::DeviceInfoUpdate {
+			session_id,
+			device_info: self.get_device_info().await?,
+		};
+		serde_json::to_vec(&response).map_err(NetworkingError::Serialization)
+	}
 }
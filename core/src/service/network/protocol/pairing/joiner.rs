@@ -2,7 +2,8 @@
 
 use super::{
 	messages::PairingMessage,
-	types::{PairingSession, PairingState},
+	metrics::PairingStep,
+	types::{PairingFailureReason, PairingSession, PairingState},
 	PairingProtocolHandler,
 };
 use crate::service::network::{
@@ -26,6 +27,8 @@ impl PairingProtocolHandler {
 			challenge.len()
 		))
 		.await;
+		self.record_pairing_step(session_id, PairingStep::Challenge)
+			.await;
 
 		// Sign the challenge
 		self.log_debug("About to sign challenge...").await;
@@ -94,13 +97,15 @@ impl PairingProtocolHandler {
 		let response = PairingMessage::Response {
 			session_id,
 			response: signature,
-			device_info,
+			device_info: self.pre_pairing_device_info(device_info).await,
 		};
 
 		self.log_debug("About to serialize response...").await;
 		let serialized =
 			serde_json::to_vec(&response).map_err(|e| NetworkingError::Serialization(e))?;
 
+		self.record_pairing_step(session_id, PairingStep::Response)
+			.await;
 		self.log_info(&format!(
 			"handle_pairing_challenge SUCCESS - returning {} bytes, waiting for Complete message",
 			serialized.len()
@@ -144,7 +149,13 @@ impl PairingProtocolHandler {
 			};
 
 			// Generate shared secret and session keys
-			let shared_secret = self.generate_shared_secret(session_id).await?;
+			let shared_secret = self
+				.generate_shared_secret(
+					session_id,
+					&self.identity.public_key_bytes(),
+					from_node.as_bytes(),
+				)
+				.await?;
 			// Joiner swaps keys so that initiator's send_key = joiner's receive_key
 			let session_keys = SessionKeys::from_shared_secret(shared_secret.clone()).swap_keys();
 
@@ -246,6 +257,8 @@ impl PairingProtocolHandler {
 					)));
 				}
 			}
+			self.record_pairing_step(session_id, PairingStep::Complete)
+				.await;
 
 			self.log_info("Pairing completed successfully with cryptographic confirmation")
 				.await;
@@ -254,9 +267,10 @@ impl PairingProtocolHandler {
 			let failure_reason = reason.unwrap_or_else(|| "Pairing failed".to_string());
 			let mut sessions = self.active_sessions.write().await;
 			if let Some(session) = sessions.get_mut(&session_id) {
-				session.state = PairingState::Failed {
-					reason: failure_reason.clone(),
-				};
+				session.state = PairingState::failed_with(
+					PairingFailureReason::RemoteRejected,
+					failure_reason.clone(),
+				);
 				self.log_error(&format!(
 					"Session {} marked as failed: {}",
 					session_id, failure_reason
@@ -273,4 +287,60 @@ impl PairingProtocolHandler {
 
 		Ok(())
 	}
+
+	/// Swap full device info with the initiator after a privacy-mode pairing
+	///
+	/// Only runs when we or the initiator withheld details during the handshake.
+	/// Failures are logged and leave the pairing itself intact.
+	pub(crate) async fn reveal_device_info(&self, session_id: Uuid, initiator_node_id: EndpointId) {
+		let initiator_minimized = {
+			let sessions = self.active_sessions.read().await;
+			sessions
+				.get(&session_id)
+				.and_then(|session| session.remote_device_info.as_ref())
+				.is_some_and(DeviceInfo::is_minimized)
+		};
+		let privacy_mode = self.proxy_config.read().await.privacy_mode;
+		if !privacy_mode && !initiator_minimized {
+			return;
+		}
+
+		if let Err(e) = self
+			.exchange_device_info(session_id, initiator_node_id)
+			.await
+		{
+			self.log_warn(&format!(
+				"Failed to exchange full device info for session {}: {}",
+				session_id, e
+			))
+			.await;
+		}
+	}
+
+	async fn exchange_device_info(
+		&self,
+		session_id: Uuid,
+		initiator_node_id: EndpointId,
+	) -> Result<()> {
+		let endpoint = self.endpoint.as_ref().ok_or_else(|| {
+			NetworkingError::ConnectionFailed("No endpoint available".to_string())
+		})?;
+
+		let request = PairingMessage::DeviceInfoUpdate {
+			session_id,
+			device_info: self.get_device_info().await?,
+		};
+		match self
+			.send_pairing_message_to_node(endpoint, initiator_node_id, &request)
+			.await?
+		{
+			Some(PairingMessage::DeviceInfoUpdate { device_info, .. }) => {
+				self.apply_device_info_update(session_id, device_info, initiator_node_id)
+					.await
+			}
+			_ => Err(NetworkingError::Protocol(
+				"Expected DeviceInfoUpdate in reply".to_string(),
+			)),
+		}
+	}
 }
@@ -0,0 +1,109 @@
+//! In-memory transport for running pairing between handlers in one process
+//!
+//! Stands in for the networking event loop: `SendMessageToNode` commands are
+//! delivered straight to the target handler, and each reply is carried back the
+//! way the pairing stream would carry it. No endpoint or sockets are involved,
+//! so a full request → challenge → response → complete handshake runs in
+//! milliseconds.
+
+use super::{PairingMessage, PairingProtocolHandler};
+use crate::service::network::{
+	core::event_loop::EventLoopCommand, protocol::Protocol, NetworkingError, Result,
+};
+use iroh::EndpointId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+
+/// Routes pairing messages between in-process handlers
+#[derive(Clone, Default)]
+pub struct LoopbackTransport {
+	handlers: Arc<RwLock<HashMap<EndpointId, Arc<PairingProtocolHandler>>>>,
+}
+
+impl LoopbackTransport {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Attach a handler under its own node id
+	///
+	/// `commands` must be the receiving end of the channel the handler's
+	/// `CommandSender` was created with. The returned task drains it until every
+	/// sender is dropped.
+	pub async fn attach(
+		&self,
+		handler: Arc<PairingProtocolHandler>,
+		mut commands: mpsc::Receiver<EventLoopCommand>,
+	) -> JoinHandle<()> {
+		let node_id = handler.identity.node_id();
+		self.handlers.write().await.insert(node_id, handler);
+
+		let transport = self.clone();
+		tokio::spawn(async move {
+			while let Some(command) = commands.recv().await {
+				match command {
+					EventLoopCommand::SendMessageToNode {
+						node_id: to,
+						protocol,
+						data,
+					} if protocol == Protocol::Pairing => {
+						if let Err(e) = transport.deliver(node_id, to, data).await {
+							tracing::warn!(
+								"Loopback delivery from {} to {} failed: {}",
+								node_id,
+								to,
+								e
+							);
+						}
+					}
+					other => {
+						tracing::debug!("Loopback transport ignoring {:?}", other);
+					}
+				}
+			}
+		})
+	}
+
+	/// Deliver a message from `from` to `to`, then pass replies back and forth
+	/// until one side has nothing more to send
+	pub async fn deliver(&self, from: EndpointId, to: EndpointId, data: Vec<u8>) -> Result<()> {
+		let (mut from, mut to, mut data) = (from, to, data);
+		loop {
+			let handler = self
+				.handlers
+				.read()
+				.await
+				.get(&to)
+				.cloned()
+				.ok_or_else(|| {
+					NetworkingError::ConnectionFailed(format!(
+						"No loopback handler for node {}",
+						to
+					))
+				})?;
+
+			let message: PairingMessage =
+				serde_json::from_slice(&data).map_err(NetworkingError::Serialization)?;
+			let session_id = message.session_id();
+			let reply = match handler.handle_pairing_message(message, from).await {
+				Ok(reply) => reply,
+				// Like the stream, report errors the sender can act on
+				Err(e) => match PairingMessage::error_reply(session_id, &e) {
+					Some(error) => {
+						Some(serde_json::to_vec(&error).map_err(NetworkingError::Serialization)?)
+					}
+					None => return Err(e),
+				},
+			};
+			match reply {
+				Some(reply) => {
+					std::mem::swap(&mut from, &mut to);
+					data = reply;
+				}
+				None => return Ok(()),
+			}
+		}
+	}
+}
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::proxy::{AcceptedDevice, RejectedDevice};
-use crate::service::network::device::{DeviceInfo, SessionKeys};
+use super::types::PairingFailureReason;
+use crate::service::network::device::DeviceInfo;
+use crate::service::network::NetworkingError;
 
 /// Messages exchanged during the pairing protocol
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,7 +43,6 @@ pub enum PairingMessage {
 		voucher_device_id: Uuid,
 		voucher_signature: Vec<u8>,
 		timestamp: chrono::DateTime<chrono::Utc>,
-		proxied_session_keys: SessionKeys,
 	},
 	// Other device -> Voucher: "I accept or reject this vouch"
 	ProxyPairingResponse {
@@ -56,5 +57,127 @@ pub enum PairingMessage {
 		voucher_device_id: Uuid,
 		accepted_by: Vec<AcceptedDevice>,
 		rejected_by: Vec<RejectedDevice>,
+		// Voucher's signature over the rest of the message; missing from older vouchers,
+		// which then fail verification instead of the whole message failing to parse
+		#[serde(default)]
+		voucher_signature: Vec<u8>,
+	},
+	// Vouchee -> Voucher and target: "I declined the pairing this device accepted"
+	ProxyPairingDeclined {
+		session_id: Uuid,
+		vouchee_device_id: Uuid,
+		declined_device_id: Uuid,
+	},
+	// Full device info, exchanged after pairing completes when either side used privacy mode
+	DeviceInfoUpdate {
+		session_id: Uuid,
+		device_info: DeviceInfo,
+	},
+	// Paired device -> paired device: "Rotate our session keys using this nonce"
+	RekeyRequest {
+		session_id: Uuid,
+		nonce: Vec<u8>,
+		proof: Vec<u8>, // Proof of the current shared secret, bound to the nonce
+	},
+	// Reply once the new keys are staged, proving they match
+	RekeyConfirm {
+		session_id: Uuid,
+		proof: Vec<u8>, // Proof of the new shared secret, bound to the same nonce
 	},
+	// Re-key starter -> peer: "I switched, switch to the staged keys too"
+	RekeyCommit {
+		session_id: Uuid,
+		proof: Vec<u8>, // Commit proof of the new shared secret, bound to the same nonce
+	},
+	// Reply when a message could not be handled, so the sender fails fast instead of timing out
+	Error {
+		session_id: Uuid,
+		code: PairingFailureReason,
+		message: String,
+	},
+}
+
+impl PairingMessage {
+	/// Most devices a `ProxyPairingComplete` may list as accepting or rejecting
+	pub const MAX_PROXY_OUTCOMES: usize = 256;
+
+	/// Pairing or vouching session this message belongs to
+	pub fn session_id(&self) -> Uuid {
+		match self {
+			Self::PairingRequest { session_id, .. }
+			| Self::Challenge { session_id, .. }
+			| Self::Response { session_id, .. }
+			| Self::Complete { session_id, .. }
+			| Self::ProxyPairingRequest { session_id, .. }
+			| Self::ProxyPairingResponse { session_id, .. }
+			| Self::ProxyPairingComplete { session_id, .. }
+			| Self::ProxyPairingDeclined { session_id, .. }
+			| Self::DeviceInfoUpdate { session_id, .. }
+			| Self::RekeyRequest { session_id, .. }
+			| Self::RekeyConfirm { session_id, .. }
+			| Self::RekeyCommit { session_id, .. }
+			| Self::Error { session_id, .. } => *session_id,
+		}
+	}
+
+	/// Check each device info the message carries with [`DeviceInfo::validate_with_max_size`]
+	///
+	/// Run on every incoming message before its device info is stored or signed.
+	/// A `ProxyPairingComplete` listing more than [`Self::MAX_PROXY_OUTCOMES`]
+	/// devices is rejected too, so its total size stays bounded.
+	pub fn validate_device_info(&self, max_size: usize) -> Result<(), NetworkingError> {
+		match self {
+			Self::PairingRequest { device_info, .. }
+			| Self::Challenge { device_info, .. }
+			| Self::Response { device_info, .. }
+			| Self::DeviceInfoUpdate { device_info, .. } => device_info.validate_with_max_size(max_size),
+			Self::ProxyPairingRequest {
+				vouchee_device_info,
+				..
+			} => vouchee_device_info.validate_with_max_size(max_size),
+			Self::ProxyPairingComplete {
+				accepted_by,
+				rejected_by,
+				..
+			} => {
+				if accepted_by.len() + rejected_by.len() > Self::MAX_PROXY_OUTCOMES {
+					return Err(NetworkingError::Protocol(format!(
+						"Proxy pairing completion lists {} devices, more than the {} allowed",
+						accepted_by.len() + rejected_by.len(),
+						Self::MAX_PROXY_OUTCOMES
+					)));
+				}
+				accepted_by
+					.iter()
+					.try_for_each(|accepted| accepted.device_info.validate_with_max_size(max_size))
+			}
+			Self::Complete { .. }
+			| Self::ProxyPairingResponse { .. }
+			| Self::ProxyPairingDeclined { .. }
+			| Self::RekeyRequest { .. }
+			| Self::RekeyConfirm { .. }
+			| Self::RekeyCommit { .. }
+			| Self::Error { .. } => Ok(()),
+		}
+	}
+
+	/// Error reply telling the peer why its message failed
+	///
+	/// None for fatal errors (a broken stream or a message we could not parse),
+	/// after which the stream is closed without a reply. Everything else, such as
+	/// a session in the wrong state, an unknown or expired session, or a failed
+	/// proof, is reported and the stream stays open.
+	pub fn error_reply(session_id: Uuid, error: &NetworkingError) -> Option<Self> {
+		match error {
+			NetworkingError::ConnectionFailed(_)
+			| NetworkingError::Transport(_)
+			| NetworkingError::Io(_)
+			| NetworkingError::Serialization(_) => None,
+			_ => Some(Self::Error {
+				session_id,
+				code: PairingFailureReason::from(error),
+				message: error.to_string(),
+			}),
+		}
+	}
 }
@@ -0,0 +1,177 @@
+//! Timing of pairing handshake phases
+//!
+//! Each side stamps the handshake steps it sees for a session. When the session
+//! completes, the gaps between the stamps are folded into one histogram per phase
+//! and the stamps are dropped, so a slow challenge verification shows up in the
+//! diagnostics without keeping anything about the sessions themselves.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Upper bounds of the histogram buckets in milliseconds
+///
+/// A final bucket counts everything slower than the last bound.
+pub const PHASE_BUCKET_BOUNDS_MS: [u64; 8] = [5, 25, 100, 250, 1_000, 2_500, 10_000, 30_000];
+
+/// Most unfinished sessions stamped at once; the oldest is dropped beyond this
+const MAX_TRACKED_SESSIONS: usize = 64;
+
+/// Step of the handshake, as seen from either side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum PairingStep {
+	/// Request sent (joiner) or received (initiator)
+	Request,
+	/// Challenge received (joiner) or sent (initiator)
+	Challenge,
+	/// Response sent (joiner) or received (initiator)
+	Response,
+	/// Session marked completed
+	Complete,
+}
+
+/// Gap between two consecutive handshake steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum PairingPhase {
+	RequestToChallenge,
+	ChallengeToResponse,
+	ResponseToComplete,
+}
+
+impl PairingPhase {
+	const ALL: [PairingPhase; 3] = [
+		PairingPhase::RequestToChallenge,
+		PairingPhase::ChallengeToResponse,
+		PairingPhase::ResponseToComplete,
+	];
+
+	fn steps(self) -> (PairingStep, PairingStep) {
+		match self {
+			PairingPhase::RequestToChallenge => (PairingStep::Request, PairingStep::Challenge),
+			PairingPhase::ChallengeToResponse => (PairingStep::Challenge, PairingStep::Response),
+			PairingPhase::ResponseToComplete => (PairingStep::Response, PairingStep::Complete),
+		}
+	}
+}
+
+/// Durations of one phase across completed sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct PhaseHistogram {
+	pub phase: PairingPhase,
+	pub count: u64,
+	pub total_ms: f64,
+	pub min_ms: Option<f64>,
+	pub max_ms: Option<f64>,
+	/// Counts per bucket of `PHASE_BUCKET_BOUNDS_MS`, plus one for slower phases
+	pub buckets: Vec<u64>,
+}
+
+impl PhaseHistogram {
+	fn new(phase: PairingPhase) -> Self {
+		Self {
+			phase,
+			count: 0,
+			total_ms: 0.0,
+			min_ms: None,
+			max_ms: None,
+			buckets: vec![0; PHASE_BUCKET_BOUNDS_MS.len() + 1],
+		}
+	}
+
+	fn record(&mut self, duration: Duration) {
+		let ms = duration.as_secs_f64() * 1_000.0;
+		self.count += 1;
+		self.total_ms += ms;
+		self.min_ms = Some(self.min_ms.map_or(ms, |min| min.min(ms)));
+		self.max_ms = Some(self.max_ms.map_or(ms, |max| max.max(ms)));
+		let bucket = PHASE_BUCKET_BOUNDS_MS
+			.iter()
+			.position(|bound| duration <= Duration::from_millis(*bound))
+			.unwrap_or(PHASE_BUCKET_BOUNDS_MS.len());
+		self.buckets[bucket] += 1;
+	}
+}
+
+/// Phase timings aggregated over completed pairings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct PairingTimings {
+	pub completed_sessions: u64,
+	pub phases: Vec<PhaseHistogram>,
+}
+
+struct SessionStamps {
+	first_seen: Instant,
+	steps: HashMap<PairingStep, Instant>,
+}
+
+/// Per-session step stamps and the phase histograms they feed
+pub struct PairingMetrics {
+	in_flight: HashMap<Uuid, SessionStamps>,
+	completed_sessions: u64,
+	phases: Vec<PhaseHistogram>,
+}
+
+impl Default for PairingMetrics {
+	fn default() -> Self {
+		Self {
+			in_flight: HashMap::new(),
+			completed_sessions: 0,
+			phases: PairingPhase::ALL
+				.into_iter()
+				.map(PhaseHistogram::new)
+				.collect(),
+		}
+	}
+}
+
+impl PairingMetrics {
+	/// Stamp a step of a session, aggregating its phases once it completes
+	///
+	/// A repeated step keeps its first stamp. Phases missing a stamp on either
+	/// end, e.g. for a session this side joined halfway, are left out.
+	pub(crate) fn record_step(&mut self, session_id: Uuid, step: PairingStep, at: Instant) {
+		if !self.in_flight.contains_key(&session_id) && self.in_flight.len() >= MAX_TRACKED_SESSIONS
+		{
+			if let Some(oldest) = self
+				.in_flight
+				.iter()
+				.min_by_key(|(_, stamps)| stamps.first_seen)
+				.map(|(id, _)| *id)
+			{
+				self.in_flight.remove(&oldest);
+			}
+		}
+
+		let stamps = self
+			.in_flight
+			.entry(session_id)
+			.or_insert_with(|| SessionStamps {
+				first_seen: at,
+				steps: HashMap::new(),
+			});
+		stamps.steps.entry(step).or_insert(at);
+
+		if step != PairingStep::Complete {
+			return;
+		}
+		let Some(stamps) = self.in_flight.remove(&session_id) else {
+			return;
+		};
+		self.completed_sessions += 1;
+		for histogram in &mut self.phases {
+			let (from, to) = histogram.phase.steps();
+			if let (Some(from), Some(to)) = (stamps.steps.get(&from), stamps.steps.get(&to)) {
+				histogram.record(to.saturating_duration_since(*from));
+			}
+		}
+	}
+
+	pub fn snapshot(&self) -> PairingTimings {
+		PairingTimings {
+			completed_sessions: self.completed_sessions,
+			phases: self.phases.clone(),
+		}
+	}
+}
@@ -1,50 +1,72 @@
 //! Pairing protocol handler
 
+pub mod diagnostics;
+pub mod history;
 pub mod initiator;
 pub mod joiner;
+#[cfg(test)]
+mod loopback;
 pub mod messages;
+pub mod metrics;
 pub mod persistence;
 pub mod proxy;
+pub mod rekey;
 pub mod security;
 pub mod types;
 pub mod vouching_queue;
 
-/// Maximum message size for pairing protocol (1MB)
-/// Prevents DoS attacks via oversized message claims
-const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+/// State machine ticks a response may wait on a full command channel before the
+/// session is failed (~5s at the 200ms tick interval)
+const MAX_FULL_CHANNEL_RETRIES: u32 = 25;
+
+/// Most vouch targets `start_proxy_vouching` dispatches at once
+const MAX_CONCURRENT_VOUCH_DISPATCH: usize = 8;
+
+/// Reason given for vouches that fail because the handler has no endpoint
+const ENDPOINT_UNAVAILABLE: &str = "networking endpoint unavailable";
 
 // Re-export main types
+pub use diagnostics::{PairingSessionSnapshot, PairingStateDump, VouchingSessionSnapshot};
+pub use history::{PairingHistoryEntry, PairingOutcome, PAIRING_HISTORY_CAPACITY};
 pub use messages::PairingMessage;
+pub use metrics::{PairingPhase, PairingTimings, PhaseHistogram};
+pub use persistence::{FilePersistenceBackend, MemoryPersistenceBackend, PersistenceBackend};
 pub use proxy::{
-	AcceptedDevice, RejectedDevice, VouchPayload, VouchState, VouchStatus, VouchingSession,
-	VouchingSessionState,
+	AcceptedDevice, ProxyCompletionPayload, ProxyConfirmationOutcome, RejectedDevice,
+	SignatureScheme, VouchPayload, VouchState, VouchStatus, VouchingSession, VouchingSessionState,
+};
+pub use types::{
+	DeviceTrustChange, EndpointAddrInfo, JoinOutcome, PairingAdvertisement, PairingCode,
+	PairingFailureReason, PairingRole, PairingSession, PairingState, PrunedVouches, UnpairedDevice,
 };
-pub use types::{PairingAdvertisement, PairingCode, PairingRole, PairingSession, PairingState};
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use blake3;
 use iroh::{endpoint::Connection, Endpoint, EndpointAddr, EndpointId, Watcher};
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-use super::{ProtocolEvent, ProtocolHandler};
+use super::{Protocol, ProtocolEvent, ProtocolHandler};
 use crate::{
 	config::app_config::ProxyPairingConfig,
-	infra::event::{Event, EventBus, ResourceMetadata},
+	infra::event::{Event, EventBus, ProxiedPairingFailure, ResourceMetadata},
 	service::network::{
-		device::{DeviceInfo, DeviceRegistry, SessionKeys},
+		core::{CommandSendError, CommandSender},
+		device::{DeviceInfo, DeviceRegistry, SessionKeys, TrustLevel},
 		utils::{self, identity::NetworkFingerprint, logging::NetworkLogger, NetworkIdentity},
 		NetworkingError, Result,
 	},
 };
-use bincode::config::standard;
-use bincode::serde::encode_to_vec;
 use persistence::PairingPersistence;
 use security::PairingSecurity;
+pub use vouching_queue::ProxyPairingAuditEntry;
 use vouching_queue::{VouchQueueStatus, VouchingQueue, VouchingQueueEntry};
 
 /// Pairing protocol handler
@@ -65,9 +87,10 @@ pub struct PairingProtocolHandler {
 	logger: Arc<dyn NetworkLogger>,
 
 	/// Command sender for dispatching commands to the NetworkingEventLoop
-	command_sender: tokio::sync::mpsc::UnboundedSender<
-		crate::service::network::core::event_loop::EventLoopCommand,
-	>,
+	command_sender: CommandSender,
+
+	/// Consecutive state machine ticks a session's response found the command channel full
+	full_channel_retries: Arc<RwLock<HashMap<Uuid, u32>>>,
 
 	/// Current pairing role
 	role: Option<PairingRole>,
@@ -90,14 +113,46 @@ pub struct PairingProtocolHandler {
 	/// Active proxy vouching sessions
 	vouching_sessions: Arc<RwLock<HashMap<Uuid, VouchingSession>>>,
 
+	/// Cancels the vouches `start_proxy_vouching` is still dispatching, by session
+	vouch_dispatches: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
+
 	/// Pending proxy confirmations awaiting user action
 	pending_proxy_confirmations: Arc<RwLock<HashMap<Uuid, PendingProxyConfirmation>>>,
 
+	/// Proxy confirmations already answered (accepted, decided at), so repeats are no-ops
+	///
+	/// Only written while the `pending_proxy_confirmations` lock is held, which makes
+	/// taking a pending confirmation and recording its decision one step.
+	proxy_confirmation_decisions: Arc<RwLock<HashMap<Uuid, (bool, chrono::DateTime<chrono::Utc>)>>>,
+
+	/// Last local device info read from the registry, used if a later read fails
+	last_device_info: Arc<RwLock<Option<DeviceInfo>>>,
+
+	/// Devices that accepted a vouch for us, awaiting our confirmation, by (session, device)
+	pending_proxied_devices: Arc<RwLock<HashMap<(Uuid, Uuid), PendingProxiedDevice>>>,
+
 	/// Persistent queue for offline vouches
 	vouching_queue: Arc<RwLock<Option<Arc<VouchingQueue>>>>,
 
-	/// Cached vouchee session keys for proxy pairing completion
-	vouching_keys: Arc<RwLock<HashMap<(Uuid, Uuid), SessionKeys>>>,
+	/// Re-keys we started and are waiting to have confirmed, by rekey session id
+	pending_rekeys: Arc<RwLock<HashMap<Uuid, rekey::PendingRekey>>>,
+
+	/// Keys a peer may still hold after an unfinished re-key, by device id
+	rekey_fallbacks: Arc<RwLock<HashMap<Uuid, rekey::RekeyFallback>>>,
+
+	/// Outcomes of recently finished sessions, kept after the sessions are dropped
+	///
+	/// Locked after `active_sessions` when both are needed.
+	pairing_history: Arc<RwLock<history::PairingHistory>>,
+
+	/// Handshake phase timings for diagnostics
+	pairing_metrics: Arc<RwLock<metrics::PairingMetrics>>,
+
+	/// Signals the background tasks to stop after their current iteration
+	shutdown_token: CancellationToken,
+
+	/// Handles of the spawned background tasks, awaited on shutdown
+	task_handles: std::sync::Mutex<Vec<JoinHandle<()>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -111,18 +166,32 @@ struct PendingProxyConfirmation {
 	created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// A device that accepted a vouch for us, held until we confirm it
+#[derive(Debug, Clone)]
+struct PendingProxiedDevice {
+	voucher_device_id: Uuid,
+	device_info: DeviceInfo,
+	session_keys: SessionKeys,
+	created_at: chrono::DateTime<chrono::Utc>,
+}
+
 impl PairingProtocolHandler {
 	/// Create a new pairing protocol handler
 	pub fn new(
 		identity: NetworkIdentity,
 		device_registry: Arc<RwLock<DeviceRegistry>>,
 		logger: Arc<dyn NetworkLogger>,
-		command_sender: tokio::sync::mpsc::UnboundedSender<
-			crate::service::network::core::event_loop::EventLoopCommand,
-		>,
+		command_sender: CommandSender,
 		endpoint: Option<Endpoint>,
 		active_connections: Arc<RwLock<HashMap<(EndpointId, Vec<u8>), Connection>>>,
 	) -> Self {
+		// Every handler built without persistence behaves the same, so warn once per process
+		static WARN_NO_PERSISTENCE: std::sync::Once = std::sync::Once::new();
+		WARN_NO_PERSISTENCE.call_once(|| {
+			tracing::warn!(
+				"Pairing session persistence is disabled; pairing sessions will not survive a restart"
+			);
+		});
 		Self {
 			identity,
 			device_registry,
@@ -130,6 +199,7 @@ impl PairingProtocolHandler {
 			pairing_codes: Arc::new(RwLock::new(HashMap::new())),
 			logger,
 			command_sender,
+			full_channel_retries: Arc::new(RwLock::new(HashMap::new())),
 			role: None,
 			persistence: None,
 			endpoint,
@@ -137,9 +207,18 @@ impl PairingProtocolHandler {
 			event_bus: Arc::new(RwLock::new(None)),
 			proxy_config: Arc::new(RwLock::new(ProxyPairingConfig::default())),
 			vouching_sessions: Arc::new(RwLock::new(HashMap::new())),
+			vouch_dispatches: Arc::new(RwLock::new(HashMap::new())),
 			pending_proxy_confirmations: Arc::new(RwLock::new(HashMap::new())),
+			proxy_confirmation_decisions: Arc::new(RwLock::new(HashMap::new())),
+			last_device_info: Arc::new(RwLock::new(None)),
+			pending_proxied_devices: Arc::new(RwLock::new(HashMap::new())),
 			vouching_queue: Arc::new(RwLock::new(None)),
-			vouching_keys: Arc::new(RwLock::new(HashMap::new())),
+			pending_rekeys: Arc::new(RwLock::new(HashMap::new())),
+			rekey_fallbacks: Arc::new(RwLock::new(HashMap::new())),
+			pairing_history: Arc::new(RwLock::new(history::PairingHistory::default())),
+			pairing_metrics: Arc::new(RwLock::new(metrics::PairingMetrics::default())),
+			shutdown_token: CancellationToken::new(),
+			task_handles: std::sync::Mutex::new(Vec::new()),
 		}
 	}
 
@@ -148,14 +227,33 @@ impl PairingProtocolHandler {
 		identity: NetworkIdentity,
 		device_registry: Arc<RwLock<DeviceRegistry>>,
 		logger: Arc<dyn NetworkLogger>,
-		command_sender: tokio::sync::mpsc::UnboundedSender<
-			crate::service::network::core::event_loop::EventLoopCommand,
-		>,
+		command_sender: CommandSender,
 		data_dir: PathBuf,
 		endpoint: Option<Endpoint>,
 		active_connections: Arc<RwLock<HashMap<(EndpointId, Vec<u8>), Connection>>>,
 	) -> Self {
-		let persistence = Arc::new(PairingPersistence::new(data_dir));
+		Self::new_with_persistence_backend(
+			identity,
+			device_registry,
+			logger,
+			command_sender,
+			Arc::new(FilePersistenceBackend::new(data_dir)),
+			endpoint,
+			active_connections,
+		)
+	}
+
+	/// Create a new pairing protocol handler persisting sessions through `backend`
+	pub fn new_with_persistence_backend(
+		identity: NetworkIdentity,
+		device_registry: Arc<RwLock<DeviceRegistry>>,
+		logger: Arc<dyn NetworkLogger>,
+		command_sender: CommandSender,
+		backend: Arc<dyn PersistenceBackend>,
+		endpoint: Option<Endpoint>,
+		active_connections: Arc<RwLock<HashMap<(EndpointId, Vec<u8>), Connection>>>,
+	) -> Self {
+		let persistence = Arc::new(PairingPersistence::with_backend(backend));
 		Self {
 			identity,
 			device_registry,
@@ -163,6 +261,7 @@ impl PairingProtocolHandler {
 			pairing_codes: Arc::new(RwLock::new(HashMap::new())),
 			logger,
 			command_sender,
+			full_channel_retries: Arc::new(RwLock::new(HashMap::new())),
 			role: None,
 			persistence: Some(persistence),
 			endpoint,
@@ -170,12 +269,29 @@ impl PairingProtocolHandler {
 			event_bus: Arc::new(RwLock::new(None)),
 			proxy_config: Arc::new(RwLock::new(ProxyPairingConfig::default())),
 			vouching_sessions: Arc::new(RwLock::new(HashMap::new())),
+			vouch_dispatches: Arc::new(RwLock::new(HashMap::new())),
 			pending_proxy_confirmations: Arc::new(RwLock::new(HashMap::new())),
+			proxy_confirmation_decisions: Arc::new(RwLock::new(HashMap::new())),
+			last_device_info: Arc::new(RwLock::new(None)),
+			pending_proxied_devices: Arc::new(RwLock::new(HashMap::new())),
 			vouching_queue: Arc::new(RwLock::new(None)),
-			vouching_keys: Arc::new(RwLock::new(HashMap::new())),
+			pending_rekeys: Arc::new(RwLock::new(HashMap::new())),
+			rekey_fallbacks: Arc::new(RwLock::new(HashMap::new())),
+			pairing_history: Arc::new(RwLock::new(history::PairingHistory::default())),
+			pairing_metrics: Arc::new(RwLock::new(metrics::PairingMetrics::default())),
+			shutdown_token: CancellationToken::new(),
+			task_handles: std::sync::Mutex::new(Vec::new()),
 		}
 	}
 
+	/// Whether pairing sessions are persisted across restarts
+	///
+	/// Only handlers built with [`Self::new_with_persistence`] or
+	/// [`Self::new_with_persistence_backend`] persist sessions.
+	pub fn persistence_enabled(&self) -> bool {
+		self.persistence.is_some()
+	}
+
 	/// Initialize sessions from persistence (call after construction)
 	pub async fn load_persisted_sessions(&self) -> Result<usize> {
 		if let Some(persistence) = &self.persistence {
@@ -212,17 +328,75 @@ impl PairingProtocolHandler {
 	}
 
 	pub fn start_vouching_queue_task(handler: Arc<Self>) {
-		tokio::spawn(async move {
-			let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
-			loop {
-				interval.tick().await;
-				if let Err(e) = handler.process_vouching_queue().await {
-					handler
-						.log_error(&format!("Vouching queue error: {}", e))
-						.await;
-				}
-			}
+		let shutdown = handler.shutdown_token.clone();
+		let tracker = handler.clone();
+		let handle = tokio::spawn(async move {
+			run_periodic(
+				shutdown,
+				|| {
+					let handler = handler.clone();
+					async move { handler.vouch_queue_poll_interval().await }
+				},
+				|| {
+					let handler = handler.clone();
+					async move {
+						if let Err(e) = handler.process_vouching_queue().await {
+							handler
+								.log_error(&format!("Vouching queue error: {}", e))
+								.await;
+						}
+					}
+				},
+			)
+			.await;
 		});
+		tracker.track_task(handle);
+	}
+
+	/// Current vouching queue poll interval from the proxy pairing config
+	async fn vouch_queue_poll_interval(&self) -> Duration {
+		let secs = self.proxy_config.read().await.vouch_queue_poll_secs;
+		Duration::from_secs(secs.max(1))
+	}
+
+	/// Current expired-session cleanup interval from the proxy pairing config
+	async fn session_cleanup_poll_interval(&self) -> Duration {
+		let secs = self.proxy_config.read().await.session_cleanup_poll_secs;
+		Duration::from_secs(secs.max(1))
+	}
+
+	/// Record a spawned background task so `shutdown` can wait for it
+	fn track_task(&self, handle: JoinHandle<()>) {
+		self.task_handles
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner())
+			.push(handle);
+	}
+
+	/// Stop all background tasks and flush sessions to persistence
+	///
+	/// Each task finishes its current iteration before exiting, so no session
+	/// update is cut off halfway. Returns once every task has stopped and the
+	/// sessions have been written.
+	pub async fn shutdown(&self) -> Result<()> {
+		self.shutdown_token.cancel();
+
+		let handles = std::mem::take(
+			&mut *self
+				.task_handles
+				.lock()
+				.unwrap_or_else(|poisoned| poisoned.into_inner()),
+		);
+		for handle in handles {
+			if let Err(e) = handle.await {
+				self.log_warn(&format!("Pairing background task ended abnormally: {}", e))
+					.await;
+			}
+		}
+
+		self.save_sessions_to_persistence().await?;
+		self.log_info("Pairing protocol handler shut down").await;
+		Ok(())
 	}
 
 	/// Save current sessions to persistence
@@ -298,6 +472,16 @@ impl PairingProtocolHandler {
 		session_id: Uuid,
 		pairing_code: PairingCode,
 	) -> Result<()> {
+		// Caller-provided session IDs must not clobber a session already in flight
+		if self.active_sessions.read().await.contains_key(&session_id)
+			|| self.pairing_codes.read().await.contains_key(&session_id)
+		{
+			return Err(NetworkingError::Protocol(format!(
+				"Pairing session {} already exists",
+				session_id
+			)));
+		}
+
 		let session = PairingSession {
 			id: session_id,
 			state: PairingState::WaitingForConnection,
@@ -306,6 +490,8 @@ impl PairingProtocolHandler {
 			remote_public_key: None,
 			shared_secret: None,
 			created_at: chrono::Utc::now(),
+			via_relay: false,
+			auto_accepted: false,
 		};
 
 		self.active_sessions
@@ -349,6 +535,10 @@ impl PairingProtocolHandler {
 			));
 		}
 
+		// Reject weak codes even if they have not expired, guarding against downgrades
+		let min_entropy_bits = { self.proxy_config.read().await.min_code_entropy_bits };
+		pairing_code.validate_entropy(min_entropy_bits)?;
+
 		// Check if session already exists to prevent conflicts
 		{
 			let sessions = self.active_sessions.read().await;
@@ -369,6 +559,8 @@ impl PairingProtocolHandler {
 			remote_public_key: None,
 			shared_secret: None,
 			created_at: chrono::Utc::now(),
+			via_relay: false,
+			auto_accepted: false,
 		};
 
 		// Insert the session
@@ -418,9 +610,30 @@ impl PairingProtocolHandler {
 	}
 
 	/// Get device info for advertising in DHT records
+	///
+	/// Falls back to the last info read successfully if the registry lookup fails,
+	/// so a transient error doesn't abort advertising or vouching.
 	pub async fn get_device_info(&self) -> Result<DeviceInfo> {
 		// Get device info from device registry (which uses device manager)
-		let mut device_info = self.device_registry.read().await.get_local_device_info()?;
+		let local_device_info = self.device_registry.read().await.get_local_device_info();
+		let mut device_info = match local_device_info {
+			Ok(device_info) => {
+				*self.last_device_info.write().await = Some(device_info.clone());
+				device_info
+			}
+			Err(e) => {
+				let cached = { self.last_device_info.read().await.clone() };
+				let Some(device_info) = cached else {
+					return Err(e);
+				};
+				self.log_warn(&format!(
+					"Failed to read local device info, using cached info: {}",
+					e
+				))
+				.await;
+				device_info
+			}
+		};
 
 		// Update network fingerprint with current identity
 		device_info.network_fingerprint = self.identity.network_fingerprint();
@@ -429,14 +642,298 @@ impl PairingProtocolHandler {
 		Ok(device_info)
 	}
 
+	/// Device info to show a peer we have not completed pairing with
+	///
+	/// In privacy mode only the name, slug and fingerprint are revealed. The full
+	/// info follows in a [`PairingMessage::DeviceInfoUpdate`] once pairing completes.
+	pub async fn pre_pairing_device_info(&self, device_info: DeviceInfo) -> DeviceInfo {
+		if self.proxy_config.read().await.privacy_mode {
+			device_info.minimized()
+		} else {
+			device_info
+		}
+	}
+
+	/// Whether a pairing requester is on the auto-accept allowlist
+	///
+	/// Listed devices may pair without the user generating a code first. Entries
+	/// match the hex public key itself or its network fingerprint hash, in either
+	/// the full or the 16-byte form. The match is made against the key the
+	/// requester must later sign the challenge with, never against the device info
+	/// it claims. An empty allowlist accepts no one.
+	async fn is_auto_accepted_requester(&self, public_key: &[u8]) -> bool {
+		let config = self.proxy_config.read().await;
+		if config.auto_accept_pairing_from.is_empty() {
+			return false;
+		}
+
+		let key_hex = hex::encode(public_key);
+		let key_hash = blake3::hash(public_key).to_hex();
+		config.auto_accept_pairing_from.iter().any(|entry| {
+			let entry = entry.to_lowercase();
+			entry == key_hex || entry == key_hash.as_str() || entry == key_hash[..32]
+		})
+	}
+
+	/// Build the joiner's initial pairing request for a session
+	pub async fn build_pairing_request(
+		&self,
+		session_id: Uuid,
+		device_info: DeviceInfo,
+	) -> PairingMessage {
+		self.record_pairing_step(session_id, metrics::PairingStep::Request)
+			.await;
+		PairingMessage::PairingRequest {
+			session_id,
+			device_info: self.pre_pairing_device_info(device_info).await,
+			public_key: self.identity.public_key_bytes(),
+		}
+	}
+
+	/// Accept a peer's full device info after pairing
+	///
+	/// Iroh authenticates the node id of every connection, so the update is only
+	/// taken if it arrives from the node the session paired with and describes the
+	/// same device.
+	async fn apply_device_info_update(
+		&self,
+		session_id: Uuid,
+		device_info: DeviceInfo,
+		remote_node_id: EndpointId,
+	) -> Result<()> {
+		let paired_device_id = {
+			let sessions = self.active_sessions.read().await;
+			let session = sessions.get(&session_id).ok_or_else(|| {
+				NetworkingError::Protocol(format!("Pairing session not found: {}", session_id))
+			})?;
+			if !matches!(session.state, PairingState::Completed) {
+				return Err(NetworkingError::Protocol(
+					"Pairing session is not completed".to_string(),
+				));
+			}
+			session.remote_device_id
+		};
+
+		if paired_device_id != Some(device_info.device_id) {
+			return Err(NetworkingError::Protocol(
+				"Device info does not match the paired device".to_string(),
+			));
+		}
+		let from_paired_node = device_info
+			.network_fingerprint
+			.node_id
+			.parse::<EndpointId>()
+			.is_ok_and(|node_id| node_id == remote_node_id);
+		if !from_paired_node {
+			return Err(NetworkingError::Protocol(
+				"Device info update did not come from the paired node".to_string(),
+			));
+		}
+
+		self.device_registry
+			.write()
+			.await
+			.update_device_info(device_info.device_id, device_info.clone())
+			.await?;
+		if let Some(session) = self.active_sessions.write().await.get_mut(&session_id) {
+			session.remote_device_info = Some(device_info.clone());
+		}
+
+		self.log_info(&format!(
+			"Received full device info for {} after pairing session {}",
+			device_info.device_id, session_id
+		))
+		.await;
+		Ok(())
+	}
+
 	/// Cancel a pairing session
 	pub async fn cancel_session(&self, session_id: Uuid) -> Result<()> {
-		self.active_sessions.write().await.remove(&session_id);
+		{
+			let mut sessions = self.active_sessions.write().await;
+			if let Some(session) = sessions.remove(&session_id) {
+				let mut history = self.pairing_history.write().await;
+				if !history.record_if_finished(&session) {
+					history.record(&session, PairingOutcome::Cancelled);
+				}
+			}
+		}
 		self.pairing_codes.write().await.remove(&session_id);
 		self.save_sessions_to_persistence().await?;
+
+		// Drop any vouches still queued for this session
+		let queue = { self.vouching_queue.read().await.clone() };
+		if let Some(queue) = queue {
+			queue.remove_entries_for_session(session_id).await?;
+		}
 		Ok(())
 	}
 
+	/// Fail a session whose joiner gave up while still scanning
+	///
+	/// Leaves the session `Failed` with a timeout, so status and history show
+	/// why it ended instead of an endless scan. Sessions that already moved on
+	/// are left alone. Returns the session's state afterwards.
+	pub async fn abandon_scanning_session(&self, session_id: Uuid) -> Option<PairingState> {
+		let state = {
+			let mut sessions = self.active_sessions.write().await;
+			let session = sessions.get_mut(&session_id)?;
+			if matches!(session.state, PairingState::Scanning) {
+				session.state = PairingState::failed_with(
+					PairingFailureReason::Timeout,
+					"The initiating device did not answer in time",
+				);
+				self.pairing_history
+					.write()
+					.await
+					.record_if_finished(session);
+			}
+			session.state.clone()
+		};
+
+		if let Err(e) = self.save_sessions_to_persistence().await {
+			self.log_warn(&format!(
+				"Failed to persist abandoned pairing session {}: {}",
+				session_id, e
+			))
+			.await;
+		}
+		Some(state)
+	}
+
+	/// Forget an established pairing with a device
+	///
+	/// Removes the device from the registry and its persisted pairing record, which
+	/// is where its session keys live, then drops any queued vouches involving it.
+	pub async fn unpair_device(&self, device_id: Uuid) -> Result<UnpairedDevice> {
+		let was_paired = {
+			let mut registry = self.device_registry.write().await;
+			registry.remove_device(device_id)?;
+			registry.remove_paired_device(device_id).await?
+		};
+
+		let queue = { self.vouching_queue.read().await.clone() };
+		let dropped_vouches = match queue {
+			Some(queue) => queue.remove_entries_for_device(device_id).await?,
+			None => 0,
+		};
+
+		self.log_info(&format!(
+			"Unpaired device {} (dropped {} queued vouches)",
+			device_id, dropped_vouches
+		))
+		.await;
+
+		Ok(UnpairedDevice {
+			was_paired,
+			dropped_vouches,
+		})
+	}
+
+	/// Change how far a paired device is trusted
+	///
+	/// Voucher eligibility is read from persistence on every proxy pairing
+	/// request, so the change applies to the next vouch. Auto-vouch skips any
+	/// device no longer trusted. Downgrading a device to unreliable drops queued
+	/// vouches addressed to it; blocking it drops every queued vouch involving it.
+	pub async fn set_device_trust(
+		&self,
+		device_id: Uuid,
+		trust_level: TrustLevel,
+	) -> Result<DeviceTrustChange> {
+		let (previous, persisted) = {
+			let registry = self.device_registry.read().await;
+			let previous = registry.set_device_trust(device_id, trust_level).await?;
+			(
+				previous,
+				registry.persistence().get_paired_device(device_id).await?,
+			)
+		};
+
+		let dropped_vouches = match (trust_level, self.vouching_queue.read().await.clone()) {
+			(TrustLevel::Blocked, Some(queue)) => {
+				queue.remove_entries_for_device(device_id).await?
+			}
+			(TrustLevel::Unreliable, Some(queue)) => {
+				queue.remove_entries_for_target(device_id).await?
+			}
+			_ => 0,
+		};
+
+		let change = DeviceTrustChange {
+			previous,
+			current: trust_level,
+			can_vouch: persisted.is_some_and(|device| device.can_vouch()),
+			dropped_vouches,
+		};
+		self.log_info(&format!(
+			"Trust of device {} changed from {:?} to {:?} (can vouch: {}, dropped {} queued vouches)",
+			device_id, previous, trust_level, change.can_vouch, dropped_vouches
+		))
+		.await;
+
+		Ok(change)
+	}
+
+	/// Clear queued vouches on an operator's request
+	///
+	/// Always drops expired vouches, plus every vouch of `session_id` and every
+	/// vouch addressed to `target_device_id` when given. Lets a stuck queue be
+	/// cleared without deleting the queue database.
+	pub async fn prune_vouching_queue(
+		&self,
+		session_id: Option<Uuid>,
+		target_device_id: Option<Uuid>,
+	) -> Result<PrunedVouches> {
+		let queue = { self.vouching_queue.read().await.clone() };
+		let Some(queue) = queue else {
+			return Ok(PrunedVouches::default());
+		};
+
+		let now = chrono::Utc::now();
+		let entries = queue.list_entries().await?;
+		let mut pruned = PrunedVouches {
+			expired: queue.remove_expired(now).await?,
+			..Default::default()
+		};
+		if let Some(session_id) = session_id {
+			pruned.for_session = queue.remove_entries_for_session(session_id).await?;
+		}
+		if let Some(target_device_id) = target_device_id {
+			pruned.for_target = queue.remove_entries_for_target(target_device_id).await?;
+		}
+
+		// Pruned vouches will never be sent, so stop showing them as pending
+		for entry in entries {
+			let reason = if entry.expires_at <= now {
+				"Vouch expired"
+			} else if session_id == Some(entry.session_id)
+				|| target_device_id == Some(entry.target_device_id)
+			{
+				"Removed from the vouching queue"
+			} else {
+				continue;
+			};
+			if self.get_vouching_session(entry.session_id).await.is_some() {
+				self.update_vouch_status(
+					entry.session_id,
+					entry.target_device_id,
+					VouchStatus::Unreachable,
+					Some(reason.to_string()),
+				)
+				.await?;
+			}
+		}
+
+		self.log_info(&format!(
+			"Pruned vouching queue ({} expired, {} for session, {} for target)",
+			pruned.expired, pruned.for_session, pruned.for_target
+		))
+		.await;
+
+		Ok(pruned)
+	}
+
 	/// Get active pairing sessions
 	pub async fn get_active_sessions(&self) -> Vec<PairingSession> {
 		let sessions = {
@@ -446,6 +943,35 @@ impl PairingProtocolHandler {
 		sessions
 	}
 
+	/// Recently finished pairing sessions, most recent first
+	pub async fn get_pairing_history(&self) -> Vec<PairingHistoryEntry> {
+		self.pairing_history.read().await.entries()
+	}
+
+	/// Handshake phase timings aggregated over completed pairings
+	pub async fn get_pairing_timings(&self) -> PairingTimings {
+		self.pairing_metrics.read().await.snapshot()
+	}
+
+	/// Stamp a handshake step of a session for the phase timings
+	pub(crate) async fn record_pairing_step(&self, session_id: Uuid, step: metrics::PairingStep) {
+		self.record_pairing_step_at(session_id, step, std::time::Instant::now())
+			.await;
+	}
+
+	/// Stamp a handshake step that happened at `at`, for steps only stamped once validated
+	pub(crate) async fn record_pairing_step_at(
+		&self,
+		session_id: Uuid,
+		step: metrics::PairingStep,
+		at: std::time::Instant,
+	) {
+		self.pairing_metrics
+			.write()
+			.await
+			.record_step(session_id, step, at);
+	}
+
 	/// Clean up expired pairing sessions
 	pub async fn cleanup_expired_sessions(&self) -> Result<usize> {
 		let now = chrono::Utc::now();
@@ -465,10 +991,49 @@ impl PairingProtocolHandler {
 		}
 
 		// Remove expired sessions and their pairing codes
+		let mut history = self.pairing_history.write().await;
 		for session_id in &sessions_to_remove {
-			sessions.remove(session_id);
+			if let Some(session) = sessions.remove(session_id) {
+				if !history.record_if_finished(&session) {
+					history.record(
+						&session,
+						PairingOutcome::Failed {
+							reason: PairingFailureReason::Timeout,
+							detail: Some("Session expired".to_string()),
+						},
+					);
+				}
+			}
 			pairing_codes.remove(session_id);
 		}
+		drop(history);
+
+		self.proxy_confirmation_decisions
+			.write()
+			.await
+			.retain(|_, (_, decided_at)| {
+				now.signed_duration_since(*decided_at) <= timeout_duration
+			});
+
+		let expired_rekeys = self.cleanup_expired_rekeys().await;
+		if expired_rekeys > 0 {
+			self.log_warn(&format!(
+				"Dropped {} re-keys that were never confirmed",
+				expired_rekeys
+			))
+			.await;
+		}
+
+		drop(sessions);
+		drop(pairing_codes);
+		let expired_confirmations = self.expire_pending_proxied_devices().await;
+		if expired_confirmations > 0 {
+			self.log_warn(&format!(
+				"Declined {} proxied devices that were never confirmed",
+				expired_confirmations
+			))
+			.await;
+		}
 
 		let cleaned_count = sessions_to_remove.len();
 		if cleaned_count > 0 {
@@ -485,29 +1050,50 @@ impl PairingProtocolHandler {
 	/// Start a background task to periodically clean up expired sessions
 	pub fn start_cleanup_task(handler: Arc<Self>) {
 		let logger = handler.logger.clone();
-		tokio::spawn(async move {
-			let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60)); // Check every minute
-
-			loop {
-				interval.tick().await;
-
-				if let Err(e) = handler.cleanup_expired_sessions().await {
-					logger
-						.error(&format!("Error during session cleanup: {}", e))
-						.await;
-				}
-			}
+		let shutdown = handler.shutdown_token.clone();
+		let tracker = handler.clone();
+		let handle = tokio::spawn(async move {
+			run_periodic(
+				shutdown,
+				|| {
+					let handler = handler.clone();
+					async move { handler.session_cleanup_poll_interval().await }
+				},
+				|| {
+					let handler = handler.clone();
+					let logger = logger.clone();
+					async move {
+						if let Err(e) = handler.cleanup_expired_sessions().await {
+							logger
+								.error(&format!("Error during session cleanup: {}", e))
+								.await;
+						}
+						if let Err(e) = handler.expire_vouching_sessions().await {
+							logger
+								.error(&format!("Error expiring vouching sessions: {}", e))
+								.await;
+						}
+					}
+				},
+			)
+			.await;
 		});
+		tracker.track_task(handle);
 	}
 
 	/// Start the background task for managing pairing state transitions
 	pub fn start_state_machine_task(handler: Arc<Self>) {
-		tokio::spawn(async move {
+		let shutdown = handler.shutdown_token.clone();
+		let tracker = handler.clone();
+		let handle = tokio::spawn(async move {
 			// Check the state every 200 milliseconds
 			let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(200));
 
 			loop {
-				interval.tick().await;
+				tokio::select! {
+					_ = shutdown.cancelled() => break,
+					_ = interval.tick() => {}
+				}
 				if let Err(e) = handler.process_state_transitions().await {
 					handler
 						.log_error(&format!("State machine error: {}", e))
@@ -515,6 +1101,7 @@ impl PairingProtocolHandler {
 				}
 			}
 		});
+		tracker.track_task(handle);
 	}
 
 	/// The core logic of the state machine - processes state transitions for all active sessions
@@ -540,24 +1127,52 @@ impl PairingProtocolHandler {
 						// Create the command to send the message
 						let command = crate::service::network::core::event_loop::EventLoopCommand::SendMessageToNode {
                             node_id: *node_id,
-                            protocol: "pairing".to_string(),
+                            protocol: Protocol::Pairing,
                             data: response_data.clone(),
                         };
 
-						// Send the command to the NetworkingEventLoop
-						if self.command_sender.send(command).is_ok() {
-							// Transition the state to prevent re-sending
-							session.state = PairingState::ResponseSent;
-							self.log_info(&format!(
-                                "State Machine: Response sent for session {}, transitioned to ResponseSent",
-                                session.id
-                            )).await;
-						} else {
-							self.log_error("State Machine: Failed to send command to event loop.")
+						// Send the command to the NetworkingEventLoop without blocking the state machine
+						match self.command_sender.try_send(command) {
+							Ok(()) => {
+								self.full_channel_retries.write().await.remove(&session.id);
+								// Transition the state to prevent re-sending
+								session.state = PairingState::ResponseSent;
+								self.log_info(&format!(
+                                    "State Machine: Response sent for session {}, transitioned to ResponseSent",
+                                    session.id
+                                )).await;
+							}
+							Err(CommandSendError::Full) => {
+								// Leave the session in ResponsePending and retry on the next tick
+								let mut retries = self.full_channel_retries.write().await;
+								let count = retries.entry(session.id).or_insert(0);
+								*count += 1;
+								if *count >= MAX_FULL_CHANNEL_RETRIES {
+									retries.remove(&session.id);
+									self.log_error(&format!(
+										"State Machine: Command channel full for session {}, giving up",
+										session.id
+									))
+									.await;
+									session.state =
+										PairingState::failed(PairingFailureReason::ChannelFull);
+								} else {
+									self.log_debug(&format!(
+										"State Machine: Command channel full for session {}, retrying ({}/{})",
+										session.id, count, MAX_FULL_CHANNEL_RETRIES
+									))
+									.await;
+								}
+							}
+							Err(CommandSendError::Closed) => {
+								self.full_channel_retries.write().await.remove(&session.id);
+								self.log_error(
+									"State Machine: Failed to send command to event loop.",
+								)
 								.await;
-							session.state = PairingState::Failed {
-								reason: "Internal channel closed".to_string(),
-							};
+								session.state =
+									PairingState::failed(PairingFailureReason::ChannelClosed);
+							}
 						}
 					} else {
 						self.log_error(&format!(
@@ -565,9 +1180,10 @@ impl PairingProtocolHandler {
 							session.id
 						))
 						.await;
-						session.state = PairingState::Failed {
-							reason: "No remote node ID for response".to_string(),
-						};
+						session.state = PairingState::failed_with(
+							PairingFailureReason::InternalError,
+							"No remote node ID for response",
+						);
 					}
 				}
 
@@ -581,9 +1197,10 @@ impl PairingProtocolHandler {
 							session.id
 						))
 						.await;
-						session.state = PairingState::Failed {
-							reason: "Scanning timeout".to_string(),
-						};
+						session.state = PairingState::failed_with(
+							PairingFailureReason::Timeout,
+							"Scanning timeout",
+						);
 					}
 				}
 
@@ -594,6 +1211,12 @@ impl PairingProtocolHandler {
 			}
 		}
 
+		// Copy finished sessions into the history while they are still around
+		let mut history = self.pairing_history.write().await;
+		for session in sessions.values() {
+			history.record_if_finished(session);
+		}
+
 		Ok(())
 	}
 
@@ -604,15 +1227,57 @@ impl PairingProtocolHandler {
 		Ok(challenge)
 	}
 
-	/// Generate shared secret for a pairing session using the pairing code secret
-	async fn generate_shared_secret(&self, session_id: Uuid) -> Result<Vec<u8>> {
-		let pairing_codes = self.pairing_codes.read().await;
-		let pairing_code = pairing_codes.get(&session_id).ok_or_else(|| {
-			NetworkingError::Protocol(format!("No pairing code found for session {}", session_id))
-		})?;
+	/// Generate shared secret for a pairing session
+	///
+	/// Sessions opened with a pairing code derive it from the code secret. An
+	/// allowlisted device pairs without a code, so both sides fall back to the key
+	/// agreement between their identities with `peer_public_key`.
+	///
+	/// The secret is bound to the joiner's key as well as the session, so every
+	/// joiner of a multi-use code ends up with keys of its own.
+	async fn generate_shared_secret(
+		&self,
+		session_id: Uuid,
+		joiner_public_key: &[u8],
+		peer_public_key: &[u8],
+	) -> Result<Vec<u8>> {
+		let code_secret = {
+			let pairing_codes = self.pairing_codes.read().await;
+			pairing_codes.get(&session_id).map(|code| *code.secret())
+		};
+		let base_secret = match code_secret {
+			Some(secret) => secret,
+			None => self.identity.shared_secret_with(peer_public_key)?,
+		};
+
+		// Bind the secret to this joiner's handshake so it cannot be reused for another one
+		PairingSecurity::derive_session_secret(
+			&base_secret,
+			Uuid::new_v5(&session_id, joiner_public_key),
+		)
+	}
 
-		// Use the pairing code secret as the shared secret
-		Ok(pairing_code.secret().to_vec())
+	/// Whether vouching `vouchee_device_id` to or through `device_id` would close a trust loop
+	///
+	/// That is the case when the vouchee is `device_id` itself or one of the devices
+	/// that introduced `device_id` to us, following the stored `vouched_by` links.
+	async fn vouch_creates_cycle(&self, vouchee_device_id: Uuid, device_id: Uuid) -> Result<bool> {
+		if vouchee_device_id == device_id {
+			return Ok(true);
+		}
+
+		let persistence = {
+			let registry = self.device_registry.read().await;
+			registry.persistence()
+		};
+		let vouched_by = persistence
+			.load_paired_devices()
+			.await?
+			.into_iter()
+			.filter_map(|(id, device)| device.vouched_by.map(|voucher| (id, voucher)))
+			.collect();
+
+		Ok(proxy::proxy_source_chain(device_id, &vouched_by).contains(&vouchee_device_id))
 	}
 
 	fn build_vouch_payload(
@@ -632,10 +1297,9 @@ impl PairingProtocolHandler {
 	}
 
 	fn sign_vouch_payload(&self, payload: &VouchPayload) -> Result<Vec<u8>> {
-		let serialized = encode_to_vec(payload, standard()).map_err(|e| {
-			NetworkingError::Protocol(format!("Failed to serialize vouch payload: {}", e))
-		})?;
-		self.identity.sign(&serialized)
+		let scheme = SignatureScheme::Ed25519;
+		let signature = self.identity.sign(&scheme.signed_message(payload))?;
+		Ok(scheme.encode_signature(&signature))
 	}
 
 	fn verify_vouch_signature(
@@ -644,11 +1308,57 @@ impl PairingProtocolHandler {
 		signature: &[u8],
 		public_key_bytes: &[u8],
 	) -> Result<bool> {
+		if signature.len() == proxy::LEGACY_VOUCH_SIGNATURE_LEN {
+			return Self::verify_ed25519(&payload.canonical_bytes(), signature, public_key_bytes);
+		}
+		self.verify_signature(
+			|scheme| scheme.signed_message(payload),
+			signature,
+			public_key_bytes,
+		)
+	}
+
+	fn sign_proxy_completion(&self, payload: &ProxyCompletionPayload) -> Result<Vec<u8>> {
+		let scheme = SignatureScheme::Ed25519;
+		let signature = self
+			.identity
+			.sign(&scheme.signed_completion_message(payload))?;
+		Ok(scheme.encode_signature(&signature))
+	}
+
+	fn verify_proxy_completion_signature(
+		&self,
+		payload: &ProxyCompletionPayload,
+		signature: &[u8],
+		public_key_bytes: &[u8],
+	) -> Result<bool> {
+		self.verify_signature(
+			|scheme| scheme.signed_completion_message(payload),
+			signature,
+			public_key_bytes,
+		)
+	}
+
+	/// Verify a scheme-tagged signature over the message `signed_message` builds
+	fn verify_signature(
+		&self,
+		signed_message: impl FnOnce(SignatureScheme) -> Vec<u8>,
+		signature: &[u8],
+		public_key_bytes: &[u8],
+	) -> Result<bool> {
+		let (scheme, signature) = SignatureScheme::decode_signature(signature)
+			.map_err(|e| NetworkingError::Protocol(e.to_string()))?;
+		let message = signed_message(scheme);
+
+		match scheme {
+			SignatureScheme::Ed25519 => Self::verify_ed25519(&message, signature, public_key_bytes),
+		}
+	}
+
+	/// Verify a raw Ed25519 signature over `message`
+	fn verify_ed25519(message: &[u8], signature: &[u8], public_key_bytes: &[u8]) -> Result<bool> {
 		PairingSecurity::validate_public_key(public_key_bytes)?;
 		PairingSecurity::validate_signature(signature)?;
-		let serialized = encode_to_vec(payload, standard()).map_err(|e| {
-			NetworkingError::Protocol(format!("Failed to serialize vouch payload: {}", e))
-		})?;
 
 		use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 		let verifying_key =
@@ -660,7 +1370,7 @@ impl PairingProtocolHandler {
 		let sig = Signature::from_slice(signature)
 			.map_err(|e| NetworkingError::Protocol(format!("Invalid signature: {}", e)))?;
 
-		Ok(verifying_key.verify(&serialized, &sig).is_ok())
+		Ok(verifying_key.verify(message, &sig).is_ok())
 	}
 
 	fn derive_proxy_shared_secret(
@@ -691,24 +1401,30 @@ impl PairingProtocolHandler {
 		Ok(derived.to_vec())
 	}
 
+	/// Session keys between the target and the vouchee of a proxied pairing
+	///
+	/// Both ends call this with the other's public key and get the same base secret
+	/// from the key agreement, so the keys never travel through the voucher.
+	/// Returns `(target_keys, vouchee_keys)`; each side keeps its own half.
 	fn derive_proxy_session_keys(
 		&self,
 		voucher_device_id: Uuid,
 		target_device_id: Uuid,
 		vouchee_device_id: Uuid,
 		vouchee_public_key: &[u8],
-		base_secret: &[u8],
+		peer_public_key: &[u8],
 	) -> Result<(SessionKeys, SessionKeys)> {
+		let base_secret = self.identity.shared_secret_with(peer_public_key)?;
 		let shared_secret = self.derive_proxy_shared_secret(
 			voucher_device_id,
 			target_device_id,
 			vouchee_device_id,
 			vouchee_public_key,
-			base_secret,
+			&base_secret,
 		)?;
-		let receiver_keys = SessionKeys::from_shared_secret(shared_secret);
-		let vouchee_keys = receiver_keys.clone().swap_keys();
-		Ok((receiver_keys, vouchee_keys))
+		let target_keys = SessionKeys::from_shared_secret(shared_secret);
+		let vouchee_keys = target_keys.clone().swap_keys();
+		Ok((target_keys, vouchee_keys))
 	}
 
 	async fn emit_vouching_session(&self, session: &VouchingSession) -> Result<()> {
@@ -740,11 +1456,6 @@ impl PairingProtocolHandler {
 		status: VouchStatus,
 		reason: Option<String>,
 	) -> Result<()> {
-		if matches!(status, VouchStatus::Rejected | VouchStatus::Unreachable) {
-			let mut keys = self.vouching_keys.write().await;
-			keys.remove(&(session_id, device_id));
-		}
-
 		let mut should_finalize = false;
 		let session_snapshot = {
 			let mut sessions = self.vouching_sessions.write().await;
@@ -805,41 +1516,31 @@ impl PairingProtocolHandler {
 		let mut accepted = Vec::new();
 		let mut rejected = Vec::new();
 
+		let accepted_ids = session
+			.vouches
+			.iter()
+			.filter(|vouch| matches!(vouch.status, VouchStatus::Accepted))
+			.map(|vouch| vouch.device_id)
+			.collect::<Vec<_>>();
+		let accepted_states = self
+			.device_registry
+			.read()
+			.await
+			.get_device_states(&accepted_ids);
+
 		for vouch in &session.vouches {
 			match vouch.status {
 				VouchStatus::Accepted => {
-					let device_info = {
-						let registry = self.device_registry.read().await;
-						match registry.get_device_state(vouch.device_id) {
-							Some(crate::service::network::device::DeviceState::Paired {
-								info,
-								..
-							})
-							| Some(crate::service::network::device::DeviceState::Connected {
-								info,
-								..
-							})
-							| Some(crate::service::network::device::DeviceState::Disconnected {
-								info,
-								..
-							}) => Some(info.clone()),
-							_ => None,
-						}
-					};
+					let device_info = accepted_states
+						.get(&vouch.device_id)
+						.and_then(|state| state.info())
+						.cloned();
 
-					let session_keys = {
-						let keys = self.vouching_keys.read().await;
-						keys.get(&(session_id, vouch.device_id)).cloned()
-					};
-
-					if let (Some(info), Some(keys)) = (device_info, session_keys) {
-						accepted.push(proxy::AcceptedDevice {
-							device_info: info,
-							session_keys: keys,
-						});
+					if let Some(info) = device_info {
+						accepted.push(proxy::AcceptedDevice { device_info: info });
 					} else {
 						self.log_warn(&format!(
-							"Missing device info or keys for accepted device {}",
+							"Missing device info for accepted device {}",
 							vouch.device_id
 						))
 						.await;
@@ -860,52 +1561,120 @@ impl PairingProtocolHandler {
 			}
 		}
 
+		// Nothing left to deliver for this session; clean up before notifying so a
+		// vouchee we cannot reach does not leave queue entries behind
+		self.schedule_vouching_cleanup(session_id).await;
+		let queue = { self.vouching_queue.read().await.clone() };
+		if let Some(queue) = queue {
+			queue.remove_entries_for_session(session_id).await?;
+		}
+
 		let vouchee_node_id = {
 			let registry = self.device_registry.read().await;
 			registry.get_node_id_for_device(session.vouchee_device_id)
 		};
 
-		if let Some(node_id) = vouchee_node_id {
-			let message = PairingMessage::ProxyPairingComplete {
-				session_id,
-				voucher_device_id: session.voucher_device_id,
-				accepted_by: accepted,
-				rejected_by: rejected,
-			};
-			self.send_pairing_message_fire_and_forget(node_id, &message)
-				.await?;
-		} else {
+		let Some(node_id) = vouchee_node_id else {
 			self.log_warn(&format!(
 				"No node ID for vouchee device {}, cannot send completion",
 				session.vouchee_device_id
 			))
 			.await;
-		}
+			return Ok(());
+		};
 
-		{
-			let mut keys = self.vouching_keys.write().await;
-			keys.retain(|(sid, _), _| *sid != session_id);
+		// The vouchee learns the outcome on a best-effort basis
+		let notified = match self.sign_proxy_completion(&ProxyCompletionPayload {
+			session_id,
+			voucher_device_id: session.voucher_device_id,
+			accepted_by: &accepted,
+			rejected_by: &rejected,
+		}) {
+			Ok(voucher_signature) => {
+				let message = PairingMessage::ProxyPairingComplete {
+					session_id,
+					voucher_device_id: session.voucher_device_id,
+					accepted_by: accepted,
+					rejected_by: rejected,
+					voucher_signature,
+				};
+				self.send_pairing_message_fire_and_forget(node_id, &message)
+					.await
+			}
+			Err(e) => Err(e),
+		};
+		if let Err(e) = notified {
+			self.log_warn(&format!(
+				"Failed to send completion of vouching session {} to device {}: {}",
+				session_id, session.vouchee_device_id, e
+			))
+			.await;
 		}
 
-		self.schedule_vouching_cleanup(session_id).await;
-
 		Ok(())
 	}
 
+	/// Finalize vouching sessions that are past their `expires_at` deadline
+	///
+	/// Vouches that have not reached a terminal status are marked unreachable so every
+	/// session ends up completed, even when some targets never come online.
+	pub async fn expire_vouching_sessions(&self) -> Result<usize> {
+		let now = chrono::Utc::now();
+		let expired = {
+			let mut sessions = self.vouching_sessions.write().await;
+			let mut expired = Vec::new();
+			for session in sessions.values_mut() {
+				if matches!(session.state, VouchingSessionState::Completed)
+					|| session.expires_at > now
+				{
+					continue;
+				}
+
+				for vouch in session.vouches.iter_mut() {
+					if !matches!(
+						vouch.status,
+						VouchStatus::Accepted | VouchStatus::Rejected | VouchStatus::Unreachable
+					) {
+						vouch.status = VouchStatus::Unreachable;
+						vouch.reason = Some("Vouching session expired".to_string());
+						vouch.updated_at = now;
+					}
+				}
+				session.state = VouchingSessionState::Completed;
+				expired.push(session.clone());
+			}
+			expired
+		};
+
+		for session in &expired {
+			self.log_warn(&format!(
+				"Vouching session {} expired, finalizing with pending vouches unreachable",
+				session.id
+			))
+			.await;
+			self.emit_vouching_session(session).await?;
+			// Keep going so one undeliverable completion does not hold up the rest
+			if let Err(e) = self.finalize_vouching_session(session.id).await {
+				self.log_error(&format!(
+					"Failed to finalize expired vouching session {}: {}",
+					session.id, e
+				))
+				.await;
+			}
+		}
+
+		Ok(expired.len())
+	}
+
 	async fn schedule_vouching_cleanup(&self, session_id: Uuid) {
 		let vouching_sessions = self.vouching_sessions.clone();
 		let event_bus = self.event_bus.clone();
-		let vouching_keys = self.vouching_keys.clone();
 		tokio::spawn(async move {
 			tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
 			{
 				let mut sessions = vouching_sessions.write().await;
 				sessions.remove(&session_id);
 			}
-			{
-				let mut keys = vouching_keys.write().await;
-				keys.retain(|(sid, _), _| *sid != session_id);
-			}
 
 			let event_bus = { event_bus.read().await.clone() };
 			if let Some(event_bus) = event_bus {
@@ -922,24 +1691,151 @@ impl PairingProtocolHandler {
 		sessions.get(&session_id).cloned()
 	}
 
+	/// Emit the current state of a vouching session again
+	///
+	/// Updates are only emitted on change, so a UI that reloads would otherwise wait
+	/// for the next one to learn the state. Returns false if the session does not exist.
+	pub async fn republish_vouching_session(&self, session_id: Uuid) -> Result<bool> {
+		let Some(session) = self.get_vouching_session(session_id).await else {
+			return Ok(false);
+		};
+		self.emit_vouching_session(&session).await?;
+		Ok(true)
+	}
+
+	/// Vouching sessions still held in memory, newest first
+	pub async fn list_vouching_sessions(&self) -> Vec<VouchingSession> {
+		let sessions = self.vouching_sessions.read().await;
+		let mut sessions: Vec<VouchingSession> = sessions.values().cloned().collect();
+		sessions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+		sessions
+	}
+
+	/// Abort a vouching session, e.g. a runaway auto-vouch
+	///
+	/// Drops the session and its queued vouches, so nothing more is delivered
+	/// for it. Targets that already accepted keep the
+	/// vouchee. Returns false if the session does not exist.
+	pub async fn cancel_vouching_session(&self, session_id: Uuid) -> Result<bool> {
+		// Stop a dispatch still running first, so it queues and sends nothing more
+		if let Some(dispatch) = self.vouch_dispatches.write().await.remove(&session_id) {
+			dispatch.cancel();
+		}
+		let removed = { self.vouching_sessions.write().await.remove(&session_id) };
+		if removed.is_none() {
+			return Ok(false);
+		}
+
+		let queue = { self.vouching_queue.read().await.clone() };
+		let dropped = match queue {
+			Some(queue) => queue.remove_entries_for_session(session_id).await?,
+			None => 0,
+		};
+
+		self.log_info(&format!(
+			"Cancelled vouching session {} ({} queued vouches dropped)",
+			session_id, dropped
+		))
+		.await;
+
+		let event_bus = { self.event_bus.read().await.clone() };
+		if let Some(event_bus) = event_bus {
+			event_bus.emit(Event::ResourceDeleted {
+				resource_type: "vouching_session".to_string(),
+				resource_id: session_id,
+			});
+		}
+
+		Ok(true)
+	}
+
+	/// Whether a vouching session was cancelled while its vouches were dispatched
+	///
+	/// Also true once the session is gone, which covers a cancel that landed
+	/// before the dispatch registered its token.
+	async fn vouch_dispatch_cancelled(
+		&self,
+		session_id: Uuid,
+		cancelled: &CancellationToken,
+	) -> bool {
+		cancelled.is_cancelled()
+			|| !self
+				.vouching_sessions
+				.read()
+				.await
+				.contains_key(&session_id)
+	}
+
+	/// Log and build the error for a vouching session refused by `max_vouching_sessions`
+	///
+	/// Every session in the map counts towards the cap, including completed ones that
+	/// are still waiting for their cleanup, so a burst of sessions cannot be made room
+	/// for by finishing them quickly.
+	async fn vouching_capacity_error(
+		&self,
+		session_id: Uuid,
+		max_sessions: usize,
+	) -> NetworkingError {
+		self.log_warn(&format!(
+			"Refusing vouching session {}: {} sessions already held",
+			session_id, max_sessions
+		))
+		.await;
+		NetworkingError::Protocol(format!(
+			"Too many vouching sessions (limit is {})",
+			max_sessions
+		))
+	}
+
+	/// Open the vouching session for a newly paired device
+	///
+	/// Repeated calls while the session has not completed return it unchanged, so a
+	/// duplicate trigger cannot drop the vouches it has gathered.
 	pub async fn create_vouching_session(
 		&self,
 		session_id: Uuid,
 		vouchee_device_info: &DeviceInfo,
-	) -> Result<()> {
+	) -> Result<VouchingSession> {
 		let voucher_device_id = self.get_device_info().await?.device_id;
+		let (max_sessions, session_ttl) = {
+			let config = self.proxy_config.read().await;
+			(
+				config.max_vouching_sessions,
+				config.vouching_session_ttl_secs,
+			)
+		};
+		let created_at = chrono::Utc::now();
 		let session = VouchingSession {
 			id: session_id,
 			vouchee_device_id: vouchee_device_info.device_id,
 			vouchee_device_name: vouchee_device_info.device_name.clone(),
 			voucher_device_id,
-			created_at: chrono::Utc::now(),
+			created_at,
+			expires_at: created_at + chrono::Duration::seconds(session_ttl as i64),
 			state: VouchingSessionState::Pending,
 			vouches: Vec::new(),
 		};
 
 		{
 			let mut sessions = self.vouching_sessions.write().await;
+			// A duplicate trigger must not reset a session that is still collecting vouches
+			if let Some(existing) = sessions
+				.get(&session_id)
+				.filter(|existing| !matches!(existing.state, VouchingSessionState::Completed))
+			{
+				let existing = existing.clone();
+				drop(sessions);
+				self.log_debug(&format!(
+					"Vouching session {} already exists, keeping it",
+					session_id
+				))
+				.await;
+				return Ok(existing);
+			}
+			if !sessions.contains_key(&session_id) && sessions.len() >= max_sessions {
+				drop(sessions);
+				return Err(self.vouching_capacity_error(session_id, max_sessions).await);
+			}
 			sessions.insert(session_id, session.clone());
 		}
 
@@ -955,18 +1851,14 @@ impl PairingProtocolHandler {
 
 		let proxy_config: ProxyPairingConfig = { self.proxy_config.read().await.clone() };
 		if proxy_config.auto_vouch_to_all {
-			let target_device_ids = {
-				let registry = self.device_registry.read().await;
-				registry
-					.get_paired_devices()
-					.into_iter()
-					.map(|device| device.device_id)
-					.filter(|device_id| {
-						*device_id != voucher_device_id
-							&& *device_id != vouchee_device_info.device_id
-					})
-					.collect::<Vec<_>>()
-			};
+			let target_device_ids = self
+				.auto_vouch_targets(
+					session_id,
+					voucher_device_id,
+					vouchee_device_info.device_id,
+					&proxy_config,
+				)
+				.await?;
 
 			if !target_device_ids.is_empty() {
 				if let Err(e) = self
@@ -982,7 +1874,98 @@ impl PairingProtocolHandler {
 			}
 		}
 
-		Ok(())
+		Ok(session)
+	}
+
+	/// Paired devices a new vouchee is auto-vouched to
+	///
+	/// Only devices still trusted are picked, and none that keep rejecting our
+	/// vouches.
+	async fn auto_vouch_targets(
+		&self,
+		session_id: Uuid,
+		voucher_device_id: Uuid,
+		vouchee_device_id: Uuid,
+		config: &ProxyPairingConfig,
+	) -> Result<Vec<Uuid>> {
+		let (candidates, persistence) = {
+			let registry = self.device_registry.read().await;
+			let candidates = registry
+				.get_paired_devices()
+				.into_iter()
+				.map(|device| device.device_id)
+				.filter(|device_id| {
+					*device_id != voucher_device_id && *device_id != vouchee_device_id
+				})
+				.collect::<Vec<_>>();
+			(candidates, registry.persistence())
+		};
+		let persisted = persistence.load_paired_devices().await?;
+
+		let mut target_device_ids = Vec::with_capacity(candidates.len());
+		for target_device_id in candidates {
+			if let Some(trust_level) = persisted
+				.get(&target_device_id)
+				.map(|device| device.trust_level)
+				.filter(|trust_level| *trust_level != TrustLevel::Trusted)
+			{
+				self.log_debug(&format!(
+					"Not auto-vouching session {} to {}: the device is {:?}",
+					session_id, target_device_id, trust_level
+				))
+				.await;
+				continue;
+			}
+			if self
+				.auto_vouch_paused(voucher_device_id, target_device_id, config)
+				.await
+			{
+				self.log_debug(&format!(
+					"Not auto-vouching session {} to {}: it keeps rejecting our vouches",
+					session_id, target_device_id
+				))
+				.await;
+				continue;
+			}
+			target_device_ids.push(target_device_id);
+		}
+
+		Ok(target_device_ids)
+	}
+
+	/// Whether vouching to a target is paused after repeated rejections
+	///
+	/// Covers both picking auto-vouch targets and resending queued vouches.
+	async fn auto_vouch_paused(
+		&self,
+		voucher_device_id: Uuid,
+		target_device_id: Uuid,
+		config: &ProxyPairingConfig,
+	) -> bool {
+		let queue = { self.vouching_queue.read().await.clone() };
+		let Some(queue) = queue else {
+			return false;
+		};
+
+		match queue
+			.rejection_streak(voucher_device_id, target_device_id)
+			.await
+		{
+			Ok(Some(streak)) => streak.is_paused(
+				config.vouch_rejection_threshold,
+				chrono::Duration::seconds(config.vouch_rejection_cooldown_secs as i64),
+				chrono::Utc::now(),
+			),
+			Ok(None) => false,
+			Err(e) => {
+				self.log_warn(&format!(
+					"Failed to read vouch rejections for {}: {}",
+					target_device_id, e
+				))
+				.await;
+				false
+			}
+		}
 	}
 
 	pub async fn start_proxy_vouching(
@@ -990,7 +1973,40 @@ impl PairingProtocolHandler {
 		session_id: Uuid,
 		target_device_ids: Vec<Uuid>,
 	) -> Result<VouchingSession> {
-		let (vouchee_device_info, vouchee_public_key, shared_secret) = {
+		use futures::StreamExt;
+
+		// The vouchee could not accept a completion listing more devices
+		if target_device_ids.len() > PairingMessage::MAX_PROXY_OUTCOMES {
+			return Err(NetworkingError::Protocol(format!(
+				"Cannot vouch to {} devices at once, the limit is {}",
+				target_device_ids.len(),
+				PairingMessage::MAX_PROXY_OUTCOMES
+			)));
+		}
+
+		// Without an endpoint no vouch could ever be sent; fail now rather than
+		// queueing vouches that would sit there forever
+		if self.endpoint.is_none() {
+			return Err(NetworkingError::ConnectionFailed(
+				ENDPOINT_UNAVAILABLE.to_string(),
+			));
+		}
+
+		// The user blocked these devices, so nothing may be vouched to them
+		let persistence = { self.device_registry.read().await.persistence() };
+		let persisted = persistence.load_paired_devices().await?;
+		if let Some(blocked) = target_device_ids.iter().find(|device_id| {
+			persisted
+				.get(device_id)
+				.is_some_and(|device| device.trust_level == TrustLevel::Blocked)
+		}) {
+			return Err(NetworkingError::Protocol(format!(
+				"Cannot vouch to blocked device {}",
+				blocked
+			)));
+		}
+
+		let (vouchee_device_info, vouchee_public_key) = {
 			let sessions = self.active_sessions.read().await;
 			let session = sessions.get(&session_id).ok_or_else(|| {
 				NetworkingError::Protocol(format!("Pairing session not found: {}", session_id))
@@ -1008,7 +2024,6 @@ impl PairingProtocolHandler {
 			let public_key = session.remote_public_key.clone().ok_or_else(|| {
 				NetworkingError::Protocol("Missing vouchee public key".to_string())
 			})?;
-			let secret = session.shared_secret.clone();
 
 			self.log_debug(&format!(
 				"Vouching device {} with node_id: '{}'",
@@ -1016,35 +2031,28 @@ impl PairingProtocolHandler {
 			))
 			.await;
 
-			(device_info, public_key, secret)
+			(device_info, public_key)
 		};
+		// Vouch targets have not paired with the vouchee either
+		let vouchee_device_info = self.pre_pairing_device_info(vouchee_device_info).await;
 
 		let voucher_device_id = self.get_device_info().await?.device_id;
-		let base_secret = match shared_secret {
-			Some(secret) => secret,
-			None => self.generate_shared_secret(session_id).await?,
-		};
 
 		let now = chrono::Utc::now();
 		let initial_vouches = {
-			let registry = self.device_registry.read().await;
+			let target_states = self
+				.device_registry
+				.read()
+				.await
+				.get_device_states(&target_device_ids);
 			target_device_ids
 				.iter()
 				.map(|device_id| {
-					let device_name = match registry.get_device_state(*device_id) {
-						Some(crate::service::network::device::DeviceState::Paired {
-							info, ..
-						})
-						| Some(crate::service::network::device::DeviceState::Connected {
-							info,
-							..
-						})
-						| Some(crate::service::network::device::DeviceState::Disconnected {
-							info,
-							..
-						}) => info.device_name.clone(),
-						_ => "Unknown device".to_string(),
-					};
+					let device_name = target_states
+						.get(device_id)
+						.and_then(|state| state.info())
+						.map(|info| info.device_name.clone())
+						.unwrap_or_else(|| "Unknown device".to_string());
 					VouchState {
 						device_id: *device_id,
 						device_name,
@@ -1056,8 +2064,19 @@ impl PairingProtocolHandler {
 				.collect::<Vec<_>>()
 		};
 
+		let (max_sessions, session_ttl) = {
+			let config = self.proxy_config.read().await;
+			(
+				config.max_vouching_sessions,
+				config.vouching_session_ttl_secs,
+			)
+		};
 		let mut session_snapshot = {
 			let mut sessions = self.vouching_sessions.write().await;
+			if !sessions.contains_key(&session_id) && sessions.len() >= max_sessions {
+				drop(sessions);
+				return Err(self.vouching_capacity_error(session_id, max_sessions).await);
+			}
 			let entry = sessions
 				.entry(session_id)
 				.or_insert_with(|| VouchingSession {
@@ -1066,6 +2085,7 @@ impl PairingProtocolHandler {
 					vouchee_device_name: vouchee_device_info.device_name.clone(),
 					voucher_device_id,
 					created_at: now,
+					expires_at: now + chrono::Duration::seconds(session_ttl as i64),
 					state: VouchingSessionState::Pending,
 					vouches: Vec::new(),
 				});
@@ -1090,161 +2110,253 @@ impl PairingProtocolHandler {
 			return Ok(session_snapshot);
 		}
 
-		for target_device_id in target_device_ids {
-			if target_device_id == voucher_device_id
-				|| target_device_id == vouchee_device_info.device_id
-			{
-				self.update_vouch_status(
+		// Targets are independent, so dispatch them concurrently; each status is
+		// updated under the session lock as its target finishes
+		let cancelled = CancellationToken::new();
+		self.vouch_dispatches
+			.write()
+			.await
+			.insert(session_id, cancelled.clone());
+		let results = futures::stream::iter(target_device_ids)
+			.map(|target_device_id| {
+				self.dispatch_vouch(
 					session_id,
 					target_device_id,
-					VouchStatus::Rejected,
-					Some("Invalid vouch target".to_string()),
+					voucher_device_id,
+					&vouchee_device_info,
+					&vouchee_public_key,
+					&cancelled,
 				)
-				.await?;
-				continue;
-			}
+			})
+			.buffer_unordered(MAX_CONCURRENT_VOUCH_DISPATCH)
+			.collect::<Vec<_>>()
+			.await;
+		self.vouch_dispatches.write().await.remove(&session_id);
+		if self.vouch_dispatch_cancelled(session_id, &cancelled).await {
+			return Err(NetworkingError::Protocol(format!(
+				"Vouching session {} was cancelled",
+				session_id
+			)));
+		}
+		for result in results {
+			result?;
+		}
 
-			let target_device_info = {
-				let registry = self.device_registry.read().await;
-				match registry.get_device_state(target_device_id) {
-					Some(crate::service::network::device::DeviceState::Paired { info, .. })
-					| Some(crate::service::network::device::DeviceState::Connected {
-						info, ..
-					})
-					| Some(crate::service::network::device::DeviceState::Disconnected {
-						info,
-						..
-					}) => Some(info.clone()),
-					_ => None,
-				}
-			};
+		let session = self
+			.get_vouching_session(session_id)
+			.await
+			.ok_or_else(|| NetworkingError::Protocol("Vouching session missing".to_string()))?;
+		session_snapshot = session.clone();
 
-			let Some(target_device_info) = target_device_info else {
-				self.update_vouch_status(
-					session_id,
-					target_device_id,
-					VouchStatus::Rejected,
-					Some("Target device not paired".to_string()),
-				)
-				.await?;
-				continue;
-			};
+		Ok(session_snapshot)
+	}
+
+	/// Vouch the vouchee to a single target: queue the vouch and send it right
+	/// away if the target is online
+	///
+	/// No session keys are part of the vouch; the target and the vouchee derive
+	/// them from each other's public keys.
+	async fn dispatch_vouch(
+		&self,
+		session_id: Uuid,
+		target_device_id: Uuid,
+		voucher_device_id: Uuid,
+		vouchee_device_info: &DeviceInfo,
+		vouchee_public_key: &[u8],
+		cancelled: &CancellationToken,
+	) -> Result<()> {
+		if self.vouch_dispatch_cancelled(session_id, cancelled).await {
+			return Ok(());
+		}
 
-			let timestamp = chrono::Utc::now();
-			let payload = self.build_vouch_payload(
+		if target_device_id == voucher_device_id
+			|| target_device_id == vouchee_device_info.device_id
+		{
+			self.update_vouch_status(
 				session_id,
-				&vouchee_device_info,
-				&vouchee_public_key,
-				timestamp,
-			);
-			let signature = self.sign_vouch_payload(&payload)?;
-			let (receiver_keys, vouchee_keys) = self.derive_proxy_session_keys(
-				voucher_device_id,
 				target_device_id,
-				vouchee_device_info.device_id,
-				&vouchee_public_key,
-				&base_secret,
-			)?;
+				VouchStatus::Rejected,
+				Some("Invalid vouch target".to_string()),
+			)
+			.await?;
+			return Ok(());
+		}
 
-			{
-				let mut keys = self.vouching_keys.write().await;
-				keys.insert((session_id, target_device_id), vouchee_keys);
-			}
+		if self
+			.vouch_creates_cycle(vouchee_device_info.device_id, target_device_id)
+			.await?
+		{
+			self.log_warn(&format!(
+				"Not vouching {} to {}: it introduced that device to us",
+				vouchee_device_info.device_id, target_device_id
+			))
+			.await;
+			self.update_vouch_status(
+				session_id,
+				target_device_id,
+				VouchStatus::Rejected,
+				Some("Vouch would create a trust loop".to_string()),
+			)
+			.await?;
+			return Ok(());
+		}
+
+		let target_device_info = self
+			.device_registry
+			.read()
+			.await
+			.get_device_state(target_device_id)
+			.and_then(|state| state.info())
+			.cloned();
 
-			let queue_entry = VouchingQueueEntry {
+		let Some(target_device_info) = target_device_info else {
+			self.update_vouch_status(
 				session_id,
 				target_device_id,
-				voucher_device_id,
-				vouchee_device_id: vouchee_device_info.device_id,
-				vouchee_device_info: vouchee_device_info.clone(),
-				vouchee_public_key: vouchee_public_key.clone(),
-				voucher_signature: signature.clone(),
-				proxied_session_keys: receiver_keys.clone(),
-				created_at: timestamp,
-				expires_at: timestamp + chrono::Duration::days(7),
-				status: VouchQueueStatus::Queued,
-				retry_count: 0,
-				last_attempt_at: None,
-			};
+				VouchStatus::Rejected,
+				Some("Target device not paired".to_string()),
+			)
+			.await?;
+			return Ok(());
+		};
 
-			let queue = { self.vouching_queue.read().await.clone() };
-			if let Some(queue) = queue {
-				queue.upsert_entry(&queue_entry).await?;
+		let timestamp = chrono::Utc::now();
+		let payload = self.build_vouch_payload(
+			session_id,
+			vouchee_device_info,
+			vouchee_public_key,
+			timestamp,
+		);
+		let signature = self.sign_vouch_payload(&payload)?;
+
+		let queue_entry = VouchingQueueEntry {
+			session_id,
+			target_device_id,
+			voucher_device_id,
+			vouchee_device_id: vouchee_device_info.device_id,
+			vouchee_device_info: vouchee_device_info.clone(),
+			vouchee_public_key: vouchee_public_key.to_vec(),
+			voucher_signature: signature.clone(),
+			created_at: timestamp,
+			expires_at: timestamp + chrono::Duration::days(7),
+			status: VouchQueueStatus::Queued,
+			retry_count: 0,
+			last_attempt_at: None,
+			last_attempt_error: None,
+		};
+
+		let queue = { self.vouching_queue.read().await.clone() };
+		if let Some(queue) = queue {
+			queue.upsert_entry(&queue_entry).await?;
+			// A cancel that cleared the queue before this entry landed would miss it
+			if self.vouch_dispatch_cancelled(session_id, cancelled).await {
+				queue.remove_entry(session_id, target_device_id).await?;
+				return Ok(());
 			}
+		}
 
-			let mut sent_now = false;
-			if let Some(endpoint) = &self.endpoint {
-				let registry = self.device_registry.read().await;
-				if registry.is_node_connected(endpoint, target_device_id) {
-					if let Some(node_id) = registry.get_node_id_for_device(target_device_id) {
-						let request = PairingMessage::ProxyPairingRequest {
-							session_id,
-							vouchee_device_info: vouchee_device_info.clone(),
-							vouchee_public_key: vouchee_public_key.clone(),
-							voucher_device_id,
-							voucher_signature: signature,
-							timestamp,
-							proxied_session_keys: receiver_keys,
-						};
-						match self
-							.send_pairing_message_fire_and_forget(node_id, &request)
-							.await
-						{
-							Ok(_) => {
-								sent_now = true;
-							}
-							Err(e) => {
-								self.log_warn(&format!(
-									"Failed to send proxy pairing request to {}: {}",
-									target_device_id, e
-								))
-								.await;
-							}
+		let mut sent_now = false;
+		let mut send_error = None;
+		if let Some(endpoint) = &self.endpoint {
+			let registry = self.device_registry.read().await;
+			if registry.is_node_connected(endpoint, target_device_id) {
+				if let Some(node_id) = registry.get_node_id_for_device(target_device_id) {
+					let request = PairingMessage::ProxyPairingRequest {
+						session_id,
+						vouchee_device_info: vouchee_device_info.clone(),
+						vouchee_public_key: vouchee_public_key.to_vec(),
+						voucher_device_id,
+						voucher_signature: signature,
+						timestamp,
+					};
+					match self
+						.send_pairing_message_fire_and_forget(node_id, &request)
+						.await
+					{
+						Ok(_) => {
+							sent_now = true;
+						}
+						Err(e) => {
+							self.log_warn(&format!(
+								"Failed to send proxy pairing request to {}: {}",
+								target_device_id, e
+							))
+							.await;
+							send_error = Some(e.to_string());
 						}
 					}
 				}
 			}
+		}
 
-			if sent_now {
+		if sent_now {
+			let queue = { self.vouching_queue.read().await.clone() };
+			if let Some(queue) = queue {
+				queue
+					.update_status(
+						session_id,
+						target_device_id,
+						VouchQueueStatus::Waiting,
+						1,
+						Some(chrono::Utc::now()),
+						None,
+					)
+					.await?;
+			}
+			self.update_vouch_status(session_id, target_device_id, VouchStatus::Waiting, None)
+				.await?;
+		} else {
+			if send_error.is_some() {
 				let queue = { self.vouching_queue.read().await.clone() };
 				if let Some(queue) = queue {
 					queue
 						.update_status(
 							session_id,
 							target_device_id,
-							VouchQueueStatus::Waiting,
-							1,
+							VouchQueueStatus::Queued,
+							0,
 							Some(chrono::Utc::now()),
+							send_error,
 						)
 						.await?;
 				}
-				self.update_vouch_status(session_id, target_device_id, VouchStatus::Waiting, None)
-					.await?;
-			} else {
-				self.update_vouch_status(session_id, target_device_id, VouchStatus::Queued, None)
-					.await?;
 			}
+			self.update_vouch_status(session_id, target_device_id, VouchStatus::Queued, None)
+				.await?;
 		}
 
-		let session = self
-			.get_vouching_session(session_id)
-			.await
-			.ok_or_else(|| NetworkingError::Protocol("Vouching session missing".to_string()))?;
-		session_snapshot = session.clone();
-
-		Ok(session_snapshot)
+		Ok(())
 	}
 
-	pub async fn confirm_proxy_pairing(&self, session_id: Uuid, accepted: bool) -> Result<()> {
+	/// Answer a pending proxy pairing confirmation
+	///
+	/// Only the first answer for a session takes effect; later ones, including a
+	/// double click or an answer after the timeout rejected it, report the earlier
+	/// decision instead of failing.
+	pub async fn confirm_proxy_pairing(
+		&self,
+		session_id: Uuid,
+		accepted: bool,
+	) -> Result<ProxyConfirmationOutcome> {
 		let pending = {
 			let mut pending = self.pending_proxy_confirmations.write().await;
-			pending.remove(&session_id)
-		};
-
-		let Some(pending) = pending else {
-			return Err(NetworkingError::Protocol(
-				"No pending proxy confirmation found".to_string(),
-			));
+			let mut decisions = self.proxy_confirmation_decisions.write().await;
+			match pending.remove(&session_id) {
+				Some(confirmation) => {
+					decisions.insert(session_id, (accepted, chrono::Utc::now()));
+					confirmation
+				}
+				None => {
+					return match decisions.get(&session_id) {
+						Some((accepted, _)) => Ok(ProxyConfirmationOutcome::AlreadyConfirmed {
+							accepted: *accepted,
+						}),
+						None => Err(NetworkingError::Protocol(
+							"No pending proxy confirmation found".to_string(),
+						)),
+					};
+				}
+			}
 		};
 
 		let accepting_device_id = self.get_device_info().await?.device_id;
@@ -1253,8 +2365,26 @@ impl PairingProtocolHandler {
 			registry.get_node_id_for_device(pending.voucher_device_id)
 		};
 
+		// Nothing takes effect unless the decision is on record
+		if let Err(e) = self
+			.audit_proxy_decision(ProxyPairingAuditEntry {
+				session_id,
+				voucher_device_id: pending.voucher_device_id,
+				vouchee_device_id: pending.vouchee_device_info.device_id,
+				vouchee_device_name: pending.vouchee_device_info.device_name.clone(),
+				accepted,
+				confirmed_by_user: true,
+				reason: None,
+				decided_at: chrono::Utc::now(),
+			})
+			.await
+		{
+			self.reopen_proxy_confirmation(pending).await;
+			return Err(e);
+		}
+
 		if accepted {
-			{
+			let completed = {
 				let mut registry = self.device_registry.write().await;
 				registry
 					.complete_pairing(
@@ -1266,31 +2396,143 @@ impl PairingProtocolHandler {
 						Some(pending.voucher_device_id),
 						Some(chrono::Utc::now()),
 					)
-					.await?;
+					.await
+			};
+			if let Err(e) = completed {
+				self.reopen_proxy_confirmation(pending).await;
+				return Err(e);
 			}
+		}
 
-			if let Some(node_id) = voucher_node_id {
-				let response = PairingMessage::ProxyPairingResponse {
-					session_id,
-					accepting_device_id,
-					accepted: true,
-					reason: None,
-				};
-				self.send_pairing_message_fire_and_forget(node_id, &response)
-					.await?;
-			}
-		} else if let Some(node_id) = voucher_node_id {
+		// The voucher resends the vouch after a reconnect and gets the decision then
+		if let Some(node_id) = voucher_node_id {
 			let response = PairingMessage::ProxyPairingResponse {
 				session_id,
 				accepting_device_id,
-				accepted: false,
-				reason: Some("User rejected proxy pairing".to_string()),
+				accepted,
+				reason: (!accepted).then(|| "User rejected proxy pairing".to_string()),
 			};
-			self.send_pairing_message_fire_and_forget(node_id, &response)
+			if let Err(e) = self
+				.send_pairing_message_fire_and_forget(node_id, &response)
+				.await
+			{
+				self.log_warn(&format!(
+					"Failed to send proxy pairing response for session {}: {}",
+					session_id, e
+				))
+				.await;
+			}
+		}
+
+		Ok(ProxyConfirmationOutcome::Applied)
+	}
+
+	/// Pick up a vouch the voucher resent for a session we already know about
+	///
+	/// A confirmation still waiting on the user is left as it is, without a
+	/// second prompt or timer. A session the user already answered gets the
+	/// recorded decision again, since the first response may have been lost to
+	/// a disconnect. Returns whether the vouch was handled.
+	async fn resume_proxy_confirmation(
+		&self,
+		remote_node_id: EndpointId,
+		session_id: Uuid,
+		voucher_device_id: Uuid,
+	) -> Result<bool> {
+		let decision = {
+			let pending = self.pending_proxy_confirmations.read().await;
+			if let Some(confirmation) = pending.get(&session_id) {
+				if confirmation.voucher_device_id == voucher_device_id {
+					return Ok(true);
+				}
+				drop(pending);
+				self.send_proxy_pairing_rejection(
+					remote_node_id,
+					session_id,
+					"Proxy pairing session already in use".to_string(),
+				)
 				.await?;
+				return Ok(true);
+			}
+			let decisions = self.proxy_confirmation_decisions.read().await;
+			decisions.get(&session_id).map(|(accepted, _)| *accepted)
+		};
+		let Some(accepted) = decision else {
+			return Ok(false);
+		};
+
+		let response = PairingMessage::ProxyPairingResponse {
+			session_id,
+			accepting_device_id: self.get_device_info().await?.device_id,
+			accepted,
+			reason: (!accepted).then(|| "Proxy pairing was already declined".to_string()),
+		};
+		self.send_pairing_message_fire_and_forget(remote_node_id, &response)
+			.await?;
+		Ok(true)
+	}
+
+	/// Leave a proxy confirmation open for another answer after nothing took effect
+	async fn reopen_proxy_confirmation(&self, pending: PendingProxyConfirmation) {
+		let mut pending_map = self.pending_proxy_confirmations.write().await;
+		self.proxy_confirmation_decisions
+			.write()
+			.await
+			.remove(&pending.session_id);
+		pending_map.insert(pending.session_id, pending);
+	}
+
+	/// Proxy pairing decisions made on this device, oldest first
+	///
+	/// Empty until the vouching queue, which stores the log, is set up.
+	pub async fn get_proxy_pairing_audit(&self) -> Result<Vec<ProxyPairingAuditEntry>> {
+		let queue = { self.vouching_queue.read().await.clone() };
+		match queue {
+			Some(queue) => queue.list_proxy_decisions().await,
+			None => Ok(Vec::new()),
 		}
+	}
 
-		Ok(())
+	/// Write a proxy pairing decision to the audit log
+	///
+	/// Called before the decision takes effect; callers abandon the decision
+	/// when this fails, so every applied decision is on record.
+	async fn audit_proxy_decision(&self, entry: ProxyPairingAuditEntry) -> Result<()> {
+		let queue = { self.vouching_queue.read().await.clone() };
+		let Some(queue) = queue else {
+			return Err(NetworkingError::Protocol(format!(
+				"Cannot record proxy pairing decision on session {}: audit log not set up",
+				entry.session_id
+			)));
+		};
+		queue.record_proxy_decision(&entry).await
+	}
+
+	/// Reject an authenticated vouch on policy grounds, recording the decision first
+	///
+	/// Only vouches whose signature checked out get here, so unauthenticated
+	/// peers cannot fill the audit log.
+	async fn reject_vouch(
+		&self,
+		remote_node_id: EndpointId,
+		session_id: Uuid,
+		voucher_device_id: Uuid,
+		vouchee_device_info: &DeviceInfo,
+		reason: String,
+	) -> Result<()> {
+		self.audit_proxy_decision(ProxyPairingAuditEntry {
+			session_id,
+			voucher_device_id,
+			vouchee_device_id: vouchee_device_info.device_id,
+			vouchee_device_name: vouchee_device_info.device_name.clone(),
+			accepted: false,
+			confirmed_by_user: false,
+			reason: Some(reason.clone()),
+			decided_at: chrono::Utc::now(),
+		})
+		.await?;
+		self.send_proxy_pairing_rejection(remote_node_id, session_id, reason)
+			.await
 	}
 
 	async fn handle_proxy_pairing_request(
@@ -1301,7 +2543,6 @@ impl PairingProtocolHandler {
 		voucher_device_id: Uuid,
 		voucher_signature: Vec<u8>,
 		timestamp: chrono::DateTime<chrono::Utc>,
-		proxied_session_keys: SessionKeys,
 		remote_node_id: EndpointId,
 	) -> Result<()> {
 		let proxy_config: ProxyPairingConfig = { self.proxy_config.read().await.clone() };
@@ -1363,14 +2604,21 @@ impl PairingProtocolHandler {
 			return Ok(());
 		}
 
-		let max_age = chrono::Duration::seconds(proxy_config.vouch_signature_max_age as i64);
-		if chrono::Utc::now().signed_duration_since(timestamp) > max_age {
-			self.send_proxy_pairing_rejection(
-				remote_node_id,
-				session_id,
-				"Vouch signature expired".to_string(),
-			)
-			.await?;
+		if let Err(e) = proxy::validate_vouch_timestamp(
+			timestamp,
+			chrono::Utc::now(),
+			chrono::Duration::seconds(proxy_config.vouch_signature_max_age as i64),
+			chrono::Duration::seconds(proxy_config.vouch_max_future_skew as i64),
+		) {
+			self.send_proxy_pairing_rejection(remote_node_id, session_id, e.to_string())
+				.await?;
+			return Ok(());
+		}
+
+		if self
+			.resume_proxy_confirmation(remote_node_id, session_id, voucher_device_id)
+			.await?
+		{
 			return Ok(());
 		}
 
@@ -1380,9 +2628,11 @@ impl PairingProtocolHandler {
 				.get_device_state(vouchee_device_info.device_id)
 				.is_some()
 			{
-				self.send_proxy_pairing_rejection(
+				self.reject_vouch(
 					remote_node_id,
 					session_id,
+					voucher_device_id,
+					&vouchee_device_info,
 					"Device already paired".to_string(),
 				)
 				.await?;
@@ -1397,52 +2647,93 @@ impl PairingProtocolHandler {
 		let persisted_voucher = persistence.get_paired_device(voucher_device_id).await?;
 
 		let Some(persisted_voucher) = persisted_voucher else {
-			self.send_proxy_pairing_rejection(
+			self.reject_vouch(
 				remote_node_id,
 				session_id,
+				voucher_device_id,
+				&vouchee_device_info,
 				"Voucher not in persistence".to_string(),
 			)
 			.await?;
 			return Ok(());
 		};
 
-		let voucher_is_trusted = matches!(
-			persisted_voucher.trust_level,
-			crate::service::network::device::TrustLevel::Trusted
-		);
-		let voucher_is_direct = matches!(
-			persisted_voucher.pairing_type,
-			crate::service::network::device::PairingType::Direct
-		);
-
-		if !voucher_is_trusted || !voucher_is_direct {
-			self.send_proxy_pairing_rejection(
+		if self
+			.vouch_creates_cycle(vouchee_device_info.device_id, voucher_device_id)
+			.await?
+		{
+			self.reject_vouch(
 				remote_node_id,
 				session_id,
-				"Voucher not trusted for proxy pairing".to_string(),
+				voucher_device_id,
+				&vouchee_device_info,
+				"Vouch would create a trust loop".to_string(),
 			)
 			.await?;
 			return Ok(());
 		}
 
-		if proxied_session_keys.send_key == proxied_session_keys.receive_key {
-			self.send_proxy_pairing_rejection(
+		let voucher_is_trusted = persisted_voucher.can_vouch();
+
+		if !voucher_is_trusted {
+			self.reject_vouch(
 				remote_node_id,
 				session_id,
-				"Invalid session keys".to_string(),
+				voucher_device_id,
+				&vouchee_device_info,
+				"Voucher not trusted for proxy pairing".to_string(),
 			)
 			.await?;
 			return Ok(());
 		}
 
-		if proxy_config.auto_accept_vouched && voucher_is_trusted {
-			{
-				self.log_info(&format!(
-					"Auto-accepting proxy pairing for device {} with node_id: '{}'",
-					vouchee_device_info.device_id, vouchee_device_info.network_fingerprint.node_id
+		let own_device_id = self.get_device_info().await?.device_id;
+		let proxied_session_keys = match self.derive_proxy_session_keys(
+			voucher_device_id,
+			own_device_id,
+			vouchee_device_info.device_id,
+			&vouchee_public_key,
+			&vouchee_public_key,
+		) {
+			Ok((target_keys, _)) => target_keys,
+			Err(e) => {
+				self.log_warn(&format!(
+					"Cannot derive session keys for vouchee {}: {}",
+					vouchee_device_info.device_id, e
 				))
 				.await;
-
+				self.reject_vouch(
+					remote_node_id,
+					session_id,
+					voucher_device_id,
+					&vouchee_device_info,
+					"Invalid vouchee public key".to_string(),
+				)
+				.await?;
+				return Ok(());
+			}
+		};
+
+		if voucher_is_trusted && proxy_config.auto_accepts_vouches_from(voucher_device_id) {
+			{
+				self.log_info(&format!(
+					"Auto-accepting proxy pairing for device {} with node_id: '{}'",
+					vouchee_device_info.device_id, vouchee_device_info.network_fingerprint.node_id
+				))
+				.await;
+
+				self.audit_proxy_decision(ProxyPairingAuditEntry {
+					session_id,
+					voucher_device_id,
+					vouchee_device_id: vouchee_device_info.device_id,
+					vouchee_device_name: vouchee_device_info.device_name.clone(),
+					accepted: true,
+					confirmed_by_user: false,
+					reason: None,
+					decided_at: chrono::Utc::now(),
+				})
+				.await?;
+
 				let mut registry = self.device_registry.write().await;
 				registry
 					.complete_pairing(
@@ -1457,10 +2748,9 @@ impl PairingProtocolHandler {
 					.await?;
 			}
 
-			let accepting_device_id = self.get_device_info().await?.device_id;
 			let response = PairingMessage::ProxyPairingResponse {
 				session_id,
-				accepting_device_id,
+				accepting_device_id: own_device_id,
 				accepted: true,
 				reason: None,
 			};
@@ -1500,6 +2790,7 @@ impl PairingProtocolHandler {
 		}
 
 		let pending_map = self.pending_proxy_confirmations.clone();
+		let decisions = self.proxy_confirmation_decisions.clone();
 		let command_sender = self.command_sender.clone();
 		let registry = self.device_registry.clone();
 		let timeout = proxy_config.vouch_response_timeout;
@@ -1509,7 +2800,14 @@ impl PairingProtocolHandler {
 			tokio::time::sleep(tokio::time::Duration::from_secs(timeout)).await;
 			let pending = {
 				let mut guard = pending_map.write().await;
-				guard.remove(&session_id)
+				let pending = guard.remove(&session_id);
+				if pending.is_some() {
+					decisions
+						.write()
+						.await
+						.insert(session_id, (false, chrono::Utc::now()));
+				}
+				pending
 			};
 
 			if let Some(pending) = pending {
@@ -1524,13 +2822,15 @@ impl PairingProtocolHandler {
 						accepted: false,
 						reason: Some("Proxy confirmation timed out".to_string()),
 					}) {
-						let _ = command_sender.send(
-							crate::service::network::core::event_loop::EventLoopCommand::SendMessageToNode {
-								node_id,
-								protocol: "pairing".to_string(),
-								data,
-							},
-						);
+						let _ = command_sender
+							.send(
+								crate::service::network::core::event_loop::EventLoopCommand::SendMessageToNode {
+									node_id,
+									protocol: Protocol::Pairing,
+									data,
+								},
+							)
+							.await;
 					}
 				}
 			}
@@ -1563,14 +2863,14 @@ impl PairingProtocolHandler {
 		accepted: bool,
 		reason: Option<String>,
 	) -> Result<()> {
-		if self.get_vouching_session(session_id).await.is_none() {
+		let Some(session) = self.get_vouching_session(session_id).await else {
 			self.log_warn(&format!(
 				"Proxy pairing response for unknown session {}",
 				session_id
 			))
 			.await;
 			return Ok(());
-		}
+		};
 
 		let status = if accepted {
 			VouchStatus::Accepted
@@ -1578,43 +2878,217 @@ impl PairingProtocolHandler {
 			VouchStatus::Rejected
 		};
 
-		if !accepted {
-			let mut keys = self.vouching_keys.write().await;
-			keys.remove(&(session_id, accepting_device_id));
-		}
-
 		let queue = { self.vouching_queue.read().await.clone() };
-		if let Some(queue) = queue {
+		if let Some(queue) = &queue {
 			queue.remove_entry(session_id, accepting_device_id).await?;
 		}
 
 		self.update_vouch_status(session_id, accepting_device_id, status, reason)
 			.await?;
 
+		// The answer is already applied; failing to count it only affects the circuit breaker
+		if let Some(queue) = queue {
+			if let Err(e) = self
+				.track_rejection_streak(
+					&queue,
+					session.voucher_device_id,
+					accepting_device_id,
+					accepted,
+				)
+				.await
+			{
+				self.log_warn(&format!(
+					"Failed to track vouch rejections for {}: {}",
+					accepting_device_id, e
+				))
+				.await;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Reset a target's rejection streak when it accepts, or extend it when it rejects
+	async fn track_rejection_streak(
+		&self,
+		queue: &VouchingQueue,
+		voucher_device_id: Uuid,
+		target_device_id: Uuid,
+		accepted: bool,
+	) -> Result<()> {
+		if accepted {
+			return queue
+				.clear_rejections(voucher_device_id, target_device_id)
+				.await;
+		}
+
+		let streak = queue
+			.record_rejection(voucher_device_id, target_device_id, chrono::Utc::now())
+			.await?;
+		let threshold = { self.proxy_config.read().await.vouch_rejection_threshold };
+		if threshold > 0 && streak.consecutive == threshold {
+			self.log_warn(&format!(
+				"Device {} rejected {} vouches in a row, pausing vouches to it",
+				target_device_id, streak.consecutive
+			))
+			.await;
+		}
 		Ok(())
 	}
 
+	/// Device we paired with in the session a voucher names its vouching session after
+	///
+	/// For a multi-use code the voucher keys its side of the handshake by the code's
+	/// session id and our public key, so both ids are tried. Finished sessions are
+	/// looked up in the pairing history once they leave the active set.
+	async fn recorded_voucher(&self, session_id: Uuid) -> Option<Uuid> {
+		let own_key = self.identity.public_key_bytes();
+		let names_session =
+			|id: Uuid| id == session_id || Uuid::new_v5(&id, &own_key) == session_id;
+
+		let active = {
+			let sessions = self.active_sessions.read().await;
+			sessions
+				.values()
+				.find(|session| {
+					names_session(session.id) && matches!(session.state, PairingState::Completed)
+				})
+				.map(|session| session.remote_device_id)
+		};
+		if let Some(remote_device_id) = active {
+			return remote_device_id;
+		}
+
+		self.pairing_history
+			.read()
+			.await
+			.entries()
+			.into_iter()
+			.find(|entry| {
+				names_session(entry.session_id)
+					&& matches!(entry.outcome, PairingOutcome::Completed)
+			})
+			.and_then(|entry| entry.remote_device_id)
+	}
+
 	async fn handle_proxy_pairing_complete(
 		&self,
 		session_id: Uuid,
 		voucher_device_id: Uuid,
 		accepted_by: Vec<proxy::AcceptedDevice>,
 		rejected_by: Vec<proxy::RejectedDevice>,
+		voucher_signature: Vec<u8>,
 	) -> Result<()> {
+		// Only the device we paired with in this session may vouch for us in it
+		if self.recorded_voucher(session_id).await != Some(voucher_device_id) {
+			self.log_warn(&format!(
+				"Rejecting proxy pairing completion for session {}: device {} is not the voucher we paired with",
+				session_id, voucher_device_id
+			))
+			.await;
+			return Err(NetworkingError::Protocol(format!(
+				"Proxy pairing completion for session {} from a device that did not pair with us in it",
+				session_id
+			)));
+		}
+		let voucher_node_id = {
+			let registry = self.device_registry.read().await;
+			registry.get_node_id_for_device(voucher_device_id)
+		};
+		let Some(voucher_node_id) = voucher_node_id else {
+			return Err(NetworkingError::Protocol(format!(
+				"Proxy pairing completion from unknown voucher {}",
+				voucher_device_id
+			)));
+		};
+		let payload = ProxyCompletionPayload {
+			session_id,
+			voucher_device_id,
+			accepted_by: &accepted_by,
+			rejected_by: &rejected_by,
+		};
+		if !self.verify_proxy_completion_signature(
+			&payload,
+			&voucher_signature,
+			voucher_node_id.as_bytes(),
+		)? {
+			self.log_warn(&format!(
+				"Rejecting proxy pairing completion for session {}: invalid voucher signature",
+				session_id
+			))
+			.await;
+			return Err(NetworkingError::Protocol(
+				"Invalid proxy pairing completion signature".to_string(),
+			));
+		}
+
+		// One device failing to pair must not hide the ones that did, so every
+		// device is attempted and the outcome reported as a whole
+		let vouchee_confirms = { self.proxy_config.read().await.vouchee_confirms };
+		let own_device_id = self.get_device_info().await?.device_id;
+		let mut paired = Vec::new();
+		let mut failed = Vec::new();
 		for accepted in accepted_by {
 			let device_id = accepted.device_info.device_id;
-			let mut registry = self.device_registry.write().await;
-			registry
-				.complete_pairing(
-					device_id,
-					accepted.device_info.clone(),
-					accepted.session_keys.clone(),
-					None,
-					crate::service::network::device::PairingType::Proxied,
-					Some(voucher_device_id),
-					Some(chrono::Utc::now()),
+			let session_keys = match self.proxied_device_session_keys(
+				voucher_device_id,
+				own_device_id,
+				&accepted.device_info,
+			) {
+				Ok(keys) => keys,
+				Err(e) => {
+					self.log_warn(&format!(
+						"Cannot derive session keys with device {} in session {}: {}",
+						device_id, session_id, e
+					))
+					.await;
+					failed.push(ProxiedPairingFailure {
+						device_id,
+						error: e.to_string(),
+					});
+					continue;
+				}
+			};
+
+			if vouchee_confirms {
+				self.request_proxied_device_confirmation(
+					session_id,
+					voucher_device_id,
+					accepted.device_info,
+					session_keys,
 				)
-				.await?;
+				.await;
+				continue;
+			}
+
+			let completed = {
+				let mut registry = self.device_registry.write().await;
+				registry
+					.complete_pairing(
+						device_id,
+						accepted.device_info,
+						session_keys,
+						None,
+						crate::service::network::device::PairingType::Proxied,
+						Some(voucher_device_id),
+						Some(chrono::Utc::now()),
+					)
+					.await
+			};
+			match completed {
+				Ok(()) => paired.push(device_id),
+				Err(e) => {
+					self.log_warn(&format!(
+						"Failed to complete proxy pairing with device {} in session {}: {}",
+						device_id, session_id, e
+					))
+					.await;
+					failed.push(ProxiedPairingFailure {
+						device_id,
+						error: e.to_string(),
+					});
+				}
+			}
 		}
 
 		if !rejected_by.is_empty() {
@@ -1626,87 +3100,385 @@ impl PairingProtocolHandler {
 		}
 
 		self.log_info(&format!(
-			"Proxy pairing completion handled for session {}",
-			session_id
+			"Proxy pairing completion handled for session {}: {} paired, {} failed",
+			session_id,
+			paired.len(),
+			failed.len()
 		))
 		.await;
 
+		let event_bus = { self.event_bus.read().await.clone() };
+		if let Some(event_bus) = event_bus {
+			event_bus.emit(Event::ProxyPairingCompleted {
+				session_id,
+				voucher_device_id,
+				paired,
+				failed,
+			});
+		}
+
 		Ok(())
 	}
 
-	async fn process_vouching_queue(&self) -> Result<()> {
-		let queue = { self.vouching_queue.read().await.clone() };
-		let Some(queue) = queue else {
-			return Ok(());
-		};
+	/// Our half of the session keys with a device that accepted a vouch for us
+	///
+	/// The target derives its half from our public key in the vouch; we derive ours
+	/// from the node id it reports in its device info.
+	fn proxied_device_session_keys(
+		&self,
+		voucher_device_id: Uuid,
+		own_device_id: Uuid,
+		target_device_info: &DeviceInfo,
+	) -> Result<SessionKeys> {
+		let target_node_id = target_device_info
+			.network_fingerprint
+			.node_id
+			.parse::<EndpointId>()
+			.map_err(|e| {
+				NetworkingError::Protocol(format!(
+					"Invalid node ID for device {}: {}",
+					target_device_info.device_id, e
+				))
+			})?;
+		let (_, vouchee_keys) = self.derive_proxy_session_keys(
+			voucher_device_id,
+			target_device_info.device_id,
+			own_device_id,
+			&self.identity.public_key_bytes(),
+			target_node_id.as_bytes(),
+		)?;
+		Ok(vouchee_keys)
+	}
 
-		let config: ProxyPairingConfig = { self.proxy_config.read().await.clone() };
-		let entries = queue.list_entries().await?;
-		let now = chrono::Utc::now();
+	/// Hold a device that accepted our vouch until the user confirms it
+	async fn request_proxied_device_confirmation(
+		&self,
+		session_id: Uuid,
+		voucher_device_id: Uuid,
+		device_info: DeviceInfo,
+		session_keys: SessionKeys,
+	) {
+		let device_id = device_info.device_id;
+		let device_name = device_info.device_name.clone();
+		self.pending_proxied_devices.write().await.insert(
+			(session_id, device_id),
+			PendingProxiedDevice {
+				voucher_device_id,
+				device_info,
+				session_keys,
+				created_at: chrono::Utc::now(),
+			},
+		);
 
-		for entry in entries {
-			if self.get_vouching_session(entry.session_id).await.is_none() {
-				queue
-					.remove_entry(entry.session_id, entry.target_device_id)
-					.await?;
-				continue;
-			}
+		let event_bus = { self.event_bus.read().await.clone() };
+		if let Some(event_bus) = event_bus {
+			event_bus.emit(Event::ProxiedDeviceConfirmationRequired {
+				session_id,
+				device_id,
+				device_name,
+				voucher_device_id,
+			});
+		}
+	}
 
-			if entry.expires_at <= now {
-				queue
-					.remove_entry(entry.session_id, entry.target_device_id)
-					.await?;
-				self.update_vouch_status(
-					entry.session_id,
-					entry.target_device_id,
-					VouchStatus::Unreachable,
-					Some("Vouch expired".to_string()),
-				)
-				.await?;
-				continue;
-			}
+	/// Answer a pending confirmation for a device that accepted a vouch for us
+	///
+	/// Only used with `vouchee_confirms` on, where proxy pairings are not completed
+	/// on our side until confirmed here. Returns false if the device had no
+	/// pending confirmation in the session.
+	pub async fn confirm_proxied_device(
+		&self,
+		session_id: Uuid,
+		device_id: Uuid,
+		accepted: bool,
+	) -> Result<bool> {
+		let pending = {
+			self.pending_proxied_devices
+				.write()
+				.await
+				.remove(&(session_id, device_id))
+		};
+		let Some(pending) = pending else {
+			return Ok(false);
+		};
 
-			if entry.retry_count >= config.vouch_queue_retry_limit {
-				queue
-					.remove_entry(entry.session_id, entry.target_device_id)
-					.await?;
-				self.update_vouch_status(
-					entry.session_id,
-					entry.target_device_id,
-					VouchStatus::Unreachable,
-					Some("Vouch retry limit exceeded".to_string()),
+		if !accepted {
+			self.log_info(&format!(
+				"Declined proxy pairing with device {} from session {}",
+				device_id, session_id
+			))
+			.await;
+			self.notify_proxied_device_declined(session_id, device_id, &pending)
+				.await;
+			return Ok(true);
+		}
+
+		let completed = {
+			let mut registry = self.device_registry.write().await;
+			registry
+				.complete_pairing(
+					device_id,
+					pending.device_info.clone(),
+					pending.session_keys.clone(),
+					None,
+					crate::service::network::device::PairingType::Proxied,
+					Some(pending.voucher_device_id),
+					Some(chrono::Utc::now()),
 				)
-				.await?;
-				continue;
-			}
+				.await
+		};
+		if let Err(e) = completed {
+			// Nothing took effect, so leave the confirmation open for another answer
+			self.pending_proxied_devices
+				.write()
+				.await
+				.insert((session_id, device_id), pending);
+			return Err(e);
+		}
 
-			if matches!(entry.status, VouchQueueStatus::Waiting) {
-				if let Some(last_attempt_at) = entry.last_attempt_at {
-					let timeout = chrono::Duration::seconds(config.vouch_response_timeout as i64);
-					if now.signed_duration_since(last_attempt_at) > timeout {
-						queue
-							.remove_entry(entry.session_id, entry.target_device_id)
-							.await?;
-						self.update_vouch_status(
-							entry.session_id,
-							entry.target_device_id,
-							VouchStatus::Unreachable,
-							Some("Proxy response timeout".to_string()),
-						)
-						.await?;
-					}
-				}
-				continue;
-			}
+		Ok(true)
+	}
 
-			if !matches!(entry.status, VouchQueueStatus::Queued) {
+	/// Tell the voucher and the target that we declined a proxy pairing
+	///
+	/// The target already paired with us when it accepted the vouch, so without
+	/// this it would keep a one-sided pairing. Best effort: a peer that misses the
+	/// message keeps the stale pairing until it is unpaired by hand.
+	async fn notify_proxied_device_declined(
+		&self,
+		session_id: Uuid,
+		declined_device_id: Uuid,
+		pending: &PendingProxiedDevice,
+	) {
+		let own_device_id = match self.get_device_info().await {
+			Ok(info) => info.device_id,
+			Err(e) => {
+				self.log_warn(&format!(
+					"Could not announce declined proxy pairing for session {}: {}",
+					session_id, e
+				))
+				.await;
+				return;
+			}
+		};
+		let message = PairingMessage::ProxyPairingDeclined {
+			session_id,
+			vouchee_device_id: own_device_id,
+			declined_device_id,
+		};
+
+		let voucher_node_id = {
+			let registry = self.device_registry.read().await;
+			registry.get_node_id_for_device(pending.voucher_device_id)
+		};
+		let target_node_id = pending
+			.device_info
+			.network_fingerprint
+			.node_id
+			.parse::<EndpointId>()
+			.ok();
+		for node_id in [voucher_node_id, target_node_id].into_iter().flatten() {
+			if let Err(e) = self
+				.send_pairing_message_fire_and_forget(node_id, &message)
+				.await
+			{
+				self.log_warn(&format!(
+					"Failed to announce declined proxy pairing to node {}: {}",
+					node_id, e
+				))
+				.await;
+			}
+		}
+	}
+
+	/// Handle a vouchee declining a pairing that a target accepted
+	///
+	/// On the target this removes the proxied pairing with the vouchee; on the
+	/// voucher it marks the target's vouch as rejected. Only the vouchee itself
+	/// may send it.
+	async fn handle_proxy_pairing_declined(
+		&self,
+		session_id: Uuid,
+		vouchee_device_id: Uuid,
+		declined_device_id: Uuid,
+		remote_node_id: EndpointId,
+	) -> Result<()> {
+		let vouchee = {
+			let registry = self.device_registry.read().await;
+			registry.get_persisted_device(vouchee_device_id).await?
+		};
+		let sent_by_vouchee = vouchee.as_ref().is_some_and(|device| {
+			device.device_info.network_fingerprint.node_id == remote_node_id.to_string()
+		});
+		if !sent_by_vouchee {
+			return Err(NetworkingError::Protocol(format!(
+				"Proxy pairing decline for device {} not sent by it",
+				vouchee_device_id
+			)));
+		}
+
+		let own_device_id = self.get_device_info().await?.device_id;
+		if declined_device_id == own_device_id {
+			let proxied = vouchee.is_some_and(|device| {
+				matches!(
+					device.pairing_type,
+					crate::service::network::device::PairingType::Proxied
+				)
+			});
+			// A direct pairing was not made by the vouch, so a decline can't undo it
+			if proxied {
+				self.unpair_device(vouchee_device_id).await?;
+				self.log_info(&format!(
+					"Device {} declined our proxy pairing from session {}, unpaired it",
+					vouchee_device_id, session_id
+				))
+				.await;
+			}
+			return Ok(());
+		}
+
+		match self.get_vouching_session(session_id).await {
+			Some(session) if session.vouchee_device_id == vouchee_device_id => {
+				self.update_vouch_status(
+					session_id,
+					declined_device_id,
+					VouchStatus::Rejected,
+					Some("Declined by the new device".to_string()),
+				)
+				.await
+			}
+			_ => {
+				self.log_warn(&format!(
+					"Proxy pairing decline for unknown session {}",
+					session_id
+				))
+				.await;
+				Ok(())
+			}
+		}
+	}
+
+	/// Decline confirmations that were never answered
+	///
+	/// Runs from the session cleanup, with the vouching session TTL as the limit.
+	async fn expire_pending_proxied_devices(&self) -> usize {
+		let ttl_secs = { self.proxy_config.read().await.vouching_session_ttl_secs };
+		let cutoff = chrono::Utc::now() - chrono::Duration::seconds(ttl_secs as i64);
+		let expired = {
+			let mut pending = self.pending_proxied_devices.write().await;
+			let expired_keys = pending
+				.iter()
+				.filter(|(_, device)| device.created_at < cutoff)
+				.map(|(key, _)| *key)
+				.collect::<Vec<_>>();
+			expired_keys
+				.into_iter()
+				.filter_map(|key| pending.remove(&key).map(|device| (key, device)))
+				.collect::<Vec<_>>()
+		};
+
+		for ((session_id, device_id), device) in &expired {
+			self.notify_proxied_device_declined(*session_id, *device_id, device)
+				.await;
+		}
+		expired.len()
+	}
+
+	async fn process_vouching_queue(&self) -> Result<()> {
+		let queue = { self.vouching_queue.read().await.clone() };
+		let Some(queue) = queue else {
+			return Ok(());
+		};
+
+		let config: ProxyPairingConfig = { self.proxy_config.read().await.clone() };
+		let entries = queue.list_entries().await?;
+		let now = chrono::Utc::now();
+
+		// Queued vouches can't be sent without an endpoint; give up on them
+		// instead of retrying every poll
+		let Some(endpoint) = &self.endpoint else {
+			for entry in entries {
+				if !matches!(entry.status, VouchQueueStatus::Queued) {
+					continue;
+				}
+				queue
+					.remove_entry(entry.session_id, entry.target_device_id)
+					.await?;
+				if self.get_vouching_session(entry.session_id).await.is_some() {
+					self.update_vouch_status(
+						entry.session_id,
+						entry.target_device_id,
+						VouchStatus::Unreachable,
+						Some(ENDPOINT_UNAVAILABLE.to_string()),
+					)
+					.await?;
+				}
+			}
+			return Ok(());
+		};
+
+		for entry in entries {
+			if self.get_vouching_session(entry.session_id).await.is_none() {
+				queue
+					.remove_entry(entry.session_id, entry.target_device_id)
+					.await?;
 				continue;
 			}
 
-			let endpoint = match &self.endpoint {
-				Some(endpoint) => endpoint,
-				None => continue,
-			};
+			if entry.expires_at <= now {
+				queue
+					.remove_entry(entry.session_id, entry.target_device_id)
+					.await?;
+				self.update_vouch_status(
+					entry.session_id,
+					entry.target_device_id,
+					VouchStatus::Unreachable,
+					Some("Vouch expired".to_string()),
+				)
+				.await?;
+				continue;
+			}
+
+			if entry.retry_count >= config.vouch_queue_retry_limit {
+				queue
+					.remove_entry(entry.session_id, entry.target_device_id)
+					.await?;
+				let reason = match &entry.last_attempt_error {
+					Some(error) => format!("Vouch retry limit exceeded (last error: {})", error),
+					None => "Vouch retry limit exceeded".to_string(),
+				};
+				self.update_vouch_status(
+					entry.session_id,
+					entry.target_device_id,
+					VouchStatus::Unreachable,
+					Some(reason),
+				)
+				.await?;
+				continue;
+			}
+
+			if matches!(entry.status, VouchQueueStatus::Waiting) {
+				if let Some(last_attempt_at) = entry.last_attempt_at {
+					let timeout = chrono::Duration::seconds(config.vouch_response_timeout as i64);
+					if now.signed_duration_since(last_attempt_at) > timeout {
+						queue
+							.remove_entry(entry.session_id, entry.target_device_id)
+							.await?;
+						self.update_vouch_status(
+							entry.session_id,
+							entry.target_device_id,
+							VouchStatus::Unreachable,
+							Some("Proxy response timeout".to_string()),
+						)
+						.await?;
+					}
+				}
+				continue;
+			}
+
+			if !matches!(entry.status, VouchQueueStatus::Queued) {
+				continue;
+			}
 
 			let (is_connected, node_id) = {
 				let registry = self.device_registry.read().await;
@@ -1724,66 +3496,170 @@ impl PairingProtocolHandler {
 				continue;
 			};
 
-			let timestamp = chrono::Utc::now();
-			let payload = self.build_vouch_payload(
+			if !self.send_queued_vouch(&queue, &entry, node_id, now).await? {
+				continue;
+			}
+
+			self.update_vouch_status(
 				entry.session_id,
-				&entry.vouchee_device_info,
-				&entry.vouchee_public_key,
-				timestamp,
-			);
-			let signature = self.sign_vouch_payload(&payload)?;
-
-			let request = PairingMessage::ProxyPairingRequest {
-				session_id: entry.session_id,
-				vouchee_device_info: entry.vouchee_device_info.clone(),
-				vouchee_public_key: entry.vouchee_public_key.clone(),
-				voucher_device_id: entry.voucher_device_id,
-				voucher_signature: signature,
-				timestamp,
-				proxied_session_keys: entry.proxied_session_keys.clone(),
-			};
+				entry.target_device_id,
+				VouchStatus::Waiting,
+				None,
+			)
+			.await?;
+		}
 
-			if let Err(e) = self
-				.send_pairing_message_fire_and_forget(node_id, &request)
-				.await
-			{
-				self.log_warn(&format!(
-					"Failed to send queued proxy pairing request to {}: {}",
-					entry.target_device_id, e
-				))
-				.await;
-				queue
-					.update_status(
-						entry.session_id,
-						entry.target_device_id,
-						VouchQueueStatus::Queued,
-						entry.retry_count + 1,
-						Some(now),
-					)
-					.await?;
-				continue;
+		Ok(())
+	}
+
+	/// Drop or rewind pairing state that involves a device that just disconnected
+	///
+	/// Pairing sessions with the device are removed. Vouches still waiting on its
+	/// answer go back to queued so they are resent once it reconnects. Proxy
+	/// confirmations involving the device stay open until their own timeout, so
+	/// the voucher's resent vouch finds them after a reconnect.
+	async fn handle_device_disconnected(&self, device_id: Uuid) -> Result<()> {
+		{
+			let mut sessions = self.active_sessions.write().await;
+			let mut history = self.pairing_history.write().await;
+			sessions.retain(|_, session| {
+				if session.remote_device_id != Some(device_id) {
+					return true;
+				}
+				if !history.record_if_finished(session) {
+					history.record(session, PairingOutcome::Cancelled);
+				}
+				false
+			});
+		}
+
+		let waiting_sessions = {
+			let sessions = self.vouching_sessions.read().await;
+			sessions
+				.values()
+				.filter(|session| {
+					session.vouches.iter().any(|vouch| {
+						vouch.device_id == device_id && matches!(vouch.status, VouchStatus::Waiting)
+					})
+				})
+				.map(|session| session.id)
+				.collect::<Vec<_>>()
+		};
+
+		let queue = { self.vouching_queue.read().await.clone() };
+		for session_id in waiting_sessions {
+			if let Some(queue) = &queue {
+				let entry = queue
+					.list_entries_for_session(session_id)
+					.await?
+					.into_iter()
+					.find(|entry| entry.target_device_id == device_id);
+				if let Some(entry) = entry {
+					queue
+						.update_status(
+							session_id,
+							device_id,
+							VouchQueueStatus::Queued,
+							entry.retry_count,
+							entry.last_attempt_at,
+							Some("Device disconnected before responding".to_string()),
+						)
+						.await?;
+				}
 			}
+			self.update_vouch_status(session_id, device_id, VouchStatus::Queued, None)
+				.await?;
+			self.log_info(&format!(
+				"Re-queued vouch for session {} after device {} disconnected",
+				session_id, device_id
+			))
+			.await;
+		}
+
+		Ok(())
+	}
 
+	/// Send one queued vouch, recording the outcome on its queue entry
+	///
+	/// Returns whether the request went out. A failed send leaves the entry queued for the next pass with the error kept
+	/// in `last_attempt_error`, so the queue shows why a vouch is stuck. A successful send resets `retry_count`, so the
+	/// retry limit counts consecutive failures rather than every failure since the vouch was queued. A target that keeps
+	/// rejecting the voucher is not sent to until its cooldown ends; the entry stays queued without counting a retry.
+	async fn send_queued_vouch(
+		&self,
+		queue: &VouchingQueue,
+		entry: &VouchingQueueEntry,
+		node_id: EndpointId,
+		now: chrono::DateTime<chrono::Utc>,
+	) -> Result<bool> {
+		let config = { self.proxy_config.read().await.clone() };
+		if self
+			.auto_vouch_paused(entry.voucher_device_id, entry.target_device_id, &config)
+			.await
+		{
+			return Ok(false);
+		}
+
+		let timestamp = chrono::Utc::now();
+		let payload = self.build_vouch_payload(
+			entry.session_id,
+			&entry.vouchee_device_info,
+			&entry.vouchee_public_key,
+			timestamp,
+		);
+		let signature = self.sign_vouch_payload(&payload)?;
+
+		let request = PairingMessage::ProxyPairingRequest {
+			session_id: entry.session_id,
+			vouchee_device_info: entry.vouchee_device_info.clone(),
+			vouchee_public_key: entry.vouchee_public_key.clone(),
+			voucher_device_id: entry.voucher_device_id,
+			voucher_signature: signature,
+			timestamp,
+		};
+
+		if let Err(e) = self
+			.send_pairing_message_fire_and_forget(node_id, &request)
+			.await
+		{
+			self.log_warn(&format!(
+				"Failed to send queued proxy pairing request to {}: {}",
+				entry.target_device_id, e
+			))
+			.await;
 			queue
 				.update_status(
 					entry.session_id,
 					entry.target_device_id,
-					VouchQueueStatus::Waiting,
+					VouchQueueStatus::Queued,
 					entry.retry_count + 1,
 					Some(now),
+					Some(e.to_string()),
 				)
 				.await?;
+			return Ok(false);
+		}
 
-			self.update_vouch_status(
+		queue
+			.update_status(
 				entry.session_id,
 				entry.target_device_id,
-				VouchStatus::Waiting,
+				VouchQueueStatus::Waiting,
+				0,
+				Some(now),
 				None,
 			)
 			.await?;
-		}
+		Ok(true)
+	}
 
-		Ok(())
+	/// Reject a message carrying device info over the configured caps
+	///
+	/// Checked before the message is handled, so oversized info is never stored,
+	/// shown or signed into a vouch.
+	async fn check_message_device_info(&self, message: &PairingMessage) -> Result<()> {
+		let max_size = { self.proxy_config.read().await.max_device_info_size };
+		message.validate_device_info(max_size)
 	}
 
 	/// Handle a pairing message received over stream
@@ -1792,16 +3668,26 @@ impl PairingProtocolHandler {
 		message: PairingMessage,
 		remote_node_id: EndpointId,
 	) -> Result<Option<Vec<u8>>> {
+		self.check_message_device_info(&message).await?;
+
 		match message {
 			PairingMessage::PairingRequest {
 				session_id,
 				device_info,
 				public_key,
 			} => {
-				// Generate a temporary device ID based on node ID
-				let from_device = self.get_device_id_for_node(remote_node_id).await;
+				// Derive a device ID from the presented public key until the device is registered
+				let from_device = self
+					.get_device_id_for_node(remote_node_id, Some(&public_key))
+					.await;
 				let response = self
-					.handle_pairing_request(from_device, session_id, device_info, public_key)
+					.handle_pairing_request(
+						from_device,
+						remote_node_id,
+						session_id,
+						device_info,
+						public_key,
+					)
 					.await?;
 				Ok(Some(response))
 			}
@@ -1820,7 +3706,10 @@ impl PairingProtocolHandler {
 				response,
 				device_info,
 			} => {
-				let from_device = self.get_device_id_for_node(remote_node_id).await;
+				let public_key = self.session_public_key(session_id).await;
+				let from_device = self
+					.get_device_id_for_node(remote_node_id, public_key.as_deref())
+					.await;
 				let response = self
 					.handle_pairing_response(from_device, session_id, response, device_info)
 					.await?;
@@ -1831,7 +3720,10 @@ impl PairingProtocolHandler {
 				success,
 				reason,
 			} => {
-				let from_device = self.get_device_id_for_node(remote_node_id).await;
+				let public_key = self.session_public_key(session_id).await;
+				let from_device = self
+					.get_device_id_for_node(remote_node_id, public_key.as_deref())
+					.await;
 				self.handle_completion(session_id, success, reason, from_device, remote_node_id)
 					.await?;
 				Ok(None) // No response needed
@@ -1843,7 +3735,6 @@ impl PairingProtocolHandler {
 				voucher_device_id,
 				voucher_signature,
 				timestamp,
-				proxied_session_keys,
 			} => {
 				self.handle_proxy_pairing_request(
 					session_id,
@@ -1852,7 +3743,6 @@ impl PairingProtocolHandler {
 					voucher_device_id,
 					voucher_signature,
 					timestamp,
-					proxied_session_keys,
 					remote_node_id,
 				)
 				.await?;
@@ -1878,55 +3768,193 @@ impl PairingProtocolHandler {
 				voucher_device_id,
 				accepted_by,
 				rejected_by,
+				voucher_signature,
 			} => {
 				self.handle_proxy_pairing_complete(
 					session_id,
 					voucher_device_id,
 					accepted_by,
 					rejected_by,
+					voucher_signature,
 				)
 				.await?;
 				Ok(None)
 			}
-		}
-	}
-
-	/// Get or create a device ID for a node
-	async fn get_device_id_for_node(&self, node_id: EndpointId) -> Uuid {
-		let registry = self.device_registry.read().await;
-		registry.get_device_by_node(node_id).unwrap_or_else(|| {
-			// Generate a deterministic UUID from the node ID
-			let mut hasher = blake3::Hasher::new();
-			hasher.update(b"spacedrive-device-id");
-			hasher.update(node_id.as_bytes());
-			let hash = hasher.finalize();
-			let mut uuid_bytes = [0u8; 16];
-			uuid_bytes.copy_from_slice(&hash.as_bytes()[..16]);
-			Uuid::from_bytes(uuid_bytes)
-		})
-	}
-
-	/// Send a pairing message to a specific node using Iroh streams
-	/// This implementation follows Iroh best practices:
-	/// - Reuses persistent connections (cached in self.connections)
-	/// - Creates a new stream for each message exchange
-	/// - Keeps connections alive for future messages
-	pub async fn send_pairing_message_to_node(
-		&self,
-		endpoint: &Endpoint,
-		node_id: EndpointId,
+			PairingMessage::ProxyPairingDeclined {
+				session_id,
+				vouchee_device_id,
+				declined_device_id,
+			} => {
+				self.handle_proxy_pairing_declined(
+					session_id,
+					vouchee_device_id,
+					declined_device_id,
+					remote_node_id,
+				)
+				.await?;
+				Ok(None)
+			}
+			PairingMessage::DeviceInfoUpdate {
+				session_id,
+				device_info,
+			} => {
+				let response = self
+					.handle_device_info_update(session_id, device_info, remote_node_id)
+					.await?;
+				Ok(Some(response))
+			}
+			PairingMessage::RekeyRequest {
+				session_id,
+				nonce,
+				proof,
+			} => {
+				let response = self
+					.handle_rekey_request(session_id, nonce, proof, remote_node_id)
+					.await?;
+				Ok(Some(response))
+			}
+			PairingMessage::RekeyConfirm { session_id, proof } => {
+				let commit = self
+					.handle_rekey_confirm(session_id, proof, remote_node_id)
+					.await?;
+				Ok(Some(
+					serde_json::to_vec(&commit).map_err(NetworkingError::Serialization)?,
+				))
+			}
+			PairingMessage::RekeyCommit { session_id, proof } => {
+				self.handle_rekey_commit(session_id, proof, remote_node_id)
+					.await?;
+				Ok(None)
+			}
+			PairingMessage::Error {
+				session_id,
+				code,
+				message,
+			} => {
+				self.handle_remote_error(session_id, code, message).await;
+				Ok(None)
+			}
+		}
+	}
+
+	/// Resolve the logical device id for a remote node
+	///
+	/// Precedence:
+	/// 1. The device the registry already maps to this node id
+	/// 2. An id derived from the remote's ed25519 public key, when the handshake provided one
+	/// 3. An id derived from the node id itself
+	///
+	/// The public key outlives node id rotation (relay changes, endpoint rebinds), so
+	/// preferring it keeps an unknown device on one logical id across reconnects.
+	async fn get_device_id_for_node(&self, node_id: EndpointId, public_key: Option<&[u8]>) -> Uuid {
+		let registry = self.device_registry.read().await;
+		registry
+			.get_device_by_node(node_id)
+			.unwrap_or_else(|| derive_device_id(public_key, node_id))
+	}
+
+	/// The peer could not handle one of our messages: fail the session now
+	/// instead of waiting for it to time out
+	async fn handle_remote_error(
+		&self,
+		session_id: Uuid,
+		code: PairingFailureReason,
+		message: String,
+	) -> NetworkingError {
+		self.log_warn(&format!(
+			"Peer reported an error for pairing session {}: {} ({})",
+			session_id, code, message
+		))
+		.await;
+
+		if let Some(session) = self.active_sessions.write().await.get_mut(&session_id) {
+			if !matches!(
+				session.state,
+				PairingState::Completed | PairingState::Failed { .. }
+			) {
+				session.state = PairingState::failed_with(code, message.clone());
+			}
+		}
+
+		NetworkingError::Protocol(format!("Peer reported {}: {}", code, message))
+	}
+
+	/// Record that a session's connection fell back to the relay
+	async fn mark_session_via_relay(&self, session_id: Uuid) {
+		if let Some(session) = self.active_sessions.write().await.get_mut(&session_id) {
+			session.via_relay = true;
+		}
+		self.log_warn(&format!(
+			"Pairing session {} is running over the relay; transfers may be slower",
+			session_id
+		))
+		.await;
+	}
+
+	/// Public key the remote presented for a session, if the handshake got that far
+	async fn session_public_key(&self, session_id: Uuid) -> Option<Vec<u8>> {
+		let sessions = self.active_sessions.read().await;
+		sessions
+			.get(&session_id)
+			.and_then(|session| session.remote_public_key.clone())
+	}
+
+	/// Warm the pairing connection to a node before any session exists
+	///
+	/// Lets the UI show a discovered peer as ready to pair, and the handshake then
+	/// reuses the cached connection instead of dialing. No session state is created.
+	/// Returns whether the connection is up.
+	pub async fn probe_connection(&self, node_id: EndpointId) -> bool {
+		let Some(endpoint) = &self.endpoint else {
+			return false;
+		};
+
+		match utils::get_or_create_connection(
+			self.connections.clone(),
+			endpoint,
+			node_id,
+			crate::service::network::core::PAIRING_ALPN,
+			&self.logger,
+		)
+		.await
+		{
+			Ok(_) => true,
+			Err(e) => {
+				self.log_debug(&format!("Pairing probe to {} failed: {}", node_id, e))
+					.await;
+				false
+			}
+		}
+	}
+
+	/// Send a pairing message to a specific node using Iroh streams
+	/// This implementation follows Iroh best practices:
+	/// - Reuses persistent connections (cached in self.connections)
+	/// - Creates a new stream for each message exchange
+	/// - Keeps connections alive for future messages
+	///
+	/// If the direct dial fails or times out, the connection falls back to the peer's home relay
+	/// and the message's session is marked `via_relay`.
+	pub async fn send_pairing_message_to_node(
+		&self,
+		endpoint: &Endpoint,
+		node_id: EndpointId,
 		message: &PairingMessage,
 	) -> Result<Option<PairingMessage>> {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 
-		let conn = utils::get_or_create_connection(
+		let (conn, via_relay) = utils::get_or_create_connection_with_relay_fallback(
 			self.connections.clone(),
 			endpoint,
 			node_id,
 			crate::service::network::core::PAIRING_ALPN,
+			utils::DIRECT_DIAL_TIMEOUT,
 			&self.logger,
 		)
 		.await?;
+		if via_relay {
+			self.mark_session_via_relay(message.session_id()).await;
+		}
 
 		let (mut send, mut recv) = conn.open_bi().await.map_err(|e| {
 			NetworkingError::ConnectionFailed(format!("Failed to open stream: {}", e))
@@ -1954,30 +3982,25 @@ impl PairingProtocolHandler {
 			return self.handle_joiner_pairing_stream(send, recv, node_id).await;
 		}
 
-		let mut len_buf = [0u8; 4];
-		match recv.read_exact(&mut len_buf).await {
-			Ok(_) => {
-				let resp_len = u32::from_be_bytes(len_buf) as usize;
-
-				if resp_len > MAX_MESSAGE_SIZE {
-					return Err(NetworkingError::Protocol(format!(
-						"Message too large: {} bytes (max: {} bytes)",
-						resp_len, MAX_MESSAGE_SIZE
-					)));
-				}
-
-				let mut resp_buf = vec![0u8; resp_len];
-				recv.read_exact(&mut resp_buf).await.map_err(|e| {
-					NetworkingError::Transport(format!("Failed to read response: {}", e))
-				})?;
+		let Some(resp_buf) = super::read_frame_if_any(&mut recv, self.max_message_size()).await?
+		else {
+			return Ok(None);
+		};
 
-				let response: PairingMessage = serde_json::from_slice(&resp_buf)
-					.map_err(|e| NetworkingError::Serialization(e))?;
+		let response: PairingMessage =
+			serde_json::from_slice(&resp_buf).map_err(|e| NetworkingError::Serialization(e))?;
+		self.check_message_device_info(&response).await?;
 
-				Ok(Some(response))
-			}
-			Err(_) => Ok(None),
+		if let PairingMessage::Error {
+			session_id,
+			code,
+			message,
+		} = response
+		{
+			return Err(self.handle_remote_error(session_id, code, message).await);
 		}
+
+		Ok(Some(response))
 	}
 
 	/// Handle the joiner's full pairing handshake on a single stream
@@ -1987,29 +4010,14 @@ impl PairingProtocolHandler {
 		mut recv: impl tokio::io::AsyncRead + Unpin,
 		initiator_node_id: EndpointId,
 	) -> Result<Option<PairingMessage>> {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 
 		// Read Challenge from initiator
-		let mut len_buf = [0u8; 4];
-		recv.read_exact(&mut len_buf).await.map_err(|e| {
-			NetworkingError::Transport(format!("Failed to read challenge length: {}", e))
-		})?;
-		let challenge_len = u32::from_be_bytes(len_buf) as usize;
-
-		if challenge_len > MAX_MESSAGE_SIZE {
-			return Err(NetworkingError::Protocol(format!(
-				"Challenge too large: {} bytes",
-				challenge_len
-			)));
-		}
-
-		let mut challenge_buf = vec![0u8; challenge_len];
-		recv.read_exact(&mut challenge_buf)
-			.await
-			.map_err(|e| NetworkingError::Transport(format!("Failed to read challenge: {}", e)))?;
+		let challenge_buf = super::read_frame(&mut recv, self.max_message_size()).await?;
 
 		let challenge_msg: PairingMessage = serde_json::from_slice(&challenge_buf)
 			.map_err(|e| NetworkingError::Serialization(e))?;
+		self.check_message_device_info(&challenge_msg).await?;
 
 		// Process Challenge and generate Response
 		let (session_id, response_data) = match challenge_msg {
@@ -2028,6 +4036,13 @@ impl PairingProtocolHandler {
 					.await?;
 				(session_id, response)
 			}
+			PairingMessage::Error {
+				session_id,
+				code,
+				message,
+			} => {
+				return Err(self.handle_remote_error(session_id, code, message).await);
+			}
 			_ => {
 				return Err(NetworkingError::Protocol(
 					"Expected Challenge message".to_string(),
@@ -2058,22 +4073,7 @@ impl PairingProtocolHandler {
 		.await;
 
 		// Read Complete message
-		recv.read_exact(&mut len_buf).await.map_err(|e| {
-			NetworkingError::Transport(format!("Failed to read complete length: {}", e))
-		})?;
-		let complete_len = u32::from_be_bytes(len_buf) as usize;
-
-		if complete_len > MAX_MESSAGE_SIZE {
-			return Err(NetworkingError::Protocol(format!(
-				"Complete message too large: {} bytes",
-				complete_len
-			)));
-		}
-
-		let mut complete_buf = vec![0u8; complete_len];
-		recv.read_exact(&mut complete_buf)
-			.await
-			.map_err(|e| NetworkingError::Transport(format!("Failed to read complete: {}", e)))?;
+		let complete_buf = super::read_frame(&mut recv, self.max_message_size()).await?;
 
 		let complete_msg: PairingMessage =
 			serde_json::from_slice(&complete_buf).map_err(|e| NetworkingError::Serialization(e))?;
@@ -2091,7 +4091,10 @@ impl PairingProtocolHandler {
 				.await;
 
 				// Process completion
-				let from_device = self.get_device_id_for_node(initiator_node_id).await;
+				let public_key = self.session_public_key(complete_session_id).await;
+				let from_device = self
+					.get_device_id_for_node(initiator_node_id, public_key.as_deref())
+					.await;
 				self.handle_completion(
 					complete_session_id,
 					success,
@@ -2100,6 +4103,10 @@ impl PairingProtocolHandler {
 					initiator_node_id,
 				)
 				.await?;
+				if success {
+					self.reveal_device_info(complete_session_id, initiator_node_id)
+						.await;
+				}
 
 				Ok(Some(PairingMessage::Complete {
 					session_id: complete_session_id,
@@ -2107,6 +4114,11 @@ impl PairingProtocolHandler {
 					reason: None,
 				}))
 			}
+			PairingMessage::Error {
+				session_id,
+				code,
+				message,
+			} => Err(self.handle_remote_error(session_id, code, message).await),
 			_ => Err(NetworkingError::Protocol(
 				"Expected Complete message".to_string(),
 			)),
@@ -2123,19 +4135,19 @@ impl PairingProtocolHandler {
 			.send(
 				crate::service::network::core::event_loop::EventLoopCommand::SendMessageToNode {
 					node_id,
-					protocol: "pairing".to_string(),
+					protocol: Protocol::Pairing,
 					data,
 				},
 			)
-			.map_err(|_| NetworkingError::Protocol("Pairing command channel closed".to_string()))?;
+			.await?;
 		Ok(())
 	}
 }
 
 #[async_trait]
 impl ProtocolHandler for PairingProtocolHandler {
-	fn protocol_name(&self) -> &str {
-		"pairing"
+	fn protocol_name(&self) -> Protocol {
+		Protocol::Pairing
 	}
 
 	fn as_any(&self) -> &dyn std::any::Any {
@@ -2148,7 +4160,7 @@ impl ProtocolHandler for PairingProtocolHandler {
 		mut recv: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
 		remote_node_id: EndpointId,
 	) {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 
 		self.logger
 			.info(&format!(
@@ -2159,47 +4171,28 @@ impl ProtocolHandler for PairingProtocolHandler {
 
 		// Keep the stream alive for multiple message exchanges
 		loop {
-			// Read the message length (4 bytes)
-			let mut len_buf = [0u8; 4];
-			match recv.read_exact(&mut len_buf).await {
-				Ok(_) => {}
+			// Read the next message; oversized ones are rejected before their body is read
+			let msg_buf = match super::read_frame_if_any(&mut recv, self.max_message_size()).await {
+				Ok(Some(buf)) => buf,
+				Ok(None) => {
+					// Connection closed - this is normal when the other side closes
+					self.logger
+						.debug("Stream closed before another message arrived")
+						.await;
+					break;
+				}
 				Err(e) => {
-					// Connection closed or error - this is normal when the other side closes
 					self.logger
-						.debug(&format!(
-							"Stream closed or error reading message length: {}",
-							e
-						))
+						.error(&format!("Failed to read message: {}", e))
 						.await;
 					break;
 				}
-			}
-			let msg_len = u32::from_be_bytes(len_buf) as usize;
-
-			// Validate message size to prevent DoS attacks
-			if msg_len > MAX_MESSAGE_SIZE {
-				self.logger
-					.error(&format!(
-						"Rejecting oversized message: {} bytes (max: {} bytes)",
-						msg_len, MAX_MESSAGE_SIZE
-					))
-					.await;
-				break;
-			}
+			};
 
 			self.logger
-				.info(&format!("Read message length: {} bytes", msg_len))
+				.info(&format!("Read message length: {} bytes", msg_buf.len()))
 				.await;
 
-			// Read the message
-			let mut msg_buf = vec![0u8; msg_len];
-			if let Err(e) = recv.read_exact(&mut msg_buf).await {
-				self.logger
-					.error(&format!("Failed to read message: {}", e))
-					.await;
-				break;
-			}
-
 			// Deserialize and handle the message
 			let message: PairingMessage = match serde_json::from_slice(&msg_buf) {
 				Ok(msg) => {
@@ -2212,6 +4205,12 @@ impl ProtocolHandler for PairingProtocolHandler {
 						PairingMessage::ProxyPairingRequest { .. } => "ProxyPairingRequest",
 						PairingMessage::ProxyPairingResponse { .. } => "ProxyPairingResponse",
 						PairingMessage::ProxyPairingComplete { .. } => "ProxyPairingComplete",
+						PairingMessage::ProxyPairingDeclined { .. } => "ProxyPairingDeclined",
+						PairingMessage::DeviceInfoUpdate { .. } => "DeviceInfoUpdate",
+						PairingMessage::RekeyRequest { .. } => "RekeyRequest",
+						PairingMessage::RekeyConfirm { .. } => "RekeyConfirm",
+						PairingMessage::RekeyCommit { .. } => "RekeyCommit",
+						PairingMessage::Error { .. } => "Error",
 					};
 					self.logger
 						.info(&format!(
@@ -2229,18 +4228,38 @@ impl ProtocolHandler for PairingProtocolHandler {
 				}
 			};
 
-			// Process the message and get response
+			// Process the message and get response. Errors the peer can act on are
+			// sent back and the stream stays open; anything else closes it.
 			let response = match self
 				.handle_pairing_message(message.clone(), remote_node_id)
 				.await
 			{
 				Ok(resp) => resp,
-				Err(e) => {
-					self.logger
-						.error(&format!("Failed to handle pairing message: {}", e))
-						.await;
-					break;
-				}
+				Err(e) => match PairingMessage::error_reply(message.session_id(), &e) {
+					Some(reply) => {
+						self.logger
+							.warn(&format!(
+								"Failed to handle pairing message, reporting to peer: {}",
+								e
+							))
+							.await;
+						match serde_json::to_vec(&reply) {
+							Ok(data) => Some(data),
+							Err(e) => {
+								self.logger
+									.error(&format!("Failed to serialize error reply: {}", e))
+									.await;
+								break;
+							}
+						}
+					}
+					None => {
+						self.logger
+							.error(&format!("Failed to handle pairing message: {}", e))
+							.await;
+						break;
+					}
+				},
 			};
 
 			// Send response if any
@@ -2291,6 +4310,7 @@ impl ProtocolHandler for PairingProtocolHandler {
 	async fn handle_request(&self, from_device: Uuid, request_data: Vec<u8>) -> Result<Vec<u8>> {
 		let message: PairingMessage =
 			serde_json::from_slice(&request_data).map_err(|e| NetworkingError::Serialization(e))?;
+		self.check_message_device_info(&message).await?;
 
 		let result = match message {
 			// Initiator handles these messages
@@ -2299,8 +4319,27 @@ impl ProtocolHandler for PairingProtocolHandler {
 				device_info,
 				public_key,
 			} => {
-				self.handle_pairing_request(from_device, session_id, device_info, public_key)
-					.await
+				// Only a device we know the node of can have its claimed identity checked
+				let remote_node_id = {
+					let registry = self.device_registry.read().await;
+					registry.get_node_id_for_device(from_device)
+				};
+				match remote_node_id {
+					Some(remote_node_id) => {
+						self.handle_pairing_request(
+							from_device,
+							remote_node_id,
+							session_id,
+							device_info,
+							public_key,
+						)
+						.await
+					}
+					None => Err(NetworkingError::AuthenticationFailed(format!(
+						"Pairing request from device {} with no known node",
+						from_device
+					))),
+				}
 			}
 			PairingMessage::Response {
 				session_id,
@@ -2313,6 +4352,12 @@ impl ProtocolHandler for PairingProtocolHandler {
 			PairingMessage::ProxyPairingRequest { .. }
 			| PairingMessage::ProxyPairingResponse { .. }
 			| PairingMessage::ProxyPairingComplete { .. }
+			| PairingMessage::ProxyPairingDeclined { .. }
+			| PairingMessage::DeviceInfoUpdate { .. }
+			| PairingMessage::RekeyRequest { .. }
+			| PairingMessage::RekeyConfirm { .. }
+			| PairingMessage::RekeyCommit { .. }
+			| PairingMessage::Error { .. }
 			| PairingMessage::Challenge { .. }
 			| PairingMessage::Complete { .. } => {
 				self.log_warn(
@@ -2335,14 +4380,21 @@ impl ProtocolHandler for PairingProtocolHandler {
 					PairingMessage::ProxyPairingRequest { session_id, .. } => Some(session_id),
 					PairingMessage::ProxyPairingResponse { session_id, .. } => Some(session_id),
 					PairingMessage::ProxyPairingComplete { session_id, .. } => Some(session_id),
+					PairingMessage::ProxyPairingDeclined { session_id, .. } => Some(session_id),
+					PairingMessage::DeviceInfoUpdate { session_id, .. } => Some(session_id),
+					PairingMessage::RekeyRequest { session_id, .. } => Some(session_id),
+					PairingMessage::RekeyConfirm { session_id, .. } => Some(session_id),
+					PairingMessage::RekeyCommit { session_id, .. } => Some(session_id),
+					PairingMessage::Error { session_id, .. } => Some(session_id),
 				};
 
 				if let Some(session_id) = session_id {
 					// Mark session as failed
 					if let Some(session) = self.active_sessions.write().await.get_mut(&session_id) {
-						session.state = PairingState::Failed {
-							reason: error.to_string(),
-						};
+						session.state = PairingState::failed_with(
+							PairingFailureReason::from(error),
+							error.to_string(),
+						);
 						self.log_error(&format!(
 							"Marked pairing session {} as failed: {}",
 							session_id, error
@@ -2372,6 +4424,7 @@ impl ProtocolHandler for PairingProtocolHandler {
 		// Parse the response message
 		let message: PairingMessage = serde_json::from_slice(&response_data)
 			.map_err(|e| NetworkingError::Serialization(e))?;
+		self.check_message_device_info(&message).await?;
 
 		self.log_debug("Parsed message type successfully").await;
 
@@ -2485,9 +4538,10 @@ impl ProtocolHandler for PairingProtocolHandler {
 									.await;
 								let mut sessions = self.active_sessions.write().await;
 								if let Some(session) = sessions.get_mut(&session_id) {
-									session.state = PairingState::Failed {
-										reason: "No endpoint available".to_string(),
-									};
+									session.state = PairingState::failed_with(
+										PairingFailureReason::InternalError,
+										"No endpoint available",
+									);
 								}
 								return Ok(());
 							}
@@ -2505,9 +4559,10 @@ impl ProtocolHandler for PairingProtocolHandler {
 									.await;
 									let mut sessions = self.active_sessions.write().await;
 									if let Some(session) = sessions.get_mut(&session_id) {
-										session.state = PairingState::Failed {
-											reason: "Failed to deserialize Response".to_string(),
-										};
+										session.state = PairingState::failed_with(
+											PairingFailureReason::ProtocolError,
+											format!("Failed to deserialize Response: {}", e),
+										);
 									}
 									return Ok(());
 								}
@@ -2530,7 +4585,7 @@ impl ProtocolHandler for PairingProtocolHandler {
 								.await;
 
 								// Process the Complete message
-								if let Err(e) = self
+								match self
 									.handle_completion(
 										complete_session_id,
 										success,
@@ -2540,11 +4595,18 @@ impl ProtocolHandler for PairingProtocolHandler {
 									)
 									.await
 								{
-									self.log_error(&format!(
-										"Failed to process Complete message: {}",
-										e
-									))
-									.await;
+									Ok(()) if success => {
+										self.reveal_device_info(complete_session_id, from_node)
+											.await;
+									}
+									Ok(()) => {}
+									Err(e) => {
+										self.log_error(&format!(
+											"Failed to process Complete message: {}",
+											e
+										))
+										.await;
+									}
 								}
 							}
 							Ok(Some(_other_msg)) => {
@@ -2554,9 +4616,10 @@ impl ProtocolHandler for PairingProtocolHandler {
 								.await;
 								let mut sessions = self.active_sessions.write().await;
 								if let Some(session) = sessions.get_mut(&session_id) {
-									session.state = PairingState::Failed {
-										reason: "Unexpected response type".to_string(),
-									};
+									session.state = PairingState::failed_with(
+										PairingFailureReason::ProtocolError,
+										"Unexpected response type",
+									);
 								}
 							}
 							Ok(None) => {
@@ -2564,9 +4627,10 @@ impl ProtocolHandler for PairingProtocolHandler {
 									.await;
 								let mut sessions = self.active_sessions.write().await;
 								if let Some(session) = sessions.get_mut(&session_id) {
-									session.state = PairingState::Failed {
-										reason: "No Complete message received".to_string(),
-									};
+									session.state = PairingState::failed_with(
+										PairingFailureReason::TransportError,
+										"No Complete message received",
+									);
 								}
 							}
 							Err(e) => {
@@ -2577,9 +4641,10 @@ impl ProtocolHandler for PairingProtocolHandler {
 								.await;
 								let mut sessions = self.active_sessions.write().await;
 								if let Some(session) = sessions.get_mut(&session_id) {
-									session.state = PairingState::Failed {
-										reason: format!("Send failed: {}", e),
-									};
+									session.state = PairingState::failed_with(
+										PairingFailureReason::from(&e),
+										format!("Send failed: {}", e),
+									);
 								}
 							}
 						}
@@ -2601,9 +4666,21 @@ impl ProtocolHandler for PairingProtocolHandler {
 				self.handle_completion(session_id, success, reason, from_device, from_node)
 					.await?;
 			}
+			PairingMessage::Error {
+				session_id,
+				code,
+				message,
+			} => {
+				self.handle_remote_error(session_id, code, message).await;
+			}
 			PairingMessage::ProxyPairingRequest { .. }
 			| PairingMessage::ProxyPairingResponse { .. }
 			| PairingMessage::ProxyPairingComplete { .. }
+			| PairingMessage::ProxyPairingDeclined { .. }
+			| PairingMessage::DeviceInfoUpdate { .. }
+			| PairingMessage::RekeyRequest { .. }
+			| PairingMessage::RekeyConfirm { .. }
+			| PairingMessage::RekeyCommit { .. }
 			| PairingMessage::PairingRequest { .. }
 			| PairingMessage::Response { .. } => {
 				self.log_warn("Received message in handle_response - this should be handled by handle_request or stream").await;
@@ -2617,9 +4694,13 @@ impl ProtocolHandler for PairingProtocolHandler {
 	async fn handle_event(&self, event: ProtocolEvent) -> Result<()> {
 		match event {
 			ProtocolEvent::DeviceDisconnected { device_id } => {
-				// Clean up any active sessions for this device
-				let mut sessions = self.active_sessions.write().await;
-				sessions.retain(|_, session| session.remote_device_id != Some(device_id));
+				if let Err(e) = self.handle_device_disconnected(device_id).await {
+					self.log_warn(&format!(
+						"Failed to clean up pairing state for disconnected device {}: {}",
+						device_id, e
+					))
+					.await;
+				}
 			}
 			_ => {}
 		}
@@ -2627,3 +4708,3832 @@ impl ProtocolHandler for PairingProtocolHandler {
 		Ok(())
 	}
 }
+
+/// Derive a deterministic device id for a device the registry does not know yet
+///
+/// Uses the remote's ed25519 public key when available and falls back to the
+/// node id otherwise.
+fn derive_device_id(public_key: Option<&[u8]>, node_id: EndpointId) -> Uuid {
+	let mut hasher = blake3::Hasher::new();
+	hasher.update(b"spacedrive-device-id");
+	match public_key {
+		Some(public_key) => hasher.update(public_key),
+		None => hasher.update(node_id.as_bytes()),
+	};
+	let hash = hasher.finalize();
+	let mut uuid_bytes = [0u8; 16];
+	uuid_bytes.copy_from_slice(&hash.as_bytes()[..16]);
+	Uuid::from_bytes(uuid_bytes)
+}
+
+/// Run `tick` immediately and then every `interval()` until `shutdown` fires
+///
+/// The interval is re-read before each wait so config changes (e.g. a
+/// power-saver mode slowing background work) apply without a restart.
+async fn run_periodic<I, IFut, T, TFut>(shutdown: CancellationToken, mut interval: I, mut tick: T)
+where
+	I: FnMut() -> IFut,
+	IFut: Future<Output = Duration>,
+	T: FnMut() -> TFut,
+	TFut: Future<Output = ()>,
+{
+	let mut delay = Duration::ZERO;
+	loop {
+		tokio::select! {
+			biased;
+			_ = shutdown.cancelled() => break,
+			_ = tokio::time::sleep(delay) => {}
+		}
+		tick().await;
+		delay = interval().await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::loopback::LoopbackTransport;
+	use super::*;
+	use crate::crypto::key_manager::KeyManager;
+	use crate::device::DeviceManager;
+	use crate::service::network::core::event_loop::CommandChannelConfig;
+	use crate::service::network::utils::SilentLogger;
+	use tempfile::TempDir;
+
+	/// Builds a handler under test
+	///
+	/// Sessions stay in memory, logs are dropped, and there is no endpoint or
+	/// vouching queue unless asked for.
+	#[derive(Default)]
+	struct TestHandlerBuilder {
+		persistent: bool,
+		endpoint: bool,
+		vouching_queue: bool,
+		logger: Option<Arc<dyn NetworkLogger>>,
+		command_channel: CommandChannelConfig,
+	}
+
+	impl TestHandlerBuilder {
+		fn new() -> Self {
+			Self::default()
+		}
+
+		/// Persist sessions under the handler's temp dir
+		fn persistent(mut self) -> Self {
+			self.persistent = true;
+			self
+		}
+
+		/// Bind a local endpoint with no relay, so vouches can be dispatched
+		fn with_endpoint(mut self) -> Self {
+			self.endpoint = true;
+			self
+		}
+
+		/// Set up the vouching queue, which also holds the proxy pairing audit log
+		fn with_vouching_queue(mut self) -> Self {
+			self.vouching_queue = true;
+			self
+		}
+
+		fn logger(mut self, logger: Arc<dyn NetworkLogger>) -> Self {
+			self.logger = Some(logger);
+			self
+		}
+
+		fn command_channel(mut self, config: CommandChannelConfig) -> Self {
+			self.command_channel = config;
+			self
+		}
+
+		async fn build(self) -> (PairingProtocolHandler, TempDir) {
+			let (handler, _command_rx, temp_dir) = self.build_with_commands().await;
+			(handler, temp_dir)
+		}
+
+		/// Build the handler along with the receiving end of its command channel
+		async fn build_with_commands(
+			self,
+		) -> (
+			PairingProtocolHandler,
+			tokio::sync::mpsc::Receiver<
+				crate::service::network::core::event_loop::EventLoopCommand,
+			>,
+			TempDir,
+		) {
+			let logger = self.logger.unwrap_or_else(|| Arc::new(SilentLogger));
+			let temp_dir = TempDir::new().unwrap();
+			let device_key_fallback = temp_dir.path().join("device_key");
+			let key_manager = Arc::new(
+				KeyManager::new_with_fallback(
+					temp_dir.path().to_path_buf(),
+					Some(device_key_fallback),
+				)
+				.unwrap(),
+			);
+			let device_manager = Arc::new(
+				DeviceManager::init(&temp_dir.path().to_path_buf(), key_manager.clone(), None)
+					.unwrap(),
+			);
+			let registry = DeviceRegistry::new(device_manager, key_manager, logger.clone());
+			let (command_sender, command_rx) = CommandSender::channel(self.command_channel);
+
+			let identity = NetworkIdentity::new().await.unwrap();
+			let device_registry = Arc::new(RwLock::new(registry));
+			let mut handler = if self.persistent {
+				PairingProtocolHandler::new_with_persistence(
+					identity,
+					device_registry,
+					logger,
+					command_sender,
+					temp_dir.path().join("pairing"),
+					None,
+					Arc::new(RwLock::new(HashMap::new())),
+				)
+			} else {
+				PairingProtocolHandler::new(
+					identity,
+					device_registry,
+					logger,
+					command_sender,
+					None,
+					Arc::new(RwLock::new(HashMap::new())),
+				)
+			};
+			if self.endpoint {
+				handler.endpoint = Some(
+					Endpoint::builder()
+						.relay_mode(iroh::RelayMode::Disabled)
+						.bind()
+						.await
+						.unwrap(),
+				);
+			}
+			if self.vouching_queue {
+				handler
+					.init_vouching_queue(temp_dir.path().to_path_buf())
+					.await
+					.unwrap();
+			}
+			(handler, command_rx, temp_dir)
+		}
+	}
+
+	async fn create_test_handler() -> (PairingProtocolHandler, TempDir) {
+		TestHandlerBuilder::new().build().await
+	}
+
+	/// Device info for a peer whose node id is derived from `seed`
+	fn test_device_info(device_id: Uuid, seed: u8) -> DeviceInfo {
+		DeviceInfo {
+			device_id,
+			device_name: format!("Device {}", seed),
+			device_slug: format!("device-{}", seed),
+			device_type: crate::service::network::device::DeviceType::Desktop,
+			os_version: "Test OS 1.0".to_string(),
+			app_version: "1.0.0".to_string(),
+			network_fingerprint: NetworkFingerprint {
+				node_id: iroh::SecretKey::from_bytes(&[seed; 32])
+					.public()
+					.to_string(),
+				public_key_hash: "test_hash".to_string(),
+			},
+			last_seen: chrono::Utc::now(),
+			version: DeviceInfo::CURRENT_VERSION,
+		}
+	}
+
+	/// Pair `device_info` with `handler` directly, or through `voucher` when given
+	async fn pair_test_device(
+		handler: &PairingProtocolHandler,
+		device_info: DeviceInfo,
+		voucher: Option<Uuid>,
+	) {
+		use crate::service::network::device::PairingType;
+
+		let (pairing_type, vouched_at) = match voucher {
+			Some(_) => (PairingType::Proxied, Some(chrono::Utc::now())),
+			None => (PairingType::Direct, None),
+		};
+		handler
+			.device_registry
+			.write()
+			.await
+			.complete_pairing(
+				device_info.device_id,
+				device_info,
+				SessionKeys::from_shared_secret(vec![3; 32]),
+				None,
+				pairing_type,
+				voucher,
+				vouched_at,
+			)
+			.await
+			.unwrap();
+	}
+
+	/// Build a voucher handler and pair `handler` with it directly
+	async fn pair_test_voucher(
+		handler: &PairingProtocolHandler,
+	) -> (PairingProtocolHandler, Uuid, TempDir) {
+		let (voucher, voucher_dir) = create_test_handler().await;
+		let voucher_info = voucher.get_device_info().await.unwrap();
+		let voucher_id = voucher_info.device_id;
+		pair_test_device(handler, voucher_info, None).await;
+		(voucher, voucher_id, voucher_dir)
+	}
+
+	/// Have `voucher` vouch a fresh device to `handler`
+	///
+	/// Returns the vouchee's device id and the session id of the vouch.
+	async fn send_test_vouch(
+		handler: &PairingProtocolHandler,
+		voucher: &PairingProtocolHandler,
+	) -> (Uuid, Uuid) {
+		let vouchee = NetworkIdentity::new().await.unwrap();
+		let mut info = voucher.get_device_info().await.unwrap();
+		info.device_id = Uuid::new_v4();
+		info.network_fingerprint = vouchee.network_fingerprint();
+		let session_id = Uuid::new_v4();
+		let vouchee_id = info.device_id;
+		send_signed_vouch(
+			handler,
+			voucher,
+			session_id,
+			info,
+			vouchee.public_key_bytes(),
+		)
+		.await;
+		(vouchee_id, session_id)
+	}
+
+	/// Have `voucher` sign and send `handler` a vouch for `info` in `session_id`
+	async fn send_signed_vouch(
+		handler: &PairingProtocolHandler,
+		voucher: &PairingProtocolHandler,
+		session_id: Uuid,
+		info: DeviceInfo,
+		public_key: Vec<u8>,
+	) {
+		let voucher_id = voucher.get_device_info().await.unwrap().device_id;
+		let timestamp = chrono::Utc::now();
+		let payload = voucher.build_vouch_payload(session_id, &info, &public_key, timestamp);
+		let signature = voucher.sign_vouch_payload(&payload).unwrap();
+		handler
+			.handle_proxy_pairing_request(
+				session_id,
+				info,
+				public_key,
+				voucher_id,
+				signature,
+				timestamp,
+				voucher.identity.node_id(),
+			)
+			.await
+			.unwrap();
+	}
+
+	/// A session in `state` that has learned nothing about the remote device
+	fn test_session(id: Uuid, state: PairingState) -> PairingSession {
+		PairingSession {
+			id,
+			state,
+			remote_device_id: None,
+			remote_device_info: None,
+			remote_public_key: None,
+			shared_secret: None,
+			created_at: chrono::Utc::now(),
+			via_relay: false,
+			auto_accepted: false,
+		}
+	}
+
+	/// A session that finished pairing with `remote`, ready to vouch for it
+	fn completed_session(id: Uuid, remote: &DeviceInfo) -> PairingSession {
+		PairingSession {
+			remote_device_id: Some(remote.device_id),
+			remote_device_info: Some(remote.clone()),
+			remote_public_key: Some(vec![1; 32]),
+			shared_secret: Some(vec![2; 32]),
+			..test_session(id, PairingState::Completed)
+		}
+	}
+
+	async fn insert_test_session(handler: &PairingProtocolHandler, session: PairingSession) {
+		handler
+			.active_sessions
+			.write()
+			.await
+			.insert(session.id, session);
+	}
+
+	/// Record a finished pairing with the voucher, as the vouchee keeps it
+	async fn record_voucher_session(handler: &PairingProtocolHandler, voucher_id: Uuid) -> Uuid {
+		let session_id = Uuid::new_v4();
+		handler.pairing_history.write().await.record(
+			&PairingSession {
+				remote_device_id: Some(voucher_id),
+				..test_session(session_id, PairingState::Completed)
+			},
+			PairingOutcome::Completed,
+		);
+		session_id
+	}
+
+	/// A vouch for `vouchee` from `voucher_device_id` that awaits the user's answer
+	fn pending_confirmation(
+		session_id: Uuid,
+		voucher_device_id: Uuid,
+		vouchee: DeviceInfo,
+	) -> PendingProxyConfirmation {
+		PendingProxyConfirmation {
+			session_id,
+			voucher_device_id,
+			voucher_device_name: "Voucher".to_string(),
+			vouchee_device_info: vouchee,
+			vouchee_public_key: vec![0u8; 32],
+			proxied_session_keys: SessionKeys::from_shared_secret(vec![3; 32]),
+			created_at: chrono::Utc::now(),
+		}
+	}
+
+	async fn insert_pending_confirmation(
+		handler: &PairingProtocolHandler,
+		confirmation: PendingProxyConfirmation,
+	) {
+		handler
+			.pending_proxy_confirmations
+			.write()
+			.await
+			.insert(confirmation.session_id, confirmation);
+	}
+
+	/// A vouch for `vouchee` queued for `target_device_id`, live for ten minutes from `now`
+	fn queued_entry(
+		session_id: Uuid,
+		target_device_id: Uuid,
+		vouchee: &DeviceInfo,
+		now: chrono::DateTime<chrono::Utc>,
+	) -> VouchingQueueEntry {
+		VouchingQueueEntry {
+			session_id,
+			target_device_id,
+			voucher_device_id: vouchee.device_id,
+			vouchee_device_id: vouchee.device_id,
+			vouchee_device_info: vouchee.clone(),
+			vouchee_public_key: vec![1; 32],
+			voucher_signature: vec![2; 64],
+			created_at: now,
+			expires_at: now + chrono::Duration::minutes(10),
+			status: VouchQueueStatus::Queued,
+			retry_count: 0,
+			last_attempt_at: None,
+			last_attempt_error: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn test_scanning_timeout_reason() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let session_id = Uuid::new_v4();
+
+		insert_test_session(
+			&handler,
+			PairingSession {
+				created_at: chrono::Utc::now() - chrono::Duration::minutes(6),
+				..test_session(session_id, PairingState::Scanning)
+			},
+		)
+		.await;
+
+		handler.process_state_transitions().await.unwrap();
+
+		let sessions = handler.active_sessions.read().await;
+		match &sessions[&session_id].state {
+			PairingState::Failed { reason, .. } => {
+				assert_eq!(*reason, PairingFailureReason::Timeout)
+			}
+			other => panic!("Expected Failed state, got {:?}", other),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_state_machine_logs_response_sent() {
+		use crate::testing::{CapturedLevel, CapturingNetworkLogger};
+
+		let logger = CapturingNetworkLogger::new();
+		let (handler, mut command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.logger(Arc::new(logger.clone()))
+			.build_with_commands()
+			.await;
+		let session_id = Uuid::new_v4();
+		let remote_node_id = NetworkIdentity::new().await.unwrap().node_id();
+
+		insert_test_session(
+			&handler,
+			test_session(
+				session_id,
+				PairingState::ResponsePending {
+					challenge: vec![1, 2, 3],
+					response_data: vec![4, 5, 6],
+					remote_node_id: Some(remote_node_id),
+				},
+			),
+		)
+		.await;
+
+		handler.process_state_transitions().await.unwrap();
+
+		assert!(matches!(
+			handler.active_sessions.read().await[&session_id].state,
+			PairingState::ResponseSent
+		));
+		assert!(command_rx.try_recv().is_ok());
+		assert!(logger.contains(
+			CapturedLevel::Info,
+			&format!("State Machine: Response sent for session {}", session_id)
+		));
+		assert!(logger.messages_at(CapturedLevel::Error).is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_state_machine_fails_session_when_channel_stays_full() {
+		use crate::service::network::core::event_loop::{CommandOverflowPolicy, EventLoopCommand};
+
+		let (handler, _command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.command_channel(CommandChannelConfig {
+				capacity: 1,
+				overflow: CommandOverflowPolicy::DropWithWarning,
+			})
+			.build_with_commands()
+			.await;
+		let session_id = Uuid::new_v4();
+		let remote_node_id = NetworkIdentity::new().await.unwrap().node_id();
+
+		// Nothing drains the channel, so it stays full after this
+		handler
+			.command_sender
+			.try_send(EventLoopCommand::Shutdown)
+			.unwrap();
+		insert_test_session(
+			&handler,
+			test_session(
+				session_id,
+				PairingState::ResponsePending {
+					challenge: vec![1, 2, 3],
+					response_data: vec![4, 5, 6],
+					remote_node_id: Some(remote_node_id),
+				},
+			),
+		)
+		.await;
+
+		// Every tick short of the limit leaves the response pending for a retry
+		for _ in 1..MAX_FULL_CHANNEL_RETRIES {
+			handler.process_state_transitions().await.unwrap();
+			assert!(matches!(
+				handler.active_sessions.read().await[&session_id].state,
+				PairingState::ResponsePending { .. }
+			));
+		}
+
+		handler.process_state_transitions().await.unwrap();
+		match &handler.active_sessions.read().await[&session_id].state {
+			PairingState::Failed { reason, .. } => {
+				assert_eq!(*reason, PairingFailureReason::ChannelFull)
+			}
+			other => panic!("Expected Failed state, got {:?}", other),
+		}
+		assert!(handler.full_channel_retries.read().await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_completed_session_stays_in_history_after_removal() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let (completed_id, cancelled_id) = (Uuid::new_v4(), Uuid::new_v4());
+		let remote_device_id = Uuid::new_v4();
+
+		for (id, state) in [
+			(completed_id, PairingState::Completed),
+			(cancelled_id, PairingState::WaitingForConnection),
+		] {
+			insert_test_session(
+				&handler,
+				PairingSession {
+					remote_device_id: Some(remote_device_id),
+					created_at: chrono::Utc::now() - chrono::Duration::minutes(11),
+					..test_session(id, state)
+				},
+			)
+			.await;
+		}
+
+		// The state machine notices the completion; the session is dropped later
+		handler.process_state_transitions().await.unwrap();
+		handler.cancel_session(cancelled_id).await.unwrap();
+		assert_eq!(handler.cleanup_expired_sessions().await.unwrap(), 1);
+		assert!(handler.active_sessions.read().await.is_empty());
+
+		let history = handler.get_pairing_history().await;
+		assert_eq!(history.len(), 2);
+		assert_eq!(history[0].session_id, cancelled_id);
+		assert_eq!(history[0].outcome, PairingOutcome::Cancelled);
+		assert_eq!(history[1].session_id, completed_id);
+		assert_eq!(history[1].outcome, PairingOutcome::Completed);
+		assert_eq!(history[1].remote_device_id, Some(remote_device_id));
+	}
+
+	#[tokio::test]
+	async fn test_shutdown_stops_tasks_and_persists_sessions() {
+		let (handler, temp_dir) = TestHandlerBuilder::new().persistent().build().await;
+		let handler = Arc::new(handler);
+		let session_id = Uuid::new_v4();
+
+		insert_test_session(
+			&handler,
+			test_session(session_id, PairingState::WaitingForConnection),
+		)
+		.await;
+
+		PairingProtocolHandler::start_state_machine_task(handler.clone());
+		PairingProtocolHandler::start_cleanup_task(handler.clone());
+		PairingProtocolHandler::start_vouching_queue_task(handler.clone());
+		assert_eq!(handler.task_handles.lock().unwrap().len(), 3);
+
+		tokio::time::timeout(std::time::Duration::from_secs(5), handler.shutdown())
+			.await
+			.expect("shutdown should not hang")
+			.unwrap();
+
+		// Every task has exited and released its clone of the handler
+		assert!(handler.task_handles.lock().unwrap().is_empty());
+		assert_eq!(Arc::strong_count(&handler), 1);
+
+		let persisted = PairingPersistence::new(temp_dir.path().join("pairing"))
+			.load_sessions()
+			.await
+			.unwrap();
+		assert!(persisted.contains_key(&session_id));
+	}
+
+	#[tokio::test]
+	async fn test_short_poll_interval_ticks_more_often() {
+		use std::sync::atomic::{AtomicU32, Ordering};
+
+		async fn count_ticks(handler: Arc<PairingProtocolHandler>, window: Duration) -> u32 {
+			let ticks = Arc::new(AtomicU32::new(0));
+			let shutdown = CancellationToken::new();
+			let task = tokio::spawn(run_periodic(
+				shutdown.clone(),
+				{
+					let handler = handler.clone();
+					move || {
+						let handler = handler.clone();
+						async move { handler.vouch_queue_poll_interval().await }
+					}
+				},
+				{
+					let ticks = ticks.clone();
+					move || {
+						ticks.fetch_add(1, Ordering::SeqCst);
+						async {}
+					}
+				},
+			));
+			tokio::time::sleep(window).await;
+			shutdown.cancel();
+			task.await.unwrap();
+			ticks.load(Ordering::SeqCst)
+		}
+
+		let (default_handler, _default_dir) = create_test_handler().await;
+		let (fast_handler, _fast_dir) = create_test_handler().await;
+		fast_handler
+			.set_proxy_config(ProxyPairingConfig {
+				vouch_queue_poll_secs: 1,
+				..ProxyPairingConfig::default()
+			})
+			.await;
+
+		let window = Duration::from_millis(2500);
+		let (default_ticks, fast_ticks) = tokio::join!(
+			count_ticks(Arc::new(default_handler), window),
+			count_ticks(Arc::new(fast_handler), window),
+		);
+
+		// Both tick once on start; only the 1s interval ticks again in the window
+		assert_eq!(default_ticks, 1);
+		assert!(fast_ticks >= 3, "expected >= 3 ticks, got {}", fast_ticks);
+	}
+
+	#[tokio::test]
+	async fn test_device_info_falls_back_to_cache_when_registry_fails() {
+		let (handler, temp_dir) = create_test_handler().await;
+
+		let failing_registry = || {
+			let key_manager = Arc::new(
+				KeyManager::new_with_fallback(
+					temp_dir.path().to_path_buf(),
+					Some(temp_dir.path().join("device_key")),
+				)
+				.unwrap(),
+			);
+			let device_manager = Arc::new(
+				DeviceManager::init(&temp_dir.path().to_path_buf(), key_manager.clone(), None)
+					.unwrap(),
+			);
+			device_manager.poison_config();
+			DeviceRegistry::new(device_manager, key_manager, Arc::new(SilentLogger))
+		};
+
+		// Nothing cached yet, so the error surfaces
+		let working_registry = std::mem::replace(
+			&mut *handler.device_registry.write().await,
+			failing_registry(),
+		);
+		assert!(handler.get_device_info().await.is_err());
+
+		// A successful read is remembered and used while the registry fails
+		*handler.device_registry.write().await = working_registry;
+		let advertised = handler.get_device_info().await.unwrap();
+		*handler.device_registry.write().await = failing_registry();
+
+		let fallback = handler.get_device_info().await.unwrap();
+		assert_eq!(fallback.device_id, advertised.device_id);
+		assert_eq!(fallback.device_name, advertised.device_name);
+		assert_eq!(fallback.network_fingerprint, advertised.network_fingerprint);
+	}
+
+	#[tokio::test]
+	async fn test_privacy_mode_minimizes_initial_pairing_request() {
+		use crate::service::network::device::DeviceType;
+
+		let (handler, _temp_dir) = create_test_handler().await;
+		let session_id = Uuid::new_v4();
+		let local_info = handler.get_device_info().await.unwrap();
+
+		// Default: the full info goes out with the request
+		match handler
+			.build_pairing_request(session_id, local_info.clone())
+			.await
+		{
+			PairingMessage::PairingRequest { device_info, .. } => {
+				assert_eq!(device_info.os_version, std::env::consts::OS);
+				assert!(!device_info.is_minimized());
+			}
+			other => panic!("Expected PairingRequest, got {:?}", other),
+		}
+
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				privacy_mode: true,
+				..ProxyPairingConfig::default()
+			})
+			.await;
+
+		let request = handler
+			.build_pairing_request(session_id, local_info.clone())
+			.await;
+		let PairingMessage::PairingRequest {
+			device_info,
+			public_key,
+			..
+		} = &request
+		else {
+			panic!("Expected PairingRequest, got {:?}", request);
+		};
+		assert_eq!(device_info.device_id, local_info.device_id);
+		assert_eq!(device_info.device_name, local_info.device_name);
+		assert_eq!(device_info.device_slug, local_info.device_slug);
+		assert_eq!(
+			device_info.network_fingerprint.node_id,
+			local_info.network_fingerprint.node_id
+		);
+		assert!(device_info.os_version.is_empty());
+		assert!(device_info.app_version.is_empty());
+		assert!(matches!(&device_info.device_type, DeviceType::Other(s) if s.is_empty()));
+		assert_eq!(public_key, &handler.identity.public_key_bytes());
+
+		// Nothing about the platform or build appears on the wire
+		let wire = serde_json::to_string(&request).unwrap();
+		assert!(!wire.contains(env!("CARGO_PKG_VERSION")));
+		assert!(!wire.contains("Desktop"));
+	}
+
+	#[tokio::test]
+	async fn test_relay_fallback_marks_session_and_persists() {
+		let (handler, temp_dir) = TestHandlerBuilder::new().persistent().build().await;
+		let session_id = Uuid::new_v4();
+
+		insert_test_session(&handler, test_session(session_id, PairingState::Scanning)).await;
+
+		handler.mark_session_via_relay(session_id).await;
+		assert!(handler.active_sessions.read().await[&session_id].via_relay);
+
+		handler.save_sessions_to_persistence().await.unwrap();
+		let persisted = PairingPersistence::new(temp_dir.path().join("pairing"))
+			.load_sessions()
+			.await
+			.unwrap();
+		assert!(persisted[&session_id].via_relay);
+	}
+
+	#[tokio::test]
+	async fn test_abandoned_join_is_marked_failed() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new().persistent().build().await;
+		let (scanning, answered) = (Uuid::new_v4(), Uuid::new_v4());
+		for (session_id, state) in [
+			(scanning, PairingState::Scanning),
+			(answered, PairingState::ResponseSent),
+		] {
+			insert_test_session(&handler, test_session(session_id, state)).await;
+		}
+
+		let state = handler.abandon_scanning_session(scanning).await.unwrap();
+		assert!(matches!(
+			state,
+			PairingState::Failed {
+				reason: PairingFailureReason::Timeout,
+				..
+			}
+		));
+		assert_eq!(
+			JoinOutcome::classify(None, Some(&state)),
+			JoinOutcome::TimedOut
+		);
+		assert!(handler
+			.get_pairing_history()
+			.await
+			.iter()
+			.any(|entry| entry.session_id == scanning));
+
+		// A session the initiator already answered keeps going
+		assert!(matches!(
+			handler.abandon_scanning_session(answered).await,
+			Some(PairingState::ResponseSent)
+		));
+		assert!(handler
+			.abandon_scanning_session(Uuid::new_v4())
+			.await
+			.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_unpair_device_removes_registry_and_queue_state() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+
+		let device_id = Uuid::new_v4();
+		let node_id = iroh::SecretKey::from_bytes(&[9u8; 32]).public();
+		let info = test_device_info(device_id, 9);
+		pair_test_device(&handler, info.clone(), None).await;
+
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		let now = chrono::Utc::now();
+		queue
+			.upsert_entry(&queued_entry(Uuid::new_v4(), device_id, &info, now))
+			.await
+			.unwrap();
+
+		let unpaired = handler.unpair_device(device_id).await.unwrap();
+		assert_eq!(
+			unpaired,
+			UnpairedDevice {
+				was_paired: true,
+				dropped_vouches: 1,
+			}
+		);
+
+		let registry = handler.device_registry.read().await;
+		assert!(registry.get_device_state(device_id).is_none());
+		assert!(registry.get_device_by_node(node_id).is_none());
+		assert!(registry.get_session_keys(device_id).is_none());
+		drop(registry);
+		assert!(queue.list_entries().await.unwrap().is_empty());
+
+		// Unpairing again is a no-op
+		let unpaired = handler.unpair_device(device_id).await.unwrap();
+		assert!(!unpaired.was_paired);
+		assert_eq!(unpaired.dropped_vouches, 0);
+	}
+
+	#[tokio::test]
+	async fn test_failed_queued_send_records_error() {
+		// The test handler's command channel is already closed, so every send fails
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+
+		let node_id = iroh::SecretKey::from_bytes(&[5u8; 32]).public();
+		let now = chrono::Utc::now();
+		let vouchee = test_device_info(Uuid::new_v4(), 5);
+		let entry = queued_entry(Uuid::new_v4(), Uuid::new_v4(), &vouchee, now);
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		queue.upsert_entry(&entry).await.unwrap();
+
+		let sent = handler
+			.send_queued_vouch(&queue, &entry, node_id, now)
+			.await
+			.unwrap();
+		assert!(!sent);
+
+		let entries = queue.list_entries().await.unwrap();
+		assert_eq!(entries.len(), 1);
+		assert!(matches!(entries[0].status, VouchQueueStatus::Queued));
+		assert_eq!(entries[0].retry_count, 1);
+		assert!(entries[0].last_attempt_error.is_some());
+	}
+
+	#[tokio::test]
+	async fn test_queued_vouch_held_while_target_is_paused() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				vouch_rejection_threshold: 2,
+				..ProxyPairingConfig::default()
+			})
+			.await;
+
+		let node_id = iroh::SecretKey::from_bytes(&[5u8; 32]).public();
+		let now = chrono::Utc::now();
+		let vouchee = test_device_info(Uuid::new_v4(), 5);
+		let entry = queued_entry(Uuid::new_v4(), Uuid::new_v4(), &vouchee, now);
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		queue.upsert_entry(&entry).await.unwrap();
+		for _ in 0..2 {
+			queue
+				.record_rejection(entry.voucher_device_id, entry.target_device_id, now)
+				.await
+				.unwrap();
+		}
+
+		// Held back without an attempt, so it neither fails nor uses up a retry
+		let sent = handler
+			.send_queued_vouch(&queue, &entry, node_id, now)
+			.await
+			.unwrap();
+		assert!(!sent);
+		let entries = queue.list_entries().await.unwrap();
+		assert!(matches!(entries[0].status, VouchQueueStatus::Queued));
+		assert_eq!(entries[0].retry_count, 0);
+		assert!(entries[0].last_attempt_at.is_none());
+
+		// After the cooldown the resend is attempted again; the closed test channel fails it
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				vouch_rejection_threshold: 2,
+				vouch_rejection_cooldown_secs: 0,
+				..ProxyPairingConfig::default()
+			})
+			.await;
+		assert!(!handler
+			.send_queued_vouch(&queue, &entry, node_id, now)
+			.await
+			.unwrap());
+		assert_eq!(queue.list_entries().await.unwrap()[0].retry_count, 1);
+	}
+
+	#[tokio::test]
+	async fn test_prune_vouching_queue_counts() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		let vouchee = handler.get_device_info().await.unwrap();
+		let session_id = Uuid::new_v4();
+		handler
+			.create_vouching_session(session_id, &vouchee)
+			.await
+			.unwrap();
+
+		// Two vouches queued long enough ago to have expired, and one still live
+		let now = chrono::Utc::now();
+		let expired = [Uuid::new_v4(), Uuid::new_v4()];
+		for target in expired {
+			let entry = queued_entry(
+				session_id,
+				target,
+				&vouchee,
+				now - chrono::Duration::hours(1),
+			);
+			queue.upsert_entry(&entry).await.unwrap();
+		}
+		let live = queued_entry(session_id, Uuid::new_v4(), &vouchee, now);
+		queue.upsert_entry(&live).await.unwrap();
+		for target in expired.into_iter().chain([live.target_device_id]) {
+			handler
+				.update_vouch_status(session_id, target, VouchStatus::Queued, None)
+				.await
+				.unwrap();
+		}
+
+		let pruned = handler.prune_vouching_queue(None, None).await.unwrap();
+		assert_eq!(pruned.expired, 2);
+		assert_eq!(pruned.total(), 2);
+		assert_eq!(queue.list_entries().await.unwrap().len(), 1);
+
+		let status_of = |session: &VouchingSession, target: Uuid| {
+			session
+				.vouches
+				.iter()
+				.find(|vouch| vouch.device_id == target)
+				.map(|vouch| (vouch.status.clone(), vouch.reason.clone()))
+				.unwrap()
+		};
+		let session = handler.get_vouching_session(session_id).await.unwrap();
+		for target in expired {
+			let (status, reason) = status_of(&session, target);
+			assert!(matches!(status, VouchStatus::Unreachable));
+			assert_eq!(reason.as_deref(), Some("Vouch expired"));
+		}
+		assert!(matches!(
+			status_of(&session, live.target_device_id).0,
+			VouchStatus::Queued
+		));
+
+		// The live vouch only goes when its target is named
+		let pruned = handler
+			.prune_vouching_queue(Some(Uuid::new_v4()), Some(live.target_device_id))
+			.await
+			.unwrap();
+		assert_eq!(
+			pruned,
+			PrunedVouches {
+				expired: 0,
+				for_session: 0,
+				for_target: 1,
+			}
+		);
+		assert!(queue.list_entries().await.unwrap().is_empty());
+
+		let session = handler.get_vouching_session(session_id).await.unwrap();
+		let (status, reason) = status_of(&session, live.target_device_id);
+		assert!(matches!(status, VouchStatus::Unreachable));
+		assert_eq!(reason.as_deref(), Some("Removed from the vouching queue"));
+	}
+
+	#[tokio::test]
+	async fn test_dump_state_omits_secrets() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let session_id = Uuid::new_v4();
+		insert_test_session(
+			&handler,
+			PairingSession {
+				remote_public_key: Some(vec![0xE3; 32]),
+				shared_secret: Some(vec![0xF4; 32]),
+				..test_session(
+					session_id,
+					PairingState::ResponsePending {
+						challenge: vec![0xC1; 32],
+						response_data: vec![0xD2; 64],
+						remote_node_id: None,
+					},
+				)
+			},
+		)
+		.await;
+
+		let dump = handler.dump_state().await;
+		assert_eq!(dump.sessions.len(), 1);
+		let session = &dump.sessions[0];
+		assert_eq!(session.session_id, session_id);
+		assert_eq!(session.state, "ResponsePending");
+		assert_eq!(session.role, Some(PairingRole::Joiner));
+		assert!(session.has_shared_secret);
+		assert_eq!(dump.vouching_queue_depth, None);
+
+		let json = serde_json::to_string(&dump).unwrap();
+		assert!(json.contains("ResponsePending"));
+		for field in [
+			"\"shared_secret\"",
+			"public_key",
+			"challenge",
+			"response_data",
+		] {
+			assert!(!json.contains(field), "dump leaked {}", field);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_successful_send_resets_retry_count() {
+		let (handler, command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.with_endpoint()
+			.with_vouching_queue()
+			.build_with_commands()
+			.await;
+		let retry_limit = handler.proxy_config.read().await.vouch_queue_retry_limit;
+		let vouchee = handler.get_device_info().await.unwrap();
+		let session_id = Uuid::new_v4();
+		let node_id = iroh::SecretKey::from_bytes(&[6u8; 32]).public();
+		let now = chrono::Utc::now();
+
+		handler
+			.create_vouching_session(session_id, &vouchee)
+			.await
+			.unwrap();
+
+		// The target was offline long enough to use up all but one retry
+		let entry = VouchingQueueEntry {
+			retry_count: retry_limit - 1,
+			last_attempt_at: Some(now),
+			last_attempt_error: Some("offline".to_string()),
+			..queued_entry(session_id, Uuid::new_v4(), &vouchee, now)
+		};
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		queue.upsert_entry(&entry).await.unwrap();
+
+		// It comes back and the send goes out
+		assert!(handler
+			.send_queued_vouch(&queue, &entry, node_id, now)
+			.await
+			.unwrap());
+		let entry = queue.list_entries().await.unwrap().remove(0);
+		assert!(matches!(entry.status, VouchQueueStatus::Waiting));
+		assert_eq!(entry.retry_count, 0);
+
+		// A later blip: the vouch is re-queued and the resend fails
+		drop(command_rx);
+		let entry = VouchingQueueEntry {
+			status: VouchQueueStatus::Queued,
+			..entry
+		};
+		queue.upsert_entry(&entry).await.unwrap();
+		assert!(!handler
+			.send_queued_vouch(&queue, &entry, node_id, now)
+			.await
+			.unwrap());
+		assert_eq!(queue.list_entries().await.unwrap()[0].retry_count, 1);
+
+		// One failure in a row is within the budget, so the vouch is kept
+		handler.process_vouching_queue().await.unwrap();
+		assert_eq!(queue.list_entries().await.unwrap().len(), 1);
+		let session = handler.get_vouching_session(session_id).await.unwrap();
+		assert!(!session
+			.vouches
+			.iter()
+			.any(|vouch| matches!(vouch.status, VouchStatus::Unreachable)));
+	}
+
+	#[tokio::test]
+	async fn test_vouch_back_to_proxy_source_is_rejected() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new().with_endpoint().build().await;
+
+		// We were introduced to the target by the voucher device
+		let voucher_id = Uuid::new_v4();
+		let target_id = Uuid::new_v4();
+		pair_test_device(&handler, test_device_info(target_id, 11), Some(voucher_id)).await;
+		assert!(handler
+			.vouch_creates_cycle(voucher_id, target_id)
+			.await
+			.unwrap());
+		assert!(!handler
+			.vouch_creates_cycle(Uuid::new_v4(), target_id)
+			.await
+			.unwrap());
+
+		// Now the voucher pairs with us directly and we try to vouch it back to the target
+		let session_id = Uuid::new_v4();
+		insert_test_session(
+			&handler,
+			completed_session(session_id, &test_device_info(voucher_id, 12)),
+		)
+		.await;
+
+		let session = handler
+			.start_proxy_vouching(session_id, vec![target_id])
+			.await
+			.unwrap();
+		let vouch = session
+			.vouches
+			.iter()
+			.find(|vouch| vouch.device_id == target_id)
+			.unwrap();
+		assert!(matches!(vouch.status, VouchStatus::Rejected));
+		assert_eq!(
+			vouch.reason.as_deref(),
+			Some("Vouch would create a trust loop")
+		);
+	}
+
+	#[tokio::test]
+	async fn test_vouching_without_endpoint_fails_loudly() {
+		let (handler, _command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build_with_commands()
+			.await;
+		let vouchee = handler.get_device_info().await.unwrap();
+
+		// Starting a vouch is refused outright
+		let session_id = Uuid::new_v4();
+		insert_test_session(&handler, completed_session(session_id, &vouchee)).await;
+		let err = handler
+			.start_proxy_vouching(session_id, vec![Uuid::new_v4()])
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains(ENDPOINT_UNAVAILABLE));
+		assert!(handler.get_vouching_session(session_id).await.is_none());
+
+		// A vouch already queued is marked unreachable on the next poll
+		let queued_session = Uuid::new_v4();
+		let target_id = Uuid::new_v4();
+		handler
+			.create_vouching_session(queued_session, &vouchee)
+			.await
+			.unwrap();
+		let now = chrono::Utc::now();
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		queue
+			.upsert_entry(&queued_entry(queued_session, target_id, &vouchee, now))
+			.await
+			.unwrap();
+		handler
+			.update_vouch_status(queued_session, target_id, VouchStatus::Queued, None)
+			.await
+			.unwrap();
+
+		handler.process_vouching_queue().await.unwrap();
+		assert!(queue.list_entries().await.unwrap().is_empty());
+		let session = handler.get_vouching_session(queued_session).await.unwrap();
+		let vouch = session
+			.vouches
+			.iter()
+			.find(|vouch| vouch.device_id == target_id)
+			.unwrap();
+		assert!(matches!(vouch.status, VouchStatus::Unreachable));
+		assert_eq!(vouch.reason.as_deref(), Some(ENDPOINT_UNAVAILABLE));
+	}
+
+	#[tokio::test]
+	async fn test_vouching_many_targets_sets_every_status() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_endpoint()
+			.with_vouching_queue()
+			.build()
+			.await;
+
+		// More paired targets than are dispatched at once
+		let mut paired = Vec::new();
+		for seed in 0..(MAX_CONCURRENT_VOUCH_DISPATCH * 3) as u8 {
+			let target_id = Uuid::new_v4();
+			pair_test_device(&handler, test_device_info(target_id, seed + 20), None).await;
+			paired.push(target_id);
+		}
+		let unpaired = Uuid::new_v4();
+
+		let session_id = Uuid::new_v4();
+		let vouchee_id = Uuid::new_v4();
+		insert_test_session(
+			&handler,
+			completed_session(session_id, &test_device_info(vouchee_id, 12)),
+		)
+		.await;
+
+		let mut targets = paired.clone();
+		targets.push(unpaired);
+		let session = handler
+			.start_proxy_vouching(session_id, targets)
+			.await
+			.unwrap();
+
+		assert_eq!(session.vouches.len(), paired.len() + 1);
+		for vouch in &session.vouches {
+			if vouch.device_id == unpaired {
+				assert!(matches!(vouch.status, VouchStatus::Rejected));
+			} else {
+				// No target is connected, so every paired target is queued for later
+				assert!(matches!(vouch.status, VouchStatus::Queued));
+			}
+		}
+
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		assert_eq!(
+			queue
+				.list_entries_for_session(session_id)
+				.await
+				.unwrap()
+				.len(),
+			paired.len()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_vouching_many_targets_dispatches_concurrently() {
+		use std::sync::atomic::{AtomicUsize, Ordering};
+
+		/// Holds each warning for a moment and records how many were being logged
+		/// at once, which is how many dispatches were in flight together
+		#[derive(Default)]
+		struct InFlightLogger {
+			in_flight: AtomicUsize,
+			peak: AtomicUsize,
+		}
+
+		#[async_trait]
+		impl NetworkLogger for InFlightLogger {
+			async fn info(&self, _message: &str) {}
+			async fn warn(&self, _message: &str) {
+				let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+				self.peak.fetch_max(now, Ordering::SeqCst);
+				tokio::time::sleep(Duration::from_millis(20)).await;
+				self.in_flight.fetch_sub(1, Ordering::SeqCst);
+			}
+			async fn error(&self, _message: &str) {}
+			async fn debug(&self, _message: &str) {}
+		}
+
+		let logger = Arc::new(InFlightLogger::default());
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_endpoint()
+			.logger(logger.clone())
+			.build()
+			.await;
+
+		// Every target was introduced by the vouchee, so each dispatch warns about
+		// the trust loop before rejecting it
+		let vouchee_id = Uuid::new_v4();
+		let mut targets = Vec::new();
+		for seed in 0..(MAX_CONCURRENT_VOUCH_DISPATCH * 3) as u8 {
+			let target_id = Uuid::new_v4();
+			pair_test_device(
+				&handler,
+				test_device_info(target_id, seed + 20),
+				Some(vouchee_id),
+			)
+			.await;
+			targets.push(target_id);
+		}
+		let session_id = Uuid::new_v4();
+		insert_test_session(
+			&handler,
+			completed_session(session_id, &test_device_info(vouchee_id, 12)),
+		)
+		.await;
+
+		let session = handler
+			.start_proxy_vouching(session_id, targets.clone())
+			.await
+			.unwrap();
+
+		assert_eq!(session.vouches.len(), targets.len());
+		assert!(session
+			.vouches
+			.iter()
+			.all(|vouch| matches!(vouch.status, VouchStatus::Rejected)));
+
+		// Dispatches overlap, but never more of them than the concurrency cap
+		let peak = logger.peak.load(Ordering::SeqCst);
+		assert!(peak > 1, "dispatches ran one at a time");
+		assert!(
+			peak <= MAX_CONCURRENT_VOUCH_DISPATCH,
+			"{} dispatches in flight, the cap is {}",
+			peak,
+			MAX_CONCURRENT_VOUCH_DISPATCH
+		);
+	}
+
+	#[tokio::test]
+	async fn test_auto_accept_limited_to_listed_vouchers() {
+		let (handler, _command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build_with_commands()
+			.await;
+		let (voucher, voucher_id, _voucher_dir) = pair_test_voucher(&handler).await;
+
+		async fn is_paired(handler: &PairingProtocolHandler, device_id: Uuid) -> bool {
+			handler
+				.device_registry
+				.read()
+				.await
+				.get_device_state(device_id)
+				.is_some()
+		}
+
+		// An empty allowlist keeps accepting every trusted voucher
+		let (vouchee_id, session_id) = send_test_vouch(&handler, &voucher).await;
+		assert!(is_paired(&handler, vouchee_id).await);
+		assert!(!handler
+			.pending_proxy_confirmations
+			.read()
+			.await
+			.contains_key(&session_id));
+
+		// A trusted voucher missing from the allowlist needs confirmation
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				auto_accept_vouched: true,
+				auto_accept_vouches_from: vec![Uuid::new_v4()],
+				..ProxyPairingConfig::default()
+			})
+			.await;
+		let (vouchee_id, session_id) = send_test_vouch(&handler, &voucher).await;
+		assert!(!is_paired(&handler, vouchee_id).await);
+		assert!(handler
+			.pending_proxy_confirmations
+			.read()
+			.await
+			.contains_key(&session_id));
+
+		// Listing the voucher restores auto-accept for it
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				auto_accept_vouched: true,
+				auto_accept_vouches_from: vec![voucher_id],
+				..ProxyPairingConfig::default()
+			})
+			.await;
+		let (vouchee_id, _) = send_test_vouch(&handler, &voucher).await;
+		assert!(is_paired(&handler, vouchee_id).await);
+	}
+
+	#[tokio::test]
+	async fn test_vouch_with_oversized_device_info_is_rejected() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let (voucher, voucher_id, _voucher_dir) = pair_test_voucher(&handler).await;
+
+		let vouchee = NetworkIdentity::new().await.unwrap();
+		let mut info = voucher.get_device_info().await.unwrap();
+		info.device_id = Uuid::new_v4();
+		info.device_name = "a".repeat(DeviceInfo::MAX_FIELD_LEN + 1);
+		info.network_fingerprint = vouchee.network_fingerprint();
+		let session_id = Uuid::new_v4();
+		let timestamp = chrono::Utc::now();
+		let payload =
+			voucher.build_vouch_payload(session_id, &info, &vouchee.public_key_bytes(), timestamp);
+		let message = PairingMessage::ProxyPairingRequest {
+			session_id,
+			vouchee_device_info: info.clone(),
+			vouchee_public_key: vouchee.public_key_bytes(),
+			voucher_device_id: voucher_id,
+			voucher_signature: voucher.sign_vouch_payload(&payload).unwrap(),
+			timestamp,
+		};
+
+		assert!(handler
+			.handle_pairing_message(message, voucher.identity.node_id())
+			.await
+			.is_err());
+		assert!(handler
+			.device_registry
+			.read()
+			.await
+			.get_device_state(info.device_id)
+			.is_none());
+		assert!(handler.pending_proxy_confirmations.read().await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_completion_listing_too_many_devices_is_rejected() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let device_info = handler.get_device_info().await.unwrap();
+		let completion = |count: usize| PairingMessage::ProxyPairingComplete {
+			session_id: Uuid::new_v4(),
+			voucher_device_id: Uuid::new_v4(),
+			accepted_by: vec![
+				proxy::AcceptedDevice {
+					device_info: device_info.clone(),
+				};
+				count
+			],
+			rejected_by: Vec::new(),
+			voucher_signature: Vec::new(),
+		};
+
+		assert!(handler
+			.check_message_device_info(&completion(PairingMessage::MAX_PROXY_OUTCOMES))
+			.await
+			.is_ok());
+		assert!(handler
+			.check_message_device_info(&completion(PairingMessage::MAX_PROXY_OUTCOMES + 1))
+			.await
+			.is_err());
+
+		// Vouches that could not be reported back are refused up front
+		let targets = vec![Uuid::new_v4(); PairingMessage::MAX_PROXY_OUTCOMES + 1];
+		let error = handler
+			.start_proxy_vouching(Uuid::new_v4(), targets)
+			.await
+			.unwrap_err();
+		assert!(error.to_string().contains("at once"));
+	}
+
+	#[tokio::test]
+	async fn test_downgraded_device_is_not_accepted_as_voucher() {
+		let (handler, mut command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build_with_commands()
+			.await;
+		let (voucher, voucher_id, _voucher_dir) = pair_test_voucher(&handler).await;
+
+		let change = handler
+			.set_device_trust(voucher_id, TrustLevel::Unreliable)
+			.await
+			.unwrap();
+		assert_eq!(change.previous, TrustLevel::Trusted);
+		assert!(!change.can_vouch);
+
+		// The downgraded voucher is rejected outright, not queued for confirmation
+		let (vouchee_id, _) = send_test_vouch(&handler, &voucher).await;
+		assert!(handler
+			.device_registry
+			.read()
+			.await
+			.get_device_state(vouchee_id)
+			.is_none());
+		assert!(handler.pending_proxy_confirmations.read().await.is_empty());
+		assert!(command_rx.try_recv().is_ok());
+
+		// The policy rejection is on record
+		let audit = handler.get_proxy_pairing_audit().await.unwrap();
+		assert_eq!(audit.len(), 1);
+		assert_eq!(audit[0].vouchee_device_id, vouchee_id);
+		assert!(!audit[0].accepted);
+		assert!(!audit[0].confirmed_by_user);
+		assert_eq!(
+			audit[0].reason.as_deref(),
+			Some("Voucher not trusted for proxy pairing")
+		);
+
+		// Restoring trust lets it vouch again
+		let change = handler
+			.set_device_trust(voucher_id, TrustLevel::Trusted)
+			.await
+			.unwrap();
+		assert!(change.can_vouch);
+		let (vouchee_id, _) = send_test_vouch(&handler, &voucher).await;
+		assert!(handler
+			.device_registry
+			.read()
+			.await
+			.get_device_state(vouchee_id)
+			.is_some());
+
+		// Unknown devices cannot be given a trust level
+		assert!(handler
+			.set_device_trust(Uuid::new_v4(), TrustLevel::Trusted)
+			.await
+			.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_untrusted_devices_are_not_vouched_to() {
+		let (handler, _command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.with_endpoint()
+			.build_with_commands()
+			.await;
+		let local_info = handler.get_device_info().await.unwrap();
+
+		let target_id = Uuid::new_v4();
+		let mut target_info = local_info.clone();
+		target_info.device_id = target_id;
+		pair_test_device(&handler, target_info, None).await;
+
+		// The targets auto-vouching would pick for a fresh vouchee
+		async fn targets(handler: &PairingProtocolHandler, local_id: Uuid) -> Vec<Uuid> {
+			handler
+				.auto_vouch_targets(
+					Uuid::new_v4(),
+					local_id,
+					Uuid::new_v4(),
+					&ProxyPairingConfig::default(),
+				)
+				.await
+				.unwrap()
+		}
+		let local_id = local_info.device_id;
+		assert_eq!(targets(&handler, local_id).await, vec![target_id]);
+
+		// A downgraded device stops being auto-vouched to
+		handler
+			.set_device_trust(target_id, TrustLevel::Unreliable)
+			.await
+			.unwrap();
+		assert!(targets(&handler, local_id).await.is_empty());
+
+		// Blocked targets are refused even when picked by hand
+		handler
+			.set_device_trust(target_id, TrustLevel::Blocked)
+			.await
+			.unwrap();
+		assert!(targets(&handler, local_id).await.is_empty());
+		let err = handler
+			.start_proxy_vouching(Uuid::new_v4(), vec![target_id])
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("blocked"));
+	}
+
+	#[tokio::test]
+	async fn test_auto_vouch_paused_after_repeated_rejections() {
+		let (handler, _command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.with_endpoint()
+			.with_vouching_queue()
+			.build_with_commands()
+			.await;
+		let local_info = handler.get_device_info().await.unwrap();
+
+		let target_id = Uuid::new_v4();
+		let mut target_info = local_info.clone();
+		target_info.device_id = target_id;
+		pair_test_device(&handler, target_info, None).await;
+
+		// Pairs a fresh vouchee and returns the targets auto-vouching picked for it
+		async fn vouch_new_device(
+			handler: &PairingProtocolHandler,
+			local_info: &DeviceInfo,
+		) -> Vec<Uuid> {
+			let session_id = Uuid::new_v4();
+			let mut vouchee_info = local_info.clone();
+			vouchee_info.device_id = Uuid::new_v4();
+			insert_test_session(handler, completed_session(session_id, &vouchee_info)).await;
+			handler
+				.create_vouching_session(session_id, &vouchee_info)
+				.await
+				.unwrap();
+			handler
+				.get_vouching_session(session_id)
+				.await
+				.unwrap()
+				.vouches
+				.iter()
+				.map(|vouch| vouch.device_id)
+				.collect()
+		}
+
+		// A session the target's responses are recorded against
+		let session_id = Uuid::new_v4();
+		handler
+			.create_vouching_session(session_id, &local_info)
+			.await
+			.unwrap();
+
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				auto_vouch_to_all: true,
+				vouch_rejection_threshold: 2,
+				..ProxyPairingConfig::default()
+			})
+			.await;
+
+		// Below the threshold the target is still vouched to
+		assert_eq!(
+			vouch_new_device(&handler, &local_info).await,
+			vec![target_id]
+		);
+		handler
+			.handle_proxy_pairing_response(session_id, target_id, false, None)
+			.await
+			.unwrap();
+		assert_eq!(
+			vouch_new_device(&handler, &local_info).await,
+			vec![target_id]
+		);
+
+		// The second rejection in a row pauses it for the cooldown
+		handler
+			.handle_proxy_pairing_response(session_id, target_id, false, None)
+			.await
+			.unwrap();
+		assert!(vouch_new_device(&handler, &local_info).await.is_empty());
+
+		// Once the cooldown is over it is vouched to again
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				auto_vouch_to_all: true,
+				vouch_rejection_threshold: 2,
+				vouch_rejection_cooldown_secs: 0,
+				..ProxyPairingConfig::default()
+			})
+			.await;
+		assert_eq!(
+			vouch_new_device(&handler, &local_info).await,
+			vec![target_id]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_vouching_sessions_capped() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				max_vouching_sessions: 2,
+				..ProxyPairingConfig::default()
+			})
+			.await;
+		let vouchee = handler.get_device_info().await.unwrap();
+
+		let first = Uuid::new_v4();
+		handler
+			.create_vouching_session(first, &vouchee)
+			.await
+			.unwrap();
+		handler
+			.create_vouching_session(Uuid::new_v4(), &vouchee)
+			.await
+			.unwrap();
+
+		let refused = Uuid::new_v4();
+		let err = handler
+			.create_vouching_session(refused, &vouchee)
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("Too many vouching sessions"));
+		assert!(handler.get_vouching_session(refused).await.is_none());
+
+		// Completed sessions still count until they are cleaned up
+		handler
+			.vouching_sessions
+			.write()
+			.await
+			.get_mut(&first)
+			.unwrap()
+			.state = VouchingSessionState::Completed;
+		assert!(handler
+			.create_vouching_session(Uuid::new_v4(), &vouchee)
+			.await
+			.is_err());
+
+		// Recreating a session that is already held does not need a new slot
+		handler
+			.create_vouching_session(first, &vouchee)
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_overdue_vouching_session_is_finalized() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let vouchee = handler.get_device_info().await.unwrap();
+		let session_id = Uuid::new_v4();
+		let pending_target = Uuid::new_v4();
+		let accepted_target = Uuid::new_v4();
+		let now = chrono::Utc::now();
+
+		handler
+			.create_vouching_session(session_id, &vouchee)
+			.await
+			.unwrap();
+		let fresh = Uuid::new_v4();
+		handler
+			.create_vouching_session(fresh, &vouchee)
+			.await
+			.unwrap();
+
+		{
+			let mut sessions = handler.vouching_sessions.write().await;
+			let session = sessions.get_mut(&session_id).unwrap();
+			session.state = VouchingSessionState::InProgress;
+			session.expires_at = now - chrono::Duration::seconds(1);
+			session.vouches = vec![
+				VouchState {
+					device_id: pending_target,
+					device_name: "Offline".to_string(),
+					status: VouchStatus::Queued,
+					updated_at: now,
+					reason: None,
+				},
+				VouchState {
+					device_id: accepted_target,
+					device_name: "Online".to_string(),
+					status: VouchStatus::Accepted,
+					updated_at: now,
+					reason: None,
+				},
+			];
+		}
+
+		assert_eq!(handler.expire_vouching_sessions().await.unwrap(), 1);
+
+		let session = handler.get_vouching_session(session_id).await.unwrap();
+		assert!(matches!(session.state, VouchingSessionState::Completed));
+		let pending = session
+			.vouches
+			.iter()
+			.find(|v| v.device_id == pending_target)
+			.unwrap();
+		assert!(matches!(pending.status, VouchStatus::Unreachable));
+		assert_eq!(pending.reason.as_deref(), Some("Vouching session expired"));
+		let accepted = session
+			.vouches
+			.iter()
+			.find(|v| v.device_id == accepted_target)
+			.unwrap();
+		assert!(matches!(accepted.status, VouchStatus::Accepted));
+
+		// Sessions within their TTL are left alone, and expiry runs only once
+		let fresh = handler.get_vouching_session(fresh).await.unwrap();
+		assert!(matches!(fresh.state, VouchingSessionState::Pending));
+		assert_eq!(handler.expire_vouching_sessions().await.unwrap(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_expired_session_is_cleaned_up_when_vouchee_is_unreachable() {
+		// The command receiver is dropped, so notifying the vouchee fails
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+
+		let vouchee_id = Uuid::new_v4();
+		let vouchee = test_device_info(vouchee_id, 7);
+		pair_test_device(&handler, vouchee.clone(), None).await;
+
+		let session_id = Uuid::new_v4();
+		handler
+			.create_vouching_session(session_id, &vouchee)
+			.await
+			.unwrap();
+		let now = chrono::Utc::now();
+		handler
+			.vouching_sessions
+			.write()
+			.await
+			.get_mut(&session_id)
+			.unwrap()
+			.expires_at = now - chrono::Duration::seconds(1);
+
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		queue
+			.upsert_entry(&queued_entry(session_id, Uuid::new_v4(), &vouchee, now))
+			.await
+			.unwrap();
+
+		assert_eq!(handler.expire_vouching_sessions().await.unwrap(), 1);
+		assert!(queue
+			.list_entries_for_session(session_id)
+			.await
+			.unwrap()
+			.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_probe_connection_warms_cache() {
+		use crate::service::network::core::PAIRING_ALPN;
+		use iroh::discovery::static_provider::StaticProvider;
+
+		let (mut handler, _temp_dir) = create_test_handler().await;
+		let unknown = iroh::SecretKey::from_bytes(&[21u8; 32]).public();
+
+		// Without an endpoint there is nothing to probe with
+		assert!(!handler.probe_connection(unknown).await);
+
+		let server = Endpoint::builder()
+			.alpns(vec![PAIRING_ALPN.to_vec()])
+			.relay_mode(iroh::RelayMode::Disabled)
+			.bind()
+			.await
+			.unwrap();
+		let accept_task = tokio::spawn({
+			let server = server.clone();
+			async move {
+				let incoming = server.accept().await.unwrap();
+				let conn = incoming.await.unwrap();
+				conn.closed().await;
+			}
+		});
+
+		let addresses = StaticProvider::new();
+		addresses.add_endpoint_info(server.addr());
+		let client = Endpoint::builder()
+			.relay_mode(iroh::RelayMode::Disabled)
+			.discovery(addresses)
+			.bind()
+			.await
+			.unwrap();
+		handler.endpoint = Some(client.clone());
+
+		assert!(handler.probe_connection(server.id()).await);
+		assert!(handler
+			.connections
+			.read()
+			.await
+			.contains_key(&(server.id(), PAIRING_ALPN.to_vec())));
+		assert!(handler.active_sessions.read().await.is_empty());
+
+		client.close().await;
+		server.close().await;
+		accept_task.abort();
+	}
+
+	#[tokio::test]
+	async fn test_device_id_stable_across_node_id_rotation() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let public_key = [7u8; 32];
+		let node_a = iroh::SecretKey::from_bytes(&[1u8; 32]).public();
+		let node_b = iroh::SecretKey::from_bytes(&[2u8; 32]).public();
+
+		let id_a = handler
+			.get_device_id_for_node(node_a, Some(&public_key))
+			.await;
+		let id_b = handler
+			.get_device_id_for_node(node_b, Some(&public_key))
+			.await;
+		assert_eq!(id_a, id_b);
+
+		// Without a public key the node id is all we have
+		let fallback_a = handler.get_device_id_for_node(node_a, None).await;
+		let fallback_b = handler.get_device_id_for_node(node_b, None).await;
+		assert_ne!(fallback_a, fallback_b);
+	}
+
+	#[tokio::test]
+	async fn test_vouch_signature_dispatches_on_scheme() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let info = test_device_info(Uuid::new_v4(), 5);
+		let payload =
+			handler.build_vouch_payload(Uuid::new_v4(), &info, &[5; 32], chrono::Utc::now());
+		let public_key = handler.identity.public_key_bytes();
+
+		let signature = handler.sign_vouch_payload(&payload).unwrap();
+		assert_eq!(signature[0], SignatureScheme::Ed25519 as u8);
+		assert!(handler
+			.verify_vouch_signature(&payload, &signature, &public_key)
+			.unwrap());
+
+		// An unknown scheme is rejected up front instead of failing on key length
+		let mut unknown = signature.clone();
+		unknown[0] = 0x7f;
+		let err = handler
+			.verify_vouch_signature(&payload, &unknown, &public_key)
+			.unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("Unsupported vouch signature scheme: 0x7f"));
+	}
+
+	#[tokio::test]
+	async fn test_untagged_vouch_signature_from_older_peers_verifies() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let info = test_device_info(Uuid::new_v4(), 5);
+		let payload =
+			handler.build_vouch_payload(Uuid::new_v4(), &info, &[5; 32], chrono::Utc::now());
+		let public_key = handler.identity.public_key_bytes();
+
+		// Older builds sign the bare payload and send the signature untagged
+		let legacy = handler.identity.sign(&payload.canonical_bytes()).unwrap();
+		assert_eq!(legacy.len(), proxy::LEGACY_VOUCH_SIGNATURE_LEN);
+		assert!(handler
+			.verify_vouch_signature(&payload, &legacy, &public_key)
+			.unwrap());
+
+		let other = handler.build_vouch_payload(Uuid::new_v4(), &info, &[5; 32], payload.timestamp);
+		assert!(!handler
+			.verify_vouch_signature(&other, &legacy, &public_key)
+			.unwrap());
+	}
+
+	#[tokio::test]
+	async fn test_allowlisted_requester_is_auto_accepted() {
+		let (handler, _temp_dir) = create_test_handler().await;
+
+		let listed_requester = NetworkIdentity::new().await.unwrap();
+		let other_requester = NetworkIdentity::new().await.unwrap();
+		let listed_key = listed_requester.public_key_bytes();
+		let other_key = other_requester.public_key_bytes();
+
+		async fn request(
+			handler: &PairingProtocolHandler,
+			requester: &NetworkIdentity,
+		) -> PairingSession {
+			let mut device_info = handler.get_device_info().await.unwrap();
+			device_info.network_fingerprint = requester.network_fingerprint();
+			let session_id = handler.start_pairing_session().await.unwrap();
+			let response = handler
+				.handle_pairing_request(
+					Uuid::new_v4(),
+					requester.node_id(),
+					session_id,
+					device_info,
+					requester.public_key_bytes(),
+				)
+				.await
+				.unwrap();
+
+			// Both paths still challenge the requester to prove its key
+			let message: PairingMessage = serde_json::from_slice(&response).unwrap();
+			assert!(matches!(message, PairingMessage::Challenge { .. }));
+
+			handler.active_sessions.read().await[&session_id].clone()
+		}
+
+		// An empty allowlist never auto-accepts
+		assert!(!request(&handler, &listed_requester).await.auto_accepted);
+
+		let fingerprint = blake3::hash(&listed_key).to_hex()[..32].to_string();
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				auto_accept_pairing_from: vec![fingerprint],
+				..ProxyPairingConfig::default()
+			})
+			.await;
+
+		let listed = request(&handler, &listed_requester).await;
+		assert!(listed.auto_accepted);
+		assert!(matches!(
+			listed.state,
+			PairingState::ChallengeReceived { .. }
+		));
+
+		let other = request(&handler, &other_requester).await;
+		assert!(!other.auto_accepted);
+		assert!(matches!(
+			other.state,
+			PairingState::ChallengeReceived { .. }
+		));
+
+		// The raw public key works as an entry too
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				auto_accept_pairing_from: vec![hex::encode(&other_key)],
+				..ProxyPairingConfig::default()
+			})
+			.await;
+		assert!(request(&handler, &other_requester).await.auto_accepted);
+		assert!(!request(&handler, &listed_requester).await.auto_accepted);
+	}
+
+	#[tokio::test]
+	async fn test_allowlisted_device_pairs_without_a_code() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let joiner = NetworkIdentity::new().await.unwrap();
+		let mut device_info = handler.get_device_info().await.unwrap();
+		device_info.device_id = Uuid::new_v4();
+		device_info.network_fingerprint = joiner.network_fingerprint();
+		let request = |session_id: Uuid| PairingMessage::PairingRequest {
+			session_id,
+			device_info: device_info.clone(),
+			public_key: joiner.public_key_bytes(),
+		};
+
+		// Off the allowlist, a session nobody opened is refused
+		let session_id = Uuid::new_v4();
+		let err = handler
+			.handle_pairing_message(request(session_id), joiner.node_id())
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("No open pairing session"));
+		assert!(!handler
+			.active_sessions
+			.read()
+			.await
+			.contains_key(&session_id));
+
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				auto_accept_pairing_from: vec![hex::encode(joiner.public_key_bytes())],
+				..ProxyPairingConfig::default()
+			})
+			.await;
+
+		// Listed, the same request runs the whole handshake with no code generated
+		let response = handler
+			.handle_pairing_message(request(session_id), joiner.node_id())
+			.await
+			.unwrap()
+			.unwrap();
+		let PairingMessage::Challenge { challenge, .. } =
+			serde_json::from_slice::<PairingMessage>(&response).unwrap()
+		else {
+			panic!("expected a challenge");
+		};
+		let response = handler
+			.handle_pairing_message(
+				PairingMessage::Response {
+					session_id,
+					response: joiner.sign(&challenge).unwrap(),
+					device_info: device_info.clone(),
+				},
+				joiner.node_id(),
+			)
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(matches!(
+			serde_json::from_slice::<PairingMessage>(&response).unwrap(),
+			PairingMessage::Complete { success: true, .. }
+		));
+
+		let session = handler.active_sessions.read().await[&session_id].clone();
+		assert!(session.auto_accepted);
+		assert!(matches!(session.state, PairingState::Completed));
+		assert!(handler.pairing_codes.read().await.is_empty());
+
+		// With no code, the secret comes from the key agreement the joiner can repeat
+		let joiner_secret = PairingSecurity::derive_session_secret(
+			&joiner
+				.shared_secret_with(&handler.identity.public_key_bytes())
+				.unwrap(),
+			Uuid::new_v5(&session_id, &joiner.public_key_bytes()),
+		)
+		.unwrap();
+		assert_eq!(session.shared_secret, Some(joiner_secret));
+		assert!(handler
+			.device_registry
+			.read()
+			.await
+			.get_paired_devices()
+			.iter()
+			.any(|paired| paired.device_id == device_info.device_id));
+	}
+
+	#[tokio::test]
+	async fn test_pairing_request_with_mismatched_fingerprint_is_rejected() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let requester = NetworkIdentity::new().await.unwrap();
+		let victim = NetworkIdentity::new().await.unwrap();
+
+		let request =
+			|device_info: DeviceInfo, public_key: Vec<u8>| PairingMessage::PairingRequest {
+				session_id: Uuid::new_v4(),
+				device_info,
+				public_key,
+			};
+
+		// Claims another node's fingerprint over the requester's own connection
+		let mut spoofed = handler.get_device_info().await.unwrap();
+		spoofed.network_fingerprint = victim.network_fingerprint();
+		let message = request(spoofed, requester.public_key_bytes());
+		let session_id = message.session_id();
+		let err = handler
+			.handle_pairing_message(message, requester.node_id())
+			.await
+			.unwrap_err();
+		assert!(matches!(err, NetworkingError::AuthenticationFailed(_)));
+		assert!(!handler
+			.active_sessions
+			.read()
+			.await
+			.contains_key(&session_id));
+
+		// Fingerprint matches the connection but the key belongs to someone else
+		let mut honest = handler.get_device_info().await.unwrap();
+		honest.network_fingerprint = requester.network_fingerprint();
+		let err = handler
+			.handle_pairing_message(
+				request(honest.clone(), victim.public_key_bytes()),
+				requester.node_id(),
+			)
+			.await
+			.unwrap_err();
+		assert!(matches!(err, NetworkingError::AuthenticationFailed(_)));
+
+		// Consistent fingerprint, key and connection are challenged as usual
+		let response = handler
+			.handle_pairing_message(
+				PairingMessage::PairingRequest {
+					session_id: handler.start_pairing_session().await.unwrap(),
+					device_info: honest,
+					public_key: requester.public_key_bytes(),
+				},
+				requester.node_id(),
+			)
+			.await
+			.unwrap()
+			.unwrap();
+		let message: PairingMessage = serde_json::from_slice(&response).unwrap();
+		assert!(matches!(message, PairingMessage::Challenge { .. }));
+
+		// Requests routed through `handle_request` are checked the same way
+		let mut known = test_device_info(Uuid::new_v4(), 9);
+		known.network_fingerprint = requester.network_fingerprint();
+		let known_id = known.device_id;
+		pair_test_device(&handler, known, None).await;
+		let mut spoofed = handler.get_device_info().await.unwrap();
+		spoofed.network_fingerprint = victim.network_fingerprint();
+		let request_data = serde_json::to_vec(&PairingMessage::PairingRequest {
+			session_id: handler.start_pairing_session().await.unwrap(),
+			device_info: spoofed,
+			public_key: requester.public_key_bytes(),
+		})
+		.unwrap();
+		for from_device in [known_id, Uuid::new_v4()] {
+			let err = handler
+				.handle_request(from_device, request_data.clone())
+				.await
+				.unwrap_err();
+			assert!(matches!(err, NetworkingError::AuthenticationFailed(_)));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_open_sessions_per_peer_are_capped() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let requester = NetworkIdentity::new().await.unwrap();
+		let mut device_info = handler.get_device_info().await.unwrap();
+		device_info.network_fingerprint = requester.network_fingerprint();
+
+		let request = |session_id: Uuid| PairingMessage::PairingRequest {
+			session_id,
+			device_info: device_info.clone(),
+			public_key: requester.public_key_bytes(),
+		};
+
+		let mut session_ids = Vec::new();
+		for _ in 0..types::MAX_OPEN_SESSIONS_PER_PEER {
+			let session_id = handler.start_pairing_session().await.unwrap();
+			handler
+				.handle_pairing_message(request(session_id), requester.node_id())
+				.await
+				.unwrap();
+			session_ids.push(session_id);
+		}
+
+		// A fourth concurrent session from the same node is refused
+		let refused = handler.start_pairing_session().await.unwrap();
+		let err = handler
+			.handle_pairing_message(request(refused), requester.node_id())
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("Too many open pairing sessions"));
+		assert!(handler.active_sessions.read().await[&refused]
+			.remote_device_id
+			.is_none());
+
+		// Repeating an already open session is not a new one
+		handler
+			.handle_pairing_message(request(session_ids[0]), requester.node_id())
+			.await
+			.unwrap();
+
+		// Other nodes are unaffected
+		let other = NetworkIdentity::new().await.unwrap();
+		let mut other_info = device_info.clone();
+		other_info.network_fingerprint = other.network_fingerprint();
+		handler
+			.handle_pairing_message(
+				PairingMessage::PairingRequest {
+					session_id: handler.start_pairing_session().await.unwrap(),
+					device_info: other_info,
+					public_key: other.public_key_bytes(),
+				},
+				other.node_id(),
+			)
+			.await
+			.unwrap();
+
+		// Once one of the open sessions goes away there is room again
+		handler.cancel_session(session_ids[1]).await.unwrap();
+		handler
+			.handle_pairing_message(request(refused), requester.node_id())
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_single_use_code_rejects_second_joiner() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let device_info = handler.get_device_info().await.unwrap();
+
+		let joiner = |identity: &NetworkIdentity, session_id: Uuid| {
+			let mut device_info = device_info.clone();
+			device_info.network_fingerprint = identity.network_fingerprint();
+			PairingMessage::PairingRequest {
+				session_id,
+				device_info,
+				public_key: identity.public_key_bytes(),
+			}
+		};
+		let first = NetworkIdentity::new().await.unwrap();
+		let second = NetworkIdentity::new().await.unwrap();
+
+		let session_id = handler.start_pairing_session().await.unwrap();
+		handler
+			.handle_pairing_message(joiner(&first, session_id), first.node_id())
+			.await
+			.unwrap();
+
+		// The first joiner may retry, anyone else is refused
+		handler
+			.handle_pairing_message(joiner(&first, session_id), first.node_id())
+			.await
+			.unwrap();
+		let err = handler
+			.handle_pairing_message(joiner(&second, session_id), second.node_id())
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("already been used"));
+		assert_eq!(
+			handler.active_sessions.read().await[&session_id].remote_device_id,
+			Some(
+				handler
+					.get_device_id_for_node(first.node_id(), Some(&first.public_key_bytes()))
+					.await
+			)
+		);
+
+		// A multi-use code admits both
+		let code = PairingCode::generate().unwrap().multi_use();
+		let multi_session_id = code.session_id();
+		handler
+			.start_pairing_session_with_id(multi_session_id, code)
+			.await
+			.unwrap();
+		for identity in [&first, &second] {
+			handler
+				.handle_pairing_message(joiner(identity, multi_session_id), identity.node_id())
+				.await
+				.unwrap();
+		}
+		assert_eq!(
+			handler.pairing_codes.read().await[&multi_session_id].uses(),
+			2
+		);
+	}
+
+	#[tokio::test]
+	async fn test_multi_use_code_pairs_concurrent_joiners() {
+		let (initiator, _initiator_dir) = create_test_handler().await;
+		let (first, _first_dir) = create_test_handler().await;
+		let (second, _second_dir) = create_test_handler().await;
+		let initiator_node = initiator.identity.node_id();
+
+		let code = PairingCode::generate().unwrap().multi_use();
+		let session_id = code.session_id();
+		initiator
+			.start_pairing_session_with_id(session_id, code.clone())
+			.await
+			.unwrap();
+
+		async fn send(
+			to: &PairingProtocolHandler,
+			message: PairingMessage,
+			from: EndpointId,
+		) -> PairingMessage {
+			let reply = to.handle_pairing_message(message, from).await.unwrap();
+			serde_json::from_slice(&reply.unwrap()).unwrap()
+		}
+
+		// Both joiners get their challenge before either answers it
+		let mut challenges = Vec::new();
+		for joiner in [&first, &second] {
+			joiner
+				.join_pairing_session(session_id, code.clone())
+				.await
+				.unwrap();
+			let request = joiner
+				.build_pairing_request(session_id, joiner.get_device_info().await.unwrap())
+				.await;
+			challenges.push(send(&initiator, request, joiner.identity.node_id()).await);
+		}
+
+		for (joiner, challenge) in [&first, &second].into_iter().zip(challenges) {
+			let response = send(joiner, challenge, initiator_node).await;
+			match send(&initiator, response, joiner.identity.node_id()).await {
+				PairingMessage::Complete { success, .. } => assert!(success),
+				other => panic!("Expected Complete, got {:?}", other),
+			}
+		}
+
+		// Each joiner's handshake is timed on its own
+		let timings = initiator.get_pairing_timings().await;
+		assert_eq!(timings.completed_sessions, 2);
+		for histogram in &timings.phases {
+			assert_eq!(histogram.count, 2, "{:?} not recorded", histogram.phase);
+		}
+
+		let paired = initiator.device_registry.read().await.get_paired_devices();
+		for joiner in [&first, &second] {
+			let joiner_id = joiner.get_device_info().await.unwrap().device_id;
+			assert!(paired.iter().any(|device| device.device_id == joiner_id));
+		}
+		let completed = initiator
+			.active_sessions
+			.read()
+			.await
+			.values()
+			.filter(|session| matches!(session.state, PairingState::Completed))
+			.count();
+		assert_eq!(completed, 2);
+	}
+
+	#[tokio::test]
+	async fn test_multi_use_code_gives_each_joiner_its_own_keys() {
+		let (initiator, _initiator_dir) = create_test_handler().await;
+		let (first, _first_dir) = create_test_handler().await;
+		let (second, _second_dir) = create_test_handler().await;
+		let initiator_node = initiator.identity.node_id();
+
+		let code = PairingCode::generate().unwrap().multi_use();
+		let session_id = code.session_id();
+		initiator
+			.start_pairing_session_with_id(session_id, code.clone())
+			.await
+			.unwrap();
+
+		let mut secrets = Vec::new();
+		for joiner in [&first, &second] {
+			joiner
+				.join_pairing_session(session_id, code.clone())
+				.await
+				.unwrap();
+			let mut message = joiner
+				.build_pairing_request(session_id, joiner.get_device_info().await.unwrap())
+				.await;
+			// Relay the handshake up to the Complete message the joiner finishes on
+			for (to, from) in [
+				(&initiator, joiner.identity.node_id()),
+				(joiner, initiator_node),
+				(&initiator, joiner.identity.node_id()),
+			] {
+				let reply = to.handle_pairing_message(message, from).await.unwrap();
+				message = serde_json::from_slice(&reply.unwrap()).unwrap();
+			}
+			joiner
+				.handle_pairing_message(message, initiator_node)
+				.await
+				.unwrap();
+
+			let handshake_id = Uuid::new_v5(&session_id, &joiner.identity.public_key_bytes());
+			let initiator_secret = initiator.active_sessions.read().await[&handshake_id]
+				.shared_secret
+				.clone()
+				.unwrap();
+			let joiner_secret = joiner.active_sessions.read().await[&session_id]
+				.shared_secret
+				.clone()
+				.unwrap();
+			assert_eq!(initiator_secret, joiner_secret);
+			secrets.push(joiner_secret);
+		}
+
+		// One joiner cannot derive the keys the other pairs with
+		assert_ne!(secrets[0], secrets[1]);
+	}
+
+	#[tokio::test]
+	async fn test_persistence_enabled_reflects_constructor() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		assert!(!handler.persistence_enabled());
+
+		let (handler, _temp_dir) = TestHandlerBuilder::new().persistent().build().await;
+		assert!(handler.persistence_enabled());
+	}
+
+	#[tokio::test]
+	async fn test_republish_vouching_session_emits_current_state() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let vouchee = handler.get_device_info().await.unwrap();
+		let session_id = Uuid::new_v4();
+		handler
+			.create_vouching_session(session_id, &vouchee)
+			.await
+			.unwrap();
+
+		// Subscribe only now, as a reloaded UI would
+		let event_bus = Arc::new(EventBus::default());
+		let mut events = event_bus.subscribe();
+		handler.set_event_bus(event_bus).await;
+
+		assert!(!handler
+			.republish_vouching_session(Uuid::new_v4())
+			.await
+			.unwrap());
+		assert!(events.try_recv().is_err());
+
+		assert!(handler
+			.republish_vouching_session(session_id)
+			.await
+			.unwrap());
+		match events.try_recv().unwrap() {
+			Event::ResourceChanged {
+				resource_type,
+				resource,
+				..
+			} => {
+				assert_eq!(resource_type, "vouching_session");
+				assert_eq!(resource["id"], session_id.to_string());
+			}
+			other => panic!("Expected ResourceChanged, got {:?}", other),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_cancel_vouching_session_drops_session_and_queue() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		let vouchee = handler.get_device_info().await.unwrap();
+		let session_id = Uuid::new_v4();
+		let other_session_id = Uuid::new_v4();
+		for id in [session_id, other_session_id] {
+			handler.create_vouching_session(id, &vouchee).await.unwrap();
+		}
+
+		let now = chrono::Utc::now();
+		let entry = queued_entry(session_id, Uuid::new_v4(), &vouchee, now);
+		queue.upsert_entry(&entry).await.unwrap();
+		queue
+			.upsert_entry(&VouchingQueueEntry {
+				session_id: other_session_id,
+				..entry.clone()
+			})
+			.await
+			.unwrap();
+
+		let event_bus = Arc::new(EventBus::default());
+		let mut events = event_bus.subscribe();
+		handler.set_event_bus(event_bus).await;
+
+		assert!(handler.cancel_vouching_session(session_id).await.unwrap());
+		assert!(handler.get_vouching_session(session_id).await.is_none());
+		assert!(queue
+			.list_entries_for_session(session_id)
+			.await
+			.unwrap()
+			.is_empty());
+		match events.try_recv().unwrap() {
+			Event::ResourceDeleted {
+				resource_type,
+				resource_id,
+			} => {
+				assert_eq!(resource_type, "vouching_session");
+				assert_eq!(resource_id, session_id);
+			}
+			other => panic!("Expected ResourceDeleted, got {:?}", other),
+		}
+
+		// The other session is untouched
+		let remaining = handler.list_vouching_sessions().await;
+		assert_eq!(remaining.len(), 1);
+		assert_eq!(remaining[0].id, other_session_id);
+		assert_eq!(
+			queue
+				.list_entries_for_session(other_session_id)
+				.await
+				.unwrap()
+				.len(),
+			1
+		);
+
+		assert!(!handler.cancel_vouching_session(session_id).await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn test_cancel_stops_vouches_still_being_dispatched() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_endpoint()
+			.with_vouching_queue()
+			.build()
+			.await;
+
+		// Enough targets that the dispatch is still going when the cancel lands
+		let mut targets = Vec::new();
+		for seed in 0..(MAX_CONCURRENT_VOUCH_DISPATCH * 4) as u8 {
+			let target_id = Uuid::new_v4();
+			pair_test_device(&handler, test_device_info(target_id, seed + 20), None).await;
+			targets.push(target_id);
+		}
+
+		let session_id = Uuid::new_v4();
+		let vouchee_id = Uuid::new_v4();
+		insert_test_session(
+			&handler,
+			completed_session(session_id, &test_device_info(vouchee_id, 12)),
+		)
+		.await;
+
+		let handler = Arc::new(handler);
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		let dispatch = tokio::spawn({
+			let handler = handler.clone();
+			async move { handler.start_proxy_vouching(session_id, targets).await }
+		});
+
+		// Cancel once the first vouches are queued
+		while queue
+			.list_entries_for_session(session_id)
+			.await
+			.unwrap()
+			.is_empty()
+		{
+			tokio::task::yield_now().await;
+		}
+		assert!(!dispatch.is_finished());
+		assert!(handler.cancel_vouching_session(session_id).await.unwrap());
+
+		let err = dispatch.await.unwrap().unwrap_err();
+		assert!(err.to_string().contains("cancelled"));
+		assert!(handler.get_vouching_session(session_id).await.is_none());
+		assert!(queue
+			.list_entries_for_session(session_id)
+			.await
+			.unwrap()
+			.is_empty());
+		assert!(handler.vouch_dispatches.read().await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_create_vouching_session_twice_keeps_vouches() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let vouchee = handler.get_device_info().await.unwrap();
+		let session_id = Uuid::new_v4();
+		let target = Uuid::new_v4();
+
+		handler
+			.create_vouching_session(session_id, &vouchee)
+			.await
+			.unwrap();
+		{
+			let mut sessions = handler.vouching_sessions.write().await;
+			let session = sessions.get_mut(&session_id).unwrap();
+			session.state = VouchingSessionState::InProgress;
+			session.vouches.push(VouchState {
+				device_id: target,
+				device_name: "Target".to_string(),
+				status: VouchStatus::Accepted,
+				updated_at: chrono::Utc::now(),
+				reason: None,
+			});
+		}
+
+		let session = handler
+			.create_vouching_session(session_id, &vouchee)
+			.await
+			.unwrap();
+		assert!(matches!(session.state, VouchingSessionState::InProgress));
+		assert_eq!(session.vouches.len(), 1);
+		assert_eq!(session.vouches[0].device_id, target);
+
+		let stored = handler.get_vouching_session(session_id).await.unwrap();
+		assert!(matches!(stored.state, VouchingSessionState::InProgress));
+		assert_eq!(stored.vouches.len(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_loopback_pairing_handshake() {
+		let (initiator, initiator_rx, _initiator_dir) =
+			TestHandlerBuilder::new().build_with_commands().await;
+		let (joiner, joiner_rx, _joiner_dir) =
+			TestHandlerBuilder::new().build_with_commands().await;
+		let (initiator, joiner) = (Arc::new(initiator), Arc::new(joiner));
+
+		let transport = LoopbackTransport::new();
+		transport.attach(initiator.clone(), initiator_rx).await;
+		transport.attach(joiner.clone(), joiner_rx).await;
+
+		let session_id = initiator.start_pairing_session().await.unwrap();
+		let pairing_code = initiator.get_current_pairing_code().await.unwrap();
+		joiner
+			.join_pairing_session(session_id, pairing_code)
+			.await
+			.unwrap();
+
+		let request = joiner
+			.build_pairing_request(session_id, joiner.get_device_info().await.unwrap())
+			.await;
+		joiner
+			.send_pairing_message_fire_and_forget(initiator.identity.node_id(), &request)
+			.await
+			.unwrap();
+
+		async fn completed(handler: &PairingProtocolHandler, session_id: Uuid) -> bool {
+			handler
+				.active_sessions
+				.read()
+				.await
+				.get(&session_id)
+				.is_some_and(|session| matches!(session.state, PairingState::Completed))
+		}
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while !(completed(&initiator, session_id).await && completed(&joiner, session_id).await)
+			{
+				tokio::time::sleep(Duration::from_millis(5)).await;
+			}
+		})
+		.await
+		.expect("loopback handshake did not complete");
+
+		let initiator_id = initiator.get_device_info().await.unwrap().device_id;
+		let joiner_id = joiner.get_device_info().await.unwrap().device_id;
+		assert!(initiator
+			.device_registry
+			.read()
+			.await
+			.get_paired_devices()
+			.iter()
+			.any(|device| device.device_id == joiner_id));
+		assert!(joiner
+			.device_registry
+			.read()
+			.await
+			.get_paired_devices()
+			.iter()
+			.any(|device| device.device_id == initiator_id));
+
+		// Both sides derived the same keys from the code
+		let initiator_secret = initiator.active_sessions.read().await[&session_id]
+			.shared_secret
+			.clone();
+		let joiner_secret = joiner.active_sessions.read().await[&session_id]
+			.shared_secret
+			.clone();
+		assert!(initiator_secret.is_some());
+		assert_eq!(initiator_secret, joiner_secret);
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn test_shared_node_addr_reaches_initiator() {
+		let (initiator, initiator_rx, _initiator_dir) =
+			TestHandlerBuilder::new().build_with_commands().await;
+		let (joiner, joiner_rx, _joiner_dir) =
+			TestHandlerBuilder::new().build_with_commands().await;
+		let (initiator, joiner) = (Arc::new(initiator), Arc::new(joiner));
+
+		let transport = LoopbackTransport::new();
+		transport.attach(initiator.clone(), initiator_rx).await;
+		transport.attach(joiner.clone(), joiner_rx).await;
+
+		let session_id = initiator.start_pairing_session().await.unwrap();
+		let pairing_code = initiator.get_current_pairing_code().await.unwrap();
+
+		// What `network.pair.generate` hands out alongside the code
+		let direct: std::net::SocketAddr = "192.0.2.7:7777".parse().unwrap();
+		let node_addr = EndpointAddrInfo::from_endpoint_addr(
+			&EndpointAddr::new(initiator.identity.node_id()).with_ip_addr(direct),
+		);
+		assert!(node_addr.is_routable());
+		assert_eq!(node_addr.direct_addresses, vec![direct.to_string()]);
+		assert_eq!(node_addr.relay_url, None);
+
+		// The joiner reaches the initiator knowing only the code and the shared address
+		let initiator_node: EndpointId = node_addr.node_id.parse().unwrap();
+		joiner
+			.join_pairing_session(session_id, pairing_code)
+			.await
+			.unwrap();
+		let request = joiner
+			.build_pairing_request(session_id, joiner.get_device_info().await.unwrap())
+			.await;
+		joiner
+			.send_pairing_message_fire_and_forget(initiator_node, &request)
+			.await
+			.unwrap();
+
+		tokio::time::timeout(Duration::from_secs(5), async {
+			loop {
+				let completed = initiator
+					.active_sessions
+					.read()
+					.await
+					.get(&session_id)
+					.is_some_and(|session| matches!(session.state, PairingState::Completed));
+				if completed {
+					break;
+				}
+				tokio::time::sleep(Duration::from_millis(5)).await;
+			}
+		})
+		.await
+		.expect("joiner did not reach the initiator at the shared address");
+	}
+
+	#[tokio::test]
+	async fn test_join_enforces_configured_min_code_entropy() {
+		let (joiner, _dir) = create_test_handler().await;
+		// The mnemonic for an all-zero secret
+		let weak = PairingCode::from_string(
+			"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+		)
+		.unwrap();
+
+		assert!(joiner
+			.join_pairing_session(weak.session_id(), weak.clone())
+			.await
+			.is_err());
+
+		// The threshold comes from the proxy pairing config
+		joiner
+			.set_proxy_config(ProxyPairingConfig {
+				min_code_entropy_bits: weak.entropy_bits(),
+				..Default::default()
+			})
+			.await;
+		joiner
+			.join_pairing_session(weak.session_id(), weak)
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_completed_pairing_records_phase_timings() {
+		let (initiator, initiator_rx, _initiator_dir) =
+			TestHandlerBuilder::new().build_with_commands().await;
+		let (joiner, joiner_rx, _joiner_dir) =
+			TestHandlerBuilder::new().build_with_commands().await;
+		let (initiator, joiner) = (Arc::new(initiator), Arc::new(joiner));
+
+		let transport = LoopbackTransport::new();
+		transport.attach(initiator.clone(), initiator_rx).await;
+		transport.attach(joiner.clone(), joiner_rx).await;
+
+		assert_eq!(initiator.get_pairing_timings().await.completed_sessions, 0);
+
+		let session_id = initiator.start_pairing_session().await.unwrap();
+		let pairing_code = initiator.get_current_pairing_code().await.unwrap();
+		joiner
+			.join_pairing_session(session_id, pairing_code)
+			.await
+			.unwrap();
+		let request = joiner
+			.build_pairing_request(session_id, joiner.get_device_info().await.unwrap())
+			.await;
+		joiner
+			.send_pairing_message_fire_and_forget(initiator.identity.node_id(), &request)
+			.await
+			.unwrap();
+
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while initiator.get_pairing_timings().await.completed_sessions == 0
+				|| joiner.get_pairing_timings().await.completed_sessions == 0
+			{
+				tokio::time::sleep(Duration::from_millis(5)).await;
+			}
+		})
+		.await
+		.expect("loopback handshake did not complete");
+
+		for handler in [&initiator, &joiner] {
+			let timings = handler.get_pairing_timings().await;
+			assert_eq!(timings.completed_sessions, 1);
+			assert_eq!(timings.phases.len(), 3);
+			for histogram in &timings.phases {
+				assert_eq!(histogram.count, 1, "{:?} not recorded", histogram.phase);
+				assert!(
+					histogram.total_ms > 0.0,
+					"{:?} took no time",
+					histogram.phase
+				);
+				assert_eq!(histogram.buckets.iter().sum::<u64>(), 1);
+			}
+		}
+
+		// The timings are part of the diagnostics dump
+		let dump = initiator.dump_state().await;
+		assert_eq!(dump.phase_timings.completed_sessions, 1);
+	}
+
+	#[tokio::test]
+	async fn test_refused_pairing_requests_are_not_timed() {
+		let (initiator, _initiator_dir) = create_test_handler().await;
+		let (joiner, _joiner_dir) = create_test_handler().await;
+		let joiner_node = joiner.identity.node_id();
+		let joiner_info = joiner.get_device_info().await.unwrap();
+
+		let session_id = initiator.start_pairing_session().await.unwrap();
+		let code = initiator.get_current_pairing_code().await.unwrap();
+		joiner.join_pairing_session(session_id, code).await.unwrap();
+		let request = joiner
+			.build_pairing_request(session_id, joiner_info.clone())
+			.await;
+		let challenge = initiator
+			.handle_pairing_message(request, joiner_node)
+			.await
+			.unwrap()
+			.unwrap();
+
+		// Requests for sessions that were never opened must not push the real one out
+		for _ in 0..100 {
+			assert!(initiator
+				.handle_pairing_request(
+					Uuid::new_v4(),
+					joiner_node,
+					Uuid::new_v4(),
+					joiner_info.clone(),
+					joiner.identity.public_key_bytes(),
+				)
+				.await
+				.is_err());
+		}
+
+		let response = joiner
+			.handle_pairing_message(
+				serde_json::from_slice(&challenge).unwrap(),
+				initiator.identity.node_id(),
+			)
+			.await
+			.unwrap()
+			.unwrap();
+		initiator
+			.handle_pairing_message(serde_json::from_slice(&response).unwrap(), joiner_node)
+			.await
+			.unwrap();
+
+		let timings = initiator.get_pairing_timings().await;
+		assert_eq!(timings.completed_sessions, 1);
+		for histogram in &timings.phases {
+			assert_eq!(histogram.count, 1, "{:?} not recorded", histogram.phase);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_protocol_error_is_reported_to_peer() {
+		use crate::service::network::protocol::{read_frame, DEFAULT_MAX_MESSAGE_SIZE};
+		use tokio::io::AsyncWriteExt;
+
+		let (handler, _temp_dir) = create_test_handler().await;
+		let (mut peer_send, handler_recv) = tokio::io::duplex(64 * 1024);
+		let (handler_send, mut peer_recv) = tokio::io::duplex(64 * 1024);
+		let peer_node = iroh::SecretKey::from_bytes(&[31u8; 32]).public();
+		let device_info = handler.get_device_info().await.unwrap();
+
+		let stream =
+			handler.handle_stream(Box::new(handler_send), Box::new(handler_recv), peer_node);
+		let peer = async {
+			// A response for a session the handler never started is a reported error,
+			// so the stream stays open for the second one
+			for _ in 0..2 {
+				let session_id = Uuid::new_v4();
+				let data = serde_json::to_vec(&PairingMessage::Response {
+					session_id,
+					response: vec![0u8; 64],
+					device_info: device_info.clone(),
+				})
+				.unwrap();
+				peer_send
+					.write_all(&(data.len() as u32).to_be_bytes())
+					.await
+					.unwrap();
+				peer_send.write_all(&data).await.unwrap();
+
+				let reply = read_frame(&mut peer_recv, DEFAULT_MAX_MESSAGE_SIZE)
+					.await
+					.unwrap();
+				match serde_json::from_slice(&reply).unwrap() {
+					PairingMessage::Error {
+						session_id: reply_session,
+						code,
+						..
+					} => {
+						assert_eq!(reply_session, session_id);
+						assert_eq!(code, PairingFailureReason::ProtocolError);
+					}
+					other => panic!("expected an error reply, got {:?}", other),
+				}
+			}
+			drop(peer_send);
+		};
+
+		tokio::time::timeout(Duration::from_secs(5), async { tokio::join!(stream, peer) })
+			.await
+			.expect("pairing stream did not finish");
+	}
+
+	type CommandReceiver =
+		tokio::sync::mpsc::Receiver<crate::service::network::core::event_loop::EventLoopCommand>;
+
+	/// Two handlers whose registries are paired with each other using matching keys
+	async fn rekey_pair() -> (
+		(
+			Arc<PairingProtocolHandler>,
+			CommandReceiver,
+			TempDir,
+			DeviceInfo,
+		),
+		(
+			Arc<PairingProtocolHandler>,
+			CommandReceiver,
+			TempDir,
+			DeviceInfo,
+		),
+	) {
+		use crate::service::network::device::PairingType;
+
+		let (alice, alice_rx, alice_dir) = TestHandlerBuilder::new().build_with_commands().await;
+		let (bob, bob_rx, bob_dir) = TestHandlerBuilder::new().build_with_commands().await;
+		let (alice, bob) = (Arc::new(alice), Arc::new(bob));
+
+		async fn info_for(handler: &PairingProtocolHandler) -> DeviceInfo {
+			let mut info = handler.get_device_info().await.unwrap();
+			info.network_fingerprint.node_id = handler.identity.node_id().to_string();
+			info
+		}
+		let (alice_info, bob_info) = (info_for(&alice).await, info_for(&bob).await);
+		let secret = vec![9u8; 32];
+		for (handler, peer, keys) in [
+			(
+				&alice,
+				bob_info.clone(),
+				SessionKeys::from_shared_secret(secret.clone()),
+			),
+			(
+				&bob,
+				alice_info.clone(),
+				SessionKeys::from_shared_secret(secret.clone()).swap_keys(),
+			),
+		] {
+			handler
+				.device_registry
+				.write()
+				.await
+				.complete_pairing(
+					peer.device_id,
+					peer,
+					keys,
+					None,
+					PairingType::Direct,
+					None,
+					None,
+				)
+				.await
+				.unwrap();
+		}
+
+		(
+			(alice, alice_rx, alice_dir, alice_info),
+			(bob, bob_rx, bob_dir, bob_info),
+		)
+	}
+
+	async fn keys_for(handler: &PairingProtocolHandler, peer: Uuid) -> SessionKeys {
+		handler
+			.device_registry
+			.read()
+			.await
+			.get_session_keys(peer)
+			.unwrap()
+	}
+
+	#[tokio::test]
+	async fn test_rekey_switches_both_sides_to_new_keys() {
+		use chacha20poly1305::{
+			aead::{Aead, KeyInit},
+			ChaCha20Poly1305, Nonce,
+		};
+
+		let ((alice, alice_rx, _alice_dir, alice_info), (bob, bob_rx, _bob_dir, bob_info)) =
+			rekey_pair().await;
+		let alice_old = keys_for(&alice, bob_info.device_id).await;
+		let bob_old = keys_for(&bob, alice_info.device_id).await;
+
+		let transport = LoopbackTransport::new();
+		transport.attach(alice.clone(), alice_rx).await;
+		transport.attach(bob.clone(), bob_rx).await;
+
+		alice.start_rekey(bob_info.device_id).await.unwrap();
+		// Bob switches last, once the commit arrives
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while keys_for(&bob, alice_info.device_id).await.send_key == bob_old.send_key {
+				tokio::time::sleep(Duration::from_millis(5)).await;
+			}
+		})
+		.await
+		.expect("re-key was not committed");
+
+		let alice_new = keys_for(&alice, bob_info.device_id).await;
+		let bob_new = keys_for(&bob, alice_info.device_id).await;
+		assert!(alice.pending_rekeys.read().await.is_empty());
+		assert!(bob.rekey_fallbacks.read().await.is_empty());
+		assert_ne!(alice_new.send_key, alice_old.send_key);
+		assert_eq!(alice_new.send_key, bob_new.receive_key);
+		assert_eq!(alice_new.receive_key, bob_new.send_key);
+
+		let nonce = Nonce::from_slice(&[0u8; 12]);
+		let seal = |key: &[u8]| {
+			ChaCha20Poly1305::new_from_slice(key)
+				.unwrap()
+				.encrypt(nonce, b"after rekey".as_slice())
+				.unwrap()
+		};
+		let open = |key: &[u8], data: &[u8]| {
+			ChaCha20Poly1305::new_from_slice(key)
+				.unwrap()
+				.decrypt(nonce, data)
+		};
+
+		// New keys interoperate in both directions
+		let sealed = seal(&alice_new.send_key);
+		assert_eq!(open(&bob_new.receive_key, &sealed).unwrap(), b"after rekey");
+		let sealed = seal(&bob_new.send_key);
+		assert_eq!(
+			open(&alice_new.receive_key, &sealed).unwrap(),
+			b"after rekey"
+		);
+
+		// Old keys no longer match the peer's
+		assert!(open(&bob_new.receive_key, &seal(&alice_old.send_key)).is_err());
+		assert!(open(&alice_old.receive_key, &seal(&bob_new.send_key)).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_lost_rekey_messages_do_not_desync_keys() {
+		use crate::service::network::core::event_loop::EventLoopCommand;
+
+		let ((alice, mut alice_rx, _alice_dir, alice_info), (bob, mut bob_rx, _bob_dir, bob_info)) =
+			rekey_pair().await;
+		let (alice_node, bob_node) = (alice.identity.node_id(), bob.identity.node_id());
+
+		async fn next_sent(rx: &mut CommandReceiver) -> PairingMessage {
+			match rx.recv().await.unwrap() {
+				EventLoopCommand::SendMessageToNode { data, .. } => {
+					serde_json::from_slice::<PairingMessage>(&data).unwrap()
+				}
+				other => panic!("unexpected command {:?}", other),
+			}
+		}
+		async fn deliver(
+			to: &PairingProtocolHandler,
+			message: PairingMessage,
+			from: EndpointId,
+		) -> Option<PairingMessage> {
+			to.handle_pairing_message(message, from)
+				.await
+				.unwrap()
+				.map(|reply| serde_json::from_slice::<PairingMessage>(&reply).unwrap())
+		}
+		async fn in_sync(
+			alice: &PairingProtocolHandler,
+			bob: &PairingProtocolHandler,
+			alice_id: Uuid,
+			bob_id: Uuid,
+		) -> bool {
+			let (a, b) = (keys_for(alice, bob_id).await, keys_for(bob, alice_id).await);
+			a.send_key == b.receive_key && a.receive_key == b.send_key
+		}
+
+		// Lost confirmation: Bob only staged the keys, so nothing moved
+		let original = keys_for(&alice, bob_info.device_id).await;
+		alice.start_rekey(bob_info.device_id).await.unwrap();
+		let request = next_sent(&mut alice_rx).await;
+		let confirm = deliver(&bob, request, alice_node).await;
+		assert!(matches!(confirm, Some(PairingMessage::RekeyConfirm { .. })));
+		alice.pending_rekeys.write().await.clear();
+		assert_eq!(
+			keys_for(&alice, bob_info.device_id).await.send_key,
+			original.send_key
+		);
+		assert!(in_sync(&alice, &bob, alice_info.device_id, bob_info.device_id).await);
+
+		// Lost commit: Alice switched, Bob is still on the old keys
+		alice.start_rekey(bob_info.device_id).await.unwrap();
+		let request = next_sent(&mut alice_rx).await;
+		let confirm = deliver(&bob, request, alice_node).await.unwrap();
+		let commit = deliver(&alice, confirm, bob_node).await;
+		assert!(matches!(commit, Some(PairingMessage::RekeyCommit { .. })));
+		assert!(!in_sync(&alice, &bob, alice_info.device_id, bob_info.device_id).await);
+
+		// Bob's next re-key is proven with the old keys, which Alice still accepts
+		bob.start_rekey(alice_info.device_id).await.unwrap();
+		let request = next_sent(&mut bob_rx).await;
+		let confirm = deliver(&alice, request, bob_node).await.unwrap();
+		let commit = deliver(&bob, confirm, alice_node).await.unwrap();
+		assert!(deliver(&alice, commit, bob_node).await.is_none());
+		assert!(in_sync(&alice, &bob, alice_info.device_id, bob_info.device_id).await);
+		assert_ne!(
+			keys_for(&alice, bob_info.device_id).await.send_key,
+			original.send_key
+		);
+
+		// A forged commit is refused
+		alice.start_rekey(bob_info.device_id).await.unwrap();
+		let request = next_sent(&mut alice_rx).await;
+		let Some(PairingMessage::RekeyConfirm { session_id, .. }) =
+			deliver(&bob, request, alice_node).await
+		else {
+			panic!("expected a confirmation");
+		};
+		let forged = PairingMessage::RekeyCommit {
+			session_id,
+			proof: vec![0u8; 32],
+		};
+		assert!(bob
+			.handle_pairing_message(forged, alice_node)
+			.await
+			.is_err());
+		assert!(in_sync(&alice, &bob, alice_info.device_id, bob_info.device_id).await);
+	}
+
+	#[tokio::test]
+	async fn test_concurrent_proxy_confirmations_apply_once() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+		let session_id = Uuid::new_v4();
+		let vouchee = handler.get_device_info().await.unwrap();
+
+		insert_pending_confirmation(
+			&handler,
+			pending_confirmation(session_id, Uuid::new_v4(), vouchee),
+		)
+		.await;
+
+		let (first, second) = tokio::join!(
+			handler.confirm_proxy_pairing(session_id, false),
+			handler.confirm_proxy_pairing(session_id, false),
+		);
+		let outcomes = [first.unwrap(), second.unwrap()];
+		assert_eq!(
+			outcomes
+				.iter()
+				.filter(|outcome| **outcome == ProxyConfirmationOutcome::Applied)
+				.count(),
+			1
+		);
+		assert!(outcomes.contains(&ProxyConfirmationOutcome::AlreadyConfirmed { accepted: false }));
+		assert!(handler.pending_proxy_confirmations.read().await.is_empty());
+
+		// A late change of mind does not overturn the first answer
+		assert_eq!(
+			handler
+				.confirm_proxy_pairing(session_id, true)
+				.await
+				.unwrap(),
+			ProxyConfirmationOutcome::AlreadyConfirmed { accepted: false }
+		);
+		assert!(handler
+			.device_registry
+			.read()
+			.await
+			.get_paired_devices()
+			.is_empty());
+
+		// Sessions that were never pending still fail
+		assert!(handler
+			.confirm_proxy_pairing(Uuid::new_v4(), true)
+			.await
+			.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_manual_proxy_confirmation_is_audited_as_user_confirmed() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+		let voucher_device_id = Uuid::new_v4();
+		let local_info = handler.get_device_info().await.unwrap();
+
+		let pending_for = |vouchee_name: &str| {
+			let mut vouchee = local_info.clone();
+			vouchee.device_id = Uuid::new_v4();
+			vouchee.device_name = vouchee_name.to_string();
+			pending_confirmation(Uuid::new_v4(), voucher_device_id, vouchee)
+		};
+		let accepted = pending_for("Accepted");
+		let rejected = pending_for("Rejected");
+		let (accepted_session, rejected_session) = (accepted.session_id, rejected.session_id);
+		let accepted_vouchee = accepted.vouchee_device_info.device_id;
+		insert_pending_confirmation(&handler, accepted).await;
+		insert_pending_confirmation(&handler, rejected).await;
+
+		handler
+			.confirm_proxy_pairing(accepted_session, true)
+			.await
+			.unwrap();
+		handler
+			.confirm_proxy_pairing(rejected_session, false)
+			.await
+			.unwrap();
+
+		let audit = handler.get_proxy_pairing_audit().await.unwrap();
+		assert_eq!(audit.len(), 2);
+		assert_eq!(audit[0].session_id, accepted_session);
+		assert_eq!(audit[0].voucher_device_id, voucher_device_id);
+		assert_eq!(audit[0].vouchee_device_id, accepted_vouchee);
+		assert_eq!(audit[0].vouchee_device_name, "Accepted");
+		assert!(audit[0].accepted);
+		assert!(audit[0].confirmed_by_user);
+		assert_eq!(audit[1].session_id, rejected_session);
+		assert!(!audit[1].accepted);
+		assert!(audit[1].confirmed_by_user);
+		assert!(audit[1].reason.is_none());
+
+		// A repeated answer is not a new decision
+		handler
+			.confirm_proxy_pairing(accepted_session, true)
+			.await
+			.unwrap();
+		assert_eq!(handler.get_proxy_pairing_audit().await.unwrap().len(), 2);
+	}
+
+	#[tokio::test]
+	async fn test_unaudited_proxy_confirmation_stays_pending() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let session_id = Uuid::new_v4();
+		let mut vouchee = handler.get_device_info().await.unwrap();
+		vouchee.device_id = Uuid::new_v4();
+		let vouchee_id = vouchee.device_id;
+
+		insert_pending_confirmation(
+			&handler,
+			pending_confirmation(session_id, Uuid::new_v4(), vouchee),
+		)
+		.await;
+
+		// Without an audit log the decision cannot be recorded, so it is not applied
+		assert!(handler
+			.confirm_proxy_pairing(session_id, true)
+			.await
+			.is_err());
+		assert!(handler
+			.device_registry
+			.read()
+			.await
+			.get_device_state(vouchee_id)
+			.is_none());
+		assert!(handler
+			.pending_proxy_confirmations
+			.read()
+			.await
+			.contains_key(&session_id));
+		assert!(handler.proxy_confirmation_decisions.read().await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_disconnect_requeues_waiting_vouch() {
+		let (handler, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build()
+			.await;
+		let vouchee = handler.get_device_info().await.unwrap();
+		let session_id = Uuid::new_v4();
+		let target = Uuid::new_v4();
+		let now = chrono::Utc::now();
+
+		handler
+			.create_vouching_session(session_id, &vouchee)
+			.await
+			.unwrap();
+		handler
+			.update_vouch_status(session_id, target, VouchStatus::Waiting, None)
+			.await
+			.unwrap();
+
+		let queue = handler.vouching_queue.read().await.clone().unwrap();
+		queue
+			.upsert_entry(&VouchingQueueEntry {
+				status: VouchQueueStatus::Waiting,
+				retry_count: 1,
+				last_attempt_at: Some(now),
+				..queued_entry(session_id, target, &vouchee, now)
+			})
+			.await
+			.unwrap();
+
+		// A confirmation this device vouched for waits for it to come back
+		let confirmation_id = Uuid::new_v4();
+		insert_pending_confirmation(
+			&handler,
+			pending_confirmation(confirmation_id, target, vouchee.clone()),
+		)
+		.await;
+
+		handler
+			.handle_event(ProtocolEvent::DeviceDisconnected { device_id: target })
+			.await
+			.unwrap();
+
+		let session = handler.get_vouching_session(session_id).await.unwrap();
+		let vouch = session
+			.vouches
+			.iter()
+			.find(|vouch| vouch.device_id == target)
+			.unwrap();
+		assert!(matches!(vouch.status, VouchStatus::Queued));
+		assert!(!matches!(session.state, VouchingSessionState::Completed));
+
+		let entries = queue.list_entries_for_session(session_id).await.unwrap();
+		assert_eq!(entries.len(), 1);
+		assert!(matches!(entries[0].status, VouchQueueStatus::Queued));
+		assert_eq!(entries[0].retry_count, 1);
+
+		assert!(handler
+			.pending_proxy_confirmations
+			.read()
+			.await
+			.contains_key(&confirmation_id));
+	}
+
+	#[tokio::test]
+	async fn test_resent_vouch_resumes_confirmation_after_reconnect() {
+		use crate::service::network::core::event_loop::EventLoopCommand;
+
+		let (handler, mut command_rx, _temp_dir) = TestHandlerBuilder::new()
+			.with_vouching_queue()
+			.build_with_commands()
+			.await;
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				auto_accept_vouched: false,
+				..ProxyPairingConfig::default()
+			})
+			.await;
+		let (voucher, voucher_id, _voucher_dir) = pair_test_voucher(&handler).await;
+
+		let vouchee = NetworkIdentity::new().await.unwrap();
+		let mut info = voucher.get_device_info().await.unwrap();
+		info.device_id = Uuid::new_v4();
+		info.network_fingerprint = vouchee.network_fingerprint();
+		let session_id = Uuid::new_v4();
+		let resend = || {
+			send_signed_vouch(
+				&handler,
+				&voucher,
+				session_id,
+				info.clone(),
+				vouchee.public_key_bytes(),
+			)
+		};
+
+		resend().await;
+		handler
+			.handle_event(ProtocolEvent::DeviceDisconnected {
+				device_id: voucher_id,
+			})
+			.await
+			.unwrap();
+
+		// The voucher resends after reconnecting and finds the same confirmation
+		resend().await;
+		{
+			let pending = handler.pending_proxy_confirmations.read().await;
+			assert_eq!(pending.len(), 1);
+			assert!(pending.contains_key(&session_id));
+		}
+
+		handler
+			.confirm_proxy_pairing(session_id, true)
+			.await
+			.unwrap();
+		while command_rx.try_recv().is_ok() {}
+
+		// A resend after the answer gets the recorded decision back
+		resend().await;
+		match command_rx.try_recv().unwrap() {
+			EventLoopCommand::SendMessageToNode { node_id, data, .. } => {
+				assert_eq!(node_id, voucher.identity.node_id());
+				assert!(matches!(
+					serde_json::from_slice::<PairingMessage>(&data).unwrap(),
+					PairingMessage::ProxyPairingResponse {
+						session_id: id,
+						accepted: true,
+						..
+					} if id == session_id
+				));
+			}
+			other => panic!("unexpected command {:?}", other),
+		}
+		assert!(handler.pending_proxy_confirmations.read().await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_vouchee_confirms_before_proxied_device_is_added() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		handler
+			.set_proxy_config(ProxyPairingConfig {
+				vouchee_confirms: true,
+				..Default::default()
+			})
+			.await;
+
+		let (voucher, voucher_id, _voucher_dir) = pair_test_voucher(&handler).await;
+
+		let session_id = record_voucher_session(&handler, voucher_id).await;
+		let mut device_info = handler.get_device_info().await.unwrap();
+		device_info.device_id = Uuid::new_v4();
+		device_info.device_name = "Proxied".to_string();
+		let device_id = device_info.device_id;
+
+		let accepted_by = vec![proxy::AcceptedDevice { device_info }];
+		let signature = voucher
+			.sign_proxy_completion(&ProxyCompletionPayload {
+				session_id,
+				voucher_device_id: voucher_id,
+				accepted_by: &accepted_by,
+				rejected_by: &[],
+			})
+			.unwrap();
+		handler
+			.handle_proxy_pairing_complete(
+				session_id,
+				voucher_id,
+				accepted_by,
+				Vec::new(),
+				signature,
+			)
+			.await
+			.unwrap();
+
+		async fn is_paired(handler: &PairingProtocolHandler, device_id: Uuid) -> bool {
+			handler
+				.device_registry
+				.read()
+				.await
+				.get_device_state(device_id)
+				.is_some()
+		}
+		assert!(!is_paired(&handler, device_id).await);
+
+		// Unknown devices have nothing to confirm
+		assert!(!handler
+			.confirm_proxied_device(session_id, Uuid::new_v4(), true)
+			.await
+			.unwrap());
+
+		assert!(handler
+			.confirm_proxied_device(session_id, device_id, true)
+			.await
+			.unwrap());
+		assert!(is_paired(&handler, device_id).await);
+		assert!(handler.pending_proxied_devices.read().await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_declined_proxied_device_is_announced_and_stale_ones_expire() {
+		use crate::service::network::core::event_loop::EventLoopCommand;
+
+		let (handler, mut command_rx, _temp_dir) =
+			TestHandlerBuilder::new().build_with_commands().await;
+		let (voucher, voucher_id, _voucher_dir) = pair_test_voucher(&handler).await;
+		let (target, _target_dir) = create_test_handler().await;
+		let target_info = target.get_device_info().await.unwrap();
+		let target_id = target_info.device_id;
+
+		let pending = |created_at| PendingProxiedDevice {
+			voucher_device_id: voucher_id,
+			device_info: target_info.clone(),
+			session_keys: SessionKeys::from_shared_secret(vec![3; 32]),
+			created_at,
+		};
+		let session_id = Uuid::new_v4();
+		handler
+			.pending_proxied_devices
+			.write()
+			.await
+			.insert((session_id, target_id), pending(chrono::Utc::now()));
+
+		fn sent_declines(rx: &mut CommandReceiver) -> Vec<(EndpointId, Uuid, Uuid)> {
+			let mut sent = Vec::new();
+			while let Ok(command) = rx.try_recv() {
+				let EventLoopCommand::SendMessageToNode { node_id, data, .. } = command else {
+					panic!("unexpected command {:?}", command);
+				};
+				match serde_json::from_slice::<PairingMessage>(&data).unwrap() {
+					PairingMessage::ProxyPairingDeclined {
+						session_id,
+						declined_device_id,
+						..
+					} => sent.push((node_id, session_id, declined_device_id)),
+					other => panic!("unexpected message {:?}", other),
+				}
+			}
+			sent.sort_by_key(|(node_id, ..)| node_id.to_string());
+			sent
+		}
+		let recipients = |session_id| {
+			let mut expected = vec![
+				(voucher.identity.node_id(), session_id, target_id),
+				(target.identity.node_id(), session_id, target_id),
+			];
+			expected.sort_by_key(|(node_id, ..)| node_id.to_string());
+			expected
+		};
+
+		// Declining tells the voucher, and the target that already paired with us
+		assert!(handler
+			.confirm_proxied_device(session_id, target_id, false)
+			.await
+			.unwrap());
+		assert_eq!(sent_declines(&mut command_rx), recipients(session_id));
+		assert!(handler.pending_proxied_devices.read().await.is_empty());
+
+		// Confirmations left unanswered past the session TTL are declined the same way
+		let ttl = handler.proxy_config.read().await.vouching_session_ttl_secs as i64;
+		let stale_session = Uuid::new_v4();
+		let fresh_session = Uuid::new_v4();
+		{
+			let mut pending_devices = handler.pending_proxied_devices.write().await;
+			pending_devices.insert(
+				(stale_session, target_id),
+				pending(chrono::Utc::now() - chrono::Duration::seconds(ttl + 1)),
+			);
+			pending_devices.insert((fresh_session, target_id), pending(chrono::Utc::now()));
+		}
+		handler.cleanup_expired_sessions().await.unwrap();
+
+		assert_eq!(sent_declines(&mut command_rx), recipients(stale_session));
+		let remaining = handler.pending_proxied_devices.read().await;
+		assert_eq!(remaining.len(), 1);
+		assert!(remaining.contains_key(&(fresh_session, target_id)));
+	}
+
+	#[tokio::test]
+	async fn test_target_unpairs_vouchee_that_declined() {
+		let (target, _target_dir) = create_test_handler().await;
+		let (vouchee, _vouchee_dir) = create_test_handler().await;
+		let vouchee_info = vouchee.get_device_info().await.unwrap();
+		let vouchee_id = vouchee_info.device_id;
+		let target_id = target.get_device_info().await.unwrap().device_id;
+		let voucher_id = Uuid::new_v4();
+		pair_test_device(&target, vouchee_info, Some(voucher_id)).await;
+
+		let session_id = Uuid::new_v4();
+		let decline = PairingMessage::ProxyPairingDeclined {
+			session_id,
+			vouchee_device_id: vouchee_id,
+			declined_device_id: target_id,
+		};
+
+		// Only the vouchee may take back its pairing
+		let (other, _other_dir) = create_test_handler().await;
+		assert!(target
+			.handle_pairing_message(decline.clone(), other.identity.node_id())
+			.await
+			.is_err());
+		assert!(target
+			.device_registry
+			.read()
+			.await
+			.get_device_state(vouchee_id)
+			.is_some());
+
+		target
+			.handle_pairing_message(decline, vouchee.identity.node_id())
+			.await
+			.unwrap();
+		assert!(target
+			.device_registry
+			.read()
+			.await
+			.get_device_state(vouchee_id)
+			.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_target_and_vouchee_derive_same_proxy_keys() {
+		let (target, _target_dir) = create_test_handler().await;
+		let (vouchee, _vouchee_dir) = create_test_handler().await;
+		let voucher_id = Uuid::new_v4();
+		let mut target_info = target.get_device_info().await.unwrap();
+		target_info.network_fingerprint = target.identity.network_fingerprint();
+		let vouchee_id = vouchee.get_device_info().await.unwrap().device_id;
+		let vouchee_public_key = vouchee.identity.public_key_bytes();
+
+		// The target only has the vouch, the vouchee only the completion
+		let (target_keys, _) = target
+			.derive_proxy_session_keys(
+				voucher_id,
+				target_info.device_id,
+				vouchee_id,
+				&vouchee_public_key,
+				&vouchee_public_key,
+			)
+			.unwrap();
+		let vouchee_keys = vouchee
+			.proxied_device_session_keys(voucher_id, vouchee_id, &target_info)
+			.unwrap();
+
+		assert_eq!(target_keys.shared_secret, vouchee_keys.shared_secret);
+		assert_eq!(target_keys.send_key, vouchee_keys.receive_key);
+		assert_eq!(target_keys.receive_key, vouchee_keys.send_key);
+		assert_ne!(target_keys.send_key, target_keys.receive_key);
+
+		// The voucher sees the same vouch but holds neither secret key
+		let (voucher, _voucher_dir) = create_test_handler().await;
+		let (voucher_keys, _) = voucher
+			.derive_proxy_session_keys(
+				voucher_id,
+				target_info.device_id,
+				vouchee_id,
+				&vouchee_public_key,
+				&vouchee_public_key,
+			)
+			.unwrap();
+		assert_ne!(voucher_keys.shared_secret, target_keys.shared_secret);
+	}
+
+	#[tokio::test]
+	async fn test_tampered_proxy_completion_is_rejected() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let (voucher, voucher_id, _voucher_dir) = pair_test_voucher(&handler).await;
+
+		let session_id = record_voucher_session(&handler, voucher_id).await;
+		let mut device_info = handler.get_device_info().await.unwrap();
+		device_info.device_id = Uuid::new_v4();
+		let device_id = device_info.device_id;
+		let accepted_by = vec![proxy::AcceptedDevice { device_info }];
+		let signature = voucher
+			.sign_proxy_completion(&ProxyCompletionPayload {
+				session_id,
+				voucher_device_id: voucher_id,
+				accepted_by: &accepted_by,
+				rejected_by: &[],
+			})
+			.unwrap();
+
+		// A renamed device no longer matches what the voucher signed
+		let mut tampered = accepted_by.clone();
+		tampered[0].device_info.device_name = "Impostor".to_string();
+		assert!(handler
+			.handle_proxy_pairing_complete(
+				session_id,
+				voucher_id,
+				tampered,
+				Vec::new(),
+				signature.clone(),
+			)
+			.await
+			.is_err());
+		assert!(handler
+			.device_registry
+			.read()
+			.await
+			.get_device_state(device_id)
+			.is_none());
+
+		// Signed by a device we never paired with as the voucher
+		let (stranger, _stranger_dir) = create_test_handler().await;
+		let forged = stranger
+			.sign_proxy_completion(&ProxyCompletionPayload {
+				session_id,
+				voucher_device_id: voucher_id,
+				accepted_by: &accepted_by,
+				rejected_by: &[],
+			})
+			.unwrap();
+		assert!(handler
+			.handle_proxy_pairing_complete(
+				session_id,
+				voucher_id,
+				accepted_by.clone(),
+				Vec::new(),
+				forged,
+			)
+			.await
+			.is_err());
+
+		// A completion from a voucher that predates signing still parses, and is refused
+		let mut unsigned = serde_json::to_value(PairingMessage::ProxyPairingComplete {
+			session_id,
+			voucher_device_id: voucher_id,
+			accepted_by: accepted_by.clone(),
+			rejected_by: Vec::new(),
+			voucher_signature: Vec::new(),
+		})
+		.unwrap();
+		unsigned["ProxyPairingComplete"]
+			.as_object_mut()
+			.unwrap()
+			.remove("voucher_signature");
+		let PairingMessage::ProxyPairingComplete {
+			voucher_signature, ..
+		} = serde_json::from_value(unsigned).unwrap()
+		else {
+			panic!("expected a proxy pairing completion");
+		};
+		assert!(handler
+			.handle_proxy_pairing_complete(
+				session_id,
+				voucher_id,
+				accepted_by.clone(),
+				Vec::new(),
+				voucher_signature,
+			)
+			.await
+			.is_err());
+
+		// Validly signed by another paired device, for a session it was not part of
+		let (other_voucher, other_voucher_id, _other_dir) = pair_test_voucher(&handler).await;
+		let other_signature = other_voucher
+			.sign_proxy_completion(&ProxyCompletionPayload {
+				session_id,
+				voucher_device_id: other_voucher_id,
+				accepted_by: &accepted_by,
+				rejected_by: &[],
+			})
+			.unwrap();
+		assert!(handler
+			.handle_proxy_pairing_complete(
+				session_id,
+				other_voucher_id,
+				accepted_by.clone(),
+				Vec::new(),
+				other_signature,
+			)
+			.await
+			.is_err());
+
+		// The untouched message goes through
+		handler
+			.handle_proxy_pairing_complete(
+				session_id,
+				voucher_id,
+				accepted_by,
+				Vec::new(),
+				signature,
+			)
+			.await
+			.unwrap();
+		assert!(handler
+			.device_registry
+			.read()
+			.await
+			.get_device_state(device_id)
+			.is_some());
+	}
+
+	#[tokio::test]
+	async fn test_proxy_completion_reports_partial_failure() {
+		let (handler, _temp_dir) = create_test_handler().await;
+		let event_bus = Arc::new(EventBus::default());
+		let mut events = event_bus.subscribe();
+		handler.set_event_bus(event_bus).await;
+		let (voucher, voucher_id, _voucher_dir) = pair_test_voucher(&handler).await;
+
+		let mut accepted_by = Vec::new();
+		for _ in 0..3 {
+			let mut device_info = handler.get_device_info().await.unwrap();
+			device_info.device_id = Uuid::new_v4();
+			device_info.network_fingerprint =
+				NetworkIdentity::new().await.unwrap().network_fingerprint();
+			accepted_by.push(proxy::AcceptedDevice { device_info });
+		}
+		// No session keys can be derived with a device whose fingerprint names no node
+		accepted_by[1].device_info.network_fingerprint.node_id = "not-a-node".to_string();
+		let ids = accepted_by
+			.iter()
+			.map(|accepted| accepted.device_info.device_id)
+			.collect::<Vec<_>>();
+
+		let session_id = record_voucher_session(&handler, voucher_id).await;
+		let signature = voucher
+			.sign_proxy_completion(&ProxyCompletionPayload {
+				session_id,
+				voucher_device_id: voucher_id,
+				accepted_by: &accepted_by,
+				rejected_by: &[],
+			})
+			.unwrap();
+		handler
+			.handle_proxy_pairing_complete(
+				session_id,
+				voucher_id,
+				accepted_by,
+				Vec::new(),
+				signature,
+			)
+			.await
+			.unwrap();
+
+		let states = handler.device_registry.read().await.get_device_states(&ids);
+		assert!(states.contains_key(&ids[0]));
+		assert!(!states.contains_key(&ids[1]));
+		assert!(states.contains_key(&ids[2]));
+
+		match events.try_recv().unwrap() {
+			Event::ProxyPairingCompleted {
+				session_id: reported,
+				paired,
+				failed,
+				..
+			} => {
+				assert_eq!(reported, session_id);
+				assert_eq!(paired, vec![ids[0], ids[2]]);
+				assert_eq!(failed.len(), 1);
+				assert_eq!(failed[0].device_id, ids[1]);
+			}
+			other => panic!("Expected ProxyPairingCompleted, got {:?}", other),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_persistence_dir_override_is_used() {
+		let (base, _base_dir) = create_test_handler().await;
+		let temp_dir = TempDir::new().unwrap();
+		let data_dir = temp_dir.path().join("data");
+		let override_dir = temp_dir.path().join("pairing");
+
+		// Unset, persistence stays under the main data directory
+		let mut config = crate::config::AppConfig::default_with_dir(data_dir.clone());
+		assert_eq!(config.pairing_dir(), data_dir);
+
+		config.pairing_data_dir = Some(override_dir.clone());
+		let pairing_dir = config.pairing_dir();
+		assert_eq!(pairing_dir, override_dir);
+
+		let (command_sender, _command_rx) = CommandSender::channel(CommandChannelConfig::default());
+		let handler = PairingProtocolHandler::new_with_persistence(
+			base.identity.clone(),
+			base.device_registry.clone(),
+			Arc::new(SilentLogger),
+			command_sender,
+			pairing_dir.clone(),
+			None,
+			Arc::new(RwLock::new(HashMap::new())),
+		);
+		handler.init_vouching_queue(pairing_dir).await.unwrap();
+		handler.save_sessions_to_persistence().await.unwrap();
+
+		let networking_dir = override_dir.join("networking");
+		assert!(networking_dir.join("pairing_sessions.json").exists());
+		assert!(networking_dir.join("vouching_queue.db").exists());
+		assert!(!data_dir.join("networking").exists());
+	}
+}
@@ -1,11 +1,13 @@
 //! Session persistence for pairing protocol
 
-use super::types::{PairingSession, PairingState};
+use super::types::{PairingFailureReason, PairingSession, PairingState};
 use crate::service::network::{NetworkingError, Result};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::fs;
 use uuid::Uuid;
 
@@ -18,6 +20,10 @@ struct SerializablePairingSession {
 	pub remote_public_key: Option<Vec<u8>>,
 	pub shared_secret: Option<Vec<u8>>,
 	pub created_at: chrono::DateTime<chrono::Utc>,
+	#[serde(default)]
+	pub via_relay: bool,
+	#[serde(default)]
+	pub auto_accepted: bool,
 }
 
 /// Serializable version of PairingState
@@ -25,10 +31,16 @@ struct SerializablePairingSession {
 enum SerializablePairingState {
 	WaitingForConnection,
 	Scanning,
-	ChallengeReceived { challenge: Vec<u8> },
+	ChallengeReceived {
+		challenge: Vec<u8>,
+	},
 	ResponseSent,
 	Completed,
-	Failed { reason: String },
+	Failed {
+		reason: PairingFailureReason,
+		#[serde(default)]
+		detail: Option<String>,
+	},
 }
 
 impl From<&PairingSession> for SerializablePairingSession {
@@ -47,18 +59,22 @@ impl From<&PairingSession> for SerializablePairingSession {
 				}
 				PairingState::ResponseSent => SerializablePairingState::ResponseSent,
 				PairingState::Completed => SerializablePairingState::Completed,
-				PairingState::Failed { reason } => SerializablePairingState::Failed {
-					reason: reason.clone(),
+				PairingState::Failed { reason, detail } => SerializablePairingState::Failed {
+					reason: *reason,
+					detail: detail.clone(),
 				},
 				// Skip non-serializable states
 				_ => SerializablePairingState::Failed {
-					reason: "State not serializable".to_string(),
+					reason: PairingFailureReason::InternalError,
+					detail: Some("State not serializable".to_string()),
 				},
 			},
 			remote_device_id: session.remote_device_id,
 			remote_public_key: session.remote_public_key.clone(),
 			shared_secret: session.shared_secret.clone(),
 			created_at: session.created_at,
+			via_relay: session.via_relay,
+			auto_accepted: session.auto_accepted,
 		}
 	}
 }
@@ -77,13 +93,17 @@ impl From<SerializablePairingSession> for PairingSession {
 				}
 				SerializablePairingState::ResponseSent => PairingState::ResponseSent,
 				SerializablePairingState::Completed => PairingState::Completed,
-				SerializablePairingState::Failed { reason } => PairingState::Failed { reason },
+				SerializablePairingState::Failed { reason, detail } => {
+					PairingState::Failed { reason, detail }
+				}
 			},
 			remote_device_id: serializable.remote_device_id,
 			remote_device_info: None, // Will be restored from device registry
 			remote_public_key: serializable.remote_public_key,
 			shared_secret: serializable.shared_secret,
 			created_at: serializable.created_at,
+			via_relay: serializable.via_relay,
+			auto_accepted: serializable.auto_accepted,
 		}
 	}
 }
@@ -95,27 +115,66 @@ struct PersistedPairingSessions {
 	last_saved: chrono::DateTime<chrono::Utc>,
 }
 
-/// Session persistence manager
-pub struct PairingPersistence {
-	data_dir: PathBuf,
+/// Storage for the serialized pairing sessions
+///
+/// [`PairingPersistence`] handles serialization and expiry, the backend only
+/// stores one opaque document. Files under the data dir are the default;
+/// [`MemoryPersistenceBackend`] keeps everything in memory for tests.
+#[async_trait]
+pub trait PersistenceBackend: Send + Sync {
+	/// Read the stored document, None if nothing has been saved yet
+	async fn read(&self) -> Result<Option<String>>;
+
+	/// Replace the stored document
+	async fn write(&self, data: &str) -> Result<()>;
+
+	/// Delete the stored document if there is one
+	async fn remove(&self) -> Result<()>;
+
+	/// Set aside a document that failed to parse so it is not read again
+	async fn quarantine(&self) -> Result<()> {
+		self.remove().await
+	}
+
+	/// File backing this store, if any
+	fn path(&self) -> Option<&Path> {
+		None
+	}
+}
+
+/// Stores sessions as JSON in `<data_dir>/networking/pairing_sessions.json`
+pub struct FilePersistenceBackend {
 	sessions_file: PathBuf,
 }
 
-impl PairingPersistence {
-	/// Create a new persistence manager
+impl FilePersistenceBackend {
 	pub fn new(data_dir: impl AsRef<Path>) -> Self {
-		let data_dir = data_dir.as_ref().to_path_buf();
-		let networking_dir = data_dir.join("networking");
-		let sessions_file = networking_dir.join("pairing_sessions.json");
-
 		Self {
-			data_dir: networking_dir,
-			sessions_file,
+			sessions_file: data_dir
+				.as_ref()
+				.join("networking")
+				.join("pairing_sessions.json"),
 		}
 	}
+}
 
-	/// Save active sessions to disk
-	pub async fn save_sessions(&self, sessions: &HashMap<Uuid, PairingSession>) -> Result<()> {
+#[async_trait]
+impl PersistenceBackend for FilePersistenceBackend {
+	async fn read(&self) -> Result<Option<String>> {
+		if !self.sessions_file.exists() {
+			return Ok(None);
+		}
+
+		match fs::read_to_string(&self.sessions_file).await {
+			Ok(data) => Ok(Some(data)),
+			Err(e) => {
+				eprintln!("Failed to read pairing sessions file: {}", e);
+				Ok(None)
+			}
+		}
+	}
+
+	async fn write(&self, data: &str) -> Result<()> {
 		// Ensure data directory exists
 		if let Some(parent) = self.sessions_file.parent() {
 			fs::create_dir_all(parent)
@@ -123,6 +182,167 @@ impl PairingPersistence {
 				.map_err(NetworkingError::Io)?;
 		}
 
+		// Write to temporary file first, then rename for atomic operation
+		let temp_file = self.sessions_file.with_extension("tmp");
+		fs::write(&temp_file, data)
+			.await
+			.map_err(NetworkingError::Io)?;
+
+		fs::rename(&temp_file, &self.sessions_file)
+			.await
+			.map_err(NetworkingError::Io)?;
+
+		Ok(())
+	}
+
+	async fn remove(&self) -> Result<()> {
+		if self.sessions_file.exists() {
+			fs::remove_file(&self.sessions_file)
+				.await
+				.map_err(NetworkingError::Io)?;
+		}
+		Ok(())
+	}
+
+	async fn quarantine(&self) -> Result<()> {
+		// Keep the corrupted file around for debugging
+		let backup_path = self.sessions_file.with_extension("json.corrupted");
+		let _ = fs::rename(&self.sessions_file, &backup_path).await;
+		eprintln!("Renamed corrupted file to: {:?}", backup_path);
+		Ok(())
+	}
+
+	fn path(&self) -> Option<&Path> {
+		Some(&self.sessions_file)
+	}
+}
+
+/// Keeps sessions in memory, nothing touches disk
+#[derive(Default)]
+pub struct MemoryPersistenceBackend {
+	data: Mutex<Option<String>>,
+}
+
+impl MemoryPersistenceBackend {
+	pub fn new() -> Self {
+		Self::default()
+	}
+}
+
+#[async_trait]
+impl PersistenceBackend for MemoryPersistenceBackend {
+	async fn read(&self) -> Result<Option<String>> {
+		Ok(self.data.lock().unwrap().clone())
+	}
+
+	async fn write(&self, data: &str) -> Result<()> {
+		*self.data.lock().unwrap() = Some(data.to_string());
+		Ok(())
+	}
+
+	async fn remove(&self) -> Result<()> {
+		*self.data.lock().unwrap() = None;
+		Ok(())
+	}
+}
+
+/// Files pairing keeps under `<dir>/networking`, moved together on relocation
+const PAIRING_FILES: &[&str] = &[
+	"pairing_sessions.json",
+	"vouching_queue.db",
+	"vouching_queue.db-journal",
+	"vouching_queue.db-wal",
+	"vouching_queue.db-shm",
+];
+
+/// File under `<data_dir>/networking` recording the directory pairing data was last kept in
+const PAIRING_DIR_MARKER: &str = "pairing_data_dir";
+
+/// Prepare `pairing_dir` to hold pairing sessions and the vouching queue
+///
+/// Its `networking` directory is created and restricted to the owner. If pairing
+/// data was last kept somewhere else, the files are moved over from there,
+/// unless `pairing_dir` already holds some of its own. Where the data was last
+/// kept is recorded under `data_dir`; installs that never recorded it kept the
+/// data in `data_dir` itself.
+pub async fn relocate_pairing_data(data_dir: &Path, pairing_dir: &Path) -> Result<()> {
+	let marker = data_dir.join("networking").join(PAIRING_DIR_MARKER);
+	let previous = match fs::read_to_string(&marker).await {
+		Ok(path) => PathBuf::from(path.trim()),
+		Err(_) => data_dir.to_path_buf(),
+	};
+
+	let target = pairing_dir.join("networking");
+	fs::create_dir_all(&target)
+		.await
+		.map_err(NetworkingError::Io)?;
+	restrict_to_owner(&target).await?;
+
+	if previous != pairing_dir {
+		let source = previous.join("networking");
+		let mut has_own_data = false;
+		for name in PAIRING_FILES {
+			has_own_data |= fs::try_exists(target.join(name)).await.unwrap_or(false);
+		}
+
+		if !has_own_data {
+			for name in PAIRING_FILES {
+				let from = source.join(name);
+				if !fs::try_exists(&from).await.unwrap_or(false) {
+					continue;
+				}
+				let to = target.join(name);
+				// Renaming fails across filesystems, so fall back to copying
+				if fs::rename(&from, &to).await.is_err() {
+					fs::copy(&from, &to).await.map_err(NetworkingError::Io)?;
+					fs::remove_file(&from).await.map_err(NetworkingError::Io)?;
+				}
+			}
+		}
+	}
+
+	if let Some(parent) = marker.parent() {
+		fs::create_dir_all(parent)
+			.await
+			.map_err(NetworkingError::Io)?;
+	}
+	fs::write(&marker, pairing_dir.to_string_lossy().as_bytes())
+		.await
+		.map_err(NetworkingError::Io)
+}
+
+#[cfg(unix)]
+async fn restrict_to_owner(dir: &Path) -> Result<()> {
+	use std::os::unix::fs::PermissionsExt;
+
+	fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+		.await
+		.map_err(NetworkingError::Io)
+}
+
+#[cfg(not(unix))]
+async fn restrict_to_owner(_dir: &Path) -> Result<()> {
+	Ok(())
+}
+
+/// Session persistence manager
+pub struct PairingPersistence {
+	backend: Arc<dyn PersistenceBackend>,
+}
+
+impl PairingPersistence {
+	/// Create a new persistence manager storing sessions under `data_dir`
+	pub fn new(data_dir: impl AsRef<Path>) -> Self {
+		Self::with_backend(Arc::new(FilePersistenceBackend::new(data_dir)))
+	}
+
+	/// Create a persistence manager on top of an arbitrary backend
+	pub fn with_backend(backend: Arc<dyn PersistenceBackend>) -> Self {
+		Self { backend }
+	}
+
+	/// Save active sessions to the backend
+	pub async fn save_sessions(&self, sessions: &HashMap<Uuid, PairingSession>) -> Result<()> {
 		// Convert to serializable format, filtering out transient states
 		let serializable_sessions: HashMap<Uuid, SerializablePairingSession> = sessions
 			.iter()
@@ -145,34 +365,16 @@ impl PairingPersistence {
 			last_saved: chrono::Utc::now(),
 		};
 
-		// Write to temporary file first, then rename for atomic operation
-		let temp_file = self.sessions_file.with_extension("tmp");
 		let json_data = serde_json::to_string_pretty(&persisted)
 			.map_err(|e| NetworkingError::Serialization(e))?;
 
-		fs::write(&temp_file, json_data)
-			.await
-			.map_err(NetworkingError::Io)?;
-
-		fs::rename(&temp_file, &self.sessions_file)
-			.await
-			.map_err(NetworkingError::Io)?;
-
-		Ok(())
+		self.backend.write(&json_data).await
 	}
 
-	/// Load sessions from disk
+	/// Load sessions from the backend
 	pub async fn load_sessions(&self) -> Result<HashMap<Uuid, PairingSession>> {
-		if !self.sessions_file.exists() {
+		let Some(json_data) = self.backend.read().await? else {
 			return Ok(HashMap::new());
-		}
-
-		let json_data = match fs::read_to_string(&self.sessions_file).await {
-			Ok(data) => data,
-			Err(e) => {
-				eprintln!("Failed to read pairing sessions file: {}", e);
-				return Ok(HashMap::new());
-			}
 		};
 
 		// Handle empty files
@@ -188,10 +390,7 @@ impl PairingPersistence {
 					"Failed to parse pairing sessions JSON: {}. File may be corrupted.",
 					e
 				);
-				// Try to rename the corrupted file for debugging
-				let backup_path = self.sessions_file.with_extension("json.corrupted");
-				let _ = fs::rename(&self.sessions_file, &backup_path).await;
-				eprintln!("Renamed corrupted file to: {:?}", backup_path);
+				self.backend.quarantine().await?;
 				return Ok(HashMap::new());
 			}
 		};
@@ -216,7 +415,7 @@ impl PairingPersistence {
 		Ok(sessions)
 	}
 
-	/// Clean up expired sessions from disk
+	/// Clean up expired sessions from the backend
 	pub async fn cleanup_expired_sessions(&self) -> Result<usize> {
 		let sessions = self.load_sessions().await?;
 		let initial_count = sessions.len();
@@ -229,17 +428,12 @@ impl PairingPersistence {
 
 	/// Delete all persisted sessions
 	pub async fn clear_all_sessions(&self) -> Result<()> {
-		if self.sessions_file.exists() {
-			fs::remove_file(&self.sessions_file)
-				.await
-				.map_err(NetworkingError::Io)?;
-		}
-		Ok(())
+		self.backend.remove().await
 	}
 
-	/// Get the path to the sessions file
-	pub fn sessions_file_path(&self) -> &Path {
-		&self.sessions_file
+	/// Get the path to the sessions file, None for backends not stored in a file
+	pub fn sessions_file_path(&self) -> Option<&Path> {
+		self.backend.path()
 	}
 }
 
@@ -269,6 +463,8 @@ mod tests {
 			remote_public_key: None,
 			shared_secret: Some(vec![1, 2, 3, 4]),
 			created_at: chrono::Utc::now(),
+			via_relay: false,
+			auto_accepted: false,
 		};
 		sessions.insert(session_id, session);
 
@@ -313,14 +509,143 @@ mod tests {
 				remote_public_key: None,
 				shared_secret: None,
 				created_at: chrono::Utc::now(),
+				via_relay: false,
+				auto_accepted: false,
 			},
 		);
 
 		persistence.save_sessions(&sessions).await.unwrap();
-		assert!(persistence.sessions_file.exists());
+		let sessions_file = persistence.sessions_file_path().unwrap().to_path_buf();
+		assert!(sessions_file.exists());
 
 		// Clear sessions
 		persistence.clear_all_sessions().await.unwrap();
-		assert!(!persistence.sessions_file.exists());
+		assert!(!sessions_file.exists());
+	}
+
+	#[tokio::test]
+	async fn test_memory_backend_round_trips_sessions() {
+		let backend = Arc::new(MemoryPersistenceBackend::new());
+		let persistence = PairingPersistence::with_backend(backend.clone());
+		assert!(persistence.sessions_file_path().is_none());
+		assert!(persistence.load_sessions().await.unwrap().is_empty());
+
+		let session_id = Uuid::new_v4();
+		let mut sessions = HashMap::new();
+		sessions.insert(
+			session_id,
+			PairingSession {
+				id: session_id,
+				state: PairingState::ResponseSent,
+				remote_device_id: Some(Uuid::new_v4()),
+				remote_device_info: None,
+				remote_public_key: Some(vec![9; 32]),
+				shared_secret: Some(vec![1, 2, 3, 4]),
+				created_at: chrono::Utc::now(),
+				via_relay: true,
+				auto_accepted: true,
+			},
+		);
+		persistence.save_sessions(&sessions).await.unwrap();
+
+		// A second manager on the same backend sees what the first one saved
+		let reloaded = PairingPersistence::with_backend(backend.clone())
+			.load_sessions()
+			.await
+			.unwrap();
+		let loaded = &reloaded[&session_id];
+		assert!(matches!(loaded.state, PairingState::ResponseSent));
+		assert_eq!(loaded.remote_public_key, Some(vec![9; 32]));
+		assert_eq!(loaded.shared_secret, Some(vec![1, 2, 3, 4]));
+		assert!(loaded.via_relay);
+		assert!(loaded.auto_accepted);
+
+		persistence.clear_all_sessions().await.unwrap();
+		assert!(backend.read().await.unwrap().is_none());
+	}
+
+	#[tokio::test]
+	async fn test_relocation_moves_pairing_data_to_new_dir() {
+		let temp_dir = TempDir::new().unwrap();
+		let data_dir = temp_dir.path().join("data");
+		let local_dir = temp_dir.path().join("local");
+		let other_dir = temp_dir.path().join("other");
+
+		// Sessions saved before any override was configured
+		let persistence = PairingPersistence::new(&data_dir);
+		let mut sessions = HashMap::new();
+		let session_id = Uuid::new_v4();
+		let session = PairingSession {
+			id: session_id,
+			state: PairingState::Completed,
+			remote_device_id: Some(Uuid::new_v4()),
+			remote_device_info: None,
+			remote_public_key: None,
+			shared_secret: None,
+			created_at: chrono::Utc::now(),
+			via_relay: false,
+			auto_accepted: false,
+		};
+		sessions.insert(session_id, session);
+		persistence.save_sessions(&sessions).await.unwrap();
+
+		relocate_pairing_data(&data_dir, &local_dir).await.unwrap();
+		assert!(!data_dir
+			.join("networking")
+			.join("pairing_sessions.json")
+			.exists());
+		let loaded = PairingPersistence::new(&local_dir)
+			.load_sessions()
+			.await
+			.unwrap();
+		assert!(loaded.contains_key(&session_id));
+
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			let mode = std::fs::metadata(local_dir.join("networking"))
+				.unwrap()
+				.permissions()
+				.mode();
+			assert_eq!(mode & 0o777, 0o700);
+		}
+
+		// Changing the override again follows the data from where it was last kept
+		relocate_pairing_data(&data_dir, &other_dir).await.unwrap();
+		let loaded = PairingPersistence::new(&other_dir)
+			.load_sessions()
+			.await
+			.unwrap();
+		assert!(loaded.contains_key(&session_id));
+
+		// Unchanged, nothing moves
+		relocate_pairing_data(&data_dir, &other_dir).await.unwrap();
+		assert!(other_dir
+			.join("networking")
+			.join("pairing_sessions.json")
+			.exists());
+	}
+
+	#[tokio::test]
+	async fn test_relocation_keeps_data_already_in_new_dir() {
+		let temp_dir = TempDir::new().unwrap();
+		let data_dir = temp_dir.path().join("data");
+		let pairing_dir = temp_dir.path().join("pairing");
+
+		let old = PairingPersistence::new(&data_dir);
+		old.save_sessions(&HashMap::new()).await.unwrap();
+		let existing = pairing_dir.join("networking").join("pairing_sessions.json");
+		std::fs::create_dir_all(existing.parent().unwrap()).unwrap();
+		std::fs::write(&existing, "{}").unwrap();
+
+		relocate_pairing_data(&data_dir, &pairing_dir)
+			.await
+			.unwrap();
+
+		assert_eq!(std::fs::read_to_string(&existing).unwrap(), "{}");
+		assert!(data_dir
+			.join("networking")
+			.join("pairing_sessions.json")
+			.exists());
 	}
 }
@@ -3,7 +3,13 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use uuid::Uuid;
 
-use crate::service::network::device::{DeviceInfo, SessionKeys};
+use crate::service::network::device::{DeviceInfo, DeviceType};
+
+/// Version tag prefixed to the canonical vouch encoding
+///
+/// Bump this whenever the layout in [`VouchPayload::canonical_bytes`] changes so
+/// peers can tell an old signature apart from a tampered one.
+pub const VOUCH_PAYLOAD_ENCODING_VERSION: u8 = 1;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VouchPayload {
@@ -14,10 +20,168 @@ pub struct VouchPayload {
 	pub session_id: Uuid,
 }
 
+impl VouchPayload {
+	/// Canonical byte encoding of the payload, used as the signed message
+	///
+	/// Fields are written in a fixed order: UUIDs as their 16 raw bytes, timestamps
+	/// as big-endian seconds followed by nanoseconds, and variable-length values
+	/// behind a big-endian `u32` length. The layout does not depend on serde derive
+	/// order, so refactoring `DeviceInfo` cannot invalidate existing signatures.
+	pub fn canonical_bytes(&self) -> Vec<u8> {
+		let mut buf = Vec::with_capacity(256);
+		buf.push(VOUCH_PAYLOAD_ENCODING_VERSION);
+		buf.extend_from_slice(self.vouchee_device_id.as_bytes());
+		put_bytes(&mut buf, &self.vouchee_public_key);
+		put_device_info(&mut buf, &self.vouchee_device_info);
+
+		put_timestamp(&mut buf, self.timestamp);
+		buf.extend_from_slice(self.session_id.as_bytes());
+		buf
+	}
+}
+
+/// Version tag prefixed to the canonical proxy pairing completion encoding
+pub const PROXY_COMPLETION_ENCODING_VERSION: u8 = 2;
+
+/// Contents of a `ProxyPairingComplete` message, as signed by the voucher
+#[derive(Debug, Clone, Copy)]
+pub struct ProxyCompletionPayload<'a> {
+	pub session_id: Uuid,
+	pub voucher_device_id: Uuid,
+	pub accepted_by: &'a [AcceptedDevice],
+	pub rejected_by: &'a [RejectedDevice],
+}
+
+impl ProxyCompletionPayload<'_> {
+	/// Canonical byte encoding of the completion, used as the signed message
+	///
+	/// Uses the same field encoding as [`VouchPayload::canonical_bytes`], behind a
+	/// label so a vouch signature can never pass as a completion signature.
+	pub fn canonical_bytes(&self) -> Vec<u8> {
+		let mut buf = Vec::with_capacity(512);
+		put_bytes(&mut buf, b"spacedrive/proxy-pairing-complete");
+		buf.push(PROXY_COMPLETION_ENCODING_VERSION);
+		buf.extend_from_slice(self.session_id.as_bytes());
+		buf.extend_from_slice(self.voucher_device_id.as_bytes());
+
+		buf.extend_from_slice(&(self.accepted_by.len() as u32).to_be_bytes());
+		for accepted in self.accepted_by {
+			put_device_info(&mut buf, &accepted.device_info);
+		}
+
+		buf.extend_from_slice(&(self.rejected_by.len() as u32).to_be_bytes());
+		for rejected in self.rejected_by {
+			buf.extend_from_slice(rejected.device_id.as_bytes());
+			put_bytes(&mut buf, rejected.device_name.as_bytes());
+			put_bytes(&mut buf, rejected.reason.as_bytes());
+		}
+		buf
+	}
+}
+
+fn put_device_info(buf: &mut Vec<u8>, info: &DeviceInfo) {
+	buf.extend_from_slice(info.device_id.as_bytes());
+	put_bytes(buf, info.device_name.as_bytes());
+	put_bytes(buf, info.device_slug.as_bytes());
+	match &info.device_type {
+		DeviceType::Desktop => buf.push(0),
+		DeviceType::Laptop => buf.push(1),
+		DeviceType::Mobile => buf.push(2),
+		DeviceType::Server => buf.push(3),
+		DeviceType::Other(name) => {
+			buf.push(4);
+			put_bytes(buf, name.as_bytes());
+		}
+	}
+	put_bytes(buf, info.os_version.as_bytes());
+	put_bytes(buf, info.app_version.as_bytes());
+	put_bytes(buf, info.network_fingerprint.node_id.as_bytes());
+	put_bytes(buf, info.network_fingerprint.public_key_hash.as_bytes());
+	put_timestamp(buf, info.last_seen);
+}
+
+fn put_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+	buf.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+	buf.extend_from_slice(bytes);
+}
+
+fn put_timestamp(buf: &mut Vec<u8>, timestamp: DateTime<Utc>) {
+	buf.extend_from_slice(&timestamp.timestamp().to_be_bytes());
+	buf.extend_from_slice(&timestamp.timestamp_subsec_nanos().to_be_bytes());
+}
+
+/// Algorithm used to sign a vouch
+///
+/// The scheme is sent as the first byte of `voucher_signature` and is also
+/// covered by the signature itself, so a vouch cannot be replayed under a
+/// different scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SignatureScheme {
+	Ed25519 = 1,
+}
+
+/// Length of an untagged vouch signature
+///
+/// Vouches from builds before signature schemes were tagged carry a bare
+/// Ed25519 signature over `VouchPayload::canonical_bytes`. A tagged signature is
+/// one byte longer, so the two formats can't be confused.
+pub const LEGACY_VOUCH_SIGNATURE_LEN: usize = 64;
+
+/// A signature declared a scheme this build does not understand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownSignatureScheme(pub u8);
+
+impl std::fmt::Display for UnknownSignatureScheme {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Unsupported vouch signature scheme: {:#04x}", self.0)
+	}
+}
+
+impl TryFrom<u8> for SignatureScheme {
+	type Error = UnknownSignatureScheme;
+
+	fn try_from(value: u8) -> Result<Self, Self::Error> {
+		match value {
+			1 => Ok(Self::Ed25519),
+			other => Err(UnknownSignatureScheme(other)),
+		}
+	}
+}
+
+impl SignatureScheme {
+	/// Bytes that get signed under this scheme
+	pub fn signed_message(self, payload: &VouchPayload) -> Vec<u8> {
+		let mut message = vec![self as u8];
+		message.extend_from_slice(&payload.canonical_bytes());
+		message
+	}
+
+	/// Bytes that get signed for a proxy pairing completion under this scheme
+	pub fn signed_completion_message(self, payload: &ProxyCompletionPayload) -> Vec<u8> {
+		let mut message = vec![self as u8];
+		message.extend_from_slice(&payload.canonical_bytes());
+		message
+	}
+
+	/// Prefix a raw signature with this scheme's tag for the wire
+	pub fn encode_signature(self, signature: &[u8]) -> Vec<u8> {
+		let mut encoded = Vec::with_capacity(signature.len() + 1);
+		encoded.push(self as u8);
+		encoded.extend_from_slice(signature);
+		encoded
+	}
+
+	/// Split a wire signature into its declared scheme and raw signature bytes
+	pub fn decode_signature(encoded: &[u8]) -> Result<(Self, &[u8]), UnknownSignatureScheme> {
+		let (&tag, signature) = encoded.split_first().ok_or(UnknownSignatureScheme(0))?;
+		Ok((Self::try_from(tag)?, signature))
+	}
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AcceptedDevice {
 	pub device_info: DeviceInfo,
-	pub session_keys: SessionKeys,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +198,8 @@ pub struct VouchingSession {
 	pub vouchee_device_name: String,
 	pub voucher_device_id: Uuid,
 	pub created_at: DateTime<Utc>,
+	/// Deadline after which the session is finalized even if vouches are still pending
+	pub expires_at: DateTime<Utc>,
 	pub state: VouchingSessionState,
 	pub vouches: Vec<VouchState>,
 }
@@ -63,3 +229,237 @@ pub enum VouchStatus {
 	Rejected,
 	Unreachable,
 }
+
+/// Result of answering a proxy pairing confirmation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyConfirmationOutcome {
+	/// This call made the decision and acted on it
+	Applied,
+	/// The session was already decided, by the user or by the timeout; nothing changed
+	AlreadyConfirmed { accepted: bool },
+}
+
+/// Why a vouch timestamp fell outside the accepted window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VouchTimestampError {
+	/// Older than the configured signature max age
+	Expired,
+	/// Further ahead of our clock than the tolerated skew
+	InFuture,
+}
+
+impl std::fmt::Display for VouchTimestampError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Expired => write!(f, "Vouch signature expired"),
+			Self::InFuture => write!(f, "Vouch timestamp in future"),
+		}
+	}
+}
+
+/// Check a vouch timestamp against our clock
+///
+/// The age check alone lets a peer whose clock runs ahead produce vouches that
+/// stay valid long into the future, so timestamps ahead of `now` are only
+/// tolerated up to `max_future_skew`.
+pub fn validate_vouch_timestamp(
+	timestamp: DateTime<Utc>,
+	now: DateTime<Utc>,
+	max_age: chrono::Duration,
+	max_future_skew: chrono::Duration,
+) -> Result<(), VouchTimestampError> {
+	let age = now.signed_duration_since(timestamp);
+	if age > max_age {
+		return Err(VouchTimestampError::Expired);
+	}
+	if -age > max_future_skew {
+		return Err(VouchTimestampError::InFuture);
+	}
+	Ok(())
+}
+
+/// Walk the `vouched_by` links starting at `device_id`
+///
+/// Returns the devices that introduced `device_id` to us, nearest first. Stops at
+/// a directly paired device or at the first repeat, so a corrupted loop in the
+/// stored links cannot hang the walk.
+pub fn proxy_source_chain(
+	device_id: Uuid,
+	vouched_by: &std::collections::HashMap<Uuid, Uuid>,
+) -> Vec<Uuid> {
+	let mut chain = Vec::new();
+	let mut current = device_id;
+	while let Some(&voucher) = vouched_by.get(&current) {
+		if voucher == device_id || chain.contains(&voucher) {
+			break;
+		}
+		chain.push(voucher);
+		current = voucher;
+	}
+	chain
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::service::network::utils::identity::NetworkFingerprint;
+	use chrono::{Duration, TimeZone};
+
+	fn fixed_payload() -> VouchPayload {
+		VouchPayload {
+			vouchee_device_id: Uuid::from_u128(1),
+			vouchee_public_key: vec![0xaa, 0xbb],
+			vouchee_device_info: DeviceInfo {
+				device_id: Uuid::from_u128(1),
+				device_name: "a".to_string(),
+				device_slug: "b".to_string(),
+				device_type: DeviceType::Other("c".to_string()),
+				os_version: "d".to_string(),
+				app_version: "e".to_string(),
+				network_fingerprint: NetworkFingerprint {
+					node_id: "f".to_string(),
+					public_key_hash: "g".to_string(),
+				},
+				last_seen: Utc.timestamp_opt(0, 0).unwrap(),
+				version: DeviceInfo::CURRENT_VERSION,
+			},
+			timestamp: Utc.timestamp_opt(1_700_000_000, 5).unwrap(),
+			session_id: Uuid::from_u128(2),
+		}
+	}
+
+	#[test]
+	fn test_vouch_payload_canonical_bytes_are_pinned() {
+		#[rustfmt::skip]
+		let expected: Vec<u8> = vec![
+			// version
+			1,
+			// vouchee_device_id
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+			// vouchee_public_key
+			0, 0, 0, 2, 0xaa, 0xbb,
+			// device_info.device_id
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+			// device_name, device_slug
+			0, 0, 0, 1, b'a',
+			0, 0, 0, 1, b'b',
+			// device_type Other("c")
+			4, 0, 0, 0, 1, b'c',
+			// os_version, app_version
+			0, 0, 0, 1, b'd',
+			0, 0, 0, 1, b'e',
+			// network_fingerprint node_id, public_key_hash
+			0, 0, 0, 1, b'f',
+			0, 0, 0, 1, b'g',
+			// last_seen: 0s, 0ns
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+			// timestamp: 1_700_000_000s, 5ns
+			0, 0, 0, 0, 0x65, 0x53, 0xf1, 0x00, 0, 0, 0, 5,
+			// session_id
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,
+		];
+
+		assert_eq!(fixed_payload().canonical_bytes(), expected);
+	}
+
+	#[test]
+	fn test_vouch_payload_canonical_bytes_cover_device_info() {
+		let payload = fixed_payload();
+		let mut renamed = payload.clone();
+		renamed.vouchee_device_info.device_name = "z".to_string();
+		let mut retyped = payload.clone();
+		retyped.vouchee_device_info.device_type = DeviceType::Laptop;
+
+		assert_ne!(payload.canonical_bytes(), renamed.canonical_bytes());
+		assert_ne!(payload.canonical_bytes(), retyped.canonical_bytes());
+	}
+
+	#[test]
+	fn test_signature_scheme_round_trip() {
+		let encoded = SignatureScheme::Ed25519.encode_signature(&[7; 64]);
+		assert_eq!(encoded[0], 1);
+
+		let (scheme, signature) = SignatureScheme::decode_signature(&encoded).unwrap();
+		assert_eq!(scheme, SignatureScheme::Ed25519);
+		assert_eq!(signature, &[7; 64][..]);
+	}
+
+	#[test]
+	fn test_unknown_signature_scheme_rejected() {
+		let mut encoded = vec![0xfe];
+		encoded.extend_from_slice(&[7; 64]);
+
+		let err = SignatureScheme::decode_signature(&encoded).unwrap_err();
+		assert_eq!(err, UnknownSignatureScheme(0xfe));
+		assert_eq!(err.to_string(), "Unsupported vouch signature scheme: 0xfe");
+		assert!(SignatureScheme::decode_signature(&[]).is_err());
+	}
+
+	#[test]
+	fn test_signed_message_covers_scheme() {
+		let payload = fixed_payload();
+		let message = SignatureScheme::Ed25519.signed_message(&payload);
+
+		assert_eq!(message[0], SignatureScheme::Ed25519 as u8);
+		assert_eq!(&message[1..], &payload.canonical_bytes()[..]);
+	}
+
+	#[test]
+	fn test_vouch_timestamp_past_expiry() {
+		let now = Utc::now();
+		let result = validate_vouch_timestamp(
+			now - Duration::seconds(301),
+			now,
+			Duration::seconds(300),
+			Duration::seconds(300),
+		);
+		assert_eq!(result, Err(VouchTimestampError::Expired));
+	}
+
+	#[test]
+	fn test_vouch_timestamp_future_skew() {
+		let now = Utc::now();
+		let result = validate_vouch_timestamp(
+			now + Duration::seconds(301),
+			now,
+			Duration::seconds(300),
+			Duration::seconds(300),
+		);
+		assert_eq!(result, Err(VouchTimestampError::InFuture));
+		assert_eq!(
+			VouchTimestampError::InFuture.to_string(),
+			"Vouch timestamp in future"
+		);
+	}
+
+	#[test]
+	fn test_vouch_timestamp_acceptable() {
+		let now = Utc::now();
+		let max_age = Duration::seconds(300);
+		let skew = Duration::seconds(300);
+
+		assert!(validate_vouch_timestamp(now, now, max_age, skew).is_ok());
+		assert!(validate_vouch_timestamp(now - Duration::seconds(60), now, max_age, skew).is_ok());
+		assert!(validate_vouch_timestamp(now + Duration::seconds(60), now, max_age, skew).is_ok());
+	}
+
+	#[test]
+	fn test_proxy_source_chain() {
+		let direct = Uuid::new_v4();
+		let first = Uuid::new_v4();
+		let second = Uuid::new_v4();
+		let vouched_by = [(first, direct), (second, first)].into_iter().collect();
+
+		assert_eq!(proxy_source_chain(second, &vouched_by), vec![first, direct]);
+		assert!(proxy_source_chain(direct, &vouched_by).is_empty());
+	}
+
+	#[test]
+	fn test_proxy_source_chain_stops_on_loop() {
+		let a = Uuid::new_v4();
+		let b = Uuid::new_v4();
+		let vouched_by = [(a, b), (b, a)].into_iter().collect();
+
+		assert_eq!(proxy_source_chain(a, &vouched_by), vec![b]);
+	}
+}
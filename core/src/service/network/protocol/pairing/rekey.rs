@@ -0,0 +1,352 @@
+//! Session key rotation for paired devices
+//!
+//! The side starting the re-key sends a random nonce with a proof that it holds
+//! the current shared secret. The peer checks the proof, ratchets its keys over
+//! the nonce and answers with a proof made from the new secret, but keeps using
+//! its current keys. The starting side checks that proof against its own
+//! ratchet, switches, and sends a commit made from the new secret; only then
+//! does the peer switch too.
+//!
+//! Either of the last two messages can be lost, so each side keeps the other key
+//! set it might be out of step with as a fallback: the peer keeps the staged
+//! keys, the starting side the keys it replaced. A later re-key request proven
+//! with a device's fallback keys first moves back onto them, so the next re-key
+//! from either side brings both registries back in line.
+
+use super::{messages::PairingMessage, PairingProtocolHandler};
+use crate::service::network::{device::SessionKeys, NetworkingError, Result};
+use iroh::EndpointId;
+use uuid::Uuid;
+
+/// How long a re-key may wait for its confirmation before it is dropped
+const REKEY_TIMEOUT_MINUTES: i64 = 5;
+
+/// How long fallback keys are kept for a peer that may be out of step
+const REKEY_FALLBACK_HOURS: i64 = 24;
+
+/// A re-key this device started and the peer has not confirmed yet
+#[derive(Debug, Clone)]
+pub(crate) struct PendingRekey {
+	pub device_id: Uuid,
+	pub nonce: Vec<u8>,
+	pub started_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Keys the peer may still hold after an unfinished re-key
+#[derive(Debug, Clone)]
+pub(crate) struct RekeyFallback {
+	pub session_id: Uuid,
+	pub nonce: Vec<u8>,
+	pub keys: SessionKeys,
+	/// Keys the peer confirmed but has not committed, rather than keys we replaced
+	pub staged: bool,
+	pub since: chrono::DateTime<chrono::Utc>,
+}
+
+/// Compare two proofs without stopping at the first differing byte
+fn proofs_match(expected: &[u8], received: &[u8]) -> bool {
+	expected.len() == received.len()
+		&& expected
+			.iter()
+			.zip(received)
+			.fold(0u8, |diff, (a, b)| diff | (a ^ b))
+			== 0
+}
+
+/// Proof sent with the commit, kept apart from the confirmation proof so one
+/// cannot be replayed as the other
+fn commit_proof(keys: &SessionKeys, nonce: &[u8]) -> Vec<u8> {
+	keys.rekey_proof(&[nonce, b"commit".as_slice()].concat())
+}
+
+impl PairingProtocolHandler {
+	/// Rotate the session keys shared with a paired device
+	///
+	/// Returns the re-key session id. Over the network the confirmation arrives on
+	/// the same stream and is applied before this returns; without an endpoint the
+	/// request goes through the event loop and the confirmation is handled when it
+	/// comes back.
+	pub async fn start_rekey(&self, device_id: Uuid) -> Result<Uuid> {
+		use rand::RngCore;
+
+		let (session_keys, node_id) = {
+			let registry = self.device_registry.read().await;
+			let keys = registry
+				.get_session_keys(device_id)
+				.ok_or(NetworkingError::DeviceNotFound(device_id))?;
+			let node_id = registry
+				.get_node_id_for_device(device_id)
+				.ok_or(NetworkingError::DeviceNotFound(device_id))?;
+			(keys, node_id)
+		};
+
+		let session_id = Uuid::new_v4();
+		let mut nonce = vec![0u8; 32];
+		rand::thread_rng().fill_bytes(&mut nonce);
+
+		self.pending_rekeys.write().await.insert(
+			session_id,
+			PendingRekey {
+				device_id,
+				nonce: nonce.clone(),
+				started_at: chrono::Utc::now(),
+			},
+		);
+
+		let request = PairingMessage::RekeyRequest {
+			session_id,
+			proof: session_keys.rekey_proof(&nonce),
+			nonce,
+		};
+
+		self.log_info(&format!(
+			"Starting re-key {} with device {}",
+			session_id, device_id
+		))
+		.await;
+
+		let sent = match &self.endpoint {
+			Some(endpoint) => {
+				match self
+					.send_pairing_message_to_node(endpoint, node_id, &request)
+					.await
+				{
+					Ok(Some(PairingMessage::RekeyConfirm { session_id, proof })) => {
+						let commit = self
+							.handle_rekey_confirm(session_id, proof, node_id)
+							.await?;
+						// Our side has switched; a lost commit is recovered through the fallback
+						if let Err(e) = self
+							.send_pairing_message_to_node(endpoint, node_id, &commit)
+							.await
+						{
+							self.log_warn(&format!(
+								"Re-key {} commit to device {} failed: {}",
+								session_id, device_id, e
+							))
+							.await;
+						}
+						Ok(())
+					}
+					Ok(_) => Err(NetworkingError::Protocol(
+						"Expected RekeyConfirm in reply".to_string(),
+					)),
+					Err(e) => Err(e),
+				}
+			}
+			None => {
+				self.send_pairing_message_fire_and_forget(node_id, &request)
+					.await
+			}
+		};
+
+		if let Err(e) = sent {
+			self.pending_rekeys.write().await.remove(&session_id);
+			return Err(e);
+		}
+
+		Ok(session_id)
+	}
+
+	/// Peer asked to rotate keys: verify and confirm with the staged new keys
+	///
+	/// The current keys stay in place until the peer commits.
+	pub(crate) async fn handle_rekey_request(
+		&self,
+		session_id: Uuid,
+		nonce: Vec<u8>,
+		proof: Vec<u8>,
+		remote_node_id: EndpointId,
+	) -> Result<Vec<u8>> {
+		let confirm_proof = {
+			let mut registry = self.device_registry.write().await;
+			let device_id = registry.get_device_by_node(remote_node_id).ok_or_else(|| {
+				NetworkingError::AuthenticationFailed(format!(
+					"Re-key request from unpaired node {}",
+					remote_node_id
+				))
+			})?;
+			let mut current = registry.get_session_keys(device_id).ok_or_else(|| {
+				NetworkingError::AuthenticationFailed(format!(
+					"No session keys for device {}",
+					device_id
+				))
+			})?;
+
+			let mut fallbacks = self.rekey_fallbacks.write().await;
+			if !proofs_match(&current.rekey_proof(&nonce), &proof) {
+				// The peer may be on the keys an unfinished re-key left behind
+				let fallback = fallbacks
+					.get(&device_id)
+					.filter(|fallback| proofs_match(&fallback.keys.rekey_proof(&nonce), &proof))
+					.cloned()
+					.ok_or_else(|| {
+						NetworkingError::AuthenticationFailed(format!(
+							"Re-key {} from device {} has an invalid proof",
+							session_id, device_id
+						))
+					})?;
+				self.log_warn(&format!(
+					"Device {} is on the keys of re-key {}; switching to them",
+					device_id, fallback.session_id
+				))
+				.await;
+				registry
+					.replace_session_keys(device_id, fallback.keys.clone())
+					.await?;
+				current = fallback.keys;
+			}
+
+			let next = current.ratchet(&nonce);
+			let confirm_proof = next.rekey_proof(&nonce);
+			fallbacks.insert(
+				device_id,
+				RekeyFallback {
+					session_id,
+					nonce,
+					keys: next,
+					staged: true,
+					since: chrono::Utc::now(),
+				},
+			);
+			confirm_proof
+		};
+
+		self.log_info(&format!(
+			"Re-key {} staged for node {}",
+			session_id, remote_node_id
+		))
+		.await;
+
+		serde_json::to_vec(&PairingMessage::RekeyConfirm {
+			session_id,
+			proof: confirm_proof,
+		})
+		.map_err(NetworkingError::Serialization)
+	}
+
+	/// Peer staged the new keys: check they match ours, switch, and return the commit
+	///
+	/// The replaced keys are kept as the fallback in case the commit is lost.
+	pub(crate) async fn handle_rekey_confirm(
+		&self,
+		session_id: Uuid,
+		proof: Vec<u8>,
+		remote_node_id: EndpointId,
+	) -> Result<PairingMessage> {
+		let pending = self
+			.pending_rekeys
+			.write()
+			.await
+			.remove(&session_id)
+			.ok_or_else(|| {
+				NetworkingError::Protocol(format!("No pending re-key {}", session_id))
+			})?;
+
+		let mut registry = self.device_registry.write().await;
+		if registry.get_device_by_node(remote_node_id) != Some(pending.device_id) {
+			return Err(NetworkingError::AuthenticationFailed(format!(
+				"Re-key {} confirmed by unexpected node {}",
+				session_id, remote_node_id
+			)));
+		}
+
+		let current = registry
+			.get_session_keys(pending.device_id)
+			.ok_or(NetworkingError::DeviceNotFound(pending.device_id))?;
+		let next = current.ratchet(&pending.nonce);
+		if !proofs_match(&next.rekey_proof(&pending.nonce), &proof) {
+			return Err(NetworkingError::AuthenticationFailed(format!(
+				"Re-key {} confirmation does not match the derived keys",
+				session_id
+			)));
+		}
+
+		let commit = PairingMessage::RekeyCommit {
+			session_id,
+			proof: commit_proof(&next, &pending.nonce),
+		};
+		registry
+			.replace_session_keys(pending.device_id, next)
+			.await?;
+		self.rekey_fallbacks.write().await.insert(
+			pending.device_id,
+			RekeyFallback {
+				session_id,
+				nonce: pending.nonce,
+				keys: current,
+				staged: false,
+				since: chrono::Utc::now(),
+			},
+		);
+		drop(registry);
+
+		self.log_info(&format!(
+			"Re-key {} confirmed by device {}",
+			session_id, pending.device_id
+		))
+		.await;
+		Ok(commit)
+	}
+
+	/// Peer switched to the new keys: switch to the staged keys as well
+	pub(crate) async fn handle_rekey_commit(
+		&self,
+		session_id: Uuid,
+		proof: Vec<u8>,
+		remote_node_id: EndpointId,
+	) -> Result<()> {
+		let mut registry = self.device_registry.write().await;
+		let device_id = registry.get_device_by_node(remote_node_id).ok_or_else(|| {
+			NetworkingError::AuthenticationFailed(format!(
+				"Re-key commit from unpaired node {}",
+				remote_node_id
+			))
+		})?;
+
+		let mut fallbacks = self.rekey_fallbacks.write().await;
+		let staged = fallbacks
+			.get(&device_id)
+			.filter(|fallback| fallback.staged && fallback.session_id == session_id)
+			.ok_or_else(|| NetworkingError::Protocol(format!("No staged re-key {}", session_id)))?;
+		if !proofs_match(&commit_proof(&staged.keys, &staged.nonce), &proof) {
+			return Err(NetworkingError::AuthenticationFailed(format!(
+				"Re-key {} commit does not match the staged keys",
+				session_id
+			)));
+		}
+
+		let staged = fallbacks
+			.remove(&device_id)
+			.expect("staged re-key checked above");
+		registry
+			.replace_session_keys(device_id, staged.keys)
+			.await?;
+		drop(fallbacks);
+		drop(registry);
+
+		self.log_info(&format!(
+			"Re-key {} committed by device {}",
+			session_id, device_id
+		))
+		.await;
+		Ok(())
+	}
+
+	/// Drop re-keys whose confirmation never arrived and fallbacks past their
+	/// grace window, returning how many re-keys were removed
+	pub(crate) async fn cleanup_expired_rekeys(&self) -> usize {
+		let now = chrono::Utc::now();
+		let fallback_cutoff = now - chrono::Duration::hours(REKEY_FALLBACK_HOURS);
+		self.rekey_fallbacks
+			.write()
+			.await
+			.retain(|_, fallback| fallback.since > fallback_cutoff);
+
+		let cutoff = now - chrono::Duration::minutes(REKEY_TIMEOUT_MINUTES);
+		let mut pending = self.pending_rekeys.write().await;
+		let before = pending.len();
+		pending.retain(|_, rekey| rekey.started_at > cutoff);
+		before - pending.len()
+	}
+}
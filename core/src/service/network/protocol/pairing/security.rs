@@ -94,6 +94,24 @@ impl PairingSecurity {
 
 		Ok(())
 	}
+
+	/// Derive the shared secret for a pairing session from the pairing code secret
+	///
+	/// The session ID is the HKDF info, so a code only produces a working secret for
+	/// the session it was generated for. Both sides must derive it the same way.
+	pub fn derive_session_secret(code_secret: &[u8], session_id: uuid::Uuid) -> Result<Vec<u8>> {
+		use hkdf::Hkdf;
+		use sha2::Sha256;
+
+		let hkdf = Hkdf::<Sha256>::new(None, code_secret);
+		let mut derived = [0u8; 32];
+		hkdf.expand(session_id.as_bytes(), &mut derived)
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to derive session secret: {}", e))
+			})?;
+
+		Ok(derived.to_vec())
+	}
 }
 
 #[cfg(test)]
@@ -192,4 +210,22 @@ mod tests {
 		assert!(result.is_ok());
 		assert!(!result.unwrap()); // Should be false
 	}
+	#[test]
+	fn test_session_secret_bound_to_session() {
+		let code_secret = [7u8; 32];
+		let session_a = uuid::Uuid::new_v4();
+		let session_b = uuid::Uuid::new_v4();
+
+		let secret_a = PairingSecurity::derive_session_secret(&code_secret, session_a).unwrap();
+		let secret_b = PairingSecurity::derive_session_secret(&code_secret, session_b).unwrap();
+
+		assert_eq!(secret_a.len(), 32);
+		assert_ne!(secret_a, secret_b);
+		assert_ne!(secret_a.as_slice(), code_secret.as_slice());
+		// Both sides derive the same secret for the same session
+		assert_eq!(
+			secret_a,
+			PairingSecurity::derive_session_secret(&code_secret, session_a).unwrap()
+		);
+	}
 }
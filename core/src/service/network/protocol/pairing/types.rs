@@ -1,7 +1,7 @@
 //! Pairing protocol types and state definitions
 
 use crate::service::network::{
-	device::{DeviceInfo, SessionKeys},
+	device::{DeviceInfo, SessionKeys, TrustLevel},
 	utils::identity::NetworkFingerprint,
 };
 use chrono::{DateTime, Utc};
@@ -9,6 +9,49 @@ use iroh::{EndpointAddr, EndpointId};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Default minimum estimated entropy (in bits) a pairing code secret must carry
+pub const DEFAULT_MIN_ENTROPY_BITS: u32 = 96;
+
+/// Most unfinished pairing sessions a single remote device may hold at once
+pub const MAX_OPEN_SESSIONS_PER_PEER: usize = 3;
+
+/// What was cleaned up when an established pairing was forgotten
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnpairedDevice {
+	/// Whether the device had a persisted pairing record
+	pub was_paired: bool,
+	/// Queued vouches addressed to or vouching for the device that were dropped
+	pub dropped_vouches: u64,
+}
+
+/// Result of changing a paired device's trust level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceTrustChange {
+	pub previous: TrustLevel,
+	pub current: TrustLevel,
+	/// Whether the device is now accepted as a voucher for proxy pairing
+	pub can_vouch: bool,
+	/// Queued vouches addressed to or vouching for the device that were dropped
+	pub dropped_vouches: u64,
+}
+
+/// Queued vouches removed by an operator prune
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrunedVouches {
+	/// Vouches past their expiry
+	pub expired: u64,
+	/// Vouches of the requested vouching session
+	pub for_session: u64,
+	/// Vouches addressed to the requested target device
+	pub for_target: u64,
+}
+
+impl PrunedVouches {
+	pub fn total(&self) -> u64 {
+		self.expired + self.for_session + self.for_target
+	}
+}
+
 /// Human-readable pairing code using BIP39 mnemonic words
 #[derive(Debug, Clone)]
 pub struct PairingCode {
@@ -26,6 +69,12 @@ pub struct PairingCode {
 
 	/// Initiator's EndpointId for remote discovery via pkarr (optional - enables relay path)
 	node_id: Option<EndpointId>,
+
+	/// Whether only the first joiner may use this code
+	single_use: bool,
+
+	/// Number of distinct joiners that have presented this code
+	uses: u32,
 }
 
 impl PairingCode {
@@ -33,9 +82,31 @@ impl PairingCode {
 	pub fn generate() -> crate::service::network::Result<Self> {
 		use rand::RngCore;
 
-		// Generate 16 bytes of entropy (enough for 12 BIP39 words)
-		let mut entropy = [0u8; 16];
-		rand::thread_rng().fill_bytes(&mut entropy);
+		// Generate 16 bytes of entropy (enough for 12 BIP39 words), drawing again in the
+		// vanishingly rare case the sample looks degenerate
+		loop {
+			let mut entropy = [0u8; 16];
+			rand::thread_rng().fill_bytes(&mut entropy);
+
+			if Self::estimate_entropy_bits(&entropy) >= DEFAULT_MIN_ENTROPY_BITS {
+				return Self::from_entropy(entropy);
+			}
+		}
+	}
+
+	/// Build a pairing code from caller-supplied entropy
+	///
+	/// The session ID is derived from the entropy, so the same entropy always
+	/// yields the same words and session ID. Used for scripted pairing where the
+	/// session must be known up front.
+	pub fn from_entropy(entropy: [u8; 16]) -> crate::service::network::Result<Self> {
+		let estimated = Self::estimate_entropy_bits(&entropy);
+		if estimated < DEFAULT_MIN_ENTROPY_BITS {
+			return Err(crate::service::network::NetworkingError::Protocol(format!(
+				"Pairing secret is too weak: ~{} bits of entropy, need at least {}",
+				estimated, DEFAULT_MIN_ENTROPY_BITS
+			)));
+		}
 
 		// Derive the full 32-byte secret deterministically from the entropy
 		// This ensures the initiator and joiner have the same secret after BIP39 round-trip
@@ -61,6 +132,8 @@ impl PairingCode {
 			session_id,
 			expires_at: Utc::now() + chrono::Duration::minutes(5),
 			node_id: None,
+			single_use: true,
+			uses: 0,
 		})
 	}
 
@@ -70,6 +143,15 @@ impl PairingCode {
 		self
 	}
 
+	/// Let any number of joiners pair with this code until it expires
+	///
+	/// Codes are single-use by default, so a code seen by a third party cannot be
+	/// used to pair alongside the intended device.
+	pub fn multi_use(mut self) -> Self {
+		self.single_use = false;
+		self
+	}
+
 	/// Parse a pairing code from a BIP39 mnemonic string (for local pairing)
 	pub fn from_string(code: &str) -> crate::service::network::Result<Self> {
 		// Trim the input and normalize whitespace
@@ -151,6 +233,8 @@ impl PairingCode {
 			session_id,
 			expires_at: Utc::now() + chrono::Duration::minutes(5),
 			node_id: None,
+			single_use: true,
+			uses: 0,
 		})
 	}
 
@@ -190,6 +274,75 @@ impl PairingCode {
 		Utc::now() > self.expires_at
 	}
 
+	/// Whether only the first joiner may use this code
+	pub fn is_single_use(&self) -> bool {
+		self.single_use
+	}
+
+	/// Number of distinct joiners that have presented this code
+	pub fn uses(&self) -> u32 {
+		self.uses
+	}
+
+	/// Count a new joiner against this code
+	///
+	/// Fails without counting if the code is single-use and already consumed.
+	pub fn record_use(&mut self) -> crate::service::network::Result<()> {
+		if self.single_use && self.uses > 0 {
+			return Err(crate::service::network::NetworkingError::Protocol(format!(
+				"Pairing code for session {} has already been used",
+				self.session_id
+			)));
+		}
+		self.uses += 1;
+		Ok(())
+	}
+
+	/// Conservative estimate of the entropy carried by this code's secret, in bits
+	pub fn entropy_bits(&self) -> u32 {
+		Self::estimate_entropy_bits(&self.secret[..16])
+	}
+
+	/// Reject codes whose secret carries less than `min_bits` of estimated entropy
+	pub fn validate_entropy(&self, min_bits: u32) -> crate::service::network::Result<()> {
+		let estimated = self.entropy_bits();
+		if estimated < min_bits {
+			return Err(crate::service::network::NetworkingError::Protocol(format!(
+				"Pairing code is too weak: ~{} bits of entropy, need at least {}",
+				estimated, min_bits
+			)));
+		}
+		Ok(())
+	}
+
+	/// Estimate the entropy of the raw code entropy bytes
+	///
+	/// The format caps the strength: 12 words from the 2048-word BIP39 list carry
+	/// 132 bits, 4 of which are checksum. A random secret keeps close to that, but
+	/// one built from a pattern is scored by how little it takes to describe: 8 bits
+	/// per distinct byte value catches repeats, and 8 bits per distinct step between
+	/// neighbouring bytes catches counters and ramps such as `0, 1, 2, ...`. Only
+	/// the first 16 bytes count since the rest of the secret is derived from them.
+	fn estimate_entropy_bits(entropy: &[u8]) -> u32 {
+		const WORDS: u32 = 12;
+		const BITS_PER_WORD: u32 = 11;
+		const CHECKSUM_BITS: u32 = 4;
+
+		fn distinct(values: impl Iterator<Item = u8>) -> u32 {
+			let mut seen = [false; 256];
+			values
+				.filter(|&value| !std::mem::replace(&mut seen[value as usize], true))
+				.count() as u32
+		}
+
+		let nominal = (WORDS * BITS_PER_WORD - CHECKSUM_BITS).min(entropy.len() as u32 * 8);
+		let values = distinct(entropy.iter().copied());
+		// The first byte fixes where the steps start from
+		let steps = distinct(entropy.windows(2).map(|pair| pair[1].wrapping_sub(pair[0]))) + 1;
+
+		nominal.min(values * 8).min(steps * 8)
+	}
+
 	/// Encode bytes to BIP39 words using proper mnemonic generation
 	fn encode_to_bip39_words(secret: &[u8; 32]) -> crate::service::network::Result<[String; 12]> {
 		use bip39::{Language, Mnemonic};
@@ -305,6 +458,10 @@ pub struct PairingSession {
 	pub remote_public_key: Option<Vec<u8>>,
 	pub shared_secret: Option<Vec<u8>>,
 	pub created_at: DateTime<Utc>,
+	/// The direct dial failed and the session is running over the relay
+	pub via_relay: bool,
+	/// The requester is on the auto-accept allowlist, so no confirmation is needed
+	pub auto_accepted: bool,
 }
 
 impl std::fmt::Display for PairingSession {
@@ -345,8 +502,167 @@ pub enum PairingState {
 	ResponseSent,
 	Completed,
 	Failed {
-		reason: String,
+		reason: PairingFailureReason,
+		detail: Option<String>,
+	},
+}
+
+impl PairingState {
+	/// Build a `Failed` state with no extra detail
+	pub fn failed(reason: PairingFailureReason) -> Self {
+		PairingState::Failed {
+			reason,
+			detail: None,
+		}
+	}
+
+	/// Build a `Failed` state carrying a free-form detail message
+	pub fn failed_with(reason: PairingFailureReason, detail: impl Into<String>) -> Self {
+		PairingState::Failed {
+			reason,
+			detail: Some(detail.into()),
+		}
+	}
+}
+
+/// Structured cause of a failed pairing session
+///
+/// Lets clients branch on and localize failures; any free-form context goes in
+/// the `detail` field of `PairingState::Failed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub enum PairingFailureReason {
+	/// A step of the protocol did not complete in time
+	Timeout,
+	/// The internal command channel to the event loop was closed
+	ChannelClosed,
+	/// The internal command channel to the event loop stayed full
+	ChannelFull,
+	/// Sending to or receiving from the remote device failed
+	TransportError,
+	/// The remote device's challenge signature did not verify
+	SignatureInvalid,
+	/// The remote device rejected the pairing
+	RemoteRejected,
+	/// The remote device sent an unexpected or malformed message
+	ProtocolError,
+	/// Local state needed to continue was missing (no endpoint, no node ID)
+	InternalError,
+	/// Unrecognized reason (e.g. persisted by an older version)
+	#[serde(other)]
+	Unknown,
+}
+
+impl std::fmt::Display for PairingFailureReason {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let text = match self {
+			Self::Timeout => "Timed out",
+			Self::ChannelClosed => "Internal channel closed",
+			Self::ChannelFull => "Internal channel full",
+			Self::TransportError => "Transport error",
+			Self::SignatureInvalid => "Invalid signature",
+			Self::RemoteRejected => "Rejected by remote device",
+			Self::ProtocolError => "Protocol error",
+			Self::InternalError => "Internal error",
+			Self::Unknown => "Unknown error",
+		};
+		write!(f, "{}", text)
+	}
+}
+
+impl From<&crate::service::network::NetworkingError> for PairingFailureReason {
+	fn from(error: &crate::service::network::NetworkingError) -> Self {
+		use crate::service::network::NetworkingError;
+		match error {
+			NetworkingError::Timeout(_) => Self::Timeout,
+			NetworkingError::ConnectionFailed(_)
+			| NetworkingError::Transport(_)
+			| NetworkingError::Io(_) => Self::TransportError,
+			NetworkingError::AuthenticationFailed(_) => Self::SignatureInvalid,
+			NetworkingError::DeviceNotFound(_) => Self::InternalError,
+			_ => Self::ProtocolError,
+		}
+	}
+}
+
+/// How a joiner's attempt to reach the initiator ended
+///
+/// Lets the UI tell a missing initiator apart from one that turned the code
+/// down, instead of leaving the joiner stuck in `Scanning`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub enum JoinOutcome {
+	/// The initiator took the pairing request and the session moved past scanning
+	Accepted,
+	/// Neither discovery path reached the initiator
+	InitiatorUnreachable { detail: String },
+	/// The initiator answered but refused the code (cancelled, expired or already used)
+	CodeRejected {
+		reason: PairingFailureReason,
+		detail: Option<String>,
 	},
+	/// Still scanning when the join timeout ran out
+	TimedOut,
+}
+
+impl JoinOutcome {
+	/// Classify a join attempt from its result and the session state it left behind
+	///
+	/// `attempt` is None when the join timeout elapsed before the initiator
+	/// answered; only then, or when the session itself timed out, is the outcome
+	/// `TimedOut`. A failed session wins over the attempt's own error, since it
+	/// carries what the initiator told us.
+	pub fn classify(
+		attempt: Option<&crate::service::network::Result<()>>,
+		state: Option<&PairingState>,
+	) -> Self {
+		match (state, attempt) {
+			(
+				Some(PairingState::Failed {
+					reason: PairingFailureReason::Timeout,
+					..
+				}),
+				_,
+			) => Self::TimedOut,
+			(Some(PairingState::Failed { reason, detail }), _) => Self::CodeRejected {
+				reason: *reason,
+				detail: detail.clone(),
+			},
+			(_, None) => Self::TimedOut,
+			(_, Some(Err(e))) => Self::InitiatorUnreachable {
+				detail: e.to_string(),
+			},
+			(Some(PairingState::Scanning) | None, Some(Ok(()))) => Self::InitiatorUnreachable {
+				detail: "Pairing session ended before the initiator answered".to_string(),
+			},
+			(Some(_), Some(Ok(()))) => Self::Accepted,
+		}
+	}
+}
+
+impl std::fmt::Display for JoinOutcome {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Accepted => write!(f, "Pairing request accepted"),
+			Self::InitiatorUnreachable { detail } => {
+				write!(f, "Could not reach the initiating device: {}", detail)
+			}
+			Self::CodeRejected {
+				detail: Some(detail),
+				..
+			} => write!(
+				f,
+				"Pairing code rejected by the initiating device: {}",
+				detail
+			),
+			Self::CodeRejected { reason, .. } => {
+				write!(
+					f,
+					"Pairing code rejected by the initiating device: {}",
+					reason
+				)
+			}
+			Self::TimedOut => write!(f, "Timed out waiting for the initiating device"),
+		}
+	}
 }
 
 impl std::fmt::Display for PairingState {
@@ -401,7 +717,7 @@ impl std::fmt::Display for PairingState {
 }
 
 /// Role in the pairing process
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
 pub enum PairingRole {
 	Initiator,
 	Joiner,
@@ -423,7 +739,7 @@ pub struct PairingAdvertisement {
 }
 
 /// Serializable representation of EndpointAddr
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct EndpointAddrInfo {
 	/// Node ID as string
 	pub node_id: String,
@@ -433,6 +749,22 @@ pub struct EndpointAddrInfo {
 	pub relay_url: Option<String>,
 }
 
+impl EndpointAddrInfo {
+	/// Snapshot of an endpoint's address as it is right now
+	pub fn from_endpoint_addr(addr: &EndpointAddr) -> Self {
+		Self {
+			node_id: addr.id.to_string(),
+			direct_addresses: addr.ip_addrs().map(|a| a.to_string()).collect(),
+			relay_url: addr.relay_urls().next().map(|url| url.to_string()),
+		}
+	}
+
+	/// Whether there is any way to reach the node besides discovery
+	pub fn is_routable(&self) -> bool {
+		!self.direct_addresses.is_empty() || self.relay_url.is_some()
+	}
+}
+
 impl PairingAdvertisement {
 	/// Convert node ID string back to EndpointId
 	pub fn node_id(&self) -> crate::service::network::Result<EndpointId> {
@@ -466,3 +798,118 @@ impl PairingAdvertisement {
 		Ok(node_addr)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_generated_code_meets_min_entropy() {
+		let code = PairingCode::generate().unwrap();
+		assert!(code.entropy_bits() >= DEFAULT_MIN_ENTROPY_BITS);
+		assert!(code.validate_entropy(DEFAULT_MIN_ENTROPY_BITS).is_ok());
+
+		// Round-tripping through the words preserves the strength
+		let parsed = PairingCode::from_string(&code.to_string()).unwrap();
+		assert_eq!(parsed.entropy_bits(), code.entropy_bits());
+	}
+
+	#[test]
+	fn test_weak_code_rejected() {
+		assert!(PairingCode::from_entropy([0u8; 16]).is_err());
+		assert!(
+			PairingCode::from_entropy([1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2]).is_err()
+		);
+
+		// Sequences have all distinct bytes but only one step between them
+		let ascending: [u8; 16] = std::array::from_fn(|i| i as u8);
+		let descending: [u8; 16] = std::array::from_fn(|i| 0xF0 - i as u8);
+		let stepped: [u8; 16] = std::array::from_fn(|i| 0x10 + 3 * i as u8);
+		for sequence in [ascending, descending, stepped] {
+			assert!(PairingCode::estimate_entropy_bits(&sequence) < DEFAULT_MIN_ENTROPY_BITS);
+			assert!(PairingCode::from_entropy(sequence).is_err());
+		}
+
+		// A weak code that arrives as words is still flagged
+		let words = PairingCode::encode_to_bip39_words(&[0u8; 32]).unwrap();
+		let weak = PairingCode::from_words(&words).unwrap();
+		assert!(weak.entropy_bits() < DEFAULT_MIN_ENTROPY_BITS);
+		assert!(weak.validate_entropy(DEFAULT_MIN_ENTROPY_BITS).is_err());
+	}
+
+	#[test]
+	fn test_join_outcome_initiator_unreachable() {
+		let attempt = Err(crate::service::network::NetworkingError::Protocol(
+			"Failed to discover initiator on local network".to_string(),
+		));
+		let outcome = JoinOutcome::classify(Some(&attempt), Some(&PairingState::Scanning));
+		assert!(matches!(outcome, JoinOutcome::InitiatorUnreachable { .. }));
+	}
+
+	#[test]
+	fn test_join_outcome_code_rejected() {
+		// Complete(success=false) from the initiator
+		let rejected = PairingState::failed_with(
+			PairingFailureReason::RemoteRejected,
+			"Pairing code for session has already been used",
+		);
+		let outcome = JoinOutcome::classify(Some(&Ok(())), Some(&rejected));
+		assert_eq!(
+			outcome,
+			JoinOutcome::CodeRejected {
+				reason: PairingFailureReason::RemoteRejected,
+				detail: Some("Pairing code for session has already been used".to_string()),
+			}
+		);
+
+		// An Error reply for an expired session fails the join but keeps the reason
+		let expired = PairingState::failed_with(
+			PairingFailureReason::ProtocolError,
+			"Pairing session expired",
+		);
+		let attempt = Err(crate::service::network::NetworkingError::Protocol(
+			"Peer reported Protocol error: Pairing session expired".to_string(),
+		));
+		assert!(matches!(
+			JoinOutcome::classify(Some(&attempt), Some(&expired)),
+			JoinOutcome::CodeRejected {
+				reason: PairingFailureReason::ProtocolError,
+				..
+			}
+		));
+	}
+
+	#[test]
+	fn test_join_outcome_timed_out_scanning() {
+		assert_eq!(
+			JoinOutcome::classify(None, Some(&PairingState::Scanning)),
+			JoinOutcome::TimedOut
+		);
+		// A join abandoned on timeout
+		assert_eq!(
+			JoinOutcome::classify(
+				None,
+				Some(&PairingState::failed(PairingFailureReason::Timeout))
+			),
+			JoinOutcome::TimedOut
+		);
+
+		// Still scanning, but the timeout never fired
+		assert!(matches!(
+			JoinOutcome::classify(Some(&Ok(())), Some(&PairingState::Scanning)),
+			JoinOutcome::InitiatorUnreachable { .. }
+		));
+		assert!(matches!(
+			JoinOutcome::classify(Some(&Ok(())), None),
+			JoinOutcome::InitiatorUnreachable { .. }
+		));
+	}
+
+	#[test]
+	fn test_join_outcome_accepted() {
+		assert_eq!(
+			JoinOutcome::classify(Some(&Ok(())), Some(&PairingState::ResponseSent)),
+			JoinOutcome::Accepted
+		);
+	}
+}
@@ -5,10 +5,7 @@ use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, Statemen
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::service::network::{
-	device::{DeviceInfo, SessionKeys},
-	NetworkingError, Result,
-};
+use crate::service::network::{device::DeviceInfo, NetworkingError, Result};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum VouchQueueStatus {
@@ -41,33 +38,96 @@ pub struct VouchingQueueEntry {
 	pub vouchee_device_info: DeviceInfo,
 	pub vouchee_public_key: Vec<u8>,
 	pub voucher_signature: Vec<u8>,
-	pub proxied_session_keys: SessionKeys,
 	pub created_at: DateTime<Utc>,
 	pub expires_at: DateTime<Utc>,
 	pub status: VouchQueueStatus,
 	pub retry_count: u32,
 	pub last_attempt_at: Option<DateTime<Utc>>,
+	/// Why the most recent delivery attempt failed, cleared once a send succeeds
+	pub last_attempt_error: Option<String>,
 }
 
+/// Consecutive rejections a target has sent for one voucher's vouches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RejectionStreak {
+	pub consecutive: u32,
+	pub last_rejected_at: DateTime<Utc>,
+}
+
+impl RejectionStreak {
+	/// Whether vouching to the target is paused at `now`
+	///
+	/// A streak at or past `threshold` pauses vouching until `cooldown` has passed
+	/// since the last rejection. A zero threshold never pauses.
+	pub fn is_paused(
+		&self,
+		threshold: u32,
+		cooldown: chrono::Duration,
+		now: DateTime<Utc>,
+	) -> bool {
+		threshold > 0 && self.consecutive >= threshold && now < self.last_rejected_at + cooldown
+	}
+}
+
+/// A decision on a vouch received for proxy pairing
+///
+/// Kept so a security review can tell whether a person approved a proxied
+/// device or the auto-accept policy let it in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct ProxyPairingAuditEntry {
+	pub session_id: Uuid,
+	pub voucher_device_id: Uuid,
+	pub vouchee_device_id: Uuid,
+	pub vouchee_device_name: String,
+	pub accepted: bool,
+	/// Whether the user made the decision rather than the auto-accept policy
+	pub confirmed_by_user: bool,
+	/// Why the vouch was refused when policy rather than the user rejected it
+	pub reason: Option<String>,
+	pub decided_at: DateTime<Utc>,
+}
+
+/// Columns selected when reading queue entries
+const ENTRY_COLUMNS: &str = "session_id, target_device_id, voucher_device_id, vouchee_device_id, \
+	vouchee_device_info, vouchee_public_key, voucher_signature, created_at, \
+	expires_at, status, retry_count, last_attempt_at, last_attempt_error";
+
+/// Columns added to `vouching_queue` after it was first created, in order
+///
+/// A queue's `user_version` is the number of steps already applied to it, so
+/// steps are only ever appended, never reordered or removed.
+const QUEUE_MIGRATIONS: &[(&str, &str)] = &[("last_attempt_error", "TEXT")];
+
 pub struct VouchingQueue {
 	conn: DatabaseConnection,
 }
 
 impl VouchingQueue {
+	/// Open the queue at its default location, `<data_dir>/networking/vouching_queue.db`
 	pub async fn open(data_dir: impl AsRef<Path>) -> Result<Self> {
-		let networking_dir = data_dir.as_ref().join("networking");
-		// Ensure networking directory exists
-		tokio::fs::create_dir_all(&networking_dir)
-			.await
-			.map_err(|e| {
-				NetworkingError::Protocol(format!("Failed to create networking directory: {}", e))
+		Self::open_at(
+			data_dir
+				.as_ref()
+				.join("networking")
+				.join("vouching_queue.db"),
+		)
+		.await
+	}
+
+	/// Open the queue stored in the database file at `db_path`
+	///
+	/// The parent directory is created if it is missing.
+	pub async fn open_at(db_path: impl AsRef<Path>) -> Result<Self> {
+		let db_path = db_path.as_ref();
+		if let Some(parent) = db_path.parent() {
+			tokio::fs::create_dir_all(parent).await.map_err(|e| {
+				NetworkingError::Protocol(format!(
+					"Failed to create vouching queue directory: {}",
+					e
+				))
 			})?;
-		// Ensure networking directory exists
-		std::fs::create_dir_all(&networking_dir).map_err(|e| {
-			NetworkingError::Protocol(format!("Failed to create networking directory: {}", e))
-		})?;
+		}
 
-		let db_path = networking_dir.join("vouching_queue.db");
 		let database_url = format!("sqlite://{}?mode=rwc", db_path.display());
 		let conn = Database::connect(&database_url).await.map_err(|e| {
 			NetworkingError::Protocol(format!("Failed to open vouching queue: {}", e))
@@ -116,6 +176,49 @@ impl VouchingQueue {
 			NetworkingError::Protocol(format!("Failed to create vouching queue: {}", e))
 		})?;
 
+		Self::migrate(conn).await?;
+
+		conn.execute(Statement::from_string(
+			DbBackend::Sqlite,
+			r#"
+			CREATE TABLE IF NOT EXISTS vouch_rejections (
+				voucher_device_id TEXT NOT NULL,
+				target_device_id TEXT NOT NULL,
+				consecutive INTEGER NOT NULL,
+				last_rejected_at TEXT NOT NULL,
+
+				PRIMARY KEY(voucher_device_id, target_device_id)
+			)
+			"#
+			.to_string(),
+		))
+		.await
+		.map_err(|e| {
+			NetworkingError::Protocol(format!("Failed to create vouch rejections: {}", e))
+		})?;
+
+		conn.execute(Statement::from_string(
+			DbBackend::Sqlite,
+			r#"
+			CREATE TABLE IF NOT EXISTS proxy_pairing_audit (
+				id INTEGER PRIMARY KEY AUTOINCREMENT,
+				session_id TEXT NOT NULL,
+				voucher_device_id TEXT NOT NULL,
+				vouchee_device_id TEXT NOT NULL,
+				vouchee_device_name TEXT NOT NULL,
+				accepted INTEGER NOT NULL,
+				confirmed_by_user INTEGER NOT NULL,
+				reason TEXT,
+				decided_at TEXT NOT NULL
+			)
+			"#
+			.to_string(),
+		))
+		.await
+		.map_err(|e| {
+			NetworkingError::Protocol(format!("Failed to create proxy pairing audit: {}", e))
+		})?;
+
 		conn.execute(Statement::from_string(
 			DbBackend::Sqlite,
 			"CREATE INDEX IF NOT EXISTS idx_vouching_queue_target ON vouching_queue(target_device_id)"
@@ -135,6 +238,71 @@ impl VouchingQueue {
 		Ok(())
 	}
 
+	/// Apply the `QUEUE_MIGRATIONS` steps this queue hasn't had yet
+	async fn migrate(conn: &DatabaseConnection) -> Result<()> {
+		let applied = Self::schema_version(conn).await? as usize;
+		for (index, (column, definition)) in QUEUE_MIGRATIONS.iter().enumerate().skip(applied) {
+			// Older builds added columns without recording a version
+			if !Self::has_column(conn, column).await? {
+				conn.execute(Statement::from_string(
+					DbBackend::Sqlite,
+					format!(
+						"ALTER TABLE vouching_queue ADD COLUMN {} {}",
+						column, definition
+					),
+				))
+				.await
+				.map_err(|e| {
+					NetworkingError::Protocol(format!("Failed to migrate vouching queue: {}", e))
+				})?;
+			}
+
+			conn.execute(Statement::from_string(
+				DbBackend::Sqlite,
+				format!("PRAGMA user_version = {}", index + 1),
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to migrate vouching queue: {}", e))
+			})?;
+		}
+
+		Ok(())
+	}
+
+	async fn schema_version(conn: &DatabaseConnection) -> Result<u32> {
+		let row = conn
+			.query_one(Statement::from_string(
+				DbBackend::Sqlite,
+				"PRAGMA user_version".to_string(),
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to inspect vouching queue: {}", e))
+			})?;
+
+		Ok(row
+			.and_then(|row| row.try_get::<i32>("", "user_version").ok())
+			.unwrap_or(0)
+			.max(0) as u32)
+	}
+
+	async fn has_column(conn: &DatabaseConnection, column: &str) -> Result<bool> {
+		let rows = conn
+			.query_all(Statement::from_string(
+				DbBackend::Sqlite,
+				"PRAGMA table_info(vouching_queue)".to_string(),
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to inspect vouching queue: {}", e))
+			})?;
+
+		Ok(rows
+			.iter()
+			.any(|row| row.try_get::<String>("", "name").ok().as_deref() == Some(column)))
+	}
+
 	pub async fn upsert_entry(&self, entry: &VouchingQueueEntry) -> Result<()> {
 		self.conn
 			.execute(Statement::from_sql_and_values(
@@ -153,9 +321,10 @@ impl VouchingQueue {
 					expires_at,
 					status,
 					retry_count,
-					last_attempt_at
+					last_attempt_at,
+					last_attempt_error
 				)
-				VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+				VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
 				ON CONFLICT(session_id, target_device_id) DO UPDATE SET
 					voucher_device_id = excluded.voucher_device_id,
 					vouchee_device_id = excluded.vouchee_device_id,
@@ -167,7 +336,8 @@ impl VouchingQueue {
 					expires_at = excluded.expires_at,
 					status = excluded.status,
 					retry_count = excluded.retry_count,
-					last_attempt_at = excluded.last_attempt_at
+					last_attempt_at = excluded.last_attempt_at,
+					last_attempt_error = excluded.last_attempt_error
 				"#,
 				vec![
 					entry.session_id.to_string().into(),
@@ -177,7 +347,9 @@ impl VouchingQueue {
 					Self::serialize(&entry.vouchee_device_info)?.into(),
 					entry.vouchee_public_key.clone().into(),
 					entry.voucher_signature.clone().into(),
-					Self::serialize(&entry.proxied_session_keys)?.into(),
+					// Session keys are no longer sent with a vouch; the column stays
+					// because it is NOT NULL in existing queues
+					"".into(),
 					entry.created_at.to_rfc3339().into(),
 					entry.expires_at.to_rfc3339().into(),
 					entry.status.as_str().into(),
@@ -187,6 +359,7 @@ impl VouchingQueue {
 						.map(|ts| ts.to_rfc3339())
 						.unwrap_or_default()
 						.into(),
+					entry.last_attempt_error.clone().into(),
 				],
 			))
 			.await
@@ -196,19 +369,33 @@ impl VouchingQueue {
 	}
 
 	pub async fn list_entries(&self) -> Result<Vec<VouchingQueueEntry>> {
+		self.query_entries(Statement::from_string(
+			DbBackend::Sqlite,
+			format!("SELECT {} FROM vouching_queue", ENTRY_COLUMNS),
+		))
+		.await
+	}
+
+	/// List the queued vouches belonging to a single vouching session
+	pub async fn list_entries_for_session(
+		&self,
+		session_id: Uuid,
+	) -> Result<Vec<VouchingQueueEntry>> {
+		self.query_entries(Statement::from_sql_and_values(
+			DbBackend::Sqlite,
+			format!(
+				"SELECT {} FROM vouching_queue WHERE session_id = ?",
+				ENTRY_COLUMNS
+			),
+			vec![session_id.to_string().into()],
+		))
+		.await
+	}
+
+	async fn query_entries(&self, statement: Statement) -> Result<Vec<VouchingQueueEntry>> {
 		let rows = self
 			.conn
-			.query_all(Statement::from_string(
-				DbBackend::Sqlite,
-				r#"
-				SELECT session_id, target_device_id, voucher_device_id, vouchee_device_id,
-					vouchee_device_info, vouchee_public_key, voucher_signature,
-					proxied_session_keys, created_at, expires_at, status,
-					retry_count, last_attempt_at
-				FROM vouching_queue
-				"#
-				.to_string(),
-			))
+			.query_all(statement)
 			.await
 			.map_err(|e| NetworkingError::Protocol(format!("Failed to list vouches: {}", e)))?;
 
@@ -237,10 +424,6 @@ impl VouchingQueue {
 			let voucher_signature: Vec<u8> = row.try_get("", "voucher_signature").map_err(|e| {
 				NetworkingError::Protocol(format!("Failed to read voucher_signature: {}", e))
 			})?;
-			let proxied_session_keys: String =
-				row.try_get("", "proxied_session_keys").map_err(|e| {
-					NetworkingError::Protocol(format!("Failed to read proxied_session_keys: {}", e))
-				})?;
 			let created_at: String = row.try_get("", "created_at").map_err(|e| {
 				NetworkingError::Protocol(format!("Failed to read created_at: {}", e))
 			})?;
@@ -254,6 +437,8 @@ impl VouchingQueue {
 				NetworkingError::Protocol(format!("Failed to read retry_count: {}", e))
 			})?;
 			let last_attempt_at: Option<String> = row.try_get("", "last_attempt_at").ok();
+			let last_attempt_error: Option<String> =
+				row.try_get("", "last_attempt_error").ok().flatten();
 
 			let entry = VouchingQueueEntry {
 				session_id: Uuid::parse_str(&session_id)
@@ -270,7 +455,6 @@ impl VouchingQueue {
 				vouchee_device_info: Self::deserialize(&vouchee_device_info)?,
 				vouchee_public_key,
 				voucher_signature,
-				proxied_session_keys: Self::deserialize(&proxied_session_keys)?,
 				created_at: DateTime::parse_from_rfc3339(&created_at)
 					.map_err(|e| NetworkingError::Protocol(format!("Invalid created_at: {}", e)))?
 					.with_timezone(&Utc),
@@ -282,6 +466,7 @@ impl VouchingQueue {
 				last_attempt_at: last_attempt_at
 					.and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
 					.map(|ts| ts.with_timezone(&Utc)),
+				last_attempt_error,
 			};
 
 			entries.push(entry);
@@ -297,13 +482,14 @@ impl VouchingQueue {
 		status: VouchQueueStatus,
 		retry_count: u32,
 		last_attempt_at: Option<DateTime<Utc>>,
+		last_attempt_error: Option<String>,
 	) -> Result<()> {
 		self.conn
 			.execute(Statement::from_sql_and_values(
 				DbBackend::Sqlite,
 				r#"
 				UPDATE vouching_queue
-				SET status = ?, retry_count = ?, last_attempt_at = ?
+				SET status = ?, retry_count = ?, last_attempt_at = ?, last_attempt_error = ?
 				WHERE session_id = ? AND target_device_id = ?
 				"#,
 				vec![
@@ -313,6 +499,7 @@ impl VouchingQueue {
 						.map(|ts| ts.to_rfc3339())
 						.unwrap_or_default()
 						.into(),
+					last_attempt_error.into(),
 					session_id.to_string().into(),
 					target_device_id.to_string().into(),
 				],
@@ -339,6 +526,269 @@ impl VouchingQueue {
 		Ok(())
 	}
 
+	/// Remove every queued vouch for a vouching session, returning how many were dropped
+	pub async fn remove_entries_for_session(&self, session_id: Uuid) -> Result<u64> {
+		let result = self
+			.conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"DELETE FROM vouching_queue WHERE session_id = ?",
+				vec![session_id.to_string().into()],
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to delete session vouches: {}", e))
+			})?;
+
+		Ok(result.rows_affected())
+	}
+
+	/// Remove every queued vouch addressed to a target device, returning how many were dropped
+	pub async fn remove_entries_for_target(&self, target_device_id: Uuid) -> Result<u64> {
+		let result = self
+			.conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"DELETE FROM vouching_queue WHERE target_device_id = ?",
+				vec![target_device_id.to_string().into()],
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to delete target vouches: {}", e))
+			})?;
+
+		Ok(result.rows_affected())
+	}
+
+	/// Remove every queued vouch addressed to or vouching for a device, returning how many
+	/// were dropped
+	pub async fn remove_entries_for_device(&self, device_id: Uuid) -> Result<u64> {
+		let result = self
+			.conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"DELETE FROM vouching_queue WHERE target_device_id = ? OR vouchee_device_id = ?",
+				vec![device_id.to_string().into(), device_id.to_string().into()],
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to delete device vouches: {}", e))
+			})?;
+
+		Ok(result.rows_affected())
+	}
+
+	/// Count a rejection from `target_device_id`, returning the streak including it
+	pub async fn record_rejection(
+		&self,
+		voucher_device_id: Uuid,
+		target_device_id: Uuid,
+		rejected_at: DateTime<Utc>,
+	) -> Result<RejectionStreak> {
+		self.conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				r#"
+				INSERT INTO vouch_rejections (
+					voucher_device_id,
+					target_device_id,
+					consecutive,
+					last_rejected_at
+				)
+				VALUES (?, ?, 1, ?)
+				ON CONFLICT(voucher_device_id, target_device_id) DO UPDATE SET
+					consecutive = consecutive + 1,
+					last_rejected_at = excluded.last_rejected_at
+				"#,
+				vec![
+					voucher_device_id.to_string().into(),
+					target_device_id.to_string().into(),
+					rejected_at.to_rfc3339().into(),
+				],
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to record vouch rejection: {}", e))
+			})?;
+
+		self.rejection_streak(voucher_device_id, target_device_id)
+			.await?
+			.ok_or_else(|| {
+				NetworkingError::Protocol("Vouch rejection was not recorded".to_string())
+			})
+	}
+
+	/// Forget a target's rejections, e.g. once it accepts a vouch
+	pub async fn clear_rejections(
+		&self,
+		voucher_device_id: Uuid,
+		target_device_id: Uuid,
+	) -> Result<()> {
+		self.conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				"DELETE FROM vouch_rejections WHERE voucher_device_id = ? AND target_device_id = ?",
+				vec![
+					voucher_device_id.to_string().into(),
+					target_device_id.to_string().into(),
+				],
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to clear vouch rejections: {}", e))
+			})?;
+
+		Ok(())
+	}
+
+	/// The current rejection streak of a target, None if it has none
+	pub async fn rejection_streak(
+		&self,
+		voucher_device_id: Uuid,
+		target_device_id: Uuid,
+	) -> Result<Option<RejectionStreak>> {
+		let row = self
+			.conn
+			.query_one(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				r#"
+				SELECT consecutive, last_rejected_at FROM vouch_rejections
+				WHERE voucher_device_id = ? AND target_device_id = ?
+				"#,
+				vec![
+					voucher_device_id.to_string().into(),
+					target_device_id.to_string().into(),
+				],
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to read vouch rejections: {}", e))
+			})?;
+
+		let Some(row) = row else {
+			return Ok(None);
+		};
+		let consecutive: i64 = row
+			.try_get("", "consecutive")
+			.map_err(|e| NetworkingError::Protocol(format!("Failed to read consecutive: {}", e)))?;
+		let last_rejected_at: String = row.try_get("", "last_rejected_at").map_err(|e| {
+			NetworkingError::Protocol(format!("Failed to read last_rejected_at: {}", e))
+		})?;
+
+		Ok(Some(RejectionStreak {
+			consecutive: consecutive as u32,
+			last_rejected_at: DateTime::parse_from_rfc3339(&last_rejected_at)
+				.map_err(|e| NetworkingError::Protocol(format!("Invalid last_rejected_at: {}", e)))?
+				.with_timezone(&Utc),
+		}))
+	}
+
+	/// Append a proxy pairing decision to the audit log
+	pub async fn record_proxy_decision(&self, entry: &ProxyPairingAuditEntry) -> Result<()> {
+		self.conn
+			.execute(Statement::from_sql_and_values(
+				DbBackend::Sqlite,
+				r#"
+				INSERT INTO proxy_pairing_audit (
+					session_id,
+					voucher_device_id,
+					vouchee_device_id,
+					vouchee_device_name,
+					accepted,
+					confirmed_by_user,
+					reason,
+					decided_at
+				)
+				VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+				"#,
+				vec![
+					entry.session_id.to_string().into(),
+					entry.voucher_device_id.to_string().into(),
+					entry.vouchee_device_id.to_string().into(),
+					entry.vouchee_device_name.clone().into(),
+					entry.accepted.into(),
+					entry.confirmed_by_user.into(),
+					entry.reason.clone().into(),
+					entry.decided_at.to_rfc3339().into(),
+				],
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to record proxy pairing decision: {}", e))
+			})?;
+
+		Ok(())
+	}
+
+	/// Every recorded proxy pairing decision, oldest first
+	pub async fn list_proxy_decisions(&self) -> Result<Vec<ProxyPairingAuditEntry>> {
+		let rows = self
+			.conn
+			.query_all(Statement::from_string(
+				DbBackend::Sqlite,
+				r#"
+				SELECT session_id, voucher_device_id, vouchee_device_id, vouchee_device_name,
+					accepted, confirmed_by_user, reason, decided_at
+				FROM proxy_pairing_audit
+				ORDER BY id
+				"#
+				.to_string(),
+			))
+			.await
+			.map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to read proxy pairing audit: {}", e))
+			})?;
+
+		let mut entries = Vec::new();
+		for row in rows {
+			let session_id: String = row.try_get("", "session_id").map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to read session_id: {}", e))
+			})?;
+			let voucher_device_id: String = row.try_get("", "voucher_device_id").map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to read voucher_device_id: {}", e))
+			})?;
+			let vouchee_device_id: String = row.try_get("", "vouchee_device_id").map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to read vouchee_device_id: {}", e))
+			})?;
+			let vouchee_device_name: String =
+				row.try_get("", "vouchee_device_name").map_err(|e| {
+					NetworkingError::Protocol(format!("Failed to read vouchee_device_name: {}", e))
+				})?;
+			let accepted: bool = row.try_get("", "accepted").map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to read accepted: {}", e))
+			})?;
+			let confirmed_by_user: bool = row.try_get("", "confirmed_by_user").map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to read confirmed_by_user: {}", e))
+			})?;
+			let reason: Option<String> = row
+				.try_get("", "reason")
+				.map_err(|e| NetworkingError::Protocol(format!("Failed to read reason: {}", e)))?;
+			let decided_at: String = row.try_get("", "decided_at").map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to read decided_at: {}", e))
+			})?;
+
+			entries.push(ProxyPairingAuditEntry {
+				session_id: Uuid::parse_str(&session_id)
+					.map_err(|e| NetworkingError::Protocol(format!("Invalid session_id: {}", e)))?,
+				voucher_device_id: Uuid::parse_str(&voucher_device_id).map_err(|e| {
+					NetworkingError::Protocol(format!("Invalid voucher_device_id: {}", e))
+				})?,
+				vouchee_device_id: Uuid::parse_str(&vouchee_device_id).map_err(|e| {
+					NetworkingError::Protocol(format!("Invalid vouchee_device_id: {}", e))
+				})?,
+				vouchee_device_name,
+				accepted,
+				confirmed_by_user,
+				reason,
+				decided_at: DateTime::parse_from_rfc3339(&decided_at)
+					.map_err(|e| NetworkingError::Protocol(format!("Invalid decided_at: {}", e)))?
+					.with_timezone(&Utc),
+			});
+		}
+
+		Ok(entries)
+	}
+
 	pub async fn remove_expired(&self, now: DateTime<Utc>) -> Result<u64> {
 		let result = self
 			.conn
@@ -355,3 +805,350 @@ impl VouchingQueue {
 		Ok(result.rows_affected())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::service::network::{device::DeviceType, utils::identity::NetworkFingerprint};
+	use tempfile::TempDir;
+
+	fn create_test_entry(session_id: Uuid) -> VouchingQueueEntry {
+		let now = Utc::now();
+		VouchingQueueEntry {
+			session_id,
+			target_device_id: Uuid::new_v4(),
+			voucher_device_id: Uuid::new_v4(),
+			vouchee_device_id: Uuid::new_v4(),
+			vouchee_device_info: DeviceInfo {
+				device_id: Uuid::new_v4(),
+				device_name: "Vouchee".to_string(),
+				device_slug: "vouchee".to_string(),
+				device_type: DeviceType::Desktop,
+				os_version: "Test OS 1.0".to_string(),
+				app_version: "1.0.0".to_string(),
+				network_fingerprint: NetworkFingerprint {
+					node_id: "test_node_id".to_string(),
+					public_key_hash: "test_hash".to_string(),
+				},
+				last_seen: now,
+				version: DeviceInfo::CURRENT_VERSION,
+			},
+			vouchee_public_key: vec![1; 32],
+			voucher_signature: vec![2; 64],
+			created_at: now,
+			expires_at: now + chrono::Duration::minutes(10),
+			status: VouchQueueStatus::Queued,
+			retry_count: 0,
+			last_attempt_at: None,
+			last_attempt_error: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn test_list_entries_for_session() {
+		let temp_dir = TempDir::new().unwrap();
+		let queue = VouchingQueue::open(temp_dir.path()).await.unwrap();
+		let session_a = Uuid::new_v4();
+		let session_b = Uuid::new_v4();
+
+		queue
+			.upsert_entry(&create_test_entry(session_a))
+			.await
+			.unwrap();
+		queue
+			.upsert_entry(&create_test_entry(session_a))
+			.await
+			.unwrap();
+		queue
+			.upsert_entry(&create_test_entry(session_b))
+			.await
+			.unwrap();
+
+		let entries = queue.list_entries_for_session(session_a).await.unwrap();
+		assert_eq!(entries.len(), 2);
+		assert!(entries.iter().all(|entry| entry.session_id == session_a));
+
+		let entries = queue
+			.list_entries_for_session(Uuid::new_v4())
+			.await
+			.unwrap();
+		assert!(entries.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_remove_entries_for_session() {
+		let temp_dir = TempDir::new().unwrap();
+		let queue = VouchingQueue::open(temp_dir.path()).await.unwrap();
+		let session_a = Uuid::new_v4();
+		let session_b = Uuid::new_v4();
+
+		queue
+			.upsert_entry(&create_test_entry(session_a))
+			.await
+			.unwrap();
+		queue
+			.upsert_entry(&create_test_entry(session_a))
+			.await
+			.unwrap();
+		queue
+			.upsert_entry(&create_test_entry(session_b))
+			.await
+			.unwrap();
+
+		assert_eq!(
+			queue.remove_entries_for_session(session_a).await.unwrap(),
+			2
+		);
+		assert_eq!(
+			queue.remove_entries_for_session(session_a).await.unwrap(),
+			0
+		);
+
+		let remaining = queue.list_entries().await.unwrap();
+		assert_eq!(remaining.len(), 1);
+		assert_eq!(remaining[0].session_id, session_b);
+	}
+
+	#[tokio::test]
+	async fn test_remove_entries_for_device() {
+		let temp_dir = TempDir::new().unwrap();
+		let queue = VouchingQueue::open(temp_dir.path()).await.unwrap();
+		let device_id = Uuid::new_v4();
+
+		let mut targeting = create_test_entry(Uuid::new_v4());
+		targeting.target_device_id = device_id;
+		let mut vouching_for = create_test_entry(Uuid::new_v4());
+		vouching_for.vouchee_device_id = device_id;
+		let unrelated = create_test_entry(Uuid::new_v4());
+
+		for entry in [&targeting, &vouching_for, &unrelated] {
+			queue.upsert_entry(entry).await.unwrap();
+		}
+
+		assert_eq!(queue.remove_entries_for_device(device_id).await.unwrap(), 2);
+
+		let remaining = queue.list_entries().await.unwrap();
+		assert_eq!(remaining.len(), 1);
+		assert_eq!(remaining[0].session_id, unrelated.session_id);
+	}
+
+	#[tokio::test]
+	async fn test_last_attempt_error_round_trips() {
+		let temp_dir = TempDir::new().unwrap();
+		let queue = VouchingQueue::open(temp_dir.path()).await.unwrap();
+		let entry = create_test_entry(Uuid::new_v4());
+		queue.upsert_entry(&entry).await.unwrap();
+
+		queue
+			.update_status(
+				entry.session_id,
+				entry.target_device_id,
+				VouchQueueStatus::Queued,
+				1,
+				Some(Utc::now()),
+				Some("connection refused".to_string()),
+			)
+			.await
+			.unwrap();
+		let entries = queue.list_entries().await.unwrap();
+		assert_eq!(
+			entries[0].last_attempt_error.as_deref(),
+			Some("connection refused")
+		);
+
+		// A successful attempt clears it
+		queue
+			.update_status(
+				entry.session_id,
+				entry.target_device_id,
+				VouchQueueStatus::Waiting,
+				2,
+				Some(Utc::now()),
+				None,
+			)
+			.await
+			.unwrap();
+		let entries = queue.list_entries().await.unwrap();
+		assert!(entries[0].last_attempt_error.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_rejection_streak_pauses_until_cooldown() {
+		let temp_dir = TempDir::new().unwrap();
+		let queue = VouchingQueue::open(temp_dir.path()).await.unwrap();
+		let voucher = Uuid::new_v4();
+		let target = Uuid::new_v4();
+		let cooldown = chrono::Duration::hours(1);
+		let now = Utc::now();
+
+		assert!(queue
+			.rejection_streak(voucher, target)
+			.await
+			.unwrap()
+			.is_none());
+
+		queue.record_rejection(voucher, target, now).await.unwrap();
+		let streak = queue.record_rejection(voucher, target, now).await.unwrap();
+		assert_eq!(streak.consecutive, 2);
+		assert!(!streak.is_paused(3, cooldown, now));
+
+		let streak = queue.record_rejection(voucher, target, now).await.unwrap();
+		assert!(streak.is_paused(3, cooldown, now));
+		assert!(!streak.is_paused(3, cooldown, now + cooldown));
+		assert!(!streak.is_paused(0, cooldown, now));
+
+		// Streaks are per voucher and target
+		assert!(queue
+			.rejection_streak(Uuid::new_v4(), target)
+			.await
+			.unwrap()
+			.is_none());
+
+		queue.clear_rejections(voucher, target).await.unwrap();
+		assert!(queue
+			.rejection_streak(voucher, target)
+			.await
+			.unwrap()
+			.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_open_at_explicit_path() {
+		let temp_dir = TempDir::new().unwrap();
+		let db_path = temp_dir.path().join("isolated").join("queue.db");
+		let entry = create_test_entry(Uuid::new_v4());
+
+		{
+			let queue = VouchingQueue::open_at(&db_path).await.unwrap();
+			queue.upsert_entry(&entry).await.unwrap();
+		}
+		assert!(db_path.exists());
+		assert!(!temp_dir.path().join("networking").exists());
+
+		let queue = VouchingQueue::open_at(&db_path).await.unwrap();
+		let entries = queue.list_entries().await.unwrap();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].session_id, entry.session_id);
+		assert_eq!(entries[0].target_device_id, entry.target_device_id);
+	}
+
+	#[tokio::test]
+	async fn test_open_adds_last_attempt_error_to_existing_queue() {
+		let temp_dir = TempDir::new().unwrap();
+		let networking_dir = temp_dir.path().join("networking");
+		std::fs::create_dir_all(&networking_dir).unwrap();
+
+		// A queue written before the column existed
+		let database_url = format!(
+			"sqlite://{}?mode=rwc",
+			networking_dir.join("vouching_queue.db").display()
+		);
+		let conn = Database::connect(&database_url).await.unwrap();
+		conn.execute(Statement::from_string(
+			DbBackend::Sqlite,
+			r#"
+			CREATE TABLE vouching_queue (
+				id INTEGER PRIMARY KEY AUTOINCREMENT,
+				session_id TEXT NOT NULL,
+				target_device_id TEXT NOT NULL,
+				voucher_device_id TEXT NOT NULL,
+				vouchee_device_id TEXT NOT NULL,
+				vouchee_device_info TEXT NOT NULL,
+				vouchee_public_key BLOB NOT NULL,
+				voucher_signature BLOB NOT NULL,
+				proxied_session_keys TEXT NOT NULL,
+				created_at TEXT NOT NULL,
+				expires_at TEXT NOT NULL,
+				status TEXT NOT NULL,
+				retry_count INTEGER DEFAULT 0,
+				last_attempt_at TEXT,
+
+				UNIQUE(session_id, target_device_id)
+			)
+			"#
+			.to_string(),
+		))
+		.await
+		.unwrap();
+		drop(conn);
+
+		// Opening twice must not try to add the column again
+		drop(VouchingQueue::open(temp_dir.path()).await.unwrap());
+		let queue = VouchingQueue::open(temp_dir.path()).await.unwrap();
+
+		let mut entry = create_test_entry(Uuid::new_v4());
+		entry.last_attempt_error = Some("timed out".to_string());
+		queue.upsert_entry(&entry).await.unwrap();
+		let entries = queue.list_entries().await.unwrap();
+		assert_eq!(entries[0].last_attempt_error.as_deref(), Some("timed out"));
+	}
+
+	#[tokio::test]
+	async fn test_migrations_are_recorded_and_applied_once() {
+		let temp_dir = TempDir::new().unwrap();
+		let db_path = temp_dir.path().join("queue.db");
+
+		// An old queue: the original table and no recorded version
+		let database_url = format!("sqlite://{}?mode=rwc", db_path.display());
+		let conn = Database::connect(&database_url).await.unwrap();
+		conn.execute(Statement::from_string(
+			DbBackend::Sqlite,
+			r#"
+			CREATE TABLE vouching_queue (
+				id INTEGER PRIMARY KEY AUTOINCREMENT,
+				session_id TEXT NOT NULL,
+				target_device_id TEXT NOT NULL,
+				voucher_device_id TEXT NOT NULL,
+				vouchee_device_id TEXT NOT NULL,
+				vouchee_device_info TEXT NOT NULL,
+				vouchee_public_key BLOB NOT NULL,
+				voucher_signature BLOB NOT NULL,
+				proxied_session_keys TEXT NOT NULL,
+				created_at TEXT NOT NULL,
+				expires_at TEXT NOT NULL,
+				status TEXT NOT NULL,
+				retry_count INTEGER DEFAULT 0,
+				last_attempt_at TEXT,
+
+				UNIQUE(session_id, target_device_id)
+			)
+			"#
+			.to_string(),
+		))
+		.await
+		.unwrap();
+		assert_eq!(VouchingQueue::schema_version(&conn).await.unwrap(), 0);
+		drop(conn);
+
+		let queue = VouchingQueue::open_at(&db_path).await.unwrap();
+		assert_eq!(
+			VouchingQueue::schema_version(&queue.conn).await.unwrap() as usize,
+			QUEUE_MIGRATIONS.len()
+		);
+		for (column, _) in QUEUE_MIGRATIONS {
+			assert!(VouchingQueue::has_column(&queue.conn, column)
+				.await
+				.unwrap());
+		}
+		drop(queue);
+
+		// Reopening at the current version changes nothing
+		let queue = VouchingQueue::open_at(&db_path).await.unwrap();
+		assert_eq!(
+			VouchingQueue::schema_version(&queue.conn).await.unwrap() as usize,
+			QUEUE_MIGRATIONS.len()
+		);
+
+		// A fresh queue ends up with the same columns and version
+		let fresh = VouchingQueue::open_at(temp_dir.path().join("fresh.db"))
+			.await
+			.unwrap();
+		assert_eq!(
+			VouchingQueue::schema_version(&fresh.conn).await.unwrap() as usize,
+			QUEUE_MIGRATIONS.len()
+		);
+		assert!(VouchingQueue::has_column(&fresh.conn, "last_attempt_error")
+			.await
+			.unwrap());
+	}
+}
@@ -1,6 +1,6 @@
 //! Protocol registry for managing protocol handlers
 
-use super::{ProtocolEvent, ProtocolHandler};
+use super::{Protocol, ProtocolEvent, ProtocolHandler};
 use crate::service::network::{NetworkingError, Result};
 use iroh::EndpointId;
 use std::collections::HashMap;
@@ -9,7 +9,7 @@ use uuid::Uuid;
 
 /// Registry for protocol handlers
 pub struct ProtocolRegistry {
-	handlers: HashMap<String, Arc<dyn ProtocolHandler>>,
+	handlers: HashMap<Protocol, Arc<dyn ProtocolHandler>>,
 }
 
 impl ProtocolRegistry {
@@ -22,42 +22,42 @@ impl ProtocolRegistry {
 
 	/// Register a protocol handler
 	pub fn register_handler(&mut self, handler: Arc<dyn ProtocolHandler>) -> Result<()> {
-		let protocol_name = handler.protocol_name().to_string();
+		let protocol = handler.protocol_name();
 
-		if self.handlers.contains_key(&protocol_name) {
+		if self.handlers.contains_key(&protocol) {
 			return Err(NetworkingError::Protocol(format!(
 				"Protocol {} already registered",
-				protocol_name
+				protocol
 			)));
 		}
 
-		self.handlers.insert(protocol_name, handler);
+		self.handlers.insert(protocol, handler);
 		Ok(())
 	}
 
 	/// Unregister a protocol handler
-	pub fn unregister_handler(&mut self, protocol_name: &str) -> Result<()> {
-		self.handlers.remove(protocol_name).ok_or_else(|| {
-			NetworkingError::Protocol(format!("Protocol {} not found", protocol_name))
-		})?;
+	pub fn unregister_handler(&mut self, protocol: Protocol) -> Result<()> {
+		self.handlers
+			.remove(&protocol)
+			.ok_or_else(|| NetworkingError::Protocol(format!("Protocol {} not found", protocol)))?;
 
 		Ok(())
 	}
 
-	/// Get a protocol handler by name
-	pub fn get_handler(&self, protocol_name: &str) -> Option<Arc<dyn ProtocolHandler>> {
-		self.handlers.get(protocol_name).cloned()
+	/// Get the handler registered for a protocol
+	pub fn get_handler(&self, protocol: Protocol) -> Option<Arc<dyn ProtocolHandler>> {
+		self.handlers.get(&protocol).cloned()
 	}
 
 	/// Handle an incoming request
 	pub async fn handle_request(
 		&self,
-		protocol_name: &str,
+		protocol: Protocol,
 		from_device: Uuid,
 		request_data: Vec<u8>,
 	) -> Result<Vec<u8>> {
-		let handler = self.get_handler(protocol_name).ok_or_else(|| {
-			NetworkingError::Protocol(format!("No handler for protocol {}", protocol_name))
+		let handler = self.get_handler(protocol).ok_or_else(|| {
+			NetworkingError::Protocol(format!("No handler for protocol {}", protocol))
 		})?;
 
 		handler.handle_request(from_device, request_data).await
@@ -66,13 +66,13 @@ impl ProtocolRegistry {
 	/// Handle an incoming response
 	pub async fn handle_response(
 		&self,
-		protocol_name: &str,
+		protocol: Protocol,
 		from_device: Uuid,
 		from_node: EndpointId,
 		response_data: Vec<u8>,
 	) -> Result<()> {
-		let handler = self.get_handler(protocol_name).ok_or_else(|| {
-			NetworkingError::Protocol(format!("No handler for protocol {}", protocol_name))
+		let handler = self.get_handler(protocol).ok_or_else(|| {
+			NetworkingError::Protocol(format!("No handler for protocol {}", protocol))
 		})?;
 
 		handler
@@ -98,7 +98,10 @@ impl ProtocolRegistry {
 
 	/// Get list of registered protocol names
 	pub fn get_protocol_names(&self) -> Vec<String> {
-		self.handlers.keys().cloned().collect()
+		self.handlers
+			.keys()
+			.map(|protocol| protocol.name().to_string())
+			.collect()
 	}
 
 	/// Get the number of registered handlers
@@ -0,0 +1,122 @@
+//! Per-peer sync payload format
+//!
+//! Devices advertise the payload formats they can decode while registering
+//! with each other during sync setup, and each side records the format it
+//! negotiated for that peer. Peers that advertise nothing predate the exchange
+//! and keep receiving JSON.
+//!
+//! The binary format is MessagePack rather than bincode or postcard: sync
+//! payloads carry `serde_json::Value` records, which only self-describing
+//! formats can deserialize.
+
+use super::messages::SyncMessage;
+use crate::service::network::{NetworkingError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Encoding of sync messages on the wire
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPayloadFormat {
+	#[default]
+	Json,
+	MessagePack,
+}
+
+/// Formats this build can decode, most preferred first
+pub const SUPPORTED_SYNC_FORMATS: [SyncPayloadFormat; 2] =
+	[SyncPayloadFormat::MessagePack, SyncPayloadFormat::Json];
+
+impl SyncPayloadFormat {
+	/// Pick the first local format the remote also supports, falling back to JSON
+	pub fn negotiate(local: &[SyncPayloadFormat], remote: &[SyncPayloadFormat]) -> Self {
+		local
+			.iter()
+			.copied()
+			.find(|format| remote.contains(format))
+			.unwrap_or(SyncPayloadFormat::Json)
+	}
+
+	/// Format of an encoded message
+	///
+	/// JSON for a `SyncMessage` always opens with `{` or `"`, which MessagePack
+	/// never emits as the first byte of a map or string. Receivers decode by
+	/// looking rather than by what they negotiated, so a message sent before
+	/// both sides recorded the same format still goes through.
+	pub fn detect(bytes: &[u8]) -> Self {
+		match bytes.first() {
+			Some(b'{') | Some(b'"') | None => SyncPayloadFormat::Json,
+			Some(_) => SyncPayloadFormat::MessagePack,
+		}
+	}
+
+	pub fn encode(self, message: &SyncMessage) -> Result<Vec<u8>> {
+		match self {
+			SyncPayloadFormat::Json => Ok(serde_json::to_vec(message)?),
+			SyncPayloadFormat::MessagePack => rmp_serde::to_vec_named(message).map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to encode sync message: {}", e))
+			}),
+		}
+	}
+
+	/// Decode a message in whichever format it was encoded
+	pub fn decode(bytes: &[u8]) -> Result<SyncMessage> {
+		match Self::detect(bytes) {
+			SyncPayloadFormat::Json => Ok(serde_json::from_slice(bytes)?),
+			SyncPayloadFormat::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| {
+				NetworkingError::Protocol(format!("Failed to decode sync message: {}", e))
+			}),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use uuid::Uuid;
+
+	#[test]
+	fn test_capable_peers_negotiate_binary() {
+		let format = SyncPayloadFormat::negotiate(&SUPPORTED_SYNC_FORMATS, &SUPPORTED_SYNC_FORMATS);
+		assert_eq!(format, SyncPayloadFormat::MessagePack);
+	}
+
+	#[test]
+	fn test_mixed_peers_fall_back_to_json() {
+		// A peer from before the exchange advertises nothing
+		assert_eq!(
+			SyncPayloadFormat::negotiate(&SUPPORTED_SYNC_FORMATS, &[]),
+			SyncPayloadFormat::Json
+		);
+		assert_eq!(
+			SyncPayloadFormat::negotiate(&SUPPORTED_SYNC_FORMATS, &[SyncPayloadFormat::Json]),
+			SyncPayloadFormat::Json
+		);
+		assert_eq!(
+			SyncPayloadFormat::negotiate(&[SyncPayloadFormat::Json], &SUPPORTED_SYNC_FORMATS),
+			SyncPayloadFormat::Json
+		);
+	}
+
+	#[test]
+	fn test_both_formats_round_trip() {
+		let message = SyncMessage::StateChange {
+			library_id: Uuid::new_v4(),
+			model_type: "location".to_string(),
+			record_uuid: Uuid::new_v4(),
+			device_id: Uuid::new_v4(),
+			data: serde_json::json!({ "name": "Photos", "size": 42, "tags": [null, true] }),
+			timestamp: chrono::Utc::now(),
+		};
+
+		for format in SUPPORTED_SYNC_FORMATS {
+			let bytes = format.encode(&message).unwrap();
+			assert_eq!(SyncPayloadFormat::detect(&bytes), format);
+
+			let decoded = SyncPayloadFormat::decode(&bytes).unwrap();
+			assert_eq!(
+				serde_json::to_value(&decoded).unwrap(),
+				serde_json::to_value(&message).unwrap()
+			);
+		}
+	}
+}
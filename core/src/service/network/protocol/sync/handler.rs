@@ -3,13 +3,20 @@
 //! Handles incoming sync messages and routes them to the appropriate
 //! PeerSync methods for processing.
 
-use super::messages::{StateRecord, SyncMessage};
+use super::{
+	format::SyncPayloadFormat,
+	messages::{StateRecord, SyncMessage},
+};
 use crate::service::{
 	network::{NetworkingError, Result},
 	sync::state::StateChangeMessage,
 };
 use async_trait::async_trait;
-use std::sync::Arc;
+use std::{
+	collections::HashMap,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
@@ -22,6 +29,7 @@ pub struct SyncProtocolHandler {
 	backfill_manager: Option<Arc<crate::service::sync::BackfillManager>>,
 	metrics: Option<Arc<crate::service::sync::SyncMetricsCollector>>,
 	device_registry: Arc<tokio::sync::RwLock<crate::service::network::device::DeviceRegistry>>,
+	membership: MembershipCache,
 }
 
 impl SyncProtocolHandler {
@@ -40,6 +48,7 @@ impl SyncProtocolHandler {
 			backfill_manager: None,
 			metrics: None,
 			device_registry,
+			membership: MembershipCache::default(),
 		}
 	}
 
@@ -66,6 +75,13 @@ impl SyncProtocolHandler {
 		self.library_id
 	}
 
+	/// Count an encoded sync message received for this library
+	pub fn record_bytes_received(&self, bytes: usize) {
+		if let Some(metrics) = &self.metrics {
+			metrics.record_bytes_received(bytes as u64);
+		}
+	}
+
 	/// Handle an incoming sync message
 	pub async fn handle_sync_message(
 		&self,
@@ -77,6 +93,10 @@ impl SyncProtocolHandler {
 			.as_ref()
 			.ok_or_else(|| NetworkingError::Protocol("PeerSync not initialized".to_string()))?;
 
+		self.membership
+			.ensure_member(peer_sync.db(), self.library_id, from_device)
+			.await?;
+
 		debug!(
 			from_device = %from_device,
 			library_id = %message.library_id(),
@@ -228,16 +248,48 @@ impl SyncProtocolHandler {
 					}
 				});
 
-				// Query local state
-				let records = peer_sync
-					.get_device_state(model_types.clone(), device_id, since, cursor, batch_size)
+				let model_type = model_types.first().cloned().unwrap_or_default();
+
+				// Query local state, limited to the locations shared with this peer
+				let location_filter = peer_sync.location_filter_for(from_device).await;
+				let (records, estimated_total) =
+					crate::infra::sync::with_location_filter(location_filter, async {
+						let records = peer_sync
+							.get_device_state(
+								model_types.clone(),
+								device_id,
+								since,
+								cursor,
+								batch_size,
+							)
+							.await?;
+
+						// Counted in the same scope as the records; the requester keeps the
+						// count from the first page
+						let estimated_total = if checkpoint.is_none() {
+							crate::infra::sync::registry::estimate_record_count(
+								&model_type,
+								device_id,
+								since,
+								peer_sync.db().clone(),
+							)
+							.await
+							.unwrap_or_else(|e| {
+								warn!(model_type = %model_type, error = %e, "Failed to count backfill records");
+								None
+							})
+						} else {
+							None
+						};
+
+						Ok::<_, anyhow::Error>((records, estimated_total))
+					})
 					.await
 					.map_err(|e| {
 						NetworkingError::Protocol(format!("Failed to query device state: {}", e))
 					})?;
 
 				// Query tombstones if incremental sync
-				let model_type = model_types.first().cloned().unwrap_or_default();
 				let deleted_uuids = if let Some(since_time) = since {
 					peer_sync
 						.get_deletion_tombstones(&model_type, device_id, since_time)
@@ -279,6 +331,7 @@ impl SyncProtocolHandler {
 					deleted_uuids,
 					checkpoint: next_checkpoint,
 					has_more,
+					estimated_total,
 				}))
 			}
 
@@ -304,6 +357,7 @@ impl SyncProtocolHandler {
 				library_id,
 				since_hlc,
 				limit,
+				content_hints,
 			} => {
 				debug!(
 					since_hlc = ?since_hlc,
@@ -322,7 +376,7 @@ impl SyncProtocolHandler {
 				// If initial backfill (since_hlc = None), include full current state
 				let current_state = if since_hlc.is_none() {
 					debug!("Initial backfill requested - querying full shared resource state");
-					match peer_sync.get_full_shared_state().await {
+					match peer_sync.get_full_shared_state(content_hints).await {
 						Ok(state) => {
 							info!("Including full state snapshot for initial backfill");
 							Some(state)
@@ -385,6 +439,46 @@ impl SyncProtocolHandler {
 				Ok(None)
 			}
 
+			SyncMessage::ContentRequest {
+				library_id,
+				model_type,
+				content_hashes,
+			} => {
+				debug!(
+					model_type = %model_type,
+					count = content_hashes.len(),
+					"Processing ContentRequest"
+				);
+
+				let records = peer_sync
+					.get_shared_content(&model_type, content_hashes)
+					.await
+					.map_err(|e| {
+						NetworkingError::Protocol(format!("Failed to query content: {}", e))
+					})?;
+
+				if let Some(metrics) = &self.metrics {
+					let bytes = serde_json::to_vec(&records).map(|v| v.len()).unwrap_or(0);
+					metrics.record_bytes_sent(bytes as u64);
+				}
+
+				Ok(Some(SyncMessage::ContentResponse {
+					library_id,
+					model_type,
+					records,
+				}))
+			}
+
+			SyncMessage::ContentResponse { model_type, .. } => {
+				// Only expected as the reply to a ContentRequest, which the
+				// backfill reads directly off the request stream
+				warn!(
+					model_type = %model_type,
+					"Received unsolicited ContentResponse"
+				);
+				Ok(None)
+			}
+
 			SyncMessage::AckSharedChanges {
 				library_id,
 				from_device,
@@ -577,27 +671,122 @@ impl SyncProtocolHandler {
 				);
 				Ok(None)
 			}
+
+			SyncMessage::NotAuthorized {
+				library_id,
+				device_id,
+			} => {
+				warn!(
+					from_device = %from_device,
+					library_id = %library_id,
+					device_id = %device_id,
+					"Peer refused a request: device is not registered in the library"
+				);
+				Ok(None)
+			}
+		}
+	}
+}
+
+/// How long a confirmed membership is trusted before the device table is asked again
+const MEMBERSHIP_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Confirmed entries kept before expired ones are swept
+const MEMBERSHIP_CACHE_SWEEP_AT: usize = 1024;
+
+/// Devices recently confirmed as members of a library
+///
+/// Every sync message is checked against the device table; this keeps that
+/// from becoming a query per message. Only confirmations are cached, so a
+/// device that just joined is let in immediately, and a device dropped from
+/// the table is refused again once its entry expires. Unpairing removes the
+/// device from the registry, which stops its messages before they get here.
+#[derive(Default)]
+pub(crate) struct MembershipCache {
+	confirmed: parking_lot::Mutex<HashMap<(Uuid, Uuid), Instant>>,
+}
+
+impl MembershipCache {
+	/// Like [`ensure_library_member`], answering from the cache when it can
+	pub(crate) async fn ensure_member(
+		&self,
+		db: &sea_orm::DatabaseConnection,
+		library_id: Uuid,
+		device_id: Uuid,
+	) -> Result<()> {
+		let key = (library_id, device_id);
+		let fresh = self
+			.confirmed
+			.lock()
+			.get(&key)
+			.is_some_and(|at| at.elapsed() < MEMBERSHIP_CACHE_TTL);
+		if fresh {
+			return Ok(());
+		}
+
+		if let Err(e) = ensure_library_member(db, library_id, device_id).await {
+			self.confirmed.lock().remove(&key);
+			return Err(e);
+		}
+
+		let mut confirmed = self.confirmed.lock();
+		if confirmed.len() >= MEMBERSHIP_CACHE_SWEEP_AT {
+			confirmed.retain(|_, at| at.elapsed() < MEMBERSHIP_CACHE_TTL);
 		}
+		confirmed.insert(key, Instant::now());
+		Ok(())
+	}
+}
+
+/// Reject a device that isn't in the library's device table
+///
+/// Only devices registered in the library may read or write its sync data.
+pub(crate) async fn ensure_library_member(
+	db: &sea_orm::DatabaseConnection,
+	library_id: Uuid,
+	device_id: Uuid,
+) -> Result<()> {
+	use crate::infra::db::entities::device;
+	use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+	let registered = device::Entity::find()
+		.filter(device::Column::Uuid.eq(device_id))
+		.count(db)
+		.await
+		.map_err(|e| NetworkingError::Protocol(format!("Database error: {}", e)))?
+		> 0;
+
+	if registered {
+		Ok(())
+	} else {
+		Err(NetworkingError::NotAuthorizedForLibrary {
+			device_id,
+			library_id,
+		})
 	}
 }
 
 #[async_trait]
 impl crate::service::network::protocol::ProtocolHandler for SyncProtocolHandler {
-	fn protocol_name(&self) -> &'static str {
-		"sync"
+	fn protocol_name(&self) -> crate::service::network::protocol::Protocol {
+		crate::service::network::protocol::Protocol::Sync
 	}
 
 	fn as_any(&self) -> &dyn std::any::Any {
 		self
 	}
 
+	fn max_message_size(&self) -> usize {
+		super::SYNC_MAX_MESSAGE_SIZE
+	}
+
 	async fn handle_stream(
 		&self,
 		mut send: Box<dyn tokio::io::AsyncWrite + Send + Unpin>,
 		mut recv: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
 		remote_node_id: iroh::EndpointId,
 	) {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 
 		tracing::info!(
 			"SyncProtocolHandler: Stream accepted from node {}",
@@ -629,25 +818,30 @@ impl crate::service::network::protocol::ProtocolHandler for SyncProtocolHandler
 			"SyncProtocolHandler: Reading request from device {}...",
 			from_device
 		);
-		let mut len_buf = [0u8; 4];
-		if let Err(e) = recv.read_exact(&mut len_buf).await {
-			// This is normal if peer just opened connection to test connectivity
-			tracing::debug!(
-				"SyncProtocolHandler: Failed to read sync request length (likely connection test): {}",
-				e
-			);
-			return;
-		}
-		let req_len = u32::from_be_bytes(len_buf) as usize;
-
-		let mut req_buf = vec![0u8; req_len];
-		if let Err(e) = recv.read_exact(&mut req_buf).await {
-			tracing::error!("Failed to read sync request: {}", e);
-			return;
-		}
+		let req_buf = match crate::service::network::protocol::read_frame_if_any(
+			&mut recv,
+			super::SYNC_MAX_MESSAGE_SIZE,
+		)
+		.await
+		{
+			Ok(Some(buf)) => buf,
+			Ok(None) => {
+				// This is normal if peer just opened connection to test connectivity
+				tracing::debug!(
+					"SyncProtocolHandler: Stream closed before a sync request (likely connection test)"
+				);
+				return;
+			}
+			Err(e) => {
+				tracing::error!("Failed to read sync request: {}", e);
+				return;
+			}
+		};
+		self.record_bytes_received(req_buf.len());
 
 		// Deserialize request
-		let request: SyncMessage = match serde_json::from_slice(&req_buf) {
+		let format = SyncPayloadFormat::detect(&req_buf);
+		let request = match SyncPayloadFormat::decode(&req_buf) {
 			Ok(msg) => msg,
 			Err(e) => {
 				tracing::error!("Failed to deserialize sync request: {}", e);
@@ -672,7 +866,7 @@ impl crate::service::network::protocol::ProtocolHandler for SyncProtocolHandler
 
 		// Send response if handler returned one
 		if let Some(response) = response_opt {
-			let resp_bytes = match serde_json::to_vec(&response) {
+			let resp_bytes = match format.encode(&response) {
 				Ok(bytes) => bytes,
 				Err(e) => {
 					tracing::error!("Failed to serialize sync response: {}", e);
@@ -700,18 +894,32 @@ impl crate::service::network::protocol::ProtocolHandler for SyncProtocolHandler
 	}
 
 	async fn handle_request(&self, from_device: Uuid, request: Vec<u8>) -> Result<Vec<u8>> {
-		let message: SyncMessage =
-			serde_json::from_slice(&request).map_err(|e| NetworkingError::Serialization(e))?;
+		self.record_bytes_received(request.len());
+		let format = SyncPayloadFormat::detect(&request);
+		let message = SyncPayloadFormat::decode(&request)?;
 
 		debug!(
 			from_device = %from_device,
 			"Received sync request"
 		);
 
-		match self.handle_sync_message(from_device, message).await? {
-			Some(response) => {
-				serde_json::to_vec(&response).map_err(|e| NetworkingError::Serialization(e))
-			}
+		let is_request = message.is_request();
+		let response = match self.handle_sync_message(from_device, message).await {
+			Ok(response) => response,
+			// Tell the requester why, so it can ask for access instead of timing out
+			Err(NetworkingError::NotAuthorizedForLibrary {
+				device_id,
+				library_id,
+			}) if is_request => Some(SyncMessage::NotAuthorized {
+				library_id,
+				device_id,
+			}),
+			Err(e) => return Err(e),
+		};
+
+		match response {
+			// Answer in the format the request came in
+			Some(response) => format.encode(&response),
 			None => Ok(Vec::new()), // No response needed
 		}
 	}
@@ -725,9 +933,9 @@ impl crate::service::network::protocol::ProtocolHandler for SyncProtocolHandler
 		if response.is_empty() {
 			return Ok(());
 		}
+		self.record_bytes_received(response.len());
 
-		let message: SyncMessage =
-			serde_json::from_slice(&response).map_err(|e| NetworkingError::Serialization(e))?;
+		let message = SyncPayloadFormat::decode(&response)?;
 
 		debug!(
 			from_device = %from_device,
@@ -754,15 +962,13 @@ mod tests {
 	use super::*;
 	use crate::service::network::protocol::ProtocolHandler;
 
-	#[test]
-	fn test_handler_creation() {
-		// Test uses mock registry
+	fn test_device_registry(
+		temp_dir: &tempfile::TempDir,
+	) -> Arc<tokio::sync::RwLock<crate::service::network::device::DeviceRegistry>> {
 		use crate::crypto::key_manager::KeyManager;
 		use crate::device::DeviceManager;
 		use crate::service::network::device::DeviceRegistry;
-		use tempfile::TempDir;
 
-		let temp_dir = TempDir::new().unwrap();
 		let device_key_fallback = temp_dir.path().join("device_key");
 		let key_manager = Arc::new(
 			KeyManager::new_with_fallback(temp_dir.path().to_path_buf(), Some(device_key_fallback))
@@ -773,9 +979,145 @@ mod tests {
 		);
 		let logger = Arc::new(crate::service::network::utils::SilentLogger);
 		let registry = DeviceRegistry::new(device_manager, key_manager, logger);
-		let device_registry = Arc::new(tokio::sync::RwLock::new(registry));
+		Arc::new(tokio::sync::RwLock::new(registry))
+	}
+
+	#[test]
+	fn test_handler_creation() {
+		// Test uses mock registry
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let handler = SyncProtocolHandler::new(Uuid::new_v4(), test_device_registry(&temp_dir));
+		assert_eq!(handler.protocol_name().name(), "sync");
+	}
+
+	#[tokio::test]
+	async fn test_received_batch_counts_its_encoded_size() {
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let library_id = Uuid::new_v4();
+		let metrics = Arc::new(crate::service::sync::SyncMetricsCollector::new());
+		let mut handler = SyncProtocolHandler::new(library_id, test_device_registry(&temp_dir));
+		handler.set_metrics(metrics.clone());
+
+		let batch = SyncPayloadFormat::MessagePack
+			.encode(&SyncMessage::StateBatch {
+				library_id,
+				model_type: "entry".to_string(),
+				device_id: Uuid::new_v4(),
+				records: Vec::new(),
+			})
+			.unwrap();
+
+		// Counted as read, even though there is no PeerSync to apply it
+		let from_node = iroh::SecretKey::from_bytes(&[1u8; 32]).public();
+		assert!(handler
+			.handle_response(Uuid::new_v4(), from_node, batch.clone())
+			.await
+			.is_err());
+
+		assert_eq!(
+			metrics
+				.metrics()
+				.data_volume
+				.bytes_received
+				.load(std::sync::atomic::Ordering::Relaxed),
+			batch.len() as u64
+		);
+	}
+
+	async fn test_library_db(temp_dir: &tempfile::TempDir) -> crate::infra::db::Database {
+		let db = crate::infra::db::Database::create(&temp_dir.path().join("library.db"))
+			.await
+			.unwrap();
+		db.migrate().await.unwrap();
+		db
+	}
+
+	async fn insert_device(db: &sea_orm::DatabaseConnection, uuid: Uuid, slug: &str) {
+		use crate::infra::db::entities::device;
+		use sea_orm::{ActiveModelTrait, Set};
+
+		device::ActiveModel {
+			uuid: Set(uuid),
+			name: Set(slug.to_string()),
+			slug: Set(slug.to_string()),
+			os: Set("Linux".to_string()),
+			network_addresses: Set(Default::default()),
+			is_online: Set(false),
+			last_seen_at: Set(chrono::Utc::now()),
+			capabilities: Set(serde_json::json!({})),
+			created_at: Set(chrono::Utc::now()),
+			updated_at: Set(chrono::Utc::now()),
+			sync_enabled: Set(true),
+			..Default::default()
+		}
+		.insert(db)
+		.await
+		.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_unregistered_device_is_not_authorized() {
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let db = test_library_db(&temp_dir).await;
+
+		let library_id = Uuid::new_v4();
+		let member = Uuid::new_v4();
+		insert_device(db.conn(), member, "member").await;
+
+		ensure_library_member(db.conn(), library_id, member)
+			.await
+			.unwrap();
+
+		let stranger = Uuid::new_v4();
+		match ensure_library_member(db.conn(), library_id, stranger).await {
+			Err(NetworkingError::NotAuthorizedForLibrary {
+				device_id,
+				library_id: rejected_library,
+			}) => {
+				assert_eq!(device_id, stranger);
+				assert_eq!(rejected_library, library_id);
+			}
+			other => panic!("expected NotAuthorizedForLibrary, got {:?}", other),
+		}
+	}
 
-		let handler = SyncProtocolHandler::new(Uuid::new_v4(), device_registry);
-		assert_eq!(handler.protocol_name(), "sync");
+	#[tokio::test]
+	async fn test_membership_cache_only_remembers_members() {
+		use crate::infra::db::entities::device;
+		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let db = test_library_db(&temp_dir).await;
+		let cache = MembershipCache::default();
+		let library_id = Uuid::new_v4();
+
+		// A refusal isn't cached, so a device is let in as soon as it joins
+		let joiner = Uuid::new_v4();
+		assert!(matches!(
+			cache.ensure_member(db.conn(), library_id, joiner).await,
+			Err(NetworkingError::NotAuthorizedForLibrary { .. })
+		));
+		insert_device(db.conn(), joiner, "joiner").await;
+		cache
+			.ensure_member(db.conn(), library_id, joiner)
+			.await
+			.unwrap();
+
+		// A confirmation is answered from the cache without asking the table
+		device::Entity::delete_many()
+			.filter(device::Column::Uuid.eq(joiner))
+			.exec(db.conn())
+			.await
+			.unwrap();
+		cache
+			.ensure_member(db.conn(), library_id, joiner)
+			.await
+			.unwrap();
+
+		// ...but only for the library it was confirmed in
+		assert!(matches!(
+			cache.ensure_member(db.conn(), Uuid::new_v4(), joiner).await,
+			Err(NetworkingError::NotAuthorizedForLibrary { .. })
+		));
 	}
 }
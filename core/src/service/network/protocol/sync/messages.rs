@@ -52,6 +52,9 @@ pub enum SyncMessage {
 		deleted_uuids: Vec<Uuid>,
 		checkpoint: Option<String>,
 		has_more: bool,
+		/// Rough count of the records the whole backfill will send, on its first page
+		#[serde(default)]
+		estimated_total: Option<u64>,
 	},
 
 	// === LOG-BASED MESSAGES (Shared Resources) ===
@@ -72,6 +75,10 @@ pub enum SyncMessage {
 		library_id: Uuid,
 		since_hlc: Option<HLC>,
 		limit: usize,
+		/// Whether the requester resolves content hash hints in the snapshot;
+		/// older devices omit it and receive full records
+		#[serde(default)]
+		content_hints: bool,
 	},
 
 	/// Response with shared changes
@@ -82,6 +89,23 @@ pub enum SyncMessage {
 		has_more: bool,
 	},
 
+	/// Request full records for content hashes missing locally
+	///
+	/// Sent after a snapshot carried content hash hints for records this device
+	/// doesn't have yet.
+	ContentRequest {
+		library_id: Uuid,
+		model_type: String,
+		content_hashes: Vec<String>,
+	},
+
+	/// Response with full records for requested content hashes
+	ContentResponse {
+		library_id: Uuid,
+		model_type: String,
+		records: Vec<StateRecord>,
+	},
+
 	/// Acknowledge shared changes (for pruning)
 	AckSharedChanges {
 		library_id: Uuid,
@@ -136,6 +160,9 @@ pub enum SyncMessage {
 
 	/// Error response
 	Error { library_id: Uuid, message: String },
+
+	/// The requesting device isn't registered in the library
+	NotAuthorized { library_id: Uuid, device_id: Uuid },
 }
 
 /// Single state record in batches
@@ -158,13 +185,16 @@ impl SyncMessage {
 			| SyncMessage::SharedChangeBatch { library_id, .. }
 			| SyncMessage::SharedChangeRequest { library_id, .. }
 			| SyncMessage::SharedChangeResponse { library_id, .. }
+			| SyncMessage::ContentRequest { library_id, .. }
+			| SyncMessage::ContentResponse { library_id, .. }
 			| SyncMessage::AckSharedChanges { library_id, .. }
 			| SyncMessage::Heartbeat { library_id, .. }
 			| SyncMessage::WatermarkExchangeRequest { library_id, .. }
 			| SyncMessage::WatermarkExchangeResponse { library_id, .. }
 			| SyncMessage::EventLogRequest { library_id, .. }
 			| SyncMessage::EventLogResponse { library_id, .. }
-			| SyncMessage::Error { library_id, .. } => *library_id,
+			| SyncMessage::Error { library_id, .. }
+			| SyncMessage::NotAuthorized { library_id, .. } => *library_id,
 		}
 	}
 
@@ -174,6 +204,7 @@ impl SyncMessage {
 			self,
 			SyncMessage::StateRequest { .. }
 				| SyncMessage::SharedChangeRequest { .. }
+				| SyncMessage::ContentRequest { .. }
 				| SyncMessage::Heartbeat { .. }
 				| SyncMessage::WatermarkExchangeRequest { .. }
 		)
@@ -190,6 +221,16 @@ impl SyncMessage {
 				| SyncMessage::AckSharedChanges { .. }
 		)
 	}
+
+	/// Number of records a change notification carries, zero for other messages
+	pub fn change_record_count(&self) -> u64 {
+		match self {
+			SyncMessage::StateChange { .. } | SyncMessage::SharedChange { .. } => 1,
+			SyncMessage::StateBatch { records, .. } => records.len() as u64,
+			SyncMessage::SharedChangeBatch { entries, .. } => entries.len() as u64,
+			_ => 0,
+		}
+	}
 }
 
 #[cfg(test)]
@@ -226,6 +267,7 @@ mod tests {
 		};
 		assert!(request.is_request());
 		assert!(!request.is_notification());
+		assert_eq!(request.change_record_count(), 0);
 
 		let change = SyncMessage::StateChange {
 			library_id,
@@ -237,5 +279,6 @@ mod tests {
 		};
 		assert!(!change.is_request());
 		assert!(change.is_notification());
+		assert_eq!(change.change_record_count(), 1);
 	}
 }
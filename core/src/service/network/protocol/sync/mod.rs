@@ -2,10 +2,18 @@
 //!
 //! Peer-to-peer sync protocol implementation
 
+pub mod format;
 pub mod handler;
 pub mod messages;
 pub mod multiplexer;
 
+pub use format::{SyncPayloadFormat, SUPPORTED_SYNC_FORMATS};
 pub use handler::SyncProtocolHandler;
 pub use messages::{StateRecord, SyncMessage};
 pub use multiplexer::SyncMultiplexer;
+
+/// Largest sync frame accepted from a peer
+///
+/// Backfill responses carry whole batches of records, so sync allows far more than
+/// the default per-message limit.
+pub const SYNC_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
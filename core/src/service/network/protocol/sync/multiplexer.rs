@@ -4,9 +4,16 @@
 //! Solves the problem of multiple libraries trying to register sync handlers
 //! when the protocol registry only allows one handler per protocol name.
 
-use super::{handler::SyncProtocolHandler, messages::SyncMessage};
+use super::{
+	format::SyncPayloadFormat, handler::SyncProtocolHandler, messages::SyncMessage,
+	SYNC_MAX_MESSAGE_SIZE,
+};
 use crate::service::{
-	network::{device::DeviceRegistry, protocol::ProtocolEvent, NetworkingError, Result},
+	network::{
+		device::DeviceRegistry,
+		protocol::{read_frame_if_any, ProtocolEvent},
+		NetworkingError, Result,
+	},
 	sync::{peer::PeerSync, BackfillManager},
 };
 use async_trait::async_trait;
@@ -58,6 +65,13 @@ impl SyncMultiplexer {
 		info!("Unregistered sync handler for library {}", library_id);
 	}
 
+	/// Count an encoded sync message received for a library
+	pub async fn record_bytes_received(&self, library_id: Uuid, bytes: usize) {
+		if let Some(handler) = self.libraries.read().await.get(&library_id) {
+			handler.record_bytes_received(bytes);
+		}
+	}
+
 	/// Handle sync message by routing to correct library
 	async fn handle_sync_message(
 		&self,
@@ -82,21 +96,25 @@ impl SyncMultiplexer {
 
 #[async_trait]
 impl crate::service::network::protocol::ProtocolHandler for SyncMultiplexer {
-	fn protocol_name(&self) -> &str {
-		"sync"
+	fn protocol_name(&self) -> crate::service::network::protocol::Protocol {
+		crate::service::network::protocol::Protocol::Sync
 	}
 
 	fn as_any(&self) -> &dyn std::any::Any {
 		self
 	}
 
+	fn max_message_size(&self) -> usize {
+		SYNC_MAX_MESSAGE_SIZE
+	}
+
 	async fn handle_stream(
 		&self,
 		mut send: Box<dyn tokio::io::AsyncWrite + Send + Unpin>,
 		mut recv: Box<dyn tokio::io::AsyncRead + Send + Unpin>,
 		remote_node_id: iroh::EndpointId,
 	) {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 
 		// Map node_id to device_id
 		let from_device = {
@@ -116,24 +134,23 @@ impl crate::service::network::protocol::ProtocolHandler for SyncMultiplexer {
 		};
 
 		// Read request with length prefix
-		let mut len_buf = [0u8; 4];
-		if let Err(e) = recv.read_exact(&mut len_buf).await {
-			tracing::debug!(
-				"SyncMultiplexer: Failed to read length (likely connection test): {}",
-				e
-			);
-			return;
-		}
-		let req_len = u32::from_be_bytes(len_buf) as usize;
-
-		let mut req_buf = vec![0u8; req_len];
-		if let Err(e) = recv.read_exact(&mut req_buf).await {
-			tracing::error!("SyncMultiplexer: Failed to read request: {}", e);
-			return;
-		}
+		let req_buf = match read_frame_if_any(&mut recv, SYNC_MAX_MESSAGE_SIZE).await {
+			Ok(Some(buf)) => buf,
+			Ok(None) => {
+				tracing::debug!(
+					"SyncMultiplexer: Stream closed before a request (likely connection test)"
+				);
+				return;
+			}
+			Err(e) => {
+				tracing::error!("SyncMultiplexer: Failed to read request: {}", e);
+				return;
+			}
+		};
 
 		// Deserialize to get library_id
-		let message: SyncMessage = match serde_json::from_slice(&req_buf) {
+		let format = SyncPayloadFormat::detect(&req_buf);
+		let message = match SyncPayloadFormat::decode(&req_buf) {
 			Ok(msg) => msg,
 			Err(e) => {
 				tracing::error!("SyncMultiplexer: Failed to deserialize: {}", e);
@@ -142,6 +159,7 @@ impl crate::service::network::protocol::ProtocolHandler for SyncMultiplexer {
 		};
 
 		let library_id = message.library_id();
+		self.record_bytes_received(library_id, req_buf.len()).await;
 		tracing::debug!(
 			"SyncMultiplexer: Routing message for library {} from device {}",
 			library_id,
@@ -149,8 +167,22 @@ impl crate::service::network::protocol::ProtocolHandler for SyncMultiplexer {
 		);
 
 		// Handle and get response
+		let is_request = message.is_request();
 		let response_opt = match self.handle_sync_message(from_device, message).await {
 			Ok(resp) => resp,
+			// Tell the requester why, so it can ask for access instead of timing out
+			Err(
+				e @ NetworkingError::NotAuthorizedForLibrary {
+					device_id,
+					library_id,
+				},
+			) if is_request => {
+				warn!("SyncMultiplexer: {}", e);
+				Some(SyncMessage::NotAuthorized {
+					library_id,
+					device_id,
+				})
+			}
 			Err(e) => {
 				tracing::error!("SyncMultiplexer: Failed to handle message: {}", e);
 				return;
@@ -159,7 +191,7 @@ impl crate::service::network::protocol::ProtocolHandler for SyncMultiplexer {
 
 		// Send response if needed
 		if let Some(response) = response_opt {
-			let resp_bytes = match serde_json::to_vec(&response) {
+			let resp_bytes = match format.encode(&response) {
 				Ok(bytes) => bytes,
 				Err(e) => {
 					tracing::error!("SyncMultiplexer: Failed to serialize response: {}", e);
@@ -181,13 +213,14 @@ impl crate::service::network::protocol::ProtocolHandler for SyncMultiplexer {
 	}
 
 	async fn handle_request(&self, from_device: Uuid, request: Vec<u8>) -> Result<Vec<u8>> {
-		let message: SyncMessage =
-			serde_json::from_slice(&request).map_err(|e| NetworkingError::Serialization(e))?;
+		let format = SyncPayloadFormat::detect(&request);
+		let message = SyncPayloadFormat::decode(&request)?;
+		self.record_bytes_received(message.library_id(), request.len())
+			.await;
 
 		match self.handle_sync_message(from_device, message).await? {
-			Some(response) => {
-				serde_json::to_vec(&response).map_err(|e| NetworkingError::Serialization(e))
-			}
+			// Answer in the format the request came in
+			Some(response) => format.encode(&response),
 			None => Ok(Vec::new()),
 		}
 	}
@@ -202,8 +235,9 @@ impl crate::service::network::protocol::ProtocolHandler for SyncMultiplexer {
 			return Ok(());
 		}
 
-		let message: SyncMessage =
-			serde_json::from_slice(&response).map_err(|e| NetworkingError::Serialization(e))?;
+		let message = SyncPayloadFormat::decode(&response)?;
+		self.record_bytes_received(message.library_id(), response.len())
+			.await;
 
 		// Process response message
 		self.handle_sync_message(from_device, message).await?;
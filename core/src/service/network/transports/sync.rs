@@ -5,7 +5,10 @@
 
 use crate::{
 	infra::sync::NetworkTransport,
-	service::network::{protocol::sync::messages::SyncMessage, NetworkingError},
+	service::network::{
+		protocol::sync::{messages::SyncMessage, SyncPayloadFormat},
+		NetworkingError,
+	},
 };
 use anyhow::Result;
 use std::sync::Arc;
@@ -26,22 +29,23 @@ impl NetworkTransport for NetworkingService {
 	/// # Implementation Details
 	///
 	/// 1. Look up NodeId for device UUID via DeviceRegistry
-	/// 2. Serialize the SyncMessage to JSON bytes
+	/// 2. Serialize the SyncMessage in the payload format negotiated with the peer
 	/// 3. Send via Iroh endpoint using the sync protocol ALPN
 	/// 4. Handle errors gracefully (device may be offline)
 	async fn send_sync_message(&self, target_device: Uuid, message: SyncMessage) -> Result<()> {
 		// 1. Look up NodeId for device UUID via public getter
 		let device_registry_arc = self.device_registry();
-		let node_id = {
+		let (node_id, format) = {
 			let registry = device_registry_arc.read().await;
-			registry
+			let node_id = registry
 				.get_node_id_for_device(target_device)
 				.ok_or_else(|| {
 					anyhow::anyhow!(
 						"Device {} not found in registry (not paired or offline)",
 						target_device
 					)
-				})?
+				})?;
+			(node_id, registry.sync_format(target_device))
 		};
 
 		tracing::info!(
@@ -52,8 +56,9 @@ impl NetworkTransport for NetworkingService {
 			message.library_id()
 		);
 
-		// 2. Serialize message to bytes
-		let bytes = serde_json::to_vec(&message)
+		// 2. Serialize message in the format negotiated with the peer
+		let bytes = format
+			.encode(&message)
 			.map_err(|e| anyhow::anyhow!("Failed to serialize sync message: {}", e))?;
 
 		// 3. Get or create connection (with caching for massive performance improvement)
@@ -111,10 +116,12 @@ impl NetworkTransport for NetworkingService {
 			// Track outbound connection so we can receive incoming streams on it
 			if let Some(cmd_sender) = self.command_sender() {
 				use crate::service::network::core::event_loop::EventLoopCommand;
-				let _ = cmd_sender.send(EventLoopCommand::TrackOutboundConnection {
-					node_id,
-					conn: new_conn.clone(),
-				});
+				let _ = cmd_sender
+					.send(EventLoopCommand::TrackOutboundConnection {
+						node_id,
+						conn: new_conn.clone(),
+					})
+					.await;
 			}
 
 			new_conn
@@ -162,21 +169,22 @@ impl NetworkTransport for NetworkingService {
 		target_device: Uuid,
 		request: SyncMessage,
 	) -> Result<SyncMessage> {
-		use tokio::io::{AsyncReadExt, AsyncWriteExt};
+		use tokio::io::AsyncWriteExt;
 		use tokio::time::{timeout, Duration};
 
 		// Look up NodeId for device UUID
 		let device_registry_arc = self.device_registry();
-		let node_id = {
+		let (node_id, format) = {
 			let registry = device_registry_arc.read().await;
-			registry
+			let node_id = registry
 				.get_node_id_for_device(target_device)
 				.ok_or_else(|| {
 					anyhow::anyhow!(
 						"Device {} not found in registry (not paired or offline)",
 						target_device
 					)
-				})?
+				})?;
+			(node_id, registry.sync_format(target_device))
 		};
 
 		debug!(
@@ -241,10 +249,12 @@ impl NetworkTransport for NetworkingService {
 			// Track it
 			if let Some(cmd_sender) = self.command_sender() {
 				use crate::service::network::core::event_loop::EventLoopCommand;
-				let _ = cmd_sender.send(EventLoopCommand::TrackOutboundConnection {
-					node_id,
-					conn: new_conn.clone(),
-				});
+				let _ = cmd_sender
+					.send(EventLoopCommand::TrackOutboundConnection {
+						node_id,
+						conn: new_conn.clone(),
+					})
+					.await;
 			}
 
 			new_conn
@@ -257,7 +267,8 @@ impl NetworkTransport for NetworkingService {
 			.map_err(|e| anyhow::anyhow!("Failed to open bidirectional stream: {}", e))?;
 
 		// Serialize and send request
-		let req_bytes = serde_json::to_vec(&request)
+		let req_bytes = format
+			.encode(&request)
 			.map_err(|e| anyhow::anyhow!("Failed to serialize sync request: {}", e))?;
 
 		let len = req_bytes.len() as u32;
@@ -276,19 +287,12 @@ impl NetworkTransport for NetworkingService {
 
 		// Read response with timeout
 		let result = timeout(Duration::from_secs(60), async {
-			let mut len_buf = [0u8; 4];
-			recv.read_exact(&mut len_buf)
-				.await
-				.map_err(|e| anyhow::anyhow!("Failed to read response length: {}", e))?;
-			let resp_len = u32::from_be_bytes(len_buf) as usize;
-
-			debug!("Receiving sync response of {} bytes", resp_len);
-
-			let mut resp_buf = vec![0u8; resp_len];
-			recv.read_exact(&mut resp_buf)
-				.await
-				.map_err(|e| anyhow::anyhow!("Failed to read response: {}", e))?;
-			Ok::<_, anyhow::Error>(resp_buf)
+			crate::service::network::protocol::read_frame(
+				&mut recv,
+				crate::service::network::protocol::sync::SYNC_MAX_MESSAGE_SIZE,
+			)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read response: {}", e))
 		})
 		.await;
 
@@ -304,8 +308,11 @@ impl NetworkTransport for NetworkingService {
 		};
 
 		// Deserialize response
-		let response: SyncMessage = serde_json::from_slice(&resp_buf)
+		let response = SyncPayloadFormat::decode(&resp_buf)
 			.map_err(|e| anyhow::anyhow!("Failed to deserialize sync response: {}", e))?;
+		self.sync_multiplexer()
+			.record_bytes_received(response.library_id(), resp_buf.len())
+			.await;
 
 		debug!(
 			device_uuid = %target_device,
@@ -313,6 +320,19 @@ impl NetworkTransport for NetworkingService {
 			"Received sync response"
 		);
 
+		// Surface a refusal as the typed error so callers can ask for access
+		if let SyncMessage::NotAuthorized {
+			library_id,
+			device_id,
+		} = response
+		{
+			return Err(NetworkingError::NotAuthorizedForLibrary {
+				device_id,
+				library_id,
+			}
+			.into());
+		}
+
 		Ok(response)
 	}
 
@@ -327,74 +347,38 @@ impl NetworkTransport for NetworkingService {
 		library_id: Uuid,
 		db: &sea_orm::DatabaseConnection,
 	) -> Result<Vec<Uuid>> {
-		use crate::infra::db::entities;
-		use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
-
-		// 1. Query devices table for THIS library with sync_enabled=true
-		let library_devices = entities::device::Entity::find()
-			.filter(entities::device::Column::SyncEnabled.eq(true))
-			.all(db)
-			.await
-			.map_err(|e| anyhow::anyhow!("Failed to query library devices: {}", e))?;
-
-		// 2. Get our own device ID to exclude from partners
+		// 1. Get our own device ID to exclude from partners
 		let our_device_id = self.device_id();
 
-		// 3. Get Iroh endpoint for checking connection state
-		let endpoint = self
-			.endpoint()
-			.ok_or_else(|| anyhow::anyhow!("Network endpoint not initialized"))?;
+		// 2. Partners are only reachable once the Iroh endpoint is up
+		if self.endpoint().is_none() {
+			return Err(anyhow::anyhow!("Network endpoint not initialized"));
+		}
 
-		// 4. Get DeviceRegistry to check which devices have NodeId mappings (paired devices)
+		// 3. Get DeviceRegistry to check which devices have NodeId mappings (paired devices)
 		let device_registry_arc = self.device_registry();
 		let registry = device_registry_arc.read().await;
 
-		// 5. Filter to OTHER devices in this library that are paired
+		// 4. Filter to OTHER sync-enabled devices in this library that are paired
 		// We don't check Iroh connection state because:
 		// - Connections may be idle (no active streams) but still reachable
 		// - send_sync_message establishes connections on-demand
 		// - Better to attempt send and handle failure than skip paired devices
-		let sync_partners: Vec<Uuid> = library_devices
-			.iter()
-			.filter(|device| {
-				// Exclude ourselves (can't sync with self)
-				if device.uuid == our_device_id {
-					return false;
-				}
-
+		let sync_partners =
+			crate::infra::sync::library_sync_partners(db, our_device_id, |device_id| {
 				// Must have NodeId mapping (paired via pairing protocol)
-				// This ensures device is known to our network layer
-				registry.get_node_id_for_device(device.uuid).is_some()
+				registry.get_node_id_for_device(device_id).is_some()
 			})
-			.map(|device| device.uuid)
-			.collect();
+			.await?;
 
 		tracing::debug!(
 			library_id = %library_id,
 			our_device_id = %our_device_id,
-			total_lib_devices = library_devices.len(),
-			sync_enabled_devices = library_devices.iter().filter(|d| d.sync_enabled).count(),
-			paired_devices = library_devices.iter().filter(|d| registry.get_node_id_for_device(d.uuid).is_some()).count(),
 			sync_partners = sync_partners.len(),
 			partner_uuids = ?sync_partners,
 			"Computed library sync partners"
 		);
 
-		// Debug each device's pairing status
-		for device in &library_devices {
-			if device.uuid != our_device_id {
-				let node_id = registry.get_node_id_for_device(device.uuid);
-				tracing::debug!(
-					device_uuid = %device.uuid,
-					device_name = %device.name,
-					sync_enabled = device.sync_enabled,
-					has_node_id = node_id.is_some(),
-					node_id = ?node_id,
-					"Device pairing status check"
-				);
-			}
-		}
-
 		Ok(sync_partners)
 	}
 
@@ -6,13 +6,29 @@
 //! - Automatic connection reuse across all protocols
 
 use crate::service::network::{NetworkingError, Result};
-use iroh::{endpoint::Connection, Endpoint, EndpointAddr, EndpointId};
-use std::collections::HashMap;
+use futures::{Stream, StreamExt};
+use iroh::{
+	discovery::{Discovery, DiscoveryItem},
+	endpoint::Connection,
+	Endpoint, EndpointAddr, EndpointId, RelayUrl,
+};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 use super::logging::NetworkLogger;
 
+/// How long a direct dial may take before falling back to the relay
+pub const DIRECT_DIAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait for discovery to report a peer's home relay
+const RELAY_RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+type ConnectionKey = (EndpointId, Vec<u8>);
+type ConnectionCache = Arc<RwLock<HashMap<ConnectionKey, Connection>>>;
+
 /// Get or create a connection to a specific node
 ///
 /// This implements Iroh's best practice of reusing persistent connections
@@ -29,38 +45,15 @@ use super::logging::NetworkLogger;
 /// * `Ok(Connection)` - Either cached or newly created connection
 /// * `Err(NetworkingError)` - If connection fails
 pub async fn get_or_create_connection(
-	connections: Arc<RwLock<HashMap<(EndpointId, Vec<u8>), Connection>>>,
+	connections: ConnectionCache,
 	endpoint: &Endpoint,
 	node_id: EndpointId,
 	alpn: &'static [u8],
 	logger: &Arc<dyn NetworkLogger>,
 ) -> Result<Connection> {
-	let alpn_vec = alpn.to_vec();
-	let cache_key = (node_id, alpn_vec.clone());
-
-	// Check cache first (keyed by both node_id AND alpn)
-	{
-		let connections_guard = connections.read().await;
-		if let Some(conn) = connections_guard.get(&cache_key) {
-			if conn.close_reason().is_none() {
-				logger
-					.debug(&format!(
-						"Reusing existing {} connection to node {}",
-						String::from_utf8_lossy(alpn),
-						node_id
-					))
-					.await;
-				return Ok(conn.clone());
-			} else {
-				logger
-					.debug(&format!(
-						"Cached {} connection to node {} is closed, creating new one",
-						String::from_utf8_lossy(alpn),
-						node_id
-					))
-					.await;
-			}
-		}
+	let cache_key = (node_id, alpn.to_vec());
+	if let Some(conn) = cached_connection(&connections, &cache_key, logger).await {
+		return Ok(conn);
 	}
 
 	// Create new connection with specified ALPN
@@ -79,10 +72,7 @@ pub async fn get_or_create_connection(
 		.map_err(|e| NetworkingError::ConnectionFailed(format!("Failed to connect: {}", e)))?;
 
 	// Cache the connection with (node_id, alpn) key
-	{
-		let mut connections_guard = connections.write().await;
-		connections_guard.insert(cache_key, conn.clone());
-	}
+	connections.write().await.insert(cache_key, conn.clone());
 
 	logger
 		.info(&format!(
@@ -94,3 +84,479 @@ pub async fn get_or_create_connection(
 
 	Ok(conn)
 }
+
+/// Get or create a connection, dialing via the peer's home relay if the direct dial fails
+///
+/// Behaves like `get_or_create_connection`, except that a direct dial which errors or
+/// takes longer than `direct_timeout` (e.g. hole-punching failed) is retried through
+/// the relay the peer published via discovery. A peer is only reachable on its own
+/// home relay, so dialing it through ours would not help.
+///
+/// # Returns
+/// * `Ok((Connection, via_relay))` - `via_relay` is true only for a new relayed connection
+/// * `Err(NetworkingError)` - If both dials fail, or the peer's home relay is unknown
+pub async fn get_or_create_connection_with_relay_fallback(
+	connections: ConnectionCache,
+	endpoint: &Endpoint,
+	node_id: EndpointId,
+	alpn: &'static [u8],
+	direct_timeout: Duration,
+	logger: &Arc<dyn NetworkLogger>,
+) -> Result<(Connection, bool)> {
+	let cache_key = (node_id, alpn.to_vec());
+	if let Some(conn) = cached_connection(&connections, &cache_key, logger).await {
+		return Ok((conn, false));
+	}
+
+	let direct = async {
+		endpoint
+			.connect(EndpointAddr::new(node_id), alpn)
+			.await
+			.map_err(|e| NetworkingError::ConnectionFailed(format!("Failed to connect: {}", e)))
+	};
+	// Only resolve the peer's relay once the direct dial has given up
+	let relay = async move {
+		let relay_url = peer_home_relay(endpoint, node_id).await.ok_or_else(|| {
+			NetworkingError::ConnectionFailed(format!("No home relay known for node {}", node_id))
+		})?;
+		endpoint
+			.connect(EndpointAddr::new(node_id).with_relay_url(relay_url), alpn)
+			.await
+			.map_err(|e| {
+				NetworkingError::ConnectionFailed(format!("Failed to connect via relay: {}", e))
+			})
+	};
+
+	let (conn, via_relay) =
+		dial_with_relay_fallback(direct, Some(relay), direct_timeout, logger).await?;
+	connections.write().await.insert(cache_key, conn.clone());
+
+	logger
+		.info(&format!(
+			"Created {} connection to node {}{}",
+			String::from_utf8_lossy(alpn),
+			node_id,
+			if via_relay { " via relay" } else { "" }
+		))
+		.await;
+
+	Ok((conn, via_relay))
+}
+
+/// Look up the home relay a peer published via discovery
+async fn peer_home_relay(endpoint: &Endpoint, node_id: EndpointId) -> Option<RelayUrl> {
+	let items = endpoint.discovery().resolve(node_id)?;
+	let addrs = items.map(|item| item.map(DiscoveryItem::into_endpoint_addr));
+	tokio::time::timeout(RELAY_RESOLVE_TIMEOUT, first_relay_url(addrs))
+		.await
+		.ok()
+		.flatten()
+}
+
+/// The first relay URL among a peer's discovered addresses
+async fn first_relay_url<S, E>(addrs: S) -> Option<RelayUrl>
+where
+	S: Stream<Item = std::result::Result<EndpointAddr, E>>,
+{
+	let mut addrs = std::pin::pin!(addrs);
+	while let Some(addr) = addrs.next().await {
+		if let Some(relay_url) = addr.ok().and_then(|addr| addr.relay_urls().next().cloned()) {
+			return Some(relay_url);
+		}
+	}
+	None
+}
+
+/// Await `direct`, falling back to `relay` if it errors or exceeds `direct_timeout`
+///
+/// Returns the connection and whether it came from the relay dial.
+pub async fn dial_with_relay_fallback<C, D, R>(
+	direct: D,
+	relay: Option<R>,
+	direct_timeout: Duration,
+	logger: &Arc<dyn NetworkLogger>,
+) -> Result<(C, bool)>
+where
+	D: Future<Output = Result<C>>,
+	R: Future<Output = Result<C>>,
+{
+	let direct_error = match tokio::time::timeout(direct_timeout, direct).await {
+		Ok(Ok(conn)) => return Ok((conn, false)),
+		Ok(Err(e)) => e.to_string(),
+		Err(_) => format!("timed out after {:?}", direct_timeout),
+	};
+
+	let Some(relay) = relay else {
+		return Err(NetworkingError::ConnectionFailed(format!(
+			"Direct dial failed ({}) and no relay is available",
+			direct_error
+		)));
+	};
+
+	logger
+		.warn(&format!(
+			"Direct dial failed ({}), falling back to relay",
+			direct_error
+		))
+		.await;
+
+	relay.await.map(|conn| (conn, true))
+}
+
+/// Return the cached connection for `cache_key` if it is still open
+async fn cached_connection(
+	connections: &ConnectionCache,
+	cache_key: &(EndpointId, Vec<u8>),
+	logger: &Arc<dyn NetworkLogger>,
+) -> Option<Connection> {
+	let (node_id, alpn) = cache_key;
+	let connections_guard = connections.read().await;
+	let conn = connections_guard.get(cache_key)?;
+
+	if conn.close_reason().is_none() {
+		logger
+			.debug(&format!(
+				"Reusing existing {} connection to node {}",
+				String::from_utf8_lossy(alpn),
+				node_id
+			))
+			.await;
+		Some(conn.clone())
+	} else {
+		logger
+			.debug(&format!(
+				"Cached {} connection to node {} is closed, creating new one",
+				String::from_utf8_lossy(alpn),
+				node_id
+			))
+			.await;
+		None
+	}
+}
+
+/// Stream frames one health ping exchange may add on either end
+///
+/// The ping and the pong are each written as a length prefix and a body and
+/// may end with a finishing frame, and both directions count on both ends.
+pub const PING_EXCHANGE_FRAMES: u64 = 8;
+
+/// Tracks when each cached connection last carried application data
+///
+/// Iroh keeps a connection "established" while QUIC keep-alives get through, which
+/// says nothing about whether anything uses it. Activity is read off the count of
+/// stream frames sent and received, so every protocol's traffic counts without
+/// reporting it here. Health pings are the exception: both ends record them, and
+/// the frames they account for don't count as activity.
+#[derive(Debug, Default)]
+pub struct ConnectionActivity {
+	seen: HashMap<ConnectionKey, (u64, Instant)>,
+	/// Health pings sent or answered on a connection since it was last observed
+	pings: HashMap<ConnectionKey, u64>,
+	/// Nodes whose last connection the sweep closed for being idle
+	idle_closed: HashSet<EndpointId>,
+}
+
+/// Connection activity shared by the idle sweep and the health pings
+pub type SharedConnectionActivity = Arc<parking_lot::Mutex<ConnectionActivity>>;
+
+impl ConnectionActivity {
+	/// Record a connection's stream frame count and return when it was last active
+	///
+	/// A connection seen for the first time, or whose count moved by more than
+	/// the health pings recorded since the last observation, is active as of `now`.
+	pub fn observe(&mut self, key: &ConnectionKey, stream_frames: u64, now: Instant) -> Instant {
+		let pings = self.pings.remove(key).unwrap_or(0);
+		self.idle_closed.remove(&key.0);
+		match self.seen.get_mut(key) {
+			Some((frames, last_activity)) => {
+				let moved = stream_frames.saturating_sub(*frames);
+				*frames = stream_frames;
+				if moved > pings * PING_EXCHANGE_FRAMES {
+					*last_activity = now;
+				}
+				*last_activity
+			}
+			None => {
+				self.seen.insert(key.clone(), (stream_frames, now));
+				now
+			}
+		}
+	}
+
+	/// Note a health ping sent or answered on a connection
+	pub fn record_ping(&mut self, key: &ConnectionKey) {
+		*self.pings.entry(key.clone()).or_default() += 1;
+	}
+
+	/// Whether the sweep closed every connection to a node for being idle
+	///
+	/// Cleared once the node has a cached connection again.
+	pub fn was_idle_closed(&self, node_id: &EndpointId) -> bool {
+		self.idle_closed.contains(node_id)
+	}
+
+	/// Drop tracking for connections no longer in the cache
+	pub fn retain(&mut self, mut keep: impl FnMut(&ConnectionKey) -> bool) {
+		self.seen.retain(|key, _| keep(key));
+		self.pings.retain(|key, _| keep(key));
+	}
+}
+
+/// Stream frames sent and received on a connection so far
+fn stream_frames(conn: &Connection) -> u64 {
+	let stats = conn.stats();
+	stats.frame_tx.stream + stats.frame_rx.stream
+}
+
+/// Close and evict cached connections with no traffic for `idle_timeout`
+///
+/// Connections that already closed are evicted as well. Nodes left without a
+/// connection because theirs went idle are remembered in `activity`. Returns the
+/// evicted keys.
+pub async fn sweep_idle_connections(
+	connections: &ConnectionCache,
+	activity: &parking_lot::Mutex<ConnectionActivity>,
+	idle_timeout: Duration,
+	now: Instant,
+	logger: &Arc<dyn NetworkLogger>,
+) -> Vec<ConnectionKey> {
+	let mut evicted = Vec::new();
+	{
+		let mut connections = connections.write().await;
+		let mut activity = activity.lock();
+		let mut went_idle = HashSet::new();
+		connections.retain(|key, conn| {
+			let last_activity = activity.observe(key, stream_frames(conn), now);
+			let idle = now.saturating_duration_since(last_activity) >= idle_timeout;
+			if !idle && conn.close_reason().is_none() {
+				return true;
+			}
+			if idle {
+				went_idle.insert(key.0);
+			}
+			conn.close(0u32.into(), b"idle");
+			evicted.push(key.clone());
+			false
+		});
+		activity.retain(|key| connections.contains_key(key));
+		for node_id in went_idle {
+			if !connections.keys().any(|(nid, _)| *nid == node_id) {
+				activity.idle_closed.insert(node_id);
+			}
+		}
+	}
+
+	for (node_id, alpn) in &evicted {
+		logger
+			.debug(&format!(
+				"Evicted idle {} connection to node {}",
+				String::from_utf8_lossy(alpn),
+				node_id
+			))
+			.await;
+	}
+	evicted
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::service::network::utils::SilentLogger;
+
+	fn logger() -> Arc<dyn NetworkLogger> {
+		Arc::new(SilentLogger)
+	}
+
+	#[tokio::test]
+	async fn test_direct_dial_success_skips_relay() {
+		let (conn, via_relay) = dial_with_relay_fallback(
+			async { Ok("direct") },
+			Some(async { Ok("relay") }),
+			Duration::from_secs(1),
+			&logger(),
+		)
+		.await
+		.unwrap();
+
+		assert_eq!(conn, "direct");
+		assert!(!via_relay);
+	}
+
+	#[tokio::test]
+	async fn test_stalled_direct_dial_falls_back_to_relay() {
+		// Hole-punching never completes
+		let direct = std::future::pending::<Result<&str>>();
+		let (conn, via_relay) = dial_with_relay_fallback(
+			direct,
+			Some(async { Ok("relay") }),
+			Duration::from_millis(50),
+			&logger(),
+		)
+		.await
+		.unwrap();
+
+		assert_eq!(conn, "relay");
+		assert!(via_relay);
+	}
+
+	#[tokio::test]
+	async fn test_failed_direct_dial_falls_back_to_relay() {
+		let direct = async {
+			Err::<&str, _>(NetworkingError::ConnectionFailed(
+				"no direct path".to_string(),
+			))
+		};
+		let (conn, via_relay) = dial_with_relay_fallback(
+			direct,
+			Some(async { Ok("relay") }),
+			Duration::from_secs(1),
+			&logger(),
+		)
+		.await
+		.unwrap();
+
+		assert_eq!(conn, "relay");
+		assert!(via_relay);
+	}
+
+	#[tokio::test]
+	async fn test_failed_direct_dial_without_relay_errors() {
+		let direct = std::future::pending::<Result<&str>>();
+		let relay: Option<std::future::Ready<Result<&str>>> = None;
+		let result =
+			dial_with_relay_fallback(direct, relay, Duration::from_millis(50), &logger()).await;
+
+		assert!(matches!(result, Err(NetworkingError::ConnectionFailed(_))));
+	}
+
+	#[tokio::test]
+	async fn test_relay_fallback_uses_peer_home_relay() {
+		let peer = iroh::SecretKey::from_bytes(&[3u8; 32]).public();
+		let home_relay: RelayUrl = "https://relay.peer.example".parse().unwrap();
+		let addrs = futures::stream::iter(vec![
+			Err("lookup failed"),
+			Ok(EndpointAddr::new(peer)),
+			Ok(EndpointAddr::new(peer).with_relay_url(home_relay.clone())),
+		]);
+
+		assert_eq!(first_relay_url(addrs).await, Some(home_relay));
+	}
+
+	#[tokio::test]
+	async fn test_peer_without_published_relay_has_no_home_relay() {
+		let peer = iroh::SecretKey::from_bytes(&[3u8; 32]).public();
+		let addrs = futures::stream::iter(vec![Ok::<_, ()>(EndpointAddr::new(peer))]);
+
+		assert_eq!(first_relay_url(addrs).await, None);
+	}
+
+	#[test]
+	fn test_traffic_resets_connection_activity() {
+		let mut activity = ConnectionActivity::default();
+		let key = (
+			iroh::SecretKey::from_bytes(&[1u8; 32]).public(),
+			b"spacedrive/test/1".to_vec(),
+		);
+		let start = Instant::now();
+		let later = start + Duration::from_secs(30);
+
+		assert_eq!(activity.observe(&key, 4, start), start);
+		assert_eq!(activity.observe(&key, 4, later), start);
+		assert_eq!(activity.observe(&key, 5, later), later);
+	}
+
+	#[test]
+	fn test_health_pings_are_not_activity() {
+		let mut activity = ConnectionActivity::default();
+		let key = (
+			iroh::SecretKey::from_bytes(&[1u8; 32]).public(),
+			b"spacedrive/test/1".to_vec(),
+		);
+		let start = Instant::now();
+		let later = start + Duration::from_secs(30);
+		let latest = later + Duration::from_secs(30);
+
+		activity.observe(&key, 4, start);
+		activity.record_ping(&key);
+		assert_eq!(
+			activity.observe(&key, 4 + PING_EXCHANGE_FRAMES, later),
+			start
+		);
+
+		// Traffic beyond what the ping accounts for is activity again
+		activity.record_ping(&key);
+		assert_eq!(
+			activity.observe(&key, 5 + 2 * PING_EXCHANGE_FRAMES, latest),
+			latest
+		);
+	}
+
+	#[tokio::test]
+	async fn test_idle_connection_is_evicted_on_next_sweep() {
+		use iroh::discovery::static_provider::StaticProvider;
+
+		const TEST_ALPN: &[u8] = b"spacedrive/test/1";
+		let server = Endpoint::builder()
+			.alpns(vec![TEST_ALPN.to_vec()])
+			.relay_mode(iroh::RelayMode::Disabled)
+			.bind()
+			.await
+			.unwrap();
+		let accept_task = tokio::spawn({
+			let server = server.clone();
+			async move {
+				let conn = server.accept().await.unwrap().await.unwrap();
+				conn.closed().await;
+			}
+		});
+
+		let addresses = StaticProvider::new();
+		addresses.add_endpoint_info(server.addr());
+		let client = Endpoint::builder()
+			.relay_mode(iroh::RelayMode::Disabled)
+			.discovery(addresses)
+			.bind()
+			.await
+			.unwrap();
+
+		let connections: ConnectionCache = Arc::new(RwLock::new(HashMap::new()));
+		let conn = get_or_create_connection(
+			connections.clone(),
+			&client,
+			server.id(),
+			TEST_ALPN,
+			&logger(),
+		)
+		.await
+		.unwrap();
+
+		let idle_timeout = Duration::from_secs(60);
+		let activity = parking_lot::Mutex::new(ConnectionActivity::default());
+		let start = Instant::now();
+		for now in [start, start + idle_timeout / 2] {
+			assert!(
+				sweep_idle_connections(&connections, &activity, idle_timeout, now, &logger())
+					.await
+					.is_empty()
+			);
+		}
+
+		// No traffic since the first sweep, so the one past the timeout closes it
+		let evicted = sweep_idle_connections(
+			&connections,
+			&activity,
+			idle_timeout,
+			start + idle_timeout,
+			&logger(),
+		)
+		.await;
+		assert_eq!(evicted, vec![(server.id(), TEST_ALPN.to_vec())]);
+		assert!(connections.read().await.is_empty());
+		assert!(conn.close_reason().is_some());
+		assert!(activity.lock().was_idle_closed(&server.id()));
+
+		client.close().await;
+		server.close().await;
+		accept_task.abort();
+	}
+}
@@ -102,6 +102,34 @@ impl NetworkIdentity {
 		}
 	}
 
+	/// X25519 agreement between this identity and another device's public key
+	///
+	/// Both Ed25519 keys are mapped to Curve25519, so two devices arrive at the same
+	/// secret from their own secret key and the other's public key alone.
+	pub fn shared_secret_with(&self, peer_public_key: &[u8]) -> Result<[u8; 32]> {
+		use ed25519_dalek::{SigningKey, VerifyingKey};
+
+		let peer_bytes: &[u8; 32] = peer_public_key.try_into().map_err(|_| {
+			NetworkingError::Protocol(format!(
+				"Invalid peer public key length: {}",
+				peer_public_key.len()
+			))
+		})?;
+		let peer = VerifyingKey::from_bytes(peer_bytes)
+			.map_err(|e| NetworkingError::Protocol(format!("Invalid peer public key: {}", e)))?;
+
+		let signing_key = SigningKey::from_bytes(&self.ed25519_seed);
+		let shared = (peer.to_montgomery() * signing_key.to_scalar()).to_bytes();
+		// A low-order peer key yields the identity point, which anyone could compute
+		if shared == [0u8; 32] {
+			return Err(NetworkingError::Protocol(
+				"Peer public key has low order".to_string(),
+			));
+		}
+
+		Ok(shared)
+	}
+
 	/// Get a deterministic device ID from the network identity
 	pub fn device_id(&self) -> Uuid {
 		// Create a deterministic UUID from the node ID
@@ -4,6 +4,9 @@ pub mod connection;
 pub mod identity;
 pub mod logging;
 
-pub use connection::get_or_create_connection;
+pub use connection::{
+	get_or_create_connection, get_or_create_connection_with_relay_fallback, sweep_idle_connections,
+	ConnectionActivity, SharedConnectionActivity, DIRECT_DIAL_TIMEOUT,
+};
 pub use identity::NetworkIdentity;
 pub use logging::{ConsoleLogger, NetworkLogger, SilentLogger};
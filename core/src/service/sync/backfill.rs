@@ -11,10 +11,15 @@ use super::{
 	metrics::SyncMetricsCollector,
 	peer::PeerSync,
 	protocol_handler::LogSyncHandler,
-	state::{select_backfill_peer, BackfillCheckpoint, DeviceSyncState, PeerInfo},
+	state::{
+		select_backfill_peer, BackfillCheckpoint, BackfillProgress, BackfillProgressTracker,
+		DeviceSyncState, PeerInfo,
+	},
 };
 use crate::{
-	infra::sync::{SharedChangeEntry, HLC},
+	infra::sync::{
+		BackfillCheckpoint as StoredCheckpoint, BackfillCheckpointStore, SharedChangeEntry, HLC,
+	},
 	service::network::protocol::sync::messages::{StateRecord, SyncMessage},
 };
 use anyhow::Result;
@@ -40,6 +45,9 @@ pub struct BackfillManager {
 
 	/// Pending shared change request channel
 	pending_shared_response: Arc<Mutex<Option<oneshot::Sender<SyncMessage>>>>,
+
+	/// Device-owned models being backfilled right now
+	progress: BackfillProgressTracker,
 }
 
 impl BackfillManager {
@@ -62,6 +70,7 @@ impl BackfillManager {
 			batch_aggregator,
 			pending_state_response: Arc::new(Mutex::new(None)),
 			pending_shared_response: Arc::new(Mutex::new(None)),
+			progress: BackfillProgressTracker::new(),
 		}
 	}
 
@@ -70,6 +79,13 @@ impl BackfillManager {
 		&self.metrics
 	}
 
+	/// Device-owned models being backfilled right now, by peer and model
+	///
+	/// Empty when no backfill or catch-up is pulling state.
+	pub fn backfill_progress(&self) -> Vec<BackfillProgress> {
+		self.progress.snapshot()
+	}
+
 	/// Get log handler for protocol operations
 	pub fn log_handler(&self) -> &Arc<LogSyncHandler> {
 		&self.log_handler
@@ -493,6 +509,28 @@ impl BackfillManager {
 				"Backfilling model type"
 			);
 
+			let model_progress = self.progress.start(peer, &model_type);
+
+			// A backfill interrupted part way keeps the count it started with, since
+			// the peer only counts what is left when it resumes
+			let sync_conn = self.peer_sync.peer_log().conn();
+			let local_device = self.peer_sync.device_id();
+			let saved =
+				BackfillCheckpointStore::load(sync_conn, local_device, peer, &model_type).await?;
+			let started_at = match &saved {
+				Some(saved) => {
+					current_checkpoint.records_applied = saved.records_synced as u64;
+					current_checkpoint.estimated_total = saved.estimated_total;
+					model_progress.record_page(
+						saved.resume_token.clone(),
+						saved.records_synced as u64,
+						saved.estimated_total,
+					);
+					saved.started_at
+				}
+				None => Utc::now(),
+			};
+
 			// Request state in batches with cursor-based pagination
 			let mut cursor_checkpoint: Option<String> = None;
 			loop {
@@ -513,6 +551,7 @@ impl BackfillManager {
 					has_more,
 					checkpoint: chk,
 					device_id: source_device_id,
+					estimated_total,
 					..
 				} = response
 				{
@@ -755,7 +794,32 @@ impl BackfillManager {
 					// Collect successfully applied UUIDs for batch event emission
 					let mut applied_uuids = Vec::new();
 
-					for data in processed_data {
+					// Apply deletions via registry. Peers send every tombstone since the
+					// watermark on each page, and none of them is for a record the page
+					// still carries, so they go first and the watermark below never
+					// passes one that was not applied.
+					for uuid in deleted_uuids {
+						crate::infra::sync::registry::apply_deletion(&model_type, uuid, db.clone())
+							.await
+							.map_err(|e| anyhow::anyhow!("{}", e))?;
+					}
+
+					// Commit the watermark every sub-batch so an interrupted backfill resumes
+					// from there
+					let record_timestamps: HashMap<Uuid, DateTime<Utc>> =
+						records.iter().map(|r| (r.uuid, r.timestamp)).collect();
+					let mut progress = crate::infra::sync::SubBatchProgress::new(
+						processed_data.iter().filter_map(|data| {
+							let uuid = data
+								.get("uuid")
+								.and_then(|v| v.as_str())
+								.and_then(|s| Uuid::parse_str(s).ok())?;
+							Some((uuid, *record_timestamps.get(&uuid)?))
+						}),
+					);
+					let sub_batch_size = self.config.batching.apply_sub_batch_size.max(1);
+
+					for (index, data) in processed_data.into_iter().enumerate() {
 						// Extract UUID before moving data
 						let record_uuid = data
 							.get("uuid")
@@ -773,6 +837,9 @@ impl BackfillManager {
 						// Track successfully applied UUID for batch event emission
 						if let Some(uuid) = record_uuid {
 							applied_uuids.push(uuid);
+							if let Some(timestamp) = record_timestamps.get(&uuid) {
+								progress.mark_applied(uuid, *timestamp);
+							}
 						}
 
 						// After successfully applying, resolve any records waiting for this one
@@ -810,6 +877,11 @@ impl BackfillManager {
 								}
 							}
 						}
+
+						if (index + 1) % sub_batch_size == 0 {
+							self.commit_sub_batch_watermark(peer, &model_type, &progress)
+								.await?;
+						}
 					}
 
 					// Emit resource events in batch for UI reactivity
@@ -835,21 +907,40 @@ impl BackfillManager {
 					self.metrics
 						.record_entries_synced(&model_type, records_count)
 						.await;
+					self.metrics
+						.record_records_pulled(peer, records_count)
+						.await;
 
 					// Feed batch aggregator for event logging
 					self.batch_aggregator
 						.add_records(model_type.clone(), records_count, Some(peer))
 						.await;
 
-					// Apply deletions via registry
-					for uuid in deleted_uuids {
-						crate::infra::sync::registry::apply_deletion(&model_type, uuid, db.clone())
-							.await
-							.map_err(|e| anyhow::anyhow!("{}", e))?;
-					}
+					// The whole page is applied
+					self.commit_sub_batch_watermark(peer, &model_type, &progress)
+						.await?;
 
-					current_checkpoint.update(chk.clone(), 0.5); // TODO: Calculate actual progress
+					current_checkpoint.record_page(chk.clone(), records_count, estimated_total);
 					current_checkpoint.save().await?;
+					BackfillCheckpointStore::save(
+						sync_conn,
+						StoredCheckpoint {
+							device_uuid: local_device,
+							peer_device_uuid: peer,
+							resource_type: model_type.clone(),
+							resume_token: chk.clone(),
+							last_watermark: max_timestamp,
+							records_synced: current_checkpoint.records_applied as usize,
+							estimated_total: current_checkpoint.estimated_total,
+							started_at,
+						},
+					)
+					.await?;
+					model_progress.record_page(
+						chk.clone(),
+						records_count,
+						current_checkpoint.estimated_total,
+					);
 
 					// Record pagination round
 					self.metrics.record_backfill_pagination_round();
@@ -863,12 +954,28 @@ impl BackfillManager {
 				}
 			}
 
+			BackfillCheckpointStore::delete(sync_conn, local_device, peer, &model_type).await?;
 			current_checkpoint.mark_completed(model_type);
 		}
 
 		Ok((current_checkpoint, max_timestamp))
 	}
 
+	/// Advance a resource watermark to what the applied sub-batches allow
+	async fn commit_sub_batch_watermark(
+		&self,
+		peer: Uuid,
+		model_type: &str,
+		progress: &crate::infra::sync::SubBatchProgress,
+	) -> Result<()> {
+		if let Some(watermark) = progress.committable() {
+			self.peer_sync
+				.update_resource_watermark(peer, model_type, watermark)
+				.await?;
+		}
+		Ok(())
+	}
+
 	/// Backfill shared resources
 	async fn backfill_shared_resources(
 		&self,
@@ -923,15 +1030,11 @@ impl BackfillManager {
 						// Get dependency-ordered list of models to prevent FK violations
 						// CRITICAL: Must apply parent models before children (e.g., user_metadata before user_metadata_tag)
 						let sync_order =
-							match crate::infra::sync::registry::compute_registry_sync_order().await
-							{
-								Ok(order) => order,
-								Err(e) => {
-									warn!("Failed to compute sync order, using unordered: {}", e);
-									// Fallback to unordered if dependency graph fails
-									state_map.keys().map(|k| k.clone()).collect::<Vec<_>>()
-								}
-							};
+							crate::infra::sync::order_models_for_apply(state_map.keys().cloned())
+								.await
+								.map_err(|e| {
+									anyhow::anyhow!("Failed to compute sync order: {}", e)
+								})?;
 
 						// Apply snapshot records in dependency order
 						for model_type in sync_order {
@@ -941,7 +1044,33 @@ impl BackfillManager {
 								None => continue,
 							};
 
-							if let Some(records_array) = records_value.as_array() {
+							if let Some(snapshot_entries) = records_value.as_array() {
+								// Content-bearing models arrive as hash hints: drop the
+								// ones we already hold and fetch the rest in full
+								let hints = crate::infra::sync::resolve_content_hints(
+									&model_type,
+									snapshot_entries,
+									self.peer_sync.db().clone(),
+								)
+								.await
+								.map_err(|e| {
+									anyhow::anyhow!("Failed to check local content: {}", e)
+								})?;
+								let mut records_array = hints.records;
+								if hints.skipped > 0 {
+									debug!(
+										model_type = %model_type,
+										skipped = hints.skipped,
+										"Skipped snapshot records whose content already exists locally"
+									);
+								}
+								if !hints.missing.is_empty() {
+									records_array.extend(
+										self.request_content(peer, &model_type, hints.missing)
+											.await?,
+									);
+								}
+
 								debug!(
 									model_type = %model_type,
 									count = records_array.len(),
@@ -951,153 +1080,136 @@ impl BackfillManager {
 								// Collect successfully applied UUIDs for batch event emission
 								let mut applied_snapshot_uuids: Vec<Uuid> = Vec::new();
 
-								for record_value in records_array {
-									if let Some(record_obj) = record_value.as_object() {
-										if let (Some(uuid_value), Some(data)) =
-											(record_obj.get("uuid"), record_obj.get("data"))
-										{
-											if let Some(uuid_str) = uuid_value.as_str() {
-												if let Ok(record_uuid) = Uuid::parse_str(uuid_str) {
-													// Construct a synthetic SharedChangeEntry for application
-													// Generate HLC for ordering (pre-sync data gets current HLC)
-													let hlc = {
-														let mut hlc_gen = self
-															.peer_sync
-															.hlc_generator()
-															.lock()
-															.await;
-														hlc_gen.next()
-													};
+								let mut snapshot_entries = Vec::with_capacity(records_array.len());
+								for record_value in &records_array {
+									if let Some(entry) =
+										self.snapshot_entry(&model_type, record_value).await
+									{
+										snapshot_entries.push(entry);
+									}
+								}
+
+								// Models that batch take the whole snapshot in one call. If that fails the
+								// records go through one at a time below, so dependency errors are still
+								// handled per record.
+								let db = self.peer_sync.db().clone();
+								if !snapshot_entries.is_empty()
+									&& crate::infra::sync::registry::has_shared_batch_apply(
+										&model_type,
+									)
+									.await
+								{
+									match crate::infra::sync::registry::apply_shared_changes_batch(
+										&model_type,
+										snapshot_entries.clone(),
+										db.clone(),
+									)
+									.await
+									{
+										Ok(()) => {
+											for entry in snapshot_entries.drain(..) {
+												applied_snapshot_uuids.push(entry.record_uuid);
+												self.resolve_snapshot_dependents(
+													entry.record_uuid,
+													&model_type,
+												)
+												.await;
+											}
+										}
+										Err(e) => {
+											debug!(
+												model_type = %model_type,
+												error = %e,
+												"Batch snapshot apply failed, applying records one at a time"
+											);
+										}
+									}
+								}
 
-													let entry = crate::infra::sync::SharedChangeEntry {
-														hlc,
-														model_type: model_type.clone(),
-														record_uuid,
-														change_type: crate::infra::sync::ChangeType::Insert,
-														data: data.clone(),
+								for entry in snapshot_entries {
+									let record_uuid = entry.record_uuid;
+									let data = &entry.data;
+									match crate::infra::sync::registry::apply_shared_change(
+										entry.clone(),
+										db.clone(),
+									)
+									.await
+									{
+										Ok(()) => {
+											// Track successfully applied UUID for batch event emission
+											applied_snapshot_uuids.push(record_uuid);
+											self.resolve_snapshot_dependents(
+												record_uuid,
+												&model_type,
+											)
+											.await;
+										}
+										Err(e) => {
+											let error_str = e.to_string();
+
+											// Check if this is a FK dependency error
+											if error_str.contains("Sync dependency missing")
+												|| error_str
+													.contains("FOREIGN KEY constraint failed")
+											{
+												// Try to extract the missing UUID from the error message
+												if let Some(missing_uuid) =
+													super::dependency::extract_missing_dependency_uuid(&error_str)
+												{
+													tracing::debug!(
+														record_uuid = %record_uuid,
+														model_type = %model_type,
+														missing_uuid = %missing_uuid,
+														"Snapshot record has missing FK dependency, buffering for retry"
+													);
+
+													// Buffer this shared change for retry when dependency arrives
+													self.peer_sync
+														.dependency_tracker()
+														.add_dependency(
+															missing_uuid,
+															super::state::BufferedUpdate::SharedChange(
+																entry.clone(),
+															),
+														)
+														.await;
+
+													continue; // Skip to next record
+												} else {
+													// FK error but can't extract UUID
+													let fk_mappings = crate::infra::sync::registry::get_fk_mappings(&model_type);
+													let uuid_fields: Vec<String> = if let Some(obj) = data.as_object() {
+														obj.keys()
+															.filter(|k| k.ends_with("_uuid"))
+															.map(|k| {
+																let value = obj.get(k).and_then(|v| v.as_str()).unwrap_or("null");
+																format!("{}={}", k, value)
+															})
+															.collect()
+													} else {
+														vec![]
 													};
 
-													let db = self.peer_sync.db().clone();
-													match crate::infra::sync::registry::apply_shared_change(entry.clone(), db.clone()).await {
-														Ok(()) => {
-															// Track successfully applied UUID for batch event emission
-															applied_snapshot_uuids.push(record_uuid);
-
-															// Resolve any state changes waiting for this shared resource
-															let waiting_updates = self
-																.peer_sync
-																.dependency_tracker()
-																.resolve(record_uuid)
-																.await;
-
-															if !waiting_updates.is_empty() {
-																tracing::debug!(
-																	resolved_uuid = %record_uuid,
-																	model_type = %model_type,
-																	waiting_count = waiting_updates.len(),
-																	"Resolving dependencies after current_state snapshot"
-																);
-
-																for update in waiting_updates {
-																	match update {
-																		super::state::BufferedUpdate::StateChange(dependent_change) => {
-																			if let Err(e) = self
-																				.peer_sync
-																				.apply_state_change(dependent_change.clone())
-																				.await
-																			{
-																				tracing::warn!(
-																					error = %e,
-																					record_uuid = %dependent_change.record_uuid,
-																					"Failed to apply dependent state change after current_state snapshot"
-																				);
-																			}
-																		}
-																		super::state::BufferedUpdate::SharedChange(dependent_entry) => {
-																			// Retry the shared change now that its dependency exists
-																			let entry_clone = dependent_entry.clone();
-																			let db = self.peer_sync.db().clone();
-																			if let Err(e) = crate::infra::sync::registry::apply_shared_change(entry_clone, db).await {
-																				tracing::warn!(
-																					error = %e,
-																					record_uuid = %dependent_entry.record_uuid,
-																					"Failed to apply dependent shared change after current_state snapshot"
-																				);
-																			}
-																		}
-																	}
-																}
-															}
-														}
-														Err(e) => {
-															let error_str = e.to_string();
-
-															// Check if this is a FK dependency error
-															if error_str.contains("Sync dependency missing")
-																|| error_str.contains("FOREIGN KEY constraint failed")
-															{
-																// Try to extract the missing UUID from the error message
-																if let Some(missing_uuid) =
-																	super::dependency::extract_missing_dependency_uuid(&error_str)
-																{
-																	tracing::debug!(
-																		record_uuid = %record_uuid,
-																		model_type = %model_type,
-																		missing_uuid = %missing_uuid,
-																		"Snapshot record has missing FK dependency, buffering for retry"
-																	);
-
-																	// Buffer this shared change for retry when dependency arrives
-																	self.peer_sync
-																		.dependency_tracker()
-																		.add_dependency(
-																			missing_uuid,
-																			super::state::BufferedUpdate::SharedChange(
-																				entry.clone(),
-																			),
-																		)
-																		.await;
-
-																	continue; // Skip to next record
-																} else {
-																	// FK error but can't extract UUID
-																	let fk_mappings = crate::infra::sync::registry::get_fk_mappings(&model_type);
-																	let uuid_fields: Vec<String> = if let Some(obj) = data.as_object() {
-																		obj.keys()
-																			.filter(|k| k.ends_with("_uuid"))
-																			.map(|k| {
-																				let value = obj.get(k).and_then(|v| v.as_str()).unwrap_or("null");
-																				format!("{}={}", k, value)
-																			})
-																			.collect()
-																	} else {
-																		vec![]
-																	};
-
-																	warn!(
-																		model_type = %model_type,
-																		uuid = %record_uuid,
-																		error = %e,
-																		fk_mappings = ?fk_mappings,
-																		uuid_fields = ?uuid_fields,
-																		"Failed to apply current_state snapshot record - FK constraint failed but cannot extract missing UUID"
-																	);
-
-																	continue; // Skip this record
-																}
-															}
-
-															// Non-dependency error - this is unexpected, log but continue
-															warn!(
-																model_type = %model_type,
-																uuid = %record_uuid,
-																error = %e,
-																"Failed to apply current_state snapshot record - unexpected error"
-															);
-														}
-													}
+													warn!(
+														model_type = %model_type,
+														uuid = %record_uuid,
+														error = %e,
+														fk_mappings = ?fk_mappings,
+														uuid_fields = ?uuid_fields,
+														"Failed to apply current_state snapshot record - FK constraint failed but cannot extract missing UUID"
+													);
+
+													continue; // Skip this record
 												}
 											}
+
+											// Non-dependency error - this is unexpected, log but continue
+											warn!(
+												model_type = %model_type,
+												uuid = %record_uuid,
+												error = %e,
+												"Failed to apply current_state snapshot record - unexpected error"
+											);
 										}
 									}
 								}
@@ -1320,6 +1432,9 @@ impl BackfillManager {
 				self.metrics
 					.record_entries_synced("shared", batch_size as u64)
 					.await;
+				self.metrics
+					.record_records_pulled(peer, batch_size as u64)
+					.await;
 
 				// Feed batch aggregator for event logging
 				self.batch_aggregator
@@ -1389,6 +1504,85 @@ impl BackfillManager {
 		}
 	}
 
+	/// Build the insert applied for one `{ "uuid", "data" }` snapshot record
+	///
+	/// Pre-sync data has no HLC of its own, so it gets the current one for ordering.
+	async fn snapshot_entry(
+		&self,
+		model_type: &str,
+		record: &serde_json::Value,
+	) -> Option<SharedChangeEntry> {
+		let record_uuid = record
+			.get("uuid")
+			.and_then(|uuid| uuid.as_str())
+			.and_then(|uuid| Uuid::parse_str(uuid).ok())?;
+		let data = record.get("data")?;
+		let hlc = {
+			let mut hlc_gen = self.peer_sync.hlc_generator().lock().await;
+			hlc_gen.next()
+		};
+
+		Some(SharedChangeEntry {
+			hlc,
+			model_type: model_type.to_string(),
+			record_uuid,
+			change_type: crate::infra::sync::ChangeType::Insert,
+			data: data.clone(),
+		})
+	}
+
+	/// Apply the changes that were waiting for a snapshot record to exist
+	async fn resolve_snapshot_dependents(&self, record_uuid: Uuid, model_type: &str) {
+		let waiting_updates = self
+			.peer_sync
+			.dependency_tracker()
+			.resolve(record_uuid)
+			.await;
+
+		if waiting_updates.is_empty() {
+			return;
+		}
+
+		tracing::debug!(
+			resolved_uuid = %record_uuid,
+			model_type = %model_type,
+			waiting_count = waiting_updates.len(),
+			"Resolving dependencies after current_state snapshot"
+		);
+
+		for update in waiting_updates {
+			match update {
+				super::state::BufferedUpdate::StateChange(dependent_change) => {
+					if let Err(e) = self
+						.peer_sync
+						.apply_state_change(dependent_change.clone())
+						.await
+					{
+						tracing::warn!(
+							error = %e,
+							record_uuid = %dependent_change.record_uuid,
+							"Failed to apply dependent state change after current_state snapshot"
+						);
+					}
+				}
+				super::state::BufferedUpdate::SharedChange(dependent_entry) => {
+					// Retry the shared change now that its dependency exists
+					let entry_clone = dependent_entry.clone();
+					let db = self.peer_sync.db().clone();
+					if let Err(e) =
+						crate::infra::sync::registry::apply_shared_change(entry_clone, db).await
+					{
+						tracing::warn!(
+							error = %e,
+							record_uuid = %dependent_entry.record_uuid,
+							"Failed to apply dependent shared change after current_state snapshot"
+						);
+					}
+				}
+			}
+		}
+	}
+
 	/// Request state batch from peer
 	///
 	/// Sends a StateRequest via bidirectional stream and waits for StateResponse.
@@ -1443,6 +1637,7 @@ impl BackfillManager {
 			library_id: self.library_id,
 			since_hlc,
 			limit,
+			content_hints: true,
 		};
 
 		// Measure RTT for peer latency tracking
@@ -1462,6 +1657,68 @@ impl BackfillManager {
 		Ok(response)
 	}
 
+	/// Fetch full records for content hashes a snapshot only hinted at
+	///
+	/// Sends one ContentRequest per [`CONTENT_HASH_BATCH_SIZE`] hashes so neither
+	/// side builds an oversized query. Returns them as `{ "uuid", "data" }`
+	/// snapshot entries.
+	///
+	/// [`CONTENT_HASH_BATCH_SIZE`]: crate::infra::sync::CONTENT_HASH_BATCH_SIZE
+	async fn request_content(
+		&self,
+		peer: Uuid,
+		model_type: &str,
+		content_hashes: Vec<String>,
+	) -> Result<Vec<serde_json::Value>> {
+		debug!(
+			peer = %peer,
+			model_type = %model_type,
+			count = content_hashes.len(),
+			"Requesting content missing locally"
+		);
+
+		let mut entries = Vec::with_capacity(content_hashes.len());
+		for chunk in content_hashes.chunks(crate::infra::sync::CONTENT_HASH_BATCH_SIZE) {
+			let request = SyncMessage::ContentRequest {
+				library_id: self.library_id,
+				model_type: model_type.to_string(),
+				content_hashes: chunk.to_vec(),
+			};
+
+			let response = self
+				.peer_sync
+				.network()
+				.send_sync_request(peer, request)
+				.await?;
+
+			match response {
+				SyncMessage::ContentResponse { records, .. } => {
+					entries.extend(records.into_iter().map(|record| {
+						serde_json::json!({
+							"uuid": record.uuid,
+							"data": record.data,
+						})
+					}))
+				}
+				SyncMessage::Error { message, .. } => {
+					return Err(anyhow::anyhow!(
+						"Peer failed to send {} content: {}",
+						model_type,
+						message
+					))
+				}
+				_ => {
+					return Err(anyhow::anyhow!(
+						"Unexpected response to ContentRequest for {}",
+						model_type
+					))
+				}
+			}
+		}
+
+		Ok(entries)
+	}
+
 	/// Handle peer disconnection during backfill
 	pub async fn on_peer_disconnected(&self, peer_id: Uuid) -> Result<()> {
 		let state = self.peer_sync.state().await;
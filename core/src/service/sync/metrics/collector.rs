@@ -185,6 +185,10 @@ impl SyncMetricsCollector {
 			.operations
 			.changes_applied
 			.fetch_add(count, std::sync::atomic::Ordering::Relaxed);
+		self.metrics
+			.throughput
+			.session_records_applied
+			.fetch_add(count, std::sync::atomic::Ordering::Relaxed);
 	}
 
 	/// Record rejected changes
@@ -295,6 +299,70 @@ impl SyncMetricsCollector {
 			.data_volume
 			.bytes_received
 			.fetch_add(bytes, std::sync::atomic::Ordering::Relaxed);
+		self.metrics
+			.throughput
+			.session_bytes_received
+			.fetch_add(bytes, std::sync::atomic::Ordering::Relaxed);
+	}
+
+	/// Record change records delivered to a peer
+	pub async fn record_records_pushed(&self, peer_id: Uuid, count: u64) {
+		if count == 0 {
+			return;
+		}
+		let mut pushed = self
+			.metrics
+			.data_volume
+			.records_pushed_per_peer
+			.write()
+			.await;
+		pushed
+			.entry(peer_id)
+			.or_insert_with(|| AtomicU64::new(0))
+			.fetch_add(count, std::sync::atomic::Ordering::Relaxed);
+	}
+
+	/// Record records received from a peer by backfill or catch-up
+	pub async fn record_records_pulled(&self, peer_id: Uuid, count: u64) {
+		if count == 0 {
+			return;
+		}
+		let mut pulled = self
+			.metrics
+			.data_volume
+			.records_pulled_per_peer
+			.write()
+			.await;
+		pulled
+			.entry(peer_id)
+			.or_insert_with(|| AtomicU64::new(0))
+			.fetch_add(count, std::sync::atomic::Ordering::Relaxed);
+	}
+
+	/// Total change records delivered to a peer
+	pub async fn records_pushed_to(&self, peer_id: Uuid) -> u64 {
+		let pushed = self
+			.metrics
+			.data_volume
+			.records_pushed_per_peer
+			.read()
+			.await;
+		pushed
+			.get(&peer_id)
+			.map_or(0, |count| count.load(std::sync::atomic::Ordering::Relaxed))
+	}
+
+	/// Total records received from a peer by backfill or catch-up
+	pub async fn records_pulled_from(&self, peer_id: Uuid) -> u64 {
+		let pulled = self
+			.metrics
+			.data_volume
+			.records_pulled_per_peer
+			.read()
+			.await;
+		pulled
+			.get(&peer_id)
+			.map_or(0, |count| count.load(std::sync::atomic::Ordering::Relaxed))
 	}
 
 	/// Record last sync time for peer
@@ -388,6 +456,10 @@ impl SyncMetricsCollector {
 			.errors
 			.total_errors
 			.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+		self.metrics
+			.throughput
+			.session_errors
+			.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
 		match error.error_type.as_str() {
 			"network" => {
@@ -435,6 +507,23 @@ impl SyncMetricsCollector {
 		);
 	}
 
+	/// Start a new throughput session, resetting the session counters
+	///
+	/// Lifetime totals are unaffected.
+	pub async fn start_throughput_session(&self) {
+		let throughput = &self.metrics.throughput;
+		*throughput.session_started_at.write().await = Utc::now();
+		throughput
+			.session_records_applied
+			.store(0, std::sync::atomic::Ordering::Relaxed);
+		throughput
+			.session_bytes_received
+			.store(0, std::sync::atomic::Ordering::Relaxed);
+		throughput
+			.session_errors
+			.store(0, std::sync::atomic::Ordering::Relaxed);
+	}
+
 	/// Record conflict detection
 	pub fn record_conflict_detected(&self) {
 		self.metrics
@@ -29,6 +29,9 @@ pub struct SyncMetricsSnapshot {
 
 	/// Error metrics
 	pub errors: ErrorSnapshot,
+
+	/// Apply-path throughput
+	pub throughput: ThroughputSnapshot,
 }
 
 /// State metrics snapshot
@@ -127,6 +130,60 @@ pub struct ErrorSnapshot {
 	pub conflicts_resolved_by_hlc: u64,
 }
 
+/// Throughput snapshot with lifetime and current-session figures
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ThroughputSnapshot {
+	pub lifetime: ThroughputWindow,
+	pub session: ThroughputWindow,
+}
+
+/// Counters and per-second rates over a single window
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct ThroughputWindow {
+	pub started_at: DateTime<Utc>,
+	pub elapsed_seconds: f64,
+	pub records_applied: u64,
+	pub bytes_received: u64,
+	pub errors: u64,
+	pub records_per_second: f64,
+	pub bytes_per_second: f64,
+	pub errors_per_second: f64,
+}
+
+impl ThroughputWindow {
+	fn new(
+		started_at: DateTime<Utc>,
+		now: DateTime<Utc>,
+		records_applied: u64,
+		bytes_received: u64,
+		errors: u64,
+	) -> Self {
+		let elapsed_seconds = now
+			.signed_duration_since(started_at)
+			.num_milliseconds()
+			.max(0) as f64
+			/ 1000.0;
+		let rate = |count: u64| {
+			if elapsed_seconds > 0.0 {
+				count as f64 / elapsed_seconds
+			} else {
+				0.0
+			}
+		};
+
+		Self {
+			started_at,
+			elapsed_seconds,
+			records_applied,
+			bytes_received,
+			errors,
+			records_per_second: rate(records_applied),
+			bytes_per_second: rate(bytes_received),
+			errors_per_second: rate(errors),
+		}
+	}
+}
+
 impl SyncMetricsSnapshot {
 	/// Create a snapshot from current metrics
 	pub async fn from_metrics(metrics: &Arc<SyncMetrics>) -> Self {
@@ -372,6 +429,34 @@ impl SyncMetricsSnapshot {
 				.load(std::sync::atomic::Ordering::Relaxed),
 		};
 
+		// Throughput snapshot
+		let session_started_at = *metrics.throughput.session_started_at.read().await;
+		let throughput = ThroughputSnapshot {
+			lifetime: ThroughputWindow::new(
+				metrics.throughput.lifetime_started_at,
+				now,
+				operations.changes_applied,
+				data_volume.bytes_received,
+				errors.total_errors,
+			),
+			session: ThroughputWindow::new(
+				session_started_at,
+				now,
+				metrics
+					.throughput
+					.session_records_applied
+					.load(std::sync::atomic::Ordering::Relaxed),
+				metrics
+					.throughput
+					.session_bytes_received
+					.load(std::sync::atomic::Ordering::Relaxed),
+				metrics
+					.throughput
+					.session_errors
+					.load(std::sync::atomic::Ordering::Relaxed),
+			),
+		};
+
 		Self {
 			timestamp: now,
 			state,
@@ -379,6 +464,7 @@ impl SyncMetricsSnapshot {
 			data_volume,
 			performance,
 			errors,
+			throughput,
 		}
 	}
 
@@ -28,6 +28,9 @@ pub struct SyncMetrics {
 
 	/// Error tracking
 	pub errors: ErrorMetrics,
+
+	/// Apply-path throughput for the current session
+	pub throughput: ThroughputMetrics,
 }
 
 impl Default for SyncMetrics {
@@ -38,6 +41,7 @@ impl Default for SyncMetrics {
 			data_volume: DataVolumeMetrics::default(),
 			performance: PerformanceMetrics::default(),
 			errors: ErrorMetrics::default(),
+			throughput: ThroughputMetrics::default(),
 		}
 	}
 }
@@ -144,6 +148,10 @@ pub struct DataVolumeMetrics {
 	pub bytes_sent: AtomicU64,
 	pub bytes_received: AtomicU64,
 
+	/// Records sent to and received from each peer
+	pub records_pushed_per_peer: Arc<RwLock<HashMap<Uuid, AtomicU64>>>,
+	pub records_pulled_per_peer: Arc<RwLock<HashMap<Uuid, AtomicU64>>>,
+
 	/// Last sync timestamps
 	pub last_sync_per_peer: Arc<RwLock<HashMap<Uuid, DateTime<Utc>>>>,
 	pub last_sync_per_model: Arc<RwLock<HashMap<String, DateTime<Utc>>>>,
@@ -156,6 +164,8 @@ impl Default for DataVolumeMetrics {
 			entries_by_device: Arc::new(RwLock::new(HashMap::new())),
 			bytes_sent: AtomicU64::new(0),
 			bytes_received: AtomicU64::new(0),
+			records_pushed_per_peer: Arc::new(RwLock::new(HashMap::new())),
+			records_pulled_per_peer: Arc::new(RwLock::new(HashMap::new())),
 			last_sync_per_peer: Arc::new(RwLock::new(HashMap::new())),
 			last_sync_per_model: Arc::new(RwLock::new(HashMap::new())),
 		}
@@ -227,6 +237,38 @@ impl Default for PerformanceMetrics {
 	}
 }
 
+/// Throughput counters for the apply path
+///
+/// Lifetime totals come from the operation, data volume and error counters;
+/// these track the same figures since the current sync session started so
+/// rates reflect recent activity rather than the whole process lifetime.
+#[derive(Debug)]
+pub struct ThroughputMetrics {
+	/// When metrics collection started
+	pub lifetime_started_at: DateTime<Utc>,
+
+	/// When the current sync session started
+	pub session_started_at: Arc<RwLock<DateTime<Utc>>>,
+
+	/// Session counters
+	pub session_records_applied: AtomicU64,
+	pub session_bytes_received: AtomicU64,
+	pub session_errors: AtomicU64,
+}
+
+impl Default for ThroughputMetrics {
+	fn default() -> Self {
+		let now = Utc::now();
+		Self {
+			lifetime_started_at: now,
+			session_started_at: Arc::new(RwLock::new(now)),
+			session_records_applied: AtomicU64::new(0),
+			session_bytes_received: AtomicU64::new(0),
+			session_errors: AtomicU64::new(0),
+		}
+	}
+}
+
 /// Histogram metric for tracking latency distributions
 #[derive(Debug)]
 pub struct HistogramMetric {
@@ -8,6 +8,7 @@ pub mod activity;
 pub mod backfill;
 pub mod dependency;
 pub mod metrics;
+pub mod pass;
 pub mod peer;
 pub mod protocol_handler;
 pub mod retry_queue;
@@ -32,6 +33,7 @@ use crate::library::Library;
 use crate::service::network::protocol::SyncProtocolHandler;
 
 pub use activity::SyncActivityAggregator;
+pub use pass::{PeerSyncPass, SyncPassStatus, SyncPassSummary};
 pub use peer::PeerSync;
 pub use state::{
 	select_backfill_peer, BackfillCheckpoint, BufferQueue, BufferedUpdate, DeviceSyncState,
@@ -130,6 +132,12 @@ pub struct SyncService {
 
 	/// Shutdown signal
 	shutdown_tx: Arc<Mutex<Option<tokio::sync::broadcast::Sender<()>>>>,
+
+	/// Wakes the sync loop early when the set of sync partners changes
+	partners_changed: Arc<tokio::sync::Notify>,
+
+	/// Partners a catch-up pass is running against (loop or manual trigger)
+	passes_in_flight: pass::PassesInFlight,
 }
 
 impl SyncService {
@@ -247,6 +255,8 @@ impl SyncService {
 			batch_aggregator,
 			is_running: Arc::new(AtomicBool::new(false)),
 			shutdown_tx: Arc::new(Mutex::new(None)),
+			partners_changed: Arc::new(tokio::sync::Notify::new()),
+			passes_in_flight: pass::PassesInFlight::default(),
 		})
 	}
 
@@ -275,6 +285,49 @@ impl SyncService {
 		&self.event_logger
 	}
 
+	/// Re-evaluate sync partners after a device's sync_enabled flag changed
+	///
+	/// Partners are recomputed from the devices table on every send, so this
+	/// only needs to drop messages still queued for a disabled device and wake
+	/// the sync loop instead of waiting out its interval.
+	pub async fn sync_partner_changed(&self, device_id: Uuid, sync_enabled: bool) {
+		if !sync_enabled {
+			let dropped = self.peer_sync.discard_retries_for_device(device_id).await;
+			if dropped > 0 {
+				debug!(
+					device_id = %device_id,
+					dropped,
+					"Dropped queued sync messages for disabled partner"
+				);
+			}
+		}
+
+		info!(
+			device_id = %device_id,
+			sync_enabled,
+			"Sync partner changed, re-evaluating partners"
+		);
+		self.partners_changed.notify_one();
+	}
+
+	/// Pause real-time sync without unpairing or disabling any partner
+	///
+	/// See [`PeerSync::pause`]. Returns false if sync was already paused.
+	pub async fn pause(&self) -> Result<bool> {
+		self.peer_sync.pause().await
+	}
+
+	/// Resume sync and flush everything held back while paused
+	///
+	/// See [`PeerSync::resume`]. Returns false if sync was not paused.
+	pub async fn resume(&self) -> Result<bool> {
+		let resumed = self.peer_sync.resume().await?;
+		if resumed {
+			self.partners_changed.notify_one();
+		}
+		Ok(resumed)
+	}
+
 	/// Get the batch aggregator
 	pub fn batch_aggregator(&self) -> &Arc<crate::infra::sync::BatchAggregator> {
 		&self.batch_aggregator
@@ -311,6 +364,8 @@ impl SyncService {
 		peer_sync: Arc<PeerSync>,
 		backfill_manager: Arc<BackfillManager>,
 		is_running: Arc<AtomicBool>,
+		partners_changed: Arc<tokio::sync::Notify>,
+		passes_in_flight: pass::PassesInFlight,
 		mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
 	) {
 		info!("Starting peer sync loop");
@@ -326,7 +381,8 @@ impl SyncService {
 
 					match state {
 						DeviceSyncState::Uninitialized => {
-							if !backfill_attempted {
+							// A paused library backfills once it is resumed
+							if !backfill_attempted && !peer_sync.is_paused().await {
 								// Get available sync partners from network (library-scoped)
 								match peer_sync.network().get_connected_sync_partners(
 									peer_sync.library_id(),
@@ -478,6 +534,12 @@ impl SyncService {
 											break; // Exit partner loop, will restart as Uninitialized
 										}
 
+										// A manually triggered pass may already be catching up with this peer
+										let Some(_pass) = pass::SyncPassGuard::try_begin(&passes_in_flight, partner_id) else {
+											debug!(peer = %partner_id, "Skipping catch-up - a sync pass is already running");
+											continue;
+										};
+
 										// Get watermarks for this specific peer (oldest across resource types)
 										let state_watermark = peer_watermarks.iter()
 											.map(|(_, ts)| *ts)
@@ -493,10 +555,13 @@ impl SyncService {
 											"Triggering incremental catch-up with peer"
 										);
 
-										// Transition to CatchingUp state
+										// Transition to CatchingUp state, unless sync was paused since the check above
 										{
-											let old_state = peer_sync.state().await;
 											let mut state = peer_sync.state.write().await;
+											let old_state = *state;
+											if !matches!(old_state, DeviceSyncState::Ready) {
+												break;
+											}
 											*state = DeviceSyncState::CatchingUp { buffered_count: 0 };
 											info!(
 												from_state = ?old_state,
@@ -518,13 +583,14 @@ impl SyncService {
 											Ok(()) => {
 												info!(peer = %partner_id, "Incremental catch-up completed");
 												retry_state.record_success();
-												// Transition back to Ready
+												// Transition back to Ready, or Paused if paused meanwhile
 												let old_state = peer_sync.state().await;
 												let mut state = peer_sync.state.write().await;
-												*state = DeviceSyncState::Ready;
+												let settled = peer_sync.settled_state().await;
+												*state = settled;
 												info!(
 													from_state = ?old_state,
-													to_state = ?DeviceSyncState::Ready,
+													to_state = ?settled,
 													peer = %partner_id,
 													reason = "catchup_completed",
 													"Sync state transition"
@@ -533,13 +599,14 @@ impl SyncService {
 											Err(e) => {
 												warn!(peer = %partner_id, error = %e, "Incremental catch-up failed");
 												retry_state.record_failure();
-												// Transition back to Ready even on error
+												// Transition back to Ready even on error (or Paused, if paused meanwhile)
 												let old_state = peer_sync.state().await;
 												let mut state = peer_sync.state.write().await;
-												*state = DeviceSyncState::Ready;
+												let settled = peer_sync.settled_state().await;
+												*state = settled;
 												info!(
 													from_state = ?old_state,
-													to_state = ?DeviceSyncState::Ready,
+													to_state = ?settled,
 													peer = %partner_id,
 													reason = "catchup_failed_but_continuing",
 													"Sync state transition"
@@ -569,9 +636,13 @@ impl SyncService {
 						}
 					}
 
-					// Sleep before next iteration (configurable)
-					tokio::time::sleep(tokio::time::Duration::from_secs(config.network.sync_loop_interval_secs))
-						.await;
+					// Sleep before next iteration (configurable), waking early if partners changed
+					tokio::select! {
+						_ = tokio::time::sleep(tokio::time::Duration::from_secs(config.network.sync_loop_interval_secs)) => {}
+						_ = partners_changed.notified() => {
+							debug!("Sync partners changed, re-evaluating immediately");
+						}
+					}
 				}
 			} => {
 				info!("Peer sync loop ended");
@@ -712,6 +783,9 @@ impl crate::service::Service for SyncService {
 		// Mark as running
 		self.is_running.store(true, Ordering::SeqCst);
 
+		// Session throughput counts from here
+		self.metrics.start_throughput_session().await;
+
 		// Start peer sync
 		self.peer_sync.start().await?;
 
@@ -720,8 +794,19 @@ impl crate::service::Service for SyncService {
 		let peer_sync = self.peer_sync.clone();
 		let backfill_manager = self.backfill_manager.clone();
 		let is_running = self.is_running.clone();
+		let partners_changed = self.partners_changed.clone();
+		let passes_in_flight = self.passes_in_flight.clone();
 		tokio::spawn(async move {
-			Self::run_sync_loop(config, peer_sync, backfill_manager, is_running, shutdown_rx).await;
+			Self::run_sync_loop(
+				config,
+				peer_sync,
+				backfill_manager,
+				is_running,
+				partners_changed,
+				passes_in_flight,
+				shutdown_rx,
+			)
+			.await;
 		});
 
 		// Spawn unified pruning task (runs hourly)
@@ -0,0 +1,207 @@
+//! On-demand sync passes
+//!
+//! A pass flushes messages queued for a partner and pulls its changes since our
+//! watermarks, the same catch-up the sync loop runs on its interval. Passes are
+//! tracked per partner, so a manual trigger and the loop never run one against
+//! the same partner at the same time.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::{DeviceSyncState, SyncService};
+
+/// Partners a sync pass is currently running against
+pub(crate) type PassesInFlight = Arc<Mutex<HashSet<Uuid>>>;
+
+/// Marks a partner as having a pass in flight until dropped
+pub(crate) struct SyncPassGuard {
+	peer: Uuid,
+	in_flight: PassesInFlight,
+}
+
+impl SyncPassGuard {
+	/// Claim `peer`, or None if a pass is already running against it
+	pub(crate) fn try_begin(in_flight: &PassesInFlight, peer: Uuid) -> Option<Self> {
+		if !in_flight.lock().unwrap().insert(peer) {
+			return None;
+		}
+		Some(Self {
+			peer,
+			in_flight: in_flight.clone(),
+		})
+	}
+}
+
+impl Drop for SyncPassGuard {
+	fn drop(&mut self) {
+		self.in_flight.lock().unwrap().remove(&self.peer);
+	}
+}
+
+/// How a pass against one partner ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncPassStatus {
+	Completed,
+	/// Another pass was already running against this partner
+	AlreadyRunning,
+	Failed(String),
+}
+
+/// Result of a pass against one partner
+#[derive(Debug, Clone)]
+pub struct PeerSyncPass {
+	pub device_id: Uuid,
+	/// Change records delivered to the partner during the pass, flushed from
+	/// the retry queue or broadcast directly
+	pub pushed: u64,
+	/// Records received from the partner
+	pub pulled: u64,
+	pub status: SyncPassStatus,
+}
+
+/// Result of a manually triggered sync pass
+#[derive(Debug, Clone, Default)]
+pub struct SyncPassSummary {
+	pub peers: Vec<PeerSyncPass>,
+}
+
+impl SyncPassSummary {
+	pub fn records_pushed(&self) -> u64 {
+		self.peers.iter().map(|p| p.pushed).sum()
+	}
+
+	pub fn records_pulled(&self) -> u64 {
+		self.peers.iter().map(|p| p.pulled).sum()
+	}
+}
+
+impl SyncService {
+	/// Run a sync pass now instead of waiting for the sync loop
+	///
+	/// Targets one partner when `peer` is given, otherwise every connected
+	/// partner. Partners that already have a pass running are reported as
+	/// [`SyncPassStatus::AlreadyRunning`] rather than synced twice.
+	pub async fn trigger_sync(&self, peer: Option<Uuid>) -> Result<SyncPassSummary> {
+		let peer_sync = &self.peer_sync;
+
+		match peer_sync.state().await {
+			// The loop may be catching up with another partner; the guard below
+			// keeps passes for the same partner apart
+			DeviceSyncState::Ready | DeviceSyncState::CatchingUp { .. } => {}
+			DeviceSyncState::Paused => {
+				return Err(anyhow::anyhow!("Sync is paused for this library"));
+			}
+			state => {
+				return Err(anyhow::anyhow!(
+					"Sync is not ready yet (state: {:?})",
+					state
+				));
+			}
+		}
+
+		let partners = peer_sync
+			.network()
+			.get_connected_sync_partners(peer_sync.library_id(), peer_sync.db())
+			.await?;
+
+		let targets = match peer {
+			Some(peer) if partners.contains(&peer) => vec![peer],
+			Some(peer) => {
+				return Err(anyhow::anyhow!(
+					"Device {} is not a connected sync partner",
+					peer
+				));
+			}
+			None => partners,
+		};
+
+		let mut summary = SyncPassSummary::default();
+		for device_id in targets {
+			let Some(_guard) = SyncPassGuard::try_begin(&self.passes_in_flight, device_id) else {
+				summary.peers.push(PeerSyncPass {
+					device_id,
+					pushed: 0,
+					pulled: 0,
+					status: SyncPassStatus::AlreadyRunning,
+				});
+				continue;
+			};
+
+			// Counted per partner, so traffic with other partners during the pass
+			// isn't attributed to this one
+			let pushed_before = self.metrics.records_pushed_to(device_id).await;
+			let pulled_before = self.metrics.records_pulled_from(device_id).await;
+
+			peer_sync.flush_retry_queue(Some(device_id)).await;
+
+			let state_watermark = peer_sync
+				.get_all_watermarks_for_peer(device_id)
+				.await
+				.unwrap_or_default()
+				.into_iter()
+				.map(|(_, ts)| ts)
+				.min();
+			let (_, shared_watermark) = peer_sync.get_watermarks().await;
+
+			let result = self
+				.backfill_manager
+				.catch_up_from_peer(
+					device_id,
+					state_watermark,
+					shared_watermark.map(|hlc| hlc.to_string()),
+				)
+				.await;
+			let pushed = self.metrics.records_pushed_to(device_id).await - pushed_before;
+			let pulled = self.metrics.records_pulled_from(device_id).await - pulled_before;
+
+			let status = match result {
+				Ok(()) => SyncPassStatus::Completed,
+				Err(e) => {
+					warn!(peer = %device_id, error = %e, "Triggered sync pass failed");
+					SyncPassStatus::Failed(e.to_string())
+				}
+			};
+
+			summary.peers.push(PeerSyncPass {
+				device_id,
+				pushed,
+				pulled,
+				status,
+			});
+		}
+
+		info!(
+			library_id = %peer_sync.library_id(),
+			peers = summary.peers.len(),
+			pushed = summary.records_pushed(),
+			pulled = summary.records_pulled(),
+			"Triggered sync pass finished"
+		);
+
+		Ok(summary)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_pass_guard_blocks_overlapping_pass() {
+		let in_flight = PassesInFlight::default();
+		let peer = Uuid::new_v4();
+
+		let guard = SyncPassGuard::try_begin(&in_flight, peer).unwrap();
+		assert!(SyncPassGuard::try_begin(&in_flight, peer).is_none());
+
+		// Other partners are independent
+		assert!(SyncPassGuard::try_begin(&in_flight, Uuid::new_v4()).is_some());
+
+		drop(guard);
+		assert!(SyncPassGuard::try_begin(&in_flight, peer).is_some());
+	}
+}
@@ -81,8 +81,8 @@ use crate::{
 	infra::{
 		event::{Event, EventBus},
 		sync::{
-			HLCGenerator, NetworkTransport, PeerLog, PeerLogError, ResourceWatermarkStore,
-			SharedChangeEntry, SystemTimeSource, HLC,
+			HLCGenerator, NetworkTransport, PeerLocationFilters, PeerLog, PeerLogError,
+			ResourceWatermarkStore, SharedChangeEntry, SystemTimeSource, HLC,
 		},
 	},
 	library::Library,
@@ -145,6 +145,9 @@ pub struct PeerSync {
 	/// Sync configuration
 	config: Arc<crate::infra::sync::SyncConfig>,
 
+	/// Library configuration (for per-peer location filters)
+	library_config: Arc<RwLock<crate::library::LibraryConfig>>,
+
 	/// General event bus (for emitting resource events, metrics)
 	event_bus: Arc<EventBus>,
 
@@ -208,6 +211,7 @@ impl PeerSync {
 			watermark_store,
 			backfill_manager: Arc::new(RwLock::new(None)),
 			config,
+			library_config: library.config_handle().clone(),
 			event_bus: library.event_bus().clone(),
 			sync_events: library.sync_events().clone(),
 			retry_queue: Arc::new(RetryQueue::new()),
@@ -244,6 +248,47 @@ impl PeerSync {
 		&self.network
 	}
 
+	/// Drop queued retries for a device that is no longer a sync partner
+	pub async fn discard_retries_for_device(&self, device_id: Uuid) -> usize {
+		self.retry_queue.remove_for_device(device_id).await
+	}
+
+	/// Number of messages waiting to be retried against a device
+	pub async fn queued_retries_for_device(&self, device_id: Uuid) -> usize {
+		self.retry_queue.len_for_device(device_id).await
+	}
+
+	/// Send queued retries now instead of waiting for their backoff
+	///
+	/// Only messages for `peer` are flushed when it is given. Messages that still
+	/// fail to send go back on the queue. Returns how many were sent.
+	pub async fn flush_retry_queue(&self, peer: Option<Uuid>) -> usize {
+		let mut sent = 0;
+		for (target_device, message) in self.retry_queue.take_for_device(peer).await {
+			match self
+				.network
+				.send_sync_message(target_device, message.clone())
+				.await
+			{
+				Ok(()) => {
+					sent += 1;
+					self.metrics
+						.record_records_pushed(target_device, message.change_record_count())
+						.await;
+				}
+				Err(e) => {
+					warn!(
+						target = %target_device,
+						error = %e,
+						"Flushing queued sync message failed, re-queueing"
+					);
+					self.retry_queue.enqueue(target_device, message).await;
+				}
+			}
+		}
+		sent
+	}
+
 	/// Get this device's ID
 	pub fn device_id(&self) -> Uuid {
 		self.device_id
@@ -507,6 +552,11 @@ impl PeerSync {
 			"Received watermark exchange response"
 		);
 
+		if self.is_paused().await {
+			debug!(peer = %peer_id, "Sync paused, deferring catch-up until resume");
+			return Ok(());
+		}
+
 		// Get our watermarks to compare
 		let (my_state_watermark, my_shared_watermark) = self.get_watermarks().await;
 
@@ -596,6 +646,7 @@ impl PeerSync {
 				library_id: self.library_id,
 				since_hlc: my_shared_watermark,
 				limit: self.config.batching.backfill_batch_size,
+				content_hints: false,
 			};
 
 			self.network
@@ -695,9 +746,11 @@ impl PeerSync {
 	/// Start background task to process retry queue
 	fn start_retry_processor(&self) {
 		let retry_queue = self.retry_queue.clone();
+		let state = self.state.clone();
 		let network = self.network.clone();
 		let is_running = self.is_running.clone();
 		let config = self.config.clone();
+		let metrics = self.metrics.clone();
 
 		tokio::spawn(async move {
 			info!("Started retry queue processor");
@@ -709,6 +762,11 @@ impl PeerSync {
 				))
 				.await;
 
+				// Queued messages wait until sync is resumed
+				if matches!(*state.read().await, DeviceSyncState::Paused) {
+					continue;
+				}
+
 				// Get messages ready for retry
 				let ready_messages = retry_queue.get_ready().await;
 
@@ -718,12 +776,11 @@ impl PeerSync {
 
 				// Attempt to send each message
 				for (target_device, message) in ready_messages {
-					match network
-						.send_sync_message(target_device, message.clone())
-						.await
-					{
+					let records = message.change_record_count();
+					match network.send_sync_message(target_device, message).await {
 						Ok(()) => {
 							debug!(target = %target_device, "Retry successful");
+							metrics.record_records_pushed(target_device, records).await;
 						}
 						Err(e) => {
 							warn!(
@@ -787,6 +844,7 @@ impl PeerSync {
 		let buffer = self.buffer.clone();
 		let last_realtime_activity = self.last_realtime_activity.clone();
 		let db = self.db.clone();
+		let library_config = self.library_config.clone();
 		let event_bus_for_emit = self.event_bus.clone();
 		let retry_queue = self.retry_queue.clone();
 		let mut subscriber = self.sync_events.subscribe();
@@ -854,6 +912,7 @@ impl PeerSync {
 													&buffer,
 													&retry_queue,
 													&db,
+													&library_config,
 													&config,
 													&last_realtime_activity,
 													&metrics,
@@ -887,6 +946,7 @@ impl PeerSync {
 										&buffer,
 										&retry_queue,
 										&db,
+										&library_config,
 										&config,
 										&metrics,
 									)
@@ -942,6 +1002,7 @@ impl PeerSync {
 									&buffer,
 									&retry_queue,
 									&db,
+									&library_config,
 									&config,
 									&last_realtime_activity,
 									&metrics,
@@ -965,6 +1026,7 @@ impl PeerSync {
 					&buffer,
 					&retry_queue,
 					&db,
+					&library_config,
 					&config,
 					&last_realtime_activity,
 					&metrics,
@@ -1199,6 +1261,7 @@ impl PeerSync {
 		buffer: &Arc<BufferQueue>,
 		retry_queue: &Arc<RetryQueue>,
 		db: &Arc<sea_orm::DatabaseConnection>,
+		library_config: &Arc<RwLock<crate::library::LibraryConfig>>,
 		config: &Arc<crate::infra::sync::SyncConfig>,
 		last_realtime_activity: &Arc<RwLock<Option<chrono::DateTime<chrono::Utc>>>>,
 		metrics: &Arc<super::metrics::SyncMetricsCollector>,
@@ -1207,6 +1270,21 @@ impl PeerSync {
 			return;
 		}
 
+		// Hold our own changes back while paused, they go out on resume
+		if matches!(*state.read().await, DeviceSyncState::Paused) {
+			for change_data in batch.drain(..) {
+				match serde_json::from_value::<StateChangeMessage>(change_data) {
+					Ok(change) => {
+						buffer
+							.push(super::state::BufferedUpdate::StateChange(change))
+							.await;
+					}
+					Err(e) => warn!(error = %e, "Skipping malformed state change while paused"),
+				}
+			}
+			return;
+		}
+
 		let batch_size = batch.len();
 		info!(
 			batch_size = batch_size,
@@ -1279,6 +1357,9 @@ impl PeerSync {
 				});
 		}
 
+		// Partial sync: peers only get records from the locations shared with them
+		let location_filters = Self::peer_location_filters(library_config).await;
+
 		// Send batched messages for each (model_type, device_id) group
 		for ((model_type, device_id), records) in grouped {
 			let record_count = records.len();
@@ -1290,13 +1371,6 @@ impl PeerSync {
 				"Sending StateBatch message"
 			);
 
-			let message = SyncMessage::StateBatch {
-				library_id,
-				model_type: model_type.clone(),
-				device_id,
-				records,
-			};
-
 			// Get connected partners
 			let connected_partners = match network.get_connected_sync_partners(library_id, db).await
 			{
@@ -1320,35 +1394,58 @@ impl PeerSync {
 				};
 
 				// Queue for all devices except self
-				for target_device_id in library_devices {
-					if target_device_id != device_id {
-						retry_queue.enqueue(target_device_id, message.clone()).await;
-					}
+				let targets = library_devices
+					.into_iter()
+					.filter(|target_device_id| *target_device_id != device_id)
+					.collect();
+				for (target_device_id, message) in Self::state_batch_per_target(
+					library_id,
+					&model_type,
+					device_id,
+					&records,
+					targets,
+					&location_filters,
+					db,
+				)
+				.await
+				{
+					retry_queue.enqueue(target_device_id, message).await;
 				}
 
 				continue;
 			}
 
+			let messages = Self::state_batch_per_target(
+				library_id,
+				&model_type,
+				device_id,
+				&records,
+				connected_partners,
+				&location_filters,
+				db,
+			)
+			.await;
+
 			// Broadcast batch to all partners in parallel
 			use futures::future::join_all;
 
 			let timeout_secs = config.network.message_timeout_secs;
-			let send_futures: Vec<_> = connected_partners
-				.iter()
-				.map(|&partner| {
+			let send_futures: Vec<_> = messages
+				.into_iter()
+				.map(|(partner, msg)| {
 					let network = network.clone();
-					let msg = message.clone();
 					async move {
 						match tokio::time::timeout(
 							std::time::Duration::from_secs(timeout_secs),
-							network.send_sync_message(partner, msg),
+							network.send_sync_message(partner, msg.clone()),
 						)
 						.await
 						{
-							Ok(Ok(())) => (partner, Ok(())),
-							Ok(Err(e)) => (partner, Err(e)),
+							Ok(Ok(())) => (partner, msg, Ok(())),
+							Ok(Err(e)) => (partner, msg, Err(e)),
 							Err(_) => (
 								partner,
+								msg,
 								Err(anyhow::anyhow!("Send timeout after {}s", timeout_secs)),
 							),
 						}
@@ -1362,11 +1459,14 @@ impl PeerSync {
 			let mut success_count = 0;
 			let mut error_count = 0;
 
-			for (partner_uuid, result) in results {
+			for (partner_uuid, message, result) in results {
 				match result {
 					Ok(()) => {
 						success_count += 1;
 						debug!(partner = %partner_uuid, "StateBatch sent successfully");
+						metrics
+							.record_records_pushed(partner_uuid, message.change_record_count())
+							.await;
 					}
 					Err(e) => {
 						error_count += 1;
@@ -1375,7 +1475,7 @@ impl PeerSync {
 							error = %e,
 							"Failed to send StateBatch to partner, enqueuing for retry"
 						);
-						retry_queue.enqueue(partner_uuid, message.clone()).await;
+						retry_queue.enqueue(partner_uuid, message).await;
 					}
 				}
 			}
@@ -1408,6 +1508,46 @@ impl PeerSync {
 		);
 	}
 
+	/// Build each target's `StateBatch`, leaving out records outside its shared locations
+	///
+	/// Targets left with nothing to receive are dropped.
+	async fn state_batch_per_target(
+		library_id: Uuid,
+		model_type: &str,
+		device_id: Uuid,
+		records: &[crate::service::network::protocol::sync::messages::StateRecord],
+		targets: Vec<Uuid>,
+		location_filters: &PeerLocationFilters,
+		db: &DatabaseConnection,
+	) -> Vec<(Uuid, SyncMessage)> {
+		let mut messages = Vec::with_capacity(targets.len());
+		for target in targets {
+			let mut visible = Vec::with_capacity(records.len());
+			for record in records {
+				if location_filters
+					.allows(target, model_type, record.uuid, &record.data, db)
+					.await
+				{
+					visible.push(record.clone());
+				}
+			}
+			if visible.is_empty() {
+				continue;
+			}
+
+			messages.push((
+				target,
+				SyncMessage::StateBatch {
+					library_id,
+					model_type: model_type.to_string(),
+					device_id,
+					records: visible,
+				},
+			));
+		}
+		messages
+	}
+
 	/// Handle state change event from TransactionManager (static version for spawned task)
 	async fn handle_state_change_event_static(
 		library_id: Uuid,
@@ -1417,6 +1557,7 @@ impl PeerSync {
 		buffer: &Arc<BufferQueue>,
 		retry_queue: &Arc<RetryQueue>,
 		db: &Arc<sea_orm::DatabaseConnection>,
+		library_config: &Arc<RwLock<crate::library::LibraryConfig>>,
 		config: &Arc<crate::infra::sync::SyncConfig>,
 		last_realtime_activity: &Arc<RwLock<Option<chrono::DateTime<chrono::Utc>>>>,
 		metrics: &Arc<super::metrics::SyncMetricsCollector>,
@@ -1497,13 +1638,24 @@ impl PeerSync {
 			"[Static Handler] Got connected sync partners from transport"
 		);
 
+		// Partial sync: peers only get records from the locations shared with them
+		let location_filters = Self::peer_location_filters(library_config).await;
+
 		if connected_partners.is_empty() {
 			debug!(
 				"[Static Handler] No connected sync partners to broadcast to, queuing for retry"
 			);
 
 			// Get all library devices for queueing
-			let library_devices = Self::get_library_devices_static(db).await?;
+			let library_devices = location_filters
+				.retain_allowed(
+					Self::get_library_devices_static(db).await?,
+					&change.model_type,
+					change.record_uuid,
+					&change.data,
+					db,
+				)
+				.await;
 
 			// Queue for all devices except self
 			for device_id in library_devices {
@@ -1515,6 +1667,20 @@ impl PeerSync {
 			return Ok(());
 		}
 
+		let connected_partners = location_filters
+			.retain_allowed(
+				connected_partners,
+				&change.model_type,
+				change.record_uuid,
+				&change.data,
+				db,
+			)
+			.await;
+		if connected_partners.is_empty() {
+			debug!("No connected sync partner shares this record's location");
+			return Ok(());
+		}
+
 		debug!(
 			model_type = %change.model_type,
 			record_uuid = %change.record_uuid,
@@ -1563,6 +1729,9 @@ impl PeerSync {
 				Ok(()) => {
 					success_count += 1;
 					debug!(partner = %partner_uuid, "State change sent successfully");
+					metrics
+						.record_records_pushed(partner_uuid, message.change_record_count())
+						.await;
 				}
 				Err(e) => {
 					error_count += 1;
@@ -1616,6 +1785,7 @@ impl PeerSync {
 		buffer: &Arc<BufferQueue>,
 		retry_queue: &Arc<RetryQueue>,
 		db: &Arc<sea_orm::DatabaseConnection>,
+		library_config: &Arc<RwLock<crate::library::LibraryConfig>>,
 		config: &Arc<crate::infra::sync::SyncConfig>,
 		metrics: &Arc<super::metrics::SyncMetricsCollector>,
 	) -> Result<()> {
@@ -1656,11 +1826,22 @@ impl PeerSync {
 				e
 			})?;
 
+		// Partial sync: peers only get records from the locations shared with them
+		let location_filters = Self::peer_location_filters(library_config).await;
+
 		if connected_partners.is_empty() {
 			debug!("No connected sync partners to broadcast to, queuing for retry");
 
 			// Get all library devices for queueing
-			let library_devices = Self::get_library_devices_static(db).await?;
+			let library_devices = location_filters
+				.retain_allowed(
+					Self::get_library_devices_static(db).await?,
+					&entry.model_type,
+					entry.record_uuid,
+					&entry.data,
+					db,
+				)
+				.await;
 
 			// Queue for all devices except self
 			for device_id in library_devices {
@@ -1672,6 +1853,20 @@ impl PeerSync {
 			return Ok(());
 		}
 
+		let connected_partners = location_filters
+			.retain_allowed(
+				connected_partners,
+				&entry.model_type,
+				entry.record_uuid,
+				&entry.data,
+				db,
+			)
+			.await;
+		if connected_partners.is_empty() {
+			debug!("No connected sync partner shares this record's location");
+			return Ok(());
+		}
+
 		debug!(
 			hlc = %entry.hlc,
 			model_type = %entry.model_type,
@@ -1720,6 +1915,9 @@ impl PeerSync {
 				Ok(()) => {
 					success_count += 1;
 					debug!(partner = %partner_uuid, "Shared change sent successfully");
+					metrics
+						.record_records_pushed(partner_uuid, message.change_record_count())
+						.await;
 				}
 				Err(e) => {
 					error_count += 1;
@@ -1778,6 +1976,97 @@ impl PeerSync {
 		*self.state.read().await
 	}
 
+	/// Whether sync has been paused by the user
+	///
+	/// Read from the library config, so a pause survives a restart and covers
+	/// a library that has not settled in [`DeviceSyncState::Paused`] yet.
+	pub async fn is_paused(&self) -> bool {
+		self.library_config.read().await.settings.sync_paused
+	}
+
+	/// The state to settle in once backfill or catch-up is done
+	pub(crate) async fn settled_state(&self) -> DeviceSyncState {
+		if self.is_paused().await {
+			DeviceSyncState::Paused
+		} else {
+			DeviceSyncState::Ready
+		}
+	}
+
+	/// Pause real-time sync for this library
+	///
+	/// Changes from both sides are buffered rather than sent or applied until
+	/// `resume()`. A library still backfilling or catching up finishes that first
+	/// and settles in paused instead of ready, so resume never skips part of a
+	/// backfill. The pause is kept in the library config; callers save it.
+	/// Returns false if already paused.
+	pub async fn pause(&self) -> Result<bool> {
+		let newly_paused = {
+			let mut config = self.library_config.write().await;
+			!std::mem::replace(&mut config.settings.sync_paused, true)
+		};
+
+		let previous = {
+			let mut state = self.state.write().await;
+			if !matches!(*state, DeviceSyncState::Ready) {
+				return Ok(newly_paused);
+			}
+			std::mem::replace(&mut *state, DeviceSyncState::Paused)
+		};
+
+		info!(
+			library_id = %self.library_id,
+			from_state = ?previous,
+			to_state = ?DeviceSyncState::Paused,
+			"Sync state transition"
+		);
+		self.metrics
+			.record_state_transition(
+				previous,
+				DeviceSyncState::Paused,
+				Some("paused by user".to_string()),
+			)
+			.await?;
+
+		Ok(true)
+	}
+
+	/// Resume real-time sync after `pause()`
+	///
+	/// Applies and broadcasts everything buffered while paused, then exchanges
+	/// watermarks with connected partners so anything that did not fit in the
+	/// buffer is caught up. Clears the pause from the library config; callers
+	/// save it. Returns false if sync was not paused.
+	pub async fn resume(&self) -> Result<bool> {
+		let was_paused = {
+			let mut config = self.library_config.write().await;
+			std::mem::replace(&mut config.settings.sync_paused, false)
+		};
+		if !matches!(self.state().await, DeviceSyncState::Paused) {
+			// Not settled yet; backfill or catch-up now ends in ready
+			return Ok(was_paused);
+		}
+
+		info!(library_id = %self.library_id, "Resuming sync");
+		self.transition_to_ready().await?;
+
+		let partners = self
+			.network
+			.get_connected_sync_partners(self.library_id, &self.db)
+			.await?;
+		for partner in partners {
+			if let Err(e) = self.exchange_watermarks_and_catchup(partner).await {
+				warn!(
+					peer = %partner,
+					error = %e,
+					"Failed to exchange watermarks after resume, catch-up will retry"
+				);
+			}
+		}
+
+		Ok(true)
+	}
+
 	/// Broadcast state change (device-owned data)
 	pub async fn broadcast_state_change(&self, change: StateChangeMessage) -> Result<()> {
 		let state = self.state().await;
@@ -1811,11 +2100,22 @@ impl PeerSync {
 				e
 			})?;
 
+		// Partial sync: peers only get records from the locations shared with them
+		let location_filters = Self::peer_location_filters(&self.library_config).await;
+
 		if connected_partners.is_empty() {
 			debug!("No connected sync partners to broadcast to, queuing for retry");
 
 			// Get all library devices for queueing
-			let library_devices = self.get_library_devices().await?;
+			let library_devices = location_filters
+				.retain_allowed(
+					self.get_library_devices().await?,
+					&change.model_type,
+					change.record_uuid,
+					&change.data,
+					&self.db,
+				)
+				.await;
 
 			// Queue for all devices except self
 			for device_id in library_devices {
@@ -1827,6 +2127,20 @@ impl PeerSync {
 			return Ok(());
 		}
 
+		let connected_partners = location_filters
+			.retain_allowed(
+				connected_partners,
+				&change.model_type,
+				change.record_uuid,
+				&change.data,
+				&self.db,
+			)
+			.await;
+		if connected_partners.is_empty() {
+			debug!("No connected sync partner shares this record's location");
+			return Ok(());
+		}
+
 		debug!(
 			model_type = %change.model_type,
 			record_uuid = %change.record_uuid,
@@ -1872,6 +2186,9 @@ impl PeerSync {
 				Ok(()) => {
 					success_count += 1;
 					debug!(partner = %partner_uuid, "State change sent successfully");
+					self.metrics
+						.record_records_pushed(partner_uuid, message.change_record_count())
+						.await;
 				}
 				Err(e) => {
 					error_count += 1;
@@ -1962,11 +2279,22 @@ impl PeerSync {
 				e
 			})?;
 
+		// Partial sync: peers only get records from the locations shared with them
+		let location_filters = Self::peer_location_filters(&self.library_config).await;
+
 		if connected_partners.is_empty() {
 			debug!("No connected sync partners to broadcast to, queuing for retry");
 
 			// Get all library devices for queueing
-			let library_devices = self.get_library_devices().await?;
+			let library_devices = location_filters
+				.retain_allowed(
+					self.get_library_devices().await?,
+					&model_type,
+					record_uuid,
+					&entry.data,
+					&self.db,
+				)
+				.await;
 
 			// Queue for all devices except self
 			for device_id in library_devices {
@@ -1978,6 +2306,20 @@ impl PeerSync {
 			return Ok(());
 		}
 
+		let connected_partners = location_filters
+			.retain_allowed(
+				connected_partners,
+				&model_type,
+				record_uuid,
+				&entry.data,
+				&self.db,
+			)
+			.await;
+		if connected_partners.is_empty() {
+			debug!("No connected sync partner shares this record's location");
+			return Ok(());
+		}
+
 		debug!(
 			hlc = %hlc,
 			model_type = %model_type,
@@ -2024,6 +2366,9 @@ impl PeerSync {
 				Ok(()) => {
 					success_count += 1;
 					debug!(partner = %partner_uuid, "Shared change sent successfully");
+					self.metrics
+						.record_records_pushed(partner_uuid, message.change_record_count())
+						.await;
 				}
 				Err(e) => {
 					error_count += 1;
@@ -2068,6 +2413,16 @@ impl PeerSync {
 		// Record metrics
 		self.metrics.record_changes_received(1);
 
+		if !self.accepts_from_peer(change.device_id, &change).await {
+			debug!(
+				model_type = %change.model_type,
+				record_uuid = %change.record_uuid,
+				peer = %change.device_id,
+				"Dropping state change outside the locations the peer shares"
+			);
+			return Ok(());
+		}
+
 		let state = self.state().await;
 
 		if state.should_buffer() {
@@ -2594,6 +2949,59 @@ impl PeerSync {
 		Ok(library_devices.iter().map(|d| d.uuid).collect())
 	}
 
+	/// Locations shared with `peer`, or `None` if the peer receives the whole library
+	pub async fn location_filter_for(&self, peer: Uuid) -> Option<Vec<Uuid>> {
+		self.library_config
+			.read()
+			.await
+			.settings
+			.shared_location_filters
+			.get(&peer)
+			.cloned()
+	}
+
+	/// Location filters of every peer the library is shared with
+	async fn peer_location_filters(
+		library_config: &RwLock<crate::library::LibraryConfig>,
+	) -> PeerLocationFilters {
+		PeerLocationFilters::new(&library_config.read().await.settings.shared_location_filters)
+	}
+
+	/// Whether a change from `peer` lies in the locations it shares with us
+	///
+	/// A peer that shared only some locations shouldn't send anything else, but
+	/// its filter is re-checked here in case it does. A record that can't be
+	/// placed yet passes, since its parent may simply not have arrived.
+	async fn accepts_from_peer(&self, peer: Uuid, change: &StateChangeMessage) -> bool {
+		let locations: std::collections::HashSet<Uuid> = {
+			let config = self.library_config.read().await;
+			match config.settings.received_location_filters.get(&peer) {
+				Some(locations) => locations.iter().copied().collect(),
+				None => return true,
+			}
+		};
+		match crate::infra::sync::record_in_locations(
+			&change.model_type,
+			change.record_uuid,
+			&change.data,
+			&locations,
+			&self.db,
+		)
+		.await
+		{
+			Ok(placed) => placed.unwrap_or(true),
+			Err(e) => {
+				warn!(
+					peer = %peer,
+					record_uuid = %change.record_uuid,
+					error = %e,
+					"Failed to check received location filter"
+				);
+				true
+			}
+		}
+	}
+
 	/// Get device-owned state for backfill (StateRequest)
 	///
 	/// This is completely domain-agnostic - it delegates to the Syncable trait
@@ -2750,7 +3158,9 @@ impl PeerSync {
 	/// to a new device during initial sync. This ensures pre-sync data is included.
 	///
 	/// This is fully generic - uses the registry to discover and query all shared models.
-	pub async fn get_full_shared_state(&self) -> Result<serde_json::Value> {
+	/// With `content_hints`, content-bearing models are sent as hash hints the
+	/// requester resolves through [`Self::get_shared_content`].
+	pub async fn get_full_shared_state(&self, content_hints: bool) -> Result<serde_json::Value> {
 		debug!("Querying full shared resource state for backfill");
 
 		// Query all shared models through the registry (fully generic)
@@ -2773,16 +3183,10 @@ impl PeerSync {
 				"Queried shared model for backfill state snapshot"
 			);
 
-			// Convert records to array of {uuid, data} objects
-			let records_json: Vec<serde_json::Value> = records
-				.into_iter()
-				.map(|(uuid, data, _ts)| {
-					serde_json::json!({
-						"uuid": uuid,
-						"data": data
-					})
-				})
-				.collect();
+			// Convert records to {uuid, data} objects, or {uuid, content_hash}
+			// hints for content-bearing models
+			let records_json =
+				crate::infra::sync::snapshot_records(&model_type, records, content_hints).await;
 
 			response.insert(model_type, serde_json::Value::Array(records_json));
 		}
@@ -2790,6 +3194,34 @@ impl PeerSync {
 		Ok(serde_json::Value::Object(response))
 	}
 
+	/// Get full records for content hashes a peer is missing (ContentRequest)
+	///
+	/// Answers the hash hints sent in [`Self::get_full_shared_state`].
+	pub async fn get_shared_content(
+		&self,
+		model_type: &str,
+		content_hashes: Vec<String>,
+	) -> Result<Vec<crate::service::network::protocol::sync::messages::StateRecord>> {
+		use crate::service::network::protocol::sync::messages::StateRecord;
+
+		let records = crate::infra::sync::registry::query_content(
+			model_type,
+			content_hashes,
+			self.db.clone(),
+		)
+		.await
+		.map_err(|e| anyhow::anyhow!("Failed to query content for {}: {}", model_type, e))?;
+
+		Ok(records
+			.into_iter()
+			.map(|(uuid, data, timestamp)| StateRecord {
+				uuid,
+				data,
+				timestamp,
+			})
+			.collect())
+	}
+
 	/// Transition to ready state (after backfill)
 	pub async fn transition_to_ready(&self) -> Result<()> {
 		let current_state = self.state().await;
@@ -2934,6 +3366,7 @@ impl PeerSync {
 				&self.buffer,
 				&self.retry_queue,
 				&self.db,
+				&self.library_config,
 				&self.config,
 				&self.last_realtime_activity,
 				&self.metrics,
@@ -2960,6 +3393,7 @@ impl PeerSync {
 				&self.buffer,
 				&self.retry_queue,
 				&self.db,
+				&self.library_config,
 				&self.config,
 				&self.metrics,
 			)
@@ -2973,24 +3407,27 @@ impl PeerSync {
 			}
 		}
 
-		// Now ready!
+		// Now ready, unless the user paused sync while we were catching up
 		let current_state_before_ready = self.state().await;
-		{
+		let settled = {
+			// Settle under the state lock so a concurrent pause() is never lost
 			let mut state = self.state.write().await;
-			*state = DeviceSyncState::Ready;
+			let settled = self.settled_state().await;
+			*state = settled;
 			info!(
 				from_state = ?current_state_before_ready,
-				to_state = ?DeviceSyncState::Ready,
+				to_state = ?settled,
 				"Sync state transition"
 			);
-		}
+			settled
+		};
 
 		// Record state transition
 		let buffered_count = self.buffer.len().await;
 		self.metrics
 			.record_state_transition(
 				DeviceSyncState::CatchingUp { buffered_count },
-				DeviceSyncState::Ready,
+				settled,
 				Some("buffered updates processed".to_string()),
 			)
 			.await?;
@@ -113,6 +113,7 @@ impl LogSyncHandler {
 		&self,
 		since_hlc: Option<HLC>,
 		limit: usize,
+		content_hints: bool,
 	) -> Result<SyncMessage> {
 		// Reject limit == 0 up front: with our `has_more = entries.len() > limit`
 		// rule it would return 0 rows with has_more = true and the caller would
@@ -138,7 +139,7 @@ impl LogSyncHandler {
 		// This ensures shared resources like content_identities are available
 		// even if they weren't recorded in peer_log (e.g., created before sync was enabled)
 		let current_state = if since_hlc.is_none() {
-			Some(self.get_current_shared_state().await?)
+			Some(self.get_current_shared_state(content_hints).await?)
 		} else {
 			None
 		};
@@ -152,7 +153,7 @@ impl LogSyncHandler {
 	}
 
 	/// Get current state of all shared resources (fallback when logs pruned)
-	async fn get_current_shared_state(&self) -> Result<serde_json::Value> {
+	async fn get_current_shared_state(&self, content_hints: bool) -> Result<serde_json::Value> {
 		// Query all shared models via registry
 		let db = Arc::new(self.db.conn().clone());
 		let results = crate::infra::sync::registry::query_all_shared_models(
@@ -165,18 +166,13 @@ impl LogSyncHandler {
 
 		// Convert to JSON format expected by backfill
 		// Format: { "model_type": [{ "uuid": "...", "data": {...} }, ...] }
+		// Content-bearing models send { "uuid", "content_hash", "timestamp" } hints
+		// instead when the requester asked for them
 		let mut json_map = serde_json::Map::new();
 
 		for (model_type, records) in results {
-			let records_json: Vec<serde_json::Value> = records
-				.into_iter()
-				.map(|(uuid, data, _timestamp)| {
-					serde_json::json!({
-						"uuid": uuid.to_string(),
-						"data": data,
-					})
-				})
-				.collect();
+			let records_json =
+				crate::infra::sync::snapshot_records(&model_type, records, content_hints).await;
 
 			json_map.insert(model_type, serde_json::Value::Array(records_json));
 		}
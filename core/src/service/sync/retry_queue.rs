@@ -110,6 +110,45 @@ impl RetryQueue {
 		ready
 	}
 
+	/// Drop all pending messages for a device
+	///
+	/// Used when a peer stops being a sync partner so queued messages are not
+	/// retried against it. Returns the number of dropped messages.
+	pub async fn remove_for_device(&self, target_device: Uuid) -> usize {
+		let mut queue = self.queue.write().await;
+		let before = queue.len();
+		queue.retain(|entry| entry.target_device != target_device);
+		before - queue.len()
+	}
+
+	/// Remove pending messages regardless of their backoff
+	///
+	/// Takes every message for `target_device`, or for all devices when it is
+	/// None, so a manual sync pass can send them right away.
+	pub async fn take_for_device(&self, target_device: Option<Uuid>) -> Vec<(Uuid, SyncMessage)> {
+		let mut queue = self.queue.write().await;
+		let mut taken = Vec::new();
+		queue.retain(|entry| {
+			if target_device.map_or(true, |target| entry.target_device == target) {
+				taken.push((entry.target_device, entry.message.clone()));
+				false
+			} else {
+				true
+			}
+		});
+		taken
+	}
+
+	/// Number of pending messages for a device
+	pub async fn len_for_device(&self, target_device: Uuid) -> usize {
+		self.queue
+			.read()
+			.await
+			.iter()
+			.filter(|entry| entry.target_device == target_device)
+			.count()
+	}
+
 	/// Get current queue size
 	pub async fn len(&self) -> usize {
 		self.queue.read().await.len()
@@ -145,4 +184,48 @@ mod tests {
 		// Still in queue
 		assert_eq!(queue.len().await, 1);
 	}
+
+	#[tokio::test]
+	async fn test_remove_for_device() {
+		let queue = RetryQueue::new();
+		let removed_device = Uuid::new_v4();
+		let kept_device = Uuid::new_v4();
+		let message = SyncMessage::Error {
+			library_id: Uuid::new_v4(),
+			message: "test".to_string(),
+		};
+
+		queue.enqueue(removed_device, message.clone()).await;
+		queue.enqueue(removed_device, message.clone()).await;
+		queue.enqueue(kept_device, message).await;
+
+		assert_eq!(queue.len_for_device(removed_device).await, 2);
+		assert_eq!(queue.remove_for_device(removed_device).await, 2);
+		assert_eq!(queue.len().await, 1);
+		assert_eq!(queue.len_for_device(removed_device).await, 0);
+		assert_eq!(queue.len_for_device(kept_device).await, 1);
+	}
+
+	#[tokio::test]
+	async fn test_take_for_device_ignores_backoff() {
+		let queue = RetryQueue::new();
+		let taken_device = Uuid::new_v4();
+		let kept_device = Uuid::new_v4();
+		let message = SyncMessage::Error {
+			library_id: Uuid::new_v4(),
+			message: "test".to_string(),
+		};
+
+		queue.enqueue(taken_device, message.clone()).await;
+		queue.enqueue(kept_device, message).await;
+
+		// Neither entry is due yet, but a targeted take still returns it
+		let taken = queue.take_for_device(Some(taken_device)).await;
+		assert_eq!(taken.len(), 1);
+		assert_eq!(taken[0].0, taken_device);
+		assert_eq!(queue.len().await, 1);
+
+		assert_eq!(queue.take_for_device(None).await.len(), 1);
+		assert!(queue.is_empty().await);
+	}
 }
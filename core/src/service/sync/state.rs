@@ -5,8 +5,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-use std::sync::Arc;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use tokio::sync::RwLock;
 use tracing::warn;
 use uuid::Uuid;
@@ -51,10 +51,16 @@ impl DeviceSyncState {
 		matches!(self, DeviceSyncState::Ready)
 	}
 
+	/// Whether live updates are held back instead of being applied or sent
+	///
+	/// A paused library buffers too, so `transition_to_ready` can flush what
+	/// accumulated once sync is resumed.
 	pub fn should_buffer(&self) -> bool {
 		matches!(
 			self,
-			DeviceSyncState::Backfilling { .. } | DeviceSyncState::CatchingUp { .. }
+			DeviceSyncState::Backfilling { .. }
+				| DeviceSyncState::CatchingUp { .. }
+				| DeviceSyncState::Paused
 		)
 	}
 }
@@ -231,6 +237,14 @@ pub struct BackfillCheckpoint {
 	/// Model types completed
 	pub completed_models: Vec<String>,
 
+	/// Records applied for the model in progress
+	#[serde(default)]
+	pub records_applied: u64,
+
+	/// Records the peer counted for the model in progress, from its first page
+	#[serde(default)]
+	pub estimated_total: Option<u64>,
+
 	/// Last updated
 	pub updated_at: DateTime<Utc>,
 }
@@ -243,6 +257,8 @@ impl BackfillCheckpoint {
 			resume_token: None,
 			progress: 0.0,
 			completed_models: Vec::new(),
+			records_applied: 0,
+			estimated_total: None,
 			updated_at: Utc::now(),
 		}
 	}
@@ -254,11 +270,35 @@ impl BackfillCheckpoint {
 		self.updated_at = Utc::now();
 	}
 
+	/// Record an applied page of the model in progress
+	///
+	/// The first count wins: a resumed backfill keeps the total it started
+	/// with rather than the peer's count of what is left. Progress stays at 0
+	/// until a count is known.
+	pub fn record_page(
+		&mut self,
+		resume_token: Option<String>,
+		records: u64,
+		estimated_total: Option<u64>,
+	) {
+		self.records_applied += records;
+		if self.estimated_total.is_none() {
+			self.estimated_total = estimated_total;
+		}
+		let progress = match self.estimated_total {
+			Some(total) if total > 0 => (self.records_applied as f32 / total as f32).min(1.0),
+			_ => 0.0,
+		};
+		self.update(resume_token, progress);
+	}
+
 	/// Mark model type as completed
 	pub fn mark_completed(&mut self, model_type: String) {
 		if !self.completed_models.contains(&model_type) {
 			self.completed_models.push(model_type);
 		}
+		self.records_applied = 0;
+		self.estimated_total = None;
 		self.updated_at = Utc::now();
 	}
 
@@ -275,6 +315,117 @@ impl BackfillCheckpoint {
 	}
 }
 
+/// Progress of backfilling one model from one peer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillProgress {
+	pub peer: Uuid,
+	pub model_type: String,
+
+	/// Cursor of the last applied page ("timestamp|uuid"); a restart resumes after it
+	pub last_applied_cursor: Option<String>,
+
+	/// Records applied so far
+	pub records_applied: u64,
+
+	/// How many records the peer expects to send, if it reported a count
+	pub estimated_total: Option<u64>,
+
+	pub updated_at: DateTime<Utc>,
+}
+
+impl BackfillProgress {
+	/// Share of the estimated total applied so far, from 0 to 100
+	///
+	/// The peer's count is an estimate, so this is capped at 100 rather than
+	/// trusted to line up with what it actually sends.
+	pub fn percentage(&self) -> Option<f32> {
+		let total = self.estimated_total.filter(|total| *total > 0)?;
+		Some((self.records_applied as f32 / total as f32 * 100.0).min(100.0))
+	}
+}
+
+type ProgressEntries = Arc<Mutex<HashMap<(Uuid, String), BackfillProgress>>>;
+
+fn lock_progress(
+	entries: &ProgressEntries,
+) -> MutexGuard<'_, HashMap<(Uuid, String), BackfillProgress>> {
+	entries.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Backfills currently running, one entry per peer and model
+#[derive(Debug, Default)]
+pub struct BackfillProgressTracker {
+	entries: ProgressEntries,
+}
+
+impl BackfillProgressTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Start tracking a model's backfill from a peer
+	///
+	/// The entry lives as long as the returned handle, so a backfill that fails
+	/// part way does not linger as active.
+	pub fn start(&self, peer: Uuid, model_type: &str) -> BackfillProgressHandle {
+		let key = (peer, model_type.to_string());
+		lock_progress(&self.entries).insert(
+			key.clone(),
+			BackfillProgress {
+				peer,
+				model_type: model_type.to_string(),
+				last_applied_cursor: None,
+				records_applied: 0,
+				estimated_total: None,
+				updated_at: Utc::now(),
+			},
+		);
+		BackfillProgressHandle {
+			entries: self.entries.clone(),
+			key,
+		}
+	}
+
+	/// Every running backfill, ordered by peer and model
+	pub fn snapshot(&self) -> Vec<BackfillProgress> {
+		let mut progress: Vec<BackfillProgress> =
+			lock_progress(&self.entries).values().cloned().collect();
+		progress.sort_by(|a, b| (a.peer, &a.model_type).cmp(&(b.peer, &b.model_type)));
+		progress
+	}
+}
+
+/// Keeps a model's entry in a [`BackfillProgressTracker`] until dropped
+pub struct BackfillProgressHandle {
+	entries: ProgressEntries,
+	key: (Uuid, String),
+}
+
+impl BackfillProgressHandle {
+	/// Record an applied page and the cursor to resume after it
+	///
+	/// The peer only reports its count with the first page, so a missing
+	/// `estimated_total` keeps the one already recorded.
+	pub fn record_page(&self, cursor: Option<String>, records: u64, estimated_total: Option<u64>) {
+		if let Some(entry) = lock_progress(&self.entries).get_mut(&self.key) {
+			if cursor.is_some() {
+				entry.last_applied_cursor = cursor;
+			}
+			entry.records_applied += records;
+			if estimated_total.is_some() {
+				entry.estimated_total = estimated_total;
+			}
+			entry.updated_at = Utc::now();
+		}
+	}
+}
+
+impl Drop for BackfillProgressHandle {
+	fn drop(&mut self) {
+		lock_progress(&self.entries).remove(&self.key);
+	}
+}
+
 /// Peer information for selection
 #[derive(Debug, Clone)]
 pub struct PeerInfo {
@@ -390,6 +541,56 @@ mod tests {
 		assert_eq!(result, selected_id);
 	}
 
+	#[test]
+	fn test_partial_backfill_reports_percentage() {
+		let tracker = BackfillProgressTracker::new();
+		assert!(tracker.snapshot().is_empty());
+
+		let peer = Uuid::new_v4();
+		let handle = tracker.start(peer, "entry");
+		handle.record_page(
+			Some("2025-01-01T00:00:00+00:00|a".to_string()),
+			300,
+			Some(1000),
+		);
+		handle.record_page(Some("2025-01-02T00:00:00+00:00|b".to_string()), 400, None);
+
+		let progress = tracker.snapshot();
+		assert_eq!(progress.len(), 1);
+		assert_eq!(progress[0].peer, peer);
+		assert_eq!(progress[0].records_applied, 700);
+		assert_eq!(
+			progress[0].last_applied_cursor.as_deref(),
+			Some("2025-01-02T00:00:00+00:00|b")
+		);
+		let percentage = progress[0].percentage().unwrap();
+		assert!(percentage > 0.0 && percentage < 100.0);
+
+		// An estimate the peer undershot never reports past 100
+		handle.record_page(None, 500, None);
+		assert_eq!(tracker.snapshot()[0].percentage(), Some(100.0));
+
+		drop(handle);
+		assert!(tracker.snapshot().is_empty());
+	}
+
+	#[test]
+	fn test_checkpoint_keeps_the_first_count() {
+		let mut checkpoint = BackfillCheckpoint::start(Uuid::new_v4());
+		checkpoint.record_page(Some("a".to_string()), 250, Some(1000));
+		assert_eq!(checkpoint.progress, 0.25);
+
+		// A resumed request counts only what is left; the original total stays
+		checkpoint.record_page(Some("b".to_string()), 250, Some(500));
+		assert_eq!(checkpoint.estimated_total, Some(1000));
+		assert_eq!(checkpoint.progress, 0.5);
+		assert_eq!(checkpoint.resume_token.as_deref(), Some("b"));
+
+		checkpoint.mark_completed("entry".to_string());
+		assert_eq!(checkpoint.records_applied, 0);
+		assert_eq!(checkpoint.estimated_total, None);
+	}
+
 	#[test]
 	fn test_sync_state_transitions() {
 		let state = DeviceSyncState::Uninitialized;
@@ -404,5 +605,9 @@ mod tests {
 		let state = DeviceSyncState::Ready;
 		assert!(state.is_ready());
 		assert!(!state.should_buffer());
+
+		let state = DeviceSyncState::Paused;
+		assert!(!state.is_ready());
+		assert!(state.should_buffer());
 	}
 }
@@ -224,6 +224,9 @@ impl TestConfigBuilder {
 			logging: crate::config::app_config::LoggingConfig::default(),
 			proxy_pairing: crate::config::app_config::ProxyPairingConfig::default(),
 			spacebot: crate::config::app_config::SpacebotConfig::default(),
+			network: crate::config::app_config::NetworkDiscoveryConfig::default(),
+			pairing_data_dir: None,
+			volume_detection: crate::volume::VolumeDetectionConfig::default(),
 		}
 	}
 
@@ -5,6 +5,8 @@
 //! as the subprocess executor, coordinated via environment variables.
 
 pub mod integration_utils;
+pub mod network_logger;
 pub mod runner;
 
+pub use network_logger::{CapturedLevel, CapturingNetworkLogger};
 pub use runner::CargoTestRunner;
@@ -0,0 +1,96 @@
+//! Network logger that records messages for test assertions
+//!
+//! Swap it in wherever a component takes an `Arc<dyn NetworkLogger>` and check
+//! afterwards that a particular line was logged, instead of eyeballing stdout.
+
+use crate::service::network::NetworkLogger;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// Level a captured message was logged at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CapturedLevel {
+	Info,
+	Warn,
+	Error,
+	Debug,
+}
+
+/// Records every `(level, message)` pair it receives
+///
+/// Clones share the same buffer, so keep one clone for assertions and hand
+/// another to the code under test.
+#[derive(Debug, Clone, Default)]
+pub struct CapturingNetworkLogger {
+	entries: Arc<Mutex<Vec<(CapturedLevel, String)>>>,
+}
+
+impl CapturingNetworkLogger {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// All captured messages in the order they were logged
+	pub fn entries(&self) -> Vec<(CapturedLevel, String)> {
+		self.entries.lock().unwrap().clone()
+	}
+
+	/// Captured messages at one level
+	pub fn messages_at(&self, level: CapturedLevel) -> Vec<String> {
+		self.entries
+			.lock()
+			.unwrap()
+			.iter()
+			.filter(|(l, _)| *l == level)
+			.map(|(_, message)| message.clone())
+			.collect()
+	}
+
+	/// Whether any message at `level` contains `needle`
+	pub fn contains(&self, level: CapturedLevel, needle: &str) -> bool {
+		self.entries
+			.lock()
+			.unwrap()
+			.iter()
+			.any(|(l, message)| *l == level && message.contains(needle))
+	}
+
+	/// Whether any message at any level contains `needle`
+	pub fn contains_message(&self, needle: &str) -> bool {
+		self.entries
+			.lock()
+			.unwrap()
+			.iter()
+			.any(|(_, message)| message.contains(needle))
+	}
+
+	pub fn clear(&self) {
+		self.entries.lock().unwrap().clear();
+	}
+
+	fn record(&self, level: CapturedLevel, message: &str) {
+		self.entries
+			.lock()
+			.unwrap()
+			.push((level, message.to_string()));
+	}
+}
+
+#[async_trait]
+impl NetworkLogger for CapturingNetworkLogger {
+	async fn info(&self, message: &str) {
+		self.record(CapturedLevel::Info, message);
+	}
+
+	async fn warn(&self, message: &str) {
+		self.record(CapturedLevel::Warn, message);
+	}
+
+	async fn error(&self, message: &str) {
+		self.record(CapturedLevel::Error, message);
+	}
+
+	async fn debug(&self, message: &str) {
+		self.record(CapturedLevel::Debug, message);
+	}
+}
@@ -3,18 +3,31 @@
 use crate::volume::{
 	error::{VolumeError, VolumeResult},
 	fs,
-	types::{Volume, VolumeDetectionConfig},
+	types::{Volume, VolumeDetectionConfig, VolumeFingerprint, VolumeType},
+	utils,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tokio::task;
 use tracing::{debug, instrument, warn};
 use uuid::Uuid;
 
 /// Detect all volumes on the system using platform-specific methods
-#[instrument(skip(config))]
 pub async fn detect_volumes(
 	device_id: Uuid,
 	config: &VolumeDetectionConfig,
+) -> VolumeResult<Vec<Volume>> {
+	detect_volumes_of_types(device_id, config, None).await
+}
+
+/// Detect volumes of the given types, or all volumes when `only_types` is None
+///
+/// Where the platform can tell network mounts apart before querying them, a
+/// poll without network types never touches them.
+#[instrument(skip(config))]
+pub async fn detect_volumes_of_types(
+	device_id: Uuid,
+	config: &VolumeDetectionConfig,
+	only_types: Option<&HashSet<VolumeType>>,
 ) -> VolumeResult<Vec<Volume>> {
 	debug!("Starting volume detection for device {}", device_id);
 
@@ -28,7 +41,7 @@ pub async fn detect_volumes(
 
 	#[cfg(target_os = "linux")]
 	{
-		volumes.extend(detect_linux_volumes(device_id, config).await?);
+		volumes.extend(detect_linux_volumes(device_id, config, only_types).await?);
 	}
 
 	#[cfg(target_os = "windows")]
@@ -41,6 +54,9 @@ pub async fn detect_volumes(
 		volumes.extend(detect_ios_volumes(device_id, config).await?);
 	}
 
+	disambiguate_fingerprints(&mut volumes);
+	retain_volume_types(&mut volumes, only_types);
+
 	// Enhance volumes with filesystem-specific capabilities
 	enhance_volumes_with_fs_capabilities(&mut volumes).await?;
 
@@ -52,6 +68,112 @@ pub async fn detect_volumes(
 	Ok(volumes)
 }
 
+/// Give every volume in one enumeration its own fingerprint
+///
+/// Storage keeps one volume per fingerprint, so colliding volumes are sorted
+/// out here. A filesystem mounted in several places (bind mounts) is a single
+/// volume; its other mount points are folded into the first. Separate drives
+/// that still collide are clones: a clone carrying a copied dotfile gets a
+/// fresh dotfile id, which keeps the drives apart from then on. Without a
+/// dotfile, the mount point is mixed into the fingerprint. Either way the
+/// volume with the first mount point keeps the original, so enumeration order
+/// can't swap ids between drives.
+fn disambiguate_fingerprints(volumes: &mut Vec<Volume>) {
+	let mut groups: HashMap<VolumeFingerprint, Vec<usize>> = HashMap::new();
+	for (index, volume) in volumes.iter().enumerate() {
+		groups
+			.entry(volume.fingerprint.clone())
+			.or_default()
+			.push(index);
+	}
+
+	let mut seen: HashSet<VolumeFingerprint> = groups.keys().cloned().collect();
+	let mut folded = HashSet::new();
+	for mut group in groups.into_values().filter(|group| group.len() > 1) {
+		group.sort_by(|a, b| volumes[*a].mount_point.cmp(&volumes[*b].mount_point));
+
+		// One entry per physical filesystem; bind mounts fold into it
+		let mut drives: Vec<usize> = Vec::new();
+		for index in group {
+			match drives
+				.iter()
+				.find(|drive| same_filesystem(&volumes[**drive], &volumes[index]))
+			{
+				Some(&drive) => {
+					let mount_point = volumes[index].mount_point.clone();
+					debug!(
+						"Volume at {} is a bind mount of {}",
+						mount_point.display(),
+						volumes[drive].mount_point.display()
+					);
+					volumes[drive].mount_points.push(mount_point);
+					folded.insert(index);
+				}
+				None => drives.push(index),
+			}
+		}
+
+		for index in drives.into_iter().skip(1) {
+			let volume = &mut volumes[index];
+			let original = volume.fingerprint.clone();
+			let replacement =
+				utils::regenerate_dotfile_sync(&volume.mount_point, volume.device_id, &original)
+					.filter(|fingerprint| !seen.contains(fingerprint))
+					.unwrap_or_else(|| original.disambiguated(&volume.mount_point));
+			warn!(
+				"Volume at {} shares fingerprint {} with another detected volume, using {}",
+				volume.mount_point.display(),
+				original.short_id(),
+				replacement.short_id()
+			);
+
+			// Ids derived from the fingerprint must follow it
+			if volume.id == Uuid::new_v5(&Uuid::NAMESPACE_OID, original.0.as_bytes()) {
+				volume.id = Uuid::new_v5(&Uuid::NAMESPACE_OID, replacement.0.as_bytes());
+			}
+			seen.insert(replacement.clone());
+			volume.fingerprint = replacement;
+		}
+	}
+
+	let mut index = 0;
+	volumes.retain(|_| {
+		index += 1;
+		!folded.contains(&(index - 1))
+	});
+}
+
+/// Whether two mounts expose the same filesystem, as bind mounts do
+fn same_filesystem(a: &Volume, b: &Volume) -> bool {
+	if let (Some(a), Some(b)) = (&a.hardware_id, &b.hardware_id) {
+		return a == b;
+	}
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::MetadataExt;
+
+		if let (Ok(first), Ok(second)) = (
+			std::fs::metadata(&a.mount_point),
+			std::fs::metadata(&b.mount_point),
+		) {
+			return first.dev() == second.dev();
+		}
+	}
+
+	false
+}
+
+/// Drop volumes whose type was not asked for
+///
+/// Runs after fingerprints are disambiguated, so a volume gets the same
+/// fingerprint whichever types are polled.
+fn retain_volume_types(volumes: &mut Vec<Volume>, only_types: Option<&HashSet<VolumeType>>) {
+	if let Some(types) = only_types {
+		volumes.retain(|volume| types.contains(&volume.volume_type));
+	}
+}
+
 /// Enhance detected volumes with filesystem-specific capabilities
 async fn enhance_volumes_with_fs_capabilities(volumes: &mut Vec<Volume>) -> VolumeResult<()> {
 	for volume in volumes.iter_mut() {
@@ -139,11 +261,13 @@ async fn detect_macos_volumes(
 async fn detect_linux_volumes(
 	device_id: Uuid,
 	config: &VolumeDetectionConfig,
+	only_types: Option<&HashSet<VolumeType>>,
 ) -> VolumeResult<Vec<Volume>> {
 	use crate::volume::platform::linux;
 
 	debug!("Starting Linux volume detection");
-	let mut volumes = linux::detect_volumes(device_id, config).await?;
+	let local_only = only_types.is_some_and(|types| !types.contains(&VolumeType::Network));
+	let mut volumes = linux::detect_volumes(device_id, config, local_only).await?;
 
 	// Enhance with filesystem-specific capabilities
 	// For ZFS, fetch dataset info once and apply to all ZFS volumes
@@ -217,3 +341,148 @@ async fn detect_ios_volumes(
 	debug!("Starting iOS volume detection");
 	ios::detect_volumes(device_id, config).await
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::domain::volume::SPACEDRIVE_VOLUME_ID_FILE;
+	use std::path::{Path, PathBuf};
+
+	#[test]
+	fn test_cloned_volumes_both_survive_detection() {
+		let device_id = Uuid::new_v4();
+		let cloned_id = Uuid::new_v4();
+		let mut volumes: Vec<Volume> = ["/media/backup", "/media/backup-clone"]
+			.into_iter()
+			.map(|mount| {
+				Volume::new(
+					device_id,
+					VolumeFingerprint::from_external_volume(cloned_id, device_id),
+					"Backup".to_string(),
+					PathBuf::from(mount),
+				)
+			})
+			.collect();
+		assert_eq!(volumes[0].fingerprint, volumes[1].fingerprint);
+
+		disambiguate_fingerprints(&mut volumes);
+
+		assert_eq!(volumes.len(), 2);
+		assert_eq!(
+			volumes[0].fingerprint,
+			VolumeFingerprint::from_external_volume(cloned_id, device_id)
+		);
+		assert_ne!(volumes[0].fingerprint, volumes[1].fingerprint);
+		assert_eq!(volumes[1].mount_point, PathBuf::from("/media/backup-clone"));
+	}
+
+	#[test]
+	fn test_cloned_dotfile_gets_a_fresh_id() {
+		let device_id = Uuid::new_v4();
+		let config = VolumeDetectionConfig::default();
+		let (source, clone) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+		let fingerprint = utils::external_volume_fingerprint(source.path(), device_id, &config);
+		std::fs::copy(
+			source.path().join(SPACEDRIVE_VOLUME_ID_FILE),
+			clone.path().join(SPACEDRIVE_VOLUME_ID_FILE),
+		)
+		.unwrap();
+
+		let detect = |mounts: [&Path; 2]| {
+			let mut volumes: Vec<Volume> = mounts
+				.into_iter()
+				.map(|mount| {
+					let mut volume = Volume::new(
+						device_id,
+						utils::external_volume_fingerprint(mount, device_id, &config),
+						"Backup".to_string(),
+						mount.to_path_buf(),
+					);
+					volume.hardware_id = Some(mount.display().to_string());
+					volume
+				})
+				.collect();
+			disambiguate_fingerprints(&mut volumes);
+			volumes.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+			volumes
+		};
+
+		// Enumerated last, the drive with the first mount point still keeps the id
+		let (first, second) = if source.path() < clone.path() {
+			(source.path(), clone.path())
+		} else {
+			(clone.path(), source.path())
+		};
+		let volumes = detect([second, first]);
+		assert_eq!(volumes.len(), 2);
+		assert_eq!(volumes[0].fingerprint, fingerprint);
+		assert_ne!(volumes[1].fingerprint, fingerprint);
+
+		// The other drive's dotfile was rewritten, so the split holds in any order
+		assert_eq!(
+			utils::external_volume_fingerprint(second, device_id, &config),
+			volumes[1].fingerprint
+		);
+		let again = detect([first, second]);
+		assert_eq!(again[0].fingerprint, volumes[0].fingerprint);
+		assert_eq!(again[1].fingerprint, volumes[1].fingerprint);
+	}
+
+	#[test]
+	fn test_bind_mounts_fold_into_one_volume() {
+		let device_id = Uuid::new_v4();
+		let fingerprint = VolumeFingerprint::from_external_volume(Uuid::new_v4(), device_id);
+		let mut volumes: Vec<Volume> = ["/srv/data", "/media/data"]
+			.into_iter()
+			.map(|mount| {
+				let mut volume = Volume::new(
+					device_id,
+					fingerprint.clone(),
+					"Data".to_string(),
+					PathBuf::from(mount),
+				);
+				volume.hardware_id = Some("/dev/sdb1".to_string());
+				volume
+			})
+			.collect();
+
+		disambiguate_fingerprints(&mut volumes);
+
+		assert_eq!(volumes.len(), 1);
+		assert_eq!(volumes[0].fingerprint, fingerprint);
+		assert_eq!(volumes[0].mount_point, PathBuf::from("/media/data"));
+		assert_eq!(volumes[0].mount_points, vec![PathBuf::from("/srv/data")]);
+	}
+
+	#[test]
+	fn test_type_filter_keeps_only_polled_types() {
+		let device_id = Uuid::new_v4();
+		let mut volumes: Vec<Volume> = [
+			("/", VolumeType::Primary),
+			("/media/usb", VolumeType::External),
+			("/mnt/share", VolumeType::Network),
+		]
+		.into_iter()
+		.map(|(mount, volume_type)| {
+			let mut volume = Volume::new(
+				device_id,
+				VolumeFingerprint::from_primary_volume(&PathBuf::from(mount), device_id),
+				mount.to_string(),
+				PathBuf::from(mount),
+			);
+			volume.volume_type = volume_type;
+			volume
+		})
+		.collect();
+
+		retain_volume_types(&mut volumes, None);
+		assert_eq!(volumes.len(), 3);
+
+		let local: HashSet<VolumeType> = [VolumeType::Primary, VolumeType::External].into();
+		retain_volume_types(&mut volumes, Some(&local));
+		assert_eq!(volumes.len(), 2);
+		assert!(volumes
+			.iter()
+			.all(|volume| volume.volume_type != VolumeType::Network));
+	}
+}
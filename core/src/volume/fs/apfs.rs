@@ -315,8 +315,16 @@ pub fn containers_to_volumes(
 
 		// Only process mounted volumes (including snapshot mounts)
 		if let Some(mount_point) = effective_mount_point {
-			// Skip system volumes unless configured to include them
+			// Create stable volume fingerprint for APFS volumes
+			// APFS volumes are always local system/primary volumes, use mount_point + device_id
+			let fingerprint = crate::volume::types::VolumeFingerprint::from_primary_volume(
+				mount_point,
+				device_id,
+			);
+
+			// Skip system volumes unless configured to include them or pinned
 			if !config.include_system
+				&& !config.is_pinned(&fingerprint)
 				&& matches!(
 					volume_info.role,
 					ApfsVolumeRole::System | ApfsVolumeRole::Preboot | ApfsVolumeRole::Recovery
@@ -335,13 +343,6 @@ pub fn containers_to_volumes(
 				Vec::new()
 			};
 
-			// Create stable volume fingerprint for APFS volumes
-			// APFS volumes are always local system/primary volumes, use mount_point + device_id
-			let fingerprint = crate::volume::types::VolumeFingerprint::from_primary_volume(
-				mount_point,
-				device_id,
-			);
-
 			debug!(
 				"APFS_CONVERT: Generated fingerprint {} for volume '{}' (consumed: {} bytes)",
 				fingerprint.short_id(),
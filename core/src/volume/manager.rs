@@ -7,16 +7,18 @@ use crate::library::LibraryManager;
 use crate::volume::{
 	detection,
 	error::{VolumeError, VolumeResult},
+	poll::VolumePollScheduler,
 	types::{
-		SpacedriveVolumeId, TrackedVolume, Volume, VolumeDetectionConfig, VolumeFingerprint,
-		VolumeInfo,
+		SpacedriveVolumeId, TrackedVolume, Volume, VolumeChange, VolumeDetectionConfig,
+		VolumeEvent, VolumeFingerprint, VolumeInfo, VolumeType,
 	},
+	watcher::{self, VolumeWatcher},
 	VolumeExt,
 };
 use crate::Core;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Weak};
 use std::time::Duration as StdDuration;
@@ -41,13 +43,12 @@ fn get_volume_watch_paths() -> Vec<PathBuf> {
 	{
 		paths.push(PathBuf::from("/media"));
 		paths.push(PathBuf::from("/mnt"));
-		// Note: Could also watch /proc/mounts but that's more complex
+		// Only used when the mount table watcher can't be started
 	}
 
 	#[cfg(target_os = "windows")]
 	{
-		// Windows drive letters are harder to watch - we'll rely on polling for now
-		// Could potentially use WMI events in the future
+		// Drive letters are covered by the WM_DEVICECHANGE mount watcher
 	}
 
 	#[cfg(target_os = "ios")]
@@ -88,6 +89,9 @@ pub struct VolumeManager {
 	/// File system watcher for real-time volume change detection
 	volume_watcher: Arc<RwLock<Option<RecommendedWatcher>>>,
 
+	/// Platform mount watcher, preferred over directory watching where available
+	mount_watcher: Arc<RwLock<Option<Box<dyn VolumeWatcher>>>>,
+
 	/// Weak reference to library manager for database operations
 	library_manager: Arc<RwLock<Option<Weak<LibraryManager>>>>,
 }
@@ -108,6 +112,7 @@ impl VolumeManager {
 			events,
 			is_monitoring: Arc::new(RwLock::new(false)),
 			volume_watcher: Arc::new(RwLock::new(None)),
+			mount_watcher: Arc::new(RwLock::new(None)),
 			library_manager: Arc::new(RwLock::new(None)),
 		}
 	}
@@ -448,8 +453,10 @@ impl VolumeManager {
 
 		*self.is_monitoring.write().await = true;
 
-		// Start file system watcher for real-time detection
-		self.start_volume_watcher().await;
+		// Start real-time detection, falling back to watching mount directories
+		if !self.start_mount_watcher().await {
+			self.start_volume_watcher().await;
+		}
 
 		// Continue with existing timer-based monitoring as fallback
 		let volumes = self.volumes.clone();
@@ -461,17 +468,27 @@ impl VolumeManager {
 		let device_id = self.device_id;
 
 		tokio::spawn(async move {
+			let Some(tick) = VolumePollScheduler::tick_interval(&config) else {
+				info!("Timer-based volume refresh disabled for all volume types");
+				return;
+			};
 			info!(
-				"Starting volume monitoring (refresh every {}s)",
-				config.refresh_interval_secs
+				"Starting volume monitoring (refresh every {}s, network every {}s)",
+				config.refresh_interval_secs,
+				config.refresh_interval_for(VolumeType::Network)
 			);
 
-			let mut interval =
-				tokio::time::interval(Duration::from_secs(config.refresh_interval_secs));
+			let mut interval = tokio::time::interval(tick);
+			let mut scheduler = VolumePollScheduler::new();
 
 			while *is_monitoring.read().await {
 				interval.tick().await;
 
+				let due = scheduler.take_due(&config, std::time::Instant::now());
+				if due.is_empty() {
+					continue;
+				}
+
 				if let Err(e) = Self::refresh_volumes_internal(
 					device_id,
 					&volumes,
@@ -480,6 +497,7 @@ impl VolumeManager {
 					&config,
 					&library_manager,
 					None,
+					Some(&due),
 				)
 				.await
 				{
@@ -491,6 +509,67 @@ impl VolumeManager {
 		});
 	}
 
+	/// Start the platform mount watcher, returning whether one is running
+	///
+	/// Mount and unmount events are forwarded to the event bus as they arrive and
+	/// trigger an immediate refresh, which emits the usual volume events. The
+	/// timer-based refresh keeps running to reconcile.
+	async fn start_mount_watcher(&self) -> bool {
+		let Some(mut mount_watcher) = watcher::platform_watcher() else {
+			return false;
+		};
+
+		let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+		if let Err(e) = mount_watcher.start(tx) {
+			warn!("Failed to start mount watcher: {}", e);
+			return false;
+		}
+		*self.mount_watcher.write().await = Some(mount_watcher);
+		info!("Watching the system mount table for volume changes");
+
+		let volumes = self.volumes.clone();
+		let path_cache = self.path_cache.clone();
+		let events = self.events.clone();
+		let config = self.config.clone();
+		let library_manager = self.library_manager.clone();
+		let device_id = self.device_id;
+		let is_monitoring = self.is_monitoring.clone();
+
+		tokio::spawn(async move {
+			while let Some(event) = rx.recv().await {
+				if !*is_monitoring.read().await {
+					break;
+				}
+				debug!("Mount change detected: {:?}", event);
+				emit_mount_event(&events, event);
+
+				// Coalesce the burst of events a single device usually produces
+				tokio::time::sleep(Duration::from_millis(200)).await;
+				while let Ok(event) = rx.try_recv() {
+					emit_mount_event(&events, event);
+				}
+
+				if let Err(e) = Self::refresh_volumes_internal(
+					device_id,
+					&volumes,
+					&path_cache,
+					&events,
+					&config,
+					&library_manager,
+					None,
+					None,
+				)
+				.await
+				{
+					error!("Error during mount-triggered volume refresh: {}", e);
+				}
+			}
+			debug!("Mount watcher event handler stopped");
+		});
+
+		true
+	}
+
 	/// Start file system watcher for real-time volume change detection
 	async fn start_volume_watcher(&self) {
 		let watch_paths = get_volume_watch_paths();
@@ -563,6 +642,7 @@ impl VolumeManager {
 								&config,
 								&library_manager,
 								None,
+								None,
 							)
 							.await
 							{
@@ -596,6 +676,10 @@ impl VolumeManager {
 		if let Some(_watcher) = self.volume_watcher.write().await.take() {
 			debug!("Volume watcher stopped");
 		}
+		if let Some(mut mount_watcher) = self.mount_watcher.write().await.take() {
+			mount_watcher.stop();
+			debug!("Mount watcher stopped");
+		}
 
 		info!("Volume monitoring stopped");
 	}
@@ -611,6 +695,7 @@ impl VolumeManager {
 			&self.config,
 			&self.library_manager,
 			Some(self.clone()),
+			None,
 		)
 		.await
 	}
@@ -624,11 +709,14 @@ impl VolumeManager {
 		config: &VolumeDetectionConfig,
 		library_manager: &RwLock<Option<Weak<LibraryManager>>>,
 		manager: Option<Arc<VolumeManager>>,
+		only_types: Option<&HashSet<VolumeType>>,
 	) -> VolumeResult<()> {
 		debug!("Refreshing volumes for device {}", device_id);
 
-		// Detect current volumes
-		let detected_volumes = detection::detect_volumes(device_id, config).await?;
+		// Detect current volumes; types not due for a poll aren't queried and keep
+		// their cached state
+		let detected_volumes =
+			detection::detect_volumes_of_types(device_id, config, only_types).await?;
 
 		debug!("VOLUME_DETECT: Detected {} volumes", detected_volumes.len());
 		for vol in &detected_volumes {
@@ -651,6 +739,7 @@ impl VolumeManager {
 				let libraries = lib_mgr.get_open_libraries().await;
 				debug!("DB_MERGE: Found {} open libraries", libraries.len());
 				for library in libraries {
+					migrate_legacy_fingerprints(&library, device_id, &detected_volumes).await;
 					debug!(
 						"DB_MERGE: Querying library {} for tracked volumes on device {}",
 						library.id(),
@@ -729,13 +818,11 @@ impl VolumeManager {
 			match current_volumes.get(&fingerprint) {
 				Some(existing) => {
 					// Volume exists - check for changes
-					let old_info = VolumeInfo::from(existing);
-					let new_info = VolumeInfo::from(&detected);
+					let change = VolumeChange::between(existing, &detected);
+					if change != VolumeChange::Unchanged {
+						let old_info = VolumeInfo::from(existing);
+						let new_info = VolumeInfo::from(&detected);
 
-					if old_info.is_mounted != new_info.is_mounted
-						|| old_info.total_bytes_available != new_info.total_bytes_available
-						|| old_info.error_status != new_info.error_status
-					{
 						// Update the volume - prefer DB UUID for stability, fall back to cache ID
 						let mut updated_volume = detected.clone();
 						updated_volume.id = tracked_volumes_map
@@ -745,47 +832,27 @@ impl VolumeManager {
 						updated_volume.update_info(new_info.clone());
 						current_volumes.insert(fingerprint.clone(), updated_volume.clone());
 
-						// Emit update event
-						events.emit(Event::VolumeUpdated {
-							fingerprint: fingerprint.clone(),
-							old_info: old_info.clone(),
-							new_info: new_info.clone(),
-						});
-
-						// Emit mount status change if applicable
-						if old_info.is_mounted != new_info.is_mounted {
-							events.emit(Event::VolumeMountChanged {
-								fingerprint: fingerprint.clone(),
-								is_mounted: new_info.is_mounted,
-							});
-
-							// Auto-run speed test when volume is mounted
-							if new_info.is_mounted
-								&& updated_volume.is_user_visible
-								&& !updated_volume.is_read_only
-							{
-								if let Some(ref mgr) = manager {
-									let mgr = mgr.clone();
-									let fp = fingerprint.clone();
-									let vol_name = updated_volume.name.clone();
-									tokio::spawn(async move {
-										info!(
-											"Auto-running speed test for mounted volume: {}",
-											vol_name
-										);
-										if let Err(e) = mgr.run_speed_test(&fp).await {
-											warn!("Auto speed test failed: {}", e);
-										}
-									});
-								}
-							}
-						}
-
-						// Emit ResourceChanged event for UI reactivity (only for user-visible volumes)
-						if updated_volume.is_user_visible {
-							use crate::domain::resource::EventEmitter;
-							if let Err(e) = updated_volume.emit_changed(&events) {
-								warn!("Failed to emit volume ResourceChanged: {}", e);
+						emit_volume_change(events, change, &old_info, &updated_volume);
+
+						// Auto-run speed test when volume is mounted
+						if !old_info.is_mounted
+							&& new_info.is_mounted
+							&& updated_volume.is_user_visible
+							&& !updated_volume.is_read_only
+						{
+							if let Some(ref mgr) = manager {
+								let mgr = mgr.clone();
+								let fp = fingerprint.clone();
+								let vol_name = updated_volume.name.clone();
+								tokio::spawn(async move {
+									info!(
+										"Auto-running speed test for mounted volume: {}",
+										vol_name
+									);
+									if let Err(e) = mgr.run_speed_test(&fp).await {
+										warn!("Auto speed test failed: {}", e);
+									}
+								});
 							}
 						}
 					}
@@ -842,9 +909,12 @@ impl VolumeManager {
 
 		// Check for removed volumes
 		let removed_fingerprints: Vec<_> = current_volumes
-			.keys()
-			.filter(|fp| !seen_fingerprints.contains(fp))
-			.cloned()
+			.iter()
+			.filter(|(fp, volume)| {
+				!seen_fingerprints.contains(fp)
+					&& only_types.map_or(true, |types| types.contains(&volume.volume_type))
+			})
+			.map(|(fp, _)| fp.clone())
 			.collect();
 
 		for fingerprint in removed_fingerprints {
@@ -2218,6 +2288,11 @@ pub struct VolumeStatistics {
 impl Drop for VolumeManager {
 	fn drop(&mut self) {
 		// Ensure monitoring is stopped when manager is dropped
+		if let Ok(mut mount_watcher) = self.mount_watcher.try_write() {
+			if let Some(mut mount_watcher) = mount_watcher.take() {
+				mount_watcher.stop();
+			}
+		}
 		let is_monitoring = self.is_monitoring.clone();
 		tokio::spawn(async move {
 			*is_monitoring.write().await = false;
@@ -2225,6 +2300,137 @@ impl Drop for VolumeManager {
 	}
 }
 
+/// Fingerprint a volume was tracked under before it was keyed by a stable hardware id
+///
+/// Windows used to fingerprint fixed and system volumes by mount point; those
+/// now use the volume GUID whenever one is available.
+fn legacy_fingerprint(volume: &Volume) -> Option<VolumeFingerprint> {
+	let hardware_id = volume.hardware_id.as_deref()?;
+	(volume.fingerprint
+		== VolumeFingerprint::from_stable_hardware_id(hardware_id, volume.device_id))
+	.then(|| VolumeFingerprint::from_primary_volume(&volume.mount_point, volume.device_id))
+}
+
+/// Re-key volumes a library tracks under a fingerprint detection no longer produces
+///
+/// Without this a tracked volume would reappear as new and untracked once its
+/// fingerprint moved to a stable hardware id. The change is synced so other
+/// devices keep the same record.
+async fn migrate_legacy_fingerprints(
+	library: &crate::library::Library,
+	device_id: Uuid,
+	detected: &[Volume],
+) {
+	let db = library.db().conn();
+	for volume in detected {
+		let Some(legacy) = legacy_fingerprint(volume) else {
+			continue;
+		};
+
+		let tracked = |fingerprint: &VolumeFingerprint| {
+			entities::volume::Entity::find()
+				.filter(entities::volume::Column::DeviceId.eq(device_id))
+				.filter(entities::volume::Column::Fingerprint.eq(fingerprint.0.clone()))
+				.one(db)
+		};
+		let legacy_model = match tracked(&legacy).await {
+			Ok(Some(model)) => model,
+			Ok(None) => continue,
+			Err(e) => {
+				warn!(
+					"Failed to look up legacy fingerprint for {}: {}",
+					volume.name, e
+				);
+				continue;
+			}
+		};
+		if !matches!(tracked(&volume.fingerprint).await, Ok(None)) {
+			continue;
+		}
+
+		let mut active_model: entities::volume::ActiveModel = legacy_model.into();
+		active_model.fingerprint = Set(volume.fingerprint.0.clone());
+		match active_model.update(db).await {
+			Ok(model) => {
+				info!(
+					"Migrated tracked volume {} from fingerprint {} to {}",
+					volume.name,
+					legacy.short_id(),
+					volume.fingerprint.short_id()
+				);
+				if let Err(e) = library.sync_model(&model, ChangeType::Update).await {
+					warn!("Failed to sync migrated volume {}: {}", volume.name, e);
+				}
+			}
+			Err(e) => warn!(
+				"Failed to migrate fingerprint of tracked volume {}: {}",
+				volume.name, e
+			),
+		}
+	}
+}
+
+/// Forward a platform watcher's mount change to the event bus
+///
+/// Sent as soon as the OS reports it; the refresh it triggers follows up with
+/// the volume events for whatever was added or removed.
+fn emit_mount_event(events: &EventBus, event: VolumeEvent) {
+	match event {
+		VolumeEvent::Mounted { mount_point } => {
+			events.emit(Event::VolumeMounted { mount_point });
+		}
+		VolumeEvent::Unmounted { mount_point } => {
+			events.emit(Event::VolumeUnmounted { mount_point });
+		}
+		_ => {}
+	}
+}
+
+/// Emit the events for a cached volume that was re-detected with changes
+///
+/// Space-only changes get the lightweight `VolumeUsageChanged` so the UI can
+/// update a usage bar without receiving the whole resource again; structural
+/// changes re-emit the full volume.
+fn emit_volume_change(
+	events: &EventBus,
+	change: VolumeChange,
+	old_info: &VolumeInfo,
+	updated: &Volume,
+) {
+	match change {
+		VolumeChange::Unchanged => {}
+		VolumeChange::Usage => {
+			events.emit(Event::VolumeUsageChanged {
+				fingerprint: updated.fingerprint.clone(),
+				available_space: updated.available_space,
+				total_capacity: updated.total_capacity,
+			});
+		}
+		VolumeChange::Structural => {
+			events.emit(Event::VolumeUpdated {
+				fingerprint: updated.fingerprint.clone(),
+				old_info: old_info.clone(),
+				new_info: VolumeInfo::from(updated),
+			});
+
+			if old_info.is_mounted != updated.is_mounted {
+				events.emit(Event::VolumeMountChanged {
+					fingerprint: updated.fingerprint.clone(),
+					is_mounted: updated.is_mounted,
+				});
+			}
+
+			// Emit ResourceChanged event for UI reactivity (only for user-visible volumes)
+			if updated.is_user_visible {
+				use crate::domain::resource::EventEmitter;
+				if let Err(e) = updated.emit_changed(events) {
+					warn!("Failed to emit volume ResourceChanged: {}", e);
+				}
+			}
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -2268,4 +2474,102 @@ mod tests {
 			.await;
 		assert!(!same);
 	}
+
+	#[test]
+	fn test_legacy_fingerprint_only_for_hardware_id_fingerprints() {
+		let device_id = Uuid::new_v4();
+		let mount_point = PathBuf::from(r"D:\");
+		let guid = r"\\?\Volume{3F2504E0-4F89-11D3-9A0C-0305E82C3301}\";
+
+		let mut volume = Volume::new(
+			device_id,
+			VolumeFingerprint::from_stable_hardware_id(guid, device_id),
+			"Data".to_string(),
+			mount_point.clone(),
+		);
+		volume.hardware_id = Some(guid.to_string());
+		assert_eq!(
+			legacy_fingerprint(&volume),
+			Some(VolumeFingerprint::from_primary_volume(
+				&mount_point,
+				device_id
+			))
+		);
+
+		// Volumes still on their mount point fingerprint have nothing to migrate
+		volume.fingerprint = VolumeFingerprint::from_primary_volume(&mount_point, device_id);
+		assert_eq!(legacy_fingerprint(&volume), None);
+		volume.hardware_id = None;
+		assert_eq!(legacy_fingerprint(&volume), None);
+	}
+
+	#[test]
+	fn test_mount_watcher_events_reach_event_bus() {
+		let events = EventBus::default();
+		let mut subscriber = events.subscribe();
+
+		emit_mount_event(
+			&events,
+			VolumeEvent::Mounted {
+				mount_point: PathBuf::from("/media/usb"),
+			},
+		);
+		emit_mount_event(
+			&events,
+			VolumeEvent::Unmounted {
+				mount_point: PathBuf::from("/media/usb"),
+			},
+		);
+
+		assert!(matches!(
+			subscriber.try_recv().unwrap(),
+			Event::VolumeMounted { ref mount_point } if mount_point == Path::new("/media/usb")
+		));
+		assert!(matches!(
+			subscriber.try_recv().unwrap(),
+			Event::VolumeUnmounted { ref mount_point } if mount_point == Path::new("/media/usb")
+		));
+		assert!(subscriber.try_recv().is_err());
+	}
+
+	#[test]
+	fn test_space_only_change_emits_usage_event() {
+		let events = EventBus::default();
+		let mut subscriber = events.subscribe();
+
+		let previous = Volume::new(
+			Uuid::new_v4(),
+			VolumeFingerprint("test".to_string()),
+			"Test Volume".to_string(),
+			PathBuf::from("/mnt/test"),
+		);
+		let old_info = VolumeInfo::from(&previous);
+
+		let mut shrunk = previous.clone();
+		shrunk.available_space = previous.available_space + 1024;
+		let change = VolumeChange::between(&previous, &shrunk);
+		assert_eq!(change, VolumeChange::Usage);
+
+		emit_volume_change(&events, change, &old_info, &shrunk);
+		assert!(matches!(
+			subscriber.try_recv().unwrap(),
+			Event::VolumeUsageChanged { available_space, .. } if available_space == shrunk.available_space
+		));
+		assert!(subscriber.try_recv().is_err());
+
+		let mut reformatted = previous.clone();
+		reformatted.file_system = crate::volume::types::FileSystem::ExFAT;
+		let change = VolumeChange::between(&previous, &reformatted);
+		assert_eq!(change, VolumeChange::Structural);
+
+		emit_volume_change(&events, change, &old_info, &reformatted);
+		assert!(matches!(
+			subscriber.try_recv().unwrap(),
+			Event::VolumeUpdated { .. }
+		));
+		assert!(matches!(
+			subscriber.try_recv().unwrap(),
+			Event::ResourceChanged { ref resource_type, .. } if resource_type == "volume"
+		));
+	}
 }
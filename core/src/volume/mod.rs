@@ -11,9 +11,11 @@ pub mod error;
 pub mod fs;
 pub mod manager;
 pub mod platform;
+pub mod poll;
 pub mod speed;
 pub mod types;
 pub mod utils;
+pub mod watcher;
 
 pub use backend::{
 	BackendType, CloudBackend, CloudServiceType, LocalBackend, RawDirEntry, RawMetadata,
@@ -21,10 +23,12 @@ pub use backend::{
 };
 pub use error::VolumeError;
 pub use manager::VolumeManager;
+pub use poll::VolumePollScheduler;
 pub use types::{
 	ApfsContainer, ApfsVolumeInfo, ApfsVolumeRole, DiskType, FileSystem, MountType, PathMapping,
-	Volume, VolumeDetectionConfig, VolumeEvent, VolumeFingerprint, VolumeInfo,
+	Volume, VolumeChange, VolumeDetectionConfig, VolumeEvent, VolumeFingerprint, VolumeInfo,
 };
+pub use watcher::VolumeWatcher;
 
 // Re-export detection functions
 pub use detection::detect_volumes;
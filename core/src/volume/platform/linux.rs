@@ -3,11 +3,23 @@
 use crate::volume::{
 	classification::{get_classifier, VolumeDetectionInfo},
 	error::{VolumeError, VolumeResult},
-	types::{DiskType, FileSystem, MountType, Volume, VolumeDetectionConfig, VolumeFingerprint},
+	types::{
+		DiskType, FileSystem, MountType, Volume, VolumeDetectionConfig, VolumeEvent,
+		VolumeFingerprint,
+	},
 	utils,
+	watcher::{diff_mount_points, VolumeWatcher},
 };
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::fd::AsRawFd;
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
 use tokio::task;
 use tracing::debug;
 use uuid::Uuid;
@@ -23,9 +35,12 @@ pub struct MountInfo {
 }
 
 /// Detect Linux volumes using df command
+///
+/// With `local_only`, df skips network filesystems instead of stat-ing them.
 pub async fn detect_volumes(
 	device_id: Uuid,
 	config: &VolumeDetectionConfig,
+	local_only: bool,
 ) -> VolumeResult<Vec<Volume>> {
 	let config = config.clone(); // Clone to move into async block
 	task::spawn_blocking(move || {
@@ -33,7 +48,7 @@ pub async fn detect_volumes(
 
 		// Use df to get mounted filesystems
 		let output = Command::new("df")
-			.args(["-h", "-T"]) // -T shows filesystem type
+			.args(df_args(local_only))
 			.output()
 			.map_err(|e| VolumeError::platform(format!("Failed to run df: {}", e)))?;
 
@@ -56,6 +71,15 @@ pub async fn detect_volumes(
 	.map_err(|e| VolumeError::platform(format!("Task join error: {}", e)))?
 }
 
+/// Arguments for the df call listing mounted filesystems
+fn df_args(local_only: bool) -> Vec<&'static str> {
+	let mut args = vec!["-h", "-T"]; // -T shows filesystem type
+	if local_only {
+		args.push("-l");
+	}
+	args
+}
+
 /// Parse a single df output line into a Volume
 fn parse_df_line(
 	line: &str,
@@ -74,15 +98,11 @@ fn parse_df_line(
 	let available_str = parts[4];
 	let mount_point = parts[6];
 
-	// Skip system filesystems unless requested
-	if !config.include_system && utils::is_system_filesystem(filesystem_device) {
-		return Ok(None);
-	}
-
-	// Skip virtual filesystems unless requested
-	if !config.include_virtual && utils::is_virtual_filesystem(filesystem_type) {
-		return Ok(None);
-	}
+	// System and virtual filesystems are skipped unless requested, but pinned
+	// volumes override the filters so we defer the decision until the
+	// fingerprint is known
+	let filtered_out = (!config.include_system && utils::is_system_filesystem(filesystem_device))
+		|| (!config.include_virtual && utils::is_virtual_filesystem(filesystem_type));
 
 	let mount_path = PathBuf::from(mount_point);
 
@@ -118,15 +138,8 @@ fn parse_df_line(
 	// Generate stable fingerprint based on volume type
 	let fingerprint = match volume_type {
 		crate::volume::types::VolumeType::External => {
-			// Try to read/create dotfile for external volumes
-			if let Some(spacedrive_id) =
-				utils::read_or_create_dotfile_sync(&mount_path, device_id, None)
-			{
-				VolumeFingerprint::from_external_volume(spacedrive_id, device_id)
-			} else {
-				// Fallback to mount_point + device_id for read-only external volumes
-				VolumeFingerprint::from_primary_volume(&mount_path, device_id)
-			}
+			// Dotfile id when allowed and writable, otherwise mount_point + device_id
+			utils::external_volume_fingerprint(&mount_path, device_id, config)
 		}
 		crate::volume::types::VolumeType::Network => {
 			// Use filesystem device as backend identifier for network volumes
@@ -139,6 +152,10 @@ fn parse_df_line(
 		}
 	};
 
+	if filtered_out && !config.is_pinned(&fingerprint) {
+		return Ok(None);
+	}
+
 	let mut volume = Volume::new(device_id, fingerprint, name.clone(), mount_path);
 
 	volume.mount_type = mount_type;
@@ -297,7 +314,11 @@ fn get_filesystem_space(mount_point: &str) -> VolumeResult<(u64, u64)> {
 }
 
 /// Create a Volume from MountInfo
-pub fn create_volume_from_mount(mount: MountInfo, device_id: Uuid) -> VolumeResult<Volume> {
+pub fn create_volume_from_mount(
+	mount: MountInfo,
+	device_id: Uuid,
+	config: &VolumeDetectionConfig,
+) -> VolumeResult<Volume> {
 	let mount_path = PathBuf::from(&mount.mount_point);
 	let file_system = utils::parse_filesystem_type(&mount.filesystem_type);
 
@@ -326,15 +347,8 @@ pub fn create_volume_from_mount(mount: MountInfo, device_id: Uuid) -> VolumeResu
 	// Generate stable fingerprint based on volume type
 	let fingerprint = match volume_type {
 		crate::volume::types::VolumeType::External => {
-			// Try to read/create dotfile for external volumes
-			if let Some(spacedrive_id) =
-				utils::read_or_create_dotfile_sync(&mount_path, device_id, None)
-			{
-				VolumeFingerprint::from_external_volume(spacedrive_id, device_id)
-			} else {
-				// Fallback to mount_point + device_id for read-only external volumes
-				VolumeFingerprint::from_primary_volume(&mount_path, device_id)
-			}
+			// Dotfile id when allowed and writable, otherwise mount_point + device_id
+			utils::external_volume_fingerprint(&mount_path, device_id, config)
 		}
 		crate::volume::types::VolumeType::Network => {
 			// Use device as backend identifier for network volumes
@@ -362,3 +376,220 @@ pub fn create_volume_from_mount(mount: MountInfo, device_id: Uuid) -> VolumeResu
 
 	Ok(volume)
 }
+
+/// Mount table of the current process
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+/// Watches the kernel mount table for mount and unmount events
+///
+/// procfs files do not produce inotify events, but the kernel flags
+/// `/proc/self/mountinfo` with `POLLPRI` whenever the mount table changes. The
+/// watcher thread waits on that, re-reading at `rescan_interval` regardless in
+/// case a wakeup is missed, and diffs the mount points it sees.
+pub struct MountInfoWatcher {
+	path: PathBuf,
+	rescan_interval: Duration,
+	stop: Arc<AtomicBool>,
+}
+
+impl MountInfoWatcher {
+	pub fn new() -> Self {
+		Self::with_path(MOUNTINFO_PATH, Duration::from_secs(5))
+	}
+
+	/// Watch a mountinfo-formatted file other than the process mount table
+	pub fn with_path(path: impl Into<PathBuf>, rescan_interval: Duration) -> Self {
+		Self {
+			path: path.into(),
+			rescan_interval,
+			stop: Arc::new(AtomicBool::new(false)),
+		}
+	}
+}
+
+impl Default for MountInfoWatcher {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl VolumeWatcher for MountInfoWatcher {
+	fn start(&mut self, events: mpsc::Sender<VolumeEvent>) -> VolumeResult<()> {
+		let mut file = File::open(&self.path).map_err(|e| {
+			VolumeError::platform(format!("Failed to open {}: {}", self.path.display(), e))
+		})?;
+		let mut known = read_mount_points(&mut file)?;
+
+		self.stop.store(false, Ordering::SeqCst);
+		let stop = self.stop.clone();
+		let rescan_interval = self.rescan_interval;
+
+		std::thread::Builder::new()
+			.name("volume-mount-watcher".to_string())
+			.spawn(move || {
+				while !stop.load(Ordering::SeqCst) && !events.is_closed() {
+					wait_for_mount_change(&file, rescan_interval);
+					if stop.load(Ordering::SeqCst) {
+						break;
+					}
+
+					let current = match read_mount_points(&mut file) {
+						Ok(current) => current,
+						Err(e) => {
+							debug!("Failed to re-read mount table: {}", e);
+							continue;
+						}
+					};
+
+					for event in diff_mount_points(&known, &current) {
+						debug!("Mount table change: {:?}", event);
+						if events.blocking_send(event).is_err() {
+							return;
+						}
+					}
+					known = current;
+				}
+				debug!("Mount watcher stopped");
+			})
+			.map_err(|e| VolumeError::platform(format!("Failed to spawn mount watcher: {}", e)))?;
+
+		Ok(())
+	}
+
+	fn stop(&mut self) {
+		self.stop.store(true, Ordering::SeqCst);
+	}
+}
+
+/// Block until the kernel flags a mount table change or the timeout elapses
+fn wait_for_mount_change(file: &File, timeout: Duration) {
+	let mut pollfd = libc::pollfd {
+		fd: file.as_raw_fd(),
+		events: libc::POLLPRI | libc::POLLERR,
+		revents: 0,
+	};
+	let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+
+	// SAFETY: pollfd points at a single valid pollfd for the duration of the call
+	unsafe {
+		libc::poll(&mut pollfd, 1, timeout_ms);
+	}
+}
+
+/// Read mount points from an open mountinfo file
+///
+/// Reading through the same descriptor that is polled is what clears the
+/// kernel's change flag, so the file is rewound rather than reopened.
+fn read_mount_points(file: &mut File) -> VolumeResult<BTreeSet<PathBuf>> {
+	let mut contents = String::new();
+	file.seek(SeekFrom::Start(0))
+		.and_then(|_| file.read_to_string(&mut contents))
+		.map_err(|e| VolumeError::platform(format!("Failed to read mount table: {}", e)))?;
+
+	Ok(parse_mountinfo(&contents))
+}
+
+/// Extract non-virtual mount points from mountinfo contents
+///
+/// Each line looks like
+/// `36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw`, where the
+/// fifth field is the mount point and the filesystem type follows the `-`.
+pub fn parse_mountinfo(contents: &str) -> BTreeSet<PathBuf> {
+	contents
+		.lines()
+		.filter_map(|line| {
+			let (mount, rest) = line.split_once(" - ")?;
+			let mount_point = mount.split_whitespace().nth(4)?;
+			let filesystem_type = rest.split_whitespace().next()?;
+			if utils::is_virtual_filesystem(filesystem_type) {
+				return None;
+			}
+			Some(PathBuf::from(unescape_mount_path(mount_point)))
+		})
+		.collect()
+}
+
+/// Undo the kernel's octal escaping of whitespace and backslashes (e.g. `\040`)
+fn unescape_mount_path(raw: &str) -> String {
+	let bytes = raw.as_bytes();
+	let mut out = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		if bytes[i] == b'\\' && i + 4 <= bytes.len() {
+			let digits = std::str::from_utf8(&bytes[i + 1..i + 4]).ok();
+			if let Some(value) = digits.and_then(|d| u8::from_str_radix(d, 8).ok()) {
+				out.push(value);
+				i += 4;
+				continue;
+			}
+		}
+		out.push(bytes[i]);
+		i += 1;
+	}
+	String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::Path;
+
+	const ROOT_LINE: &str = "22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n";
+	const PROC_LINE: &str = "23 22 0:21 / /proc rw,nosuid shared:12 - proc proc rw\n";
+	const USB_LINE: &str =
+		"90 22 8:17 / /media/user/My\\040Drive rw,nosuid shared:40 - vfat /dev/sdb1 rw\n";
+
+	#[test]
+	fn test_parse_mountinfo() {
+		let mounts = parse_mountinfo(&format!("{ROOT_LINE}{PROC_LINE}{USB_LINE}"));
+
+		assert_eq!(mounts.len(), 2);
+		assert!(mounts.contains(Path::new("/")));
+		assert!(mounts.contains(Path::new("/media/user/My Drive")));
+	}
+
+	#[test]
+	fn test_local_only_detection_skips_network_filesystems() {
+		assert!(!df_args(false).contains(&"-l"));
+		assert!(df_args(true).contains(&"-l"));
+	}
+
+	#[tokio::test]
+	async fn test_mountinfo_watcher_reports_synthetic_changes() {
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let path = temp_dir.path().join("mountinfo");
+		std::fs::write(&path, format!("{ROOT_LINE}{PROC_LINE}")).unwrap();
+
+		let (tx, mut rx) = mpsc::channel(16);
+		let mut watcher = MountInfoWatcher::with_path(&path, Duration::from_millis(20));
+		watcher.start(tx).unwrap();
+
+		// Append and truncate in single syscalls so the watcher never sees a half-written table
+		let mut table = std::fs::OpenOptions::new()
+			.append(true)
+			.open(&path)
+			.unwrap();
+		let original_len = table.metadata().unwrap().len();
+		std::io::Write::write_all(&mut table, USB_LINE.as_bytes()).unwrap();
+		let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(matches!(
+			event,
+			VolumeEvent::Mounted { mount_point } if mount_point == Path::new("/media/user/My Drive")
+		));
+
+		table.set_len(original_len).unwrap();
+		let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(matches!(
+			event,
+			VolumeEvent::Unmounted { mount_point } if mount_point == Path::new("/media/user/My Drive")
+		));
+
+		watcher.stop();
+	}
+}
@@ -57,15 +57,11 @@ pub async fn detect_non_apfs_volumes(
 					continue;
 				}
 
-				// Skip system filesystems unless requested
-				if !config.include_system && utils::is_system_filesystem(filesystem) {
-					continue;
-				}
-
-				// Skip virtual filesystems unless requested
-				if !config.include_virtual && utils::is_virtual_filesystem(filesystem) {
-					continue;
-				}
+				// System and virtual filesystems are skipped unless requested,
+				// but pinned volumes override the filters once fingerprinted
+				let filtered_out = (!config.include_system
+					&& utils::is_system_filesystem(filesystem))
+					|| (!config.include_virtual && utils::is_virtual_filesystem(filesystem));
 
 				// Parse sizes (in bytes)
 				let total_bytes = utils::parse_size_string(fields[1]).unwrap_or(0);
@@ -91,15 +87,8 @@ pub async fn detect_non_apfs_volumes(
 				// Generate stable fingerprint based on volume type
 				let fingerprint = match volume_type {
 					crate::volume::types::VolumeType::External => {
-						// Try to read/create dotfile for external volumes
-						if let Some(spacedrive_id) =
-							utils::read_or_create_dotfile_sync(&mount_path, device_id, None)
-						{
-							VolumeFingerprint::from_external_volume(spacedrive_id, device_id)
-						} else {
-							// Fallback to mount_point + device_id for read-only external volumes
-							VolumeFingerprint::from_primary_volume(&mount_path, device_id)
-						}
+						// Dotfile id when allowed and writable, otherwise mount_point + device_id
+						utils::external_volume_fingerprint(&mount_path, device_id, &config)
 					}
 					crate::volume::types::VolumeType::Network => {
 						// Use filesystem as backend identifier for network volumes
@@ -115,6 +104,10 @@ pub async fn detect_non_apfs_volumes(
 					}
 				};
 
+				if filtered_out && !config.is_pinned(&fingerprint) {
+					continue;
+				}
+
 				// Check if volume should be user-visible
 				let is_user_visible = should_be_user_visible(&mount_path, &name, filesystem);
 
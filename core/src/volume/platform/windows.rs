@@ -6,10 +6,17 @@
 use crate::volume::{
 	classification::{get_classifier, VolumeDetectionInfo},
 	error::{VolumeError, VolumeResult},
-	types::{DiskType, FileSystem, MountType, Volume, VolumeDetectionConfig, VolumeFingerprint},
+	types::{
+		DiskType, FileSystem, MountType, Volume, VolumeDetectionConfig, VolumeEvent,
+		VolumeFingerprint,
+	},
 	utils,
+	watcher::{diff_mount_points, VolumeWatcher},
 };
+use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::path::PathBuf;
+use tokio::sync::mpsc;
 use tokio::task;
 use tracing::{debug, warn};
 use uuid::Uuid;
@@ -64,25 +71,26 @@ pub async fn detect_volumes(
 			let volume_type =
 				classify_volume(&mount_point, &file_system, &name, is_removable, total_space);
 
+			let guid = volume_guid(&mount_point);
+
 			// Generate stable fingerprint based on volume type
 			let fingerprint = match volume_type {
 				crate::volume::types::VolumeType::External => {
-					if let Some(spacedrive_id) =
-						utils::read_or_create_dotfile_sync(&mount_point, device_id, None)
-					{
-						VolumeFingerprint::from_external_volume(spacedrive_id, device_id)
-					} else {
-						VolumeFingerprint::from_primary_volume(&mount_point, device_id)
-					}
+					utils::external_volume_fingerprint(&mount_point, device_id, &config)
 				}
 				crate::volume::types::VolumeType::Network => {
 					let path_lossy = mount_point.to_string_lossy();
 					VolumeFingerprint::from_network_volume(&path_lossy, &path_lossy)
 				}
-				_ => VolumeFingerprint::from_primary_volume(&mount_point, device_id),
+				// Drive letters can change between reboots, the volume GUID does not
+				_ => match &guid {
+					Some(guid) => VolumeFingerprint::from_stable_hardware_id(guid, device_id),
+					None => VolumeFingerprint::from_primary_volume(&mount_point, device_id),
+				},
 			};
 
 			let mut volume = Volume::new(device_id, fingerprint, name.clone(), mount_point);
+			volume.hardware_id = guid;
 
 			volume.mount_type = mount_type;
 			volume.volume_type = volume_type;
@@ -92,7 +100,7 @@ pub async fn detect_volumes(
 			volume.available_space = available_space;
 			volume.is_read_only = false;
 
-			if should_include_volume(&volume, &config) {
+			if utils::should_include_volume(&volume, &config) {
 				debug!(
 					"Detected volume: {} ({}) - {} bytes",
 					volume.name,
@@ -140,15 +148,207 @@ fn determine_mount_type_windows(mount_point: &std::path::Path) -> MountType {
 	}
 }
 
-/// Check if volume should be included based on config
-pub fn should_include_volume(volume: &Volume, config: &VolumeDetectionConfig) -> bool {
-	if !config.include_system && matches!(volume.mount_type, MountType::System) {
-		return false;
+/// Watches for drive arrival and removal via `WM_DEVICECHANGE`
+///
+/// Volume arrival is only broadcast to top-level windows, so the watcher owns a
+/// hidden window on a dedicated thread and diffs the logical drive bitmask
+/// whenever the system reports a device change.
+pub struct DeviceChangeWatcher {
+	thread_id: Option<u32>,
+}
+
+impl DeviceChangeWatcher {
+	pub fn new() -> Self {
+		Self { thread_id: None }
+	}
+}
+
+struct DeviceChangeState {
+	events: mpsc::Sender<VolumeEvent>,
+	drives: BTreeSet<PathBuf>,
+}
+
+thread_local! {
+	static DEVICE_CHANGE_STATE: RefCell<Option<DeviceChangeState>> = RefCell::new(None);
+}
+
+impl Default for DeviceChangeWatcher {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl VolumeWatcher for DeviceChangeWatcher {
+	fn start(&mut self, events: mpsc::Sender<VolumeEvent>) -> VolumeResult<()> {
+		let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+		std::thread::Builder::new()
+			.name("volume-device-watcher".to_string())
+			.spawn(move || run_device_change_loop(events, ready_tx))
+			.map_err(|e| VolumeError::platform(format!("Failed to spawn device watcher: {}", e)))?;
+
+		let thread_id = ready_rx
+			.recv()
+			.map_err(|_| VolumeError::platform("Device watcher exited during startup"))??;
+		self.thread_id = Some(thread_id);
+
+		Ok(())
+	}
+
+	fn stop(&mut self) {
+		use windows_sys::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+		if let Some(thread_id) = self.thread_id.take() {
+			// SAFETY: posting to a thread id has no memory safety requirements
+			unsafe {
+				PostThreadMessageW(thread_id, WM_QUIT, 0, 0);
+			}
+		}
+	}
+}
+
+/// Volume GUID path (e.g. `\\?\Volume{...}\`) for a drive root, if Windows has one
+fn volume_guid(mount_point: &std::path::Path) -> Option<String> {
+	use std::os::windows::ffi::OsStrExt;
+	use windows_sys::Win32::Storage::FileSystem::GetVolumeNameForVolumeMountPointW;
+
+	let mut root: Vec<u16> = mount_point.as_os_str().encode_wide().collect();
+	if root.last() != Some(&(b'\\' as u16)) {
+		root.push(b'\\' as u16);
+	}
+	root.push(0);
+
+	// A volume GUID path is 49 characters plus the terminator
+	let mut buffer = [0u16; 50];
+	// SAFETY: `root` is null-terminated and `buffer` is writable for its full length
+	let ok = unsafe {
+		GetVolumeNameForVolumeMountPointW(root.as_ptr(), buffer.as_mut_ptr(), buffer.len() as u32)
+	};
+	if ok == 0 {
+		debug!(
+			"No volume GUID for {}, falling back to mount point",
+			mount_point.display()
+		);
+		return None;
+	}
+
+	let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+	Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// Drive roots (e.g. `C:\`) currently present according to `GetLogicalDrives`
+fn logical_drives() -> BTreeSet<PathBuf> {
+	use windows_sys::Win32::Storage::FileSystem::GetLogicalDrives;
+
+	// SAFETY: GetLogicalDrives takes no arguments and only returns a bitmask
+	let mask = unsafe { GetLogicalDrives() };
+	(0..26u8)
+		.filter(|bit| mask & (1 << bit) != 0)
+		.map(|bit| PathBuf::from(format!("{}:\\", (b'A' + bit) as char)))
+		.collect()
+}
+
+fn run_device_change_loop(
+	events: mpsc::Sender<VolumeEvent>,
+	ready: std::sync::mpsc::Sender<VolumeResult<u32>>,
+) {
+	use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+	use windows_sys::Win32::System::Threading::GetCurrentThreadId;
+	use windows_sys::Win32::UI::WindowsAndMessaging::{
+		CreateWindowExW, DestroyWindow, DispatchMessageW, GetMessageW, RegisterClassW,
+		TranslateMessage, MSG, WNDCLASSW,
+	};
+
+	let class_name: Vec<u16> = "SpacedriveVolumeWatcher\0".encode_utf16().collect();
+
+	DEVICE_CHANGE_STATE.with(|state| {
+		*state.borrow_mut() = Some(DeviceChangeState {
+			events,
+			drives: logical_drives(),
+		});
+	});
+
+	// SAFETY: all pointers passed below outlive the calls, and the window is
+	// created, pumped and destroyed on this thread only
+	unsafe {
+		let instance = GetModuleHandleW(std::ptr::null());
+		let class = WNDCLASSW {
+			style: 0,
+			lpfnWndProc: Some(device_change_wndproc),
+			cbClsExtra: 0,
+			cbWndExtra: 0,
+			hInstance: instance,
+			hIcon: 0,
+			hCursor: 0,
+			hbrBackground: 0,
+			lpszMenuName: std::ptr::null(),
+			lpszClassName: class_name.as_ptr(),
+		};
+		RegisterClassW(&class);
+
+		// A hidden top-level window: message-only windows miss device broadcasts
+		let hwnd = CreateWindowExW(
+			0,
+			class_name.as_ptr(),
+			class_name.as_ptr(),
+			0,
+			0,
+			0,
+			0,
+			0,
+			0,
+			0,
+			instance,
+			std::ptr::null(),
+		);
+		if hwnd == 0 {
+			let _ = ready.send(Err(VolumeError::platform(
+				"Failed to create device watcher window",
+			)));
+			return;
+		}
+		let _ = ready.send(Ok(GetCurrentThreadId()));
+
+		let mut msg: MSG = std::mem::zeroed();
+		while GetMessageW(&mut msg, 0, 0, 0) > 0 {
+			TranslateMessage(&msg);
+			DispatchMessageW(&msg);
+		}
+
+		DestroyWindow(hwnd);
 	}
 
-	if !config.include_virtual && volume.total_bytes_capacity() == 0 {
-		return false;
+	DEVICE_CHANGE_STATE.with(|state| state.borrow_mut().take());
+	debug!("Device watcher stopped");
+}
+
+unsafe extern "system" fn device_change_wndproc(
+	hwnd: windows_sys::Win32::Foundation::HWND,
+	msg: u32,
+	wparam: windows_sys::Win32::Foundation::WPARAM,
+	lparam: windows_sys::Win32::Foundation::LPARAM,
+) -> windows_sys::Win32::Foundation::LRESULT {
+	use windows_sys::Win32::UI::WindowsAndMessaging::{
+		DefWindowProcW, DBT_DEVICEARRIVAL, DBT_DEVICEREMOVECOMPLETE, WM_DEVICECHANGE,
+	};
+
+	if msg == WM_DEVICECHANGE
+		&& (wparam == DBT_DEVICEARRIVAL as usize || wparam == DBT_DEVICEREMOVECOMPLETE as usize)
+	{
+		DEVICE_CHANGE_STATE.with(|state| {
+			if let Some(state) = state.borrow_mut().as_mut() {
+				let current = logical_drives();
+				for event in diff_mount_points(&state.drives, &current) {
+					debug!("Device change: {:?}", event);
+					if state.events.blocking_send(event).is_err() {
+						warn!("Volume event receiver dropped, ignoring device change");
+						break;
+					}
+				}
+				state.drives = current;
+			}
+		});
 	}
 
-	true
+	DefWindowProcW(hwnd, msg, wparam, lparam)
 }
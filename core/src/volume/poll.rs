@@ -0,0 +1,111 @@
+//! Per-type volume polling schedule
+//!
+//! The monitor wakes at the shortest configured interval and only refreshes the
+//! volume types whose own interval has elapsed since they were last polled, so
+//! slow network mounts are not re-read every time a local drive is.
+
+use crate::volume::types::{VolumeDetectionConfig, VolumeType};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Tracks when each volume type was last polled
+#[derive(Debug, Default)]
+pub struct VolumePollScheduler {
+	last_polled: HashMap<VolumeType, Instant>,
+}
+
+impl VolumePollScheduler {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// How often the monitor needs to wake, None if no type is polled on a timer
+	///
+	/// A type whose interval is zero is never polled by the timer; it is still
+	/// refreshed on mount events and explicit refreshes.
+	pub fn tick_interval(config: &VolumeDetectionConfig) -> Option<Duration> {
+		VolumeType::ALL
+			.iter()
+			.map(|volume_type| config.refresh_interval_for(*volume_type))
+			.filter(|secs| *secs > 0)
+			.min()
+			.map(Duration::from_secs)
+	}
+
+	/// Volume types due for a poll at `now`, which are marked as polled
+	pub fn take_due(
+		&mut self,
+		config: &VolumeDetectionConfig,
+		now: Instant,
+	) -> HashSet<VolumeType> {
+		let mut due = HashSet::new();
+		for volume_type in VolumeType::ALL {
+			let secs = config.refresh_interval_for(volume_type);
+			if secs == 0 {
+				continue;
+			}
+			let is_due = match self.last_polled.get(&volume_type) {
+				Some(last) => now.saturating_duration_since(*last) >= Duration::from_secs(secs),
+				None => true,
+			};
+			if is_due {
+				self.last_polled.insert(volume_type, now);
+				due.insert(volume_type);
+			}
+		}
+		due
+	}
+
+	/// When a volume type was last polled
+	pub fn last_polled(&self, volume_type: VolumeType) -> Option<Instant> {
+		self.last_polled.get(&volume_type).copied()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_network_volumes_polled_less_often_than_local() {
+		let config = VolumeDetectionConfig::default();
+		let tick = VolumePollScheduler::tick_interval(&config).unwrap();
+		assert_eq!(tick, Duration::from_secs(config.refresh_interval_secs));
+
+		let mut scheduler = VolumePollScheduler::new();
+		let mut polls: HashMap<VolumeType, usize> = HashMap::new();
+		let start = Instant::now();
+
+		// Simulate an hour of monitor ticks
+		let mut elapsed = Duration::ZERO;
+		while elapsed <= Duration::from_secs(3600) {
+			for volume_type in scheduler.take_due(&config, start + elapsed) {
+				*polls.entry(volume_type).or_default() += 1;
+			}
+			elapsed += tick;
+		}
+
+		let primary = polls[&VolumeType::Primary];
+		let external = polls[&VolumeType::External];
+		let network = polls[&VolumeType::Network];
+		assert_eq!(primary, 121);
+		assert_eq!(external, primary);
+		assert_eq!(network, 13);
+		assert!(network < primary);
+		assert_eq!(
+			scheduler.last_polled(VolumeType::Network),
+			Some(start + Duration::from_secs(3600))
+		);
+	}
+
+	#[test]
+	fn test_zero_interval_disables_timer_polling() {
+		let mut config = VolumeDetectionConfig::default();
+		config.type_refresh_intervals.insert(VolumeType::Network, 0);
+
+		let mut scheduler = VolumePollScheduler::new();
+		let due = scheduler.take_due(&config, Instant::now());
+		assert!(due.contains(&VolumeType::Primary));
+		assert!(!due.contains(&VolumeType::Network));
+	}
+}
@@ -6,6 +6,6 @@
 // Re-export all volume types from domain
 pub use crate::domain::volume::{
 	ApfsContainer, ApfsVolumeInfo, ApfsVolumeRole, DiskType, FileSystem, MountType, PathMapping,
-	SpacedriveVolumeId, TrackedVolume, Volume, VolumeDetectionConfig, VolumeEvent,
+	SpacedriveVolumeId, TrackedVolume, Volume, VolumeChange, VolumeDetectionConfig, VolumeEvent,
 	VolumeFingerprint, VolumeInfo, VolumeType,
 };
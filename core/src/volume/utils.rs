@@ -4,7 +4,7 @@ use crate::{
 	domain::volume::{SpacedriveVolumeId, SPACEDRIVE_VOLUME_ID_FILE},
 	volume::{
 		error::{VolumeError, VolumeResult},
-		types::FileSystem,
+		types::{FileSystem, MountType, Volume, VolumeDetectionConfig, VolumeFingerprint},
 	},
 };
 use std::path::Path;
@@ -49,6 +49,25 @@ pub fn parse_size_string(size_str: &str) -> VolumeResult<u64> {
 	Ok((number * multiplier as f64) as u64)
 }
 
+/// Check if volume should be included based on config
+///
+/// Pinned volumes are always included, regardless of the system/virtual filters.
+pub fn should_include_volume(volume: &Volume, config: &VolumeDetectionConfig) -> bool {
+	if config.is_pinned(&volume.fingerprint) {
+		return true;
+	}
+
+	if !config.include_system && matches!(volume.mount_type, MountType::System) {
+		return false;
+	}
+
+	if !config.include_virtual && volume.total_bytes_capacity() == 0 {
+		return false;
+	}
+
+	true
+}
+
 /// Check if a filesystem should be considered a system filesystem
 pub fn is_system_filesystem(filesystem: &str) -> bool {
 	matches!(
@@ -322,6 +341,69 @@ pub fn read_or_create_dotfile_sync(
 	None
 }
 
+/// Give a cloned volume's dotfile a fresh id
+///
+/// A drive cloned from another carries a copy of its dotfile, so both would
+/// fingerprint the same. Only a dotfile whose id produces `fingerprint` is
+/// replaced; the rest of its contents are kept. Returns the new fingerprint, or
+/// None if there's no such dotfile or it can't be rewritten.
+pub fn regenerate_dotfile_sync(
+	mount_point: &Path,
+	device_id: Uuid,
+	fingerprint: &VolumeFingerprint,
+) -> Option<VolumeFingerprint> {
+	let id_file_path = mount_point.join(SPACEDRIVE_VOLUME_ID_FILE);
+
+	let content = std::fs::read_to_string(&id_file_path).ok()?;
+	let mut spacedrive_id = serde_json::from_str::<SpacedriveVolumeId>(&content).ok()?;
+	if VolumeFingerprint::from_external_volume(spacedrive_id.id, device_id) != *fingerprint {
+		return None;
+	}
+
+	let previous = spacedrive_id.id;
+	spacedrive_id.id = Uuid::new_v4();
+	spacedrive_id.created = chrono::Utc::now();
+
+	let content = serde_json::to_string_pretty(&spacedrive_id).ok()?;
+	if let Err(e) = std::fs::write(&id_file_path, content) {
+		warn!(
+			"Could not rewrite cloned dotfile at {}: {}",
+			id_file_path.display(),
+			e
+		);
+		return None;
+	}
+
+	info!(
+		"Replaced cloned dotfile ID {} with {} at {}",
+		previous,
+		spacedrive_id.id,
+		id_file_path.display()
+	);
+	Some(VolumeFingerprint::from_external_volume(
+		spacedrive_id.id,
+		device_id,
+	))
+}
+
+/// Fingerprint for an external volume
+///
+/// Uses the volume's dotfile id when dotfiles are enabled, creating the file if
+/// needed. Without a dotfile (disabled, or the volume is read-only) the
+/// fingerprint falls back to the mount point and device id.
+pub fn external_volume_fingerprint(
+	mount_point: &Path,
+	device_id: Uuid,
+	config: &VolumeDetectionConfig,
+) -> VolumeFingerprint {
+	if config.write_external_dotfiles {
+		if let Some(spacedrive_id) = read_or_create_dotfile_sync(mount_point, device_id, None) {
+			return VolumeFingerprint::from_external_volume(spacedrive_id, device_id);
+		}
+	}
+	VolumeFingerprint::from_primary_volume(mount_point, device_id)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -350,6 +432,60 @@ mod tests {
 		assert!(!is_system_filesystem("/home"));
 	}
 
+	#[test]
+	fn test_pinned_volume_overrides_system_filter() {
+		use crate::volume::types::VolumeFingerprint;
+		use std::path::PathBuf;
+
+		let device_id = Uuid::new_v4();
+		let fingerprint =
+			VolumeFingerprint::from_primary_volume(&PathBuf::from("/boot"), device_id);
+		let mut volume = Volume::new(
+			device_id,
+			fingerprint.clone(),
+			"boot".to_string(),
+			PathBuf::from("/boot"),
+		);
+		volume.mount_type = MountType::System;
+		volume.total_capacity = 512 * 1024 * 1024;
+
+		let mut config = VolumeDetectionConfig {
+			include_system: false,
+			..Default::default()
+		};
+		assert!(!should_include_volume(&volume, &config));
+
+		config.pinned_fingerprints.push(fingerprint);
+		assert!(should_include_volume(&volume, &config));
+	}
+
+	#[test]
+	fn test_external_fingerprint_without_dotfiles() {
+		let mount = tempfile::tempdir().unwrap();
+		let device_id = Uuid::new_v4();
+		let config = VolumeDetectionConfig {
+			write_external_dotfiles: false,
+			..Default::default()
+		};
+
+		let fingerprint = external_volume_fingerprint(mount.path(), device_id, &config);
+
+		assert_eq!(
+			fingerprint,
+			VolumeFingerprint::from_primary_volume(mount.path(), device_id)
+		);
+		assert!(!mount.path().join(SPACEDRIVE_VOLUME_ID_FILE).exists());
+
+		// With dotfiles enabled the same volume gets a file-backed fingerprint
+		let fingerprint =
+			external_volume_fingerprint(mount.path(), device_id, &VolumeDetectionConfig::default());
+		assert!(mount.path().join(SPACEDRIVE_VOLUME_ID_FILE).exists());
+		assert_ne!(
+			fingerprint,
+			VolumeFingerprint::from_primary_volume(mount.path(), device_id)
+		);
+	}
+
 	#[test]
 	fn test_parse_filesystem_type() {
 		assert!(matches!(parse_filesystem_type("apfs"), FileSystem::APFS));
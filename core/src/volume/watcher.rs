@@ -0,0 +1,83 @@
+//! Event-driven mount/unmount detection
+//!
+//! Platform watchers push [`VolumeEvent::Mounted`] and [`VolumeEvent::Unmounted`]
+//! as soon as the OS reports a change, so new drives show up without waiting for
+//! the next poll. The periodic refresh in the volume manager stays in place as a
+//! reconciliation pass for anything a watcher misses.
+
+use crate::volume::{error::VolumeResult, types::VolumeEvent};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+
+/// A source of mount and unmount notifications
+pub trait VolumeWatcher: Send + Sync {
+	/// Start watching, sending events on `events` until stopped or the receiver is dropped
+	fn start(&mut self, events: mpsc::Sender<VolumeEvent>) -> VolumeResult<()>;
+
+	/// Stop watching. Events already queued may still be delivered.
+	fn stop(&mut self);
+}
+
+/// Create the mount watcher for the current platform, if there is one
+///
+/// Platforms without a native watcher rely on directory watching and polling.
+pub fn platform_watcher() -> Option<Box<dyn VolumeWatcher>> {
+	#[cfg(target_os = "linux")]
+	{
+		return Some(Box::new(super::platform::linux::MountInfoWatcher::new()));
+	}
+
+	#[cfg(target_os = "windows")]
+	{
+		return Some(Box::new(
+			super::platform::windows::DeviceChangeWatcher::new(),
+		));
+	}
+
+	#[allow(unreachable_code)]
+	None
+}
+
+/// Turn two snapshots of mount points into mount and unmount events
+pub fn diff_mount_points(
+	previous: &BTreeSet<PathBuf>,
+	current: &BTreeSet<PathBuf>,
+) -> Vec<VolumeEvent> {
+	let unmounted = previous
+		.difference(current)
+		.map(|mount_point| VolumeEvent::Unmounted {
+			mount_point: mount_point.clone(),
+		});
+	let mounted = current
+		.difference(previous)
+		.map(|mount_point| VolumeEvent::Mounted {
+			mount_point: mount_point.clone(),
+		});
+
+	unmounted.chain(mounted).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_diff_mount_points() {
+		let previous: BTreeSet<_> = [PathBuf::from("/"), PathBuf::from("/media/old")].into();
+		let current: BTreeSet<_> = [PathBuf::from("/"), PathBuf::from("/media/new")].into();
+
+		let events = diff_mount_points(&previous, &current);
+
+		assert_eq!(events.len(), 2);
+		assert!(matches!(
+			&events[0],
+			VolumeEvent::Unmounted { mount_point } if mount_point == &PathBuf::from("/media/old")
+		));
+		assert!(matches!(
+			&events[1],
+			VolumeEvent::Mounted { mount_point } if mount_point == &PathBuf::from("/media/new")
+		));
+		assert!(diff_mount_points(&current, &current).is_empty());
+	}
+}
@@ -7,6 +7,7 @@
 use sd_core::{
 	domain::addressing::{SdPath, SdPathBatch},
 	ops::files::copy::{action::FileCopyAction, CopyOptions},
+	service::network::Protocol,
 	testing::CargoTestRunner,
 	Core,
 };
@@ -335,7 +336,7 @@ async fn bob_cross_device_copy_scenario() {
 	if let Some(networking) = core.networking() {
 		let protocol_registry = networking.protocol_registry();
 		let registry_guard = protocol_registry.read().await;
-		if let Some(file_transfer_handler) = registry_guard.get_handler("file_transfer") {
+		if let Some(file_transfer_handler) = registry_guard.get_handler(Protocol::FileTransfer) {
 			if let Some(handler) = file_transfer_handler
 				.as_any()
 				.downcast_ref::<sd_core::service::network::protocol::FileTransferProtocolHandler>(
@@ -5,7 +5,7 @@
 
 use sd_core::{
 	domain::SdPath,
-	infra::event::{Event, ResourceMetadata},
+	infra::event::{Event, EventBus, ResourceMetadata, SubscriptionFilter, REPLAY_BUFFER_CAPACITY},
 };
 use std::path::PathBuf;
 
@@ -275,3 +275,96 @@ fn test_empty_affected_paths_global_resource() {
 		"Global resources (empty affected_paths) should match all scopes"
 	);
 }
+
+#[test]
+fn test_filtered_subscriber_only_sees_matching_events() {
+	let bus = EventBus::default();
+	let mut vouching = bus.subscribe_filtered(vec![SubscriptionFilter::Global {
+		resource_type: "vouching_session".to_string(),
+	}]);
+	let mut core_events = bus.subscribe_filtered(vec![SubscriptionFilter::EventKind {
+		event_type: "CoreStarted".to_string(),
+	}]);
+	let mut everything = bus.subscribe();
+
+	bus.emit(Event::CoreStarted);
+	bus.emit(Event::ResourceChanged {
+		resource_type: "location".to_string(),
+		resource: serde_json::json!({"id": "123"}),
+		metadata: None,
+	});
+	bus.emit(Event::ResourceChanged {
+		resource_type: "vouching_session".to_string(),
+		resource: serde_json::json!({"id": "456"}),
+		metadata: None,
+	});
+
+	let event = vouching.try_recv().unwrap();
+	assert_eq!(event.resource_type(), Some("vouching_session"));
+	assert!(vouching.try_recv().is_err());
+
+	assert!(matches!(
+		core_events.try_recv().unwrap(),
+		Event::CoreStarted
+	));
+	assert!(core_events.try_recv().is_err());
+
+	// Unfiltered subscribers still receive every event
+	for _ in 0..3 {
+		everything.try_recv().unwrap();
+	}
+}
+
+#[test]
+fn test_late_subscriber_can_replay_missed_events() {
+	let bus = EventBus::default();
+	let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+	let session_id = uuid::Uuid::new_v4();
+	bus.emit(Event::ProxyPairingVouchingReady {
+		session_id,
+		vouchee_device_id: uuid::Uuid::new_v4(),
+	});
+	bus.emit(Event::Custom {
+		event_type: "secret".to_string(),
+		data: serde_json::json!({"code": "hunter2"}),
+	});
+	bus.emit(Event::CoreStarted);
+
+	// Joined after the emissions, so nothing arrives live
+	let mut late = bus.subscribe();
+	assert!(late.try_recv().is_err());
+
+	let replayed = bus.replay_since(before);
+	assert_eq!(replayed.len(), 2, "custom events must not be buffered");
+	assert!(matches!(
+		replayed[0].event,
+		Event::ProxyPairingVouchingReady { session_id: id, .. } if id == session_id
+	));
+	assert!(matches!(replayed[1].event, Event::CoreStarted));
+	assert!(replayed[0].emitted_at <= replayed[1].emitted_at);
+
+	assert!(bus.replay_since(replayed[1].emitted_at).is_empty());
+}
+
+#[test]
+fn test_replay_buffer_is_bounded_per_kind() {
+	let bus = EventBus::default();
+	let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+	bus.emit(Event::CoreStarted);
+	for _ in 0..REPLAY_BUFFER_CAPACITY * 2 {
+		bus.emit(Event::LibraryClosed {
+			id: uuid::Uuid::new_v4(),
+			name: "Library".to_string(),
+		});
+	}
+
+	// A burst of one kind keeps only its newest events and leaves the rest alone
+	let replayed = bus.replay_since(before);
+	assert_eq!(replayed.len(), REPLAY_BUFFER_CAPACITY + 1);
+	assert!(matches!(replayed[0].event, Event::CoreStarted));
+	assert!(replayed[1..]
+		.iter()
+		.all(|e| matches!(e.event, Event::LibraryClosed { .. })));
+}
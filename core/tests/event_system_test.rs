@@ -14,6 +14,7 @@
 //! potential conflicts between tests
 
 use sd_core::{
+	domain::ScanState,
 	infra::event::{Event, EventFilter},
 	location::{create_location, IndexMode, LocationCreateArgs},
 	Core,
@@ -240,6 +241,106 @@ async fn test_location_and_job_events() -> Result<(), Box<dyn std::error::Error
 	Ok(())
 }
 
+#[tokio::test]
+async fn test_scan_completion_emits_terminal_state_once(
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+	let temp_dir = TempDir::new()?;
+	let core = Core::new(temp_dir.path().to_path_buf()).await?;
+
+	let library = core
+		.libraries
+		.create_library("Test Scan State Events", None, core.context.clone())
+		.await?;
+	let library_id = library.id();
+
+	let scan_states = Arc::new(Mutex::new(Vec::new()));
+	let scan_states_clone = scan_states.clone();
+	let mut event_subscriber = core.events.subscribe();
+	let event_collector = tokio::spawn(async move {
+		while let Ok(event) = event_subscriber.recv().await {
+			if let Event::LocationScanStateChanged {
+				library_id: lid,
+				state,
+				..
+			} = event
+			{
+				if lid == library_id {
+					scan_states_clone.lock().await.push(state);
+				}
+			}
+		}
+	});
+
+	let test_location_dir = temp_dir.path().join("scan_state_location");
+	tokio::fs::create_dir_all(&test_location_dir).await?;
+	tokio::fs::write(test_location_dir.join("test.txt"), "Hello World").await?;
+
+	use sd_core::infra::db::entities;
+	use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter};
+
+	let device = core.device.to_device()?;
+	let device_record = match entities::device::Entity::find()
+		.filter(entities::device::Column::Uuid.eq(device.id))
+		.one(library.db().conn())
+		.await?
+	{
+		Some(existing) => existing,
+		None => {
+			let device_model: entities::device::ActiveModel = device.into();
+			device_model.insert(library.db().conn()).await?
+		}
+	};
+
+	create_location(
+		library.clone(),
+		&core.events,
+		LocationCreateArgs {
+			path: test_location_dir,
+			name: Some("Scan State Location".to_string()),
+			index_mode: IndexMode::Shallow,
+		},
+		device_record.id,
+	)
+	.await?;
+
+	// Wait for the scan to reach a terminal state, then a little longer for stragglers
+	let waited = timeout(Duration::from_secs(30), async {
+		loop {
+			if scan_states
+				.lock()
+				.await
+				.iter()
+				.any(|state| matches!(state, ScanState::Completed | ScanState::Failed))
+			{
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	})
+	.await;
+	assert!(waited.is_ok(), "Scan should reach a terminal state");
+	tokio::time::sleep(Duration::from_millis(500)).await;
+	event_collector.abort();
+
+	let states = scan_states.lock().await;
+	assert!(matches!(states.first(), Some(ScanState::Scanning { .. })));
+	assert_eq!(
+		states
+			.iter()
+			.filter(|state| **state == ScanState::Completed)
+			.count(),
+		1,
+		"Completing a scan should emit its terminal state exactly once: {:?}",
+		states
+	);
+
+	drop(library);
+	core.libraries.close_library(library_id).await?;
+	core.shutdown().await?;
+
+	Ok(())
+}
+
 #[tokio::test]
 async fn test_event_filtering() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 	let temp_dir = TempDir::new()?;
@@ -7,6 +7,7 @@
 use sd_core::{
 	domain::addressing::{SdPath, SdPathBatch},
 	ops::files::copy::{action::FileCopyAction, CopyOptions},
+	service::network::Protocol,
 	testing::CargoTestRunner,
 	Core,
 };
@@ -106,7 +107,7 @@ async fn alice_pull_source_scenario() {
 	if let Some(networking) = core.networking() {
 		let protocol_registry = networking.protocol_registry();
 		let registry = protocol_registry.read().await;
-		if let Some(handler) = registry.get_handler("file_transfer") {
+		if let Some(handler) = registry.get_handler(Protocol::FileTransfer) {
 			if let Some(ft_handler) =
 				handler
 					.as_any()
@@ -369,7 +370,7 @@ async fn bob_pull_receiver_scenario() {
 	if let Some(networking) = core.networking() {
 		let protocol_registry = networking.protocol_registry();
 		let registry = protocol_registry.read().await;
-		if let Some(handler) = registry.get_handler("file_transfer") {
+		if let Some(handler) = registry.get_handler(Protocol::FileTransfer) {
 			if let Some(ft_handler) =
 				handler
 					.as_any()
@@ -66,6 +66,7 @@ impl FileSyncTestSetup {
 			logging: sd_core::config::LoggingConfig::default(),
 			proxy_pairing: sd_core::config::ProxyPairingConfig::default(),
 			spacebot: sd_core::config::SpacebotConfig::default(),
+			network: sd_core::config::NetworkDiscoveryConfig::default(),
 		};
 		config.save()?;
 
@@ -4,8 +4,10 @@
 //! (sender) pairs with Bob (receiver) and transfers multiple test files.
 
 use sd_core::{
-	domain::content_identity::ContentHashGenerator, service::file_sharing::TransferState,
-	testing::CargoTestRunner, Core,
+	domain::content_identity::ContentHashGenerator,
+	service::{file_sharing::TransferState, network::Protocol},
+	testing::CargoTestRunner,
+	Core,
 };
 use std::{env, path::PathBuf, time::Duration};
 use tokio::time::timeout;
@@ -58,7 +60,7 @@ async fn alice_file_transfer_scenario() {
 	if let Some(networking) = core.networking() {
 		let protocol_registry = networking.protocol_registry();
 		let registry = protocol_registry.read().await;
-		if let Some(handler) = registry.get_handler("file_transfer") {
+		if let Some(handler) = registry.get_handler(Protocol::FileTransfer) {
 			if let Some(ft_handler) =
 				handler
 					.as_any()
@@ -404,7 +406,7 @@ async fn bob_file_transfer_scenario() {
 	if let Some(networking) = core.networking() {
 		let protocol_registry = networking.protocol_registry();
 		let registry = protocol_registry.read().await;
-		if let Some(handler) = registry.get_handler("file_transfer") {
+		if let Some(handler) = registry.get_handler(Protocol::FileTransfer) {
 			if let Some(ft_handler) =
 				handler
 					.as_any()
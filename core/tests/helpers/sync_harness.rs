@@ -73,6 +73,7 @@ impl TestConfigBuilder {
 			},
 			proxy_pairing: sd_core::config::app_config::ProxyPairingConfig::default(),
 			spacebot: sd_core::config::app_config::SpacebotConfig::default(),
+			network: sd_core::config::app_config::NetworkDiscoveryConfig::default(),
 		};
 
 		config.save()?;
@@ -145,7 +146,7 @@ pub async fn register_device(
 		boot_disk_type: Set(None),
 		boot_disk_capacity_bytes: Set(None),
 		swap_total_bytes: Set(None),
-		network_addresses: Set(serde_json::json!([])),
+		network_addresses: Set(Default::default()),
 		is_online: Set(false),
 		last_seen_at: Set(Utc::now()),
 		capabilities: Set(serde_json::json!({})),
@@ -809,6 +810,7 @@ pub struct TwoDeviceHarnessBuilder {
 	start_in_ready_state: bool,
 	collect_events: bool,
 	collect_sync_events: bool,
+	sync_config: sd_core::infra::sync::SyncConfig,
 }
 
 #[allow(dead_code)]
@@ -840,6 +842,7 @@ impl TwoDeviceHarnessBuilder {
 			start_in_ready_state: true,
 			collect_events: false,
 			collect_sync_events: false,
+			sync_config: sd_core::infra::sync::SyncConfig::default(),
 		})
 	}
 
@@ -861,6 +864,12 @@ impl TwoDeviceHarnessBuilder {
 		self
 	}
 
+	/// Sync configuration for both devices' sync services
+	pub fn sync_config(mut self, config: sd_core::infra::sync::SyncConfig) -> Self {
+		self.sync_config = config;
+		self
+	}
+
 	pub async fn build(self) -> anyhow::Result<TwoDeviceHarness> {
 		// Initialize tracing
 		init_test_tracing(&self.test_name, &self.snapshot_dir)?;
@@ -934,16 +943,18 @@ impl TwoDeviceHarnessBuilder {
 
 		// Initialize sync services
 		library_alice
-			.init_sync_service(
+			.init_sync_service_with_config(
 				device_alice_id,
 				transport_alice.clone() as Arc<dyn NetworkTransport>,
+				self.sync_config.clone(),
 			)
 			.await?;
 
 		library_bob
-			.init_sync_service(
+			.init_sync_service_with_config(
 				device_bob_id,
 				transport_bob.clone() as Arc<dyn NetworkTransport>,
+				self.sync_config.clone(),
 			)
 			.await?;
 
@@ -23,6 +23,14 @@ pub struct MockTransport {
 	sync_services: Arc<Mutex<HashMap<Uuid, Weak<SyncService>>>>,
 	/// Devices that are "offline" and won't receive messages
 	blocked_devices: Arc<Mutex<std::collections::HashSet<Uuid>>>,
+	/// Number of times this device asked for its sync partners
+	partner_queries: std::sync::atomic::AtomicUsize,
+	/// Devices this transport fails to send to, so messages land in the retry queue
+	failing_devices: Mutex<std::collections::HashSet<Uuid>>,
+	/// Tombstones added to every state response for a model, as incremental pages carry
+	injected_tombstones: Mutex<HashMap<String, Vec<Uuid>>>,
+	/// Records sent without their name in state responses, so applying them fails
+	corrupt_records: Mutex<std::collections::HashSet<Uuid>>,
 }
 
 impl MockTransport {
@@ -42,6 +50,10 @@ impl MockTransport {
 			history,
 			sync_services,
 			blocked_devices,
+			partner_queries: std::sync::atomic::AtomicUsize::new(0),
+			failing_devices: Mutex::new(std::collections::HashSet::new()),
+			injected_tombstones: Mutex::new(HashMap::new()),
+			corrupt_records: Mutex::new(std::collections::HashSet::new()),
 		})
 	}
 
@@ -98,6 +110,29 @@ impl MockTransport {
 		);
 	}
 
+	/// Make sends from this device to `device_id` fail (simulate a broken link)
+	pub async fn fail_sends_to(&self, device_id: Uuid) {
+		self.failing_devices.lock().await.insert(device_id);
+	}
+
+	/// Add tombstones to every state response this device receives for a model
+	pub async fn inject_tombstones(&self, model_type: &str, uuids: Vec<Uuid>) {
+		self.injected_tombstones
+			.lock()
+			.await
+			.insert(model_type.to_string(), uuids);
+	}
+
+	/// Make a record in state responses this device receives fail to apply
+	pub async fn corrupt_state_record(&self, uuid: Uuid) {
+		self.corrupt_records.lock().await.insert(uuid);
+	}
+
+	/// Send every record in state responses intact again
+	pub async fn clear_corrupt_state_records(&self) {
+		self.corrupt_records.lock().await.clear();
+	}
+
 	/// Unblock a device (simulate coming online)
 	pub async fn unblock_device(&self, device_id: Uuid) {
 		self.blocked_devices.lock().await.remove(&device_id);
@@ -112,6 +147,12 @@ impl MockTransport {
 		self.blocked_devices.lock().await.contains(&device_id)
 	}
 
+	/// How many times this device has asked for its sync partners
+	pub fn partner_query_count(&self) -> usize {
+		self.partner_queries
+			.load(std::sync::atomic::Ordering::SeqCst)
+	}
+
 	/// Register a sync service for request/response handling
 	pub async fn register_sync_service(&self, device_id: Uuid, sync_service: Weak<SyncService>) {
 		self.sync_services
@@ -179,13 +220,19 @@ impl MockTransport {
 					library_id,
 					since_hlc,
 					limit,
+					content_hints,
 				} => {
 					let (entries, has_more) = sync_service
 						.peer_sync()
 						.get_shared_changes(since_hlc, limit)
 						.await?;
 					let current_state = if since_hlc.is_none() {
-						Some(sync_service.peer_sync().get_full_shared_state().await?)
+						Some(
+							sync_service
+								.peer_sync()
+								.get_full_shared_state(content_hints)
+								.await?,
+						)
 					} else {
 						None
 					};
@@ -337,17 +384,21 @@ impl MockTransport {
 						}
 					});
 
-					// Query actual state from this device's database
-					let records = sync_service
-						.peer_sync()
-						.get_device_state(
+					// Query actual state from this device's database, limited to the
+					// locations shared with the requester like the real handler does
+					let location_filter =
+						sync_service.peer_sync().location_filter_for(sender).await;
+					let records = sd_core::infra::sync::with_location_filter(
+						location_filter,
+						sync_service.peer_sync().get_device_state(
 							model_types.clone(),
 							requested_device_id,
 							since,
 							cursor,
 							batch_size,
-						)
-						.await?;
+						),
+					)
+					.await?;
 
 					// Query tombstones if incremental sync
 					let deleted_uuids = if let Some(since_time) = since {
@@ -380,6 +431,7 @@ impl MockTransport {
 						deleted_uuids,
 						has_more,
 						checkpoint: next_checkpoint,
+						estimated_total: None,
 					};
 
 					self.send_sync_message(sender, response).await?;
@@ -561,6 +613,9 @@ impl MockTransport {
 			SyncMessage::Error { message, .. } => {
 				tracing::warn!(error = %message, "Sync error received");
 			}
+			SyncMessage::NotAuthorized { device_id, .. } => {
+				tracing::warn!(device_id = %device_id, "Sync request refused");
+			}
 			SyncMessage::EventLogRequest { .. } => {
 				tracing::debug!("EventLogRequest received in mock transport - ignoring");
 			}
@@ -583,6 +638,10 @@ impl NetworkTransport for MockTransport {
 			return Err(anyhow::anyhow!("device {} not connected", target_device));
 		}
 
+		if self.failing_devices.lock().await.contains(&target_device) {
+			return Err(anyhow::anyhow!("send to device {} failed", target_device));
+		}
+
 		// Check if target device is blocked (simulating offline)
 		if self.blocked_devices.lock().await.contains(&target_device) {
 			tracing::trace!(
@@ -635,6 +694,11 @@ impl NetworkTransport for MockTransport {
 			"[MockTransport] Delivering message to target sync service"
 		);
 
+		// Count the message as the receiver counts the frame it reads off the stream
+		sync_service
+			.metrics()
+			.record_bytes_received(serde_json::to_vec(&message)?.len() as u64);
+
 		MockTransport::deliver_message(&sync_service, self.my_device_id, message).await?;
 
 		tracing::debug!(
@@ -702,14 +766,36 @@ impl NetworkTransport for MockTransport {
 					}
 				});
 
-				// Query actual state from target device's database
-				let records = sync_service
+				// Query actual state from target device's database, limited to the
+				// locations it shares with us like the real handler does
+				let location_filter = sync_service
 					.peer_sync()
-					.get_device_state(model_types.clone(), *device_id, *since, cursor, *batch_size)
-					.await?;
+					.location_filter_for(self.my_device_id)
+					.await;
+				let mut records = sd_core::infra::sync::with_location_filter(
+					location_filter,
+					sync_service.peer_sync().get_device_state(
+						model_types.clone(),
+						*device_id,
+						*since,
+						cursor,
+						*batch_size,
+					),
+				)
+				.await?;
+				let corrupt_records = self.corrupt_records.lock().await;
+				for record in records
+					.iter_mut()
+					.filter(|record| corrupt_records.contains(&record.uuid))
+				{
+					if let Some(data) = record.data.as_object_mut() {
+						data.remove("name");
+					}
+				}
+				drop(corrupt_records);
 
 				// Query tombstones if incremental sync
-				let deleted_uuids = if let Some(since_time) = since {
+				let mut deleted_uuids = if let Some(since_time) = since {
 					sync_service
 						.peer_sync()
 						.get_deletion_tombstones(
@@ -721,6 +807,11 @@ impl NetworkTransport for MockTransport {
 				} else {
 					vec![]
 				};
+				if let Some(model_type) = model_types.first() {
+					if let Some(injected) = self.injected_tombstones.lock().await.get(model_type) {
+						deleted_uuids.extend(injected.iter().copied());
+					}
+				}
 
 				let has_more = records.len() >= *batch_size;
 				let next_checkpoint = if has_more {
@@ -739,10 +830,14 @@ impl NetworkTransport for MockTransport {
 					deleted_uuids,
 					checkpoint: next_checkpoint,
 					has_more,
+					estimated_total: None,
 				}
 			}
 			SyncMessage::SharedChangeRequest {
-				since_hlc, limit, ..
+				since_hlc,
+				limit,
+				content_hints,
+				..
 			} => {
 				// Query actual shared changes from target device
 				let (entries, has_more) = sync_service
@@ -752,7 +847,12 @@ impl NetworkTransport for MockTransport {
 
 				// Include current state snapshot if initial backfill
 				let current_state = if since_hlc.is_none() {
-					Some(sync_service.peer_sync().get_full_shared_state().await?)
+					Some(
+						sync_service
+							.peer_sync()
+							.get_full_shared_state(*content_hints)
+							.await?,
+					)
 				} else {
 					None
 				};
@@ -764,6 +864,18 @@ impl NetworkTransport for MockTransport {
 					has_more,
 				}
 			}
+			SyncMessage::ContentRequest {
+				model_type,
+				content_hashes,
+				..
+			} => SyncMessage::ContentResponse {
+				library_id: request.library_id(),
+				model_type: model_type.clone(),
+				records: sync_service
+					.peer_sync()
+					.get_shared_content(model_type, content_hashes.clone())
+					.await?,
+			},
 			_ => {
 				return Err(anyhow::anyhow!(
 					"send_sync_request called with non-request message type"
@@ -771,15 +883,35 @@ impl NetworkTransport for MockTransport {
 			}
 		};
 
+		// Count the response as the requester counts the frame it reads back
+		let requester = self
+			.sync_services
+			.lock()
+			.await
+			.get(&self.my_device_id)
+			.and_then(|weak| weak.upgrade());
+		if let Some(requester) = requester {
+			requester
+				.metrics()
+				.record_bytes_received(serde_json::to_vec(&response)?.len() as u64);
+		}
+
 		Ok(response)
 	}
 
 	async fn get_connected_sync_partners(
 		&self,
 		_library_id: Uuid,
-		_db: &sea_orm::DatabaseConnection,
+		db: &sea_orm::DatabaseConnection,
 	) -> anyhow::Result<Vec<Uuid>> {
-		Ok(self.connected_peers.clone())
+		self.partner_queries
+			.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+		// Same filter as the real transport, with connected peers standing in for paired ones
+		sd_core::infra::sync::library_sync_partners(db, self.my_device_id, |peer| {
+			self.connected_peers.contains(&peer)
+		})
+		.await
 	}
 
 	async fn is_device_reachable(&self, device_uuid: Uuid) -> bool {
@@ -0,0 +1,67 @@
+//! Library Discovery Stats Integration Test
+//!
+//! Verifies that the cached discovery totals follow the entries the indexer
+//! creates and deletes, without recounting the library.
+//!
+//! ## Running Tests
+//! ```bash
+//! cargo test -p sd-core --test library_discovery_stats_test -- --nocapture
+//! ```
+
+mod helpers;
+
+use helpers::IndexingHarnessBuilder;
+use sd_core::domain::IndexMode;
+
+/// Test: Reindexing a location adds and removes entries from the cached totals
+#[tokio::test]
+async fn test_reindex_updates_cached_discovery_stats_without_recount() -> anyhow::Result<()> {
+	let harness = IndexingHarnessBuilder::new("discovery_stats_reindex")
+		.disable_watcher()
+		.build()
+		.await?;
+
+	let test_location = harness.create_test_location("stats").await?;
+	test_location.write_file("keep.txt", "keep").await?;
+	test_location.write_file("remove.txt", "remove me").await?;
+	let location = test_location.index("Stats", IndexMode::Shallow).await?;
+
+	// Prime the cache with a full recount
+	let library = harness.library.clone();
+	let primed = library.discovery_stats().await?;
+	let recounts = library.discovery_stats_cache().recount_count();
+
+	// The processing phase counts the file it creates
+	location.write_file("added.txt", "added").await?;
+	location.reindex().await?;
+	let after_add = library.discovery_stats().await?;
+	assert_eq!(after_add.total_entries, primed.total_entries + 1);
+	assert_eq!(
+		after_add.total_size_bytes,
+		primed.total_size_bytes + "added".len() as u64
+	);
+
+	// ...and drops the one it deletes once the file is gone
+	location.delete_file("remove.txt").await?;
+	location.reindex().await?;
+	let after_remove = library.discovery_stats().await?;
+	assert_eq!(after_remove.total_entries, after_add.total_entries - 1);
+	assert_eq!(
+		after_remove.total_size_bytes,
+		after_add.total_size_bytes - "remove me".len() as u64
+	);
+
+	assert_eq!(
+		library.discovery_stats_cache().recount_count(),
+		recounts,
+		"indexing should update the cache rather than trigger a recount"
+	);
+
+	// The incremental totals agree with a real recount
+	let recounted = library.recompute_discovery_stats().await?;
+	assert_eq!(recounted.total_entries, after_remove.total_entries);
+	assert_eq!(recounted.total_size_bytes, after_remove.total_size_bytes);
+
+	drop(library);
+	harness.shutdown().await
+}
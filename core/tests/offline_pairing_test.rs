@@ -0,0 +1,118 @@
+//! Offline pairing test - verifies two cores pair with relay and public discovery off
+//!
+//! Bob only learns Alice's address from the static address map in his config, so
+//! nothing here needs internet access or a relay.
+
+use sd_core::config::{AppConfig, NetworkDiscoveryConfig, StaticPeerAddr};
+use sd_core::Core;
+use std::path::Path;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+async fn offline_core(data_dir: &Path, name: &str, network: NetworkDiscoveryConfig) -> Core {
+	let mut config = AppConfig::default_with_dir(data_dir.to_path_buf());
+	config.telemetry_enabled = false;
+	config.services.networking_enabled = true;
+	config.services.volume_monitoring_enabled = false;
+	config.services.fs_watcher_enabled = false;
+	config.services.statistics_listener_enabled = false;
+	config.network = network;
+
+	let mut core = timeout(
+		Duration::from_secs(10),
+		Core::new_with_config(data_dir.to_path_buf(), Some(config), None),
+	)
+	.await
+	.unwrap()
+	.unwrap();
+	core.device.set_name(name.to_string()).unwrap();
+	timeout(Duration::from_secs(10), core.init_networking())
+		.await
+		.unwrap()
+		.unwrap();
+	core
+}
+
+async fn wait_for_connection(core: &Core) {
+	timeout(Duration::from_secs(30), async {
+		loop {
+			let connected = core.services.device.get_connected_devices().await.unwrap();
+			if !connected.is_empty() {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(250)).await;
+		}
+	})
+	.await
+	.expect("devices did not connect");
+}
+
+#[tokio::test]
+async fn test_pairing_with_static_addresses_only() {
+	let alice_dir = TempDir::new().unwrap();
+	let bob_dir = TempDir::new().unwrap();
+
+	let alice = offline_core(
+		alice_dir.path(),
+		"Alice's Offline Device",
+		NetworkDiscoveryConfig::offline(),
+	)
+	.await;
+	let alice_net = alice.networking().unwrap();
+
+	// Without a relay the endpoint only has direct addresses, which show up once
+	// the local interfaces have been read
+	let alice_addr = timeout(Duration::from_secs(10), async {
+		loop {
+			let addr = alice_net.get_node_addr().unwrap().unwrap();
+			if addr.ip_addrs().next().is_some() {
+				break addr;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	})
+	.await
+	.expect("endpoint never reported a direct address");
+
+	let bob = offline_core(
+		bob_dir.path(),
+		"Bob's Offline Device",
+		NetworkDiscoveryConfig {
+			static_addrs: vec![StaticPeerAddr {
+				node_id: alice_addr.id.to_string(),
+				addrs: alice_addr.ip_addrs().copied().collect(),
+				relay_url: None,
+			}],
+			..NetworkDiscoveryConfig::offline()
+		},
+	)
+	.await;
+	let bob_net = bob.networking().unwrap();
+
+	timeout(
+		Duration::from_secs(15),
+		alice_net.start_pairing_as_initiator(false),
+	)
+	.await
+	.unwrap()
+	.unwrap();
+	let pairing_code = alice_net
+		.get_pairing_code_for_current_session()
+		.await
+		.unwrap()
+		.expect("initiator has a pairing session");
+	assert_eq!(pairing_code.node_id(), Some(alice_addr.id));
+
+	// mDNS is off, so Bob can only reach Alice through her static address
+	timeout(
+		Duration::from_secs(15),
+		bob_net.start_pairing_as_joiner_with_code(pairing_code, false),
+	)
+	.await
+	.unwrap()
+	.unwrap();
+
+	wait_for_connection(&alice).await;
+	wait_for_connection(&bob).await;
+}
@@ -22,6 +22,7 @@ fn test_vouching_session_creation() {
 		vouchee_device_name: "Test Device".to_string(),
 		voucher_device_id,
 		created_at: Utc::now(),
+		expires_at: Utc::now() + chrono::Duration::days(7),
 		state: VouchingSessionState::Pending,
 		vouches: vec![],
 	};
@@ -100,6 +101,7 @@ fn test_vouching_session_with_multiple_vouches() {
 		vouchee_device_name: "New Device".to_string(),
 		voucher_device_id,
 		created_at: Utc::now(),
+		expires_at: Utc::now() + chrono::Duration::days(7),
 		state: VouchingSessionState::InProgress,
 		vouches: vec![
 			VouchState {
@@ -184,6 +186,7 @@ fn test_vouch_payload_structure() {
 			public_key_hash: "abcdef1234567890".to_string(),
 		},
 		last_seen: Utc::now(),
+		version: DeviceInfo::CURRENT_VERSION,
 	};
 
 	let timestamp = Utc::now();
@@ -227,6 +230,7 @@ fn test_vouching_session_state_transitions() {
 		vouchee_device_name: "Test Device".to_string(),
 		voucher_device_id: Uuid::new_v4(),
 		created_at: Utc::now(),
+		expires_at: Utc::now() + chrono::Duration::days(7),
 		state: VouchingSessionState::Pending,
 		vouches: vec![],
 	};
@@ -287,6 +291,7 @@ fn test_vouching_session_cleanup_timing() {
 		vouchee_device_name: "Test Device".to_string(),
 		voucher_device_id: Uuid::new_v4(),
 		created_at: Utc::now(),
+		expires_at: Utc::now() + chrono::Duration::days(7),
 		state: VouchingSessionState::Completed,
 		vouches: vec![],
 	};
@@ -0,0 +1,238 @@
+//! Remote library state test using subprocess framework
+//!
+//! Alice pairs with Bob and inspects Bob's library over the network before any
+//! sync setup, the way the UI checks for slug collisions ahead of joining.
+
+use sd_core::testing::CargoTestRunner;
+use sd_core::Core;
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::time::timeout;
+
+const TEST_DIR: &str = "/tmp/spacedrive-remote-library-state-test";
+
+/// Alice queries Bob's library state after pairing
+#[tokio::test]
+#[ignore]
+async fn alice_remote_library_state_scenario() {
+	if env::var("TEST_ROLE").unwrap_or_default() != "alice" {
+		return;
+	}
+
+	env::set_var("SPACEDRIVE_TEST_DIR", TEST_DIR);
+
+	let data_dir = PathBuf::from(format!("{}/alice", TEST_DIR));
+
+	println!("Alice: Starting remote library state test");
+
+	let mut core = timeout(Duration::from_secs(10), Core::new(data_dir))
+		.await
+		.unwrap()
+		.unwrap();
+
+	core.device.set_name("Alice Device".to_string()).unwrap();
+
+	timeout(Duration::from_secs(10), core.init_networking())
+		.await
+		.unwrap()
+		.unwrap();
+
+	tokio::time::sleep(Duration::from_secs(2)).await;
+	println!("Alice: Core initialized");
+
+	let (pairing_code, _) = if let Some(networking) = core.networking() {
+		timeout(
+			Duration::from_secs(15),
+			networking.start_pairing_as_initiator(false),
+		)
+		.await
+		.unwrap()
+		.unwrap()
+	} else {
+		panic!("Networking not initialized");
+	};
+
+	std::fs::write(format!("{}/pairing_code.txt", TEST_DIR), &pairing_code).unwrap();
+
+	println!("Alice: Waiting for Bob's library ID...");
+	let library_id = loop {
+		if let Ok(id) = std::fs::read_to_string(format!("{}/library_id.txt", TEST_DIR)) {
+			break uuid::Uuid::parse_str(id.trim()).unwrap();
+		}
+		tokio::time::sleep(Duration::from_millis(500)).await;
+	};
+
+	println!("Alice: Waiting for Bob to pair...");
+	let mut attempts = 0;
+	let bob_device_id = loop {
+		tokio::time::sleep(Duration::from_secs(1)).await;
+
+		let connected = core.services.device.get_connected_devices().await.unwrap();
+		if let Some(device_id) = connected.first() {
+			break *device_id;
+		}
+
+		attempts += 1;
+		if attempts >= 45 {
+			panic!("Alice: Pairing timeout");
+		}
+	};
+	println!("Alice: Paired with Bob ({})", bob_device_id);
+
+	use sd_core::infra::action::CoreAction;
+	use sd_core::ops::network::sync_setup::{
+		GetRemoteLibraryStateAction, GetRemoteLibraryStateInput,
+	};
+
+	let state = GetRemoteLibraryStateAction::from_input(GetRemoteLibraryStateInput {
+		device_id: bob_device_id,
+		library_id,
+	})
+	.unwrap()
+	.execute(core.context.clone())
+	.await
+	.expect("Alice: Library state request failed");
+
+	println!("Alice: Bob's library state: {:?}", state);
+	assert_eq!(state.library_id, library_id);
+	assert_eq!(state.library_name, "Bob Library");
+	assert!(state.device_count >= 1);
+	assert_eq!(state.device_count, state.device_slugs.len());
+
+	// A library Bob does not have fails instead of hanging
+	let missing = timeout(
+		Duration::from_secs(30),
+		GetRemoteLibraryStateAction::from_input(GetRemoteLibraryStateInput {
+			device_id: bob_device_id,
+			library_id: uuid::Uuid::new_v4(),
+		})
+		.unwrap()
+		.execute(core.context.clone()),
+	)
+	.await
+	.expect("Alice: Library state request for a missing library did not return");
+	assert!(missing.is_err());
+
+	std::fs::write(format!("{}/alice_success.txt", TEST_DIR), "success").unwrap();
+
+	// Keep the connection up until Bob is done
+	tokio::time::sleep(Duration::from_secs(3)).await;
+	println!("Alice: Test completed");
+}
+
+/// Bob owns the library and answers Alice's request
+#[tokio::test]
+#[ignore]
+async fn bob_remote_library_state_scenario() {
+	if env::var("TEST_ROLE").unwrap_or_default() != "bob" {
+		return;
+	}
+
+	env::set_var("SPACEDRIVE_TEST_DIR", TEST_DIR);
+
+	let data_dir = PathBuf::from(format!("{}/bob", TEST_DIR));
+
+	println!("Bob: Starting remote library state test");
+
+	let mut core = timeout(Duration::from_secs(10), Core::new(data_dir))
+		.await
+		.unwrap()
+		.unwrap();
+
+	core.device.set_name("Bob Device".to_string()).unwrap();
+
+	timeout(Duration::from_secs(10), core.init_networking())
+		.await
+		.unwrap()
+		.unwrap();
+
+	tokio::time::sleep(Duration::from_secs(2)).await;
+	println!("Bob: Core initialized");
+
+	let library = core
+		.libraries
+		.create_library("Bob Library".to_string(), None, core.context.clone())
+		.await
+		.unwrap();
+
+	std::fs::write(
+		format!("{}/library_id.txt", TEST_DIR),
+		library.id().to_string(),
+	)
+	.unwrap();
+
+	println!("Bob: Waiting for pairing code...");
+	let pairing_code = loop {
+		if let Ok(code) = std::fs::read_to_string(format!("{}/pairing_code.txt", TEST_DIR)) {
+			break code.trim().to_string();
+		}
+		tokio::time::sleep(Duration::from_millis(500)).await;
+	};
+
+	if let Some(networking) = core.networking() {
+		timeout(
+			Duration::from_secs(15),
+			networking.start_pairing_as_joiner(&pairing_code, false),
+		)
+		.await
+		.unwrap()
+		.unwrap();
+	}
+
+	// Stay online until Alice has her answers
+	let mut attempts = 0;
+	while attempts < 60 {
+		tokio::time::sleep(Duration::from_secs(1)).await;
+		if std::path::Path::new(&format!("{}/alice_success.txt", TEST_DIR)).exists() {
+			println!("Bob: Alice received the library state");
+			break;
+		}
+		attempts += 1;
+	}
+
+	if attempts >= 60 {
+		panic!("Bob: Timeout waiting for Alice");
+	}
+
+	println!("Bob: Test completed");
+}
+
+/// Main test orchestrator
+#[tokio::test]
+async fn test_get_remote_library_state() {
+	println!("Testing remote library state inspection...");
+
+	let _ = std::fs::remove_dir_all(TEST_DIR);
+	std::fs::create_dir_all(TEST_DIR).unwrap();
+
+	let mut runner = CargoTestRunner::for_test_file("remote_library_state_test")
+		.with_timeout(Duration::from_secs(120))
+		.add_subprocess("alice", "alice_remote_library_state_scenario")
+		.add_subprocess("bob", "bob_remote_library_state_scenario");
+
+	println!("Starting Alice...");
+	runner.spawn_single_process("alice").await.unwrap();
+
+	tokio::time::sleep(Duration::from_secs(8)).await;
+
+	println!("Starting Bob...");
+	runner.spawn_single_process("bob").await.unwrap();
+
+	let result = runner
+		.wait_for_success(|_| {
+			std::fs::read_to_string(format!("{}/alice_success.txt", TEST_DIR))
+				.map(|c| c.trim() == "success")
+				.unwrap_or(false)
+		})
+		.await;
+
+	if let Err(e) = result {
+		for (name, output) in runner.get_all_outputs() {
+			println!("\n{} output:\n{}", name, output);
+		}
+		panic!("Remote library state test failed: {}", e);
+	}
+
+	println!("✅ Remote library state test PASSED");
+}
@@ -0,0 +1,139 @@
+//! Backfill Resume Integration Test
+//!
+//! Verifies that a backfill interrupted part way through a page keeps the
+//! watermark of the sub-batches it already applied, even when the page carries
+//! tombstones, and resumes from there.
+//!
+//! ## Running Tests
+//! ```bash
+//! cargo test -p sd-core --test sync_backfill_resume_test -- --test-threads=1 --nocapture
+//! ```
+
+mod helpers;
+
+use helpers::{TwoDeviceHarness, TwoDeviceHarnessBuilder};
+use sd_core::{
+	infra::{db::entities, sync::SyncConfig},
+	service::network::protocol::sync::messages::SyncMessage,
+};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use uuid::Uuid;
+
+/// Far longer than any test, so only the test itself starts a catch-up
+const IDLE_LOOP_INTERVAL_SECS: u64 = 3600;
+
+async fn build_harness(test_name: &str) -> anyhow::Result<TwoDeviceHarness> {
+	let mut sync_config = SyncConfig::default();
+	sync_config.network.sync_loop_interval_secs = IDLE_LOOP_INTERVAL_SECS;
+	sync_config.batching.apply_sub_batch_size = 2;
+
+	TwoDeviceHarnessBuilder::new(test_name)
+		.await?
+		.start_in_ready_state(true)
+		.sync_config(sync_config)
+		.build()
+		.await
+}
+
+/// The `since` of the last entry state request Bob sent Alice
+async fn last_entry_request_since(
+	harness: &TwoDeviceHarness,
+) -> Option<Option<chrono::DateTime<chrono::Utc>>> {
+	harness
+		.transport_bob
+		.get_messages_between(harness.device_bob_id, harness.device_alice_id)
+		.await
+		.into_iter()
+		.filter_map(|message| match message {
+			SyncMessage::StateRequest {
+				model_types, since, ..
+			} if model_types == ["entry"] => Some(since),
+			_ => None,
+		})
+		.last()
+}
+
+/// Test: A page with tombstones still commits its applied sub-batches, and the
+/// next catch-up resumes from them
+#[tokio::test]
+async fn test_interrupted_backfill_resumes_from_committed_sub_batch() -> anyhow::Result<()> {
+	let harness = build_harness("sync_backfill_resume").await?;
+
+	// Keep Alice's live changes from reaching Bob so only the backfill delivers them
+	harness
+		.transport_alice
+		.block_device(harness.device_bob_id)
+		.await;
+
+	let files = harness.data_dir_alice.join("files");
+	tokio::fs::create_dir_all(&files).await?;
+	for i in 0..8 {
+		tokio::fs::write(files.join(format!("file_{i}.txt")), format!("file {i}")).await?;
+	}
+	harness
+		.add_and_index_location_alice(files.to_str().unwrap(), "files")
+		.await?;
+
+	let alice_entries = harness
+		.library_alice
+		.sync_service()
+		.unwrap()
+		.peer_sync()
+		.get_device_state(
+			vec!["entry".to_string()],
+			Some(harness.device_alice_id),
+			None,
+			None,
+			10_000,
+		)
+		.await?;
+	assert!(alice_entries.len() >= 5);
+
+	// Every page carries tombstones, and the newest record fails to apply
+	let broken = alice_entries.last().unwrap();
+	harness
+		.transport_bob
+		.inject_tombstones("entry", vec![Uuid::new_v4(), Uuid::new_v4()])
+		.await;
+	harness
+		.transport_bob
+		.corrupt_state_record(broken.uuid)
+		.await;
+
+	let bob_sync = harness.library_bob.sync_service().unwrap();
+	assert!(bob_sync
+		.backfill_manager()
+		.catch_up_from_peer(harness.device_alice_id, None, None)
+		.await
+		.is_err());
+
+	// The sub-batches applied before the failure were committed
+	let watermark = bob_sync
+		.peer_sync()
+		.get_resource_watermark(harness.device_alice_id, "entry")
+		.await?
+		.expect("applied sub-batches should commit a watermark despite the tombstones");
+	assert!(watermark <= broken.timestamp);
+
+	// The next catch-up asks only for what the committed sub-batches don't cover
+	harness.transport_bob.clear_corrupt_state_records().await;
+	bob_sync
+		.backfill_manager()
+		.catch_up_from_peer(harness.device_alice_id, None, None)
+		.await?;
+	assert_eq!(
+		last_entry_request_since(&harness).await,
+		Some(Some(watermark))
+	);
+
+	let bob_db = harness.library_bob.db().conn();
+	for record in &alice_entries {
+		let count = entities::entry::Entity::find()
+			.filter(entities::entry::Column::Uuid.eq(record.uuid))
+			.count(bob_db)
+			.await?;
+		assert_eq!(count, 1, "entry {} should be backfilled", record.uuid);
+	}
+
+	Ok(())
+}
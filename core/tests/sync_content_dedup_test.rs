@@ -0,0 +1,224 @@
+//! Content deduplication test - verifies backfill skips content already held locally
+//!
+//! Content identities travel in backfill snapshots as content hash hints. A hint
+//! for content this device already has is dropped without fetching the record,
+//! and only the missing or newer hashes are requested from the peer.
+
+mod helpers;
+
+use helpers::TwoDeviceHarnessBuilder;
+use sd_core::{
+	infra::{
+		db::entities::content_identity,
+		sync::{registry, resolve_content_hints, snapshot_records},
+	},
+	service::{network::protocol::sync::messages::SyncMessage, sync::state::PeerInfo},
+	Core,
+};
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+	Set,
+};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+async fn insert_content(
+	db: &DatabaseConnection,
+	hash: &str,
+	text: &str,
+	verified_at: chrono::DateTime<chrono::Utc>,
+) {
+	content_identity::ActiveModel {
+		uuid: Set(Some(content_identity::Model::deterministic_uuid(hash))),
+		content_hash: Set(hash.to_string()),
+		kind_id: Set(0),
+		text_content: Set(Some(text.to_string())),
+		total_size: Set(4096),
+		entry_count: Set(1),
+		first_seen_at: Set(verified_at),
+		last_verified_at: Set(verified_at),
+		..Default::default()
+	}
+	.insert(db)
+	.await
+	.unwrap();
+}
+
+async fn text_for(db: &DatabaseConnection, hash: &str) -> Option<String> {
+	content_identity::Entity::find()
+		.filter(content_identity::Column::ContentHash.eq(hash))
+		.one(db)
+		.await
+		.unwrap()
+		.and_then(|content| content.text_content)
+}
+
+#[tokio::test]
+async fn test_existing_content_is_not_refetched() {
+	let temp_dir = TempDir::new().unwrap();
+	let core = Core::new(temp_dir.path().to_path_buf()).await.unwrap();
+	let library = core
+		.libraries
+		.create_library("Dedup Test", None, core.context.clone())
+		.await
+		.unwrap();
+	let db = Arc::new(library.db().conn().clone());
+
+	let shared_hash = "shared-content-hash";
+	let verified_at = chrono::Utc::now();
+	insert_content(
+		db.as_ref(),
+		shared_hash,
+		"a large extracted text payload",
+		verified_at,
+	)
+	.await;
+
+	let local = registry::local_content(
+		"content_identity",
+		vec![shared_hash.to_string(), "unknown-hash".to_string()],
+		db.clone(),
+	)
+	.await
+	.unwrap();
+	assert_eq!(local.len(), 1);
+	assert!(local.contains_key(shared_hash));
+
+	// The sender's snapshot carries only the hash, not the payload
+	let records = registry::query_content(
+		"content_identity",
+		vec![shared_hash.to_string()],
+		db.clone(),
+	)
+	.await
+	.unwrap();
+	let mut entries = snapshot_records("content_identity", records.clone(), true).await;
+	assert_eq!(entries.len(), 1);
+	assert!(entries[0].get("data").is_none());
+	assert_eq!(entries[0]["content_hash"], shared_hash);
+
+	// Requesters that don't resolve hints get the full record
+	let full = snapshot_records("content_identity", records, false).await;
+	assert!(full[0].get("data").is_some());
+	assert!(full[0].get("content_hash").is_none());
+
+	// A hint for content we don't have, one newer than ours, and a full record
+	let missing_hash = "missing-content-hash";
+	entries.push(serde_json::json!({
+		"uuid": content_identity::Model::deterministic_uuid(missing_hash),
+		"content_hash": missing_hash,
+		"timestamp": verified_at,
+	}));
+	entries.push(serde_json::json!({
+		"uuid": content_identity::Model::deterministic_uuid(shared_hash),
+		"content_hash": shared_hash,
+		"timestamp": verified_at + chrono::Duration::hours(1),
+	}));
+	entries.push(serde_json::json!({
+		"uuid": content_identity::Model::deterministic_uuid("full-record-hash"),
+		"data": { "content_hash": "full-record-hash" },
+	}));
+
+	let hints = resolve_content_hints("content_identity", &entries, db.clone())
+		.await
+		.unwrap();
+	assert_eq!(hints.skipped, 1);
+	assert_eq!(
+		hints.missing,
+		vec![missing_hash.to_string(), shared_hash.to_string()]
+	);
+	assert_eq!(hints.records.len(), 1);
+
+	// The local record is left as it was
+	assert_eq!(
+		text_for(db.as_ref(), shared_hash).await.as_deref(),
+		Some("a large extracted text payload")
+	);
+}
+
+#[tokio::test]
+async fn test_backfill_fetches_only_missing_or_newer_content() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("content_dedup_backfill")
+		.await?
+		.build()
+		.await?;
+	let alice_db = harness.library_alice.db().conn();
+	let bob_db = harness.library_bob.db().conn();
+	let now = chrono::Utc::now();
+
+	// Bob holds the same content as Alice, more recently verified
+	insert_content(
+		alice_db,
+		"held",
+		"alice copy",
+		now - chrono::Duration::days(1),
+	)
+	.await;
+	insert_content(bob_db, "held", "bob copy", now).await;
+	// Bob holds an older copy than Alice
+	insert_content(alice_db, "stale", "alice update", now).await;
+	insert_content(
+		bob_db,
+		"stale",
+		"bob original",
+		now - chrono::Duration::days(2),
+	)
+	.await;
+	// Only Alice has these, more than one ContentRequest's worth
+	let missing: Vec<String> = (0..sd_core::infra::sync::CONTENT_HASH_BATCH_SIZE + 5)
+		.map(|i| format!("missing-{i}"))
+		.collect();
+	for hash in &missing {
+		insert_content(alice_db, hash, "alice only", now).await;
+	}
+
+	harness
+		.library_bob
+		.sync_service()
+		.unwrap()
+		.backfill_manager()
+		.start_backfill(vec![PeerInfo {
+			device_id: harness.device_alice_id,
+			is_online: true,
+			latency_ms: 1.0,
+			has_complete_state: true,
+			active_syncs: 0,
+		}])
+		.await?;
+
+	assert_eq!(
+		content_identity::Entity::find().count(bob_db).await?,
+		content_identity::Entity::find().count(alice_db).await?
+	);
+	assert_eq!(text_for(bob_db, "held").await.as_deref(), Some("bob copy"));
+	assert_eq!(
+		text_for(bob_db, "stale").await.as_deref(),
+		Some("alice update")
+	);
+	assert_eq!(
+		text_for(bob_db, &missing[0]).await.as_deref(),
+		Some("alice only")
+	);
+
+	// Only the missing and newer hashes were requested, in bounded batches
+	let requests: Vec<Vec<String>> = harness
+		.transport_bob
+		.get_messages_between(harness.device_bob_id, harness.device_alice_id)
+		.await
+		.into_iter()
+		.filter_map(|message| match message {
+			SyncMessage::ContentRequest { content_hashes, .. } => Some(content_hashes),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(requests.len(), 2);
+	assert!(requests
+		.iter()
+		.all(|hashes| hashes.len() <= sd_core::infra::sync::CONTENT_HASH_BATCH_SIZE));
+	let requested: Vec<String> = requests.into_iter().flatten().collect();
+	assert_eq!(requested.len(), missing.len() + 1);
+	assert!(requested.contains(&"stale".to_string()));
+	assert!(!requested.contains(&"held".to_string()));
+
+	Ok(())
+}
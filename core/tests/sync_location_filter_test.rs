@@ -0,0 +1,158 @@
+//! Partial Library Sync Test
+//!
+//! Verifies that a location filter limits what a peer receives, both during
+//! backfill and in real time: only the selected locations and the entries
+//! inside them are sent.
+
+mod helpers;
+
+use anyhow::Result;
+use helpers::{IndexingHarnessBuilder, TwoDeviceHarnessBuilder};
+use sd_core::infra::db::entities;
+use sd_core::infra::sync::{registry::query_device_state, with_location_filter};
+use sd_core::location::IndexMode;
+use sd_core::service::network::protocol::sync::messages::SyncMessage;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::{fs, time::Duration};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_filtered_setup_syncs_only_selected_location() -> Result<()> {
+	let harness = IndexingHarnessBuilder::new("sync_location_filter")
+		.disable_watcher()
+		.build()
+		.await?;
+
+	let docs = harness.create_test_location("docs").await?;
+	docs.write_file("notes.txt", "shared").await?;
+	docs.create_dir("drafts").await?;
+	docs.write_file("drafts/plan.md", "# Plan").await?;
+	let docs = docs.index("Docs", IndexMode::Shallow).await?;
+
+	let private = harness.create_test_location("private").await?;
+	private.write_file("secret.txt", "not shared").await?;
+	let private = private.index("Private", IndexMode::Shallow).await?;
+
+	let db = Arc::new(harness.library.db().conn().clone());
+	let filter = Some(vec![docs.uuid]);
+
+	let locations = with_location_filter(
+		filter.clone(),
+		query_device_state("location", None, None, None, 10_000, db.clone()),
+	)
+	.await?;
+	let location_uuids: Vec<_> = locations.iter().map(|(uuid, _, _)| *uuid).collect();
+	assert_eq!(location_uuids, vec![docs.uuid]);
+
+	let entries = with_location_filter(
+		filter,
+		query_device_state("entry", None, None, None, 10_000, db.clone()),
+	)
+	.await?;
+	let synced: HashSet<_> = entries.iter().map(|(uuid, _, _)| *uuid).collect();
+
+	let expected: HashSet<_> = docs
+		.get_all_entries()
+		.await?
+		.into_iter()
+		.filter_map(|entry| entry.uuid)
+		.collect();
+	assert!(!expected.is_empty());
+	assert_eq!(
+		synced, expected,
+		"only the docs location's entries should sync"
+	);
+
+	for entry in private.get_all_entries().await? {
+		if let Some(uuid) = entry.uuid {
+			assert!(!synced.contains(&uuid), "private entry {} leaked", uuid);
+		}
+	}
+
+	// Without a filter both locations are visible
+	let unfiltered = query_device_state("location", None, None, None, 10_000, db).await?;
+	assert_eq!(unfiltered.len(), 2);
+
+	harness.shutdown().await?;
+	Ok(())
+}
+
+/// Test: once a filter is set, a new location outside it never reaches the peer
+#[tokio::test]
+async fn test_live_change_in_unshared_location_is_not_sent() -> Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("sync_location_filter_live")
+		.await?
+		.start_in_ready_state(true)
+		.build()
+		.await?;
+	let alice = harness.device_alice_id;
+	let bob = harness.device_bob_id;
+
+	let docs_dir = harness.snapshot_dir.join("docs");
+	fs::create_dir_all(&docs_dir).await?;
+	fs::write(docs_dir.join("notes.txt"), "shared").await?;
+	let docs = harness
+		.add_and_index_location_alice(docs_dir.to_str().unwrap(), "Docs")
+		.await?;
+	harness.wait_for_sync(Duration::from_secs(60)).await?;
+
+	// From here on Bob only receives the docs location
+	harness
+		.library_alice
+		.update_config(|config| {
+			config
+				.settings
+				.shared_location_filters
+				.insert(bob, vec![docs]);
+		})
+		.await?;
+
+	let private_dir = harness.snapshot_dir.join("private");
+	fs::create_dir_all(&private_dir).await?;
+	fs::write(private_dir.join("secret.txt"), "not shared").await?;
+	let private = harness
+		.add_and_index_location_alice(private_dir.to_str().unwrap(), "Private")
+		.await?;
+	tokio::time::sleep(Duration::from_secs(2)).await;
+
+	let sent: HashSet<Uuid> = harness
+		.transport_alice
+		.get_messages_between(alice, bob)
+		.await
+		.into_iter()
+		.flat_map(|message| match message {
+			SyncMessage::StateChange { record_uuid, .. } => vec![record_uuid],
+			SyncMessage::StateBatch { records, .. }
+			| SyncMessage::StateResponse { records, .. } => {
+				records.into_iter().map(|record| record.uuid).collect()
+			}
+			_ => vec![],
+		})
+		.collect();
+	assert!(
+		sent.contains(&docs),
+		"the shared location should have been sent"
+	);
+	assert!(
+		!sent.contains(&private),
+		"the unshared location was sent in real time"
+	);
+
+	let secret = entities::entry::Entity::find()
+		.filter(entities::entry::Column::Name.starts_with("secret"))
+		.one(harness.library_alice.db().conn())
+		.await?
+		.and_then(|entry| entry.uuid)
+		.expect("Alice should have indexed the private file");
+	assert!(!sent.contains(&secret), "an unshared entry was sent");
+
+	let private_on_bob = entities::location::Entity::find()
+		.filter(entities::location::Column::Uuid.eq(private))
+		.one(harness.library_bob.db().conn())
+		.await?;
+	assert!(private_on_bob.is_none());
+
+	Ok(())
+}
@@ -12,11 +12,14 @@ mod helpers;
 
 use helpers::TwoDeviceHarnessBuilder;
 use sd_core::{
-	infra::db::entities, library::Library, service::sync::metrics::snapshot::SyncMetricsSnapshot,
+	infra::db::entities,
+	library::Library,
+	service::sync::metrics::{snapshot::SyncMetricsSnapshot, ErrorEvent, SyncMetricsCollector},
 };
 use sea_orm::{EntityTrait, PaginatorTrait};
 use std::sync::Arc;
 use tokio::{fs, time::Duration};
+use uuid::Uuid;
 
 /// Get metrics snapshot for a library
 async fn get_metrics_snapshot(library: &Arc<Library>) -> SyncMetricsSnapshot {
@@ -191,6 +194,11 @@ async fn test_metrics_broadcast_counting() -> anyhow::Result<()> {
 		applied_ratio * 100.0
 	);
 
+	// Direct broadcasts count as records pushed to the partner they reached
+	let alice_metrics = harness.library_alice.sync_service().unwrap().metrics();
+	assert!(alice_metrics.records_pushed_to(harness.device_bob_id).await > 0);
+	assert_eq!(alice_metrics.records_pushed_to(Uuid::new_v4()).await, 0);
+
 	Ok(())
 }
 
@@ -458,3 +466,107 @@ async fn test_metrics_snapshot_structure() -> anyhow::Result<()> {
 
 	Ok(())
 }
+
+/// Test: Throughput counters track applied records for the lifetime and the session
+#[tokio::test]
+async fn test_metrics_throughput_counters() -> anyhow::Result<()> {
+	let collector = SyncMetricsCollector::new();
+
+	for _ in 0..5 {
+		collector.record_changes_applied(1);
+		collector.record_bytes_received(128);
+	}
+	collector
+		.record_error(ErrorEvent::new("apply".to_string(), "test".to_string()))
+		.await;
+
+	let snapshot = SyncMetricsSnapshot::from_metrics(collector.metrics()).await;
+
+	assert_eq!(snapshot.throughput.lifetime.records_applied, 5);
+	assert_eq!(snapshot.throughput.lifetime.bytes_received, 5 * 128);
+	assert_eq!(snapshot.throughput.lifetime.errors, 1);
+	assert_eq!(snapshot.throughput.session.records_applied, 5);
+	assert_eq!(snapshot.throughput.session.bytes_received, 5 * 128);
+	assert_eq!(snapshot.throughput.session.errors, 1);
+	assert!(snapshot.throughput.lifetime.records_per_second >= 0.0);
+
+	// A new session starts from zero but keeps the lifetime totals
+	collector.start_throughput_session().await;
+	collector.record_changes_applied(2);
+
+	let snapshot = SyncMetricsSnapshot::from_metrics(collector.metrics()).await;
+	assert_eq!(snapshot.throughput.lifetime.records_applied, 7);
+	assert_eq!(snapshot.throughput.session.records_applied, 2);
+	assert_eq!(snapshot.throughput.session.bytes_received, 0);
+	assert_eq!(snapshot.throughput.session.errors, 0);
+
+	Ok(())
+}
+
+/// Test: Records pushed and pulled are attributed to the peer they went to or came from
+#[tokio::test]
+async fn test_metrics_records_per_peer() -> anyhow::Result<()> {
+	let collector = SyncMetricsCollector::new();
+	let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+
+	collector.record_records_pushed(alice, 3).await;
+	collector.record_records_pushed(alice, 2).await;
+	collector.record_records_pulled(bob, 7).await;
+
+	assert_eq!(collector.records_pushed_to(alice).await, 5);
+	assert_eq!(collector.records_pulled_from(alice).await, 0);
+	assert_eq!(collector.records_pushed_to(bob).await, 0);
+	assert_eq!(collector.records_pulled_from(bob).await, 7);
+
+	Ok(())
+}
+
+/// Test: Records applied during sync show up in the throughput counters
+#[tokio::test]
+async fn test_metrics_throughput_apply_path() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("metrics_throughput")
+		.await?
+		.build()
+		.await?;
+
+	let test_dir = harness.snapshot_dir.join("throughput_test");
+	fs::create_dir_all(&test_dir).await?;
+
+	for i in 0..5 {
+		let file_path = test_dir.join(format!("throughput_file_{}.txt", i));
+		fs::write(&file_path, format!("Throughput test {}", i)).await?;
+	}
+
+	harness
+		.add_and_index_location_alice(test_dir.to_str().unwrap(), "Throughput Test")
+		.await?;
+
+	harness.wait_for_sync(Duration::from_secs(30)).await?;
+
+	let bob = get_metrics_snapshot(&harness.library_bob).await;
+	save_metrics_snapshot(
+		&harness.snapshot_dir,
+		"throughput",
+		&get_metrics_snapshot(&harness.library_alice).await,
+		&bob,
+	)
+	.await?;
+
+	let bob_db_entries = entities::entry::Entity::find()
+		.count(harness.library_bob.db().conn())
+		.await?;
+
+	assert_eq!(
+		bob.throughput.lifetime.records_applied,
+		bob.operations.changes_applied
+	);
+	assert!(
+		bob.throughput.session.records_applied >= bob_db_entries,
+		"Every synced entry should be counted as applied ({} < {})",
+		bob.throughput.session.records_applied,
+		bob_db_entries
+	);
+	assert!(bob.throughput.session.bytes_received > 0);
+
+	Ok(())
+}
@@ -0,0 +1,165 @@
+//! Sync Partner Toggle Integration Test
+//!
+//! Verifies that flipping a device's `sync_enabled` flag at runtime takes effect
+//! without restarting the sync service.
+//!
+//! ## Running Tests
+//! ```bash
+//! cargo test -p sd-core --test sync_partner_toggle_test -- --test-threads=1 --nocapture
+//! ```
+
+mod helpers;
+
+use helpers::{MockTransport, TwoDeviceHarness, TwoDeviceHarnessBuilder};
+use sd_core::{
+	infra::{
+		action::LibraryAction,
+		db::entities,
+		sync::{ChangeType, SyncConfig},
+	},
+	ops::devices::{SetDeviceSyncEnabledAction, SetDeviceSyncEnabledInput},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use tokio::time::Duration;
+
+/// Far longer than any test, so a sync loop pass can only come from a wakeup
+const IDLE_LOOP_INTERVAL_SECS: u64 = 3600;
+
+async fn build_harness(test_name: &str) -> anyhow::Result<TwoDeviceHarness> {
+	let mut sync_config = SyncConfig::default();
+	sync_config.network.sync_loop_interval_secs = IDLE_LOOP_INTERVAL_SECS;
+
+	TwoDeviceHarnessBuilder::new(test_name)
+		.await?
+		.start_in_ready_state(true)
+		.sync_config(sync_config)
+		.build()
+		.await
+}
+
+/// Wait until nothing has asked Alice's transport for partners for a while
+async fn wait_for_quiet_partner_lookups(transport: &MockTransport) -> usize {
+	let mut last = transport.partner_query_count();
+	loop {
+		tokio::time::sleep(Duration::from_millis(300)).await;
+		let now = transport.partner_query_count();
+		if now == last {
+			return now;
+		}
+		last = now;
+	}
+}
+
+async fn set_sync_enabled(
+	harness: &TwoDeviceHarness,
+	enabled: bool,
+) -> anyhow::Result<sd_core::ops::devices::SetDeviceSyncEnabledOutput> {
+	let action_manager = harness
+		.core_alice
+		.context
+		.get_action_manager()
+		.await
+		.unwrap();
+	Ok(action_manager
+		.dispatch_library(
+			Some(harness.library_alice.id()),
+			SetDeviceSyncEnabledAction::from_input(SetDeviceSyncEnabledInput {
+				device_id: harness.device_bob_id,
+				enabled,
+			})
+			.unwrap(),
+		)
+		.await?)
+}
+
+/// Test: Disabling Bob on Alice removes Bob from Alice's sync partners and drops
+/// messages still queued for him
+#[tokio::test]
+async fn test_disabling_device_removes_sync_partner() -> anyhow::Result<()> {
+	let harness = build_harness("sync_partner_toggle").await?;
+	let library_id = harness.library_alice.id();
+	let alice_db = harness.library_alice.db().conn();
+	let peer_sync = harness.library_alice.sync_service().unwrap().peer_sync();
+	let network = peer_sync.network().clone();
+
+	let partners = network
+		.get_connected_sync_partners(library_id, alice_db)
+		.await?;
+	assert!(partners.contains(&harness.device_bob_id));
+
+	// Queue a change for Bob that can't be delivered
+	harness
+		.transport_alice
+		.fail_sends_to(harness.device_bob_id)
+		.await;
+	let alice_row = entities::device::Entity::find()
+		.filter(entities::device::Column::Uuid.eq(harness.device_alice_id))
+		.one(alice_db)
+		.await?
+		.expect("Alice should be registered in her library");
+	harness
+		.library_alice
+		.sync_model(&alice_row, ChangeType::Update)
+		.await?;
+	tokio::time::timeout(Duration::from_secs(5), async {
+		while peer_sync
+			.queued_retries_for_device(harness.device_bob_id)
+			.await == 0
+		{
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+	})
+	.await
+	.expect("the failed send to Bob should be queued for retry");
+
+	let output = set_sync_enabled(&harness, false).await?;
+	assert!(output.changed);
+	assert!(!output.sync_enabled);
+
+	// The transport's partner filter drops Bob as soon as the row changes
+	let partners = network
+		.get_connected_sync_partners(library_id, alice_db)
+		.await?;
+	assert!(!partners.contains(&harness.device_bob_id));
+	assert_eq!(
+		peer_sync
+			.queued_retries_for_device(harness.device_bob_id)
+			.await,
+		0
+	);
+
+	let bob_row = entities::device::Entity::find()
+		.filter(entities::device::Column::Uuid.eq(harness.device_bob_id))
+		.one(alice_db)
+		.await?
+		.expect("Bob should still be registered in Alice's library");
+	assert!(!bob_row.sync_enabled);
+
+	Ok(())
+}
+
+/// Test: A partner change wakes the sync loop instead of waiting out its interval
+#[tokio::test]
+async fn test_partner_change_wakes_sync_loop() -> anyhow::Result<()> {
+	let harness = build_harness("sync_partner_toggle_wakeup").await?;
+	let sync_service = harness.library_alice.sync_service().unwrap();
+
+	// With the loop asleep for an hour, no one looks up partners on their own
+	let before = wait_for_quiet_partner_lookups(&harness.transport_alice).await;
+	tokio::time::sleep(Duration::from_secs(1)).await;
+	assert_eq!(harness.transport_alice.partner_query_count(), before);
+
+	sync_service
+		.sync_partner_changed(harness.device_bob_id, false)
+		.await;
+
+	tokio::time::timeout(Duration::from_secs(2), async {
+		while harness.transport_alice.partner_query_count() == before {
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+	})
+	.await
+	.expect("the sync loop should re-evaluate partners right away");
+
+	Ok(())
+}
@@ -0,0 +1,180 @@
+//! Sync Pause/Resume Integration Test
+//!
+//! Verifies that pausing a library's sync holds back incoming changes and that
+//! everything made while paused is applied once sync is resumed.
+//!
+//! ## Running Tests
+//! ```bash
+//! cargo test -p sd-core --test sync_pause_test -- --test-threads=1 --nocapture
+//! ```
+
+mod helpers;
+
+use helpers::TwoDeviceHarnessBuilder;
+use sd_core::{
+	infra::{action::LibraryAction, db::entities},
+	library::LibraryConfig,
+	ops::sync::{
+		pause::{PauseLibrarySyncAction, PauseLibrarySyncInput},
+		resume::{ResumeLibrarySyncAction, ResumeLibrarySyncInput},
+	},
+	service::sync::state::DeviceSyncState,
+};
+use sea_orm::{EntityTrait, PaginatorTrait};
+use tokio::{fs, time::Duration};
+
+/// Test: Changes Alice makes while Bob is paused reach Bob after he resumes
+#[tokio::test]
+async fn test_changes_made_while_paused_apply_after_resume() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("sync_pause_resume")
+		.await?
+		.start_in_ready_state(true)
+		.build()
+		.await?;
+
+	let library_bob_id = harness.library_bob.id();
+	let action_manager = harness.core_bob.context.get_action_manager().await.unwrap();
+
+	let paused = action_manager
+		.dispatch_library(
+			Some(library_bob_id),
+			PauseLibrarySyncAction::from_input(PauseLibrarySyncInput {
+				library_id: library_bob_id,
+			})
+			.unwrap(),
+		)
+		.await?;
+	assert!(paused.changed);
+	let peer_sync_bob = harness.library_bob.sync_service().unwrap().peer_sync();
+	assert!(peer_sync_bob.is_paused().await);
+
+	// Alice keeps working while Bob is paused
+	let test_dir = harness.snapshot_dir.join("test_data");
+	fs::create_dir_all(&test_dir).await?;
+	for i in 0..5 {
+		fs::write(
+			test_dir.join(format!("paused_{}.txt", i)),
+			format!("Written while paused {}", i),
+		)
+		.await?;
+	}
+	harness
+		.add_and_index_location_alice(test_dir.to_str().unwrap(), "paused")
+		.await?;
+
+	tokio::time::sleep(Duration::from_secs(2)).await;
+	let locations_bob = entities::location::Entity::find()
+		.count(harness.library_bob.db().conn())
+		.await?;
+	assert_eq!(
+		locations_bob, 0,
+		"Bob should not apply Alice's changes while paused"
+	);
+
+	let resumed = action_manager
+		.dispatch_library(
+			Some(library_bob_id),
+			ResumeLibrarySyncAction::from_input(ResumeLibrarySyncInput {
+				library_id: library_bob_id,
+			})
+			.unwrap(),
+		)
+		.await?;
+	assert!(resumed.changed);
+	assert!(!peer_sync_bob.is_paused().await);
+
+	harness.wait_for_sync(Duration::from_secs(60)).await?;
+
+	let locations_bob = entities::location::Entity::find()
+		.count(harness.library_bob.db().conn())
+		.await?;
+	assert_eq!(locations_bob, 1, "Alice's location should reach Bob");
+
+	let entries_alice = entities::entry::Entity::find()
+		.count(harness.library_alice.db().conn())
+		.await?;
+	let entries_bob = entities::entry::Entity::find()
+		.count(harness.library_bob.db().conn())
+		.await?;
+	assert_eq!(entries_alice, entries_bob);
+
+	// Resuming again is a no-op
+	let resumed = action_manager
+		.dispatch_library(
+			Some(library_bob_id),
+			ResumeLibrarySyncAction::from_input(ResumeLibrarySyncInput {
+				library_id: library_bob_id,
+			})
+			.unwrap(),
+		)
+		.await?;
+	assert!(!resumed.changed);
+
+	Ok(())
+}
+
+/// Test: A pause is saved with the library and isn't undone by a catch-up finishing
+#[tokio::test]
+async fn test_pause_is_persisted_and_survives_catch_up() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("sync_pause_persisted")
+		.await?
+		.start_in_ready_state(true)
+		.build()
+		.await?;
+
+	let library_bob_id = harness.library_bob.id();
+	let action_manager = harness.core_bob.context.get_action_manager().await.unwrap();
+	let peer_sync_bob = harness.library_bob.sync_service().unwrap().peer_sync();
+	let config_path = harness.library_bob.path().join("library.json");
+
+	// Pausing mid catch-up lands in Paused once the catch-up is done
+	peer_sync_bob
+		.set_state_for_test(DeviceSyncState::CatchingUp { buffered_count: 0 })
+		.await;
+	let paused = action_manager
+		.dispatch_library(
+			Some(library_bob_id),
+			PauseLibrarySyncAction::from_input(PauseLibrarySyncInput {
+				library_id: library_bob_id,
+			})
+			.unwrap(),
+		)
+		.await?;
+	assert!(paused.changed);
+	assert!(peer_sync_bob.is_paused().await);
+	assert!(
+		LibraryConfig::load(&config_path)
+			.await?
+			.settings
+			.sync_paused
+	);
+
+	peer_sync_bob.transition_to_ready().await?;
+	assert!(matches!(
+		peer_sync_bob.state().await,
+		DeviceSyncState::Paused
+	));
+
+	let resumed = action_manager
+		.dispatch_library(
+			Some(library_bob_id),
+			ResumeLibrarySyncAction::from_input(ResumeLibrarySyncInput {
+				library_id: library_bob_id,
+			})
+			.unwrap(),
+		)
+		.await?;
+	assert!(resumed.changed);
+	assert!(matches!(
+		peer_sync_bob.state().await,
+		DeviceSyncState::Ready
+	));
+	assert!(
+		!LibraryConfig::load(&config_path)
+			.await?
+			.settings
+			.sync_paused
+	);
+
+	Ok(())
+}
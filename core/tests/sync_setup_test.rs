@@ -115,6 +115,7 @@ async fn alice_sync_setup_scenario() {
 					library_name: "Test Library".to_string(),
 				},
 				leader_device_id: core.device.device_id().unwrap(),
+				location_filter: None,
 			};
 
 			let action = LibrarySyncSetupAction::from_input(input).unwrap();
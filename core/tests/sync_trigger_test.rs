@@ -0,0 +1,90 @@
+//! Manual Sync Trigger Integration Test
+//!
+//! Verifies that triggering a sync pass pulls a partner's changes right away
+//! instead of waiting for the sync loop's next interval.
+//!
+//! ## Running Tests
+//! ```bash
+//! cargo test -p sd-core --test sync_trigger_test -- --test-threads=1 --nocapture
+//! ```
+
+mod helpers;
+
+use helpers::TwoDeviceHarnessBuilder;
+use sd_core::{
+	infra::{action::LibraryAction, db::entities},
+	ops::sync::trigger::{TriggerSyncAction, TriggerSyncInput},
+};
+use sea_orm::{EntityTrait, PaginatorTrait};
+use tokio::{fs, time::Duration};
+
+/// Test: A change Bob missed reaches him as soon as he triggers a sync
+#[tokio::test]
+async fn test_trigger_sync_pulls_missed_changes() -> anyhow::Result<()> {
+	let harness = TwoDeviceHarnessBuilder::new("sync_trigger")
+		.await?
+		.start_in_ready_state(true)
+		.build()
+		.await?;
+
+	// Drop Alice's broadcasts so Bob only learns about the change by pulling
+	harness
+		.transport_alice
+		.block_device(harness.device_bob_id)
+		.await;
+
+	let test_dir = harness.snapshot_dir.join("test_data");
+	fs::create_dir_all(&test_dir).await?;
+	for i in 0..5 {
+		fs::write(
+			test_dir.join(format!("trigger_{}.txt", i)),
+			format!("Missed by Bob {}", i),
+		)
+		.await?;
+	}
+	harness
+		.add_and_index_location_alice(test_dir.to_str().unwrap(), "trigger")
+		.await?;
+
+	tokio::time::sleep(Duration::from_secs(2)).await;
+	let locations_bob = entities::location::Entity::find()
+		.count(harness.library_bob.db().conn())
+		.await?;
+	assert_eq!(locations_bob, 0, "Bob should have missed Alice's broadcast");
+
+	harness
+		.transport_alice
+		.unblock_device(harness.device_bob_id)
+		.await;
+
+	let library_bob_id = harness.library_bob.id();
+	let output = harness
+		.core_bob
+		.context
+		.get_action_manager()
+		.await
+		.unwrap()
+		.dispatch_library(
+			Some(library_bob_id),
+			TriggerSyncAction::from_input(TriggerSyncInput {
+				library_id: library_bob_id,
+				peer: Some(harness.device_alice_id),
+			})
+			.unwrap(),
+		)
+		.await?;
+
+	assert_eq!(output.peers.len(), 1);
+	assert_eq!(output.peers[0].device_id, harness.device_alice_id);
+	assert!(output.peers[0].ran);
+	assert_eq!(output.peers[0].error, None);
+	assert!(output.records_pulled > 0);
+
+	// No wait_for_sync: the pass has already applied Alice's changes
+	let locations_bob = entities::location::Entity::find()
+		.count(harness.library_bob.db().conn())
+		.await?;
+	assert_eq!(locations_bob, 1, "Alice's location should reach Bob");
+
+	Ok(())
+}
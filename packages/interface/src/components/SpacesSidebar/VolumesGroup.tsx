@@ -1,5 +1,9 @@
 import {EyeSlash} from '@phosphor-icons/react';
-import {getVolumeIcon, useNormalizedQuery} from '@sd/ts-client';
+import {
+	getVolumeIcon,
+	useNormalizedQuery,
+	useVolumeUsageUpdates
+} from '@sd/ts-client';
 import type {Device, Volume} from '@sd/ts-client';
 import {GroupHeader} from './GroupHeader';
 import {SpaceItem} from './SpaceItem';
@@ -74,6 +78,7 @@ export function VolumesGroup({
 		input: {filter},
 		resourceType: 'volume'
 	});
+	useVolumeUsageUpdates();
 
 	const {data: devicesData} = useNormalizedQuery({
 		query: 'devices.list',
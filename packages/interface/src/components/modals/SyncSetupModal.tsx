@@ -150,7 +150,7 @@ function SyncSetupDialog(props: SyncSetupDialogProps) {
       localLibraryId: currentLibraryId,
       remoteLibraryId,
       action,
-      leaderDeviceId: currentDeviceId, // Deprecated but required
+      leaderDeviceId: currentDeviceId, // This device creates the library on the remote
     };
 
     console.log({ data });
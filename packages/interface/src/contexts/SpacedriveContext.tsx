@@ -15,6 +15,7 @@ export {
 	useCoreMutation,
 	useLibraryMutation,
 	useNormalizedQuery,
+	useVolumeUsageUpdates,
 } from "@sd/ts-client/hooks";
 
 // Export client type
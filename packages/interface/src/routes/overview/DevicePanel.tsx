@@ -35,7 +35,8 @@ import {useJobsContext} from '../../components/JobManager/hooks/JobsContext';
 import {
 	getDeviceIcon,
 	useCoreQuery,
-	useNormalizedQuery
+	useNormalizedQuery,
+	useVolumeUsageUpdates
 } from '../../contexts/SpacedriveContext';
 import {VolumeBar} from './VolumeBar';
 
@@ -89,6 +90,8 @@ export function DevicePanel({onLocationSelect}: DevicePanelProps = {}) {
 		input: {filter: 'All'},
 		resourceType: 'volume'
 	});
+	// Space-only changes arrive as VolumeUsageChanged, not ResourceChanged
+	useVolumeUsageUpdates();
 
 	// Fetch all devices using normalized cache
 	const {data: devicesData, isLoading: devicesLoading} = useNormalizedQuery<
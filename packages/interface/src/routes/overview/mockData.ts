@@ -27,7 +27,7 @@ export const mockOverviewData = {
 			os: "MacOS",
 			os_version: "15.0",
 			hardware_model: "MacBook Pro (14-inch, M3 Max, 2023)",
-			network_addresses: ["192.168.1.100"],
+			network_addresses: [{ kind: "Direct", value: "192.168.1.100", last_verified: null }],
 			capabilities: {
 				indexing: true,
 				p2p: true,
@@ -45,7 +45,7 @@ export const mockOverviewData = {
 			os: "IOs",
 			os_version: "18.0",
 			hardware_model: "iPhone 15 Pro",
-			network_addresses: ["192.168.1.101"],
+			network_addresses: [{ kind: "Direct", value: "192.168.1.101", last_verified: null }],
 			capabilities: {
 				indexing: false,
 				p2p: true,
@@ -63,7 +63,7 @@ export const mockOverviewData = {
 			os: "MacOS",
 			os_version: "14.5",
 			hardware_model: "Mac Studio (M2 Ultra, 2023)",
-			network_addresses: ["192.168.1.102"],
+			network_addresses: [{ kind: "Direct", value: "192.168.1.102", last_verified: null }],
 			capabilities: {
 				indexing: true,
 				p2p: true,
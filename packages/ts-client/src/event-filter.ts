@@ -52,8 +52,11 @@ export const DEFAULT_EVENT_SUBSCRIPTION: EventVariant[] = [
 	"VolumeAdded",
 	"VolumeRemoved",
 	"VolumeUpdated",
+	"VolumeUsageChanged",
 	"VolumeSpeedTested",
 	"VolumeMountChanged",
+	"VolumeMounted",
+	"VolumeUnmounted",
 	"VolumeError",
 	// Job lifecycle
 	"JobQueued",
@@ -68,9 +71,12 @@ export const DEFAULT_EVENT_SUBSCRIPTION: EventVariant[] = [
 	"IndexingStarted",
 	"IndexingCompleted",
 	"IndexingFailed",
+	"LocationScanStateChanged",
 	// Device events
 	"DeviceConnected",
 	"DeviceDisconnected",
+	"DeviceInfoChanged",
+	"DeviceUnpaired",
 	// Resource events (normalized cache updates)
 	"ResourceChanged",
 	"ResourceChangedBatch",
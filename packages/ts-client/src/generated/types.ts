@@ -26,6 +26,23 @@ export type AddItemInput = { space_id: string; group_id: string | null; item_typ
 
 export type AddItemOutput = { item: SpaceItem };
 
+/**
+ * How a network address reaches the device
+ */
+export type AddrKind = 
+/**
+ * Directly dialable socket address
+ */
+"Direct" | 
+/**
+ * Address of a relay server the device is reachable through
+ */
+"Relay" | 
+/**
+ * Provenance not recorded (addresses stored before kinds existed)
+ */
+"Unknown";
+
 /**
  * Input for alternate instances query
  */
@@ -106,7 +123,11 @@ proxy_pairing: ProxyPairingConfigOutput;
 /**
  * Spacebot companion configuration
  */
-spacebot: SpacebotConfigOutput };
+spacebot: SpacebotConfigOutput; 
+/**
+ * Directory pairing sessions and the vouching queue are stored under, if not the data directory
+ */
+pairing_data_dir: string | null };
 
 export type ApplyTagsInput = { 
 /**
@@ -182,6 +203,27 @@ export type ApplyToTargets =
  */
 export type AudioMediaData = { uuid: string; duration_seconds: number | null; bit_rate: number | null; sample_rate: number | null; channels: string | null; codec: string | null; title: string | null; artist: string | null; album: string | null; album_artist: string | null; genre: string | null; year: number | null; track_number: number | null; disc_number: number | null; composer: string | null; publisher: string | null; copyright: string | null };
 
+/**
+ * Progress of backfilling one model from one peer
+ */
+export type BackfillModelStatus = { peer_device_id: string; model_type: string; 
+/**
+ * Cursor of the last applied page ("timestamp|uuid")
+ */
+last_applied_cursor: string | null; 
+/**
+ * Watermark recorded for this peer and model, if any
+ */
+watermark: string | null; records_applied: number; 
+/**
+ * Record count the peer reported, if it sent one
+ */
+estimated_total: number | null; 
+/**
+ * Share of the estimated total applied, from 0 to 100
+ */
+percentage: number | null; updated_at: string };
+
 /**
  * Cloud service type identifier
  */
@@ -695,7 +737,7 @@ swap_total_bytes: number | null;
 /**
  * Network addresses for P2P connections
  */
-network_addresses: string[]; 
+network_addresses: NetworkAddress[]; 
 /**
  * Device capabilities (indexing, P2P, volume detection, etc.)
  */
@@ -737,7 +779,11 @@ is_connected?: boolean;
  */
 connection_method?: ConnectionMethod | null };
 
-export type DeviceDebugInfo = { uuid: string; name: string; sync_enabled: boolean; has_node_id: boolean; node_id: string | null };
+export type DeviceDebugInfo = { uuid: string; name: string; sync_enabled: boolean; has_node_id: boolean; node_id: string | null; 
+/**
+ * Why this device is not a sync partner, None when it is one
+ */
+excluded_reason: SyncPartnerExclusion | null };
 
 /**
  * Device form factor types
@@ -746,6 +792,11 @@ export type DeviceFormFactor = "Desktop" | "Laptop" | "Mobile" | "Tablet" | "Ser
 
 export type DeviceInfo = { id: string; name: string; slug: string; os: string; hardware_model: string | null; created_at: string };
 
+/**
+ * A field of [`DeviceInfo`]
+ */
+export type DeviceInfoField = "DeviceId" | "DeviceName" | "DeviceSlug" | "DeviceType" | "OsVersion" | "AppVersion" | "NetworkFingerprint" | "LastSeen" | "Version";
+
 /**
  * Device metrics snapshot
  */
@@ -936,6 +987,23 @@ location_id: string;
  */
 job_id: string };
 
+/**
+ * Serializable representation of EndpointAddr
+ */
+export type EndpointAddrInfo = { 
+/**
+ * Node ID as string
+ */
+node_id: string; 
+/**
+ * Direct socket addresses
+ */
+direct_addresses: string[]; 
+/**
+ * Relay URL if available
+ */
+relay_url: string | null };
+
 /**
  * Type of filesystem entry
  */
@@ -1100,7 +1168,11 @@ error_type: string } } | { LibraryStatisticsUpdated: { library_id: string; stati
  * Refresh event - signals that all frontend caches should be invalidated
  * Emitted after major data recalculations (e.g., volume unique_bytes refresh)
  */
-"Refresh" | { ProxyPairingConfirmationRequired: { session_id: string; vouchee_device_name: string; vouchee_device_os: string; voucher_device_name: string; voucher_device_id: string; expires_at: string } } | { ProxyPairingVouchingReady: { session_id: string; vouchee_device_id: string } } | { EntryCreated: { library_id: string; entry_id: string } } | { EntryModified: { library_id: string; entry_id: string } } | { EntryDeleted: { library_id: string; entry_id: string } } | { EntryMoved: { library_id: string; entry_id: string; old_path: string; new_path: string } } | { FsRawChange: { library_id: string; kind: FsRawEventKind } } | { VolumeAdded: Volume } | { VolumeRemoved: { fingerprint: VolumeFingerprint } } | { VolumeUpdated: { fingerprint: VolumeFingerprint; old_info: VolumeInfo; new_info: VolumeInfo } } | { VolumeSpeedTested: { fingerprint: VolumeFingerprint; read_speed_mbps: number; write_speed_mbps: number } } | { VolumeMountChanged: { fingerprint: VolumeFingerprint; is_mounted: boolean } } | { VolumeError: { fingerprint: VolumeFingerprint; error: string } } | { JobQueued: { job_id: string; job_type: string; device_id: string } } | { JobStarted: { job_id: string; job_type: string; device_id: string } } | { JobProgress: { job_id: string; job_type: string; device_id: string; progress: number; message: string | null; generic_progress: GenericProgress | null } } | { JobCompleted: { job_id: string; job_type: string; device_id: string; output: JobOutput } } | { JobFailed: { job_id: string; job_type: string; device_id: string; error: string } } | { JobCancelled: { job_id: string; job_type: string; device_id: string } } | { JobPaused: { job_id: string; device_id: string } } | { JobResumed: { job_id: string; device_id: string } } | { IndexingStarted: { location_id: string } } | { IndexingProgress: { location_id: string; processed: number; total: number | null } } | { IndexingCompleted: { location_id: string; total_files: number; total_dirs: number } } | { IndexingFailed: { location_id: string; error: string } } | { DeviceConnected: { device_id: string; device_name: string } } | { DeviceDisconnected: { device_id: string } } | { SyncStateChanged: { library_id: string; previous_state: string; new_state: string; timestamp: string } } | { SyncActivity: { library_id: string; peer_device_id: string; activity_type: SyncActivityType; model_type: string | null; count: number; timestamp: string } } | { SyncConnectionChanged: { library_id: string; peer_device_id: string; peer_name: string; connected: boolean; timestamp: string } } | { SyncError: { library_id: string; peer_device_id: string | null; error_type: string; message: string; timestamp: string } } | { ResourceChanged: { 
+"Refresh" | { ProxyPairingConfirmationRequired: { session_id: string; vouchee_device_name: string; vouchee_device_os: string; voucher_device_name: string; voucher_device_id: string; expires_at: string } } | { ProxyPairingVouchingReady: { session_id: string; vouchee_device_id: string } } | { ProxiedDeviceConfirmationRequired: { session_id: string; device_id: string; device_name: string; voucher_device_id: string } } | { ProxyPairingCompleted: { session_id: string; voucher_device_id: string; paired: string[]; failed: ProxiedPairingFailure[] } } | { EntryCreated: { library_id: string; entry_id: string } } | { EntryModified: { library_id: string; entry_id: string } } | { EntryDeleted: { library_id: string; entry_id: string } } | { EntryMoved: { library_id: string; entry_id: string; old_path: string; new_path: string } } | { FsRawChange: { library_id: string; kind: FsRawEventKind } } | { VolumeAdded: Volume } | { VolumeRemoved: { fingerprint: VolumeFingerprint } } | { VolumeUpdated: { fingerprint: VolumeFingerprint; old_info: VolumeInfo; new_info: VolumeInfo } } | 
+/**
+ * Free space or capacity changed and nothing else did
+ */
+{ VolumeUsageChanged: { fingerprint: VolumeFingerprint; available_space: number; total_capacity: number } } | { VolumeSpeedTested: { fingerprint: VolumeFingerprint; read_speed_mbps: number; write_speed_mbps: number } } | { VolumeMountChanged: { fingerprint: VolumeFingerprint; is_mounted: boolean } } | { VolumeMounted: { mount_point: string } } | { VolumeUnmounted: { mount_point: string } } | { VolumeError: { fingerprint: VolumeFingerprint; error: string } } | { JobQueued: { job_id: string; job_type: string; device_id: string } } | { JobStarted: { job_id: string; job_type: string; device_id: string } } | { JobProgress: { job_id: string; job_type: string; device_id: string; progress: number; message: string | null; generic_progress: GenericProgress | null } } | { JobCompleted: { job_id: string; job_type: string; device_id: string; output: JobOutput } } | { JobFailed: { job_id: string; job_type: string; device_id: string; error: string } } | { JobCancelled: { job_id: string; job_type: string; device_id: string } } | { JobPaused: { job_id: string; device_id: string } } | { JobResumed: { job_id: string; device_id: string } } | { IndexingStarted: { location_id: string } } | { IndexingProgress: { location_id: string; processed: number; total: number | null } } | { IndexingCompleted: { location_id: string; total_files: number; total_dirs: number } } | { IndexingFailed: { location_id: string; error: string } } | { LocationScanStateChanged: { library_id: string; location_id: string; state: ScanState } } | { DeviceConnected: { device_id: string; device_name: string } } | { DeviceDisconnected: { device_id: string } } | { DeviceInfoChanged: { device_id: string; changed: DeviceInfoField[] } } | { DeviceUnpaired: { device_id: string; purged_synced_data: boolean } } | { SyncStateChanged: { library_id: string; previous_state: string; new_state: string; timestamp: string } } | { SyncActivity: { library_id: string; peer_device_id: string; activity_type: SyncActivityType; model_type: string | null; count: number; timestamp: string } } | { SyncConnectionChanged: { library_id: string; peer_device_id: string; peer_name: string; connected: boolean; timestamp: string } } | { SyncError: { library_id: string; peer_device_id: string | null; error_type: string; message: string; timestamp: string } } | { ResourceChanged: { 
 /**
  * Resource type identifier (e.g., "location", "tag", "album")
  */
@@ -1598,6 +1670,34 @@ export type GetAppConfigQueryInput = null;
  */
 export type GetLibraryConfigQueryInput = null;
 
+export type GetRemoteLibraryStateInput = { 
+/**
+ * Paired device to ask
+ */
+device_id: string; 
+/**
+ * Library to inspect on that device
+ */
+library_id: string };
+
+export type GetRemoteLibraryStateOutput = { 
+/**
+ * Library ID the remote device answered for
+ */
+library_id: string; 
+/**
+ * Library name on the remote device
+ */
+library_name: string; 
+/**
+ * Slugs of every device registered in the remote library
+ */
+device_slugs: string[]; 
+/**
+ * Number of devices registered in the remote library
+ */
+device_count: number };
+
 export type GetSourceInput = { source_id: string };
 
 /**
@@ -1682,7 +1782,11 @@ operations_only: boolean | null;
 /**
  * Show only error metrics
  */
-errors_only: boolean | null };
+errors_only: boolean | null; 
+/**
+ * Show only throughput metrics
+ */
+throughput_only: boolean | null };
 
 export type GetSyncMetricsOutput = { 
 /**
@@ -2481,6 +2585,10 @@ enable_ai_tagging: boolean;
  * Whether sync is enabled for this library
  */
 sync_enabled: boolean; 
+/**
+ * Whether the user paused sync for this library, kept so a pause survives a restart
+ */
+sync_paused?: boolean; 
 /**
  * Whether the library is encrypted at rest
  */
@@ -2508,7 +2616,17 @@ auto_track_external_volumes: boolean;
 /**
  * Indexer settings (rule toggles and related)
  */
-indexer?: IndexerSettings };
+indexer?: IndexerSettings; 
+/**
+ * Locations shared with each peer device, for peers that only sync part of the library
+ * 
+ * Peers without an entry receive every location.
+ */
+shared_location_filters?: { [key in string]: string[] }; 
+/**
+ * Locations each peer device shares with us, as announced during sync setup
+ */
+received_location_filters?: { [key in string]: string[] } };
 
 /**
  * Library settings output
@@ -2653,9 +2771,17 @@ remoteLibraryId: string | null;
  */
 action: LibrarySyncAction; 
 /**
- * DEPRICATED: Which device should be the sync leader (for future sync implementation)
+ * Which device creates the shared library; the other one only registers
+ * 
+ * Must be the local or the remote device.
+ */
+leaderDeviceId: string; 
+/**
+ * Only sync these locations (and their entries) with the remote device
+ * 
+ * `None` shares the whole library. Only supported when sharing a local library.
  */
-leaderDeviceId: string };
+locationFilter?: string[] | null };
 
 /**
  * Result of library sync setup operation
@@ -3105,11 +3231,24 @@ export type MountType =
  */
 "User";
 
+/**
+ * A network address for a device, with where it came from and when it last worked
+ */
+export type NetworkAddress = { kind: AddrKind; value: string; 
+/**
+ * Last time a connection over this address succeeded
+ */
+last_verified: string | null };
+
 export type NetworkStartInput = Record<string, never>;
 
 export type NetworkStartOutput = { started: boolean };
 
-export type NetworkStatus = { running: boolean; node_id: string | null; addresses: string[]; paired_devices: number; connected_devices: number; version: string; relay_url: string | null };
+export type NetworkStatus = { running: boolean; node_id: string | null; addresses: string[]; paired_devices: number; connected_devices: number; version: string; relay_url: string | null; 
+/**
+ * Health of the trusted relays in the discovery config, in config order
+ */
+trusted_relays: RelayHealth[] };
 
 export type NetworkStatusQueryInput = null;
 
@@ -3183,11 +3322,40 @@ export type PairCancelInput = { session_id: string };
 
 export type PairCancelOutput = { cancelled: boolean };
 
-export type PairConfirmProxyInput = { session_id: string; accepted: boolean };
+export type PairConfirmProxyInput = { session_id: string; accepted: boolean; 
+/**
+ * Set when, as the vouchee, confirming a device that accepted our vouch
+ */
+device_id?: string | null };
+
+export type PairConfirmProxyOutput = { success: boolean; error: string | null; 
+/**
+ * The session had already been answered; this request changed nothing
+ */
+already_confirmed: boolean };
 
-export type PairConfirmProxyOutput = { success: boolean; error: string | null };
+export type PairDiagnosticsOutput = { 
+/**
+ * Redacted pairing state, None when networking is not running
+ */
+state: PairingStateDump | null };
 
-export type PairGenerateInput = Record<string, never>;
+export type PairDiagnosticsQueryInput = null;
+
+export type PairGenerateInput = { 
+/**
+ * Expected session ID. Session IDs are derived from the code secret, so this
+ * must be paired with `secret` and is checked against the derived value.
+ */
+session_id?: string | null; 
+/**
+ * Hex-encoded 16-byte pairing secret. When omitted a random one is generated.
+ */
+secret?: string | null; 
+/**
+ * Let the code pair any number of devices until it expires instead of only the first
+ */
+multi_use?: boolean };
 
 export type PairGenerateOutput = { code: string; session_id: string; expires_at: string; 
 /**
@@ -3197,7 +3365,20 @@ qr_json: string;
 /**
  * Node ID for relay-based pairing (share this for cross-network pairing)
  */
-node_id: string | null };
+node_id: string | null; 
+/**
+ * This node's direct addresses and relay at generation time, for embedding in
+ * a link or QR code so the joiner can connect without a discovery lookup
+ */
+node_addr: EndpointAddrInfo | null };
+
+export type PairHistoryOutput = { 
+/**
+ * Most recent first, capped at the last 50 sessions
+ */
+entries: PairingHistoryEntry[] };
+
+export type PairHistoryQueryInput = null;
 
 export type PairJoinInput = { code: string; 
 /**
@@ -3207,7 +3388,11 @@ node_id: string | null };
 
 export type PairJoinOutput = { paired_device_id: string; device_name: string };
 
-export type PairStatusOutput = { sessions: PairingSessionSummary[] };
+export type PairStatusOutput = { sessions: PairingSessionSummary[]; 
+/**
+ * Pairing sessions are saved to disk; when false they are lost on restart
+ */
+persistence_enabled: boolean };
 
 export type PairStatusQueryInput = null;
 
@@ -3248,13 +3433,96 @@ isConnected: boolean;
  */
 lastSeen: string };
 
-export type PairingSessionSummary = { id: string; state: SerializablePairingState; remote_device_id: string | null; expires_at: string | null };
+/**
+ * One finished pairing session
+ */
+export type PairingHistoryEntry = { session_id: string; remote_device_id: string | null; remote_device_name: string | null; outcome: PairingOutcome; 
+/**
+ * When the session was seen to finish
+ */
+finished_at: string };
+
+/**
+ * How a pairing session ended
+ */
+export type PairingOutcome = "Completed" | { Failed: { reason: string; detail: string | null } } | 
+/**
+ * Removed by the user (or because its device went away) before finishing
+ */
+"Cancelled";
+
+/**
+ * Gap between two consecutive handshake steps
+ */
+export type PairingPhase = "RequestToChallenge" | "ChallengeToResponse" | "ResponseToComplete";
+
+/**
+ * Role in the pairing process
+ */
+export type PairingRole = "Initiator" | "Joiner";
+
+/**
+ * One active pairing session, without key material
+ */
+export type PairingSessionSnapshot = { session_id: string; 
+/**
+ * State name; challenge and response bytes are left out
+ */
+state: string; failure_reason: string | null; 
+/**
+ * Inferred from the state, None for states both sides pass through
+ */
+role: PairingRole | null; remote_device_id: string | null; remote_device_name: string | null; created_at: string; via_relay: boolean; auto_accepted: boolean; 
+/**
+ * Whether a shared secret has been derived, never the secret itself
+ */
+has_shared_secret: boolean };
+
+export type PairingSessionSummary = { id: string; state: SerializablePairingState; remote_device_id: string | null; expires_at: string | null; 
+/**
+ * Connection fell back to the relay; expect degraded performance
+ */
+via_relay: boolean; 
+/**
+ * Requester is on the auto-accept allowlist; no confirmation prompt is needed
+ */
+auto_accepted: boolean };
+
+/**
+ * Snapshot of the pairing handler for debugging
+ */
+export type PairingStateDump = { captured_at: string; sessions: PairingSessionSnapshot[]; vouching_sessions: VouchingSessionSnapshot[]; pending_proxy_confirmations: number; pending_rekeys: number; 
+/**
+ * Entries in the offline vouching queue, None when the queue is not set up
+ */
+vouching_queue_depth: number | null; history_entries: number; persistence_enabled: boolean; 
+/**
+ * Handshake phase durations over the pairings completed since startup
+ */
+phase_timings: PairingTimings };
+
+/**
+ * Phase timings aggregated over completed pairings
+ */
+export type PairingTimings = { completed_sessions: number; phases: PhaseHistogram[] };
 
 /**
  * Path mapping for resolving virtual paths to actual storage locations
  */
 export type PathMapping = { virtual_path: string; actual_path: string };
 
+export type PauseLibrarySyncInput = { 
+/**
+ * Library whose sync should be paused
+ */
+library_id: string };
+
+export type PauseLibrarySyncOutput = { library_id: string; 
+/**
+ * False when sync was already paused
+ */
+changed: boolean };
+
 /**
  * Per-peer activity information
  */
@@ -3290,6 +3558,15 @@ warning_count: number };
  */
 export type PerformanceSnapshot = { broadcast_latency: LatencySnapshot; apply_latency: LatencySnapshot; backfill_request_latency: LatencySnapshot; state_watermark: string; shared_watermark: string; watermark_lag_ms: { [key in string]: number }; hlc_physical_drift_ms: number; hlc_counter_max: number; db_query_duration: LatencySnapshot; db_query_count: number };
 
+/**
+ * Durations of one phase across completed sessions
+ */
+export type PhaseHistogram = { phase: PairingPhase; count: number; total_ms: number; min_ms: number | null; max_ms: number | null; 
+/**
+ * Counts per bucket of `PHASE_BUCKET_BOUNDS_MS`, plus one for slower phases
+ */
+buckets: number[] };
+
 export type PingInput = { message: string; count?: number | null };
 
 export type PingOutput = { echo: string; count: number; extension_works: boolean };
@@ -3337,10 +3614,39 @@ bytes_completed: number | null;
  */
 total_bytes: number | null };
 
+/**
+ * A device that accepted a vouch for us but could not be paired with
+ */
+export type ProxiedPairingFailure = { device_id: string; error: string };
+
+export type ProxyAuditOutput = { 
+/**
+ * Proxy pairing decisions, oldest first
+ */
+entries: ProxyPairingAuditEntry[] };
+
+export type ProxyAuditQueryInput = null;
+
+/**
+ * A decision on a vouch received for proxy pairing
+ * 
+ * Kept so a security review can tell whether a person approved a proxied
+ * device or the auto-accept policy let it in.
+ */
+export type ProxyPairingAuditEntry = { session_id: string; voucher_device_id: string; vouchee_device_id: string; vouchee_device_name: string; accepted: boolean; 
+/**
+ * Whether the user made the decision rather than the auto-accept policy
+ */
+confirmed_by_user: boolean; 
+/**
+ * Why the vouch was refused when policy rather than the user rejected it
+ */
+reason: string | null; decided_at: string };
+
 /**
  * Proxy pairing configuration output
  */
-export type ProxyPairingConfigOutput = { auto_accept_vouched: boolean; auto_vouch_to_all: boolean; vouch_signature_max_age: number; vouch_response_timeout: number; vouch_queue_retry_limit: number };
+export type ProxyPairingConfigOutput = { auto_accept_vouched: boolean; auto_vouch_to_all: boolean; vouch_signature_max_age: number; vouch_max_future_skew: number; vouch_response_timeout: number; vouch_queue_retry_limit: number; vouch_queue_poll_secs: number; session_cleanup_poll_secs: number; privacy_mode: boolean; max_vouching_sessions: number; vouching_session_ttl_secs: number; auto_accept_pairing_from: string[]; auto_accept_vouches_from: string[]; vouch_rejection_threshold: number; vouch_rejection_cooldown_secs: number; vouchee_confirms: boolean; max_device_info_size: number; min_code_entropy_bits: number };
 
 /**
  * Proxy/sidecar generation policy (video scrubbing)
@@ -3401,6 +3707,24 @@ generated_count: number;
  */
 variants: string[] };
 
+/**
+ * Health of one trusted relay
+ */
+export type RelayHealth = { url: string; status: RelayHealthStatus; 
+/**
+ * Probes failed in a row; zero once one succeeds
+ */
+consecutive_failures: number; last_error: string | null; last_checked_at: string | null };
+
+/**
+ * Result of the most recent probe of a relay
+ */
+export type RelayHealthStatus = 
+/**
+ * Not probed yet
+ */
+"Unknown" | "Healthy" | "Unhealthy";
+
 /**
  * State of a job running on a remote device
  */
@@ -3451,6 +3775,23 @@ export type ReorderItemsInput = { group_id: string | null; item_ids: string[] };
 
 export type ReorderOutput = { success: boolean };
 
+export type ReplayEventsInput = { 
+/**
+ * Return events emitted after this time, e.g. when the client last saw an event
+ */
+since: string };
+
+export type ReplayEventsOutput = { 
+/**
+ * Buffered events emitted after `since`, oldest first
+ */
+events: ReplayedEvent[] };
+
+/**
+ * An event kept for clients that reconnect after it was emitted
+ */
+export type ReplayedEvent = { emitted_at: string; event: Event };
+
 export type ResetDataInput = { 
 /**
  * Confirmation flag to prevent accidental data loss
@@ -3484,6 +3825,18 @@ alternate_ids: string[];
  */
 affected_paths?: SdPath[] };
 
+export type ResumeLibrarySyncInput = { 
+/**
+ * Library whose sync should be resumed
+ */
+library_id: string };
+
+export type ResumeLibrarySyncOutput = { library_id: string; 
+/**
+ * False when sync was not paused
+ */
+changed: boolean };
+
 /**
  * Risk level for adding a path as a location
  */
@@ -3739,6 +4092,18 @@ export type ServiceState = { running: boolean; details: string | null };
 
 export type ServiceStatus = { location_watcher: ServiceState; networking: ServiceState; volume_monitor: ServiceState; file_sharing: ServiceState };
 
+export type SetDeviceTrustInput = { device_id: string; trust_level: TrustLevel };
+
+export type SetDeviceTrustOutput = { previous_trust_level: TrustLevel; trust_level: TrustLevel; 
+/**
+ * Whether the device is now accepted as a voucher for proxy pairing
+ */
+can_vouch: boolean; 
+/**
+ * Queued vouches involving the device that were dropped
+ */
+dropped_vouches: number };
+
 /**
  * Domain representation of a sidecar
  */
@@ -4019,6 +4384,17 @@ export type SuggestedLocationsQueryInput = null;
  */
 export type SyncActivityType = { type: "BroadcastSent"; data: { changes: number } } | { type: "ChangesReceived"; data: { changes: number } } | { type: "ChangesApplied"; data: { changes: number } } | { type: "BackfillStarted" } | { type: "BackfillCompleted"; data: { records: number } } | { type: "CatchUpStarted" } | { type: "CatchUpCompleted" };
 
+/**
+ * Input for getting the progress of active backfills
+ */
+export type SyncBackfillStatusInput = Record<string, never>;
+
+export type SyncBackfillStatusOutput = { 
+/**
+ * Models currently being backfilled, sorted by peer then model; empty when idle
+ */
+backfills: BackfillModelStatus[] };
+
 /**
  * A logged sync event
  */
@@ -4108,7 +4484,67 @@ performance: PerformanceSnapshot;
 /**
  * Error metrics
  */
-errors: ErrorSnapshot };
+errors: ErrorSnapshot; 
+/**
+ * Apply-path throughput
+ */
+throughput: ThroughputSnapshot };
+
+export type SyncModelInfo = { 
+/**
+ * The model's `SYNC_MODEL` name
+ */
+sync_model: string; table_name: string; kind: SyncModelKind; 
+/**
+ * Models that must be synced before this one
+ */
+depends_on: string[] };
+
+/**
+ * How a model is replicated between devices
+ */
+export type SyncModelKind = 
+/**
+ * State-based sync, only the owning device writes
+ */
+"device_owned" | 
+/**
+ * Log-based sync with HLC ordering
+ */
+"shared";
+
+export type SyncModelsInput = Record<string, never>;
+
+export type SyncModelsOutput = { 
+/**
+ * Registered models, sorted by name
+ */
+models: SyncModelInfo[] };
+
+/**
+ * The first condition that keeps a device out of the sync partners
+ */
+export type SyncPartnerExclusion = 
+/**
+ * The device is this one
+ */
+"LocalDevice" | 
+/**
+ * Paired, but not a member of this library
+ */
+"NotInLibrary" | 
+/**
+ * A library member with sync turned off
+ */
+"SyncDisabled" | 
+/**
+ * Not paired, so the network layer has no node to reach it at
+ */
+"NoNodeId" | 
+/**
+ * Paired and syncing, but not connected right now
+ */
+"NotConnected";
 
 export type SyncPartnerInfo = { device_uuid: string; device_name: string; is_paired: boolean };
 
@@ -4258,6 +4694,16 @@ export type TagType =
  */
 export type TextHighlight = { field: string; text: string; start: number; end: number };
 
+/**
+ * Throughput snapshot with lifetime and current-session figures
+ */
+export type ThroughputSnapshot = { lifetime: ThroughputWindow; session: ThroughputWindow };
+
+/**
+ * Counters and per-second rates over a single window
+ */
+export type ThroughputWindow = { started_at: string; elapsed_seconds: number; records_applied: number; bytes_received: number; errors: number; records_per_second: number; bytes_per_second: number; errors_per_second: number };
+
 export type ThumbnailInput = { paths: string[]; size: number; quality: number };
 
 /**
@@ -4302,9 +4748,56 @@ export type TranscribeAudioOutput = {
  */
 job_id: string };
 
+export type TriggerSyncInput = { 
+/**
+ * Library to sync
+ */
+library_id: string; 
+/**
+ * Only sync with this partner; all connected partners when omitted
+ */
+peer?: string | null };
+
+export type TriggerSyncOutput = { library_id: string; 
+/**
+ * Change records delivered across all partners
+ */
+records_pushed: number; 
+/**
+ * Records received across all partners
+ */
+records_pulled: number; peers: TriggeredPeerSync[] };
+
+export type TriggeredPeerSync = { device_id: string; records_pushed: number; records_pulled: number; 
+/**
+ * False when another pass was already running against this partner
+ */
+ran: boolean; 
+/**
+ * Why the pass failed, if it did
+ */
+error: string | null };
+
 /**
  * Statistics for the unified ephemeral index
  */
+/**
+ * Trust level for persistent connections
+ */
+export type TrustLevel = 
+/**
+ * Device was manually paired and should auto-reconnect
+ */
+"Trusted" | 
+/**
+ * Device connection failed multiple times, deprioritize
+ */
+"Unreliable" | 
+/**
+ * Device manually disconnected, don't auto-reconnect
+ */
+"Blocked";
+
 export type UnifiedIndexStats = { 
 /**
  * Total entries in the shared arena
@@ -4381,6 +4874,31 @@ total_count: number;
  */
 total_size: number };
 
+export type UnpairDeviceInput = { device_id: string; 
+/**
+ * Whether to also delete the device's synced data from every open library
+ * 
+ * Only rows the device owns (its locations, their entries and its volumes) are
+ * deleted. The device record is shared with the library, so it is kept with
+ * sync turned off. Shared rows such as tags and content identities may still be
+ * referenced by other devices and are left untouched.
+ */
+purge_synced_data?: boolean };
+
+export type UnpairDeviceOutput = { 
+/**
+ * Whether the device had a persisted pairing that was removed
+ */
+unpaired: boolean; 
+/**
+ * Queued vouches involving the device that were dropped
+ */
+dropped_vouches: number; 
+/**
+ * Libraries the device's synced data was purged from
+ */
+purged_libraries: number };
+
 export type UpdateAdapterInput = { adapter_id: string };
 
 export type UpdateAdapterOutput = { adapter_id: string; old_version: string; new_version: string; schema_changed: boolean };
@@ -4442,6 +4960,10 @@ proxy_pairing_auto_vouch_to_all?: boolean | null;
  * Maximum age of vouch signatures in seconds
  */
 proxy_pairing_vouch_signature_max_age?: number | null; 
+/**
+ * Tolerated clock skew for vouch timestamps ahead of ours, in seconds
+ */
+proxy_pairing_vouch_max_future_skew?: number | null; 
 /**
  * Timeout for proxy confirmation in seconds
  */
@@ -4450,6 +4972,58 @@ proxy_pairing_vouch_response_timeout?: number | null;
  * Maximum retries for queued vouches
  */
 proxy_pairing_vouch_queue_retry_limit?: number | null; 
+/**
+ * How often the vouching queue is processed, in seconds
+ */
+proxy_pairing_vouch_queue_poll_secs?: number | null; 
+/**
+ * How often expired pairing sessions are cleaned up, in seconds
+ */
+proxy_pairing_session_cleanup_poll_secs?: number | null; 
+/**
+ * Only reveal name, slug and fingerprint to devices that are not yet paired
+ */
+proxy_pairing_privacy_mode?: boolean | null; 
+/**
+ * Maximum number of vouching sessions held at once
+ */
+proxy_pairing_max_vouching_sessions?: number | null; 
+/**
+ * How long a vouching session may stay open before it is finalized, in seconds
+ */
+proxy_pairing_vouching_session_ttl_secs?: number | null; 
+/**
+ * Devices allowed to complete direct pairing without a pairing code
+ */
+proxy_pairing_auto_accept_pairing_from?: string[] | null; 
+/**
+ * Vouchers whose proxy pairings are auto-accepted; empty allows every trusted voucher
+ */
+proxy_pairing_auto_accept_vouches_from?: string[] | null; 
+/**
+ * Consecutive rejections before auto-vouching to a target pauses; 0 disables the pause
+ */
+proxy_pairing_vouch_rejection_threshold?: number | null; 
+/**
+ * How long auto-vouching to a rejecting target stays paused, in seconds
+ */
+proxy_pairing_vouch_rejection_cooldown_secs?: number | null; 
+/**
+ * Whether a new device confirms each device that accepted a vouch for it
+ */
+proxy_pairing_vouchee_confirms?: boolean | null; 
+/**
+ * Largest device info accepted in a pairing or vouch message, in bytes
+ */
+proxy_pairing_max_device_info_size?: number | null; 
+/**
+ * Least estimated entropy, in bits, a pairing code must carry for us to join it
+ */
+proxy_pairing_min_code_entropy_bits?: number | null; 
+/**
+ * Directory for pairing sessions and the vouching queue; an empty path resets it to the data directory
+ */
+pairing_data_dir?: string | null; 
 /**
  * Whether Spacebot features are enabled in the UI
  */
@@ -4994,15 +5568,68 @@ export type VouchState = { device_id: string; device_name: string; status: Vouch
 
 export type VouchStatus = "Selected" | "Queued" | "Waiting" | "Accepted" | "Rejected" | "Unreachable";
 
-export type VouchingSession = { id: string; vouchee_device_id: string; vouchee_device_name: string; voucher_device_id: string; created_at: string; state: VouchingSessionState; vouches: VouchState[] };
+export type VouchingQueuePruneInput = { 
+/**
+ * Also drop every queued vouch of this vouching session
+ */
+session_id?: string | null; 
+/**
+ * Also drop every queued vouch addressed to this device
+ */
+target_device_id?: string | null };
+
+export type VouchingQueuePruneOutput = { 
+/**
+ * Vouches dropped because they had expired
+ */
+expired: number; 
+/**
+ * Vouches dropped for the requested session
+ */
+for_session: number; 
+/**
+ * Vouches dropped for the requested target device
+ */
+for_target: number; total: number };
+
+export type VouchingSession = { id: string; vouchee_device_id: string; vouchee_device_name: string; voucher_device_id: string; created_at: string; expires_at: string; state: VouchingSessionState; vouches: VouchState[] };
+
+export type VouchingSessionCancelInput = { session_id: string };
+
+export type VouchingSessionCancelOutput = { 
+/**
+ * Whether the session existed and was cancelled
+ */
+cancelled: boolean };
 
 export type VouchingSessionInput = { session_id: string };
 
 export type VouchingSessionOutput = { session: VouchingSession | null };
 
+export type VouchingSessionRefreshInput = { session_id: string };
+
+export type VouchingSessionRefreshOutput = { 
+/**
+ * Whether the session exists and its state was emitted again
+ */
+republished: boolean };
+
+/**
+ * One proxy vouching session, without the vouchee's keys
+ */
+export type VouchingSessionSnapshot = { session_id: string; state: VouchingSessionState; vouch_count: number; expires_at: string };
+
 export type VouchingSessionState = "Pending" | "InProgress" | "Completed";
 // ===== API Type Unions =====
 
+export type VouchingSessionsOutput = { 
+/**
+ * Newest first
+ */
+sessions: VouchingSession[] };
+
+export type VouchingSessionsQueryInput = null;
+
 export type CoreAction =
      { type: 'config.app.update'; input: UpdateAppConfigInput; output: UpdateAppConfigOutput }
   |  { type: 'core.ephemeral_reset'; input: EphemeralCacheResetInput; output: EphemeralCacheResetOutput }
@@ -5014,15 +5641,21 @@ export type CoreAction =
   |  { type: 'models.whisper.delete'; input: DeleteWhisperModelInput; output: DeleteWhisperModelOutput }
   |  { type: 'models.whisper.download'; input: DownloadWhisperModelInput; output: DownloadWhisperModelOutput }
   |  { type: 'network.device.revoke'; input: DeviceRevokeInput; output: DeviceRevokeOutput }
+  |  { type: 'network.device.trust'; input: SetDeviceTrustInput; output: SetDeviceTrustOutput }
+  |  { type: 'network.device.unpair'; input: UnpairDeviceInput; output: UnpairDeviceOutput }
   |  { type: 'network.pair.cancel'; input: PairCancelInput; output: PairCancelOutput }
   |  { type: 'network.pair.confirmProxy'; input: PairConfirmProxyInput; output: PairConfirmProxyOutput }
   |  { type: 'network.pair.generate'; input: PairGenerateInput; output: PairGenerateOutput }
   |  { type: 'network.pair.join'; input: PairJoinInput; output: PairJoinOutput }
+  |  { type: 'network.pair.pruneVouchingQueue'; input: VouchingQueuePruneInput; output: VouchingQueuePruneOutput }
   |  { type: 'network.pair.vouch'; input: PairVouchInput; output: PairVouchOutput }
+  |  { type: 'network.pair.vouching_session.refresh'; input: VouchingSessionRefreshInput; output: VouchingSessionRefreshOutput }
+  |  { type: 'network.pair.vouching_session.cancel'; input: VouchingSessionCancelInput; output: VouchingSessionCancelOutput }
   |  { type: 'network.spacedrop.send'; input: SpacedropSendInput; output: SpacedropSendOutput }
   |  { type: 'network.start'; input: NetworkStartInput; output: NetworkStartOutput }
   |  { type: 'network.stop'; input: NetworkStopInput; output: NetworkStopOutput }
   |  { type: 'network.sync_setup'; input: LibrarySyncSetupInput; output: LibrarySyncSetupOutput }
+  |  { type: 'network.sync_setup.library_state'; input: GetRemoteLibraryStateInput; output: GetRemoteLibraryStateOutput }
 ;
 
 export type LibraryAction =
@@ -5067,6 +5700,9 @@ export type LibraryAction =
   |  { type: 'spaces.reorder_items'; input: ReorderItemsInput; output: ReorderOutput }
   |  { type: 'spaces.update'; input: SpaceUpdateInput; output: SpaceUpdateOutput }
   |  { type: 'spaces.update_group'; input: UpdateGroupInput; output: UpdateGroupOutput }
+  |  { type: 'sync.pause'; input: PauseLibrarySyncInput; output: PauseLibrarySyncOutput }
+  |  { type: 'sync.resume'; input: ResumeLibrarySyncInput; output: ResumeLibrarySyncOutput }
+  |  { type: 'sync.trigger'; input: TriggerSyncInput; output: TriggerSyncOutput }
   |  { type: 'tags.apply'; input: ApplyTagsInput; output: ApplyTagsOutput }
   |  { type: 'tags.create'; input: CreateTagInput; output: CreateTagOutput }
   |  { type: 'tags.delete'; input: DeleteTagInput; output: DeleteTagOutput }
@@ -5085,16 +5721,22 @@ export type CoreQuery =
      { type: 'config.app.get'; input: GetAppConfigQueryInput; output: AppConfigOutput }
   |  { type: 'core.ephemeral_status'; input: EphemeralCacheStatusInput; output: EphemeralCacheStatus }
   |  { type: 'core.events.list'; input: ListEventsInput; output: ListEventsOutput }
+  |  { type: 'core.events.replay'; input: ReplayEventsInput; output: ReplayEventsOutput }
   |  { type: 'core.status'; input: Empty; output: CoreStatus }
   |  { type: 'jobs.remote.all_devices'; input: RemoteJobsAllDevicesInput; output: RemoteJobsAllDevicesOutput }
   |  { type: 'jobs.remote.for_device'; input: RemoteJobsForDeviceInput; output: RemoteJobsForDeviceOutput }
   |  { type: 'libraries.list'; input: ListLibrariesInput; output: [LibraryInfo] }
   |  { type: 'models.whisper.list'; input: ListWhisperModelsInput; output: ListWhisperModelsOutput }
   |  { type: 'network.devices.list'; input: ListPairedDevicesInput; output: ListPairedDevicesOutput }
+  |  { type: 'network.pair.diagnostics'; input: PairDiagnosticsQueryInput; output: PairDiagnosticsOutput }
+  |  { type: 'network.pair.history'; input: PairHistoryQueryInput; output: PairHistoryOutput }
+  |  { type: 'network.pair.proxy_audit'; input: ProxyAuditQueryInput; output: ProxyAuditOutput }
   |  { type: 'network.pair.status'; input: PairStatusQueryInput; output: PairStatusOutput }
   |  { type: 'network.pair.vouching_session'; input: VouchingSessionInput; output: VouchingSessionOutput }
+  |  { type: 'network.pair.vouching_sessions'; input: VouchingSessionsQueryInput; output: VouchingSessionsOutput }
   |  { type: 'network.status'; input: NetworkStatusQueryInput; output: NetworkStatus }
   |  { type: 'network.sync_setup.discover'; input: DiscoverRemoteLibrariesInput; output: DiscoverRemoteLibrariesOutput }
+  |  { type: 'sync.models'; input: SyncModelsInput; output: SyncModelsOutput }
 ;
 
 export type LibraryQuery =
@@ -5126,6 +5768,7 @@ export type LibraryQuery =
   |  { type: 'spaces.get_layout'; input: SpaceLayoutQueryInput; output: SpaceLayout }
   |  { type: 'spaces.list'; input: SpacesListQueryInput; output: SpacesListOutput }
   |  { type: 'sync.activity'; input: GetSyncActivityInput; output: GetSyncActivityOutput }
+  |  { type: 'sync.backfill_status'; input: SyncBackfillStatusInput; output: SyncBackfillStatusOutput }
   |  { type: 'sync.eventLog'; input: GetSyncEventLogInput; output: GetSyncEventLogOutput }
   |  { type: 'sync.metrics'; input: GetSyncMetricsInput; output: GetSyncMetricsOutput }
   |  { type: 'sync.partners'; input: GetSyncPartnersInput; output: GetSyncPartnersOutput }
@@ -5148,15 +5791,21 @@ export const WIRE_METHODS = {
     'models.whisper.delete': 'action:models.whisper.delete.input',
     'models.whisper.download': 'action:models.whisper.download.input',
     'network.device.revoke': 'action:network.device.revoke.input',
+    'network.device.trust': 'action:network.device.trust.input',
+    'network.device.unpair': 'action:network.device.unpair.input',
     'network.pair.cancel': 'action:network.pair.cancel.input',
     'network.pair.confirmProxy': 'action:network.pair.confirmProxy.input',
     'network.pair.generate': 'action:network.pair.generate.input',
     'network.pair.join': 'action:network.pair.join.input',
+    'network.pair.pruneVouchingQueue': 'action:network.pair.pruneVouchingQueue.input',
     'network.pair.vouch': 'action:network.pair.vouch.input',
+    'network.pair.vouching_session.refresh': 'action:network.pair.vouching_session.refresh.input',
+    'network.pair.vouching_session.cancel': 'action:network.pair.vouching_session.cancel.input',
     'network.spacedrop.send': 'action:network.spacedrop.send.input',
     'network.start': 'action:network.start.input',
     'network.stop': 'action:network.stop.input',
     'network.sync_setup': 'action:network.sync_setup.input',
+    'network.sync_setup.library_state': 'action:network.sync_setup.library_state.input',
   },
 
   libraryActions: {
@@ -5201,6 +5850,9 @@ export const WIRE_METHODS = {
     'spaces.reorder_items': 'action:spaces.reorder_items.input',
     'spaces.update': 'action:spaces.update.input',
     'spaces.update_group': 'action:spaces.update_group.input',
+    'sync.pause': 'action:sync.pause.input',
+    'sync.resume': 'action:sync.resume.input',
+    'sync.trigger': 'action:sync.trigger.input',
     'tags.apply': 'action:tags.apply.input',
     'tags.create': 'action:tags.create.input',
     'tags.delete': 'action:tags.delete.input',
@@ -5219,16 +5871,22 @@ export const WIRE_METHODS = {
     'config.app.get': 'query:config.app.get',
     'core.ephemeral_status': 'query:core.ephemeral_status',
     'core.events.list': 'query:core.events.list',
+    'core.events.replay': 'query:core.events.replay',
     'core.status': 'query:core.status',
     'jobs.remote.all_devices': 'query:jobs.remote.all_devices',
     'jobs.remote.for_device': 'query:jobs.remote.for_device',
     'libraries.list': 'query:libraries.list',
     'models.whisper.list': 'query:models.whisper.list',
     'network.devices.list': 'query:network.devices.list',
+    'network.pair.diagnostics': 'query:network.pair.diagnostics',
+    'network.pair.history': 'query:network.pair.history',
+    'network.pair.proxy_audit': 'query:network.pair.proxy_audit',
     'network.pair.status': 'query:network.pair.status',
     'network.pair.vouching_session': 'query:network.pair.vouching_session',
+    'network.pair.vouching_sessions': 'query:network.pair.vouching_sessions',
     'network.status': 'query:network.status',
     'network.sync_setup.discover': 'query:network.sync_setup.discover',
+    'sync.models': 'query:sync.models',
   },
 
   libraryQueries: {
@@ -5260,6 +5918,7 @@ export const WIRE_METHODS = {
     'spaces.get_layout': 'query:spaces.get_layout',
     'spaces.list': 'query:spaces.list',
     'sync.activity': 'query:sync.activity',
+    'sync.backfill_status': 'query:sync.backfill_status',
     'sync.eventLog': 'query:sync.eventLog',
     'sync.metrics': 'query:sync.metrics',
     'sync.partners': 'query:sync.partners',
@@ -0,0 +1,41 @@
+/**
+ * useVolumeUsageUpdates Tests
+ *
+ * Validates that space-only volume events patch cached volume lists.
+ */
+
+import { describe, it, expect } from "bun:test";
+import { QueryClient } from "@tanstack/react-query";
+import { applyVolumeUsage } from "../useVolumeUsageUpdates";
+import type { Event } from "../../generated/types";
+
+describe("useVolumeUsageUpdates", () => {
+	it("updates only the matching volume's usage", () => {
+		const queryClient = new QueryClient();
+		const key = ["query:volumes.list", "library", { filter: "All" }];
+		queryClient.setQueryData(key, {
+			volumes: [
+				{ fingerprint: "a", name: "A", available_space: 10, total_capacity: 100 },
+				{ fingerprint: "b", name: "B", available_space: 20, total_capacity: 200 },
+			],
+		});
+
+		const event = {
+			VolumeUsageChanged: {
+				fingerprint: "a",
+				available_space: 5,
+				total_capacity: 100,
+			},
+		} as Event;
+		applyVolumeUsage(event, queryClient);
+
+		const data = queryClient.getQueryData<any>(key);
+		expect(data.volumes[0]).toEqual({
+			fingerprint: "a",
+			name: "A",
+			available_space: 5,
+			total_capacity: 100,
+		});
+		expect(data.volumes[1].available_space).toBe(20);
+	});
+});
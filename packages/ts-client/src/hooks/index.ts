@@ -7,5 +7,6 @@ export { useCoreMutation, useLibraryMutation } from "./useMutation";
 export { useNormalizedQuery } from "./useNormalizedQuery";
 // Alias for backwards compatibility
 export { useNormalizedQuery as useNormalizedCache } from "./useNormalizedQuery";
+export { useVolumeUsageUpdates } from "./useVolumeUsageUpdates";
 export { useJobs, type UseJobsOptions, type UseJobsReturn, type SpeedSample, type ExtendedJobListItem } from "./useJobs";
 export { useSearchFiles, type UseSearchFilesOptions, type UseSearchFilesReturn, type SearchScopeUI } from "./useSearchFiles";
@@ -0,0 +1,81 @@
+/**
+ * useVolumeUsageUpdates - Keep volume free space current
+ *
+ * The core reports space-only volume changes as a lightweight
+ * `VolumeUsageChanged` event rather than a full `ResourceChanged`, so
+ * normalized volume queries don't see them. This hook patches the usage
+ * fields of the matching volume in every cached `volumes.list` result.
+ */
+
+import { useEffect } from "react";
+import { useQueryClient, QueryClient } from "@tanstack/react-query";
+import { useSpacedriveClient } from "./useClient";
+import type { Event, VolumeListOutput } from "../generated/types";
+
+const VOLUMES_LIST_QUERY = "query:volumes.list";
+
+/**
+ * Apply a `VolumeUsageChanged` event to cached volume lists
+ *
+ * Exported for testing.
+ */
+export function applyVolumeUsage(event: Event, queryClient: QueryClient) {
+	if (typeof event === "string" || !("VolumeUsageChanged" in event)) {
+		return;
+	}
+
+	const { fingerprint, available_space, total_capacity } =
+		event.VolumeUsageChanged;
+
+	queryClient.setQueriesData<VolumeListOutput>(
+		{ queryKey: [VOLUMES_LIST_QUERY] },
+		(data) => {
+			if (!data?.volumes?.some((v) => v.fingerprint === fingerprint)) {
+				return data;
+			}
+			return {
+				...data,
+				volumes: data.volumes.map((volume) =>
+					volume.fingerprint === fingerprint
+						? { ...volume, available_space, total_capacity }
+						: volume,
+				),
+			};
+		},
+	);
+}
+
+export function useVolumeUsageUpdates() {
+	const client = useSpacedriveClient();
+	const queryClient = useQueryClient();
+
+	useEffect(() => {
+		let unsubscribe: (() => void) | undefined;
+		let isCancelled = false;
+
+		const filter = {
+			event_types: ["VolumeUsageChanged"],
+			// Unique subscription key, kept apart from resource subscriptions
+			resource_type: undefined,
+			path_scope: undefined,
+			library_id: undefined,
+		};
+
+		client
+			.subscribeFiltered(filter, (event: Event) =>
+				applyVolumeUsage(event, queryClient),
+			)
+			.then((unsub) => {
+				if (isCancelled) {
+					unsub();
+				} else {
+					unsubscribe = unsub;
+				}
+			});
+
+		return () => {
+			isCancelled = true;
+			unsubscribe?.();
+		};
+	}, [client, queryClient]);
+}